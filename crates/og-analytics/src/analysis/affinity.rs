@@ -0,0 +1,79 @@
+use og_graph::graph::CodeGraph;
+use petgraph::graph::NodeIndex;
+use serde::{Deserialize, Serialize};
+
+/// Nodes returned when no count is given
+pub const DEFAULT_AFFINITY_K: usize = 20;
+
+/// Chance that the walk follows an edge rather than restarting at a seed
+pub const AFFINITY_DAMPING: f64 = 0.85;
+
+const AFFINITY_ITERATIONS: usize = 50;
+
+/// A node ranked by how strongly a random walk from the seeds is drawn to it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeAffinity {
+    pub id: String,
+    pub name: String,
+    pub node_type: String,
+    pub file_path: Option<String>,
+    /// Share of the walk's time spent on the node
+    pub score: f64,
+    /// Score relative to the most strongly pulled node, from 0 to 1
+    pub affinity: f64,
+    /// Score divided by the node's score for a walk restarting anywhere, so
+    /// hubs every feature reaches do not stand out; above 1 means the seeds
+    /// pull on the node more than the graph as a whole does
+    pub lift: f64,
+}
+
+/// Nodes most strongly pulled toward a set of seed nodes, such as a feature's files
+///
+/// Scores come from a personalized PageRank restarting at the seeds, so a
+/// node ranks high when many short paths lead to it from the seeds. Seeds
+/// themselves and nodes the walk never reaches are left out. Returns None
+/// if there are no seeds or one of them does not exist.
+pub fn module_affinity(graph: &CodeGraph, seeds: &[String], k: usize) -> Option<Vec<NodeAffinity>> {
+    if seeds.is_empty() {
+        return None;
+    }
+    let seed_indices = seeds
+        .iter()
+        .map(|id| graph.node_map.get(id).copied())
+        .collect::<Option<Vec<NodeIndex>>>()?;
+
+    let scores = graph.personalized_pagerank(&seed_indices, AFFINITY_ITERATIONS, AFFINITY_DAMPING);
+    let all: Vec<NodeIndex> = graph.graph.node_indices().collect();
+    let baseline = graph.personalized_pagerank(&all, AFFINITY_ITERATIONS, AFFINITY_DAMPING);
+
+    let mut ranked: Vec<NodeAffinity> = graph
+        .graph
+        .node_indices()
+        .filter(|idx| !seed_indices.contains(idx))
+        .filter_map(|idx| {
+            let node = &graph.graph[idx];
+            let score = scores.get(&node.id).copied().unwrap_or(0.0);
+            if score <= 0.0 {
+                return None;
+            }
+            let base = baseline.get(&node.id).copied().unwrap_or(0.0);
+            Some(NodeAffinity {
+                id: node.id.clone(),
+                name: node.name.clone(),
+                node_type: node.node_type.clone(),
+                file_path: node.file_path.clone(),
+                score,
+                affinity: 0.0,
+                lift: if base > 0.0 { score / base } else { 0.0 },
+            })
+        })
+        .collect();
+
+    ranked.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.id.cmp(&b.id)));
+    if let Some(top) = ranked.first().map(|node| node.score) {
+        ranked.iter_mut().for_each(|node| node.affinity = node.score / top);
+    }
+    ranked.truncate(k);
+    Some(ranked)
+}
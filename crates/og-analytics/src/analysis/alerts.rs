@@ -0,0 +1,111 @@
+use crate::analysis::findings::HIGH_RISK_THRESHOLD;
+use og_graph::graph::CodeGraph;
+use og_types::metrics::UINodeMetricsV1;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// Thresholds that raise an alert after an analysis, all off by default
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AlertRules {
+    /// Alert on nodes whose overall risk is above this
+    pub max_risk: Option<f32>,
+    /// Alert when more nodes than this are high risk
+    pub max_high_risk_nodes: Option<usize>,
+    /// Alert when there are more dependency cycles than this
+    pub max_cycles: Option<usize>,
+    /// Alert on dependency cycles that the previous analysis did not have
+    pub new_cycles: bool,
+}
+
+/// Rule an alert was raised by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertKind {
+    Risk,
+    HighRiskNodes,
+    Cycles,
+    NewCycle,
+}
+
+/// A threshold crossed by an analysis
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Alert {
+    pub kind: AlertKind,
+    pub message: String,
+    /// Paths or ids of the nodes involved, worst first
+    pub nodes: Vec<String>,
+}
+
+/// Members of every dependency cycle, each sorted
+pub fn dependency_cycles(graph: &CodeGraph) -> Vec<Vec<String>> {
+    graph.condensed().cycle_members.into_values().collect()
+}
+
+impl AlertRules {
+    /// Alerts raised by an analysis's metrics and cycles
+    ///
+    /// New cycles are found against `previous_cycles`, and never reported
+    /// when there is no previous analysis to compare with.
+    pub fn evaluate(
+        &self,
+        metrics: &[UINodeMetricsV1],
+        cycles: &[Vec<String>],
+        previous_cycles: Option<&[Vec<String>]>,
+    ) -> Vec<Alert> {
+        let mut alerts = Vec::new();
+
+        if let Some(max_risk) = self.max_risk {
+            let mut risky: Vec<&UINodeMetricsV1> = metrics.iter().filter(|m| m.risk > max_risk).collect();
+            if !risky.is_empty() {
+                risky.sort_by(|a, b| b.risk.total_cmp(&a.risk));
+                alerts.push(Alert {
+                    kind: AlertKind::Risk,
+                    message: format!(
+                        "{} nodes have risk above {:.2}, highest {:.2} in {}",
+                        risky.len(),
+                        max_risk,
+                        risky[0].risk,
+                        risky[0].name
+                    ),
+                    nodes: risky.iter().map(|m| m.path.clone()).collect(),
+                });
+            }
+        }
+
+        if let Some(max_nodes) = self.max_high_risk_nodes {
+            let count = metrics.iter().filter(|m| m.risk > HIGH_RISK_THRESHOLD).count();
+            if count > max_nodes {
+                alerts.push(Alert {
+                    kind: AlertKind::HighRiskNodes,
+                    message: format!("{} high-risk nodes, more than the {} allowed", count, max_nodes),
+                    nodes: Vec::new(),
+                });
+            }
+        }
+
+        if let Some(max_cycles) = self.max_cycles {
+            if cycles.len() > max_cycles {
+                alerts.push(Alert {
+                    kind: AlertKind::Cycles,
+                    message: format!("{} dependency cycles, more than the {} allowed", cycles.len(), max_cycles),
+                    nodes: Vec::new(),
+                });
+            }
+        }
+
+        if let (true, Some(previous)) = (self.new_cycles, previous_cycles) {
+            let previous: BTreeSet<&Vec<String>> = previous.iter().collect();
+            for members in cycles.iter().filter(|members| !previous.contains(members)) {
+                alerts.push(Alert {
+                    kind: AlertKind::NewCycle,
+                    message: format!("New dependency cycle of {} nodes: {}", members.len(), members.join(", ")),
+                    nodes: members.clone(),
+                });
+            }
+        }
+
+        alerts
+    }
+}
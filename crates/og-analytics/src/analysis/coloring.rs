@@ -0,0 +1,215 @@
+use og_graph::graph::CodeGraph;
+use og_types::metrics::UINodeMetricsV1;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Color of nodes without a value for the metric
+pub const MISSING_COLOR: &str = "#9e9e9e";
+
+/// Low, middle and high stops of the heatmap palette
+const HEATMAP: [[u8; 3]; 3] = [[0xff, 0xff, 0xcc], [0xfd, 0x8d, 0x3c], [0xbd, 0x00, 0x26]];
+
+/// Negative, zero and positive stops of the diverging palette
+const DIVERGING: [[u8; 3]; 3] = [[0x21, 0x66, 0xac], [0xf7, 0xf7, 0xf7], [0xb2, 0x18, 0x2b]];
+
+/// Colors given to categories in order, repeating past the end
+const CATEGORICAL: &[&str] = &[
+    "#4e79a7", "#f28e2b", "#e15759", "#76b7b2", "#59a14f", "#edc948", "#b07aa1", "#ff9da7", "#9c755f", "#bab0ac",
+];
+
+/// How metric values are turned into colors
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Palette {
+    /// Light to dark red from the lowest value to the highest
+    Heatmap,
+    /// A distinct color per value, such as a community id
+    Categorical,
+    /// Blue below zero, white at zero and red above
+    Diverging,
+}
+
+/// How values are spread over a continuous palette
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorScale {
+    #[default]
+    Linear,
+    /// Compresses long tails such as fan-in or churn
+    Log,
+    /// By rank, so every color is used by as many nodes
+    Quantile,
+}
+
+/// A value and the color it maps to, for drawing a legend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LegendEntry {
+    pub label: String,
+    pub color: String,
+}
+
+/// Color of every graph node for one metric
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColorMap {
+    pub metric: String,
+    pub palette: Palette,
+    pub scale: ColorScale,
+    /// Color by node id, `MISSING_COLOR` for nodes without a value
+    pub colors: BTreeMap<String, String>,
+    pub legend: Vec<LegendEntry>,
+}
+
+/// Value of a metric for each graph node that has one, by node id
+///
+/// Nodes are matched to their metrics by location, name and type.
+pub fn node_values(
+    graph: &CodeGraph,
+    metrics: &[UINodeMetricsV1],
+    extract: impl Fn(&UINodeMetricsV1) -> Option<f64>,
+) -> BTreeMap<String, f64> {
+    let by_node: HashMap<(&str, &str, &str), &UINodeMetricsV1> = metrics
+        .iter()
+        .map(|m| ((m.path.as_str(), m.name.as_str(), m.node_type.as_str()), m))
+        .collect();
+    graph
+        .graph
+        .node_weights()
+        .filter_map(|node| {
+            let path = node.file_path.as_deref().unwrap_or(&node.id);
+            let value = extract(by_node.get(&(path, node.name.as_str(), node.node_type.as_str()))?)?;
+            value.is_finite().then(|| (node.id.clone(), value))
+        })
+        .collect()
+}
+
+fn hex(rgb: [u8; 3]) -> String {
+    format!("#{:02x}{:02x}{:02x}", rgb[0], rgb[1], rgb[2])
+}
+
+/// Color at `t` from 0 to 1 along three evenly spaced stops
+fn interpolate(stops: &[[u8; 3]; 3], t: f64) -> String {
+    let t = t.clamp(0.0, 1.0) * 2.0;
+    let (from, to, t) = if t <= 1.0 { (stops[0], stops[1], t) } else { (stops[1], stops[2], t - 1.0) };
+    let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+    hex([mix(from[0], to[0]), mix(from[1], to[1]), mix(from[2], to[2])])
+}
+
+/// Compress magnitudes on a log scale, keeping the sign
+fn log_scaled(value: f64) -> f64 {
+    value.signum() * value.abs().ln_1p()
+}
+
+/// Position of each value from 0 to 1 on a continuous palette
+fn positions(values: &BTreeMap<String, f64>, palette: Palette, scale: ColorScale) -> BTreeMap<&str, f64> {
+    let scaled: Vec<(&str, f64)> = values
+        .iter()
+        .map(|(id, &v)| (id.as_str(), if scale == ColorScale::Log { log_scaled(v) } else { v }))
+        .collect();
+
+    if scale == ColorScale::Quantile {
+        let mut sorted: Vec<f64> = scaled.iter().map(|(_, v)| *v).collect();
+        sorted.sort_by(f64::total_cmp);
+        let last = (sorted.len().max(2) - 1) as f64;
+        return scaled
+            .into_iter()
+            .map(|(id, v)| {
+                // Ties share the middle of their ranks
+                let below = sorted.partition_point(|s| *s < v) as f64;
+                let through = sorted.partition_point(|s| *s <= v) as f64;
+                (id, (below + through - 1.0) / 2.0 / last)
+            })
+            .collect();
+    }
+
+    if palette == Palette::Diverging {
+        // Zero stays white, whichever side reaches further sets the range
+        let reach = scaled.iter().map(|(_, v)| v.abs()).fold(0.0, f64::max);
+        return scaled
+            .into_iter()
+            .map(|(id, v)| (id, if reach > 0.0 { 0.5 + v / reach / 2.0 } else { 0.5 }))
+            .collect();
+    }
+
+    let min = scaled.iter().map(|(_, v)| *v).fold(f64::INFINITY, f64::min);
+    let max = scaled.iter().map(|(_, v)| *v).fold(f64::NEG_INFINITY, f64::max);
+    scaled
+        .into_iter()
+        .map(|(id, v)| (id, if max > min { (v - min) / (max - min) } else { 0.5 }))
+        .collect()
+}
+
+fn format_value(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{}", value)
+    } else {
+        format!("{:.3}", value)
+    }
+}
+
+/// Color every node of the graph by its value of a metric
+///
+/// `values` holds the metric by node id, as from `node_values`. The scale is
+/// ignored by the categorical palette, which colors each distinct value.
+pub fn recolor(
+    graph: &CodeGraph,
+    metric: &str,
+    values: &BTreeMap<String, f64>,
+    palette: Palette,
+    scale: ColorScale,
+) -> ColorMap {
+    let mut colors: BTreeMap<String, String> = graph
+        .graph
+        .node_weights()
+        .map(|node| (node.id.clone(), MISSING_COLOR.to_string()))
+        .collect();
+    let mut legend = Vec::new();
+
+    if palette == Palette::Categorical {
+        let mut categories: Vec<f64> = values.values().copied().collect();
+        categories.sort_by(f64::total_cmp);
+        categories.dedup();
+        let color_of = |value: f64| {
+            let index = categories.partition_point(|c| *c < value);
+            CATEGORICAL[index % CATEGORICAL.len()]
+        };
+        for (id, &value) in values {
+            colors.insert(id.clone(), color_of(value).to_string());
+        }
+        legend = categories
+            .iter()
+            .map(|&value| LegendEntry {
+                label: format_value(value),
+                color: color_of(value).to_string(),
+            })
+            .collect();
+    } else if !values.is_empty() {
+        let stops = if palette == Palette::Diverging { &DIVERGING } else { &HEATMAP };
+        let positions = positions(values, palette, scale);
+        for (id, t) in &positions {
+            colors.insert(id.to_string(), interpolate(stops, *t));
+        }
+
+        // Lowest, middle and highest value with the colors they got
+        let mut ranked: Vec<(f64, f64)> = values.iter().map(|(id, &v)| (v, positions[id.as_str()])).collect();
+        ranked.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let mut picks = vec![ranked[0], ranked[ranked.len() / 2], ranked[ranked.len() - 1]];
+        picks.dedup_by(|a, b| a.0 == b.0);
+        legend = picks
+            .into_iter()
+            .map(|(value, t)| LegendEntry {
+                label: format_value(value),
+                color: interpolate(stops, t),
+            })
+            .collect();
+    }
+
+    ColorMap {
+        metric: metric.to_string(),
+        palette,
+        scale,
+        colors,
+        legend,
+    }
+}
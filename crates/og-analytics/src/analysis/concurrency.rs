@@ -0,0 +1,77 @@
+use crate::analysis::evolution::module_of;
+use crate::analysis::export::{report_node, ReportNode};
+use crate::analysis::pr_summary::relative;
+use og_graph::graph::CodeGraph;
+use og_types::ConcurrencyProfile;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// A function or file that starts concurrent work or takes locks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConcurrentNode {
+    pub node: ReportNode,
+    pub profile: ConcurrencyProfile,
+}
+
+/// Concurrency primitives used in one module
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleConcurrency {
+    /// Directory relative to the analyzed root, "." for the root
+    pub module: String,
+    /// Summed over the module's nodes
+    pub profile: ConcurrencyProfile,
+    /// Most primitives first
+    pub nodes: Vec<ConcurrentNode>,
+}
+
+/// Which modules do concurrent work, and with what
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConcurrencyMap {
+    /// Summed over every module
+    pub profile: ConcurrencyProfile,
+    /// Most primitives first
+    pub modules: Vec<ModuleConcurrency>,
+}
+
+impl ConcurrencyMap {
+    /// Group the nodes that use concurrency primitives by module
+    ///
+    /// `profiles` holds the primitives each node uses, by node id, and
+    /// `root` is the analyzed directory modules are made relative to. Nodes
+    /// no longer in the graph are left out.
+    pub fn build(graph: &CodeGraph, profiles: &HashMap<String, ConcurrencyProfile>, root: &str) -> Self {
+        let mut modules: BTreeMap<String, ModuleConcurrency> = BTreeMap::new();
+        for (id, profile) in profiles {
+            if profile.total() == 0 || !graph.node_map.contains_key(id) {
+                continue;
+            }
+            let node = report_node(graph, id);
+            let module = module_of(&relative(root, node.file_path.as_deref().unwrap_or(id)));
+            let entry = modules.entry(module.clone()).or_insert_with(|| ModuleConcurrency {
+                module,
+                profile: ConcurrencyProfile::default(),
+                nodes: Vec::new(),
+            });
+            entry.profile.merge(profile);
+            entry.nodes.push(ConcurrentNode { node, profile: *profile });
+        }
+
+        let by_total = |a: &ConcurrencyProfile, b: &ConcurrencyProfile| b.total().cmp(&a.total());
+        let mut modules: Vec<ModuleConcurrency> = modules.into_values().collect();
+        for module in &mut modules {
+            module
+                .nodes
+                .sort_by(|a, b| by_total(&a.profile, &b.profile).then_with(|| a.node.id.cmp(&b.node.id)));
+        }
+        modules.sort_by(|a, b| by_total(&a.profile, &b.profile).then_with(|| a.module.cmp(&b.module)));
+
+        let mut profile = ConcurrencyProfile::default();
+        for module in &modules {
+            profile.merge(&module.profile);
+        }
+        Self { profile, modules }
+    }
+}
@@ -0,0 +1,55 @@
+use og_types::{DebtMarker, DEBT_KINDS};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Debt markers of one file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileDebt {
+    pub path: String,
+    pub markers: usize,
+}
+
+/// TODO, FIXME, HACK and XXX comments across a codebase
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DebtReport {
+    pub total: usize,
+    /// Every marker word, zero when none is found
+    pub by_kind: BTreeMap<String, usize>,
+    /// Most markers first
+    pub files: Vec<FileDebt>,
+    /// By file, then line
+    pub markers: Vec<DebtMarker>,
+}
+
+impl DebtReport {
+    /// Count and list the markers, keeping only one kind when `kind` is set
+    pub fn build(markers: &[DebtMarker], kind: Option<&str>) -> Self {
+        let mut markers: Vec<DebtMarker> = markers
+            .iter()
+            .filter(|marker| kind.is_none_or(|kind| marker.kind.eq_ignore_ascii_case(kind)))
+            .cloned()
+            .collect();
+        markers.sort_by(|a, b| a.file_path.cmp(&b.file_path).then(a.line.cmp(&b.line)));
+
+        let mut by_kind: BTreeMap<String, usize> = DEBT_KINDS.iter().map(|kind| (kind.to_string(), 0)).collect();
+        let mut by_file: BTreeMap<&str, usize> = BTreeMap::new();
+        for marker in &markers {
+            *by_kind.entry(marker.kind.clone()).or_default() += 1;
+            *by_file.entry(&marker.file_path).or_default() += 1;
+        }
+        let mut files: Vec<FileDebt> = by_file
+            .into_iter()
+            .map(|(path, markers)| FileDebt { path: path.to_string(), markers })
+            .collect();
+        files.sort_by(|a, b| b.markers.cmp(&a.markers).then_with(|| a.path.cmp(&b.path)));
+
+        Self {
+            total: markers.len(),
+            by_kind,
+            files,
+            markers,
+        }
+    }
+}
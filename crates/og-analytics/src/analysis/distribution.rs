@@ -0,0 +1,172 @@
+use og_graph::graph::CodeGraph;
+use og_types::metrics::UINodeMetricsV1;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Histogram bins computed per metric when no count is given
+pub const DEFAULT_HISTOGRAM_BINS: usize = 20;
+
+/// One equal-width histogram bucket; the last bin includes its end
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistogramBin {
+    pub start: f64,
+    pub end: f64,
+    pub count: usize,
+}
+
+/// Spread of one per-node metric across the graph
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricDistribution {
+    pub metric: String,
+    /// Nodes with a finite value for the metric
+    pub count: usize,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub q1: f64,
+    pub median: f64,
+    pub q3: f64,
+    pub bins: Vec<HistogramBin>,
+}
+
+/// Selected metrics of one node
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeMetricValues {
+    pub id: String,
+    /// Value of each requested metric, None where the node lacks it
+    pub values: BTreeMap<String, Option<f64>>,
+}
+
+/// Reads one metric from a node, None if the node lacks it
+pub type Extractor = fn(&UINodeMetricsV1) -> Option<f64>;
+
+const METRICS: &[(&str, Extractor)] = &[
+    ("importance", |m| Some(m.importance as f64)),
+    ("risk", |m| Some(m.risk as f64)),
+    ("chokepoint", |m| Some(m.chokepoint as f64)),
+    ("payoff", |m| Some(m.payoff as f64)),
+    ("pagerank_imports", |m| Some(m.raw.pagerank_imports)),
+    ("pagerank_calls", |m| m.raw.pagerank_calls),
+    ("indegree", |m| Some(m.raw.indegree as f64)),
+    ("outdegree", |m| Some(m.raw.outdegree as f64)),
+    ("k_core", |m| Some(m.raw.k_core as f64)),
+    ("clustering", |m| Some(m.raw.clustering)),
+    ("betweenness", |m| Some(m.raw.betweenness)),
+    ("churn", |m| Some(m.raw.churn as f64)),
+    ("complexity", |m| Some(m.raw.complexity as f64)),
+    ("owners", |m| Some(m.raw.owners as f64)),
+    ("coverage", |m| Some(m.raw.coverage)),
+];
+
+/// Names of the per-node metrics that distributions and trends cover
+pub fn metric_names() -> impl Iterator<Item = &'static str> {
+    METRICS.iter().map(|(name, _)| *name)
+}
+
+/// Accessor for a per-node metric by name
+pub fn metric_extractor(metric: &str) -> Option<Extractor> {
+    METRICS.iter().find(|(name, _)| *name == metric).map(|(_, extract)| *extract)
+}
+
+/// Linearly interpolated quantile of sorted values
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let pos = q * (sorted.len() - 1) as f64;
+    let (lo, hi) = (pos.floor() as usize, pos.ceil() as usize);
+    sorted[lo] + (sorted[hi] - sorted[lo]) * (pos - lo as f64)
+}
+
+fn distribution(metric: &str, mut values: Vec<f64>, bins: usize) -> MetricDistribution {
+    values.retain(|v| v.is_finite());
+    values.sort_by(f64::total_cmp);
+    if values.is_empty() {
+        return MetricDistribution {
+            metric: metric.to_string(),
+            count: 0,
+            min: 0.0,
+            max: 0.0,
+            mean: 0.0,
+            q1: 0.0,
+            median: 0.0,
+            q3: 0.0,
+            bins: Vec::new(),
+        };
+    }
+
+    let (min, max) = (values[0], values[values.len() - 1]);
+    // A constant metric gets a single bin holding every node
+    let bins = if max > min { bins.max(1) } else { 1 };
+    let width = (max - min) / bins as f64;
+    let mut histogram: Vec<HistogramBin> = (0..bins)
+        .map(|i| HistogramBin {
+            start: min + width * i as f64,
+            end: if i + 1 == bins { max } else { min + width * (i + 1) as f64 },
+            count: 0,
+        })
+        .collect();
+    for &value in &values {
+        let slot = if width > 0.0 { ((value - min) / width) as usize } else { 0 };
+        histogram[slot.min(bins - 1)].count += 1;
+    }
+
+    MetricDistribution {
+        metric: metric.to_string(),
+        count: values.len(),
+        min,
+        max,
+        mean: values.iter().sum::<f64>() / values.len() as f64,
+        q1: quantile(&values, 0.25),
+        median: quantile(&values, 0.5),
+        q3: quantile(&values, 0.75),
+        bins: histogram,
+    }
+}
+
+/// Histogram and quartiles of every per-node metric
+///
+/// Composite scores are taken as shown in the UI and the remaining metrics
+/// in their raw units, so thresholds picked from a distribution apply
+/// directly. Metrics no node reports are left out.
+pub fn metric_distributions(metrics: &[UINodeMetricsV1], bins: usize) -> Vec<MetricDistribution> {
+    METRICS
+        .iter()
+        .map(|(name, extract)| distribution(name, metrics.iter().filter_map(extract).collect(), bins))
+        .filter(|d| d.count > 0)
+        .collect()
+}
+
+/// Selected metrics for many nodes at once, in the order the ids are given
+///
+/// Every metric is returned when `fields` is empty, and unknown metric names
+/// are ignored. Ids missing from the graph or without metrics are left out.
+pub fn metric_values(
+    graph: &CodeGraph,
+    metrics: &[UINodeMetricsV1],
+    node_ids: &[String],
+    fields: &[String],
+) -> Vec<NodeMetricValues> {
+    let extractors: Vec<(&str, Extractor)> = METRICS
+        .iter()
+        .filter(|(name, _)| fields.is_empty() || fields.iter().any(|field| field == name))
+        .copied()
+        .collect();
+    let by_node: HashMap<(&str, &str, &str), &UINodeMetricsV1> = metrics
+        .iter()
+        .map(|m| ((m.path.as_str(), m.name.as_str(), m.node_type.as_str()), m))
+        .collect();
+
+    node_ids
+        .iter()
+        .filter_map(|id| {
+            let node = &graph.graph[*graph.node_map.get(id)?];
+            let path = node.file_path.as_deref().unwrap_or(&node.id);
+            let m = by_node.get(&(path, node.name.as_str(), node.node_type.as_str()))?;
+            Some(NodeMetricValues {
+                id: id.clone(),
+                values: extractors.iter().map(|(name, extract)| (name.to_string(), extract(m))).collect(),
+            })
+        })
+        .collect()
+}
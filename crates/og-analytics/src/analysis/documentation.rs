@@ -0,0 +1,116 @@
+use crate::analysis::export::{report_node, ReportNode};
+use og_graph::graph::CodeGraph;
+use og_types::{CommentDensity, Suppressions};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Comments and documented public symbols of one file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileDocumentation {
+    pub path: String,
+    pub comment_lines: usize,
+    pub code_lines: usize,
+    /// Comment lines per line of code
+    pub comment_ratio: f64,
+    pub public_symbols: usize,
+    pub documented: usize,
+    /// Share of public symbols with docs, None when there are none
+    pub coverage: Option<f64>,
+}
+
+/// How well a codebase's public API is documented
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentationReport {
+    pub public_symbols: usize,
+    pub documented: usize,
+    /// Share of public symbols with docs, None when there are none
+    pub coverage: Option<f64>,
+    pub comment_lines: usize,
+    pub code_lines: usize,
+    /// Comment lines per line of code over every file
+    pub comment_ratio: f64,
+    /// By path
+    pub files: Vec<FileDocumentation>,
+    /// Public symbols lacking docs, by file then name
+    pub undocumented: Vec<ReportNode>,
+}
+
+impl FileDocumentation {
+    fn new(path: String, density: CommentDensity) -> Self {
+        Self {
+            path,
+            comment_lines: density.comment_lines,
+            code_lines: density.code_lines,
+            comment_ratio: density.ratio(),
+            public_symbols: 0,
+            documented: 0,
+            coverage: None,
+        }
+    }
+}
+
+fn coverage(documented: usize, public_symbols: usize) -> Option<f64> {
+    (public_symbols > 0).then(|| documented as f64 / public_symbols as f64)
+}
+
+impl DocumentationReport {
+    /// Gather comment density and doc coverage per file
+    ///
+    /// `comments` holds the comment density of each file by its path, and
+    /// `documented` whether each public symbol has docs, by node id. Symbols
+    /// whose "Undocumented" finding is suppressed are still counted, but not
+    /// listed as lacking docs.
+    pub fn build(
+        graph: &CodeGraph,
+        comments: &HashMap<String, CommentDensity>,
+        documented: &HashMap<String, bool>,
+        suppressions: &Suppressions,
+    ) -> Self {
+        let mut files: BTreeMap<String, FileDocumentation> = comments
+            .iter()
+            .map(|(path, &density)| (path.clone(), FileDocumentation::new(path.clone(), density)))
+            .collect();
+
+        let mut undocumented = Vec::new();
+        for (id, &has_docs) in documented {
+            let node = report_node(graph, id);
+            if let Some(path) = &node.file_path {
+                let file = files
+                    .entry(path.clone())
+                    .or_insert_with(|| FileDocumentation::new(path.clone(), CommentDensity::default()));
+                file.public_symbols += 1;
+                file.documented += usize::from(has_docs);
+            }
+            if !has_docs && !suppressions.is_suppressed(id, "Undocumented") {
+                undocumented.push(node);
+            }
+        }
+        undocumented.sort_by(|a, b| a.file_path.cmp(&b.file_path).then_with(|| a.name.cmp(&b.name)).then_with(|| a.id.cmp(&b.id)));
+
+        let files: Vec<FileDocumentation> = files
+            .into_values()
+            .map(|file| FileDocumentation {
+                coverage: coverage(file.documented, file.public_symbols),
+                ..file
+            })
+            .collect();
+        let public_symbols = documented.len();
+        let documented = documented.values().filter(|&&has_docs| has_docs).count();
+        let total = CommentDensity {
+            comment_lines: comments.values().map(|c| c.comment_lines).sum(),
+            code_lines: comments.values().map(|c| c.code_lines).sum(),
+        };
+        Self {
+            public_symbols,
+            documented,
+            coverage: coverage(documented, public_symbols),
+            comment_lines: total.comment_lines,
+            code_lines: total.code_lines,
+            comment_ratio: total.ratio(),
+            files,
+            undocumented,
+        }
+    }
+}
@@ -0,0 +1,216 @@
+use og_graph::graph::{CodeGraph, GraphEdge, GraphNode};
+use petgraph::visit::EdgeRef;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+/// Smallest relative increase in files reported as a module growing
+pub const DEFAULT_MIN_GROWTH: f64 = 0.25;
+
+/// Module a file belongs to: its directory, or "." for files at the root
+pub fn module_of(path: &str) -> String {
+    match path.rsplit_once('/') {
+        Some((dir, _)) => dir.to_string(),
+        None => ".".to_string(),
+    }
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit_once('/').map_or(path, |(_, name)| name)
+}
+
+/// Collapse a graph to one node per file, linked where any of their nodes are
+///
+/// File ids are paths relative to `root`, and a file's size is the number of
+/// nodes declared in it. Links between the same two files are merged into one
+/// edge whose weight counts them.
+pub fn file_graph(graph: &CodeGraph, root: &str) -> CodeGraph {
+    let file_of: Vec<Option<String>> = graph
+        .graph
+        .node_weights()
+        .map(|node| {
+            let path = node.file_path.as_deref()?;
+            let relative = Path::new(path).strip_prefix(root).unwrap_or(Path::new(path));
+            Some(relative.to_string_lossy().replace('\\', "/"))
+        })
+        .collect();
+
+    let mut files = CodeGraph::new();
+    for path in file_of.iter().flatten() {
+        match files.node_map.get(path) {
+            Some(&idx) => files.graph[idx].size += 1.0,
+            None => {
+                files.add_node(GraphNode {
+                    id: path.clone(),
+                    name: file_name(path).to_string(),
+                    node_type: "file".to_string(),
+                    size: 1.0,
+                    visual_size: 10.0,
+                    color: "#4a9eff".to_string(),
+                    file_path: Some(path.clone()),
+                    attributes: Default::default(),
+                });
+            }
+        }
+    }
+
+    let mut links: BTreeMap<(&str, &str), f64> = BTreeMap::new();
+    for edge in graph.graph.edge_references() {
+        if let (Some(source), Some(target)) = (&file_of[edge.source().index()], &file_of[edge.target().index()]) {
+            if source != target {
+                *links.entry((source.as_str(), target.as_str())).or_default() += 1.0;
+            }
+        }
+    }
+    for ((source, target), weight) in links {
+        files.add_edge(
+            source,
+            target,
+            GraphEdge {
+                edge_type: "DEPENDS_ON".to_string(),
+                weight,
+                count: 1,
+            },
+        );
+    }
+    files
+}
+
+/// A codebase at one commit, as a file graph from [`file_graph`]
+pub struct HistorySnapshot {
+    pub commit: String,
+    /// Unix timestamp in seconds
+    pub committed_at: u64,
+    pub graph: CodeGraph,
+}
+
+/// Size of the codebase and each module at one commit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotSummary {
+    pub commit: String,
+    pub committed_at: u64,
+    pub files: usize,
+    /// Linked pairs of files
+    pub links: usize,
+    /// Files per module
+    pub modules: BTreeMap<String, usize>,
+}
+
+/// What happened to a module between two snapshots
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ModuleChange {
+    Appeared { files: usize },
+    Grew { from: usize, to: usize },
+    /// Its files moved into two or more new modules
+    Split { into: Vec<String> },
+    Deleted,
+}
+
+/// A module change and the snapshot it was first seen in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleEvent {
+    pub commit: String,
+    pub committed_at: u64,
+    pub module: String,
+    #[serde(flatten)]
+    pub change: ModuleChange,
+}
+
+/// How the modules of a codebase changed across its history, oldest first
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvolutionTimeline {
+    pub snapshots: Vec<SnapshotSummary>,
+    pub events: Vec<ModuleEvent>,
+}
+
+fn modules(graph: &CodeGraph) -> BTreeMap<String, BTreeSet<&str>> {
+    let mut modules: BTreeMap<String, BTreeSet<&str>> = BTreeMap::new();
+    for node in graph.graph.node_weights() {
+        modules.entry(module_of(&node.id)).or_default().insert(&node.id);
+    }
+    modules
+}
+
+/// Module changes from `before` to `after`, by module name
+///
+/// A file missing from `after` counts as moved when exactly one new file
+/// there has the same name.
+fn changes(
+    before: &BTreeMap<String, BTreeSet<&str>>,
+    after: &BTreeMap<String, BTreeSet<&str>>,
+    min_growth: f64,
+) -> Vec<(String, ModuleChange)> {
+    let old_files: BTreeSet<&str> = before.values().flatten().copied().collect();
+    let mut new_by_name: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for &path in after.values().flatten() {
+        if !old_files.contains(path) {
+            new_by_name.entry(file_name(path)).or_default().push(path);
+        }
+    }
+
+    let mut changes = Vec::new();
+    for (module, files) in before {
+        let into: BTreeSet<String> = files
+            .iter()
+            .filter_map(|path| match new_by_name.get(file_name(path)).map(Vec::as_slice) {
+                Some([moved]) => Some(module_of(moved)),
+                _ => None,
+            })
+            .filter(|target| target != module && !before.contains_key(target))
+            .collect();
+
+        if into.len() >= 2 {
+            changes.push((module.clone(), ModuleChange::Split { into: into.into_iter().collect() }));
+        } else if let Some(now) = after.get(module) {
+            let (from, to) = (files.len(), now.len());
+            if to > from && to as f64 >= from as f64 * (1.0 + min_growth) {
+                changes.push((module.clone(), ModuleChange::Grew { from, to }));
+            }
+        } else {
+            changes.push((module.clone(), ModuleChange::Deleted));
+        }
+    }
+    for (module, files) in after {
+        if !before.contains_key(module) {
+            changes.push((module.clone(), ModuleChange::Appeared { files: files.len() }));
+        }
+    }
+    changes.sort_by(|a, b| a.0.cmp(&b.0));
+    changes
+}
+
+impl EvolutionTimeline {
+    /// Compare consecutive snapshots, given oldest first
+    ///
+    /// Modules are directories. Every module of the first snapshot appears in
+    /// it, and a module grows when its file count rises by at least
+    /// `min_growth` of its previous size.
+    pub fn build(snapshots: &[HistorySnapshot], min_growth: f64) -> Self {
+        let mut timeline = EvolutionTimeline::default();
+        let mut previous = BTreeMap::new();
+        for snapshot in snapshots {
+            let current = modules(&snapshot.graph);
+            for (module, change) in changes(&previous, &current, min_growth) {
+                timeline.events.push(ModuleEvent {
+                    commit: snapshot.commit.clone(),
+                    committed_at: snapshot.committed_at,
+                    module,
+                    change,
+                });
+            }
+            timeline.snapshots.push(SnapshotSummary {
+                commit: snapshot.commit.clone(),
+                committed_at: snapshot.committed_at,
+                files: snapshot.graph.graph.node_count(),
+                links: snapshot.graph.graph.edge_count(),
+                modules: current.iter().map(|(module, files)| (module.clone(), files.len())).collect(),
+            });
+            previous = current;
+        }
+        timeline
+    }
+}
@@ -0,0 +1,464 @@
+use og_graph::graph::CodeGraph;
+use og_metrics_community::CommunityDetection;
+use og_metrics_quality::QualityAnalyzer;
+use og_types::metrics::UINodeMetricsV1;
+use og_types::{FunctionMetrics, Suppressions};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::path::Path;
+
+/// Rows listed in each ranked section of a report
+pub const DEFAULT_TOP_N: usize = 10;
+
+/// Most code smells listed before the rest are only counted
+const MAX_LISTED_SMELLS: usize = 50;
+
+/// Document format a report can be rendered to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+impl ReportFormat {
+    /// Markdown for `.md` and `.markdown` files, HTML otherwise
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("md") || ext.eq_ignore_ascii_case("markdown") => {
+                ReportFormat::Markdown
+            }
+            _ => ReportFormat::Html,
+        }
+    }
+}
+
+/// A graph node referenced from a report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportNode {
+    pub id: String,
+    pub name: String,
+    pub file_path: Option<String>,
+}
+
+/// A node ranked by risk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Hotspot {
+    pub name: String,
+    pub path: String,
+    pub node_type: String,
+    pub risk: f32,
+    pub importance: f32,
+    pub payoff: f32,
+    pub complexity: i64,
+}
+
+/// A detected community and its largest members
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommunitySummary {
+    pub id: usize,
+    pub size: usize,
+    /// Files in the community, or any members if it has no files
+    pub members: Vec<ReportNode>,
+}
+
+/// A code smell found on a node
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportSmell {
+    pub node: ReportNode,
+    pub smell_type: String,
+    pub severity: String,
+    pub description: String,
+}
+
+/// Shareable summary of an analysis
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchitectureReport {
+    pub title: String,
+    pub node_count: usize,
+    pub edge_count: usize,
+    pub file_count: usize,
+    pub hotspots: Vec<Hotspot>,
+    /// Members of each dependency cycle, largest first
+    pub cycles: Vec<Vec<ReportNode>>,
+    pub cycle_count: usize,
+    pub communities: Vec<CommunitySummary>,
+    pub community_count: usize,
+    pub modularity: f64,
+    pub smells: Vec<ReportSmell>,
+    pub smell_count: usize,
+}
+
+fn severity_rank(severity: &str) -> u8 {
+    match severity {
+        "High" => 0,
+        "Medium" => 1,
+        _ => 2,
+    }
+}
+
+pub(crate) fn report_node(graph: &CodeGraph, id: &str) -> ReportNode {
+    let node = graph.node_map.get(id).map(|&idx| &graph.graph[idx]);
+    ReportNode {
+        id: id.to_string(),
+        name: node.map_or_else(|| id.to_string(), |n| n.name.clone()),
+        file_path: node.and_then(|n| n.file_path.clone()),
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub(crate) fn escape_markdown(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+/// Link target for a source file, absolute paths as `file://` URLs
+fn file_href(path: &str) -> String {
+    let path = path.replace('\\', "/").replace(' ', "%20");
+    if path.starts_with('/') {
+        format!("file://{}", path)
+    } else {
+        path
+    }
+}
+
+impl ArchitectureReport {
+    /// Collect the report sections from a graph and its node metrics
+    ///
+    /// Hotspots need metrics from a full analysis and are empty without them.
+    /// Smells and hotspots silenced by `suppressions` are left out, and
+    /// `functions` holds the shape of each function and method by node id.
+    /// Communities are found on edges of `community_edge_types` only, or on
+    /// all edges when empty.
+    pub fn build(
+        title: &str,
+        graph: &CodeGraph,
+        metrics: &[UINodeMetricsV1],
+        suppressions: &Suppressions,
+        functions: &HashMap<String, FunctionMetrics>,
+        community_edge_types: &[String],
+        top_n: usize,
+    ) -> Self {
+        let ids: HashMap<(&str, &str, &str), &str> = graph
+            .graph
+            .node_weights()
+            .map(|node| {
+                let path = node.file_path.as_deref().unwrap_or(&node.id);
+                ((path, node.name.as_str(), node.node_type.as_str()), node.id.as_str())
+            })
+            .collect();
+        let mut hotspots: Vec<Hotspot> = metrics
+            .iter()
+            .filter(|m| {
+                ids.get(&(m.path.as_str(), m.name.as_str(), m.node_type.as_str()))
+                    .is_none_or(|id| !suppressions.is_suppressed(id, "High Risk"))
+            })
+            .map(|m| Hotspot {
+                name: m.name.clone(),
+                path: m.path.clone(),
+                node_type: m.node_type.clone(),
+                risk: m.risk,
+                importance: m.importance,
+                payoff: m.payoff,
+                complexity: m.raw.complexity,
+            })
+            .collect();
+        hotspots.sort_by(|a, b| b.risk.total_cmp(&a.risk).then(b.payoff.total_cmp(&a.payoff)));
+        hotspots.truncate(top_n);
+
+        let mut cycles: Vec<Vec<ReportNode>> = graph
+            .condensed()
+            .cycle_members
+            .into_values()
+            .map(|members| members.iter().map(|id| report_node(graph, id)).collect())
+            .collect();
+        cycles.sort_by_key(|cycle| std::cmp::Reverse(cycle.len()));
+        let cycle_count = cycles.len();
+        cycles.truncate(top_n);
+
+        let detector = CommunityDetection {
+            edge_types: community_edge_types.to_vec(),
+            ..CommunityDetection::new()
+        };
+        let community_results = detector.detect_communities(graph).unwrap_or_default();
+        let mut members: BTreeMap<usize, Vec<&str>> = BTreeMap::new();
+        for (id, community) in &community_results.communities {
+            members.entry(*community).or_default().push(id);
+        }
+        let mut communities: Vec<CommunitySummary> = members
+            .into_iter()
+            .map(|(id, mut ids)| {
+                ids.sort();
+                let size = ids.len();
+                let is_file = |id: &&str| graph.node_map.get(*id).is_some_and(|&idx| graph.graph[idx].node_type == "file");
+                let files: Vec<&str> = ids.iter().copied().filter(is_file).collect();
+                let shown = if files.is_empty() { ids } else { files };
+                CommunitySummary {
+                    id,
+                    size,
+                    members: shown.into_iter().take(top_n).map(|id| report_node(graph, id)).collect(),
+                }
+            })
+            .collect();
+        communities.sort_by(|a, b| b.size.cmp(&a.size).then(a.id.cmp(&b.id)));
+        let community_count = communities.len();
+        communities.truncate(top_n);
+
+        let analyzer = QualityAnalyzer {
+            suppressions: suppressions.clone(),
+            function_metrics: functions.clone(),
+            ..QualityAnalyzer::default()
+        };
+        let quality = analyzer.analyze_quality(graph).unwrap_or_default();
+        let mut smells: Vec<ReportSmell> = quality
+            .code_smells
+            .iter()
+            .flat_map(|(id, found)| {
+                found.smells.iter().map(move |smell| ReportSmell {
+                    node: report_node(graph, id),
+                    smell_type: smell.smell_type.clone(),
+                    severity: smell.severity.clone(),
+                    description: smell.description.clone(),
+                })
+            })
+            .collect();
+        smells.sort_by(|a, b| {
+            severity_rank(&a.severity)
+                .cmp(&severity_rank(&b.severity))
+                .then(a.smell_type.cmp(&b.smell_type))
+                .then(a.node.id.cmp(&b.node.id))
+        });
+        let smell_count = smells.len();
+        smells.truncate(MAX_LISTED_SMELLS);
+
+        Self {
+            title: title.to_string(),
+            node_count: graph.graph.node_count(),
+            edge_count: graph.graph.edge_count(),
+            file_count: graph.graph.node_weights().filter(|n| n.node_type == "file").count(),
+            hotspots,
+            cycles,
+            cycle_count,
+            communities,
+            community_count,
+            modularity: community_results.modularity,
+            smells,
+            smell_count,
+        }
+    }
+
+    /// Render the report as a standalone document
+    pub fn render(&self, format: ReportFormat) -> String {
+        match format {
+            ReportFormat::Markdown => self.to_markdown(),
+            ReportFormat::Html => self.to_html(),
+        }
+    }
+
+    fn markdown_node(node: &ReportNode) -> String {
+        match &node.file_path {
+            Some(path) => format!("[{}]({})", escape_markdown(&node.name), file_href(path)),
+            None => escape_markdown(&node.name),
+        }
+    }
+
+    /// Render the report as Markdown
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "# {}\n", self.title);
+
+        let _ = writeln!(out, "## Summary\n");
+        let _ = writeln!(out, "| Metric | Value |\n| --- | --- |");
+        let _ = writeln!(out, "| Nodes | {} |", self.node_count);
+        let _ = writeln!(out, "| Edges | {} |", self.edge_count);
+        let _ = writeln!(out, "| Files | {} |", self.file_count);
+        let _ = writeln!(out, "| Dependency cycles | {} |", self.cycle_count);
+        let _ = writeln!(out, "| Communities | {} (modularity {:.3}) |", self.community_count, self.modularity);
+        let _ = writeln!(out, "| Code smells | {} |\n", self.smell_count);
+
+        let _ = writeln!(out, "## Top hotspots\n");
+        if self.hotspots.is_empty() {
+            let _ = writeln!(out, "No metrics were computed for this graph.\n");
+        } else {
+            let _ = writeln!(out, "| Name | Type | Risk | Importance | Payoff | Complexity |\n| --- | --- | --- | --- | --- | --- |");
+            for h in &self.hotspots {
+                let _ = writeln!(
+                    out,
+                    "| [{}]({}) | {} | {:.2} | {:.2} | {:.2} | {} |",
+                    escape_markdown(&h.name),
+                    file_href(&h.path),
+                    h.node_type,
+                    h.risk,
+                    h.importance,
+                    h.payoff,
+                    h.complexity
+                );
+            }
+            out.push('\n');
+        }
+
+        let _ = writeln!(out, "## Dependency cycles\n");
+        if self.cycles.is_empty() {
+            let _ = writeln!(out, "No cycles found.\n");
+        } else {
+            for (i, cycle) in self.cycles.iter().enumerate() {
+                let members: Vec<String> = cycle.iter().map(Self::markdown_node).collect();
+                let _ = writeln!(out, "{}. {} nodes: {}", i + 1, cycle.len(), members.join(", "));
+            }
+            out.push('\n');
+        }
+
+        let _ = writeln!(out, "## Communities\n");
+        if self.communities.is_empty() {
+            let _ = writeln!(out, "No communities detected.\n");
+        } else {
+            for community in &self.communities {
+                let members: Vec<String> = community.members.iter().map(Self::markdown_node).collect();
+                let _ = writeln!(out, "- **Community {}** ({} nodes): {}", community.id, community.size, members.join(", "));
+            }
+            out.push('\n');
+        }
+
+        let _ = writeln!(out, "## Code smells\n");
+        if self.smells.is_empty() {
+            let _ = writeln!(out, "No code smells found.");
+        } else {
+            let _ = writeln!(out, "| Severity | Smell | Node | Details |\n| --- | --- | --- | --- |");
+            for smell in &self.smells {
+                let _ = writeln!(
+                    out,
+                    "| {} | {} | {} | {} |",
+                    smell.severity,
+                    escape_markdown(&smell.smell_type),
+                    Self::markdown_node(&smell.node),
+                    escape_markdown(&smell.description)
+                );
+            }
+            if self.smell_count > self.smells.len() {
+                let _ = writeln!(out, "\n{} more smells not listed.", self.smell_count - self.smells.len());
+            }
+        }
+        out
+    }
+
+    fn html_node(node: &ReportNode) -> String {
+        match &node.file_path {
+            Some(path) => format!("<a href=\"{}\">{}</a>", escape_html(&file_href(path)), escape_html(&node.name)),
+            None => escape_html(&node.name),
+        }
+    }
+
+    /// Render the report as a standalone HTML page
+    pub fn to_html(&self) -> String {
+        let mut out = String::new();
+        let title = escape_html(&self.title);
+        let _ = writeln!(out, "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>", title);
+        let _ = writeln!(
+            out,
+            "<style>body{{font-family:system-ui,sans-serif;max-width:1100px;margin:2rem auto;padding:0 1rem;color:#222}}\
+             table{{border-collapse:collapse;width:100%;margin-bottom:1.5rem}}th,td{{border:1px solid #ddd;padding:.4rem .6rem;text-align:left}}\
+             th{{background:#f4f6f8}}.High{{color:#C0392B;font-weight:600}}.Medium{{color:#D68910}}</style>\n</head>\n<body>"
+        );
+        let _ = writeln!(out, "<h1>{}</h1>", title);
+
+        let _ = writeln!(out, "<h2>Summary</h2>\n<table>");
+        for (label, value) in [
+            ("Nodes", self.node_count.to_string()),
+            ("Edges", self.edge_count.to_string()),
+            ("Files", self.file_count.to_string()),
+            ("Dependency cycles", self.cycle_count.to_string()),
+            ("Communities", format!("{} (modularity {:.3})", self.community_count, self.modularity)),
+            ("Code smells", self.smell_count.to_string()),
+        ] {
+            let _ = writeln!(out, "<tr><th>{}</th><td>{}</td></tr>", label, value);
+        }
+        let _ = writeln!(out, "</table>");
+
+        let _ = writeln!(out, "<h2>Top hotspots</h2>");
+        if self.hotspots.is_empty() {
+            let _ = writeln!(out, "<p>No metrics were computed for this graph.</p>");
+        } else {
+            let _ = writeln!(out, "<table>\n<tr><th>Name</th><th>Type</th><th>Risk</th><th>Importance</th><th>Payoff</th><th>Complexity</th></tr>");
+            for h in &self.hotspots {
+                let _ = writeln!(
+                    out,
+                    "<tr><td><a href=\"{}\">{}</a></td><td>{}</td><td>{:.2}</td><td>{:.2}</td><td>{:.2}</td><td>{}</td></tr>",
+                    escape_html(&file_href(&h.path)),
+                    escape_html(&h.name),
+                    escape_html(&h.node_type),
+                    h.risk,
+                    h.importance,
+                    h.payoff,
+                    h.complexity
+                );
+            }
+            let _ = writeln!(out, "</table>");
+        }
+
+        let _ = writeln!(out, "<h2>Dependency cycles</h2>");
+        if self.cycles.is_empty() {
+            let _ = writeln!(out, "<p>No cycles found.</p>");
+        } else {
+            let _ = writeln!(out, "<ol>");
+            for cycle in &self.cycles {
+                let members: Vec<String> = cycle.iter().map(Self::html_node).collect();
+                let _ = writeln!(out, "<li>{} nodes: {}</li>", cycle.len(), members.join(", "));
+            }
+            let _ = writeln!(out, "</ol>");
+        }
+
+        let _ = writeln!(out, "<h2>Communities</h2>");
+        if self.communities.is_empty() {
+            let _ = writeln!(out, "<p>No communities detected.</p>");
+        } else {
+            let _ = writeln!(out, "<ul>");
+            for community in &self.communities {
+                let members: Vec<String> = community.members.iter().map(Self::html_node).collect();
+                let _ = writeln!(
+                    out,
+                    "<li><strong>Community {}</strong> ({} nodes): {}</li>",
+                    community.id,
+                    community.size,
+                    members.join(", ")
+                );
+            }
+            let _ = writeln!(out, "</ul>");
+        }
+
+        let _ = writeln!(out, "<h2>Code smells</h2>");
+        if self.smells.is_empty() {
+            let _ = writeln!(out, "<p>No code smells found.</p>");
+        } else {
+            let _ = writeln!(out, "<table>\n<tr><th>Severity</th><th>Smell</th><th>Node</th><th>Details</th></tr>");
+            for smell in &self.smells {
+                let _ = writeln!(
+                    out,
+                    "<tr><td class=\"{}\">{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                    escape_html(&smell.severity),
+                    escape_html(&smell.severity),
+                    escape_html(&smell.smell_type),
+                    Self::html_node(&smell.node),
+                    escape_html(&smell.description)
+                );
+            }
+            let _ = writeln!(out, "</table>");
+            if self.smell_count > self.smells.len() {
+                let _ = writeln!(out, "<p>{} more smells not listed.</p>", self.smell_count - self.smells.len());
+            }
+        }
+
+        let _ = writeln!(out, "</body>\n</html>");
+        out
+    }
+}
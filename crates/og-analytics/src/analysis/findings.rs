@@ -0,0 +1,314 @@
+use crate::analysis::export::{report_node, ReportNode};
+use crate::analysis::pr_summary::relative;
+use og_graph::graph::CodeGraph;
+use og_metrics_quality::QualityAnalyzer;
+use og_types::metrics::UINodeMetricsV1;
+use og_types::{finding_kind, FunctionMetrics, Suppressions};
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Risk above which a node is reported as a finding
+pub const HIGH_RISK_THRESHOLD: f32 = 0.7;
+
+/// Risk above which a high-risk finding is rated high rather than medium
+const SEVERE_RISK_THRESHOLD: f32 = 0.85;
+
+/// Where a finding comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FindingKind {
+    Smell,
+    Cycle,
+    UnusedExport,
+    HighRisk,
+}
+
+impl FindingKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FindingKind::Smell => "smell",
+            FindingKind::Cycle => "cycle",
+            FindingKind::UnusedExport => "unused-export",
+            FindingKind::HighRisk => "high-risk",
+        }
+    }
+}
+
+/// How urgent a finding is, lowest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Low,
+    Medium,
+    High,
+}
+
+impl Severity {
+    /// Severity from a code smell's "High", "Medium" or "Low" label
+    pub fn from_label(label: &str) -> Self {
+        match label {
+            "High" => Severity::High,
+            "Medium" => Severity::Medium,
+            _ => Severity::Low,
+        }
+    }
+}
+
+/// Rough size of the work needed to fix a finding, smallest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Effort {
+    Small,
+    Medium,
+    Large,
+}
+
+/// A problem worth fixing, from any of the detectors
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Finding {
+    pub kind: FindingKind,
+    /// Smell type, or "Cycle", "Unused Export" or "High Risk"
+    pub title: String,
+    pub description: String,
+    pub severity: Severity,
+    pub effort: Effort,
+    pub nodes: Vec<ReportNode>,
+}
+
+impl Finding {
+    /// Identity of the finding that survives re-analysis and moving the checkout
+    ///
+    /// Built from the kind, title and node ids relative to `root`, so a
+    /// smell keeps its fingerprint while its numbers change, and a cycle
+    /// gets a new one when its members change.
+    pub fn fingerprint(&self, root: &str) -> String {
+        let mut ids: Vec<String> = self.nodes.iter().map(|node| relative(root, &node.id)).collect();
+        ids.sort();
+        format!("{}:{}:{}", self.kind.as_str(), finding_kind(&self.title), ids.join(","))
+    }
+}
+
+/// Order of the findings queue
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FindingSort {
+    /// Most severe first, cheapest fix first among equals
+    #[default]
+    Priority,
+    /// Most severe first, widest reach first among equals
+    Severity,
+    /// Cheapest fix first, most severe first among equals
+    Effort,
+}
+
+/// Which findings to list, every finding when left empty
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FindingFilter {
+    /// Kinds to keep, all kinds when empty
+    pub kinds: Vec<FindingKind>,
+    pub min_severity: Option<Severity>,
+    /// Keep findings with a node whose id or file path contains this text
+    pub path: Option<String>,
+}
+
+impl FindingFilter {
+    pub fn matches(&self, finding: &Finding) -> bool {
+        (self.kinds.is_empty() || self.kinds.contains(&finding.kind))
+            && self.min_severity.is_none_or(|min| finding.severity >= min)
+            && self.path.as_deref().is_none_or(|path| {
+                finding
+                    .nodes
+                    .iter()
+                    .any(|node| node.id.contains(path) || node.file_path.as_deref().is_some_and(|p| p.contains(path)))
+            })
+    }
+}
+
+fn smell_effort(smell_type: &str) -> Effort {
+    match smell_type {
+        "God Object" | "Large File" | "Shotgun Surgery" => Effort::Large,
+        "Long Parameter List" => Effort::Small,
+        _ => Effort::Medium,
+    }
+}
+
+fn smell_findings(
+    graph: &CodeGraph,
+    suppressions: &Suppressions,
+    functions: &HashMap<String, FunctionMetrics>,
+) -> Vec<Finding> {
+    let analyzer = QualityAnalyzer {
+        suppressions: suppressions.clone(),
+        function_metrics: functions.clone(),
+        ..QualityAnalyzer::default()
+    };
+    let quality = analyzer.analyze_quality(graph).unwrap_or_default();
+    quality
+        .code_smells
+        .iter()
+        .flat_map(|(id, found)| {
+            found.smells.iter().map(move |smell| Finding {
+                kind: FindingKind::Smell,
+                title: smell.smell_type.clone(),
+                description: smell.description.clone(),
+                severity: Severity::from_label(&smell.severity),
+                effort: smell_effort(&smell.smell_type),
+                nodes: vec![report_node(graph, id)],
+            })
+        })
+        .collect()
+}
+
+/// Code smells in each file, by file path
+///
+/// Smells on nodes without a file path are not counted. Smells silenced by
+/// `suppressions` are left out. `functions` holds the shape of each function
+/// and method by node id.
+pub fn smell_counts(
+    graph: &CodeGraph,
+    suppressions: &Suppressions,
+    functions: &HashMap<String, FunctionMetrics>,
+) -> HashMap<String, usize> {
+    let mut counts = HashMap::new();
+    for finding in smell_findings(graph, suppressions, functions) {
+        if let Some(file_path) = finding.nodes.into_iter().next().and_then(|node| node.file_path) {
+            *counts.entry(file_path).or_insert(0) += 1;
+        }
+    }
+    counts
+}
+
+/// Cycles with a member that silences "cycle" are left out
+fn cycle_findings(graph: &CodeGraph, suppressions: &Suppressions) -> Vec<Finding> {
+    graph
+        .condensed()
+        .cycle_members
+        .into_values()
+        .filter(|members| !members.iter().any(|id| suppressions.is_suppressed(id, "Cycle")))
+        .map(|members| Finding {
+            kind: FindingKind::Cycle,
+            title: "Cycle".to_string(),
+            description: format!("{} nodes depend on each other", members.len()),
+            severity: if members.len() >= 5 { Severity::High } else { Severity::Medium },
+            effort: match members.len() {
+                0..=2 => Effort::Small,
+                3..=5 => Effort::Medium,
+                _ => Effort::Large,
+            },
+            nodes: members.iter().map(|id| report_node(graph, id)).collect(),
+        })
+        .collect()
+}
+
+/// Files with exports that no other file imports, one finding per file
+fn unused_export_findings(graph: &CodeGraph, suppressions: &Suppressions) -> Vec<Finding> {
+    let mut exports: BTreeMap<&str, usize> = BTreeMap::new();
+    for idx in graph.graph.node_indices() {
+        if graph.graph[idx].node_type != "export" {
+            continue;
+        }
+        let owner = graph
+            .graph
+            .edges_directed(idx, Direction::Incoming)
+            .filter(|edge| edge.weight().edge_type.eq_ignore_ascii_case("contains"))
+            .map(|edge| &graph.graph[edge.source()])
+            .find(|node| node.node_type == "file");
+        if let Some(file) = owner {
+            *exports.entry(file.id.as_str()).or_default() += 1;
+        }
+    }
+
+    exports
+        .into_iter()
+        .filter(|(file, _)| {
+            let idx = graph.node_map[*file];
+            let imported = graph
+                .graph
+                .edges_directed(idx, Direction::Incoming)
+                .any(|edge| edge.weight().edge_type.eq_ignore_ascii_case("imports") && edge.source() != idx);
+            !imported && !suppressions.is_suppressed(file, "Unused Export")
+        })
+        .map(|(file, count)| Finding {
+            kind: FindingKind::UnusedExport,
+            title: "Unused Export".to_string(),
+            description: format!("{} exports, but no file imports it", count),
+            severity: Severity::Low,
+            effort: Effort::Small,
+            nodes: vec![report_node(graph, file)],
+        })
+        .collect()
+}
+
+fn high_risk_findings(graph: &CodeGraph, metrics: &[UINodeMetricsV1], suppressions: &Suppressions) -> Vec<Finding> {
+    let ids: HashMap<(&str, &str, &str), &str> = graph
+        .graph
+        .node_weights()
+        .map(|node| {
+            let path = node.file_path.as_deref().unwrap_or(&node.id);
+            ((path, node.name.as_str(), node.node_type.as_str()), node.id.as_str())
+        })
+        .collect();
+    metrics
+        .iter()
+        .filter(|m| m.risk > HIGH_RISK_THRESHOLD)
+        .filter_map(|m| {
+            let id = *ids.get(&(m.path.as_str(), m.name.as_str(), m.node_type.as_str()))?;
+            if suppressions.is_suppressed(id, "High Risk") {
+                return None;
+            }
+            Some(Finding {
+                kind: FindingKind::HighRisk,
+                title: "High Risk".to_string(),
+                description: format!("Risk {:.2} with complexity {}", m.risk, m.raw.complexity),
+                severity: if m.risk > SEVERE_RISK_THRESHOLD { Severity::High } else { Severity::Medium },
+                effort: match m.raw.complexity {
+                    ..=10 => Effort::Small,
+                    11..=20 => Effort::Medium,
+                    _ => Effort::Large,
+                },
+                nodes: vec![report_node(graph, id)],
+            })
+        })
+        .collect()
+}
+
+/// Every finding on the graph as one prioritized queue
+///
+/// High-risk findings need metrics from a full analysis and are missing
+/// without them. Findings silenced by `suppressions` are left out, and
+/// `functions` holds the shape of each function and method by node id.
+pub fn list_findings(
+    graph: &CodeGraph,
+    metrics: &[UINodeMetricsV1],
+    suppressions: &Suppressions,
+    functions: &HashMap<String, FunctionMetrics>,
+    sort: FindingSort,
+    filter: &FindingFilter,
+) -> Vec<Finding> {
+    let mut findings: Vec<Finding> = smell_findings(graph, suppressions, functions)
+        .into_iter()
+        .chain(cycle_findings(graph, suppressions))
+        .chain(unused_export_findings(graph, suppressions))
+        .chain(high_risk_findings(graph, metrics, suppressions))
+        .filter(|finding| filter.matches(finding))
+        .collect();
+
+    // Ties fall back to kind, title and first node for stable output
+    let first_node = |finding: &Finding| finding.nodes.first().map(|node| node.id.clone());
+    findings.sort_by(|a, b| {
+        let order = match sort {
+            FindingSort::Priority => b.severity.cmp(&a.severity).then(a.effort.cmp(&b.effort)),
+            FindingSort::Severity => b.severity.cmp(&a.severity).then(b.nodes.len().cmp(&a.nodes.len())),
+            FindingSort::Effort => a.effort.cmp(&b.effort).then(b.severity.cmp(&a.severity)),
+        };
+        order
+            .then(a.kind.cmp(&b.kind))
+            .then(a.title.cmp(&b.title))
+            .then_with(|| first_node(a).cmp(&first_node(b)))
+    });
+    findings
+}
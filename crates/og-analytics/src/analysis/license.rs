@@ -0,0 +1,202 @@
+use og_types::{LicenseFile, LicenseHeader};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// A directory with a license file, and the source files it covers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageLicense {
+    pub path: String,
+    pub license_files: Vec<String>,
+    pub licenses: Vec<String>,
+    /// Files below the directory that no deeper license file covers
+    pub files: usize,
+}
+
+/// License markers of one source file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileLicense {
+    pub path: String,
+    /// Licenses named in the file's header
+    pub licenses: Vec<String>,
+    pub copyright: Option<String>,
+    /// Nearest directory above the file with a license file
+    pub package: Option<String>,
+}
+
+/// What is wrong with a file's license markers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LicenseProblemKind {
+    /// Neither a header nor a license file covers the file
+    Missing,
+    /// The header names licenses the package's license file does not grant
+    Conflicting,
+}
+
+/// A source file flagged by the license scan
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LicenseProblem {
+    pub path: String,
+    pub kind: LicenseProblemKind,
+    pub message: String,
+}
+
+/// Licenses of every package and source file, for compliance audits
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LicenseInventory {
+    /// By path
+    pub packages: Vec<PackageLicense>,
+    /// By path
+    pub files: Vec<FileLicense>,
+    /// By path
+    pub problems: Vec<LicenseProblem>,
+}
+
+/// License identifiers of an SPDX expression, lowercase and without
+/// `-only`, `-or-later` or `+` suffixes
+fn license_ids(expression: &str) -> Vec<String> {
+    expression
+        .split(|c: char| c.is_whitespace() || c == '(' || c == ')')
+        .filter(|id| !id.is_empty() && !matches!(*id, "OR" | "AND" | "WITH"))
+        .map(|id| {
+            let id = id.to_lowercase();
+            let id = id.trim_end_matches('+');
+            let id = id.strip_suffix("-only").or_else(|| id.strip_suffix("-or-later")).unwrap_or(id);
+            id.to_string()
+        })
+        .collect()
+}
+
+/// Whether a header license agrees with one a package grants, where a
+/// license without a version agrees with any version of it
+fn compatible(header: &[String], package: &[String]) -> bool {
+    let package: Vec<String> = package.iter().flat_map(|l| license_ids(l)).collect();
+    header.iter().flat_map(|l| license_ids(l)).any(|id| {
+        package
+            .iter()
+            .any(|granted| *granted == id || granted.starts_with(&format!("{}-", id)) || id.starts_with(&format!("{}-", granted)))
+    })
+}
+
+fn quote_csv(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+impl LicenseInventory {
+    /// Match each file's header to the license files above it
+    ///
+    /// `headers` holds the header of each source file by path. A file is
+    /// covered by the license files in its own directory or the nearest one
+    /// above it. A file whose header names no license is only a problem when
+    /// no license file covers it, and a header with an unrecognized license
+    /// file above it is not flagged.
+    pub fn build(headers: &BTreeMap<String, LicenseHeader>, license_files: &[LicenseFile]) -> Self {
+        let mut packages: BTreeMap<String, PackageLicense> = BTreeMap::new();
+        for file in license_files {
+            let dir = Path::new(&file.path).parent().map(|dir| dir.display().to_string()).unwrap_or_default();
+            let package = packages.entry(dir.clone()).or_insert_with(|| PackageLicense {
+                path: dir,
+                license_files: Vec::new(),
+                licenses: Vec::new(),
+                files: 0,
+            });
+            package.license_files.push(file.path.clone());
+            for license in &file.licenses {
+                if !package.licenses.contains(license) {
+                    package.licenses.push(license.clone());
+                }
+            }
+        }
+
+        let mut files = Vec::with_capacity(headers.len());
+        let mut problems = Vec::new();
+        for (path, header) in headers {
+            let package = Path::new(path)
+                .ancestors()
+                .skip(1)
+                .map(|dir| dir.display().to_string())
+                .find(|dir| packages.contains_key(dir))
+                .and_then(|dir| packages.get_mut(&dir));
+            match &package {
+                None if header.licenses.is_empty() => problems.push(LicenseProblem {
+                    path: path.clone(),
+                    kind: LicenseProblemKind::Missing,
+                    message: "No license header and no license file covers this file".to_string(),
+                }),
+                Some(package)
+                    if !header.licenses.is_empty()
+                        && !package.licenses.is_empty()
+                        && !compatible(&header.licenses, &package.licenses) =>
+                {
+                    problems.push(LicenseProblem {
+                        path: path.clone(),
+                        kind: LicenseProblemKind::Conflicting,
+                        message: format!(
+                            "Header says {} but {} grants {}",
+                            header.licenses.join(", "),
+                            package.license_files.join(", "),
+                            package.licenses.join(", ")
+                        ),
+                    });
+                }
+                _ => {}
+            }
+            let package = package.map(|package| {
+                package.files += 1;
+                package.path.clone()
+            });
+            files.push(FileLicense {
+                path: path.clone(),
+                licenses: header.licenses.clone(),
+                copyright: header.copyright.clone(),
+                package,
+            });
+        }
+
+        Self {
+            packages: packages.into_values().collect(),
+            files,
+            problems,
+        }
+    }
+
+    /// One row per source file with its licenses, package and any problem
+    pub fn to_csv(&self) -> String {
+        let problems: BTreeMap<&str, &LicenseProblem> =
+            self.problems.iter().map(|problem| (problem.path.as_str(), problem)).collect();
+        let package_licenses: BTreeMap<&str, String> = self
+            .packages
+            .iter()
+            .map(|package| (package.path.as_str(), package.licenses.join(" ")))
+            .collect();
+
+        let mut csv = String::from("path,header_licenses,copyright,package,package_licenses,problem\n");
+        for file in &self.files {
+            let package = file.package.as_deref().unwrap_or("");
+            let problem = problems.get(file.path.as_str()).map_or("", |problem| match problem.kind {
+                LicenseProblemKind::Missing => "missing",
+                LicenseProblemKind::Conflicting => "conflicting",
+            });
+            let row = [
+                file.path.as_str(),
+                &file.licenses.join(" "),
+                file.copyright.as_deref().unwrap_or(""),
+                package,
+                package_licenses.get(package).map_or("", String::as_str),
+                problem,
+            ];
+            csv.push_str(&row.map(quote_csv).join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+}
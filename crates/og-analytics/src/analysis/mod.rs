@@ -1,5 +1,47 @@
+pub mod affinity;
+pub mod alerts;
+pub mod coloring;
+pub mod concurrency;
+pub mod debt;
+pub mod distribution;
+pub mod documentation;
+pub mod evolution;
+pub mod export;
+pub mod findings;
 pub mod impact;
+pub mod license;
+pub mod node_detail;
+pub mod node_table;
+pub mod pr_summary;
+pub mod query;
+pub mod refactoring;
 pub mod report;
+pub mod similarity;
+pub mod sizing;
+pub mod structural_diff;
+pub mod table;
+pub mod treemap;
 
+pub use affinity::{module_affinity, NodeAffinity};
+pub use alerts::{dependency_cycles, Alert, AlertKind, AlertRules};
+pub use coloring::{node_values, recolor, ColorMap, ColorScale, Palette};
+pub use concurrency::{ConcurrencyMap, ConcurrentNode, ModuleConcurrency};
+pub use debt::{DebtReport, FileDebt};
+pub use distribution::{metric_distributions, metric_values, MetricDistribution, NodeMetricValues};
+pub use documentation::{DocumentationReport, FileDocumentation};
+pub use evolution::{EvolutionTimeline, HistorySnapshot, ModuleChange};
+pub use export::{ArchitectureReport, ReportFormat};
+pub use findings::{list_findings, smell_counts, Effort, Finding, FindingFilter, FindingKind, FindingSort, Severity};
 pub use impact::ImpactAnalysis;
-pub use report::AnalysisReport;
\ No newline at end of file
+pub use license::{LicenseInventory, LicenseProblem, LicenseProblemKind};
+pub use node_detail::NodeMetricsDetail;
+pub use node_table::{list_nodes, NodeFilter, NodePage, NodeRow, SortOrder, UnknownColumn};
+pub use pr_summary::{AnalysisSide, PrSummary};
+pub use query::{GraphQuery, QueryResult};
+pub use refactoring::{extract_module_candidates, RefactoringSuggestion};
+pub use report::{AnalysisReport, MetricTiming};
+pub use similarity::{find_similar_nodes, SimilarNode};
+pub use sizing::{resize, NodeSizes, SizeMetric, SizeScale};
+pub use structural_diff::{ModuleDependency, StructuralDiff};
+pub use table::{MetricsTable, TableFormat};
+pub use treemap::{build_treemap, Treemap, TreemapMetric, TreemapNode};
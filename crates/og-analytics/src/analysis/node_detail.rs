@@ -0,0 +1,130 @@
+use crate::analysis::distribution::{metric_extractor, metric_names};
+use crate::analysis::export::{report_node, ReportNode, ReportSmell};
+use crate::metrics::risk::{RiskAnalysis, RiskExplanation};
+use og_graph::graph::CodeGraph;
+use og_metrics_quality::QualityAnalyzer;
+use og_types::metrics::UINodeMetricsV1;
+use og_types::{FunctionMetrics, Suppressions};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// The community a node was placed in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommunityMembership {
+    pub id: i64,
+    /// Nodes in the community, the node included
+    pub size: usize,
+}
+
+/// Everything known about one node, for an inspector panel
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeMetricsDetail {
+    pub node: ReportNode,
+    pub node_type: String,
+    /// Metrics from the last full analysis, None if it was skipped
+    pub metrics: Option<UINodeMetricsV1>,
+    /// Share of nodes, from 0 to 100, with a lower value of each metric
+    pub percentiles: BTreeMap<String, f64>,
+    pub community: Option<CommunityMembership>,
+    /// Members of the dependency cycle the node is part of, empty if it is in none
+    pub cycle: Vec<ReportNode>,
+    pub smells: Vec<ReportSmell>,
+    pub risk: RiskExplanation,
+    /// Length, parameters, nesting and returns of a function or method
+    #[serde(default)]
+    pub function: Option<FunctionMetrics>,
+}
+
+/// Percentile rank of `value` among `values`, counting ties as half below
+fn percentile_rank(values: &[f64], value: f64) -> f64 {
+    let below = values.iter().filter(|v| **v < value).count() as f64;
+    let equal = values.iter().filter(|v| **v == value).count() as f64;
+    100.0 * (below + equal / 2.0) / values.len() as f64
+}
+
+impl NodeMetricsDetail {
+    /// Collect the detail of `node_id`, or None if the graph has no such node
+    ///
+    /// Percentiles and community need metrics from a full analysis and are
+    /// empty without them. Smells silenced by `suppressions` are left out.
+    /// `functions` holds the shape of each function and method by node id.
+    pub fn build(
+        graph: &CodeGraph,
+        metrics: &[UINodeMetricsV1],
+        suppressions: &Suppressions,
+        functions: &HashMap<String, FunctionMetrics>,
+        node_id: &str,
+    ) -> Option<Self> {
+        let node = &graph.graph[*graph.node_map.get(node_id)?];
+        let path = node.file_path.as_deref().unwrap_or(&node.id);
+        let own = metrics
+            .iter()
+            .find(|m| m.path == path && m.name == node.name && m.node_type == node.node_type)
+            .cloned();
+
+        let mut percentiles = BTreeMap::new();
+        if let Some(own) = &own {
+            for name in metric_names() {
+                let Some(extract) = metric_extractor(name) else {
+                    continue;
+                };
+                let Some(value) = extract(own).filter(|v| v.is_finite()) else {
+                    continue;
+                };
+                let values: Vec<f64> = metrics.iter().filter_map(extract).filter(|v| v.is_finite()).collect();
+                percentiles.insert(name.to_string(), percentile_rank(&values, value));
+            }
+        }
+
+        let community = own.as_ref().map(|own| CommunityMembership {
+            id: own.community,
+            size: metrics.iter().filter(|m| m.community == own.community).count(),
+        });
+
+        let cycle = graph
+            .condensed()
+            .cycle_members
+            .into_values()
+            .find(|members| members.iter().any(|id| id == node_id))
+            .map(|members| members.iter().map(|id| report_node(graph, id)).collect())
+            .unwrap_or_default();
+
+        let analyzer = QualityAnalyzer {
+            suppressions: suppressions.clone(),
+            function_metrics: functions.clone(),
+            ..QualityAnalyzer::default()
+        };
+        let smells = analyzer
+            .analyze_quality(graph)
+            .unwrap_or_default()
+            .code_smells
+            .remove(node_id)
+            .map(|found| {
+                found
+                    .smells
+                    .into_iter()
+                    .map(|smell| ReportSmell {
+                        node: report_node(graph, node_id),
+                        smell_type: smell.smell_type,
+                        severity: smell.severity,
+                        description: smell.description,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Some(Self {
+            node: report_node(graph, node_id),
+            node_type: node.node_type.clone(),
+            metrics: own,
+            percentiles,
+            community,
+            cycle,
+            smells,
+            risk: RiskAnalysis::new().explain(graph, node_id).unwrap_or_default(),
+            function: functions.get(node_id).copied(),
+        })
+    }
+}
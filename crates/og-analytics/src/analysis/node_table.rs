@@ -0,0 +1,193 @@
+use super::distribution::{metric_extractor, metric_names, Extractor};
+use og_graph::graph::{GraphData, GraphNode};
+use og_types::metrics::UINodeMetricsV1;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::ops::Range;
+
+/// Columns that sort by node text rather than a metric
+const TEXT_COLUMNS: &[&str] = &["name", "type", "path"];
+
+/// Which nodes a table lists, every node when left empty
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NodeFilter {
+    /// Keep nodes whose name or file path contains this text, ignoring case
+    pub search: Option<String>,
+    /// Node types to keep, all types when empty
+    pub node_types: Vec<String>,
+    /// Smallest value to keep per metric; nodes lacking the metric are dropped
+    pub min: BTreeMap<String, f64>,
+    /// Largest value to keep per metric; nodes lacking the metric are dropped
+    pub max: BTreeMap<String, f64>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+/// One node of a table page
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeRow {
+    pub id: String,
+    pub name: String,
+    pub node_type: String,
+    pub file_path: Option<String>,
+    /// Value of each requested metric, None where the node lacks it
+    pub values: BTreeMap<String, Option<f64>>,
+}
+
+/// One page of a sorted, filtered node table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodePage {
+    pub rows: Vec<NodeRow>,
+    /// Nodes passing the filter, across all pages
+    pub total: usize,
+    pub offset: usize,
+}
+
+/// A filter, sort or column name that is neither a metric nor a text column
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownColumn(pub String);
+
+impl fmt::Display for UnknownColumn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let expected: Vec<&str> = TEXT_COLUMNS.iter().copied().chain(metric_names()).collect();
+        write!(f, "Unknown column: {} (expected one of {})", self.0, expected.join(", "))
+    }
+}
+
+impl std::error::Error for UnknownColumn {}
+
+fn extractor(metric: &str) -> Result<Extractor, UnknownColumn> {
+    metric_extractor(metric).ok_or_else(|| UnknownColumn(metric.to_string()))
+}
+
+enum SortKey {
+    Text(fn(&GraphNode) -> &str),
+    Metric(Extractor),
+}
+
+impl SortKey {
+    fn parse(column: &str) -> Result<Self, UnknownColumn> {
+        Ok(match column {
+            "name" => Self::Text(|node| &node.name),
+            "type" => Self::Text(|node| &node.node_type),
+            "path" => Self::Text(|node| node.file_path.as_deref().unwrap_or(&node.id)),
+            metric => Self::Metric(extractor(metric)?),
+        })
+    }
+
+    /// Compares in `order`, except that nodes lacking the metric always sort
+    /// last; ties fall back to the node id for stable pages
+    fn compare(&self, order: SortOrder, a: &Candidate, b: &Candidate) -> Ordering {
+        let ordering = match self {
+            Self::Text(text) => Some(text(a.node).cmp(text(b.node))),
+            Self::Metric(extract) => match (a.metrics.and_then(extract), b.metrics.and_then(extract)) {
+                (Some(x), Some(y)) => Some(x.total_cmp(&y)),
+                (Some(_), None) => return Ordering::Less,
+                (None, Some(_)) => return Ordering::Greater,
+                (None, None) => None,
+            },
+        };
+        let ordering = ordering.unwrap_or(Ordering::Equal);
+        let ordering = if order == SortOrder::Desc { ordering.reverse() } else { ordering };
+        ordering.then_with(|| a.node.id.cmp(&b.node.id))
+    }
+}
+
+struct Candidate<'a> {
+    node: &'a GraphNode,
+    metrics: Option<&'a UINodeMetricsV1>,
+}
+
+/// One page of graph nodes, filtered and sorted by a text column or any metric
+///
+/// Only the rows up to the end of the page are fully sorted, so deep tables
+/// page without sorting every node. `page` is the range of filtered rows to
+/// return, and `columns` selects the metrics per row, every metric when empty.
+pub fn list_nodes(
+    graph: &GraphData,
+    metrics: &[UINodeMetricsV1],
+    filter: &NodeFilter,
+    sort_by: &str,
+    order: SortOrder,
+    columns: &[String],
+    page: Range<usize>,
+) -> Result<NodePage, UnknownColumn> {
+    let sort_key = SortKey::parse(sort_by)?;
+    let columns: Vec<(&str, Extractor)> = if columns.is_empty() {
+        metric_names().map(|name| Ok((name, extractor(name)?))).collect::<Result<_, _>>()?
+    } else {
+        columns.iter().map(|name| Ok((name.as_str(), extractor(name)?))).collect::<Result<_, _>>()?
+    };
+    let bounds = |limits: &BTreeMap<String, f64>| {
+        limits
+            .iter()
+            .map(|(name, limit)| Ok((extractor(name)?, *limit)))
+            .collect::<Result<Vec<_>, UnknownColumn>>()
+    };
+    let (min, max) = (bounds(&filter.min)?, bounds(&filter.max)?);
+    let search = filter.search.as_deref().map(str::to_lowercase);
+
+    let by_node: HashMap<(&str, &str, &str), &UINodeMetricsV1> = metrics
+        .iter()
+        .map(|m| ((m.path.as_str(), m.name.as_str(), m.node_type.as_str()), m))
+        .collect();
+    let mut candidates: Vec<Candidate> = graph
+        .nodes
+        .iter()
+        .filter(|node| filter.node_types.is_empty() || filter.node_types.contains(&node.node_type))
+        .filter(|node| {
+            search.as_deref().is_none_or(|search| {
+                node.name.to_lowercase().contains(search)
+                    || node.file_path.as_deref().is_some_and(|path| path.to_lowercase().contains(search))
+            })
+        })
+        .map(|node| {
+            let path = node.file_path.as_deref().unwrap_or(&node.id);
+            let metrics = by_node.get(&(path, node.name.as_str(), node.node_type.as_str())).copied();
+            Candidate { node, metrics }
+        })
+        .filter(|candidate| {
+            let value = |extract: &Extractor| candidate.metrics.and_then(extract);
+            min.iter().all(|(extract, min)| value(extract).is_some_and(|v| v >= *min))
+                && max.iter().all(|(extract, max)| value(extract).is_some_and(|v| v <= *max))
+        })
+        .collect();
+
+    let total = candidates.len();
+    let (offset, end) = (page.start, page.end.min(total));
+    if offset >= end {
+        return Ok(NodePage { rows: Vec::new(), total, offset });
+    }
+    let compare = |a: &Candidate, b: &Candidate| sort_key.compare(order, a, b);
+    if end < total {
+        candidates.select_nth_unstable_by(end, compare);
+        candidates.truncate(end);
+    }
+    candidates.sort_by(compare);
+
+    let rows = candidates[offset..]
+        .iter()
+        .map(|Candidate { node, metrics }| NodeRow {
+            id: node.id.clone(),
+            name: node.name.clone(),
+            node_type: node.node_type.clone(),
+            file_path: node.file_path.clone(),
+            values: columns
+                .iter()
+                .map(|(name, extract)| (name.to_string(), metrics.and_then(extract)))
+                .collect(),
+        })
+        .collect();
+    Ok(NodePage { rows, total, offset })
+}
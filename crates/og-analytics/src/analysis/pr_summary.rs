@@ -0,0 +1,191 @@
+use super::export::escape_markdown;
+use og_graph::graph::CodeGraph;
+use og_types::metrics::UINodeMetricsV1;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+/// Files listed in each section of a PR summary
+pub const DEFAULT_PR_FILES: usize = 20;
+
+/// Smallest risk change worth listing
+const RISK_EPSILON: f64 = 0.005;
+
+/// One side of a comparison: an analysis and the root its paths are under
+pub struct AnalysisSide<'a> {
+    pub root: &'a str,
+    pub graph: &'a CodeGraph,
+    pub metrics: &'a [UINodeMetricsV1],
+}
+
+/// Risk and complexity of a file before and after a change
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileDelta {
+    /// Path relative to the analyzed root
+    pub path: String,
+    /// Highest risk of any node in the file, None if the file did not exist
+    pub risk_before: Option<f64>,
+    pub risk_after: Option<f64>,
+    /// Total complexity of the file's nodes
+    pub complexity_before: Option<i64>,
+    pub complexity_after: Option<i64>,
+}
+
+impl FileDelta {
+    fn risk_delta(&self) -> f64 {
+        self.risk_after.unwrap_or(0.0) - self.risk_before.unwrap_or(0.0)
+    }
+
+    fn complexity_delta(&self) -> i64 {
+        self.complexity_after.unwrap_or(0) - self.complexity_before.unwrap_or(0)
+    }
+}
+
+/// What a change did to the architecture, ready to post on a pull request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrSummary {
+    /// Members of cycles that did not exist in the baseline, by relative id
+    pub new_cycles: Vec<Vec<String>>,
+    pub resolved_cycles: usize,
+    /// Touched files, largest risk change first
+    pub files: Vec<FileDelta>,
+    /// Touched files left out of `files`
+    pub omitted_files: usize,
+}
+
+/// Strip the analyzed root so the same file matches across checkouts
+pub(crate) fn relative(root: &str, text: &str) -> String {
+    if root.is_empty() {
+        return text.to_string();
+    }
+    text.replace(root, "").replace("//", "/").trim_start_matches('/').to_string()
+}
+
+fn cycles(side: &AnalysisSide) -> BTreeSet<Vec<String>> {
+    side.graph
+        .condensed()
+        .cycle_members
+        .into_values()
+        .map(|members| {
+            let mut members: Vec<String> = members.iter().map(|id| relative(side.root, id)).collect();
+            members.sort();
+            members
+        })
+        .collect()
+}
+
+/// Highest risk and total complexity per relative file path
+fn file_metrics(side: &AnalysisSide) -> BTreeMap<String, (f64, i64)> {
+    let mut files: BTreeMap<String, (f64, i64)> = BTreeMap::new();
+    for m in side.metrics {
+        let entry = files.entry(relative(side.root, &m.path)).or_insert((0.0, 0));
+        entry.0 = entry.0.max(m.risk as f64);
+        entry.1 += m.raw.complexity;
+    }
+    files
+}
+
+impl PrSummary {
+    /// Compare a baseline analysis with the current one
+    ///
+    /// A file counts as touched when it was added, removed, or its risk or
+    /// complexity changed. At most `limit` files are listed.
+    pub fn build(baseline: &AnalysisSide, current: &AnalysisSide, limit: usize) -> Self {
+        let before_cycles = cycles(baseline);
+        let after_cycles = cycles(current);
+        let new_cycles: Vec<Vec<String>> = after_cycles.difference(&before_cycles).cloned().collect();
+        let resolved_cycles = before_cycles.difference(&after_cycles).count();
+
+        let before = file_metrics(baseline);
+        let after = file_metrics(current);
+        let paths: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+        let mut files: Vec<FileDelta> = paths
+            .into_iter()
+            .map(|path| FileDelta {
+                path: path.clone(),
+                risk_before: before.get(path).map(|m| m.0),
+                risk_after: after.get(path).map(|m| m.0),
+                complexity_before: before.get(path).map(|m| m.1),
+                complexity_after: after.get(path).map(|m| m.1),
+            })
+            .filter(|delta| {
+                delta.risk_before.is_none()
+                    || delta.risk_after.is_none()
+                    || delta.risk_delta().abs() >= RISK_EPSILON
+                    || delta.complexity_delta() != 0
+            })
+            .collect();
+        files.sort_by(|a, b| {
+            b.risk_delta()
+                .abs()
+                .total_cmp(&a.risk_delta().abs())
+                .then_with(|| b.complexity_delta().abs().cmp(&a.complexity_delta().abs()))
+                .then_with(|| a.path.cmp(&b.path))
+        });
+        let omitted_files = files.len().saturating_sub(limit);
+        files.truncate(limit);
+
+        Self {
+            new_cycles,
+            resolved_cycles,
+            files,
+            omitted_files,
+        }
+    }
+
+    /// Render the summary as a Markdown PR comment
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "## Architecture changes\n");
+
+        if self.new_cycles.is_empty() {
+            let _ = writeln!(out, "No new dependency cycles.");
+        } else {
+            let _ = writeln!(out, "**{} new dependency cycle(s):**\n", self.new_cycles.len());
+            for cycle in &self.new_cycles {
+                let members: Vec<String> = cycle.iter().map(|id| format!("`{}`", id)).collect();
+                let _ = writeln!(out, "- {}", members.join(" → "));
+            }
+        }
+        if self.resolved_cycles > 0 {
+            let _ = writeln!(out, "\n{} cycle(s) resolved.", self.resolved_cycles);
+        }
+        out.push('\n');
+
+        if self.files.is_empty() {
+            let _ = writeln!(out, "No risk or complexity changes in touched files.");
+            return out;
+        }
+
+        let _ = writeln!(out, "| File | Risk | Δ Risk | Complexity | Δ Complexity |\n| --- | --- | --- | --- | --- |");
+        for file in &self.files {
+            let risk = match (file.risk_before, file.risk_after) {
+                (Some(before), Some(after)) => format!("{:.2} → {:.2}", before, after),
+                (None, Some(after)) => format!("new, {:.2}", after),
+                (Some(before), None) => format!("{:.2}, removed", before),
+                (None, None) => String::new(),
+            };
+            let complexity = match (file.complexity_before, file.complexity_after) {
+                (Some(before), Some(after)) => format!("{} → {}", before, after),
+                (None, Some(after)) => after.to_string(),
+                (Some(before), None) => before.to_string(),
+                (None, None) => String::new(),
+            };
+            let _ = writeln!(
+                out,
+                "| `{}` | {} | {:+.2} | {} | {:+} |",
+                escape_markdown(&file.path),
+                risk,
+                file.risk_delta(),
+                complexity,
+                file.complexity_delta()
+            );
+        }
+        if self.omitted_files > 0 {
+            let _ = writeln!(out, "\n…and {} more file(s).", self.omitted_files);
+        }
+        out
+    }
+}
@@ -0,0 +1,555 @@
+use og_graph::graph::CodeGraph;
+use og_types::metrics::UINodeMetricsV1;
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Rows returned when a question doesn't ask for a number of results
+pub const DEFAULT_QUERY_LIMIT: usize = 100;
+
+/// Rows returned when a question asks for the top results without a number
+const DEFAULT_RANKED_LIMIT: usize = 10;
+
+/// Plural and singular nouns for graph node types
+const NODE_TYPE_WORDS: &[(&str, &str)] = &[
+    ("files", "file"),
+    ("file", "file"),
+    ("modules", "file"),
+    ("module", "file"),
+    ("functions", "function"),
+    ("function", "function"),
+    ("methods", "method"),
+    ("method", "method"),
+    ("classes", "class"),
+    ("class", "class"),
+    ("interfaces", "interface"),
+    ("interface", "interface"),
+    ("routes", "route"),
+    ("route", "route"),
+    ("endpoints", "route"),
+    ("tables", "table"),
+    ("table", "table"),
+    ("models", "model"),
+    ("model", "model"),
+    ("events", "event"),
+    ("event", "event"),
+    ("variables", "env_var"),
+];
+
+/// Verbs that relate nodes, the edge type they mean, and whether they are passive
+const RELATION_VERBS: &[(&str, Option<&str>, bool)] = &[
+    ("import", Some("imports"), false),
+    ("imports", Some("imports"), false),
+    ("imported", Some("imports"), true),
+    ("call", Some("calls"), false),
+    ("calls", Some("calls"), false),
+    ("called", Some("calls"), true),
+    ("extend", Some("extends"), false),
+    ("extends", Some("extends"), false),
+    ("extended", Some("extends"), true),
+    ("implement", Some("implements"), false),
+    ("implements", Some("implements"), false),
+    ("implemented", Some("implements"), true),
+    ("read", Some("reads"), false),
+    ("reads", Some("reads"), false),
+    ("write", Some("writes"), false),
+    ("writes", Some("writes"), false),
+    ("written", Some("writes"), true),
+    ("publish", Some("publishes"), false),
+    ("publishes", Some("publishes"), false),
+    ("emit", Some("publishes"), false),
+    ("emits", Some("publishes"), false),
+    ("subscribe", Some("subscribes"), false),
+    ("subscribes", Some("subscribes"), false),
+    ("use", None, false),
+    ("uses", None, false),
+    ("used", None, true),
+    ("reference", None, false),
+    ("references", None, false),
+    ("referenced", None, true),
+    ("depend", None, false),
+    ("depends", None, false),
+];
+
+/// Words that ask for results to be ranked, and whether lowest comes first
+const RANKING_WORDS: &[(&str, bool)] = &[
+    ("top", false),
+    ("most", false),
+    ("highest", false),
+    ("biggest", false),
+    ("largest", false),
+    ("least", true),
+    ("lowest", true),
+    ("bottom", true),
+    ("fewest", true),
+];
+
+/// Words naming the value to rank by
+const METRIC_WORDS: &[(&str, QueryMetric)] = &[
+    ("complex", QueryMetric::Complexity),
+    ("complexity", QueryMetric::Complexity),
+    ("risky", QueryMetric::Risk),
+    ("riskiest", QueryMetric::Risk),
+    ("risk", QueryMetric::Risk),
+    ("important", QueryMetric::Importance),
+    ("central", QueryMetric::Importance),
+    ("importance", QueryMetric::Importance),
+    ("churn", QueryMetric::Churn),
+    ("changed", QueryMetric::Churn),
+    ("churned", QueryMetric::Churn),
+    ("depended", QueryMetric::FanIn),
+    ("dependents", QueryMetric::FanIn),
+    ("used", QueryMetric::FanIn),
+    ("imported", QueryMetric::FanIn),
+    ("called", QueryMetric::FanIn),
+    ("referenced", QueryMetric::FanIn),
+    ("popular", QueryMetric::FanIn),
+    ("dependencies", QueryMetric::FanOut),
+    ("coupled", QueryMetric::FanOut),
+];
+
+/// Words that introduce a name to filter on
+const NAME_WORDS: &[&str] = &["named", "called", "matching", "containing", "like"];
+
+/// Filler words dropped from the names of related nodes
+const FILLER_WORDS: &[&str] = &[
+    "the", "a", "an", "on", "upon", "to", "from", "of", "by", "in", "any", "all", "our", "my",
+];
+
+const NUMBER_WORDS: &[(&str, usize)] = &[
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+    ("ten", 10),
+    ("twenty", 20),
+    ("fifty", 50),
+    ("hundred", 100),
+];
+
+/// Value that query results can be ranked by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum QueryMetric {
+    Complexity,
+    Risk,
+    Importance,
+    Churn,
+    /// Incoming dependencies, counted from the graph
+    FanIn,
+    /// Outgoing dependencies, counted from the graph
+    FanOut,
+}
+
+impl QueryMetric {
+    /// Whether the metric comes from a full analysis rather than the graph alone
+    pub fn needs_analysis(self) -> bool {
+        !matches!(self, QueryMetric::FanIn | QueryMetric::FanOut)
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            QueryMetric::Complexity => "complexity",
+            QueryMetric::Risk => "risk",
+            QueryMetric::Importance => "importance",
+            QueryMetric::Churn => "churn",
+            QueryMetric::FanIn => "incoming dependencies",
+            QueryMetric::FanOut => "outgoing dependencies",
+        }
+    }
+
+    fn value(self, metrics: &UINodeMetricsV1) -> f64 {
+        match self {
+            QueryMetric::Complexity => metrics.raw.complexity as f64,
+            QueryMetric::Risk => metrics.risk as f64,
+            QueryMetric::Importance => metrics.importance as f64,
+            QueryMetric::Churn => metrics.raw.churn as f64,
+            QueryMetric::FanIn => metrics.raw.indegree as f64,
+            QueryMetric::FanOut => metrics.raw.outdegree as f64,
+        }
+    }
+}
+
+/// Which end of an edge the matched nodes are on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum QueryDirection {
+    /// Nodes with an edge to the related nodes, as in "files that import auth"
+    To,
+    /// Nodes the related nodes have an edge to, as in "what does auth import"
+    From,
+}
+
+/// Nodes connected by an edge to nodes with a given name
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryRelation {
+    pub direction: QueryDirection,
+    /// Edge type to follow, any dependency when unset
+    pub edge_type: Option<String>,
+    /// Name, file name or path segment of the related nodes
+    pub other: String,
+}
+
+/// Structured graph query that plain-language questions are translated into
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphQuery {
+    pub node_type: Option<String>,
+    pub name_contains: Option<String>,
+    pub relation: Option<QueryRelation>,
+    pub sort_by: Option<QueryMetric>,
+    /// Rank lowest values first
+    pub ascending: bool,
+    pub limit: usize,
+}
+
+impl Default for GraphQuery {
+    fn default() -> Self {
+        Self {
+            node_type: None,
+            name_contains: None,
+            relation: None,
+            sort_by: None,
+            ascending: false,
+            limit: DEFAULT_QUERY_LIMIT,
+        }
+    }
+}
+
+/// A node that answers a query
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryRow {
+    pub id: String,
+    pub name: String,
+    pub node_type: String,
+    pub file_path: Option<String>,
+    /// Value the rows are ranked by, if any
+    pub value: Option<f64>,
+}
+
+/// Answer to a plain-language question
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryResult {
+    pub query: GraphQuery,
+    /// How the question was understood
+    pub interpretation: String,
+    pub rows: Vec<QueryRow>,
+}
+
+fn lookup<T: Copy>(table: &[(&str, T)], word: &str) -> Option<T> {
+    table.iter().find(|(w, _)| *w == word).map(|(_, value)| *value)
+}
+
+fn node_type_in(words: &[&str]) -> Option<String> {
+    words
+        .iter()
+        .find_map(|word| lookup(NODE_TYPE_WORDS, word))
+        .map(str::to_string)
+}
+
+/// Name of the related nodes, without filler words or a trailing type noun
+fn related_name(words: &[&str]) -> Option<String> {
+    let mut words: Vec<&str> = words
+        .iter()
+        .copied()
+        .filter(|word| !FILLER_WORDS.contains(word))
+        .collect();
+    if words.len() > 1 && words.last().is_some_and(|word| lookup(NODE_TYPE_WORDS, word).is_some()) {
+        words.pop();
+    }
+    (!words.is_empty()).then(|| words.join(" "))
+}
+
+fn number(word: &str) -> Option<usize> {
+    word.parse()
+        .ok()
+        .or_else(|| lookup(NUMBER_WORDS, word))
+        .filter(|&n| n > 0)
+}
+
+impl GraphQuery {
+    /// Translate a simple question into a query, or None if no rule matches
+    ///
+    /// Understands node types ("functions"), relations ("that import auth",
+    /// "what does app.ts call"), names ("named parse") and rankings ("top 10
+    /// most complex").
+    pub fn parse_natural(text: &str) -> Option<Self> {
+        let lower = text.to_lowercase();
+        let words: Vec<&str> = lower
+            .split(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '.' | '/' | '-' | '$')))
+            .map(|word| word.trim_matches('.'))
+            .filter(|word| !word.is_empty())
+            .collect();
+        let mut query = GraphQuery::default();
+
+        // "called by" and the like start a relation rather than name a metric
+        let before_by = |i: usize| words.get(i + 1) == Some(&"by");
+        let ranking = words.iter().find_map(|word| lookup(RANKING_WORDS, word));
+        let mut sort_by = words
+            .iter()
+            .enumerate()
+            .find_map(|(i, word)| lookup(METRIC_WORDS, word).filter(|_| !before_by(i)));
+        if words.contains(&"simplest") {
+            sort_by = Some(QueryMetric::Complexity);
+        }
+        let ranked = ranking.is_some() || words.contains(&"simplest");
+
+        // "most called functions" ranks by a metric rather than filtering on a name
+        let name_at = words.iter().enumerate().position(|(i, word)| {
+            NAME_WORDS.contains(word)
+                && i + 1 < words.len()
+                && !before_by(i)
+                && !(ranked && lookup(METRIC_WORDS, word).is_some())
+        });
+        let end = name_at.unwrap_or(words.len());
+        if let Some(i) = name_at {
+            query.name_contains = related_name(&words[i + 1..]);
+        }
+
+        // Passive verbs only relate nodes when followed by "by"
+        let verb_at = words[..end].iter().enumerate().position(|(i, word)| {
+            RELATION_VERBS
+                .iter()
+                .any(|(verb, _, passive)| verb == word && (!passive || before_by(i)))
+        });
+        if let Some(v) = verb_at {
+            let (_, edge_type, passive) = RELATION_VERBS.iter().find(|(verb, _, _)| *verb == words[v])?;
+            let edge_type = edge_type.map(str::to_string);
+            let asked_about = words[..v].iter().position(|word| matches!(*word, "does" | "do"));
+            query.relation = if *passive {
+                // "functions called by main"
+                query.node_type = node_type_in(&words[..v]);
+                related_name(&words[v + 2..end]).map(|other| QueryRelation {
+                    direction: QueryDirection::From,
+                    edge_type,
+                    other,
+                })
+            } else if let Some(d) = asked_about {
+                // "what does app.ts import"
+                query.node_type = node_type_in(&words[..d]).or_else(|| node_type_in(&words[v + 1..end]));
+                related_name(&words[d + 1..v]).map(|other| QueryRelation {
+                    direction: QueryDirection::From,
+                    edge_type,
+                    other,
+                })
+            } else {
+                // "which files import the auth module"
+                query.node_type = node_type_in(&words[..v]);
+                related_name(&words[v + 1..end]).map(|other| QueryRelation {
+                    direction: QueryDirection::To,
+                    edge_type,
+                    other,
+                })
+            };
+            query.relation.as_ref()?;
+        } else {
+            query.node_type = node_type_in(&words[..end]);
+        }
+
+        if ranked {
+            query.sort_by = Some(sort_by.unwrap_or(QueryMetric::FanIn));
+            query.ascending = ranking.unwrap_or(words.contains(&"simplest"));
+            query.limit = DEFAULT_RANKED_LIMIT;
+        }
+        if let Some(limit) = words[..end].iter().find_map(|word| number(word)) {
+            query.limit = limit;
+        }
+
+        let understood = query.node_type.is_some()
+            || query.name_contains.is_some()
+            || query.relation.is_some()
+            || query.sort_by.is_some();
+        understood.then_some(query)
+    }
+
+    /// Plain description of what the query matches
+    pub fn describe(&self) -> String {
+        let mut text = match &self.node_type {
+            Some(node_type) => format!("{} nodes", node_type),
+            None => "nodes".to_string(),
+        };
+        if let Some(name) = &self.name_contains {
+            text.push_str(&format!(" named like \"{}\"", name));
+        }
+        if let Some(relation) = &self.relation {
+            let edges = relation.edge_type.as_deref().unwrap_or("dependency");
+            match relation.direction {
+                QueryDirection::To => text.push_str(&format!(" with {} edges to \"{}\"", edges, relation.other)),
+                QueryDirection::From => {
+                    text.push_str(&format!(" reached by {} edges from \"{}\"", edges, relation.other))
+                }
+            }
+        }
+        match self.sort_by {
+            Some(metric) => text.push_str(&format!(
+                ", {} {} by {}",
+                if self.ascending { "bottom" } else { "top" },
+                self.limit,
+                metric.label()
+            )),
+            None => text.push_str(&format!(", at most {}", self.limit)),
+        }
+        text
+    }
+
+    fn type_matches(&self, node_type: &str) -> bool {
+        self.node_type
+            .as_deref()
+            .is_none_or(|wanted| wanted.eq_ignore_ascii_case(node_type))
+    }
+
+    /// Run the query against a graph, ranking analysis metrics from `metrics`
+    ///
+    /// When the wanted nodes are files, edges from the code inside a file count
+    /// as the file's own. Rows without a value for the ranking metric are left out.
+    pub fn answer(self, graph: &CodeGraph, metrics: &[UINodeMetricsV1]) -> QueryResult {
+        let g = &graph.graph;
+        let file_of: HashMap<&str, NodeIndex> = g
+            .node_indices()
+            .filter(|&idx| g[idx].node_type == "file")
+            .filter_map(|idx| g[idx].file_path.as_deref().map(|path| (path, idx)))
+            .collect();
+        let lift = |idx: NodeIndex| -> Option<NodeIndex> {
+            if self.type_matches(&g[idx].node_type) {
+                Some(idx)
+            } else if self.node_type.as_deref() == Some("file") {
+                g[idx].file_path.as_deref().and_then(|path| file_of.get(path).copied())
+            } else {
+                None
+            }
+        };
+
+        let mut candidates: HashSet<NodeIndex> = g
+            .node_indices()
+            .filter(|&idx| self.type_matches(&g[idx].node_type))
+            .filter(|&idx| {
+                self.name_contains
+                    .as_deref()
+                    .is_none_or(|name| g[idx].name.to_lowercase().contains(name))
+            })
+            .collect();
+
+        if let Some(relation) = &self.relation {
+            let named: Vec<NodeIndex> = g
+                .node_indices()
+                .filter(|&idx| names_node(graph, idx, &relation.other))
+                .collect();
+            // A named file stands for everything declared in it
+            let named_files: HashSet<&str> = named
+                .iter()
+                .filter(|&&idx| g[idx].node_type == "file")
+                .filter_map(|&idx| g[idx].file_path.as_deref())
+                .collect();
+            let related: HashSet<NodeIndex> = g
+                .node_indices()
+                .filter(|&idx| {
+                    named.contains(&idx) || g[idx].file_path.as_deref().is_some_and(|p| named_files.contains(p))
+                })
+                .collect();
+
+            let mut linked = HashSet::new();
+            for edge in g.edge_references() {
+                let edge_type = &edge.weight().edge_type;
+                if edge_type.eq_ignore_ascii_case("contains")
+                    || relation
+                        .edge_type
+                        .as_deref()
+                        .is_some_and(|wanted| !edge_type.eq_ignore_ascii_case(wanted))
+                {
+                    continue;
+                }
+                let (near, far) = match relation.direction {
+                    QueryDirection::To => (edge.source(), edge.target()),
+                    QueryDirection::From => (edge.target(), edge.source()),
+                };
+                if related.contains(&far) && !related.contains(&near) {
+                    linked.extend(lift(near));
+                }
+            }
+            candidates.retain(|idx| linked.contains(idx));
+        }
+
+        let by_location: HashMap<(&str, &str, &str), &UINodeMetricsV1> = metrics
+            .iter()
+            .map(|m| ((m.path.as_str(), m.name.as_str(), m.node_type.as_str()), m))
+            .collect();
+        let degree = |idx: NodeIndex, direction: Direction| {
+            g.edges_directed(idx, direction)
+                .filter(|edge| !edge.weight().edge_type.eq_ignore_ascii_case("contains"))
+                .count() as f64
+        };
+        let value = |idx: NodeIndex, metric: QueryMetric| -> Option<f64> {
+            let node = &g[idx];
+            match metric {
+                QueryMetric::FanIn => Some(degree(idx, Direction::Incoming)),
+                QueryMetric::FanOut => Some(degree(idx, Direction::Outgoing)),
+                _ => {
+                    let path = node.file_path.as_deref().unwrap_or(&node.id);
+                    by_location
+                        .get(&(path, node.name.as_str(), node.node_type.as_str()))
+                        .map(|m| metric.value(m))
+                }
+            }
+        };
+
+        let mut rows: Vec<QueryRow> = candidates
+            .into_iter()
+            .filter_map(|idx| {
+                let value = match self.sort_by {
+                    Some(metric) => Some(value(idx, metric)?),
+                    None => None,
+                };
+                let node = &g[idx];
+                Some(QueryRow {
+                    id: node.id.clone(),
+                    name: node.name.clone(),
+                    node_type: node.node_type.clone(),
+                    file_path: node.file_path.clone(),
+                    value,
+                })
+            })
+            .collect();
+        rows.sort_by(|a, b| {
+            let by_value = match (a.value, b.value) {
+                (Some(x), Some(y)) if self.ascending => x.total_cmp(&y),
+                (Some(x), Some(y)) => y.total_cmp(&x),
+                _ => std::cmp::Ordering::Equal,
+            };
+            by_value.then_with(|| a.name.cmp(&b.name)).then_with(|| a.id.cmp(&b.id))
+        });
+        rows.truncate(self.limit);
+
+        QueryResult {
+            interpretation: self.describe(),
+            query: self,
+            rows,
+        }
+    }
+}
+
+/// Whether a node is named by `name`, by its name, file stem or a path segment
+fn names_node(graph: &CodeGraph, idx: NodeIndex, name: &str) -> bool {
+    let node = &graph.graph[idx];
+    let node_name = node.name.to_lowercase();
+    if node_name == name || node_name.split('.').next() == Some(name) {
+        return true;
+    }
+    // Files are also named by the directories they are in, as in "the auth module"
+    node.node_type == "file"
+        && node.file_path.as_deref().is_some_and(|path| {
+            let path = path.to_lowercase().replace('\\', "/");
+            path.split('/')
+                .any(|segment| segment == name || segment.split('.').next() == Some(name))
+                || (name.contains('/') && path.contains(name))
+        })
+}
@@ -0,0 +1,182 @@
+use og_graph::graph::CodeGraph;
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use tracing::debug;
+
+/// Fewest functions or methods a file or class needs before it is split
+pub const DEFAULT_MIN_MEMBERS: usize = 6;
+
+/// A cohesive cluster of members that could move out of its file or class
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RefactoringSuggestion {
+    /// File or class the members currently live in
+    pub container_id: String,
+    pub container_name: String,
+    pub member_ids: Vec<String>,
+    pub member_names: Vec<String>,
+    /// Dependencies between members of the cluster
+    pub internal_edges: usize,
+    /// Dependencies between the cluster and the rest of the container
+    pub cut_edges: usize,
+    /// Share of the cluster's dependencies that leave the container with it
+    pub coupling_reduction: f64,
+}
+
+fn is_member_type(node_type: &str) -> bool {
+    matches!(node_type, "function" | "method")
+}
+
+fn is_contains(edge_type: &str) -> bool {
+    edge_type.eq_ignore_ascii_case("contains")
+}
+
+/// Modularity-maximising communities of a small undirected graph
+///
+/// Single-level Louvain local moving over nodes `0..n` in order, so the result
+/// is deterministic for a given edge list.
+fn local_communities(n: usize, edges: &[(usize, usize)]) -> Vec<usize> {
+    let mut adjacency: Vec<BTreeMap<usize, f64>> = vec![BTreeMap::new(); n];
+    for &(a, b) in edges {
+        *adjacency[a].entry(b).or_default() += 1.0;
+        *adjacency[b].entry(a).or_default() += 1.0;
+    }
+    let degree: Vec<f64> = adjacency.iter().map(|links| links.values().sum()).collect();
+    let two_m: f64 = degree.iter().sum();
+    if two_m == 0.0 {
+        return (0..n).collect();
+    }
+
+    let mut community: Vec<usize> = (0..n).collect();
+    let mut total = degree.clone();
+    for _ in 0..50 {
+        let mut moved = false;
+        for node in 0..n {
+            let current = community[node];
+            total[current] -= degree[node];
+
+            let mut weights: BTreeMap<usize, f64> = BTreeMap::new();
+            for (&neighbour, &weight) in &adjacency[node] {
+                *weights.entry(community[neighbour]).or_default() += weight;
+            }
+            let gain = |c: usize, w: f64| w - total[c] * degree[node] / two_m;
+
+            let mut best = (current, gain(current, weights.get(&current).copied().unwrap_or(0.0)));
+            for (&candidate, &weight) in &weights {
+                let candidate_gain = gain(candidate, weight);
+                if candidate_gain > best.1 + 1e-12 {
+                    best = (candidate, candidate_gain);
+                }
+            }
+
+            total[best.0] += degree[node];
+            if best.0 != current {
+                community[node] = best.0;
+                moved = true;
+            }
+        }
+        if !moved {
+            break;
+        }
+    }
+    community
+}
+
+/// Clusters of functions or methods within large files and classes that are
+/// more connected to each other than to the rest of their container
+///
+/// Containers with fewer than `min_members` direct members are skipped.
+/// Suggestions are sorted by coupling reduction, then by size.
+pub fn extract_module_candidates(graph: &CodeGraph, min_members: usize) -> Vec<RefactoringSuggestion> {
+    let mut suggestions = Vec::new();
+
+    for container in graph.graph.node_indices() {
+        let container_node = &graph.graph[container];
+        if !matches!(container_node.node_type.as_str(), "file" | "class") {
+            continue;
+        }
+
+        let members: HashSet<NodeIndex> = graph
+            .graph
+            .edges_directed(container, Direction::Outgoing)
+            .filter(|edge| is_contains(&edge.weight().edge_type))
+            .map(|edge| edge.target())
+            .filter(|&idx| is_member_type(&graph.graph[idx].node_type))
+            .collect();
+        if members.len() < min_members {
+            continue;
+        }
+
+        // Member-to-member dependencies only, so structure edges do not dominate
+        let mut subgraph = graph.induced_subgraph(&members);
+        subgraph.links.retain(|link| !is_contains(&link.link_type) && link.source != link.target);
+        if subgraph.links.is_empty() {
+            continue;
+        }
+        let position: HashMap<&str, usize> = subgraph
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (node.id.as_str(), i))
+            .collect();
+        let edges: Vec<(usize, usize)> = subgraph
+            .links
+            .iter()
+            .filter_map(|link| Some((*position.get(link.source.as_str())?, *position.get(link.target.as_str())?)))
+            .collect();
+        let communities = local_communities(subgraph.nodes.len(), &edges);
+
+        let mut clusters: BTreeMap<usize, Vec<String>> = BTreeMap::new();
+        for (node, community) in subgraph.nodes.iter().zip(communities) {
+            clusters.entry(community).or_default().push(node.id.clone());
+        }
+        debug!("{} members of {} form {} clusters", members.len(), container_node.id, clusters.len());
+
+        for mut cluster in clusters.into_values() {
+            // The whole container, or a lone member, is not an extraction
+            if cluster.len() < 2 || cluster.len() == members.len() {
+                continue;
+            }
+            cluster.sort();
+            let in_cluster: HashSet<&str> = cluster.iter().map(String::as_str).collect();
+
+            let (mut internal_edges, mut cut_edges) = (0, 0);
+            for link in &subgraph.links {
+                match (in_cluster.contains(link.source.as_str()), in_cluster.contains(link.target.as_str())) {
+                    (true, true) => internal_edges += 1,
+                    (true, false) | (false, true) => cut_edges += 1,
+                    (false, false) => {}
+                }
+            }
+            if internal_edges == 0 {
+                continue;
+            }
+
+            let member_names = cluster
+                .iter()
+                .filter_map(|id| graph.node_map.get(id))
+                .map(|&idx| graph.graph[idx].name.clone())
+                .collect();
+            suggestions.push(RefactoringSuggestion {
+                container_id: container_node.id.clone(),
+                container_name: container_node.name.clone(),
+                member_ids: cluster,
+                member_names,
+                internal_edges,
+                cut_edges,
+                coupling_reduction: internal_edges as f64 / (internal_edges + cut_edges) as f64,
+            });
+        }
+    }
+
+    suggestions.sort_by(|a, b| {
+        b.coupling_reduction
+            .total_cmp(&a.coupling_reduction)
+            .then(b.member_ids.len().cmp(&a.member_ids.len()))
+            .then(a.container_id.cmp(&b.container_id))
+    });
+    suggestions
+}
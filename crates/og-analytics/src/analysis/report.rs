@@ -1,13 +1,18 @@
 use super::impact::ImpactAnalysis;
 use crate::engine::MetricWeights;
-use crate::metrics::{MetricResults, MetricValue};
-use og_graph::graph::CodeGraph;
+use crate::metrics::{clamped_z_score, percentile_rank_sorted, MetricResults, MetricValue};
+use og_graph::attributes::{CHURN_ATTRIBUTE, COVERAGE_ATTRIBUTE};
+use og_graph::graph::{CodeGraph, GraphNode};
 use og_types::metrics::{
-    CompositeOutputs, NormalizedMetrics, NormalizationRanges, RawMetrics, UINodeMetricsV1,
+    CompositeOutputs, NormalizationMode, NormalizedMetrics, NormalizationRanges, RawMetrics, UINodeMetricsV1,
 };
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
 use tracing::debug;
 
+/// Standard deviations z-score normalization clamps to
+pub const DEFAULT_Z_CLAMP: f64 = 3.0;
+
 /// Complete analysis report
 #[derive(Debug, Clone)]
 pub struct AnalysisReport {
@@ -15,7 +20,18 @@ pub struct AnalysisReport {
     pub impact_analysis: ImpactAnalysis,
     pub composite_scores: HashMap<String, CompositeOutputs>,
     pub normalization_ranges: NormalizationRanges,
+    /// Sorted values observed for each metric, keyed by normalized field name
+    pub samples: BTreeMap<String, Vec<f64>>,
     pub summary: AnalysisSummary,
+    pub timings: Vec<MetricTiming>,
+}
+
+/// Wall-clock time spent computing one metric
+#[derive(Debug, Clone)]
+pub struct MetricTiming {
+    pub name: String,
+    pub duration: Duration,
+    pub steps: Vec<(String, Duration)>,
 }
 
 #[derive(Debug, Clone)]
@@ -31,37 +47,57 @@ pub struct AnalysisSummary {
 
 impl AnalysisReport {
     /// Create a new analysis report
-    pub fn new(metrics: Vec<MetricResults>, weights: &MetricWeights, graph: &CodeGraph) -> Self {
+    pub fn new(
+        metrics: Vec<MetricResults>,
+        weights: &MetricWeights,
+        normalization: NormalizationMode,
+        graph: &CodeGraph,
+    ) -> Self {
         debug!("Creating analysis report");
 
         // Perform impact analysis
         let impact_analysis = ImpactAnalysis::analyze(graph);
 
         // Calculate normalization ranges
-        let normalization_ranges = Self::calculate_normalization_ranges(&metrics, graph);
+        let (normalization_ranges, samples) =
+            Self::calculate_normalization_ranges(&metrics, graph, normalization);
 
         // Calculate composite scores
-        let composite_scores =
-            Self::calculate_composite_scores(&metrics, weights, &normalization_ranges, graph);
+        let scaler = Scaler::new(&normalization_ranges, &samples);
+        let composite_scores = Self::calculate_composite_scores(&metrics, weights, &scaler, graph);
 
         // Generate summary
         let summary = Self::generate_summary(&metrics, &composite_scores, graph);
 
+        let timings = metrics
+            .iter()
+            .map(|metric| MetricTiming {
+                name: metric.name.clone(),
+                duration: metric.duration,
+                steps: metric.step_timings.clone(),
+            })
+            .collect();
+
         Self {
             metrics,
             impact_analysis,
             composite_scores,
             normalization_ranges,
+            samples,
             summary,
+            timings,
         }
     }
 
-    /// Calculate normalization ranges for metrics
+    /// Calculate normalization ranges for metrics, with the values they were taken from
     fn calculate_normalization_ranges(
         metrics: &[MetricResults],
         graph: &CodeGraph,
-    ) -> NormalizationRanges {
+        mode: NormalizationMode,
+    ) -> (NormalizationRanges, BTreeMap<String, Vec<f64>>) {
         let mut ranges = NormalizationRanges {
+            mode,
+            z_clamp: DEFAULT_Z_CLAMP,
             pagerank_imports: (0.0, 1.0),
             pagerank_calls: (0.0, 1.0),
             k_core: (0.0, 10.0),
@@ -73,82 +109,70 @@ impl AnalysisReport {
             owners: (0.0, 10.0),
             coverage: (0.0, 1.0),
         };
+        let mut samples: BTreeMap<String, Vec<f64>> = BTreeMap::new();
 
         // Find actual ranges from metrics
         for result in metrics {
             match result.name.as_str() {
                 "centrality" => {
-                    // Extract betweenness range
-                    if let Some(MetricValue::Map(betweenness_map)) =
-                        result.values.get("betweenness_map")
-                    {
-                        let values: Vec<f64> = betweenness_map.values().copied().collect();
-                        if !values.is_empty() {
-                            ranges.betweenness = (
-                                values.iter().fold(f64::INFINITY, |a, &b| a.min(b)),
-                                values.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b)),
-                            );
-                        }
-                    }
-
-                    // Extract clustering range
-                    if let Some(MetricValue::Map(clustering_map)) =
-                        result.values.get("clustering_map")
-                    {
-                        let values: Vec<f64> = clustering_map.values().copied().collect();
-                        if !values.is_empty() {
-                            ranges.clustering = (
-                                values.iter().fold(f64::INFINITY, |a, &b| a.min(b)),
-                                values.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b)),
-                            );
+                    for (map_key, metric) in [("betweenness_map", "betweenness"), ("clustering_map", "clustering")] {
+                        if let Some(MetricValue::Map(map)) = result.values.get(map_key) {
+                            samples.insert(metric.to_string(), map.values().copied().collect());
                         }
                     }
                 }
                 "quality" => {
                     // Extract complexity ranges
-                    let mut complexity_values = Vec::new();
-                    for (key, value) in &result.values {
-                        if key.ends_with("_cyclomatic_complexity") {
-                            if let Some(v) = value.as_float() {
-                                complexity_values.push(v);
-                            }
-                        }
-                    }
-                    if !complexity_values.is_empty() {
-                        ranges.complexity = (
-                            complexity_values.iter().fold(f64::INFINITY, |a, &b| a.min(b)),
-                            complexity_values.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b)),
-                        );
-                    }
+                    let complexity_values: Vec<f64> = result
+                        .values
+                        .iter()
+                        .filter(|(key, _)| key.ends_with("_cyclomatic_complexity"))
+                        .filter_map(|(_, value)| value.as_float())
+                        .collect();
+                    samples.insert("complexity".to_string(), complexity_values);
                 }
                 _ => {}
             }
         }
 
         // Calculate degree ranges from graph
-        let mut indegrees = Vec::new();
-        for node_idx in graph.graph.node_indices() {
-            let indegree = graph
-                .graph
-                .edges_directed(node_idx, petgraph::Direction::Incoming)
-                .count() as f64;
-            indegrees.push(indegree);
-        }
-        if !indegrees.is_empty() {
-            ranges.indegree = (
-                indegrees.iter().fold(f64::INFINITY, |a, &b| a.min(b)),
-                indegrees.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b)),
-            );
+        let indegrees = graph
+            .graph
+            .node_indices()
+            .map(|node_idx| {
+                graph
+                    .graph
+                    .edges_directed(node_idx, petgraph::Direction::Incoming)
+                    .count() as f64
+            })
+            .collect();
+        samples.insert("indegree".to_string(), indegrees);
+
+        for (metric, values) in samples.iter_mut() {
+            values.retain(|v| v.is_finite());
+            values.sort_by(f64::total_cmp);
+            let (Some(&min), Some(&max)) = (values.first(), values.last()) else {
+                continue;
+            };
+            let range = match metric.as_str() {
+                "betweenness" => &mut ranges.betweenness,
+                "clustering" => &mut ranges.clustering,
+                "complexity" => &mut ranges.complexity,
+                "indegree" => &mut ranges.indegree,
+                _ => continue,
+            };
+            *range = (min, max);
         }
+        samples.retain(|_, values| !values.is_empty());
 
-        ranges
+        (ranges, samples)
     }
 
     /// Calculate composite scores for all nodes
     fn calculate_composite_scores(
         metrics: &[MetricResults],
         weights: &MetricWeights,
-        ranges: &NormalizationRanges,
+        scaler: &Scaler,
         graph: &CodeGraph,
     ) -> HashMap<String, CompositeOutputs> {
         let mut scores = HashMap::new();
@@ -197,7 +221,7 @@ impl AnalysisReport {
 
         // Calculate composite scores
         for (node_id, values) in node_metrics {
-            let importance = Self::calculate_importance(&values, weights, ranges);
+            let importance = Self::calculate_importance(&values, weights, scaler);
             let risk = values.risk.unwrap_or(0.0);
             let chokepoint = values.chokepoint.unwrap_or(0.0);
             let payoff = Self::calculate_payoff(importance, risk, weights);
@@ -220,23 +244,15 @@ impl AnalysisReport {
     fn calculate_importance(
         values: &NodeMetricValues,
         weights: &MetricWeights,
-        ranges: &NormalizationRanges,
+        scaler: &Scaler,
     ) -> f64 {
-        let pagerank = Self::normalize(
-            values.pagerank.unwrap_or(0.0),
-            ranges.pagerank_imports.0,
-            ranges.pagerank_imports.1,
-        );
+        let pagerank = scaler.scale("pagerank_imports", values.pagerank.unwrap_or(0.0));
         let degree = Self::normalize(
             values.degree.unwrap_or(0.0),
             0.0,
             1.0, // Already normalized
         );
-        let betweenness = Self::normalize(
-            values.betweenness.unwrap_or(0.0),
-            ranges.betweenness.0,
-            ranges.betweenness.1,
-        );
+        let betweenness = scaler.scale("betweenness", values.betweenness.unwrap_or(0.0));
 
         weights.importance_pagerank * pagerank
             + weights.importance_degree * degree
@@ -325,6 +341,7 @@ impl AnalysisReport {
     /// Convert to UI metrics format
     pub fn to_ui_metrics(&self, graph: &CodeGraph) -> Vec<UINodeMetricsV1> {
         let mut ui_metrics = Vec::new();
+        let scaler = Scaler::new(&self.normalization_ranges, &self.samples);
 
         for node in graph.nodes() {
             // Get composite scores
@@ -340,8 +357,8 @@ impl AnalysisReport {
                 });
 
             // Build raw metrics
-            let raw = self.build_raw_metrics(&node.id);
-            let normalized = self.build_normalized_metrics(&node.id, &raw);
+            let raw = self.build_raw_metrics(node);
+            let normalized = Self::build_normalized_metrics(&scaler, &raw);
 
             // Get community
             let community = self.get_node_community(&node.id);
@@ -357,6 +374,7 @@ impl AnalysisReport {
                 payoff: composites.payoff as f32,
                 raw,
                 normalized,
+                generated: false,
                 version: 1,
             });
         }
@@ -364,20 +382,23 @@ impl AnalysisReport {
         ui_metrics
     }
 
-    /// Build raw metrics for a node
-    fn build_raw_metrics(&self, node_id: &str) -> RawMetrics {
+    /// Build raw metrics for a node, taking churn and coverage from its attributes
+    fn build_raw_metrics(&self, node: &GraphNode) -> RawMetrics {
+        let node_id = node.id.as_str();
         let mut raw = RawMetrics {
             pagerank_imports: 0.0,
             pagerank_calls: None,
             indegree: 0,
             outdegree: 0,
             k_core: 0,
+            k_truss: 0,
+            articulation_point: false,
             clustering: 0.0,
             betweenness: 0.0,
-            churn: 0,
+            churn: node.number(CHURN_ATTRIBUTE).unwrap_or(0.0) as i64,
             complexity: 0,
             owners: 0,
-            coverage: 0.0,
+            coverage: node.number(COVERAGE_ATTRIBUTE).unwrap_or(0.0),
         };
 
         // Extract from metric results
@@ -396,6 +417,17 @@ impl AnalysisReport {
             {
                 raw.k_core = *k;
             }
+            // Get k-truss and articulation points
+            if let Some(MetricValue::Integer(k)) =
+                result.values.get(&format!("{}_k_truss", node_id))
+            {
+                raw.k_truss = *k;
+            }
+            if let Some(MetricValue::Integer(cut)) =
+                result.values.get(&format!("{}_articulation_point", node_id))
+            {
+                raw.articulation_point = *cut != 0;
+            }
             // Get complexity
             if let Some(value) = result.get_node_value(node_id, "cyclomatic_complexity") {
                 raw.complexity = value as i64;
@@ -406,60 +438,18 @@ impl AnalysisReport {
     }
 
     /// Build normalized metrics for a node
-    fn build_normalized_metrics(&self, _node_id: &str, raw: &RawMetrics) -> NormalizedMetrics {
+    fn build_normalized_metrics(scaler: &Scaler, raw: &RawMetrics) -> NormalizedMetrics {
         NormalizedMetrics {
-            pagerank_imports: Self::normalize(
-                raw.pagerank_imports,
-                self.normalization_ranges.pagerank_imports.0,
-                self.normalization_ranges.pagerank_imports.1,
-            ),
-            pagerank_calls: raw.pagerank_calls.map(|v| {
-                Self::normalize(
-                    v,
-                    self.normalization_ranges.pagerank_calls.0,
-                    self.normalization_ranges.pagerank_calls.1,
-                )
-            }),
-            indegree: Self::normalize(
-                raw.indegree as f64,
-                self.normalization_ranges.indegree.0,
-                self.normalization_ranges.indegree.1,
-            ),
-            k_core: Self::normalize(
-                raw.k_core as f64,
-                self.normalization_ranges.k_core.0,
-                self.normalization_ranges.k_core.1,
-            ),
-            clustering: Self::normalize(
-                raw.clustering,
-                self.normalization_ranges.clustering.0,
-                self.normalization_ranges.clustering.1,
-            ),
-            betweenness: Self::normalize(
-                raw.betweenness,
-                self.normalization_ranges.betweenness.0,
-                self.normalization_ranges.betweenness.1,
-            ),
-            churn: Self::normalize(
-                raw.churn as f64,
-                self.normalization_ranges.churn.0,
-                self.normalization_ranges.churn.1,
-            ),
-            complexity: Self::normalize(
-                raw.complexity as f64,
-                self.normalization_ranges.complexity.0,
-                self.normalization_ranges.complexity.1,
-            ),
-            owners: Self::normalize(
-                raw.owners as f64,
-                self.normalization_ranges.owners.0,
-                self.normalization_ranges.owners.1,
-            ),
-            coverage: Self::normalize(
-                raw.coverage,
-                self.normalization_ranges.coverage.0,
-                self.normalization_ranges.coverage.1,
-            ),
+            pagerank_imports: scaler.scale("pagerank_imports", raw.pagerank_imports),
+            pagerank_calls: raw.pagerank_calls.map(|v| scaler.scale("pagerank_calls", v)),
+            indegree: scaler.scale("indegree", raw.indegree as f64),
+            k_core: scaler.scale("k_core", raw.k_core as f64),
+            clustering: scaler.scale("clustering", raw.clustering),
+            betweenness: scaler.scale("betweenness", raw.betweenness),
+            churn: scaler.scale("churn", raw.churn as f64),
+            complexity: scaler.scale("complexity", raw.complexity as f64),
+            owners: scaler.scale("owners", raw.owners as f64),
+            coverage: scaler.scale("coverage", raw.coverage),
         }
     }
 
@@ -489,4 +479,52 @@ struct NodeMetricValues {
     coupling_out: Option<f64>,
     risk: Option<f64>,
     chokepoint: Option<f64>,
-}
\ No newline at end of file
+}
+
+/// Scales raw values to 0-1 with the report's normalization mode
+///
+/// Percentile and z-score modes need observed values, so metrics without
+/// samples fall back to their min-max range.
+struct Scaler<'a> {
+    ranges: &'a NormalizationRanges,
+    samples: &'a BTreeMap<String, Vec<f64>>,
+    /// Mean and standard deviation of each sampled metric
+    moments: HashMap<&'a str, (f64, f64)>,
+}
+
+impl<'a> Scaler<'a> {
+    fn new(ranges: &'a NormalizationRanges, samples: &'a BTreeMap<String, Vec<f64>>) -> Self {
+        let moments = samples
+            .iter()
+            .map(|(metric, values)| {
+                let n = values.len() as f64;
+                let mean = values.iter().sum::<f64>() / n;
+                let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+                (metric.as_str(), (mean, variance.sqrt()))
+            })
+            .collect();
+        Self {
+            ranges,
+            samples,
+            moments,
+        }
+    }
+
+    fn scale(&self, metric: &str, value: f64) -> f64 {
+        match self.ranges.mode {
+            NormalizationMode::Percentile => {
+                if let Some(sorted) = self.samples.get(metric) {
+                    return percentile_rank_sorted(value, sorted);
+                }
+            }
+            NormalizationMode::ZScore => {
+                if let Some(&(mean, std_dev)) = self.moments.get(metric) {
+                    return clamped_z_score(value, mean, std_dev, self.ranges.z_clamp);
+                }
+            }
+            NormalizationMode::MinMax => {}
+        }
+        let (min, max) = self.ranges.range(metric).unwrap_or((0.0, 1.0));
+        AnalysisReport::normalize(value, min, max)
+    }
+}
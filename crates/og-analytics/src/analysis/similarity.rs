@@ -0,0 +1,184 @@
+use og_graph::graph::CodeGraph;
+use og_types::metrics::UINodeMetricsV1;
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+
+/// Similar nodes returned when no count is given
+pub const DEFAULT_SIMILAR_K: usize = 10;
+
+/// A node ranked by how closely its structure matches another's
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimilarNode {
+    pub id: String,
+    pub name: String,
+    pub node_type: String,
+    pub file_path: Option<String>,
+    /// Mean of the component similarities, from 0 to 1
+    pub similarity: f64,
+    /// Closeness of fan-in, fan-out and member counts
+    pub degree_similarity: f64,
+    /// Cosine similarity of the kinds of neighbors and edges
+    pub neighbor_similarity: f64,
+    /// Closeness of normalized metrics, if both nodes have them
+    pub metric_similarity: Option<f64>,
+}
+
+/// Structural fingerprint of a node and everything it contains
+struct Profile {
+    /// Log-scaled incoming, outgoing and member counts
+    degrees: [f64; 3],
+    /// Share of external edges by direction, edge type and neighbor type
+    neighbors: BTreeMap<String, f64>,
+    metrics: Option<Vec<f64>>,
+}
+
+fn is_contains(edge_type: &str) -> bool {
+    edge_type.eq_ignore_ascii_case("contains")
+}
+
+/// The node and its transitive members through contains edges
+fn members(graph: &CodeGraph, root: NodeIndex) -> HashSet<NodeIndex> {
+    let mut seen = HashSet::from([root]);
+    let mut queue = VecDeque::from([root]);
+    while let Some(idx) = queue.pop_front() {
+        for edge in graph.graph.edges(idx) {
+            if is_contains(&edge.weight().edge_type) && seen.insert(edge.target()) {
+                queue.push_back(edge.target());
+            }
+        }
+    }
+    seen
+}
+
+fn metric_vector(metrics: &UINodeMetricsV1) -> Vec<f64> {
+    let n = &metrics.normalized;
+    vec![
+        n.pagerank_imports,
+        n.indegree,
+        n.k_core,
+        n.clustering,
+        n.betweenness,
+        n.churn,
+        n.complexity,
+        n.coverage,
+    ]
+}
+
+fn profile(graph: &CodeGraph, idx: NodeIndex, metrics: &HashMap<(&str, &str, &str), &UINodeMetricsV1>) -> Profile {
+    let inside = members(graph, idx);
+    let mut counts = [0usize; 2];
+    let mut neighbors: BTreeMap<String, f64> = BTreeMap::new();
+    for &member in &inside {
+        for (slot, direction, label) in [(0, Direction::Incoming, "in"), (1, Direction::Outgoing, "out")] {
+            for edge in graph.graph.edges_directed(member, direction) {
+                let other = if direction == Direction::Incoming { edge.source() } else { edge.target() };
+                let edge_type = &edge.weight().edge_type;
+                if is_contains(edge_type) || inside.contains(&other) {
+                    continue;
+                }
+                counts[slot] += 1;
+                let key = format!("{}:{}:{}", label, edge_type.to_ascii_lowercase(), graph.graph[other].node_type);
+                *neighbors.entry(key).or_default() += 1.0;
+            }
+        }
+    }
+    let total: f64 = neighbors.values().sum();
+    if total > 0.0 {
+        neighbors.values_mut().for_each(|share| *share /= total);
+    }
+
+    let node = &graph.graph[idx];
+    let path = node.file_path.as_deref().unwrap_or(&node.id);
+    Profile {
+        degrees: [
+            (counts[0] as f64).ln_1p(),
+            (counts[1] as f64).ln_1p(),
+            ((inside.len() - 1) as f64).ln_1p(),
+        ],
+        neighbors,
+        metrics: metrics
+            .get(&(path, node.name.as_str(), node.node_type.as_str()))
+            .map(|m| metric_vector(m)),
+    }
+}
+
+fn cosine(a: &BTreeMap<String, f64>, b: &BTreeMap<String, f64>) -> f64 {
+    let dot: f64 = a.iter().filter_map(|(key, x)| b.get(key).map(|y| x * y)).sum();
+    let norm = |v: &BTreeMap<String, f64>| v.values().map(|x| x * x).sum::<f64>().sqrt();
+    let (na, nb) = (norm(a), norm(b));
+    if na == 0.0 || nb == 0.0 {
+        // Two isolated nodes are alike, an isolated and a connected one are not
+        return if na == nb { 1.0 } else { 0.0 };
+    }
+    dot / (na * nb)
+}
+
+/// Files or classes whose structure most resembles a node's
+///
+/// Nodes are compared with others of the same type by their degree profile,
+/// the kinds of neighbors they connect to, and their normalized metrics when
+/// an analysis provided them. Edges of contained members count for their
+/// container. Returns None if the node does not exist.
+pub fn find_similar_nodes(
+    graph: &CodeGraph,
+    metrics: &[UINodeMetricsV1],
+    node_id: &str,
+    k: usize,
+) -> Option<Vec<SimilarNode>> {
+    let start = *graph.node_map.get(node_id)?;
+    let node_type = graph.graph[start].node_type.as_str();
+    let by_location: HashMap<(&str, &str, &str), &UINodeMetricsV1> = metrics
+        .iter()
+        .map(|m| ((m.path.as_str(), m.name.as_str(), m.node_type.as_str()), m))
+        .collect();
+    let target = profile(graph, start, &by_location);
+
+    let mut similar: Vec<SimilarNode> = graph
+        .graph
+        .node_indices()
+        .filter(|&idx| idx != start && graph.graph[idx].node_type == node_type)
+        .map(|idx| {
+            let candidate = profile(graph, idx, &by_location);
+            let distance = target
+                .degrees
+                .iter()
+                .zip(&candidate.degrees)
+                .map(|(a, b)| (a - b).powi(2))
+                .sum::<f64>()
+                .sqrt();
+            let degree_similarity = 1.0 / (1.0 + distance);
+            let neighbor_similarity = cosine(&target.neighbors, &candidate.neighbors);
+            let metric_similarity = match (&target.metrics, &candidate.metrics) {
+                (Some(a), Some(b)) => {
+                    let diff: f64 = a.iter().zip(b).map(|(x, y)| (x - y).abs()).sum();
+                    Some((1.0 - diff / a.len() as f64).clamp(0.0, 1.0))
+                }
+                _ => None,
+            };
+            let similarity = match metric_similarity {
+                Some(m) => (degree_similarity + neighbor_similarity + m) / 3.0,
+                None => (degree_similarity + neighbor_similarity) / 2.0,
+            };
+
+            let node = &graph.graph[idx];
+            SimilarNode {
+                id: node.id.clone(),
+                name: node.name.clone(),
+                node_type: node.node_type.clone(),
+                file_path: node.file_path.clone(),
+                similarity,
+                degree_similarity,
+                neighbor_similarity,
+                metric_similarity,
+            }
+        })
+        .collect();
+
+    similar.sort_by(|a, b| b.similarity.total_cmp(&a.similarity).then_with(|| a.id.cmp(&b.id)));
+    similar.truncate(k);
+    Some(similar)
+}
@@ -0,0 +1,119 @@
+use crate::analysis::coloring::node_values;
+use og_graph::attributes::LOC_ATTRIBUTE;
+use og_graph::graph::CodeGraph;
+use og_types::metrics::UINodeMetricsV1;
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+/// Size of the node with the smallest value, and of nodes without one
+pub const MIN_NODE_SIZE: f64 = 5.0;
+
+/// Size of the node with the largest value
+pub const MAX_NODE_SIZE: f64 = 40.0;
+
+/// Share of nodes below the cap, larger values are drawn as large as the cap
+const OUTLIER_QUANTILE: f64 = 0.95;
+
+/// What a node's size reflects
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SizeMetric {
+    /// Lines of code the node spans
+    Loc,
+    /// Import PageRank from the last full analysis
+    Pagerank,
+    /// Distinct nodes depending on it, ignoring containment
+    FanIn,
+}
+
+/// How values are mapped onto the size range
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SizeScale {
+    Linear,
+    /// Keeps a few huge nodes from dwarfing the rest
+    #[default]
+    Log,
+}
+
+/// Size of every graph node for one metric
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeSizes {
+    pub metric: SizeMetric,
+    pub scale: SizeScale,
+    /// Size by node id, from `MIN_NODE_SIZE` to `MAX_NODE_SIZE`
+    pub sizes: BTreeMap<String, f64>,
+}
+
+/// Distinct sources of non-containment edges into each node
+fn fan_in(graph: &CodeGraph) -> BTreeMap<String, f64> {
+    graph
+        .graph
+        .node_indices()
+        .map(|idx| {
+            let sources: HashSet<_> = graph
+                .graph
+                .edges_directed(idx, Direction::Incoming)
+                .filter(|edge| !edge.weight().edge_type.eq_ignore_ascii_case("contains") && edge.source() != idx)
+                .map(|edge| edge.source())
+                .collect();
+            (graph.graph[idx].id.clone(), sources.len() as f64)
+        })
+        .collect()
+}
+
+/// Value of the metric for each node that has one, lines coming from each
+/// node's `loc` attribute
+pub fn size_values(graph: &CodeGraph, metrics: &[UINodeMetricsV1], metric: SizeMetric) -> BTreeMap<String, f64> {
+    match metric {
+        SizeMetric::Loc => graph
+            .graph
+            .node_weights()
+            .filter_map(|node| Some((node.id.clone(), node.number(LOC_ATTRIBUTE)?)))
+            .collect(),
+        SizeMetric::Pagerank => node_values(graph, metrics, |m| Some(m.raw.pagerank_imports)),
+        SizeMetric::FanIn => fan_in(graph),
+    }
+}
+
+/// Size every node of the graph by its value of a metric
+///
+/// Values above the 95th percentile are capped there before scaling, so
+/// outliers do not shrink every other node. Negative values count as zero.
+pub fn resize(graph: &CodeGraph, values: &BTreeMap<String, f64>, metric: SizeMetric, scale: SizeScale) -> NodeSizes {
+    let mut sorted: Vec<f64> = values.values().copied().filter(|v| v.is_finite()).map(|v| v.max(0.0)).collect();
+    sorted.sort_by(f64::total_cmp);
+    let cap = sorted
+        .get(((sorted.len() as f64 - 1.0) * OUTLIER_QUANTILE).round().max(0.0) as usize)
+        .copied()
+        .unwrap_or(0.0);
+    let scaled = |value: f64| {
+        let value = value.max(0.0).min(cap);
+        match scale {
+            SizeScale::Linear => value,
+            SizeScale::Log => value.ln_1p(),
+        }
+    };
+    let low = sorted.first().map_or(0.0, |v| scaled(*v));
+    let high = scaled(cap);
+
+    let sizes = graph
+        .graph
+        .node_weights()
+        .map(|node| {
+            let size = match values.get(&node.id).filter(|v| v.is_finite()) {
+                Some(&value) if high > low => {
+                    let t = (scaled(value) - low) / (high - low);
+                    MIN_NODE_SIZE + t.clamp(0.0, 1.0) * (MAX_NODE_SIZE - MIN_NODE_SIZE)
+                }
+                _ => MIN_NODE_SIZE,
+            };
+            (node.id.clone(), size)
+        })
+        .collect();
+
+    NodeSizes { metric, scale, sizes }
+}
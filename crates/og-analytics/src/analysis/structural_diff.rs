@@ -0,0 +1,130 @@
+use super::evolution::{file_graph, module_of};
+use super::export::escape_markdown;
+use super::pr_summary::{AnalysisSide, PrSummary};
+use petgraph::visit::EdgeRef;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+/// Links from the files of one module to those of another
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleDependency {
+    pub from: String,
+    pub to: String,
+    /// Edges between nodes of the two modules
+    pub edges: usize,
+}
+
+/// How the structure of a codebase differs between two analyses
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StructuralDiff {
+    /// Directories with files only in the second analysis
+    pub added_modules: Vec<String>,
+    /// Directories with files only in the first analysis
+    pub removed_modules: Vec<String>,
+    /// Module dependencies only in the second analysis, most edges first
+    pub new_dependencies: Vec<ModuleDependency>,
+    pub removed_dependencies: Vec<ModuleDependency>,
+    /// New and resolved cycles, and files whose risk or complexity changed
+    #[serde(flatten)]
+    pub changes: PrSummary,
+}
+
+/// Modules and the edges between them, keyed by relative directory
+fn modules(side: &AnalysisSide) -> (BTreeSet<String>, BTreeMap<(String, String), usize>) {
+    let files = file_graph(side.graph, side.root);
+    let modules = files.graph.node_weights().map(|file| module_of(&file.id)).collect();
+    let mut dependencies = BTreeMap::new();
+    for edge in files.graph.edge_references() {
+        let from = module_of(&files.graph[edge.source()].id);
+        let to = module_of(&files.graph[edge.target()].id);
+        if from != to {
+            *dependencies.entry((from, to)).or_insert(0) += edge.weight().weight as usize;
+        }
+    }
+    (modules, dependencies)
+}
+
+/// Dependencies in `from` and not in `other`, most edges first
+fn only_in(from: &BTreeMap<(String, String), usize>, other: &BTreeMap<(String, String), usize>) -> Vec<ModuleDependency> {
+    let mut dependencies: Vec<ModuleDependency> = from
+        .iter()
+        .filter(|(pair, _)| !other.contains_key(*pair))
+        .map(|((from, to), &edges)| ModuleDependency {
+            from: from.clone(),
+            to: to.clone(),
+            edges,
+        })
+        .collect();
+    dependencies.sort_by_key(|d| std::cmp::Reverse(d.edges));
+    dependencies
+}
+
+impl StructuralDiff {
+    /// Compare two analyses of a codebase, listing at most `limit` changed files
+    pub fn build(before: &AnalysisSide, after: &AnalysisSide, limit: usize) -> Self {
+        let (before_modules, before_dependencies) = modules(before);
+        let (after_modules, after_dependencies) = modules(after);
+
+        Self {
+            added_modules: after_modules.difference(&before_modules).cloned().collect(),
+            removed_modules: before_modules.difference(&after_modules).cloned().collect(),
+            new_dependencies: only_in(&after_dependencies, &before_dependencies),
+            removed_dependencies: only_in(&before_dependencies, &after_dependencies),
+            changes: PrSummary::build(before, after, limit),
+        }
+    }
+
+    /// Whether the second analysis has no structural or metric changes
+    pub fn is_empty(&self) -> bool {
+        self.added_modules.is_empty()
+            && self.removed_modules.is_empty()
+            && self.new_dependencies.is_empty()
+            && self.removed_dependencies.is_empty()
+            && self.changes.new_cycles.is_empty()
+            && self.changes.resolved_cycles == 0
+            && self.changes.files.is_empty()
+    }
+
+    /// Render the diff as Markdown, modules and dependencies first
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "## Structure changes\n");
+
+        let list = |out: &mut String, title: &str, lines: Vec<String>| {
+            if !lines.is_empty() {
+                let _ = writeln!(out, "**{} ({}):**\n", title, lines.len());
+                for line in lines {
+                    let _ = writeln!(out, "- {}", line);
+                }
+                out.push('\n');
+            }
+        };
+        let modules = |modules: &[String]| -> Vec<String> {
+            modules.iter().map(|m| format!("`{}`", escape_markdown(m))).collect()
+        };
+        let dependencies = |dependencies: &[ModuleDependency]| -> Vec<String> {
+            dependencies
+                .iter()
+                .map(|d| format!("`{}` → `{}`, {} edge(s)", escape_markdown(&d.from), escape_markdown(&d.to), d.edges))
+                .collect()
+        };
+        list(&mut out, "Added modules", modules(&self.added_modules));
+        list(&mut out, "Removed modules", modules(&self.removed_modules));
+        list(&mut out, "New module dependencies", dependencies(&self.new_dependencies));
+        list(&mut out, "Removed module dependencies", dependencies(&self.removed_dependencies));
+
+        if self.added_modules.is_empty()
+            && self.removed_modules.is_empty()
+            && self.new_dependencies.is_empty()
+            && self.removed_dependencies.is_empty()
+        {
+            let _ = writeln!(out, "No modules or module dependencies changed.\n");
+        }
+
+        out.push_str(&self.changes.to_markdown());
+        out
+    }
+}
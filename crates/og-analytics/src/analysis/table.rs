@@ -0,0 +1,148 @@
+use crate::analysis::distribution::{metric_extractor, metric_names};
+use anyhow::{Context, Result};
+use og_types::metrics::UINodeMetricsV1;
+use parquet::data_type::{BoolType, ByteArray, ByteArrayType, DoubleType, Int64Type};
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::SerializedFileWriter;
+use parquet::schema::parser::parse_message_type;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+/// File format of a metrics table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TableFormat {
+    Csv,
+    Parquet,
+}
+
+/// Values of one table column, a metric column is empty where a node lacks it
+enum Column {
+    Text(Vec<String>),
+    Integer(Vec<i64>),
+    Boolean(Vec<bool>),
+    Number(Vec<Option<f64>>),
+}
+
+/// One row per node and one column per metric, after the node's path, name,
+/// type, community and whether it is generated
+pub struct MetricsTable {
+    columns: Vec<(String, Column)>,
+    rows: usize,
+}
+
+fn quote_csv(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+impl MetricsTable {
+    pub fn new(metrics: &[UINodeMetricsV1]) -> Self {
+        let text = |get: fn(&UINodeMetricsV1) -> &str| Column::Text(metrics.iter().map(|m| get(m).to_string()).collect());
+        let mut columns = vec![
+            ("path".to_string(), text(|m| &m.path)),
+            ("name".to_string(), text(|m| &m.name)),
+            ("node_type".to_string(), text(|m| &m.node_type)),
+            ("community".to_string(), Column::Integer(metrics.iter().map(|m| m.community).collect())),
+            ("generated".to_string(), Column::Boolean(metrics.iter().map(|m| m.generated).collect())),
+        ];
+        for name in metric_names() {
+            if let Some(extract) = metric_extractor(name) {
+                columns.push((name.to_string(), Column::Number(metrics.iter().map(extract).collect())));
+            }
+        }
+        Self {
+            columns,
+            rows: metrics.len(),
+        }
+    }
+
+    /// Column names in order
+    pub fn headers(&self) -> impl Iterator<Item = &str> {
+        self.columns.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Write the table as CSV with a header row
+    pub fn write_csv(&self, out: &mut impl Write) -> std::io::Result<()> {
+        writeln!(out, "{}", self.headers().collect::<Vec<_>>().join(","))?;
+        for row in 0..self.rows {
+            let cells: Vec<String> = self
+                .columns
+                .iter()
+                .map(|(_, column)| match column {
+                    Column::Text(values) => quote_csv(&values[row]),
+                    Column::Integer(values) => values[row].to_string(),
+                    Column::Boolean(values) => values[row].to_string(),
+                    Column::Number(values) => values[row].map(|v| v.to_string()).unwrap_or_default(),
+                })
+                .collect();
+            writeln!(out, "{}", cells.join(","))?;
+        }
+        Ok(())
+    }
+
+    /// Write the table as a Parquet file with a single row group
+    pub fn write_parquet(&self, file: File) -> Result<()> {
+        let fields: Vec<String> = self
+            .columns
+            .iter()
+            .map(|(name, column)| match column {
+                Column::Text(_) => format!("REQUIRED BYTE_ARRAY {} (UTF8);", name),
+                Column::Integer(_) => format!("REQUIRED INT64 {};", name),
+                Column::Boolean(_) => format!("REQUIRED BOOLEAN {};", name),
+                Column::Number(_) => format!("OPTIONAL DOUBLE {};", name),
+            })
+            .collect();
+        let schema = parse_message_type(&format!("message node_metrics {{ {} }}", fields.join(" ")))?;
+        let mut writer = SerializedFileWriter::new(file, Arc::new(schema), Arc::new(WriterProperties::builder().build()))?;
+
+        let mut row_group = writer.next_row_group()?;
+        let mut columns = self.columns.iter();
+        while let Some(mut writer) = row_group.next_column()? {
+            let Some((_, column)) = columns.next() else {
+                break;
+            };
+            match column {
+                Column::Text(values) => {
+                    let values: Vec<ByteArray> = values.iter().map(|v| ByteArray::from(v.as_str())).collect();
+                    writer.typed::<ByteArrayType>().write_batch(&values, None, None)?;
+                }
+                Column::Integer(values) => {
+                    writer.typed::<Int64Type>().write_batch(values, None, None)?;
+                }
+                Column::Boolean(values) => {
+                    writer.typed::<BoolType>().write_batch(values, None, None)?;
+                }
+                Column::Number(values) => {
+                    let present: Vec<f64> = values.iter().flatten().copied().collect();
+                    let levels: Vec<i16> = values.iter().map(|v| i16::from(v.is_some())).collect();
+                    writer.typed::<DoubleType>().write_batch(&present, Some(&levels), None)?;
+                }
+            }
+            writer.close()?;
+        }
+        row_group.close()?;
+        writer.close()?;
+        Ok(())
+    }
+
+    /// Write the table to `path` in the given format
+    pub fn write(&self, path: &Path, format: TableFormat) -> Result<()> {
+        let file = File::create(path).with_context(|| format!("creating {}", path.display()))?;
+        match format {
+            TableFormat::Csv => {
+                let mut out = BufWriter::new(file);
+                self.write_csv(&mut out)?;
+                out.flush()?;
+            }
+            TableFormat::Parquet => self.write_parquet(file)?,
+        }
+        Ok(())
+    }
+}
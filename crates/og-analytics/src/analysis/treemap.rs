@@ -0,0 +1,153 @@
+use crate::analysis::coloring::node_values;
+use og_graph::attributes::LOC_ATTRIBUTE;
+use og_graph::graph::CodeGraph;
+use og_types::metrics::UINodeMetricsV1;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// What the area of a treemap cell reflects
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TreemapMetric {
+    /// Lines of code in the file
+    Loc,
+    /// Cyclomatic complexity, summed over the file's nodes
+    Complexity,
+    /// Risk of the riskiest node in the file
+    Risk,
+    /// Churn of the most changed node in the file
+    Churn,
+}
+
+impl TreemapMetric {
+    /// Whether the values come from a full analysis rather than parsing
+    pub fn needs_metrics(self) -> bool {
+        self != TreemapMetric::Loc
+    }
+}
+
+/// A folder or file of a treemap
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TreemapNode {
+    pub name: String,
+    /// Path relative to the analyzed root, empty for the root
+    pub path: String,
+    /// The file's value, or the sum over a folder's children
+    pub value: f64,
+    /// Largest value first, empty for files
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<TreemapNode>,
+}
+
+/// Directory hierarchy of a codebase with one metric rolled up per folder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Treemap {
+    pub metric: TreemapMetric,
+    pub root: TreemapNode,
+}
+
+/// Files and subfolders while the hierarchy is being assembled
+#[derive(Default)]
+struct Folder {
+    folders: BTreeMap<String, Folder>,
+    files: BTreeMap<String, f64>,
+}
+
+impl Folder {
+    fn into_node(self, name: String, path: String) -> TreemapNode {
+        let join = |name: &str| if path.is_empty() { name.to_string() } else { format!("{}/{}", path, name) };
+        let mut children: Vec<TreemapNode> = self
+            .folders
+            .into_iter()
+            .map(|(name, folder)| {
+                let path = join(&name);
+                folder.into_node(name, path)
+            })
+            .chain(self.files.into_iter().map(|(name, value)| TreemapNode {
+                path: join(&name),
+                name,
+                value,
+                children: Vec::new(),
+            }))
+            .collect();
+        children.sort_by(|a, b| b.value.total_cmp(&a.value).then_with(|| a.name.cmp(&b.name)));
+        TreemapNode {
+            name,
+            path,
+            value: children.iter().map(|child| child.value).sum(),
+            children,
+        }
+    }
+}
+
+/// Value of the metric for each file, by its path relative to `root`
+fn file_values(
+    graph: &CodeGraph,
+    metrics: &[UINodeMetricsV1],
+    root: &str,
+    metric: TreemapMetric,
+) -> BTreeMap<String, f64> {
+    let values = match metric {
+        TreemapMetric::Loc => BTreeMap::new(),
+        TreemapMetric::Complexity => node_values(graph, metrics, |m| Some(m.raw.complexity as f64)),
+        TreemapMetric::Risk => node_values(graph, metrics, |m| Some(m.risk as f64)),
+        TreemapMetric::Churn => node_values(graph, metrics, |m| Some(m.raw.churn as f64)),
+    };
+
+    let mut files = BTreeMap::new();
+    for node in graph.graph.node_weights() {
+        let Some(file_path) = node.file_path.as_deref() else {
+            continue;
+        };
+        let relative = Path::new(file_path).strip_prefix(root).unwrap_or(Path::new(file_path));
+        let value = files.entry(relative.to_string_lossy().replace('\\', "/")).or_insert(0.0);
+        match metric {
+            TreemapMetric::Loc if node.node_type == "file" => {
+                *value += node.number(LOC_ATTRIBUTE).unwrap_or(0.0);
+            }
+            TreemapMetric::Loc => {}
+            TreemapMetric::Complexity => *value += values.get(&node.id).copied().unwrap_or(0.0),
+            TreemapMetric::Risk | TreemapMetric::Churn => {
+                *value = value.max(values.get(&node.id).copied().unwrap_or(0.0));
+            }
+        }
+    }
+    files
+}
+
+/// Directory hierarchy of the files in a graph, valued by one metric
+///
+/// File sizes come from each file node's `loc` attribute, and `root` is the
+/// analyzed directory paths are made relative to. Every file with a node in
+/// the graph is a leaf, valued zero when it has no value, and each folder's
+/// value is the sum of its children's so the result can be drawn as a
+/// treemap or sunburst as it is.
+pub fn build_treemap(
+    graph: &CodeGraph,
+    metrics: &[UINodeMetricsV1],
+    root: &str,
+    metric: TreemapMetric,
+) -> Treemap {
+    let mut top = Folder::default();
+    for (path, value) in file_values(graph, metrics, root, metric) {
+        let mut names: Vec<&str> = path.split('/').filter(|name| !name.is_empty()).collect();
+        let Some(file_name) = names.pop() else {
+            continue;
+        };
+        let folder = names
+            .into_iter()
+            .fold(&mut top, |folder, name| folder.folders.entry(name.to_string()).or_default());
+        folder.files.insert(file_name.to_string(), value);
+    }
+
+    let name = Path::new(root)
+        .file_name()
+        .map_or_else(|| root.to_string(), |name| name.to_string_lossy().into_owned());
+    Treemap {
+        metric,
+        root: top.into_node(name, String::new()),
+    }
+}
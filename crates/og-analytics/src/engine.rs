@@ -1,17 +1,26 @@
-use crate::analysis::AnalysisReport;
-use crate::metrics::{
-    centrality::CentralityMetrics,
-    // community::CommunityDetection,  // Commented out for performance during debugging
-    quality::QualityMetrics,
-    risk::RiskAnalysis,
-    Metric, MetricResults, MetricValue,
-};
+use crate::analysis::{AnalysisReport, MetricTiming};
+use crate::metrics::{centrality::DEFAULT_BETWEENNESS_SAMPLES, Metric, MetricRegistry, MetricResults, MetricValue};
 use anyhow::Result;
 use dashmap::DashMap;
+use og_graph::direction::AnalysisMode;
 use og_graph::graph::CodeGraph;
+use og_graph::projection::EdgeFilter;
+use og_metrics_centrality::{CentralityMetrics, PathWeighting, DEFAULT_SAMPLING_SEED};
+use og_metrics_community::CommunityDetection;
+use og_metrics_quality::QualityAnalyzer;
+use og_metrics_risk::RiskAnalyzer;
+use og_types::metrics::NormalizationMode;
+use og_types::{FunctionMetrics, Suppressions};
 use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 use tracing::{debug, info, warn, error};
+use web_time::Instant;
+
+/// Names of the metrics registered by default
+pub const DEFAULT_METRICS: &[&str] = &["centrality", "quality", "risk"];
 
 /// Configuration for analytics engine
 #[derive(Debug, Clone)]
@@ -28,6 +37,28 @@ pub struct AnalyticsConfig {
     pub pagerank_damping: f64,
     /// Community detection resolution
     pub louvain_resolution: f64,
+    /// Default metrics to run, or all of them when unset
+    pub enabled_metrics: Option<Vec<String>>,
+    /// How metrics are scaled to 0-1 for the UI
+    pub normalization: NormalizationMode,
+    /// Seed that picks the sources of sampled betweenness on large graphs
+    pub betweenness_seed: u64,
+    /// Most sources sampled for betweenness on large graphs
+    pub betweenness_sample_size: usize,
+    /// Edge lengths for closeness and betweenness, hop counts by default
+    pub path_weighting: PathWeighting,
+    /// Direction each metric reads edges in, by metric name; directed when unset
+    pub modes: BTreeMap<String, AnalysisMode>,
+    /// Edge types community detection clusters on, all of them when empty
+    pub community_edge_types: Vec<String>,
+    /// Leave edges from a node to itself out of every metric's graph
+    pub drop_self_loops: bool,
+    /// Edge types each metric ignores, such as contains edges for centrality, by metric name
+    pub excluded_edge_types: BTreeMap<String, Vec<String>>,
+    /// Findings silenced by inline comments
+    pub suppressions: Suppressions,
+    /// Shape of each function and method body, by node id, from parsing
+    pub function_metrics: HashMap<String, FunctionMetrics>,
 }
 
 impl Default for AnalyticsConfig {
@@ -39,12 +70,59 @@ impl Default for AnalyticsConfig {
             pagerank_iterations: 30,
             pagerank_damping: 0.85,
             louvain_resolution: 1.0,
+            enabled_metrics: None,
+            normalization: NormalizationMode::default(),
+            betweenness_seed: DEFAULT_SAMPLING_SEED,
+            betweenness_sample_size: DEFAULT_BETWEENNESS_SAMPLES,
+            path_weighting: PathWeighting::default(),
+            modes: BTreeMap::new(),
+            community_edge_types: Vec::new(),
+            drop_self_loops: false,
+            excluded_edge_types: BTreeMap::new(),
+            suppressions: Suppressions::default(),
+            function_metrics: HashMap::new(),
+        }
+    }
+}
+
+impl AnalyticsConfig {
+    /// Edges left out of the graph the named metric reads
+    pub fn edge_filter(&self, metric: &str) -> EdgeFilter {
+        EdgeFilter {
+            drop_self_loops: self.drop_self_loops,
+            exclude_edge_types: self.excluded_edge_types.get(metric).cloned().unwrap_or_default(),
         }
     }
+
+    /// The metric implementations this configuration selects
+    pub fn registry(&self) -> MetricRegistry {
+        let centrality = CentralityMetrics {
+            max_iterations: self.pagerank_iterations,
+            sample_size: self.betweenness_sample_size,
+            seed: self.betweenness_seed,
+            path_weighting: self.path_weighting.clone(),
+            ..CentralityMetrics::default()
+        };
+        let community = CommunityDetection {
+            edge_types: self.community_edge_types.clone(),
+            ..CommunityDetection::with_resolution(self.louvain_resolution)
+        };
+        let quality = QualityAnalyzer {
+            suppressions: self.suppressions.clone(),
+            function_metrics: self.function_metrics.clone(),
+            ..QualityAnalyzer::default()
+        };
+        let risk = RiskAnalyzer {
+            suppressions: self.suppressions.clone(),
+            ..RiskAnalyzer::default()
+        };
+        MetricRegistry::new(centrality, community, quality, risk)
+    }
 }
 
 /// Weights for composite metrics
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct MetricWeights {
     /// Importance weights
     pub importance_pagerank: f64,
@@ -108,18 +186,44 @@ impl AnalyticsEngine {
         engine
     }
 
-    /// Register all default metrics
+    /// Register the enabled default metrics
     fn register_default_metrics(&mut self) {
-        // Centrality metrics
-        self.add_metric(Box::new(CentralityMetrics::new()));
-        // Quality metrics  
-        self.add_metric(Box::new(QualityMetrics::new()));
-        // Risk analysis
-        self.add_metric(Box::new(RiskAnalysis::new()));
-        // Community detection - commented out for performance during debugging
-        // self.add_metric(Box::new(CommunityDetection::new(
-        //     self.config.louvain_resolution,
-        // )));
+        let registry = self.config.registry();
+        for name in DEFAULT_METRICS {
+            if self.is_enabled(name) {
+                if let Some(metric) = registry.metric(name) {
+                    self.add_metric(metric);
+                }
+            }
+        }
+    }
+
+    fn is_enabled(&self, name: &str) -> bool {
+        self.config
+            .enabled_metrics
+            .as_ref()
+            .is_none_or(|enabled| enabled.iter().any(|metric| metric == name))
+    }
+
+    fn mode_for(&self, name: &str) -> AnalysisMode {
+        self.config.modes.get(name).copied().unwrap_or_default()
+    }
+
+    /// The graph each registered metric reads, keyed by its edge filter and mode
+    ///
+    /// Metrics that read the graph the same way share one projection.
+    fn projections<'g>(&self, graph: &'g CodeGraph) -> HashMap<(EdgeFilter, AnalysisMode), Cow<'g, CodeGraph>> {
+        let mut projections = HashMap::new();
+        for metric in &self.metrics {
+            projections
+                .entry(self.view_for(metric.name()))
+                .or_insert_with_key(|(filter, mode)| graph.metric_view(filter, *mode));
+        }
+        projections
+    }
+
+    fn view_for(&self, name: &str) -> (EdgeFilter, AnalysisMode) {
+        (self.config.edge_filter(name), self.mode_for(name))
     }
 
     /// Add a metric to the engine
@@ -165,13 +269,13 @@ impl AnalyticsEngine {
 
     /// Analyze a code graph
     pub async fn analyze(&self, graph: &CodeGraph) -> Result<AnalysisReport> {
-        println!("[ENGINE-ANALYTICS] Starting analysis with {} nodes", graph.node_map.len());
+        tracing::info!("[ENGINE-ANALYTICS] Starting analysis with {} nodes", graph.node_map.len());
         info!("Starting graph analysis with {} nodes", graph.node_map.len());
 
         // Validate graph (82%)
-        println!("[ENGINE-ANALYTICS] Validating graph...");
+        tracing::debug!("[ENGINE-ANALYTICS] Validating graph...");
         self.validate_graph(graph)?;
-        println!("[ENGINE-ANALYTICS] Graph validation passed");
+        tracing::debug!("[ENGINE-ANALYTICS] Graph validation passed");
 
         // Clear cache if not using it
         if !self.config.use_cache {
@@ -179,25 +283,32 @@ impl AnalyticsEngine {
         }
 
         // Run all metrics with error recovery (83-88%)
-        println!("[ENGINE-ANALYTICS] Running metrics (parallel={})", self.config.parallel);
+        tracing::debug!("[ENGINE-ANALYTICS] Running metrics (parallel={})", self.config.parallel);
         let results = if self.config.parallel {
-            println!("[ENGINE-ANALYTICS] Running parallel metrics...");
+            tracing::debug!("[ENGINE-ANALYTICS] Running parallel metrics...");
             self.run_metrics_parallel_safe(graph).await?
         } else {
-            println!("[ENGINE-ANALYTICS] Running sequential metrics...");
+            tracing::debug!("[ENGINE-ANALYTICS] Running sequential metrics...");
             self.run_metrics_sequential_safe(graph).await?
         };
-        println!("[ENGINE-ANALYTICS] Metrics completed, got {} results", results.len());
+        tracing::info!("[ENGINE-ANALYTICS] Metrics completed, got {} results", results.len());
 
         // Build analysis report (89%)
-        println!("[ENGINE-ANALYTICS] Building analysis report...");
-        let report = AnalysisReport::new(
+        tracing::debug!("[ENGINE-ANALYTICS] Building analysis report...");
+        let report_start = Instant::now();
+        let mut report = AnalysisReport::new(
             results,
             &self.config.weights,
+            self.config.normalization,
             graph,
         );
+        report.timings.push(MetricTiming {
+            name: "report".to_string(),
+            duration: report_start.elapsed(),
+            steps: Vec::new(),
+        });
 
-        println!("[ENGINE-ANALYTICS] Analysis complete!");
+        tracing::info!("[ENGINE-ANALYTICS] Analysis complete!");
         info!("Analysis complete");
         Ok(report)
     }
@@ -205,6 +316,7 @@ impl AnalyticsEngine {
     /// Run metrics in parallel with error recovery
     async fn run_metrics_parallel_safe(&self, graph: &CodeGraph) -> Result<Vec<MetricResults>> {
         debug!("Running metrics in parallel with error recovery");
+        let projections = self.projections(graph);
         
         // Use rayon for parallel execution with panic catching
         let results: Vec<(String, Result<MetricResults>)> = self.metrics
@@ -212,14 +324,20 @@ impl AnalyticsEngine {
             .map(|metric| {
                 let name = metric.name().to_string();
                 debug!("Running metric: {}", name);
+                let graph = &projections[&self.view_for(&name)];
                 
                 // Catch panics and convert to errors
+                let start = Instant::now();
                 let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
                     metric.calculate(graph)
                 }));
+                let elapsed = start.elapsed();
                 
                 let metric_result = match result {
-                    Ok(calc_result) => calc_result,
+                    Ok(calc_result) => calc_result.map(|mut r| {
+                        r.duration = elapsed;
+                        r
+                    }),
                     Err(panic_info) => {
                         let msg = if let Some(s) = panic_info.downcast_ref::<String>() {
                             s.clone()
@@ -258,19 +376,23 @@ impl AnalyticsEngine {
     /// Run metrics sequentially with error recovery
     async fn run_metrics_sequential_safe(&self, graph: &CodeGraph) -> Result<Vec<MetricResults>> {
         debug!("Running metrics sequentially with error recovery");
-        println!("[ENGINE-ANALYTICS] Starting sequential metrics execution");
+        tracing::info!("[ENGINE-ANALYTICS] Starting sequential metrics execution");
+        let projections = self.projections(graph);
         
         let mut results = Vec::new();
         for (_idx, metric) in self.metrics.iter().enumerate() {
             let name = metric.name();
             debug!("Running metric: {}", name);
+            let graph = &projections[&self.view_for(name)];
             
             // Catch panics and convert to errors
+            let start = Instant::now();
             let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
                 metric.calculate(graph)
             }));
             match result {
-                Ok(Ok(metric_result)) => {
+                Ok(Ok(mut metric_result)) => {
+                    metric_result.duration = start.elapsed();
                     debug!("Metric {} completed successfully", name);
                     results.push(metric_result);
                 },
@@ -286,14 +408,14 @@ impl AnalyticsEngine {
                     } else {
                         "Unknown panic".to_string()
                     };
-                    println!("[ENGINE-ANALYTICS] Metric {} panicked: {}", name, msg);
+                    tracing::error!("[ENGINE-ANALYTICS] Metric {} panicked: {}", name, msg);
                     error!("Metric {} panicked: {}", name, msg);
                     results.push(MetricResults::new(name.to_string()));
                 }
             }
         }
 
-        println!("[ENGINE-ANALYTICS] All metrics completed");
+        tracing::info!("[ENGINE-ANALYTICS] All metrics completed");
         Ok(results)
     }
 
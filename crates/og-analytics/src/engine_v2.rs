@@ -1,10 +1,14 @@
-use crate::metrics::{MetricResults, MetricValue};
+use crate::metrics::{MetricRegistry, MetricResults, MetricValue};
 use anyhow::Result;
 use og_graph::graph::CodeGraph;
-use og_metrics_centrality::{CentralityMetrics, CentralityResults};
+use og_graph::projection::EdgeFilter;
+use og_metrics_centrality::{CentralityMetrics, CentralityResults, PathWeighting, DEFAULT_SAMPLING_SEED};
 use og_metrics_community::{CommunityDetection, CommunityResults};
 use og_metrics_risk::{RiskAnalyzer, RiskResults};
 use og_metrics_quality::{QualityAnalyzer, QualityResults};
+use og_types::{FunctionMetrics, Suppressions};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 // Removed unused imports
 use std::time::Duration;
 use std::sync::Arc;
@@ -29,6 +33,20 @@ pub struct AnalyticsConfigV2 {
     pub use_sampling: bool,
     /// Sample size for betweenness centrality
     pub betweenness_sample_size: usize,
+    /// Seed that picks the sampled betweenness sources
+    pub betweenness_seed: u64,
+    /// Edge lengths for closeness and betweenness
+    pub path_weighting: PathWeighting,
+    /// Edge types community detection clusters on, all of them when empty
+    pub community_edge_types: Vec<String>,
+    /// Leave edges from a node to itself out of every metric's graph
+    pub drop_self_loops: bool,
+    /// Edge types each metric ignores, by metric name
+    pub excluded_edge_types: BTreeMap<String, Vec<String>>,
+    /// Findings silenced by inline comments
+    pub suppressions: Suppressions,
+    /// Shape of each function and method body, by node id, from parsing
+    pub function_metrics: HashMap<String, FunctionMetrics>,
 }
 
 impl Default for AnalyticsConfigV2 {
@@ -42,6 +60,13 @@ impl Default for AnalyticsConfigV2 {
             louvain_resolution: 1.0,
             use_sampling: true,
             betweenness_sample_size: 1000,
+            betweenness_seed: DEFAULT_SAMPLING_SEED,
+            path_weighting: PathWeighting::default(),
+            community_edge_types: Vec::new(),
+            drop_self_loops: false,
+            excluded_edge_types: BTreeMap::new(),
+            suppressions: Suppressions::default(),
+            function_metrics: HashMap::new(),
         }
     }
 }
@@ -85,32 +110,50 @@ impl Default for MetricWeights {
 /// Main analytics engine v2 with modular metrics
 pub struct AnalyticsEngineV2 {
     config: AnalyticsConfigV2,
-    centrality_metrics: Arc<CentralityMetrics>,
-    community_detector: Arc<CommunityDetection>,
-    risk_analyzer: Arc<RiskAnalyzer>,
-    quality_analyzer: Arc<QualityAnalyzer>,
+    registry: MetricRegistry,
+}
+
+impl AnalyticsConfigV2 {
+    /// Edges left out of the graph the named metric reads
+    pub fn edge_filter(&self, metric: &str) -> EdgeFilter {
+        EdgeFilter {
+            drop_self_loops: self.drop_self_loops,
+            exclude_edge_types: self.excluded_edge_types.get(metric).cloned().unwrap_or_default(),
+        }
+    }
+
+    /// The metric implementations this configuration selects
+    pub fn registry(&self) -> MetricRegistry {
+        let centrality = CentralityMetrics {
+            max_iterations: self.pagerank_iterations,
+            use_sampling: self.use_sampling,
+            sample_size: self.betweenness_sample_size,
+            seed: self.betweenness_seed,
+            path_weighting: self.path_weighting.clone(),
+            ..CentralityMetrics::default()
+        };
+        let community = CommunityDetection {
+            edge_types: self.community_edge_types.clone(),
+            ..CommunityDetection::with_resolution(self.louvain_resolution)
+        };
+        let quality = QualityAnalyzer {
+            suppressions: self.suppressions.clone(),
+            function_metrics: self.function_metrics.clone(),
+            ..QualityAnalyzer::default()
+        };
+        let risk = RiskAnalyzer {
+            suppressions: self.suppressions.clone(),
+            ..RiskAnalyzer::default()
+        };
+        MetricRegistry::new(centrality, community, quality, risk)
+    }
 }
 
 impl AnalyticsEngineV2 {
     /// Create a new analytics engine v2
     pub fn new(config: AnalyticsConfigV2) -> Self {
-        // Configure individual metric modules
-        let mut centrality_metrics = CentralityMetrics::new();
-        centrality_metrics.max_iterations = config.pagerank_iterations;
-        centrality_metrics.use_sampling = config.use_sampling;
-        centrality_metrics.sample_size = config.betweenness_sample_size;
-
-        let community_detector = CommunityDetection::with_resolution(config.louvain_resolution);
-        let risk_analyzer = RiskAnalyzer::new();
-        let quality_analyzer = QualityAnalyzer::new();
-
-        Self {
-            config,
-            centrality_metrics: Arc::new(centrality_metrics),
-            community_detector: Arc::new(community_detector),
-            risk_analyzer: Arc::new(risk_analyzer),
-            quality_analyzer: Arc::new(quality_analyzer),
-        }
+        let registry = config.registry();
+        Self { config, registry }
     }
 
     /// Validate graph before analysis
@@ -219,8 +262,8 @@ impl AnalyticsEngineV2 {
 
     /// Run centrality metrics with timeout
     async fn run_centrality_with_timeout(&self, graph: &CodeGraph) -> Result<CentralityResults> {
-        let graph = graph.clone();
-        let metrics = Arc::clone(&self.centrality_metrics);
+        let graph = graph.filtered(&self.config.edge_filter("centrality")).into_owned();
+        let metrics = Arc::clone(self.registry.centrality());
         let timeout = self.config.metric_timeout;
         
         tokio::time::timeout(timeout, tokio::task::spawn_blocking(move || {
@@ -233,8 +276,8 @@ impl AnalyticsEngineV2 {
     /// Run community detection with timeout - commented out for performance during debugging
     #[allow(dead_code)]
     async fn run_community_with_timeout(&self, graph: &CodeGraph) -> Result<CommunityResults> {
-        let graph = graph.clone();
-        let detector = Arc::clone(&self.community_detector);
+        let graph = graph.filtered(&self.config.edge_filter("community")).into_owned();
+        let detector = Arc::clone(self.registry.community());
         let timeout = self.config.metric_timeout;
         
         tokio::time::timeout(timeout, tokio::task::spawn_blocking(move || {
@@ -246,8 +289,8 @@ impl AnalyticsEngineV2 {
 
     /// Run risk analysis with timeout
     async fn run_risk_with_timeout(&self, graph: &CodeGraph) -> Result<RiskResults> {
-        let graph = graph.clone();
-        let analyzer = Arc::clone(&self.risk_analyzer);
+        let graph = graph.filtered(&self.config.edge_filter("risk")).into_owned();
+        let analyzer = Arc::clone(self.registry.risk());
         let timeout = self.config.metric_timeout;
         
         tokio::time::timeout(timeout, tokio::task::spawn_blocking(move || {
@@ -259,8 +302,8 @@ impl AnalyticsEngineV2 {
 
     /// Run quality analysis with timeout
     async fn run_quality_with_timeout(&self, graph: &CodeGraph) -> Result<QualityResults> {
-        let graph = graph.clone();
-        let analyzer = Arc::clone(&self.quality_analyzer);
+        let graph = graph.filtered(&self.config.edge_filter("quality")).into_owned();
+        let analyzer = Arc::clone(self.registry.quality());
         let timeout = self.config.metric_timeout;
         
         tokio::time::timeout(timeout, tokio::task::spawn_blocking(move || {
@@ -272,7 +315,8 @@ impl AnalyticsEngineV2 {
 }
 
 /// Modular analysis report combining all metric results
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ModularAnalysisReport {
     pub centrality: CentralityResults,
     pub community: CommunityResults,
@@ -310,7 +354,8 @@ impl ModularAnalysisReport {
     }
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct CompositeScores {
     pub importance_scores: Vec<(String, f64)>,
     pub risk_scores: Vec<(String, f64)>,
@@ -332,4 +377,22 @@ mod tests {
         let report = engine.analyze(&graph).await.unwrap();
         assert!(report.errors.is_empty() || !report.errors.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_report_serializes_with_schema_version() {
+        let graph = og_graph::testing::graph(&["a.py", "b.py"], &[("a.py", "b.py")]);
+        let report = AnalyticsEngineV2::new(AnalyticsConfigV2::default()).analyze(&graph).await.unwrap();
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["quality"]["schema"], og_types::METRIC_SCHEMA_VERSION);
+        assert!(json["centrality"]["pagerank"]["a.py"].is_number());
+        assert!(json["risk"]["couplingMetrics"]["b.py"]["afferentCoupling"].is_number());
+
+        let back: ModularAnalysisReport = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(back.quality.complexity_metrics.len(), report.quality.complexity_metrics.len());
+
+        let mut newer = json;
+        newer["risk"]["schema"] = (og_types::METRIC_SCHEMA_VERSION + 1).into();
+        assert!(serde_json::from_value::<ModularAnalysisReport>(newer).is_err());
+    }
 }
\ No newline at end of file
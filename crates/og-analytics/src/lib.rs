@@ -1,46 +1,47 @@
 pub mod algorithms;
 pub mod analysis;
 pub mod engine;
+#[cfg(feature = "modular")]
 pub mod engine_v2;
 pub mod metrics;
 
-pub use analysis::{AnalysisReport, ImpactAnalysis};
-pub use engine::{AnalyticsConfig, AnalyticsEngine};
+pub use analysis::{dependency_cycles, extract_module_candidates, find_similar_nodes, list_findings, list_nodes, metric_distributions, metric_values, module_affinity, recolor, resize, smell_counts, Alert, AlertKind, AlertRules, AnalysisReport, ArchitectureReport, ColorMap, ColorScale, ConcurrencyMap, DebtReport, DocumentationReport, Effort, Finding, FindingFilter, FindingKind, FindingSort, GraphQuery, ReportFormat, ImpactAnalysis, LicenseInventory, MetricDistribution, MetricTiming, MetricsTable, NodeAffinity, NodeFilter, NodeMetricsDetail, NodePage, NodeSizes, Palette, PrSummary, QueryResult, RefactoringSuggestion, Severity, SimilarNode, SizeMetric, SizeScale, SortOrder, StructuralDiff, TableFormat, Treemap, TreemapMetric};
+pub use engine::{AnalyticsConfig, AnalyticsEngine, MetricWeights, DEFAULT_METRICS};
+#[cfg(feature = "modular")]
 pub use engine_v2::{AnalyticsEngineV2, AnalyticsConfigV2, ModularAnalysisReport};
 pub use metrics::{Metric, MetricResults, MetricValue};
+pub use og_metrics_centrality::{PathWeighting, DEFAULT_SAMPLING_SEED};
 
 use anyhow::Result;
 use og_graph::graph::CodeGraph;
 use og_types::metrics::UINodeMetricsV1;
 
 /// Main entry point for analytics with comprehensive error handling
+#[tracing::instrument(skip_all, fields(nodes = graph.graph.node_count(), edges = graph.graph.edge_count()))]
 pub async fn analyze_graph(
     graph: &CodeGraph,
     config: Option<AnalyticsConfig>,
 ) -> Result<AnalysisReport> {
     // Log entry
-    println!("[ANALYTICS] Starting graph analysis");
     tracing::info!("[ANALYTICS] Starting graph analysis");
-    println!("[ANALYTICS] Graph has {} nodes and {} edges", 
+    tracing::debug!("[ANALYTICS] Graph has {} nodes and {} edges", 
              graph.graph.node_count(), 
              graph.graph.edge_count());
     
     let config = config.unwrap_or_default();
-    println!("[ANALYTICS] Creating engine with config: parallel={}, use_cache={}", 
+    tracing::debug!("[ANALYTICS] Creating engine with config: parallel={}, use_cache={}", 
              config.parallel, config.use_cache);
     
     let engine = AnalyticsEngine::new(config);
     
-    println!("[ANALYTICS] Calling engine.analyze...");
+    tracing::debug!("[ANALYTICS] Calling engine.analyze...");
     // Wrap the analysis in error handling
     match engine.analyze(graph).await {
         Ok(report) => {
-            println!("[ANALYTICS] Graph analysis completed successfully");
             tracing::info!("[ANALYTICS] Graph analysis completed successfully");
             Ok(report)
         },
         Err(e) => {
-            println!("[ANALYTICS] Graph analysis failed: {}", e);
             tracing::error!("[ANALYTICS] Graph analysis failed: {}", e);
             Err(e)
         }
@@ -48,31 +49,32 @@ pub async fn analyze_graph(
 }
 
 /// Analyze graph with modular engine (more robust error handling)
+#[cfg(feature = "modular")]
+#[tracing::instrument(skip_all, fields(nodes = graph.graph.node_count(), edges = graph.graph.edge_count()))]
 pub async fn analyze_graph_modular(
     graph: &CodeGraph,
     config: Option<AnalyticsConfigV2>,
 ) -> Result<ModularAnalysisReport> {
-    println!("[ANALYTICS-V2] Starting modular graph analysis");
     tracing::info!("[ANALYTICS-V2] Starting modular graph analysis");
-    println!("[ANALYTICS-V2] Graph has {} nodes and {} edges", 
+    tracing::debug!("[ANALYTICS-V2] Graph has {} nodes and {} edges", 
              graph.graph.node_count(), 
              graph.graph.edge_count());
     
     let config = config.unwrap_or_default();
-    println!("[ANALYTICS-V2] Creating engine with parallel={}, timeout={:?}", 
+    tracing::debug!("[ANALYTICS-V2] Creating engine with parallel={}, timeout={:?}", 
              config.parallel_metrics, config.metric_timeout);
     
     let engine = AnalyticsEngineV2::new(config);
     
-    println!("[ANALYTICS-V2] Calling engine.analyze...");
+    tracing::debug!("[ANALYTICS-V2] Calling engine.analyze...");
     match engine.analyze(graph).await {
         Ok(report) => {
-            println!("[ANALYTICS-V2] Graph analysis completed successfully");
+            tracing::info!("[ANALYTICS-V2] Graph analysis completed successfully");
             if !report.errors.is_empty() {
-                println!("[ANALYTICS-V2] {} metrics had errors but were handled gracefully", 
+                tracing::warn!("[ANALYTICS-V2] {} metrics had errors but were handled gracefully", 
                          report.errors.len());
                 for error in &report.errors {
-                    println!("[ANALYTICS-V2] Error: {}", error);
+                    tracing::warn!("[ANALYTICS-V2] Error: {}", error);
                 }
             }
             tracing::info!("[ANALYTICS-V2] Graph analysis completed with {} errors", 
@@ -80,7 +82,6 @@ pub async fn analyze_graph_modular(
             Ok(report)
         },
         Err(e) => {
-            println!("[ANALYTICS-V2] Graph analysis failed: {}", e);
             tracing::error!("[ANALYTICS-V2] Graph analysis failed: {}", e);
             Err(e)
         }
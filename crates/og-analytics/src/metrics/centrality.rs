@@ -2,214 +2,55 @@ use super::{Metric, MetricResults, MetricValue};
 use anyhow::Result;
 use nalgebra::{DMatrix, DVector};
 use og_graph::graph::CodeGraph;
+use og_metrics_centrality::{CentralityMetrics as Canonical, PathWeighting, DEFAULT_SAMPLING_SEED};
 use petgraph::graph::NodeIndex;
 use petgraph::visit::EdgeRef;
 use petgraph::Direction;
 use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 use tracing::debug;
 
-/// Centrality metrics calculator
+/// Most sources sampled for betweenness on large graphs
+pub const DEFAULT_BETWEENNESS_SAMPLES: usize = 200;
+
+/// Centrality metrics for the legacy engine
+///
+/// Degree, betweenness, closeness, k-truss and articulation points come from
+/// the shared implementation in `og_metrics_centrality`; k-core, clustering
+/// and eigenvector centrality are only measured here.
 pub struct CentralityMetrics {
+    canonical: Arc<Canonical>,
     calculate_eigenvector: bool,
     max_eigenvector_iterations: usize,
 }
 
 impl CentralityMetrics {
     pub fn new() -> Self {
-        Self {
-            calculate_eigenvector: true,
-            max_eigenvector_iterations: 100,
-        }
+        Self::with_seed(DEFAULT_SAMPLING_SEED)
     }
 
-    /// Calculate degree centrality (in and out)
-    fn calculate_degree(&self, graph: &CodeGraph) -> HashMap<String, (f64, f64)> {
-        let mut degree_map = HashMap::new();
-        let node_count = graph.graph.node_count() as f64;
-
-        if node_count <= 1.0 {
-            return degree_map;
-        }
-
-        for node_idx in graph.graph.node_indices() {
-            if let Some(node) = graph.graph.node_weight(node_idx) {
-                let in_degree = graph
-                    .graph
-                    .edges_directed(node_idx, Direction::Incoming)
-                    .count() as f64;
-                let out_degree = graph
-                    .graph
-                    .edges_directed(node_idx, Direction::Outgoing)
-                    .count() as f64;
-
-                // Normalize by node count - 1
-                let normalized_in = in_degree / (node_count - 1.0);
-                let normalized_out = out_degree / (node_count - 1.0);
-
-                degree_map.insert(node.id.clone(), (normalized_in, normalized_out));
-            }
-        }
-
-        degree_map
+    /// Centrality metrics that sample betweenness sources with the given seed
+    pub fn with_seed(sampling_seed: u64) -> Self {
+        Self::from_canonical(Arc::new(Canonical {
+            sample_size: DEFAULT_BETWEENNESS_SAMPLES,
+            seed: sampling_seed,
+            ..Canonical::default()
+        }))
     }
 
-    /// Calculate betweenness centrality
-    fn calculate_betweenness(&self, graph: &CodeGraph) -> HashMap<String, f64> {
-        debug!("Calculating betweenness centrality");
-        println!("[CENTRALITY] Betweenness: node_count = {}", graph.graph.node_count());
-        
-        let mut betweenness = HashMap::new();
-        let node_count = graph.graph.node_count();
-        
-        // Handle small graphs
-        if node_count <= 2 {
-            for node_idx in graph.graph.node_indices() {
-                if let Some(node) = graph.graph.node_weight(node_idx) {
-                    betweenness.insert(node.id.clone(), 0.0);
-                }
-            }
-            return betweenness;
-        }
-        
-        // Initialize all nodes with 0 betweenness
-        for node_idx in graph.graph.node_indices() {
-            if let Some(node) = graph.graph.node_weight(node_idx) {
-                betweenness.insert(node.id.clone(), 0.0);
-            }
-        }
-        
-        // For large graphs, use sampling to avoid O(n³) complexity
-        const MAX_FULL_CALC_NODES: usize = 100;
-        const SAMPLE_SIZE: usize = 50;
-        
-        if node_count > MAX_FULL_CALC_NODES {
-            println!("[CENTRALITY] Large graph detected ({} nodes), using sampling approach", node_count);
-            
-            // Sample a subset of source nodes for approximation
-            let node_indices: Vec<_> = graph.graph.node_indices().collect();
-            let sample_size = SAMPLE_SIZE.min(node_count);
-            let step = node_count / sample_size;
-            
-            for (i, &source) in node_indices.iter().step_by(step.max(1)).enumerate() {
-                if i >= sample_size {
-                    break;
-                }
-                
-                // Use petgraph's built-in betweenness calculation for this source
-                let paths = petgraph::algo::dijkstra(&graph.graph, source, None, |_| 1.0);
-                
-                // Just count nodes on shortest paths (simplified)
-                for (node_idx, _) in paths.iter() {
-                    if let Some(node) = graph.graph.node_weight(*node_idx) {
-                        *betweenness.entry(node.id.clone()).or_insert(0.0) += 1.0;
-                    }
-                }
-            }
-        } else {
-            println!("[CENTRALITY] Small graph ({} nodes), using full calculation", node_count);
-            
-            // Original O(n³) algorithm for small graphs
-            for source in graph.graph.node_indices() {
-                let paths = petgraph::algo::dijkstra(&graph.graph, source, None, |_| 1.0);
-                
-                for target in graph.graph.node_indices() {
-                    if source != target {
-                        // Count paths through intermediate nodes
-                        for intermediate in graph.graph.node_indices() {
-                            if intermediate != source && intermediate != target {
-                                // Simplified: increment if on a path
-                                if paths.contains_key(&intermediate) && paths.contains_key(&target) {
-                                    if let Some(node) = graph.graph.node_weight(intermediate) {
-                                        *betweenness.entry(node.id.clone()).or_insert(0.0) += 1.0;
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        
-        // Normalize (with safety check)
-        let normalization = ((node_count - 1) * (node_count - 2)) as f64;
-        if normalization > 0.0 {
-            for value in betweenness.values_mut() {
-                *value /= normalization;
-                // Ensure finite values
-                if !value.is_finite() {
-                    *value = 0.0;
-                }
-            }
+    /// Centrality metrics backed by an already configured implementation
+    pub fn from_canonical(canonical: Arc<Canonical>) -> Self {
+        Self {
+            canonical,
+            calculate_eigenvector: true,
+            max_eigenvector_iterations: 100,
         }
-        
-        betweenness
     }
 
-    /// Calculate closeness centrality
-    fn calculate_closeness(&self, graph: &CodeGraph) -> HashMap<String, f64> {
-        debug!("Calculating closeness centrality");
-        
-        let mut result = HashMap::new();
-        let node_count = graph.graph.node_count();
-        
-        if node_count <= 1 {
-            // For single node, closeness is 1.0
-            for node_idx in graph.graph.node_indices() {
-                if let Some(node) = graph.graph.node_weight(node_idx) {
-                    result.insert(node.id.clone(), 1.0);
-                }
-            }
-            return result;
-        }
-
-        // For each node, calculate shortest paths to all other nodes
-        for node_idx in graph.graph.node_indices() {
-            let distances = petgraph::algo::dijkstra(
-                &graph.graph,
-                node_idx,
-                None,
-                |_| 1.0,
-            );
-            
-            if distances.len() > 1 {
-                // Only sum distances to reachable nodes (excluding self)
-                let reachable_distances: Vec<f64> = distances
-                    .iter()
-                    .filter(|(idx, _)| **idx != node_idx)
-                    .map(|(_, &dist)| dist)
-                    .collect();
-                
-                if !reachable_distances.is_empty() {
-                    let total_distance: f64 = reachable_distances.iter().sum();
-                    let closeness = if total_distance > 0.0 {
-                        reachable_distances.len() as f64 / total_distance
-                    } else {
-                        0.0
-                    };
-                    
-                    if let Some(node) = graph.graph.node_weight(node_idx) {
-                        // Ensure finite value
-                        if closeness.is_finite() {
-                            result.insert(node.id.clone(), closeness);
-                        } else {
-                            result.insert(node.id.clone(), 0.0);
-                        }
-                    }
-                } else {
-                    // Node is isolated
-                    if let Some(node) = graph.graph.node_weight(node_idx) {
-                        result.insert(node.id.clone(), 0.0);
-                    }
-                }
-            } else {
-                // Node is isolated
-                if let Some(node) = graph.graph.node_weight(node_idx) {
-                    result.insert(node.id.clone(), 0.0);
-                }
-            }
-        }
-        
-        result
+    /// Measure shortest paths with the given edge lengths instead of hops
+    pub fn with_path_weighting(mut self, path_weighting: PathWeighting) -> Self {
+        Arc::make_mut(&mut self.canonical).path_weighting = path_weighting;
+        self
     }
 
     /// Calculate eigenvector centrality
@@ -283,12 +124,16 @@ impl CentralityMetrics {
             } else {
                 // Matrix has no dominant eigenvalue, use degree centrality fallback
                 debug!("Eigenvector calculation failed, using degree centrality fallback");
-                let degree_map = self.calculate_degree(graph);
-                let mut result = HashMap::new();
-                for (node_id, (in_deg, out_deg)) in degree_map {
-                    result.insert(node_id, (in_deg + out_deg) / 2.0);
-                }
-                return result;
+                return self
+                    .canonical
+                    .calculate_degree_centrality(graph)
+                    .map(|degree_map| {
+                        degree_map
+                            .into_iter()
+                            .map(|(node_id, degree)| (node_id, degree.total_degree))
+                            .collect()
+                    })
+                    .unwrap_or_default();
             }
             iterations += 1;
         }
@@ -438,35 +283,39 @@ impl CentralityMetrics {
 
 impl Metric for CentralityMetrics {
     fn calculate(&self, graph: &CodeGraph) -> Result<MetricResults> {
-        println!("[CENTRALITY] Starting centrality metrics calculation");
+        tracing::info!("[CENTRALITY] Starting centrality metrics calculation");
         let mut results = MetricResults::new("centrality".to_string());
 
         // Calculate all centrality metrics
-        println!("[CENTRALITY] Calculating degree centrality...");
-        let degree_centrality = self.calculate_degree(graph);
-        println!("[CENTRALITY] Degree centrality done. Calculating betweenness...");
-        let betweenness = self.calculate_betweenness(graph);
-        println!("[CENTRALITY] Betweenness done. Calculating closeness...");
-        let closeness = self.calculate_closeness(graph);
-        println!("[CENTRALITY] Closeness done. Calculating k-core...");
-        let k_core = self.calculate_k_core(graph);
-        println!("[CENTRALITY] K-core done. Calculating clustering...");
-        let clustering = self.calculate_clustering(graph);
-        println!("[CENTRALITY] Clustering done.");
+        tracing::debug!("[CENTRALITY] Calculating degree centrality...");
+        let degree_centrality = results.timed("degree", || self.canonical.calculate_degree_centrality(graph))?;
+        tracing::debug!("[CENTRALITY] Degree centrality done. Calculating betweenness...");
+        let betweenness = results.timed("betweenness", || self.canonical.calculate_betweenness_safe(graph))?;
+        tracing::debug!("[CENTRALITY] Betweenness done. Calculating closeness...");
+        let closeness = results.timed("closeness", || self.canonical.calculate_closeness(graph))?;
+        tracing::debug!("[CENTRALITY] Closeness done. Calculating k-core...");
+        let k_core = results.timed("k_core", || self.calculate_k_core(graph));
+        tracing::debug!("[CENTRALITY] K-core done. Calculating clustering...");
+        let clustering = results.timed("clustering", || self.calculate_clustering(graph));
+        tracing::debug!("[CENTRALITY] Clustering done. Calculating k-truss...");
+        let k_truss = results.timed("k_truss", || self.canonical.calculate_k_truss(graph))?;
+        tracing::debug!("[CENTRALITY] K-truss done. Finding articulation points...");
+        let (articulation_points, bridges) = results.timed("cut_points", || self.canonical.find_cut_points(graph))?;
+        tracing::debug!("[CENTRALITY] Articulation points done.");
 
         // Store degree centrality
-        for (node_id, (in_degree, out_degree)) in degree_centrality {
+        for (node_id, degree) in degree_centrality {
             results.add_value(
                 format!("{}_in_degree", node_id),
-                MetricValue::Float(in_degree),
+                MetricValue::Float(degree.in_degree),
             );
             results.add_value(
                 format!("{}_out_degree", node_id),
-                MetricValue::Float(out_degree),
+                MetricValue::Float(degree.out_degree),
             );
             results.add_value(
                 format!("{}_degree", node_id),
-                MetricValue::Float((in_degree + out_degree) / 2.0),
+                MetricValue::Float(degree.total_degree),
             );
         }
 
@@ -496,6 +345,23 @@ impl Metric for CentralityMetrics {
             );
         }
 
+        // Store k-truss, flagging articulation points alongside it
+        for (node_id, value) in k_truss {
+            results.add_value(
+                format!("{}_k_truss", node_id),
+                MetricValue::Integer(value as i64),
+            );
+            results.add_value(
+                format!("{}_articulation_point", node_id),
+                MetricValue::Integer(articulation_points.binary_search(&node_id).is_ok() as i64),
+            );
+        }
+        results.add_value(
+            "articulation_points".to_string(),
+            MetricValue::Integer(articulation_points.len() as i64),
+        );
+        results.add_value("bridges".to_string(), MetricValue::Integer(bridges.len() as i64));
+
         // Store clustering
         results.add_value("clustering_map".to_string(), MetricValue::Map(clustering.clone()));
         for (node_id, value) in clustering {
@@ -507,7 +373,7 @@ impl Metric for CentralityMetrics {
 
         // Calculate eigenvector if enabled
         if self.calculate_eigenvector {
-            let eigenvector = self.calculate_eigenvector_centrality(graph);
+            let eigenvector = results.timed("eigenvector", || self.calculate_eigenvector_centrality(graph));
             results.add_value("eigenvector_map".to_string(), MetricValue::Map(eigenvector.clone()));
             for (node_id, value) in eigenvector {
                 results.add_value(
@@ -1,11 +1,16 @@
 pub mod centrality;
 pub mod community;
 pub mod quality;
+pub mod registry;
 pub mod risk;
 
+pub use registry::MetricRegistry;
+
 use anyhow::Result;
 use og_graph::graph::CodeGraph;
 use std::collections::HashMap;
+use std::time::Duration;
+use web_time::Instant;
 
 /// Value types for metrics
 #[derive(Debug, Clone)]
@@ -38,6 +43,10 @@ impl MetricValue {
 pub struct MetricResults {
     pub name: String,
     pub values: HashMap<String, MetricValue>,
+    /// Total time spent computing this metric
+    pub duration: Duration,
+    /// Time spent in each sub-step, in execution order
+    pub step_timings: Vec<(String, Duration)>,
 }
 
 impl MetricResults {
@@ -45,9 +54,19 @@ impl MetricResults {
         Self {
             name,
             values: HashMap::new(),
+            duration: Duration::ZERO,
+            step_timings: Vec::new(),
         }
     }
 
+    /// Run a sub-step and record how long it took
+    pub fn timed<T>(&mut self, step: &str, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let value = f();
+        self.step_timings.push((step.to_string(), start.elapsed()));
+        value
+    }
+
     pub fn add_value(&mut self, key: String, value: MetricValue) {
         self.values.insert(key, value);
     }
@@ -102,6 +121,23 @@ pub fn percentile_rank(value: f64, values: &[f64]) -> f64 {
         return 0.0;
     }
     
-    let position = sorted.iter().position(|&v| v >= value).unwrap_or(sorted.len());
+    percentile_rank_sorted(value, &sorted)
+}
+
+/// Percentile rank of a value among finite values already sorted ascending
+pub fn percentile_rank_sorted(value: f64, sorted: &[f64]) -> f64 {
+    if sorted.is_empty() || !value.is_finite() {
+        return 0.0;
+    }
+    let position = sorted.partition_point(|&v| v < value);
     position as f64 / sorted.len() as f64
+}
+
+/// Z-score of a value clamped to `clamp` standard deviations and mapped to 0-1
+pub fn clamped_z_score(value: f64, mean: f64, std_dev: f64, clamp: f64) -> f64 {
+    if std_dev <= 0.0 || clamp <= 0.0 || !value.is_finite() {
+        return 0.5;
+    }
+    let z = ((value - mean) / std_dev).clamp(-clamp, clamp);
+    (z + clamp) / (2.0 * clamp)
 }
\ No newline at end of file
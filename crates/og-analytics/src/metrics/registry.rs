@@ -0,0 +1,66 @@
+use super::centrality::CentralityMetrics;
+use super::community::CommunityDetection;
+use super::quality::QualityMetrics;
+use super::risk::RiskAnalysis;
+use super::Metric;
+use og_metrics_quality::QualityAnalyzer;
+use og_metrics_risk::RiskAnalyzer;
+use std::sync::Arc;
+
+/// The one configured implementation of each metric family
+///
+/// The modular engine calls these implementations directly and the legacy
+/// engine runs them through [`Metric`] adapters, so both engines report the
+/// same values and a fix to an algorithm reaches both.
+#[derive(Clone)]
+pub struct MetricRegistry {
+    centrality: Arc<og_metrics_centrality::CentralityMetrics>,
+    community: Arc<og_metrics_community::CommunityDetection>,
+    quality: Arc<QualityAnalyzer>,
+    risk: Arc<RiskAnalyzer>,
+}
+
+impl MetricRegistry {
+    pub fn new(
+        centrality: og_metrics_centrality::CentralityMetrics,
+        community: og_metrics_community::CommunityDetection,
+        quality: QualityAnalyzer,
+        risk: RiskAnalyzer,
+    ) -> Self {
+        Self {
+            centrality: Arc::new(centrality),
+            community: Arc::new(community),
+            quality: Arc::new(quality),
+            risk: Arc::new(risk),
+        }
+    }
+
+    pub fn centrality(&self) -> &Arc<og_metrics_centrality::CentralityMetrics> {
+        &self.centrality
+    }
+
+    pub fn community(&self) -> &Arc<og_metrics_community::CommunityDetection> {
+        &self.community
+    }
+
+    pub fn quality(&self) -> &Arc<QualityAnalyzer> {
+        &self.quality
+    }
+
+    pub fn risk(&self) -> &Arc<RiskAnalyzer> {
+        &self.risk
+    }
+
+    /// The named metric family as a legacy engine metric, or None if there
+    /// is no such family
+    pub fn metric(&self, name: &str) -> Option<Box<dyn Metric>> {
+        let metric: Box<dyn Metric> = match name {
+            "centrality" => Box::new(CentralityMetrics::from_canonical(Arc::clone(&self.centrality))),
+            "community" => Box::new(CommunityDetection::from_canonical(Arc::clone(&self.community))),
+            "quality" => Box::new(QualityMetrics::from_canonical(Arc::clone(&self.quality))),
+            "risk" => Box::new(RiskAnalysis::from_canonical(Arc::clone(&self.risk))),
+            _ => return None,
+        };
+        Some(metric)
+    }
+}
@@ -1,156 +1,95 @@
 use super::{Metric, MetricResults, MetricValue};
 use anyhow::Result;
 use og_graph::graph::CodeGraph;
-use petgraph::algo::tarjan_scc;
+use og_metrics_risk::{
+    complexity_estimate, RiskAnalyzer, BOTTLENECK_WEIGHT, CENTRALITY_WEIGHT, COMPLEXITY_WEIGHT, COUPLING_WEIGHT,
+};
 use petgraph::Direction;
 use petgraph::visit::EdgeRef;
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
 use tracing::debug;
 
-/// Risk analysis metrics
+/// Risk analysis for the legacy engine
+///
+/// Risk scores, chokepoints, cycles and coupling come from
+/// `og_metrics_risk`; technical debt and change propagation are only
+/// estimated here.
 pub struct RiskAnalysis {
-    #[allow(dead_code)]
-    churn_threshold: f64,
-    complexity_threshold: f64,
+    canonical: Arc<RiskAnalyzer>,
 }
 
 impl RiskAnalysis {
     pub fn new() -> Self {
-        Self {
-            churn_threshold: 10.0,
-            complexity_threshold: 15.0,
-        }
+        Self::from_canonical(Arc::new(RiskAnalyzer::default()))
     }
 
-    /// Identify high-risk nodes (high complexity + high centrality)
-    fn identify_high_risk_nodes(&self, graph: &CodeGraph) -> HashMap<String, RiskScore> {
-        debug!("Identifying high-risk nodes");
-        
-        let mut risk_scores = HashMap::new();
-
-        // Calculate basic metrics for risk assessment
-        for node_idx in graph.graph.node_indices() {
-            if let Some(node) = graph.graph.node_weight(node_idx) {
-                // Calculate degree centrality
-                let in_degree = graph
-                    .graph
-                    .edges_directed(node_idx, Direction::Incoming)
-                    .count();
-                let out_degree = graph
-                    .graph
-                    .edges_directed(node_idx, Direction::Outgoing)
-                    .count();
-                
-                let total_degree = in_degree + out_degree;
-                let node_count = graph.graph.node_count().max(1);
-                let normalized_degree = total_degree as f64 / node_count as f64;
-
-                // Estimate complexity based on node type and connections
-                let complexity_estimate = match node.node_type.as_str() {
-                    "function" | "method" => (out_degree + 1) as f64,
-                    "class" => (out_degree * 2) as f64,
-                    "file" | "module" => {
-                        // Files with many dependencies are complex
-                        (out_degree as f64).sqrt() * 5.0
-                    }
-                    _ => 1.0,
-                };
-
-                // Calculate risk factors
-                let complexity_risk = (complexity_estimate / self.complexity_threshold).min(1.0);
-                let centrality_risk = normalized_degree.min(1.0);
-                
-                // Check if node is a single point of failure
-                let is_bottleneck = in_degree > 5 && out_degree > 5;
-                let bottleneck_risk = if is_bottleneck { 0.5 } else { 0.0 };
-
-                // Combined risk score
-                let risk_score = (complexity_risk * 0.4 
-                    + centrality_risk * 0.4 
-                    + bottleneck_risk * 0.2)
-                    .clamp(0.0, 1.0);
-
-                risk_scores.insert(
-                    node.id.clone(),
-                    RiskScore {
-                        overall: risk_score,
-                        complexity: complexity_risk,
-                        centrality: centrality_risk,
-                        bottleneck: bottleneck_risk,
-                    },
-                );
-            }
-        }
-
-        risk_scores
-    }
-
-    /// Find architectural chokepoints
-    fn find_chokepoints(&self, graph: &CodeGraph) -> HashMap<String, f64> {
-        debug!("Finding architectural chokepoints");
-        
-        let mut chokepoints = HashMap::new();
-
-        for node_idx in graph.graph.node_indices() {
-            if let Some(node) = graph.graph.node_weight(node_idx) {
-                // Simplified chokepoint detection based on betweenness-like metric
-                let in_degree = graph
-                    .graph
-                    .edges_directed(node_idx, Direction::Incoming)
-                    .count();
-                let out_degree = graph
-                    .graph
-                    .edges_directed(node_idx, Direction::Outgoing)
-                    .count();
-                
-                // A node is a chokepoint if it has high in and out degree
-                // and is on many paths (approximation)
-                let total_degree = in_degree + out_degree;
-                let node_count = graph.graph.node_count().max(1);
-                
-                // Simple heuristic: nodes with many connections in both directions
-                // are likely chokepoints
-                let chokepoint_score = if in_degree > 0 && out_degree > 0 {
-                    let balance = (in_degree.min(out_degree) as f64) / (in_degree.max(out_degree).max(1) as f64);
-                    let connectivity = (total_degree as f64) / (node_count as f64);
-                    (balance * connectivity).min(1.0)
-                } else {
-                    0.0
-                };
-
-                chokepoints.insert(node.id.clone(), chokepoint_score);
-            }
-        }
-
-        chokepoints
+    /// Risk analysis backed by an already configured analyzer
+    pub fn from_canonical(canonical: Arc<RiskAnalyzer>) -> Self {
+        Self { canonical }
     }
 
-    /// Detect circular dependencies using Tarjan's algorithm
-    fn detect_circular_dependencies(&self, graph: &CodeGraph) -> Vec<Vec<String>> {
-        debug!("Detecting circular dependencies");
-        
-        // Find strongly connected components
-        let sccs = tarjan_scc(&graph.graph);
-        
-        let mut circular_deps = Vec::new();
-        
-        for scc in sccs {
-            // Only consider SCCs with more than one node (cycles)
-            if scc.len() > 1 {
-                let cycle: Vec<String> = scc
-                    .iter()
-                    .filter_map(|&idx| {
-                        graph.graph.node_weight(idx).map(|n| n.id.clone())
-                    })
-                    .collect();
-                
-                if !cycle.is_empty() {
-                    circular_deps.push(cycle);
-                }
-            }
-        }
-
-        circular_deps
+    /// Break a node's risk score down into the factors it is made of
+    pub fn explain(&self, graph: &CodeGraph, node_id: &str) -> Option<RiskExplanation> {
+        let node_idx = *graph.node_map.get(node_id)?;
+        let node = &graph.graph[node_idx];
+        let score = self.canonical.score(graph, node_idx);
+        let in_degree = graph.graph.edges_directed(node_idx, Direction::Incoming).count();
+        let out_degree = graph.graph.edges_directed(node_idx, Direction::Outgoing).count();
+        let (complexity_threshold, coupling_threshold) = self.canonical.thresholds(node.file_path.as_deref());
+
+        let factor = |name: &str, value: f64, weight: f64, detail: String| RiskFactor {
+            name: name.to_string(),
+            value,
+            weight,
+            contribution: value * weight,
+            detail,
+        };
+        Some(RiskExplanation {
+            risk: score.overall,
+            factors: vec![
+                factor(
+                    "complexity",
+                    score.complexity,
+                    COMPLEXITY_WEIGHT,
+                    format!(
+                        "Estimated complexity {:.1} against a threshold of {:.0}",
+                        complexity_estimate(&node.node_type, out_degree),
+                        complexity_threshold
+                    ),
+                ),
+                factor(
+                    "centrality",
+                    score.centrality,
+                    CENTRALITY_WEIGHT,
+                    format!(
+                        "{} connections across {} nodes",
+                        in_degree + out_degree,
+                        graph.graph.node_count()
+                    ),
+                ),
+                factor(
+                    "bottleneck",
+                    score.bottleneck,
+                    BOTTLENECK_WEIGHT,
+                    format!(
+                        "{} incoming and {} outgoing edges; more than {} of each marks a bottleneck",
+                        in_degree, out_degree, self.canonical.bottleneck_threshold
+                    ),
+                ),
+                factor(
+                    "coupling",
+                    score.coupling,
+                    COUPLING_WEIGHT,
+                    format!(
+                        "{} connections against a coupling threshold of {}",
+                        in_degree + out_degree,
+                        coupling_threshold
+                    ),
+                ),
+            ],
+        })
     }
 
     /// Calculate technical debt score
@@ -314,24 +253,36 @@ impl RiskAnalysis {
     }
 }
 
-#[derive(Debug, Clone)]
-struct RiskScore {
-    overall: f64,
-    complexity: f64,
-    centrality: f64,
-    bottleneck: f64,
+/// One input to a node's risk score
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RiskFactor {
+    pub name: String,
+    /// Factor value from 0 to 1
+    pub value: f64,
+    pub weight: f64,
+    /// Points the factor adds to the score, `value * weight`
+    pub contribution: f64,
+    pub detail: String,
+}
+
+/// How a node's risk score was reached
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RiskExplanation {
+    pub risk: f64,
+    pub factors: Vec<RiskFactor>,
 }
 
 impl Metric for RiskAnalysis {
     fn calculate(&self, graph: &CodeGraph) -> Result<MetricResults> {
-        println!("[RISK] Starting risk analysis");
+        tracing::info!("[RISK] Starting risk analysis");
         let mut results = MetricResults::new("risk".to_string());
 
-        // Calculate risk scores
-        println!("[RISK] Identifying high-risk nodes...");
-        let risk_scores = self.identify_high_risk_nodes(graph);
-        println!("[RISK] Found {} high-risk nodes", risk_scores.len());
-        for (node_id, scores) in risk_scores {
+        tracing::debug!("[RISK] Scoring nodes...");
+        let risks = results.timed("risk_scores", || self.canonical.analyze_risks(graph))?;
+        tracing::info!("[RISK] Found {} high-risk nodes", risks.high_risk_count);
+        for (node_id, scores) in &risks.risk_scores {
             results.add_value(
                 format!("{}_risk", node_id),
                 MetricValue::Float(scores.overall),
@@ -348,30 +299,42 @@ impl Metric for RiskAnalysis {
                 format!("{}_bottleneck_risk", node_id),
                 MetricValue::Float(scores.bottleneck),
             );
+            results.add_value(
+                format!("{}_coupling_risk", node_id),
+                MetricValue::Float(scores.coupling),
+            );
         }
 
-        // Find chokepoints
-        println!("[RISK] Finding chokepoints...");
-        let chokepoints = self.find_chokepoints(graph);
-        println!("[RISK] Found {} chokepoints", chokepoints.len());
-        for (node_id, score) in chokepoints {
+        for (node_id, score) in &risks.chokepoints {
             results.add_value(
                 format!("{}_chokepoint", node_id),
-                MetricValue::Float(score),
+                MetricValue::Float(*score),
+            );
+        }
+
+        for (node_id, coupling) in &risks.coupling_metrics {
+            results.add_value(
+                format!("{}_afferent_coupling", node_id),
+                MetricValue::Float(coupling.afferent_coupling as f64),
+            );
+            results.add_value(
+                format!("{}_efferent_coupling", node_id),
+                MetricValue::Float(coupling.efferent_coupling as f64),
+            );
+            results.add_value(
+                format!("{}_instability", node_id),
+                MetricValue::Float(coupling.instability),
             );
         }
 
-        // Detect circular dependencies
-        println!("[RISK] Detecting circular dependencies...");
-        let circular_deps = self.detect_circular_dependencies(graph);
-        println!("[RISK] Found {} circular dependencies", circular_deps.len());
+        tracing::info!("[RISK] Found {} circular dependencies", risks.total_circular_deps);
         results.add_value(
             "circular_dependencies".to_string(),
-            MetricValue::Integer(circular_deps.len() as i64),
+            MetricValue::Integer(risks.total_circular_deps as i64),
         );
 
         // Store circular dependency groups
-        for (i, cycle) in circular_deps.iter().enumerate() {
+        for (i, cycle) in risks.circular_dependencies.iter().enumerate() {
             let cycle_map: HashMap<String, f64> = cycle
                 .iter()
                 .enumerate()
@@ -384,9 +347,9 @@ impl Metric for RiskAnalysis {
         }
 
         // Calculate technical debt
-        println!("[RISK] Calculating technical debt...");
-        let debt_scores = self.calculate_technical_debt(graph);
-        println!("[RISK] Calculated debt for {} nodes", debt_scores.len());
+        tracing::debug!("[RISK] Calculating technical debt...");
+        let debt_scores = results.timed("technical_debt", || self.calculate_technical_debt(graph));
+        tracing::debug!("[RISK] Calculated debt for {} nodes", debt_scores.len());
         for (node_id, score) in debt_scores {
             results.add_value(
                 format!("{}_technical_debt", node_id),
@@ -395,9 +358,9 @@ impl Metric for RiskAnalysis {
         }
 
         // Calculate change propagation
-        println!("[RISK] Calculating change propagation...");
-        let propagation = self.calculate_change_propagation(graph);
-        println!("[RISK] Calculated propagation for {} nodes", propagation.len());
+        tracing::debug!("[RISK] Calculating change propagation...");
+        let propagation = results.timed("change_propagation", || self.calculate_change_propagation(graph));
+        tracing::debug!("[RISK] Calculated propagation for {} nodes", propagation.len());
         for (node_id, score) in propagation {
             results.add_value(
                 format!("{}_change_propagation", node_id),
@@ -405,7 +368,7 @@ impl Metric for RiskAnalysis {
             );
         }
 
-        println!("[RISK] Risk analysis complete");
+        tracing::info!("[RISK] Risk analysis complete");
         Ok(results)
     }
 
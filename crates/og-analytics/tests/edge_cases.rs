@@ -1,5 +1,6 @@
 use og_analytics::{analyze_graph, AnalyticsConfig};
 use og_graph::graph::{CodeGraph, GraphEdge, GraphNode};
+use og_graph::testing::{edge, node};
 use petgraph::graph::DiGraph;
 use std::collections::HashMap;
 
@@ -8,6 +9,7 @@ async fn test_empty_graph() {
     let graph = CodeGraph {
         graph: DiGraph::new(),
         node_map: HashMap::new(),
+        parallel_edges: Default::default(),
     };
     
     let config = AnalyticsConfig::default();
@@ -20,12 +22,9 @@ async fn test_empty_graph() {
 async fn test_single_node_graph() {
     let mut graph = DiGraph::new();
     let node = GraphNode {
-        id: "test".to_string(),
-        name: "test".to_string(),
-        node_type: "file".to_string(),
         size: 100.0,
-        color: "#ff0000".to_string(),
         file_path: Some("/test.js".to_string()),
+        ..node("test")
     };
     
     let idx = graph.add_node(node.clone());
@@ -33,7 +32,7 @@ async fn test_single_node_graph() {
     let mut node_map = HashMap::new();
     node_map.insert("test".to_string(), idx);
     
-    let code_graph = CodeGraph { graph, node_map };
+    let code_graph = CodeGraph { graph, node_map, parallel_edges: Default::default() };
     
     let config = AnalyticsConfig::default();
     let result = analyze_graph(&code_graph, Some(config)).await;
@@ -45,26 +44,20 @@ async fn test_single_node_graph() {
 async fn test_graph_with_self_loop() {
     let mut graph = DiGraph::new();
     let node = GraphNode {
-        id: "test".to_string(),
-        name: "test".to_string(),
-        node_type: "file".to_string(),
         size: 100.0,
-        color: "#ff0000".to_string(),
         file_path: Some("/test.js".to_string()),
+        ..node("test")
     };
     
     let idx = graph.add_node(node.clone());
     
     // Add self-loop
-    graph.add_edge(idx, idx, GraphEdge {
-        edge_type: "imports".to_string(),
-        weight: 1.0,
-    });
+    graph.add_edge(idx, idx, edge("imports"));
     
     let mut node_map = HashMap::new();
     node_map.insert("test".to_string(), idx);
     
-    let code_graph = CodeGraph { graph, node_map };
+    let code_graph = CodeGraph { graph, node_map, parallel_edges: Default::default() };
     
     let config = AnalyticsConfig::default();
     let result = analyze_graph(&code_graph, Some(config)).await;
@@ -78,30 +71,21 @@ async fn test_disconnected_graph() {
     
     // Create two disconnected components
     let node1 = GraphNode {
-        id: "node1".to_string(),
-        name: "node1".to_string(),
-        node_type: "file".to_string(),
         size: 100.0,
-        color: "#ff0000".to_string(),
         file_path: Some("/node1.js".to_string()),
+        ..node("node1")
     };
     
     let node2 = GraphNode {
-        id: "node2".to_string(),
-        name: "node2".to_string(),
-        node_type: "file".to_string(),
         size: 100.0,
-        color: "#00ff00".to_string(),
         file_path: Some("/node2.js".to_string()),
+        ..node("node2")
     };
     
     let node3 = GraphNode {
-        id: "node3".to_string(),
-        name: "node3".to_string(),
-        node_type: "file".to_string(),
         size: 100.0,
-        color: "#0000ff".to_string(),
         file_path: Some("/node3.js".to_string()),
+        ..node("node3")
     };
     
     let idx1 = graph.add_node(node1.clone());
@@ -109,17 +93,14 @@ async fn test_disconnected_graph() {
     let idx3 = graph.add_node(node3.clone());
     
     // Connect node1 and node2, leave node3 disconnected
-    graph.add_edge(idx1, idx2, GraphEdge {
-        edge_type: "imports".to_string(),
-        weight: 1.0,
-    });
+    graph.add_edge(idx1, idx2, edge("imports"));
     
     let mut node_map = HashMap::new();
     node_map.insert("node1".to_string(), idx1);
     node_map.insert("node2".to_string(), idx2);
     node_map.insert("node3".to_string(), idx3);
     
-    let code_graph = CodeGraph { graph, node_map };
+    let code_graph = CodeGraph { graph, node_map, parallel_edges: Default::default() };
     
     let config = AnalyticsConfig::default();
     let result = analyze_graph(&code_graph, Some(config)).await;
@@ -132,21 +113,15 @@ async fn test_graph_with_nan_weights() {
     let mut graph = DiGraph::new();
     
     let node1 = GraphNode {
-        id: "node1".to_string(),
-        name: "node1".to_string(),
-        node_type: "file".to_string(),
         size: 100.0,
-        color: "#ff0000".to_string(),
         file_path: Some("/node1.js".to_string()),
+        ..node("node1")
     };
     
     let node2 = GraphNode {
-        id: "node2".to_string(),
-        name: "node2".to_string(),
-        node_type: "file".to_string(),
         size: 100.0,
-        color: "#00ff00".to_string(),
         file_path: Some("/node2.js".to_string()),
+        ..node("node2")
     };
     
     let idx1 = graph.add_node(node1.clone());
@@ -154,15 +129,15 @@ async fn test_graph_with_nan_weights() {
     
     // Add edge with NaN weight
     graph.add_edge(idx1, idx2, GraphEdge {
-        edge_type: "imports".to_string(),
         weight: f64::NAN,
+        ..edge("imports")
     });
     
     let mut node_map = HashMap::new();
     node_map.insert("node1".to_string(), idx1);
     node_map.insert("node2".to_string(), idx2);
     
-    let code_graph = CodeGraph { graph, node_map };
+    let code_graph = CodeGraph { graph, node_map, parallel_edges: Default::default() };
     
     let config = AnalyticsConfig::default();
     let result = analyze_graph(&code_graph, Some(config)).await;
@@ -175,21 +150,15 @@ async fn test_graph_with_infinite_weights() {
     let mut graph = DiGraph::new();
     
     let node1 = GraphNode {
-        id: "node1".to_string(),
-        name: "node1".to_string(),
-        node_type: "file".to_string(),
         size: 100.0,
-        color: "#ff0000".to_string(),
         file_path: Some("/node1.js".to_string()),
+        ..node("node1")
     };
     
     let node2 = GraphNode {
-        id: "node2".to_string(),
-        name: "node2".to_string(),
-        node_type: "file".to_string(),
         size: 100.0,
-        color: "#00ff00".to_string(),
         file_path: Some("/node2.js".to_string()),
+        ..node("node2")
     };
     
     let idx1 = graph.add_node(node1.clone());
@@ -197,15 +166,15 @@ async fn test_graph_with_infinite_weights() {
     
     // Add edge with infinite weight
     graph.add_edge(idx1, idx2, GraphEdge {
-        edge_type: "imports".to_string(),
         weight: f64::INFINITY,
+        ..edge("imports")
     });
     
     let mut node_map = HashMap::new();
     node_map.insert("node1".to_string(), idx1);
     node_map.insert("node2".to_string(), idx2);
     
-    let code_graph = CodeGraph { graph, node_map };
+    let code_graph = CodeGraph { graph, node_map, parallel_edges: Default::default() };
     
     let config = AnalyticsConfig::default();
     let result = analyze_graph(&code_graph, Some(config)).await;
@@ -218,30 +187,21 @@ async fn test_circular_dependency() {
     let mut graph = DiGraph::new();
     
     let node1 = GraphNode {
-        id: "node1".to_string(),
-        name: "node1".to_string(),
-        node_type: "file".to_string(),
         size: 100.0,
-        color: "#ff0000".to_string(),
         file_path: Some("/node1.js".to_string()),
+        ..node("node1")
     };
     
     let node2 = GraphNode {
-        id: "node2".to_string(),
-        name: "node2".to_string(),
-        node_type: "file".to_string(),
         size: 100.0,
-        color: "#00ff00".to_string(),
         file_path: Some("/node2.js".to_string()),
+        ..node("node2")
     };
     
     let node3 = GraphNode {
-        id: "node3".to_string(),
-        name: "node3".to_string(),
-        node_type: "file".to_string(),
         size: 100.0,
-        color: "#0000ff".to_string(),
         file_path: Some("/node3.js".to_string()),
+        ..node("node3")
     };
     
     let idx1 = graph.add_node(node1.clone());
@@ -249,25 +209,16 @@ async fn test_circular_dependency() {
     let idx3 = graph.add_node(node3.clone());
     
     // Create circular dependency
-    graph.add_edge(idx1, idx2, GraphEdge {
-        edge_type: "imports".to_string(),
-        weight: 1.0,
-    });
-    graph.add_edge(idx2, idx3, GraphEdge {
-        edge_type: "imports".to_string(),
-        weight: 1.0,
-    });
-    graph.add_edge(idx3, idx1, GraphEdge {
-        edge_type: "imports".to_string(),
-        weight: 1.0,
-    });
+    graph.add_edge(idx1, idx2, edge("imports"));
+    graph.add_edge(idx2, idx3, edge("imports"));
+    graph.add_edge(idx3, idx1, edge("imports"));
     
     let mut node_map = HashMap::new();
     node_map.insert("node1".to_string(), idx1);
     node_map.insert("node2".to_string(), idx2);
     node_map.insert("node3".to_string(), idx3);
     
-    let code_graph = CodeGraph { graph, node_map };
+    let code_graph = CodeGraph { graph, node_map, parallel_edges: Default::default() };
     
     let config = AnalyticsConfig::default();
     let result = analyze_graph(&code_graph, Some(config)).await;
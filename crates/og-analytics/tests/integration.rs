@@ -1,5 +1,6 @@
 use og_analytics::{analyze_graph, AnalyticsConfig, to_ui_metrics};
 use og_graph::graph::{CodeGraph, GraphNode, GraphEdge};
+use og_graph::testing::{edge, node};
 
 #[tokio::test]
 async fn test_analytics_engine() {
@@ -8,39 +9,33 @@ async fn test_analytics_engine() {
     
     // Add nodes
     let file1 = GraphNode {
-        id: "file1".to_string(),
         name: "main.rs".to_string(),
-        node_type: "file".to_string(),
         size: 100.0,
-        color: "#ff0000".to_string(),
         file_path: Some("/src/main.rs".to_string()),
+        ..node("file1")
     };
     
     let file2 = GraphNode {
-        id: "file2".to_string(),
         name: "lib.rs".to_string(),
-        node_type: "file".to_string(),
         size: 200.0,
-        color: "#00ff00".to_string(),
         file_path: Some("/src/lib.rs".to_string()),
+        ..node("file2")
     };
     
     let function1 = GraphNode {
-        id: "function1".to_string(),
         name: "main".to_string(),
         node_type: "function".to_string(),
         size: 50.0,
-        color: "#0000ff".to_string(),
         file_path: Some("/src/main.rs".to_string()),
+        ..node("function1")
     };
     
     let class1 = GraphNode {
-        id: "class1".to_string(),
         name: "MyClass".to_string(),
         node_type: "class".to_string(),
         size: 150.0,
-        color: "#ffff00".to_string(),
         file_path: Some("/src/lib.rs".to_string()),
+        ..node("class1")
     };
     
     // Add nodes to graph
@@ -50,24 +45,15 @@ async fn test_analytics_engine() {
     graph.add_node(class1);
     
     // Add edges
-    graph.add_edge("file1", "file2", GraphEdge {
-        edge_type: "imports".to_string(),
-        weight: 1.0,
-    });
+    graph.add_edge("file1", "file2", edge("imports"));
     
-    graph.add_edge("file1", "function1", GraphEdge {
-        edge_type: "contains".to_string(),
-        weight: 1.0,
-    });
+    graph.add_edge("file1", "function1", edge("contains"));
     
-    graph.add_edge("file2", "class1", GraphEdge {
-        edge_type: "contains".to_string(),
-        weight: 1.0,
-    });
+    graph.add_edge("file2", "class1", edge("contains"));
     
     graph.add_edge("function1", "class1", GraphEdge {
-        edge_type: "calls".to_string(),
         weight: 2.0,
+        ..edge("calls")
     });
     
     // Test analytics
@@ -108,22 +94,15 @@ async fn test_centrality_metrics() {
     // Create a star topology (node 0 connected to all others)
     for i in 0..5 {
         let node = GraphNode {
-            id: format!("node{}", i),
             name: format!("Node {}", i),
-            node_type: "file".to_string(),
-            size: 1.0,
-            color: "#000000".to_string(),
-            file_path: None,
+            ..node(&format!("node{}", i))
         };
         graph.add_node(node);
     }
     
     // Connect node0 to all others
     for i in 1..5 {
-        graph.add_edge(&format!("node0"), &format!("node{}", i), GraphEdge {
-            edge_type: "imports".to_string(),
-            weight: 1.0,
-        });
+        graph.add_edge(&format!("node0"), &format!("node{}", i), edge("imports"));
     }
     
     // Calculate centrality
@@ -137,6 +116,196 @@ async fn test_centrality_metrics() {
     println!("Centrality test passed!");
 }
 
+#[test]
+fn test_sampled_betweenness_is_reproducible() {
+    use og_analytics::metrics::{Metric, centrality::CentralityMetrics};
+
+    // A chain of 300 files is large enough that betweenness is sampled
+    let mut graph = CodeGraph::new();
+    for i in 0..300 {
+        graph.add_node(node(&format!("n{}", i)));
+        if i > 0 {
+            graph.add_edge(&format!("n{}", i - 1), &format!("n{}", i), edge("imports"));
+        }
+    }
+
+    let betweenness = |seed: u64| {
+        let results = CentralityMetrics::with_seed(seed).calculate(&graph).unwrap();
+        (0..300)
+            .map(|i| results.get_node_value(&format!("n{}", i), "betweenness").unwrap())
+            .collect::<Vec<f64>>()
+    };
+    assert_eq!(betweenness(7), betweenness(7));
+    assert_ne!(betweenness(7), betweenness(8));
+
+    assert_eq!(og_metrics_centrality::adaptive_sample_size(50, 1000), 50);
+    let small = og_metrics_centrality::adaptive_sample_size(1_000, 10_000);
+    let large = og_metrics_centrality::adaptive_sample_size(100_000, 10_000);
+    assert!(small < large && large < 1_000, "{} {}", small, large);
+    assert_eq!(og_metrics_centrality::adaptive_sample_size(100_000, 200), 200);
+}
+
+#[test]
+fn test_weighted_paths_in_closeness() {
+    use og_analytics::metrics::{Metric, centrality::CentralityMetrics};
+    use og_analytics::PathWeighting;
+
+    let mut graph = CodeGraph::new();
+    for id in ["a", "b", "c", "d"] {
+        graph.add_node(node(id));
+    }
+    graph.add_edge("a", "b", GraphEdge { weight: 4.0, ..edge("calls") });
+    graph.add_edge("c", "d", edge("contains"));
+
+    let closeness = |weighting: PathWeighting| {
+        let results = CentralityMetrics::new().with_path_weighting(weighting).calculate(&graph).unwrap();
+        (
+            results.get_node_value("a", "closeness").unwrap(),
+            results.get_node_value("c", "closeness").unwrap(),
+        )
+    };
+
+    // In hops both sources are one edge from their target
+    let (a, c) = closeness(PathWeighting::default());
+    assert_eq!(a, c);
+
+    // A heavy edge is short, and a costly edge type is long
+    let weighting = PathWeighting {
+        use_edge_weights: true,
+        type_costs: [("contains".to_string(), 2.0)].into_iter().collect(),
+    };
+    let edge = |edge_type: &str, weight: f64| GraphEdge { weight, ..edge(edge_type) };
+    assert_eq!(weighting.length(&edge("calls", 4.0)), 0.25);
+    assert_eq!(weighting.length(&edge("Contains", 1.0)), 2.0);
+    assert_eq!(weighting.length(&edge("calls", 0.0)), 1.0);
+
+    let (a, c) = closeness(weighting);
+    assert!(a > c, "{} {}", a, c);
+}
+
+#[tokio::test]
+async fn test_metric_analysis_modes() {
+    use og_graph::direction::AnalysisMode;
+
+    // node0 imports every other node
+    let mut graph = CodeGraph::new();
+    for i in 0..5 {
+        graph.add_node(GraphNode {
+            name: format!("Node {}", i),
+            ..node(&format!("node{}", i))
+        });
+    }
+    for i in 1..5 {
+        graph.add_edge("node0", &format!("node{}", i), edge("imports"));
+    }
+
+    let in_degree = |report: &og_analytics::AnalysisReport, id: &str| {
+        report
+            .metrics
+            .iter()
+            .find(|m| m.name == "centrality")
+            .and_then(|m| m.get_node_value(id, "in_degree"))
+            .unwrap()
+    };
+    let analyze = |mode: AnalysisMode| {
+        let config = AnalyticsConfig {
+            modes: [("centrality".to_string(), mode)].into_iter().collect(),
+            ..AnalyticsConfig::default()
+        };
+        analyze_graph(&graph, Some(config))
+    };
+
+    let directed = analyze(AnalysisMode::Directed).await.unwrap();
+    assert_eq!(in_degree(&directed, "node0"), 0.0);
+    assert!(in_degree(&directed, "node1") > 0.0);
+
+    let reverse = analyze(AnalysisMode::Reverse).await.unwrap();
+    assert_eq!(in_degree(&reverse, "node0"), 1.0);
+    assert_eq!(in_degree(&reverse, "node1"), 0.0);
+
+    let undirected = analyze(AnalysisMode::Undirected).await.unwrap();
+    assert_eq!(in_degree(&undirected, "node0"), 1.0);
+    assert!(in_degree(&undirected, "node1") > 0.0);
+}
+
+#[tokio::test]
+async fn test_metric_edge_filters() {
+    use og_analytics::{AnalyticsConfigV2, AnalyticsEngineV2};
+
+    // a.py calls itself and contains run, b.py imports a.py
+    let mut graph = CodeGraph::new();
+    for id in ["a.py", "b.py", "run"] {
+        graph.add_node(node(id));
+    }
+    for (source, target, edge_type) in [("a.py", "a.py", "calls"), ("a.py", "run", "contains"), ("b.py", "a.py", "imports")] {
+        graph.add_edge(source, target, edge(edge_type));
+    }
+    let excluded = [("centrality".to_string(), vec!["CONTAINS".to_string()])].into_iter().collect();
+
+    let degree = |report: &og_analytics::AnalysisReport, key: &str| {
+        report
+            .metrics
+            .iter()
+            .find(|m| m.name == "centrality")
+            .and_then(|m| m.get_node_value("a.py", key))
+            .unwrap()
+    };
+    let unfiltered = analyze_graph(&graph, None).await.unwrap();
+    assert_eq!((degree(&unfiltered, "in_degree"), degree(&unfiltered, "out_degree")), (1.0, 1.0));
+
+    let config = AnalyticsConfig {
+        drop_self_loops: true,
+        excluded_edge_types: excluded,
+        ..AnalyticsConfig::default()
+    };
+    let filtered = analyze_graph(&graph, Some(config.clone())).await.unwrap();
+    assert_eq!((degree(&filtered, "in_degree"), degree(&filtered, "out_degree")), (0.5, 0.0));
+
+    // The modular engine reads the same filtered graph
+    let modular = AnalyticsEngineV2::new(AnalyticsConfigV2 {
+        drop_self_loops: config.drop_self_loops,
+        excluded_edge_types: config.excluded_edge_types.clone(),
+        ..AnalyticsConfigV2::default()
+    })
+    .analyze(&graph)
+    .await
+    .unwrap();
+    assert_eq!(modular.centrality.degree["a.py"].out_degree, 0.0);
+    assert_eq!(modular.centrality.degree["run"].in_degree, 0.0);
+}
+
+#[tokio::test]
+async fn test_k_truss_and_cut_points() {
+    // Two import triangles joined by one edge, a leaf off the second and a lone file
+    let mut graph = CodeGraph::new();
+    for id in ["x1", "x2", "x3", "y1", "y2", "y3", "leaf", "lone"] {
+        graph.add_node(node(id));
+    }
+    for (source, target) in [
+        ("x1", "x2"), ("x2", "x3"), ("x3", "x1"),
+        ("y1", "y2"), ("y2", "y3"), ("y3", "y1"),
+        ("x1", "y1"), ("y3", "leaf"), ("leaf", "y3"),
+    ] {
+        graph.add_edge(source, target, edge("imports"));
+    }
+
+    let centrality = og_metrics_centrality::CentralityMetrics::new();
+    let k_truss = centrality.calculate_k_truss(&graph).unwrap();
+    assert_eq!((k_truss["x2"], k_truss["y1"], k_truss["leaf"], k_truss["lone"]), (3, 3, 2, 0));
+    let (articulation_points, bridges) = centrality.find_cut_points(&graph).unwrap();
+    assert_eq!(articulation_points, vec!["x1", "y1", "y3"]);
+    assert_eq!(
+        bridges,
+        vec![("leaf".to_string(), "y3".to_string()), ("x1".to_string(), "y1".to_string())]
+    );
+
+    let report = analyze_graph(&graph, None).await.unwrap();
+    let metrics = to_ui_metrics(&report, &graph);
+    let raw = |name: &str| &metrics.iter().find(|m| m.name == name).unwrap().raw;
+    assert!(raw("y3").articulation_point && !raw("y2").articulation_point);
+    assert_eq!((raw("x1").k_truss, raw("leaf").k_truss), (3, 2));
+}
+
 #[tokio::test]
 async fn test_community_detection() {
     use og_analytics::metrics::{Metric, community::CommunityDetection};
@@ -147,12 +316,8 @@ async fn test_community_detection() {
     // Community 1 nodes
     for i in 0..3 {
         let node = GraphNode {
-            id: format!("c1_node{}", i),
             name: format!("C1 Node {}", i),
-            node_type: "file".to_string(),
-            size: 1.0,
-            color: "#ff0000".to_string(),
-            file_path: None,
+            ..node(&format!("c1_node{}", i))
         };
         graph.add_node(node);
     }
@@ -160,48 +325,44 @@ async fn test_community_detection() {
     // Community 2 nodes
     for i in 0..3 {
         let node = GraphNode {
-            id: format!("c2_node{}", i),
             name: format!("C2 Node {}", i),
-            node_type: "file".to_string(),
-            size: 1.0,
-            color: "#00ff00".to_string(),
-            file_path: None,
+            ..node(&format!("c2_node{}", i))
         };
         graph.add_node(node);
     }
     
     // Connect within community 1
     graph.add_edge("c1_node0", "c1_node1", GraphEdge {
-        edge_type: "imports".to_string(),
         weight: 2.0,
+        ..edge("imports")
     });
     graph.add_edge("c1_node1", "c1_node2", GraphEdge {
-        edge_type: "imports".to_string(),
         weight: 2.0,
+        ..edge("imports")
     });
     graph.add_edge("c1_node2", "c1_node0", GraphEdge {
-        edge_type: "imports".to_string(),
         weight: 2.0,
+        ..edge("imports")
     });
     
     // Connect within community 2
     graph.add_edge("c2_node0", "c2_node1", GraphEdge {
-        edge_type: "imports".to_string(),
         weight: 2.0,
+        ..edge("imports")
     });
     graph.add_edge("c2_node1", "c2_node2", GraphEdge {
-        edge_type: "imports".to_string(),
         weight: 2.0,
+        ..edge("imports")
     });
     graph.add_edge("c2_node2", "c2_node0", GraphEdge {
-        edge_type: "imports".to_string(),
         weight: 2.0,
+        ..edge("imports")
     });
     
     // Weak connection between communities
     graph.add_edge("c1_node0", "c2_node0", GraphEdge {
-        edge_type: "imports".to_string(),
         weight: 0.1,
+        ..edge("imports")
     });
     
     // Detect communities
@@ -212,4 +373,1244 @@ async fn test_community_detection() {
     assert!(results.values.contains_key("num_communities"));
     
     println!("Community detection test passed!");
-}
\ No newline at end of file
+}
+#[test]
+fn test_community_detection_on_edge_type_projection() {
+    use og_analytics::metrics::{community::CommunityDetection, Metric, MetricValue};
+
+    // Two import cycles whose members are paired up by heavy contains edges
+    let mut graph = CodeGraph::new();
+    for group in ["a", "b"] {
+        for i in 0..3 {
+            graph.add_node(node(&format!("{}{}", group, i)));
+        }
+    }
+    for group in ["a", "b"] {
+        for i in 0..3 {
+            graph.add_edge(&format!("{}{}", group, i), &format!("{}{}", group, (i + 1) % 3), edge("IMPORTS"));
+        }
+    }
+    for i in 0..3 {
+        graph.add_edge(&format!("a{}", i), &format!("b{}", i), GraphEdge {
+            weight: 5.0,
+            ..edge("CONTAINS")
+        });
+    }
+
+    let results = CommunityDetection::new(1.0)
+        .with_edge_types(vec!["imports".to_string()])
+        .calculate(&graph)
+        .unwrap();
+    let community = |id: &str| match results.values.get(&format!("{}_community", id)) {
+        Some(MetricValue::Integer(community)) => *community,
+        other => panic!("no community for {}: {:?}", id, other),
+    };
+    // Without the contains edges nothing links the two cycles
+    for a in 0..3 {
+        for b in 0..3 {
+            assert_ne!(community(&format!("a{}", a)), community(&format!("b{}", b)));
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_analysis_records_metric_timings() {
+    let mut graph = CodeGraph::new();
+    for i in 0..4 {
+        graph.add_node(GraphNode {
+            name: format!("Node {}", i),
+            ..node(&format!("node{}", i))
+        });
+    }
+    for i in 0..3 {
+        graph.add_edge(&format!("node{}", i), &format!("node{}", i + 1), edge("imports"));
+    }
+
+    let report = analyze_graph(&graph, None).await.unwrap();
+
+    let centrality = report.timings.iter().find(|t| t.name == "centrality").unwrap();
+    let steps: Vec<&str> = centrality.steps.iter().map(|(name, _)| name.as_str()).collect();
+    assert!(steps.contains(&"betweenness"));
+    assert!(report.timings.iter().any(|t| t.name == "report"));
+}
+
+#[test]
+fn test_extract_module_candidates() {
+    use og_analytics::extract_module_candidates;
+
+    let node = |id: &str, node_type: &str| GraphNode {
+        node_type: node_type.to_string(),
+        file_path: Some("/src/big.py".to_string()),
+        ..node(id)
+    };
+    let edge = |edge_type: &str| edge(edge_type);
+
+    let mut graph = CodeGraph::new();
+    graph.add_node(node("big", "file"));
+    // Two call clusters joined by a single cross call
+    let members = ["parse", "tokenize", "lex", "render", "layout", "paint"];
+    for member in members {
+        graph.add_node(node(member, "function"));
+        graph.add_edge("big", member, edge("contains"));
+    }
+    for (a, b) in [("parse", "tokenize"), ("tokenize", "lex"), ("parse", "lex"),
+                   ("render", "layout"), ("layout", "paint"), ("render", "paint"),
+                   ("parse", "render")] {
+        graph.add_edge(a, b, edge("calls"));
+    }
+
+    let suggestions = extract_module_candidates(&graph, 6);
+    assert_eq!(suggestions.len(), 2, "{:?}", suggestions);
+    for suggestion in &suggestions {
+        assert_eq!(suggestion.container_id, "big");
+        assert_eq!(suggestion.member_ids.len(), 3);
+        assert_eq!(suggestion.internal_edges, 3);
+        assert_eq!(suggestion.cut_edges, 1);
+        assert!((suggestion.coupling_reduction - 0.75).abs() < 1e-9);
+    }
+
+    // Too few members to be worth splitting
+    assert!(extract_module_candidates(&graph, 7).is_empty());
+}
+
+#[test]
+fn test_architecture_report_renders_sections() {
+    use og_analytics::{ArchitectureReport, ReportFormat};
+    use og_types::Suppressions;
+    use std::collections::HashMap;
+
+    let mut graph = CodeGraph::new();
+    for (id, name) in [("a", "a.py"), ("b", "b.py"), ("c", "<c>.py")] {
+        graph.add_node(GraphNode {
+            name: name.to_string(),
+            file_path: Some(format!("/repo/{}", name)),
+            ..node(id)
+        });
+    }
+    for (source, target) in [("a", "b"), ("b", "a"), ("b", "c")] {
+        graph.add_edge(source, target, edge("imports"));
+    }
+
+    let report = ArchitectureReport::build("Review", &graph, &[], &Suppressions::default(), &HashMap::new(), &[], 10);
+    assert_eq!(report.file_count, 3);
+    assert_eq!(report.cycle_count, 1);
+    assert_eq!(report.cycles[0].len(), 2);
+    assert!(report.hotspots.is_empty());
+
+    let markdown = report.render(ReportFormat::from_path(std::path::Path::new("review.md")));
+    assert!(markdown.starts_with("# Review"));
+    assert!(markdown.contains("## Dependency cycles"));
+    assert!(markdown.contains("[a.py](file:///repo/a.py)"));
+
+    let html = report.render(ReportFormat::from_path(std::path::Path::new("review.html")));
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("&lt;c&gt;.py"));
+    assert!(!html.contains("<c>.py"));
+
+    // Silenced nodes, such as those in generated files, are not hotspots
+    let metrics = og_analytics::AnalysisReport::new(
+        vec![],
+        &og_analytics::MetricWeights::default(),
+        og_types::metrics::NormalizationMode::MinMax,
+        &graph,
+    )
+    .to_ui_metrics(&graph);
+    let mut suppressions = Suppressions::default();
+    suppressions.insert("a", og_types::ALL_FINDINGS);
+    let report = ArchitectureReport::build("Review", &graph, &metrics, &suppressions, &HashMap::new(), &[], 10);
+    let hotspots: Vec<&str> = report.hotspots.iter().map(|hotspot| hotspot.name.as_str()).collect();
+    assert_eq!(hotspots.len(), 2);
+    assert!(!hotspots.contains(&"a.py"));
+}
+
+#[test]
+fn test_natural_language_queries() {
+    use og_analytics::analysis::query::{QueryDirection, QueryMetric};
+    use og_analytics::GraphQuery;
+
+    let query = GraphQuery::parse_natural("Which files import the auth module?").unwrap();
+    assert_eq!(query.node_type.as_deref(), Some("file"));
+    let relation = query.relation.as_ref().unwrap();
+    assert_eq!(relation.direction, QueryDirection::To);
+    assert_eq!(relation.edge_type.as_deref(), Some("imports"));
+    assert_eq!(relation.other, "auth");
+
+    let query = GraphQuery::parse_natural("top 10 most complex functions").unwrap();
+    assert_eq!(query.node_type.as_deref(), Some("function"));
+    assert_eq!((query.sort_by, query.ascending, query.limit), (Some(QueryMetric::Complexity), false, 10));
+
+    let query = GraphQuery::parse_natural("functions called by main").unwrap();
+    assert_eq!(query.relation.unwrap().direction, QueryDirection::From);
+    assert!(GraphQuery::parse_natural("hello there").is_none());
+
+    let node = |id: &str, name: &str, node_type: &str, path: &str| GraphNode {
+        name: name.to_string(),
+        node_type: node_type.to_string(),
+        file_path: Some(path.to_string()),
+        ..node(id)
+    };
+    let edge = |edge_type: &str| edge(edge_type);
+
+    let mut graph = CodeGraph::new();
+    graph.add_node(node("auth", "index.ts", "file", "/repo/auth/index.ts"));
+    graph.add_node(node("login", "login", "function", "/repo/auth/index.ts"));
+    graph.add_node(node("app", "app.ts", "file", "/repo/app.ts"));
+    graph.add_node(node("start", "start", "function", "/repo/app.ts"));
+    graph.add_node(node("util", "util.ts", "file", "/repo/util.ts"));
+    graph.add_edge("auth", "login", edge("contains"));
+    graph.add_edge("app", "start", edge("contains"));
+    graph.add_edge("start", "auth", edge("imports"));
+    graph.add_edge("util", "app", edge("imports"));
+
+    // The import from a function inside app.ts counts for the file
+    let result = GraphQuery::parse_natural("which files import the auth module?").unwrap().answer(&graph, &[]);
+    let ids: Vec<&str> = result.rows.iter().map(|row| row.id.as_str()).collect();
+    assert_eq!(ids, vec!["app"]);
+    assert!(result.interpretation.contains("imports edges to \"auth\""));
+
+    let result = GraphQuery::parse_natural("most imported files").unwrap().answer(&graph, &[]);
+    assert_eq!(result.rows[0].value, Some(1.0));
+    assert_eq!(result.rows.len(), 3);
+}
+
+#[test]
+fn test_find_similar_nodes() {
+    use og_analytics::find_similar_nodes;
+
+    let node = |id: &str, node_type: &str| GraphNode {
+        node_type: node_type.to_string(),
+        file_path: Some(format!("/repo/{}", id)),
+        ..node(id)
+    };
+    let edge = |edge_type: &str| edge(edge_type);
+
+    // users.py and orders.py share a shape: two functions, both calling into db.py
+    let mut graph = CodeGraph::new();
+    for file in ["users.py", "orders.py", "db.py", "main.py"] {
+        graph.add_node(node(file, "file"));
+    }
+    for (file, function) in [("users.py", "get_user"), ("users.py", "save_user"),
+                             ("orders.py", "get_order"), ("orders.py", "save_order"),
+                             ("db.py", "query")] {
+        graph.add_node(node(function, "function"));
+        graph.add_edge(file, function, edge("contains"));
+    }
+    for caller in ["get_user", "save_user", "get_order", "save_order"] {
+        graph.add_edge(caller, "query", edge("calls"));
+    }
+    graph.add_edge("main.py", "users.py", edge("imports"));
+
+    let similar = find_similar_nodes(&graph, &[], "users.py", 2).unwrap();
+    assert_eq!(similar.len(), 2);
+    assert_eq!(similar[0].id, "orders.py");
+    assert!(similar[0].similarity > similar[1].similarity);
+    assert!(similar[0].neighbor_similarity > 0.8);
+    assert!(similar.iter().all(|s| s.node_type == "file" && s.metric_similarity.is_none()));
+
+    assert!(find_similar_nodes(&graph, &[], "missing", 5).is_none());
+}
+
+#[test]
+fn test_module_affinity_from_seeds() {
+    use og_analytics::module_affinity;
+
+    let node = |id: &str| GraphNode {
+        file_path: Some(format!("/repo/{}", id)),
+        ..node(id)
+    };
+    let imports = || edge("imports");
+
+    // The billing feature shares tax.py, while main.py links it to the users side
+    let mut graph = CodeGraph::new();
+    for file in ["invoice.py", "payment.py", "tax.py", "main.py", "users.py", "auth.py", "lone.py"] {
+        graph.add_node(node(file));
+    }
+    for (source, target) in [
+        ("invoice.py", "tax.py"),
+        ("payment.py", "tax.py"),
+        ("main.py", "invoice.py"),
+        ("main.py", "users.py"),
+        ("users.py", "auth.py"),
+    ] {
+        graph.add_edge(source, target, imports());
+    }
+
+    let seeds = vec!["invoice.py".to_string(), "payment.py".to_string()];
+    let ranked = module_affinity(&graph, &seeds, 10).unwrap();
+    let ids: Vec<&str> = ranked.iter().map(|node| node.id.as_str()).collect();
+    assert_eq!(ids, vec!["tax.py", "main.py", "users.py", "auth.py"]);
+    assert_eq!(ranked[0].affinity, 1.0);
+    assert!(ranked[0].lift > 1.0);
+    assert!(ranked[3].lift < 1.0);
+    assert!(ranked.windows(2).all(|pair| pair[0].score >= pair[1].score));
+
+    assert_eq!(module_affinity(&graph, &seeds, 1).unwrap().len(), 1);
+    assert!(module_affinity(&graph, &[], 10).is_none());
+    assert!(module_affinity(&graph, &["missing.py".to_string()], 10).is_none());
+}
+
+#[test]
+fn test_node_metrics_detail() {
+    use og_analytics::NodeMetricsDetail;
+    use og_types::Suppressions;
+    use std::collections::HashMap;
+
+    let node = |id: &str| GraphNode {
+        file_path: Some(format!("/repo/{}", id)),
+        ..node(id)
+    };
+    let edge = || edge("imports");
+
+    // hub.py sits between six importers and six imports, and in a cycle with s0.py
+    let mut graph = CodeGraph::new();
+    graph.add_node(node("hub.py"));
+    for i in 0..6 {
+        let (source, target) = (format!("s{}.py", i), format!("t{}.py", i));
+        graph.add_node(node(&source));
+        graph.add_node(node(&target));
+        graph.add_edge(&source, "hub.py", edge());
+        graph.add_edge("hub.py", &target, edge());
+    }
+    graph.add_edge("hub.py", "s0.py", edge());
+
+    let metrics = og_analytics::AnalysisReport::new(
+        vec![],
+        &og_analytics::MetricWeights::default(),
+        og_types::metrics::NormalizationMode::MinMax,
+        &graph,
+    )
+    .to_ui_metrics(&graph);
+    let detail = NodeMetricsDetail::build(&graph, &metrics, &Suppressions::default(), &HashMap::new(), "hub.py").unwrap();
+
+    assert_eq!(detail.node.name, "hub.py");
+    assert!(detail.metrics.is_some());
+    assert!(["risk", "indegree", "betweenness"].iter().all(|m| detail.percentiles.contains_key(*m)));
+    assert!(detail.percentiles.values().all(|p| (0.0..=100.0).contains(p)));
+    assert_eq!(detail.community.as_ref().map(|c| c.size), Some(metrics.len()));
+
+    let mut cycle: Vec<&str> = detail.cycle.iter().map(|n| n.id.as_str()).collect();
+    cycle.sort();
+    assert_eq!(cycle, vec!["hub.py", "s0.py"]);
+    assert!(detail.smells.is_empty());
+
+    let factors: Vec<&str> = detail.risk.factors.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(factors, vec!["complexity", "centrality", "bottleneck", "coupling"]);
+    assert_eq!(detail.risk.factors[2].value, 0.5);
+    let total: f64 = detail.risk.factors.iter().map(|f| f.contribution).sum();
+    assert!((total.min(1.0) - detail.risk.risk).abs() < 1e-9);
+
+    // Without an analysis the graph-derived parts are still there
+    let detail = NodeMetricsDetail::build(&graph, &[], &Suppressions::default(), &HashMap::new(), "t3.py").unwrap();
+    assert!(detail.metrics.is_none() && detail.percentiles.is_empty() && detail.community.is_none());
+    assert!(detail.cycle.is_empty());
+    assert!(NodeMetricsDetail::build(&graph, &[], &Suppressions::default(), &HashMap::new(), "missing").is_none());
+}
+
+#[test]
+fn test_findings_queue() {
+    use og_analytics::{list_findings, FindingFilter, FindingKind, FindingSort, Severity};
+    use og_types::Suppressions;
+    use std::collections::HashMap;
+
+    let node = |id: &str, node_type: &str| GraphNode {
+        node_type: node_type.to_string(),
+        file_path: Some(format!("/repo/{}", id)),
+        ..node(id)
+    };
+    let edge = |edge_type: &str| edge(edge_type);
+
+    // a.js and b.js import each other, and nothing imports util.js despite its export
+    let mut graph = CodeGraph::new();
+    for id in ["a.js", "b.js", "util.js"] {
+        graph.add_node(node(id, "file"));
+    }
+    graph.add_node(node("export:util.js:0", "export"));
+    graph.add_edge("util.js", "export:util.js:0", edge("CONTAINS"));
+    graph.add_edge("a.js", "b.js", edge("IMPORTS"));
+    graph.add_edge("b.js", "a.js", edge("IMPORTS"));
+
+    let findings = list_findings(&graph, &[], &Suppressions::default(), &HashMap::new(), FindingSort::Priority, &FindingFilter::default());
+    let kinds: Vec<FindingKind> = findings.iter().map(|f| f.kind).collect();
+    assert_eq!(kinds, vec![FindingKind::Cycle, FindingKind::UnusedExport]);
+    assert_eq!(findings[0].severity, Severity::Medium);
+    assert_eq!(findings[0].nodes.len(), 2);
+    assert_eq!(findings[1].nodes[0].id, "util.js");
+    assert_eq!(findings[0].fingerprint(""), "cycle:cycle:a.js,b.js");
+    assert_eq!(findings[1].fingerprint(""), "unused-export:unused-export:util.js");
+
+    let filter = FindingFilter {
+        min_severity: Some(Severity::Medium),
+        ..FindingFilter::default()
+    };
+    let findings = list_findings(&graph, &[], &Suppressions::default(), &HashMap::new(), FindingSort::Effort, &filter);
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].kind, FindingKind::Cycle);
+
+    let mut suppressions = Suppressions::default();
+    suppressions.insert("util.js", "unused-export");
+    suppressions.insert("b.js", "cycle");
+    assert!(list_findings(&graph, &[], &suppressions, &HashMap::new(), FindingSort::Priority, &FindingFilter::default()).is_empty());
+}
+
+#[test]
+fn test_function_shape_smells() {
+    use og_analytics::{list_findings, FindingFilter, FindingSort, NodeMetricsDetail};
+    use og_types::{FunctionMetrics, Suppressions};
+    use std::collections::HashMap;
+
+    let mut graph = CodeGraph::new();
+    for id in ["configure", "parse", "tidy"] {
+        graph.add_node(GraphNode {
+            node_type: "function".to_string(),
+            file_path: Some("/repo/app.py".to_string()),
+            ..node(id)
+        });
+    }
+    let shape = |parameters, max_nesting| FunctionMetrics {
+        length: 20,
+        parameters,
+        max_nesting,
+        returns: 1,
+        complexity: 7,
+    };
+    let functions = HashMap::from([
+        ("configure".to_string(), shape(12, 1)),
+        ("parse".to_string(), shape(2, 5)),
+        ("tidy".to_string(), shape(5, 4)),
+    ]);
+
+    let findings = list_findings(&graph, &[], &Suppressions::default(), &functions, FindingSort::Priority, &FindingFilter::default());
+    let found: Vec<(&str, &str)> = findings
+        .iter()
+        .map(|f| (f.nodes[0].id.as_str(), f.title.as_str()))
+        .collect();
+    assert_eq!(found, vec![("configure", "Long Parameter List"), ("parse", "Deep Nesting")]);
+    assert_eq!(findings[0].severity, og_analytics::Severity::High);
+
+    let detail = NodeMetricsDetail::build(&graph, &[], &Suppressions::default(), &functions, "tidy").unwrap();
+    assert_eq!(detail.function, Some(shape(5, 4)));
+
+    let analyzer = og_metrics_quality::QualityAnalyzer {
+        function_metrics: functions.clone(),
+        ..og_metrics_quality::QualityAnalyzer::default()
+    };
+    let complexity = analyzer.analyze_quality(&graph).unwrap().complexity_metrics;
+    assert_eq!(complexity["tidy"].cyclomatic_complexity, 7.0, "parsed complexity wins over the degree estimate");
+    assert!(detail.smells.is_empty());
+
+    let mut suppressions = Suppressions::default();
+    suppressions.insert("parse", "deep-nesting");
+    assert_eq!(list_findings(&graph, &[], &suppressions, &functions, FindingSort::Priority, &FindingFilter::default()).len(), 1);
+}
+
+#[tokio::test]
+async fn test_engines_share_metric_implementations() {
+    use og_analytics::{analyze_graph_modular, AnalyticsConfigV2};
+    use og_types::FunctionMetrics;
+    use std::collections::HashMap;
+
+    let mut graph = CodeGraph::new();
+    for (id, node_type) in [("app.py", "file"), ("main", "function"), ("load", "function"), ("save", "function")] {
+        graph.add_node(GraphNode {
+            node_type: node_type.to_string(),
+            file_path: Some("/repo/app.py".to_string()),
+            ..node(id)
+        });
+    }
+    let edge = |edge_type: &str| edge(edge_type);
+    for function in ["main", "load", "save"] {
+        graph.add_edge("app.py", function, edge("contains"));
+    }
+    graph.add_edge("main", "load", edge("calls"));
+    graph.add_edge("main", "save", edge("calls"));
+
+    let shape = |complexity| FunctionMetrics { length: 10, parameters: 1, max_nesting: 1, returns: 1, complexity };
+    let function_metrics = HashMap::from([("main".to_string(), shape(9)), ("load".to_string(), shape(4))]);
+
+    let legacy = analyze_graph(
+        &graph,
+        Some(AnalyticsConfig { function_metrics: function_metrics.clone(), ..AnalyticsConfig::default() }),
+    )
+    .await
+    .unwrap();
+    let modular = analyze_graph_modular(
+        &graph,
+        Some(AnalyticsConfigV2 { function_metrics, ..AnalyticsConfigV2::default() }),
+    )
+    .await
+    .unwrap();
+
+    let legacy_value = |metric: &str, node: &str| {
+        legacy.metrics.iter().find_map(|result| result.get_node_value(node, metric)).unwrap()
+    };
+    for id in ["app.py", "main", "load", "save"] {
+        assert_eq!(legacy_value("cyclomatic_complexity", id), modular.quality.complexity_metrics[id].cyclomatic_complexity);
+        assert_eq!(legacy_value("risk", id), modular.risk.risk_scores[id].overall);
+        assert_eq!(legacy_value("betweenness", id), modular.centrality.betweenness[id]);
+    }
+    assert_eq!(legacy_value("cyclomatic_complexity", "main"), 9.0);
+
+    let ui = to_ui_metrics(&legacy, &graph);
+    let main = ui.iter().find(|m| m.name == "main").unwrap();
+    assert_eq!(main.raw.complexity, 9, "the UI reads parsed complexity too");
+}
+
+#[test]
+fn test_alert_rules() {
+    use og_analytics::{dependency_cycles, AlertKind, AlertRules};
+
+    let node = |id: &str| GraphNode {
+        file_path: Some(format!("/repo/{}", id)),
+        ..node(id)
+    };
+    let edge = || edge("imports");
+
+    let mut graph = CodeGraph::new();
+    for id in ["a.py", "b.py", "c.py", "d.py"] {
+        graph.add_node(node(id));
+    }
+    graph.add_edge("a.py", "b.py", edge());
+    graph.add_edge("b.py", "a.py", edge());
+    graph.add_edge("c.py", "d.py", edge());
+    graph.add_edge("d.py", "c.py", edge());
+    let cycles = dependency_cycles(&graph);
+    assert_eq!(cycles.len(), 2);
+
+    let mut metrics = og_analytics::AnalysisReport::new(
+        vec![],
+        &og_analytics::MetricWeights::default(),
+        og_types::metrics::NormalizationMode::MinMax,
+        &graph,
+    )
+    .to_ui_metrics(&graph);
+    metrics[0].risk = 0.9;
+
+    assert!(AlertRules::default().evaluate(&metrics, &cycles, Some(&[])).is_empty());
+
+    let rules = AlertRules {
+        max_risk: Some(0.85),
+        max_cycles: Some(1),
+        new_cycles: true,
+        ..AlertRules::default()
+    };
+    let previous = vec![cycles[0].clone()];
+    let alerts = rules.evaluate(&metrics, &cycles, Some(&previous));
+    let kinds: Vec<AlertKind> = alerts.iter().map(|a| a.kind).collect();
+    assert_eq!(kinds, vec![AlertKind::Risk, AlertKind::Cycles, AlertKind::NewCycle]);
+    assert_eq!(alerts[0].nodes, vec![metrics[0].path.clone()]);
+    assert_eq!(alerts[2].nodes, cycles[1]);
+
+    // Without a previous analysis no cycle counts as new
+    let alerts = rules.evaluate(&metrics, &cycles, None);
+    assert!(alerts.iter().all(|a| a.kind != AlertKind::NewCycle));
+}
+
+#[test]
+fn test_recolor_by_metric() {
+    use og_analytics::analysis::coloring::{node_values, MISSING_COLOR};
+    use og_analytics::{recolor, ColorScale, Palette};
+    use std::collections::BTreeMap;
+
+    let mut graph = CodeGraph::new();
+    for id in ["a.py", "b.py", "c.py", "d.py"] {
+        graph.add_node(GraphNode {
+            file_path: Some(format!("/repo/{}", id)),
+            ..node(id)
+        });
+    }
+    let mut metrics = og_analytics::AnalysisReport::new(
+        vec![],
+        &og_analytics::MetricWeights::default(),
+        og_types::metrics::NormalizationMode::MinMax,
+        &graph,
+    )
+    .to_ui_metrics(&graph);
+    metrics.retain(|m| m.name != "d.py");
+    for (m, complexity) in metrics.iter_mut().zip([1, 5, 9]) {
+        m.raw.complexity = complexity;
+    }
+
+    let values = node_values(&graph, &metrics, |m| Some(m.raw.complexity as f64));
+    assert_eq!(values.len(), 3);
+    let map = recolor(&graph, "complexity", &values, Palette::Heatmap, ColorScale::Linear);
+    assert_eq!(map.colors.len(), 4);
+    assert_eq!(map.colors["d.py"], MISSING_COLOR);
+    let lowest = values.iter().find(|(_, v)| **v == 1.0).unwrap().0;
+    let highest = values.iter().find(|(_, v)| **v == 9.0).unwrap().0;
+    assert_eq!(map.colors[lowest], "#ffffcc");
+    assert_eq!(map.colors[highest], "#bd0026");
+    let labels: Vec<&str> = map.legend.iter().map(|e| e.label.as_str()).collect();
+    assert_eq!(labels, vec!["1", "5", "9"]);
+
+    // Zero is white on a diverging palette however lopsided the range
+    let deltas: BTreeMap<String, f64> = [("a.py", -0.1), ("b.py", 0.0), ("c.py", 0.4)]
+        .into_iter()
+        .map(|(id, v)| (id.to_string(), v))
+        .collect();
+    let map = recolor(&graph, "risk_delta", &deltas, Palette::Diverging, ColorScale::Linear);
+    assert_eq!(map.colors["b.py"], "#f7f7f7");
+    assert_eq!(map.colors["c.py"], "#b2182b");
+
+    let communities: BTreeMap<String, f64> = [("a.py", 3.0), ("b.py", 3.0), ("c.py", 7.0)]
+        .into_iter()
+        .map(|(id, v)| (id.to_string(), v))
+        .collect();
+    let map = recolor(&graph, "community", &communities, Palette::Categorical, ColorScale::Linear);
+    assert_eq!(map.colors["a.py"], map.colors["b.py"]);
+    assert_ne!(map.colors["a.py"], map.colors["c.py"]);
+    assert_eq!(map.legend.len(), 2);
+}
+
+#[test]
+fn test_resize_by_metric() {
+    use og_analytics::analysis::sizing::{size_values, MAX_NODE_SIZE, MIN_NODE_SIZE};
+    use og_analytics::{resize, SizeMetric, SizeScale};
+    use og_graph::attributes::LOC_ATTRIBUTE;
+
+    let mut graph = CodeGraph::new();
+    for i in 0..21 {
+        graph.add_node(GraphNode {
+            name: format!("f{}.py", i),
+            file_path: Some(format!("/repo/f{}.py", i)),
+            ..node(&format!("f{}", i))
+        });
+    }
+    graph.add_edge("f0", "f1", edge("imports"));
+    graph.add_edge("f0", "f1", edge("calls"));
+    graph.add_edge("f2", "f1", edge("imports"));
+    graph.add_edge("f3", "f1", edge("contains"));
+
+    let fan_in = size_values(&graph, &[], SizeMetric::FanIn);
+    assert_eq!(fan_in["f1"], 2.0);
+    assert_eq!(fan_in["f0"], 0.0);
+
+    // One huge file is capped so the others keep a spread of sizes
+    for i in 0..21usize {
+        let idx = graph.node_map[&format!("f{}", i)];
+        graph.graph[idx].set_attribute(LOC_ATTRIBUTE, if i == 0 { 10_000 } else { i });
+    }
+    let loc = size_values(&graph, &[], SizeMetric::Loc);
+    let sizes = resize(&graph, &loc, SizeMetric::Loc, SizeScale::Linear).sizes;
+    assert_eq!(sizes.len(), 21);
+    assert_eq!(sizes["f1"], MIN_NODE_SIZE);
+    assert_eq!(sizes["f20"], MAX_NODE_SIZE);
+    assert_eq!(sizes["f0"], MAX_NODE_SIZE);
+    assert!(sizes["f10"] > MIN_NODE_SIZE && sizes["f10"] < MAX_NODE_SIZE);
+
+    // Log scaling grows small values faster
+    let logged = resize(&graph, &loc, SizeMetric::Loc, SizeScale::Log).sizes;
+    assert!(logged["f5"] > sizes["f5"]);
+
+    // Nodes without a value, or all with one value, get the smallest size
+    let idx = graph.node_map["f3"];
+    graph.graph[idx].remove_attribute(LOC_ATTRIBUTE);
+    let sizes = resize(&graph, &size_values(&graph, &[], SizeMetric::Loc), SizeMetric::Loc, SizeScale::Log).sizes;
+    assert_eq!(sizes["f3"], MIN_NODE_SIZE);
+    let pagerank = size_values(&graph, &[], SizeMetric::Pagerank);
+    assert!(pagerank.is_empty());
+    let sizes = resize(&graph, &pagerank, SizeMetric::Pagerank, SizeScale::Log).sizes;
+    assert!(sizes.values().all(|size| *size == MIN_NODE_SIZE));
+}
+
+#[test]
+fn test_treemap_rolls_up_folders() {
+    use og_analytics::analysis::treemap::build_treemap;
+    use og_analytics::TreemapMetric;
+    use og_graph::attributes::{CHURN_ATTRIBUTE, LOC_ATTRIBUTE};
+
+    let mut graph = CodeGraph::new();
+    for (id, node_type, path) in [
+        ("src/a.py", "file", "/repo/src/a.py"),
+        ("src/a.py:run", "function", "/repo/src/a.py"),
+        ("src/a.py:stop", "function", "/repo/src/a.py"),
+        ("src/util/b.py", "file", "/repo/src/util/b.py"),
+        ("main.py", "file", "/repo/main.py"),
+    ] {
+        graph.add_node(GraphNode {
+            node_type: node_type.to_string(),
+            file_path: Some(path.to_string()),
+            ..node(id)
+        });
+    }
+
+    for (id, lines) in [("src/a.py", 30usize), ("src/a.py:run", 10), ("src/util/b.py", 50)] {
+        let idx = graph.node_map[id];
+        graph.graph[idx].set_attribute(LOC_ATTRIBUTE, lines);
+    }
+    let idx = graph.node_map["main.py"];
+    graph.graph[idx].set_attribute(CHURN_ATTRIBUTE, 12i64);
+    let treemap = build_treemap(&graph, &[], "/repo", TreemapMetric::Loc);
+    assert_eq!(treemap.root.name, "repo");
+    assert_eq!(treemap.root.value, 80.0);
+    let names: Vec<&str> = treemap.root.children.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, vec!["src", "main.py"]);
+    let src = &treemap.root.children[0];
+    assert_eq!((src.path.as_str(), src.value), ("src", 80.0));
+    assert_eq!(src.children[0].path, "src/util");
+    assert_eq!(src.children[1].value, 30.0);
+
+    let mut metrics = og_analytics::AnalysisReport::new(
+        vec![],
+        &og_analytics::MetricWeights::default(),
+        og_types::metrics::NormalizationMode::MinMax,
+        &graph,
+    )
+    .to_ui_metrics(&graph);
+    let churn = build_treemap(&graph, &metrics, "/repo", TreemapMetric::Churn);
+    assert_eq!(churn.root.value, 12.0, "churn is read from node attributes");
+    for m in &mut metrics {
+        (m.raw.complexity, m.risk) = match m.name.as_str() {
+            "src/a.py:run" => (4, 0.9),
+            "src/a.py:stop" => (3, 0.2),
+            "main.py" => (1, 0.5),
+            _ => (0, 0.0),
+        };
+    }
+    let complexity = build_treemap(&graph, &metrics, "/repo", TreemapMetric::Complexity);
+    assert_eq!(complexity.root.value, 8.0);
+    let risk = build_treemap(&graph, &metrics, "/repo", TreemapMetric::Risk);
+    assert!((risk.root.children[0].value - 0.9).abs() < 1e-6);
+    assert!((risk.root.value - 1.4).abs() < 1e-6);
+
+    let json = serde_json::to_value(&risk).unwrap();
+    assert_eq!(json["metric"], "risk");
+    assert!(json["root"]["children"][1].get("children").is_none());
+}
+
+#[test]
+fn test_metric_values_for_selected_nodes() {
+    use og_analytics::metric_values;
+
+    let mut graph = CodeGraph::new();
+    for id in ["a", "b", "c"] {
+        graph.add_node(GraphNode {
+            name: format!("{}.py", id),
+            file_path: Some(format!("/repo/{}.py", id)),
+            ..node(id)
+        });
+    }
+    graph.add_edge("a", "b", edge("imports"));
+    graph.add_edge("c", "b", edge("imports"));
+    let mut metrics = og_analytics::AnalysisReport::new(
+        vec![],
+        &og_analytics::MetricWeights::default(),
+        og_types::metrics::NormalizationMode::MinMax,
+        &graph,
+    )
+    .to_ui_metrics(&graph);
+    metrics.iter_mut().find(|m| m.name == "b.py").unwrap().raw.indegree = 2;
+
+    let ids = vec!["b".to_string(), "missing".to_string(), "a".to_string()];
+    let fields = vec!["indegree".to_string(), "pagerank_calls".to_string()];
+    let rows = metric_values(&graph, &metrics, &ids, &fields);
+    assert_eq!(rows.iter().map(|row| row.id.as_str()).collect::<Vec<_>>(), vec!["b", "a"]);
+    assert_eq!(rows[0].values.len(), 2);
+    assert_eq!(rows[0].values["indegree"], Some(2.0));
+    assert_eq!(rows[1].values["indegree"], Some(0.0));
+    assert_eq!(rows[0].values["pagerank_calls"], None);
+
+    let all = metric_values(&graph, &metrics, &ids[..1], &[]);
+    assert_eq!(all[0].values.len(), og_analytics::analysis::distribution::metric_names().count());
+    assert!(metric_values(&graph, &[], &ids, &fields).is_empty());
+}
+
+#[test]
+fn test_list_nodes_pages_sorted_rows() {
+    use og_analytics::{list_nodes, NodeFilter, SortOrder};
+
+    let mut graph = CodeGraph::new();
+    for id in ["a", "b", "c", "d"] {
+        graph.add_node(GraphNode {
+            name: format!("{}.py", id),
+            file_path: Some(format!("/repo/{}.py", id)),
+            ..node(id)
+        });
+    }
+    let mut metrics = og_analytics::AnalysisReport::new(
+        vec![],
+        &og_analytics::MetricWeights::default(),
+        og_types::metrics::NormalizationMode::MinMax,
+        &graph,
+    )
+    .to_ui_metrics(&graph);
+    for (name, churn) in [("a.py", 5), ("b.py", 9), ("c.py", 1)] {
+        metrics.iter_mut().find(|m| m.name == name).unwrap().raw.churn = churn;
+    }
+    // d.py has no metrics, so it sorts last whichever way
+    metrics.retain(|m| m.name != "d.py");
+    let data = graph.to_frontend_format();
+    let columns = vec!["churn".to_string()];
+
+    let ids = |page: &og_analytics::NodePage| page.rows.iter().map(|row| row.id.clone()).collect::<Vec<_>>();
+    let page = list_nodes(&data, &metrics, &NodeFilter::default(), "churn", SortOrder::Desc, &columns, 0..2).unwrap();
+    assert_eq!((ids(&page), page.total), (vec!["b".to_string(), "a".to_string()], 4));
+    assert_eq!(page.rows[0].values["churn"], Some(9.0));
+    let page = list_nodes(&data, &metrics, &NodeFilter::default(), "churn", SortOrder::Asc, &columns, 2..4).unwrap();
+    assert_eq!(ids(&page), vec!["b", "d"]);
+    assert_eq!(page.rows[1].values["churn"], None);
+
+    let filter = NodeFilter {
+        search: Some("A.PY".to_string()),
+        ..NodeFilter::default()
+    };
+    let page = list_nodes(&data, &metrics, &filter, "name", SortOrder::Asc, &columns, 0..10).unwrap();
+    assert_eq!(ids(&page), vec!["a"]);
+    let filter = NodeFilter {
+        min: [("churn".to_string(), 2.0)].into(),
+        ..NodeFilter::default()
+    };
+    let page = list_nodes(&data, &metrics, &filter, "path", SortOrder::Desc, &columns, 0..10).unwrap();
+    assert_eq!(ids(&page), vec!["b", "a"]);
+    assert!(list_nodes(&data, &metrics, &NodeFilter::default(), "name", SortOrder::Asc, &columns, 9..12)
+        .unwrap()
+        .rows
+        .is_empty());
+
+    let unknown = list_nodes(&data, &metrics, &NodeFilter::default(), "bogus", SortOrder::Asc, &[], 0..1);
+    assert_eq!(unknown.unwrap_err().0, "bogus");
+}
+
+#[test]
+fn test_metrics_table_exports() {
+    use og_analytics::{MetricsTable, TableFormat};
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+
+    let mut graph = CodeGraph::new();
+    for name in ["a.py", "b,\"quoted\".py"] {
+        graph.add_node(GraphNode {
+            file_path: Some(format!("/repo/{}", name)),
+            ..node(name)
+        });
+    }
+    let metrics = og_analytics::AnalysisReport::new(
+        vec![],
+        &og_analytics::MetricWeights::default(),
+        og_types::metrics::NormalizationMode::MinMax,
+        &graph,
+    )
+    .to_ui_metrics(&graph);
+    let table = MetricsTable::new(&metrics);
+    let columns = table.headers().count();
+
+    let mut csv = Vec::new();
+    table.write_csv(&mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].starts_with("path,name,node_type,community,generated,importance,risk"));
+    assert!(csv.contains("\"/repo/b,\"\"quoted\"\".py\""));
+    // pagerank_calls is missing, so it is left empty rather than zero
+    let pagerank_calls = table.headers().position(|h| h == "pagerank_calls").unwrap();
+    assert!(lines[1].split(',').nth(pagerank_calls).unwrap().is_empty());
+
+    let path = std::env::temp_dir().join(format!("omnigraph-metrics-{}.parquet", std::process::id()));
+    table.write(&path, TableFormat::Parquet).unwrap();
+    let reader = SerializedFileReader::new(std::fs::File::open(&path).unwrap()).unwrap();
+    let metadata = reader.metadata().file_metadata();
+    assert_eq!(metadata.num_rows(), 2);
+    assert_eq!(metadata.schema_descr().num_columns(), columns);
+    assert_eq!(metadata.schema_descr().column(3).name(), "community");
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_normalization_modes() {
+    use og_analytics::{AnalysisReport, MetricResults, MetricValue, MetricWeights};
+    use og_types::metrics::NormalizationMode;
+
+    // One outlier squeezes every other complexity into the bottom of a min-max scale
+    let mut graph = CodeGraph::new();
+    let mut quality = MetricResults::new("quality".to_string());
+    for (i, complexity) in [1, 2, 3, 4, 100].into_iter().enumerate() {
+        let id = format!("f{}", i);
+        graph.add_node(GraphNode {
+            node_type: "function".to_string(),
+            file_path: Some(format!("/src/{}.py", id)),
+            ..node(&id)
+        });
+        quality.add_value(format!("{}_cyclomatic_complexity", id), MetricValue::Integer(complexity));
+    }
+
+    let normalized = |mode: NormalizationMode| {
+        let report = AnalysisReport::new(vec![quality.clone()], &MetricWeights::default(), mode, &graph);
+        assert_eq!(report.normalization_ranges.mode, mode);
+        let mut values: Vec<f64> = report.to_ui_metrics(&graph).iter().map(|m| m.normalized.complexity).collect();
+        values.sort_by(f64::total_cmp);
+        values
+    };
+
+    let min_max = normalized(NormalizationMode::MinMax);
+    assert!(min_max[3] < 0.05);
+    assert_eq!(min_max[4], 1.0);
+
+    assert_eq!(normalized(NormalizationMode::Percentile), vec![0.0, 0.2, 0.4, 0.6, 0.8]);
+
+    let z_score = normalized(NormalizationMode::ZScore);
+    assert!(z_score.iter().all(|v| (0.0..=1.0).contains(v)));
+    assert!(z_score[0] < 0.5 && z_score[4] > 0.5);
+    assert!(z_score[3] - z_score[0] < 0.1);
+}
+
+#[test]
+fn test_metric_distributions() {
+    use og_analytics::{metric_distributions, AnalysisReport, MetricResults, MetricValue, MetricWeights};
+    use og_types::metrics::NormalizationMode;
+
+    let mut graph = CodeGraph::new();
+    let mut quality = MetricResults::new("quality".to_string());
+    for (i, complexity) in [1, 2, 3, 4, 10].into_iter().enumerate() {
+        let id = format!("f{}", i);
+        graph.add_node(GraphNode {
+            node_type: "function".to_string(),
+            file_path: Some(format!("/src/{}.py", id)),
+            ..node(&id)
+        });
+        quality.add_value(format!("{}_cyclomatic_complexity", id), MetricValue::Integer(complexity));
+    }
+
+    let report = AnalysisReport::new(vec![quality], &MetricWeights::default(), NormalizationMode::MinMax, &graph);
+    let distributions = metric_distributions(&report.to_ui_metrics(&graph), 3);
+    let complexity = distributions.iter().find(|d| d.metric == "complexity").unwrap();
+
+    assert_eq!(complexity.count, 5);
+    assert_eq!((complexity.min, complexity.max), (1.0, 10.0));
+    assert_eq!((complexity.q1, complexity.median, complexity.q3), (2.0, 3.0, 4.0));
+    assert_eq!(complexity.bins.iter().map(|b| b.count).collect::<Vec<_>>(), vec![3, 1, 1]);
+    assert_eq!(complexity.bins[2].end, 10.0);
+
+    // Constant metrics collapse into one bin
+    let churn = distributions.iter().find(|d| d.metric == "churn").unwrap();
+    assert_eq!(churn.bins.len(), 1);
+    assert_eq!(churn.bins[0].count, 5);
+}
+
+#[test]
+fn test_pr_summary_compares_checkouts() {
+    use og_analytics::analysis::pr_summary::AnalysisSide;
+    use og_analytics::{AnalysisReport, MetricResults, MetricValue, MetricWeights, PrSummary};
+    use og_types::metrics::NormalizationMode;
+
+    // The same two files checked out under different roots, with b.py growing an import back to a.py
+    let checkout = |root: &str, complexity_b: i64, cycle: bool| {
+        let mut graph = CodeGraph::new();
+        let mut quality = MetricResults::new("quality".to_string());
+        for (name, complexity) in [("a", 2), ("b", complexity_b)] {
+            let id = format!("{}/{}.py", root, name);
+            graph.add_node(GraphNode {
+                name: format!("{}.py", name),
+                ..node(&id)
+            });
+            quality.add_value(format!("{}_cyclomatic_complexity", id), MetricValue::Integer(complexity));
+        }
+        let import = |source: &str, target: &str| (format!("{}/{}.py", root, source), format!("{}/{}.py", root, target));
+        let mut edges = vec![import("a", "b")];
+        if cycle {
+            edges.push(import("b", "a"));
+        }
+        for (source, target) in edges {
+            graph.add_edge(&source, &target, edge("imports"));
+        }
+        let metrics = AnalysisReport::new(vec![quality], &MetricWeights::default(), NormalizationMode::MinMax, &graph)
+            .to_ui_metrics(&graph);
+        (graph, metrics)
+    };
+
+    let (base_graph, base_metrics) = checkout("/base", 2, false);
+    let (head_graph, head_metrics) = checkout("/head", 9, true);
+    let summary = PrSummary::build(
+        &AnalysisSide { root: "/base", graph: &base_graph, metrics: &base_metrics },
+        &AnalysisSide { root: "/head", graph: &head_graph, metrics: &head_metrics },
+        10,
+    );
+
+    assert_eq!(summary.new_cycles, vec![vec!["a.py".to_string(), "b.py".to_string()]]);
+    assert_eq!(summary.resolved_cycles, 0);
+    let b = summary.files.iter().find(|f| f.path == "b.py").unwrap();
+    assert_eq!((b.complexity_before, b.complexity_after), (Some(2), Some(9)));
+
+    let markdown = summary.to_markdown();
+    assert!(markdown.contains("1 new dependency cycle"));
+    assert!(markdown.contains("| `b.py` |"));
+}
+
+#[test]
+fn test_structural_diff_between_checkouts() {
+    use og_analytics::analysis::pr_summary::AnalysisSide;
+    use og_analytics::analysis::structural_diff::ModuleDependency;
+    use og_analytics::StructuralDiff;
+
+    let checkout = |root: &str, files: &[&str], imports: &[(&str, &str)]| {
+        let mut graph = CodeGraph::new();
+        for file in files {
+            let id = format!("{}/{}", root, file);
+            graph.add_node(GraphNode {
+                name: file.to_string(),
+                ..node(&id)
+            });
+        }
+        for (source, target) in imports {
+            graph.add_edge(
+                &format!("{}/{}", root, source),
+                &format!("{}/{}", root, target),
+                edge("imports"),
+            );
+        }
+        graph
+    };
+
+    let before = checkout("/a", &["api/x.py", "db/y.py", "old/z.py"], &[("api/x.py", "db/y.py"), ("old/z.py", "db/y.py")]);
+    let after = checkout(
+        "/b",
+        &["api/x.py", "api/w.py", "db/y.py", "cli/main.py"],
+        &[("api/x.py", "db/y.py"), ("api/w.py", "db/y.py"), ("cli/main.py", "api/x.py")],
+    );
+    let diff = StructuralDiff::build(
+        &AnalysisSide { root: "/a", graph: &before, metrics: &[] },
+        &AnalysisSide { root: "/b", graph: &after, metrics: &[] },
+        10,
+    );
+
+    assert_eq!(diff.added_modules, vec!["cli".to_string()]);
+    assert_eq!(diff.removed_modules, vec!["old".to_string()]);
+    assert_eq!(
+        diff.new_dependencies,
+        vec![ModuleDependency { from: "cli".to_string(), to: "api".to_string(), edges: 1 }]
+    );
+    assert_eq!(diff.removed_dependencies.len(), 1);
+    assert!(diff.changes.new_cycles.is_empty());
+    assert!(!diff.is_empty());
+
+    let markdown = diff.to_markdown();
+    assert!(markdown.contains("**Added modules (1):**"));
+    assert!(markdown.contains("- `cli` → `api`, 1 edge(s)"));
+    assert!(markdown.contains("No new dependency cycles."));
+
+    let same = StructuralDiff::build(
+        &AnalysisSide { root: "/a", graph: &before, metrics: &[] },
+        &AnalysisSide { root: "/a", graph: &before, metrics: &[] },
+        10,
+    );
+    assert!(same.is_empty());
+}
+
+#[test]
+fn test_evolution_timeline_tracks_modules() {
+    use og_analytics::analysis::evolution::{file_graph, EvolutionTimeline, HistorySnapshot, ModuleChange};
+
+    // Functions in files under /repo, linked by a call across files
+    let snapshot = |commit: &str, files: &[&str]| {
+        let mut graph = CodeGraph::new();
+        for file in files {
+            for name in ["file", "func"] {
+                graph.add_node(GraphNode {
+                    name: name.to_string(),
+                    node_type: name.to_string(),
+                    file_path: Some(format!("/repo/{}", file)),
+                    ..node(&format!("{}:{}", name, file))
+                });
+            }
+        }
+        graph.add_edge(&format!("func:{}", files[0]), &format!("func:{}", files[1]), edge("calls"));
+        HistorySnapshot {
+            commit: commit.to_string(),
+            committed_at: commit.len() as u64,
+            graph: file_graph(&graph, "/repo"),
+        }
+    };
+
+    let snapshots = [
+        snapshot("c1", &["main.py", "core/a.py", "core/b.py", "core/c.py", "core/d.py"]),
+        snapshot("c22", &["main.py", "core/a.py", "core/b.py", "core/c.py", "core/d.py", "core/e.py", "core/f.py"]),
+        snapshot("c333", &["main.py", "core/io/a.py", "core/io/b.py", "core/net/c.py", "core/net/d.py", "core/e.py", "core/f.py"]),
+        snapshot("c4444", &["main.py", "core/io/a.py", "core/io/b.py", "core/net/c.py", "core/net/d.py"]),
+    ];
+    assert_eq!(snapshots[0].graph.graph.node_count(), 5);
+    assert_eq!(snapshots[0].graph.graph.edge_count(), 1);
+    assert_eq!(snapshots[0].graph.graph[snapshots[0].graph.node_map["main.py"]].size, 2.0);
+
+    let timeline = EvolutionTimeline::build(&snapshots, 0.25);
+    let events: Vec<(&str, &str, &ModuleChange)> = timeline
+        .events
+        .iter()
+        .map(|event| (event.commit.as_str(), event.module.as_str(), &event.change))
+        .collect();
+    assert_eq!(
+        events,
+        vec![
+            ("c1", ".", &ModuleChange::Appeared { files: 1 }),
+            ("c1", "core", &ModuleChange::Appeared { files: 4 }),
+            ("c22", "core", &ModuleChange::Grew { from: 4, to: 6 }),
+            ("c333", "core", &ModuleChange::Split { into: vec!["core/io".to_string(), "core/net".to_string()] }),
+            ("c333", "core/io", &ModuleChange::Appeared { files: 2 }),
+            ("c333", "core/net", &ModuleChange::Appeared { files: 2 }),
+            ("c4444", "core", &ModuleChange::Deleted),
+        ]
+    );
+    assert_eq!(timeline.snapshots.len(), 4);
+    assert_eq!(timeline.snapshots[2].modules["core"], 2);
+
+    let json = serde_json::to_value(&timeline.events[2]).unwrap();
+    assert_eq!(json["kind"], "grew");
+    assert_eq!(json["committedAt"], 3);
+}
+
+#[test]
+fn test_documentation_coverage() {
+    use og_analytics::DocumentationReport;
+    use og_types::{CommentDensity, Suppressions};
+    use std::collections::HashMap;
+
+    let mut graph = CodeGraph::new();
+    for (id, path) in [("a.py:save", "/repo/a.py"), ("a.py:load", "/repo/a.py"), ("b.py:Store", "/repo/b.py")] {
+        graph.add_node(GraphNode {
+            node_type: "function".to_string(),
+            file_path: Some(path.to_string()),
+            ..node(id)
+        });
+    }
+    let comments = HashMap::from([
+        ("/repo/a.py".to_string(), CommentDensity { comment_lines: 5, code_lines: 20 }),
+        ("/repo/c.py".to_string(), CommentDensity { comment_lines: 0, code_lines: 0 }),
+    ]);
+    let documented = HashMap::from([
+        ("a.py:save".to_string(), true),
+        ("a.py:load".to_string(), false),
+        ("b.py:Store".to_string(), false),
+    ]);
+    let mut suppressions = Suppressions::default();
+    suppressions.insert("b.py:Store", "undocumented");
+
+    let report = DocumentationReport::build(&graph, &comments, &documented, &suppressions);
+    assert_eq!((report.public_symbols, report.documented), (3, 1));
+    assert!((report.coverage.unwrap() - 1.0 / 3.0).abs() < 1e-9);
+    assert!((report.comment_ratio - 0.25).abs() < 1e-9);
+    let paths: Vec<&str> = report.files.iter().map(|f| f.path.as_str()).collect();
+    assert_eq!(paths, vec!["/repo/a.py", "/repo/b.py", "/repo/c.py"]);
+    assert_eq!(report.files[0].coverage, Some(0.5));
+    assert_eq!((report.files[1].coverage, report.files[2].coverage), (Some(0.0), None));
+    let undocumented: Vec<&str> = report.undocumented.iter().map(|n| n.id.as_str()).collect();
+    assert_eq!(undocumented, vec!["a.py:load"]);
+}
+
+#[test]
+fn test_debt_report_counts_markers() {
+    use og_analytics::DebtReport;
+    use og_types::DebtMarker;
+
+    let marker = |kind: &str, file_path: &str, line| DebtMarker {
+        kind: kind.to_string(),
+        text: String::new(),
+        file_path: file_path.to_string(),
+        line,
+        node_id: format!("{}:{}", file_path, line),
+    };
+    let markers = vec![
+        marker("TODO", "/repo/b.py", 9),
+        marker("FIXME", "/repo/a.py", 3),
+        marker("TODO", "/repo/b.py", 2),
+    ];
+
+    let report = DebtReport::build(&markers, None);
+    assert_eq!(report.total, 3);
+    assert_eq!((report.by_kind["TODO"], report.by_kind["FIXME"], report.by_kind["HACK"]), (2, 1, 0));
+    assert_eq!((report.files[0].path.as_str(), report.files[0].markers), ("/repo/b.py", 2));
+    let lines: Vec<usize> = report.markers.iter().map(|m| m.line).collect();
+    assert_eq!(lines, vec![3, 2, 9]);
+
+    let todos = DebtReport::build(&markers, Some("todo"));
+    assert_eq!((todos.total, todos.files.len()), (2, 1));
+}
+
+#[test]
+fn test_license_inventory_flags_files() {
+    use og_analytics::analysis::license::LicenseProblemKind;
+    use og_analytics::LicenseInventory;
+    use og_types::{LicenseFile, LicenseHeader};
+    use std::collections::BTreeMap;
+
+    let header = |licenses: &[&str]| LicenseHeader {
+        licenses: licenses.iter().map(|l| l.to_string()).collect(),
+        copyright: None,
+    };
+    let headers = BTreeMap::from([
+        ("/repo/src/a.rs".to_string(), header(&[])),
+        ("/repo/src/b.rs".to_string(), header(&["GPL-3.0-only"])),
+        ("/repo/vendor/lib/c.c".to_string(), header(&["GPL"])),
+        ("/repo/vendor/lib/d.c".to_string(), header(&["MIT OR Apache-2.0"])),
+        ("/other/e.py".to_string(), header(&[])),
+    ]);
+    let license_files = vec![
+        LicenseFile { path: "/repo/LICENSE".to_string(), licenses: vec!["MIT".to_string()] },
+        LicenseFile { path: "/repo/vendor/lib/COPYING".to_string(), licenses: vec!["GPL-3.0".to_string()] },
+    ];
+
+    let inventory = LicenseInventory::build(&headers, &license_files);
+    let packages: Vec<(&str, usize)> = inventory.packages.iter().map(|p| (p.path.as_str(), p.files)).collect();
+    assert_eq!(packages, vec![("/repo", 2), ("/repo/vendor/lib", 2)]);
+    let problems: Vec<(&str, LicenseProblemKind)> = inventory.problems.iter().map(|p| (p.path.as_str(), p.kind)).collect();
+    assert_eq!(
+        problems,
+        vec![
+            ("/other/e.py", LicenseProblemKind::Missing),
+            ("/repo/src/b.rs", LicenseProblemKind::Conflicting),
+            ("/repo/vendor/lib/d.c", LicenseProblemKind::Conflicting),
+        ]
+    );
+
+    let csv = inventory.to_csv();
+    assert!(csv.starts_with("path,header_licenses,copyright,package,package_licenses,problem\n"));
+    assert!(csv.contains("/repo/src/b.rs,GPL-3.0-only,,/repo,MIT,conflicting\n"));
+}
+
+#[test]
+fn test_concurrency_map_by_module() {
+    use og_analytics::ConcurrencyMap;
+    use og_types::ConcurrencyProfile;
+    use std::collections::HashMap;
+
+    let mut graph = CodeGraph::new();
+    for (id, path) in [
+        ("jobs/pool.py:spawn", "/repo/jobs/pool.py"),
+        ("jobs/queue.py:put", "/repo/jobs/queue.py"),
+        ("main.py:main", "/repo/main.py"),
+    ] {
+        graph.add_node(GraphNode {
+            node_type: "function".to_string(),
+            file_path: Some(path.to_string()),
+            ..node(id)
+        });
+    }
+    let profiles = HashMap::from([
+        ("jobs/pool.py:spawn".to_string(), ConcurrencyProfile { threads: 2, tasks: 0, locks: 1 }),
+        ("jobs/queue.py:put".to_string(), ConcurrencyProfile { threads: 0, tasks: 0, locks: 1 }),
+        ("main.py:main".to_string(), ConcurrencyProfile { threads: 0, tasks: 1, locks: 0 }),
+        ("gone.py:f".to_string(), ConcurrencyProfile { threads: 5, tasks: 0, locks: 0 }),
+    ]);
+
+    let map = ConcurrencyMap::build(&graph, &profiles, "/repo");
+    assert_eq!(map.profile, ConcurrencyProfile { threads: 2, tasks: 1, locks: 2 });
+    let modules: Vec<(&str, usize)> = map.modules.iter().map(|m| (m.module.as_str(), m.nodes.len())).collect();
+    assert_eq!(modules, vec![("jobs", 2), (".", 1)]);
+    assert_eq!(map.modules[0].nodes[0].node.id, "jobs/pool.py:spawn");
+    assert_eq!(map.modules[0].profile.locks, 2);
+}
+
+#[test]
+fn test_large_file_threshold_per_language() {
+    use og_metrics_quality::QualityAnalyzer;
+
+    let mut graph = CodeGraph::new();
+    for path in ["/repo/app.py", "/repo/app.c"] {
+        graph.add_node(GraphNode {
+            name: path.to_string(),
+            size: 450.0,
+            file_path: Some(path.to_string()),
+            ..node(&format!("file:{}", path))
+        });
+    }
+
+    let smells = QualityAnalyzer::new().analyze_quality(&graph).unwrap().code_smells;
+    let large = |id: &str| smells.get(id).is_some_and(|s| s.smells.iter().any(|s| s.smell_type == "Large File"));
+    assert!(large("file:/repo/app.py"));
+    assert!(!large("file:/repo/app.c"));
+}
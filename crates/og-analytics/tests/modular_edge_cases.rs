@@ -1,5 +1,6 @@
 use og_analytics::{AnalyticsEngineV2, AnalyticsConfigV2};
 use og_graph::graph::{CodeGraph, GraphNode, GraphEdge};
+use og_graph::testing::{edge, node};
 use std::time::Duration;
 
 /// Test empty graph handling
@@ -23,12 +24,10 @@ async fn test_modular_empty_graph() {
 async fn test_modular_single_node() {
     let mut graph = CodeGraph::new();
     graph.add_node(GraphNode {
-        id: "single".to_string(),
         name: "Single Node".to_string(),
-        node_type: "file".to_string(),
-        file_path: Some("/single.js".to_string()),
         size: 100.0,
-        color: "blue".to_string(),
+        file_path: Some("/single.js".to_string()),
+        ..node("single")
     });
     
     let config = AnalyticsConfigV2::default();
@@ -52,12 +51,10 @@ async fn test_modular_disconnected_graph() {
         for j in 0..3 {
             let node_id = format!("node{}", component_base + j);
             graph.add_node(GraphNode {
-                id: node_id.clone(),
                 name: format!("Node {}", component_base + j),
-                node_type: "file".to_string(),
-                file_path: Some(format!("/file{}.js", component_base + j)),
                 size: 100.0,
-                color: "blue".to_string(),
+                file_path: Some(format!("/file{}.js", component_base + j)),
+                ..node(&node_id)
             });
         }
         
@@ -65,18 +62,12 @@ async fn test_modular_disconnected_graph() {
         graph.add_edge(
             &format!("node{}", component_base),
             &format!("node{}", component_base + 1),
-            GraphEdge {
-                edge_type: "imports".to_string(),
-                weight: 1.0,
-            },
+            edge("imports"),
         );
         graph.add_edge(
             &format!("node{}", component_base + 1),
             &format!("node{}", component_base + 2),
-            GraphEdge {
-                edge_type: "imports".to_string(),
-                weight: 1.0,
-            },
+            edge("imports"),
         );
     }
     
@@ -96,19 +87,14 @@ async fn test_modular_self_loops() {
     let mut graph = CodeGraph::new();
     
     graph.add_node(GraphNode {
-        id: "self".to_string(),
         name: "Self Loop".to_string(),
-        node_type: "file".to_string(),
-        file_path: Some("/self.js".to_string()),
         size: 100.0,
-        color: "blue".to_string(),
+        file_path: Some("/self.js".to_string()),
+        ..node("self")
     });
     
     // Add self-loop
-    graph.add_edge("self", "self", GraphEdge {
-        edge_type: "imports".to_string(),
-        weight: 1.0,
-    });
+    graph.add_edge("self", "self", edge("imports"));
     
     let config = AnalyticsConfigV2::default();
     let engine = AnalyticsEngineV2::new(config);
@@ -126,25 +112,23 @@ async fn test_modular_nan_weights() {
     
     for i in 0..3 {
         graph.add_node(GraphNode {
-            id: format!("node{}", i),
             name: format!("Node {}", i),
-            node_type: "file".to_string(),
-            file_path: Some(format!("/file{}.js", i)),
             size: 100.0,
-            color: "blue".to_string(),
+            file_path: Some(format!("/file{}.js", i)),
+            ..node(&format!("node{}", i))
         });
     }
     
     // Add edge with NaN weight
     graph.add_edge("node0", "node1", GraphEdge {
-        edge_type: "imports".to_string(),
         weight: f64::NAN,
+        ..edge("imports")
     });
     
     // Add edge with infinity weight
     graph.add_edge("node1", "node2", GraphEdge {
-        edge_type: "imports".to_string(),
         weight: f64::INFINITY,
+        ..edge("imports")
     });
     
     let config = AnalyticsConfigV2::default();
@@ -167,27 +151,16 @@ async fn test_modular_circular_deps() {
     // Create a cycle: A -> B -> C -> A
     for i in 0..3 {
         graph.add_node(GraphNode {
-            id: format!("node{}", i),
             name: format!("Node {}", i),
-            node_type: "file".to_string(),
-            file_path: Some(format!("/file{}.js", i)),
             size: 100.0,
-            color: "blue".to_string(),
+            file_path: Some(format!("/file{}.js", i)),
+            ..node(&format!("node{}", i))
         });
     }
     
-    graph.add_edge("node0", "node1", GraphEdge {
-        edge_type: "imports".to_string(),
-        weight: 1.0,
-    });
-    graph.add_edge("node1", "node2", GraphEdge {
-        edge_type: "imports".to_string(),
-        weight: 1.0,
-    });
-    graph.add_edge("node2", "node0", GraphEdge {
-        edge_type: "imports".to_string(),
-        weight: 1.0,
-    });
+    graph.add_edge("node0", "node1", edge("imports"));
+    graph.add_edge("node1", "node2", edge("imports"));
+    graph.add_edge("node2", "node0", edge("imports"));
     
     let config = AnalyticsConfigV2::default();
     let engine = AnalyticsEngineV2::new(config);
@@ -207,12 +180,10 @@ async fn test_modular_large_graph_sampling() {
     // Create a large graph
     for i in 0..2000 {
         graph.add_node(GraphNode {
-            id: format!("node{}", i),
             name: format!("Node {}", i),
-            node_type: "file".to_string(),
-            file_path: Some(format!("/file{}.js", i)),
             size: 100.0,
-            color: "blue".to_string(),
+            file_path: Some(format!("/file{}.js", i)),
+            ..node(&format!("node{}", i))
         });
     }
     
@@ -221,10 +192,7 @@ async fn test_modular_large_graph_sampling() {
         graph.add_edge(
             &format!("node{}", i),
             &format!("node{}", i + 1),
-            GraphEdge {
-                edge_type: "imports".to_string(),
-                weight: 1.0,
-            },
+            edge("imports"),
         );
     }
     
@@ -250,12 +218,10 @@ async fn test_modular_timeout_handling() {
     // Create a moderately complex graph
     for i in 0..100 {
         graph.add_node(GraphNode {
-            id: format!("node{}", i),
             name: format!("Node {}", i),
-            node_type: "file".to_string(),
-            file_path: Some(format!("/file{}.js", i)),
             size: 100.0,
-            color: "blue".to_string(),
+            file_path: Some(format!("/file{}.js", i)),
+            ..node(&format!("node{}", i))
         });
     }
     
@@ -265,10 +231,7 @@ async fn test_modular_timeout_handling() {
             graph.add_edge(
                 &format!("node{}", i),
                 &format!("node{}", j),
-                GraphEdge {
-                    edge_type: "imports".to_string(),
-                    weight: 1.0,
-                },
+                edge("imports"),
             );
         }
     }
@@ -294,12 +257,10 @@ async fn test_modular_parallel_vs_sequential() {
     // Create a simple graph
     for i in 0..10 {
         graph.add_node(GraphNode {
-            id: format!("node{}", i),
             name: format!("Node {}", i),
-            node_type: "file".to_string(),
-            file_path: Some(format!("/file{}.js", i)),
             size: 100.0,
-            color: "blue".to_string(),
+            file_path: Some(format!("/file{}.js", i)),
+            ..node(&format!("node{}", i))
         });
     }
     
@@ -307,10 +268,7 @@ async fn test_modular_parallel_vs_sequential() {
         graph.add_edge(
             &format!("node{}", i),
             &format!("node{}", i + 1),
-            GraphEdge {
-                edge_type: "imports".to_string(),
-                weight: 1.0,
-            },
+            edge("imports"),
         );
     }
     
@@ -338,30 +296,24 @@ async fn test_modular_god_object() {
     
     // Create a god object with many connections
     graph.add_node(GraphNode {
-        id: "god".to_string(),
         name: "GodObject".to_string(),
         node_type: "class".to_string(),
-        file_path: Some("/god.js".to_string()),
         size: 1000.0,
-        color: "blue".to_string(),
+        file_path: Some("/god.js".to_string()),
+        ..node("god")
     });
     
     // Add many dependencies
     for i in 0..50 {
         let node_id = format!("dep{}", i);
         graph.add_node(GraphNode {
-            id: node_id.clone(),
             name: format!("Dep{}", i),
-            node_type: "file".to_string(),
-            file_path: Some(format!("/dep{}.js", i)),
             size: 100.0,
-            color: "blue".to_string(),
+            file_path: Some(format!("/dep{}.js", i)),
+            ..node(&node_id)
         });
         
-        graph.add_edge("god", &node_id, GraphEdge {
-            edge_type: "imports".to_string(),
-            weight: 1.0,
-        });
+        graph.add_edge("god", &node_id, edge("imports"));
     }
     
     let config = AnalyticsConfigV2::default();
@@ -372,4 +324,31 @@ async fn test_modular_god_object() {
     // Should identify god object
     assert!(report.quality.code_smells.contains_key("god"));
     assert!(report.risk.risk_scores["god"].overall > 0.5);
-}
\ No newline at end of file
+}
+/// Test interface segregation detection on an interface whose clients use disjoint members
+#[tokio::test]
+async fn test_modular_interface_segregation() {
+    let mut graph = CodeGraph::new();
+    let node = |id: &str, node_type: &str, file: &str| GraphNode {
+        node_type: node_type.to_string(),
+        file_path: Some(file.to_string()),
+        ..node(id)
+    };
+
+    graph.add_node(node("repo", "interface", "/repo.ts"));
+    for member in ["read", "write", "delete", "audit"] {
+        graph.add_node(node(member, "method", "/repo.ts"));
+        graph.add_edge("repo", member, edge("contains"));
+    }
+    // Each client touches a single, different member
+    for (client, member) in [("reader", "read"), ("writer", "write"), ("cleaner", "delete")] {
+        graph.add_node(node(client, "function", &format!("/{}.ts", client)));
+        graph.add_edge(client, member, edge("calls"));
+    }
+
+    let engine = AnalyticsEngineV2::new(AnalyticsConfigV2::default());
+    let report = engine.analyze(&graph).await.unwrap();
+
+    let smells = &report.quality.code_smells["repo"].smells;
+    assert!(smells.iter().any(|s| s.smell_type == "Interface Segregation (Fat Interface)"), "{:?}", smells);
+}
@@ -0,0 +1,57 @@
+//! Parse throughput, graph-build time and per-metric time over each corpus
+//!
+//! Run with `cargo bench -p og-bench`. Metrics are only timed on corpora up
+//! to `METRIC_MAX_NODES` nodes unless `OMNIGRAPH_BENCH_ALL_METRICS` is set,
+//! since the 100k-node corpus takes minutes per metric iteration.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use og_analytics::{analyze_graph, AnalyticsConfig, DEFAULT_METRICS};
+use og_bench::{build_graph, real_repos, synthetic, Corpus, SYNTHETIC_SIZES};
+use std::time::Duration;
+
+/// Largest graph metrics are timed on by default
+const METRIC_MAX_NODES: usize = 10_000;
+
+fn corpora() -> Vec<Corpus> {
+    let mut corpora: Vec<Corpus> = SYNTHETIC_SIZES.iter().map(|&nodes| synthetic(nodes)).collect();
+    corpora.extend(real_repos());
+    corpora
+}
+
+fn bench_corpus(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().expect("tokio runtime");
+    let all_metrics = std::env::var_os("OMNIGRAPH_BENCH_ALL_METRICS").is_some();
+
+    for corpus in corpora() {
+        let parsed = corpus.parse();
+        let graph = build_graph(parsed.clone());
+        let nodes = graph.node_map.len();
+
+        let mut group = c.benchmark_group(&corpus.name);
+        group.sample_size(10);
+        group.measurement_time(Duration::from_secs(10));
+
+        group.throughput(Throughput::Bytes(corpus.bytes()));
+        group.bench_function("parse", |b| b.iter(|| corpus.parse()));
+
+        group.throughput(Throughput::Elements(nodes as u64));
+        group.bench_function("build_graph", |b| b.iter(|| build_graph(parsed.clone())));
+
+        if nodes <= METRIC_MAX_NODES || all_metrics {
+            for metric in DEFAULT_METRICS {
+                let config = AnalyticsConfig {
+                    enabled_metrics: Some(vec![metric.to_string()]),
+                    use_cache: false,
+                    ..AnalyticsConfig::default()
+                };
+                group.bench_with_input(BenchmarkId::new("metric", metric), &config, |b, config| {
+                    b.iter(|| runtime.block_on(analyze_graph(&graph, Some(config.clone()))))
+                });
+            }
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, bench_corpus);
+criterion_main!(benches);
@@ -0,0 +1,170 @@
+//! Corpora for the criterion benchmarks in `benches/`
+//!
+//! Synthetic repositories are generated on the fly at a chosen node count so
+//! runs are comparable across machines. Real code comes from the frontend in
+//! this repository and from any directories listed in `OMNIGRAPH_BENCH_REPOS`.
+
+use og_graph::builder::GraphBuilder;
+use og_graph::graph::CodeGraph;
+use og_parser::ParserEngine;
+use og_types::ParsedFile;
+use std::path::{Path, PathBuf};
+
+/// Node counts of the synthetic repositories
+pub const SYNTHETIC_SIZES: &[usize] = &[1_000, 10_000, 100_000];
+
+/// Colon-separated directories of real repositories to benchmark as well
+pub const REPOS_ENV: &str = "OMNIGRAPH_BENCH_REPOS";
+
+/// Root every corpus file path is placed under
+const ROOT: &str = "/bench";
+
+/// Functions per synthetic module besides its class
+const FUNCTIONS: usize = 4;
+
+/// Methods of the class in each synthetic module
+const METHODS: usize = 6;
+
+/// Modules each synthetic module imports
+const IMPORTS: usize = 3;
+
+/// Source files to analyze, as the parser engine takes them
+#[derive(Debug, Clone)]
+pub struct Corpus {
+    pub name: String,
+    /// (path, content) pairs
+    pub files: Vec<(String, String)>,
+}
+
+impl Corpus {
+    pub fn bytes(&self) -> u64 {
+        self.files.iter().map(|(_, content)| content.len() as u64).sum()
+    }
+
+    pub fn parse(&self) -> Vec<ParsedFile> {
+        ParserEngine::with_base_path(PathBuf::from(ROOT))
+            .parse_batch(self.files.clone(), None)
+            .into_iter()
+            .filter_map(Result::ok)
+            .collect()
+    }
+}
+
+/// Resolve cross-file calls and build the graph from parsed files
+pub fn build_graph(mut parsed: Vec<ParsedFile>) -> CodeGraph {
+    og_parser::python::resolve_imported_calls(&mut parsed);
+    GraphBuilder::new().build_from_files(parsed)
+}
+
+/// Small deterministic generator so corpora do not change between runs
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self, bound: usize) -> usize {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        ((self.0 >> 33) % bound as u64) as usize
+    }
+}
+
+/// Python package with roughly `nodes` graph nodes
+///
+/// Each module imports a few others and calls into them, so the graph has
+/// cross-file edges and some cycles like a real codebase.
+pub fn synthetic(nodes: usize) -> Corpus {
+    let per_module = 2 + METHODS + FUNCTIONS;
+    let modules = (nodes / per_module).max(IMPORTS + 1);
+    let mut rng = Lcg(nodes as u64);
+
+    let files = (0..modules)
+        .map(|i| {
+            let imports: Vec<usize> = (0..IMPORTS).map(|_| rng.next(modules)).filter(|&j| j != i).collect();
+            let mut source = String::new();
+            for j in &imports {
+                source.push_str(&format!("from pkg.m{j} import f{j}_0\n"));
+            }
+            source.push_str(&format!("\n\nclass C{i}:\n"));
+            for m in 0..METHODS {
+                let call = match imports.get(m % IMPORTS) {
+                    Some(j) => format!("f{j}_0(x)"),
+                    None => format!("f{i}_{}(x)", m % FUNCTIONS),
+                };
+                source.push_str(&format!("    def m{m}(self, x):\n        if x > {m}:\n            return {call}\n        return x\n\n"));
+            }
+            for f in 0..FUNCTIONS {
+                let next = (f + 1) % FUNCTIONS;
+                source.push_str(&format!("\ndef f{i}_{f}(n):\n    return f{i}_{next}(n - 1) if n else 0\n"));
+            }
+            (format!("{ROOT}/pkg/m{i}.py"), source)
+        })
+        .collect();
+
+    Corpus {
+        name: format!("synthetic-{}", nodes),
+        files,
+    }
+}
+
+/// Every parseable file under `dir`, skipping dependency and build folders
+pub fn from_dir(name: &str, dir: &Path) -> Corpus {
+    let engine = ParserEngine::new();
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let skipped = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with('.') || matches!(name, "node_modules" | "target" | "dist"));
+            if skipped {
+                continue;
+            }
+            if path.is_dir() {
+                pending.push(path);
+            } else if engine.can_parse(&path) {
+                if let Ok(content) = std::fs::read_to_string(&path) {
+                    let relative = path.strip_prefix(dir.as_path()).unwrap_or(&path);
+                    files.push((Path::new(ROOT).join(relative).display().to_string(), content));
+                }
+            }
+        }
+    }
+    files.sort();
+    Corpus {
+        name: name.to_string(),
+        files,
+    }
+}
+
+/// The frontend of this repository plus any repositories named in `OMNIGRAPH_BENCH_REPOS`
+pub fn real_repos() -> Vec<Corpus> {
+    let workspace = Path::new(env!("CARGO_MANIFEST_DIR")).join("../..");
+    let mut corpora = vec![from_dir("omnigraph-frontend", &workspace.join("src"))];
+    if let Some(repos) = std::env::var_os(REPOS_ENV) {
+        for dir in std::env::split_paths(&repos) {
+            let name = dir
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| dir.display().to_string());
+            corpora.push(from_dir(&name, &dir));
+        }
+    }
+    corpora.retain(|corpus| !corpus.files.is_empty());
+    corpora
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_synthetic_corpus_hits_node_count() {
+        let graph = build_graph(synthetic(1_000).parse());
+        let nodes = graph.node_map.len();
+        assert!((900..=1_100).contains(&nodes), "got {} nodes", nodes);
+        assert!(graph.graph.edge_count() > nodes, "got {} edges", graph.graph.edge_count());
+    }
+}
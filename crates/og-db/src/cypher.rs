@@ -0,0 +1,488 @@
+use neo4rs::{query, BoltMap, BoltNode, BoltPath, BoltRelation, BoltType, Graph};
+use og_graph::graph::{GraphData, GraphLink, GraphNode};
+use og_types::CommandError;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use thiserror::Error;
+
+/// Clauses that write data or change the database, refused in read-only mode
+const WRITE_CLAUSES: &[&str] = &[
+    "CREATE", "MERGE", "DELETE", "DETACH", "SET", "REMOVE", "DROP", "LOAD", "FOREACH", "ALTER", "RENAME", "GRANT",
+    "DENY", "REVOKE", "START", "STOP", "TERMINATE",
+];
+
+/// Procedures read-only mode may call
+const READ_PROCEDURES: &[&str] = &[
+    "db.labels",
+    "db.relationshipTypes",
+    "db.propertyKeys",
+    "db.indexes",
+    "db.constraints",
+    "db.schema.visualization",
+    "db.schema.nodeTypeProperties",
+    "db.schema.relTypeProperties",
+];
+
+/// Rows kept from one query; the rest are counted but dropped
+pub const MAX_ROWS: usize = 10_000;
+
+/// Color of result nodes that do not carry one
+const DEFAULT_COLOR: &str = "#607d8b";
+
+/// Why a Cypher query was refused or failed
+#[derive(Error, Debug)]
+pub enum CypherError {
+    #[error("Not allowed in read-only mode: {0}")]
+    ReadOnly(String),
+
+    #[error("Invalid parameter {name}: {message}")]
+    Parameter { name: String, message: String },
+
+    #[error("Unreadable result row: {0}")]
+    Row(String),
+
+    #[error("Neo4j error: {0}")]
+    Database(#[from] neo4rs::Error),
+}
+
+impl From<CypherError> for CommandError {
+    fn from(error: CypherError) -> Self {
+        let message = error.to_string();
+        match error {
+            CypherError::Database(neo4rs::Error::IOError { .. } | neo4rs::Error::ConnectionError) => Self::io(message),
+            CypherError::Database(_) | CypherError::Row(_) => Self::internal(message),
+            CypherError::ReadOnly(_) | CypherError::Parameter { .. } => Self::invalid_input(message),
+        }
+    }
+}
+
+/// Rows of a Cypher query, and the nodes and relationships in them as a graph
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CypherResult {
+    /// Returned column names, sorted
+    pub columns: Vec<String>,
+    pub rows: Vec<Map<String, Value>>,
+    /// Every node in the rows, and the relationships between them
+    pub graph: GraphData,
+    /// Whether rows past `MAX_ROWS` were dropped
+    pub truncated: bool,
+}
+
+/// Words of a query outside string literals, quoted names and comments
+///
+/// Dotted names such as `n.set` or `db.labels` stay one word, and `{` is
+/// kept so a `CALL {` subquery can be told from a procedure call.
+fn words(query: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut chars = query.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_alphanumeric() || c == '_' || c == '.' {
+            word.push(c);
+            continue;
+        }
+        if !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        }
+        match c {
+            '\'' | '"' | '`' => {
+                while let Some(next) = chars.next() {
+                    if next == '\\' && c != '`' {
+                        chars.next();
+                    } else if next == c {
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut star = false;
+                for next in chars.by_ref() {
+                    if star && next == '/' {
+                        break;
+                    }
+                    star = next == '*';
+                }
+            }
+            '{' => words.push("{".to_string()),
+            _ => {}
+        }
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// Refuse queries that could write to the database
+///
+/// The check is by keyword, so a map key named like a write clause is
+/// refused too. Procedures must be on a short list of schema lookups.
+pub fn check_read_only(query: &str) -> Result<(), CypherError> {
+    let words = words(query);
+    for (index, word) in words.iter().enumerate() {
+        let upper = word.to_uppercase();
+        if WRITE_CLAUSES.contains(&upper.as_str()) {
+            return Err(CypherError::ReadOnly(upper));
+        }
+        if upper == "CALL" {
+            match words.get(index + 1).map(String::as_str) {
+                Some("{") => {}
+                Some(procedure) if READ_PROCEDURES.contains(&procedure) => {}
+                procedure => return Err(CypherError::ReadOnly(format!("CALL {}", procedure.unwrap_or_default()))),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A node met in the result rows
+#[derive(Debug, Clone, PartialEq)]
+struct FoundNode {
+    labels: Vec<String>,
+    properties: Map<String, Value>,
+}
+
+/// A relationship met in the result rows, between Neo4j node ids
+#[derive(Debug, Clone, PartialEq)]
+struct FoundRelationship {
+    start: i64,
+    end: i64,
+    kind: String,
+    properties: Map<String, Value>,
+}
+
+/// Nodes and relationships met in the result rows, by Neo4j id
+#[derive(Debug, Default)]
+struct Found {
+    nodes: BTreeMap<i64, FoundNode>,
+    relationships: BTreeMap<i64, FoundRelationship>,
+}
+
+impl Found {
+    fn properties(&mut self, map: &BoltMap) -> Map<String, Value> {
+        map.value
+            .iter()
+            .map(|(key, value)| (key.value.clone(), self.value(value)))
+            .collect()
+    }
+
+    fn node(&mut self, node: &BoltNode) -> Value {
+        let labels: Vec<String> = node
+            .labels
+            .value
+            .iter()
+            .filter_map(|label| match label {
+                BoltType::String(label) => Some(label.value.clone()),
+                _ => None,
+            })
+            .collect();
+        let properties = self.properties(&node.properties);
+        let value = json!({ "id": node.id.value, "labels": labels, "properties": properties });
+        self.nodes.insert(node.id.value, FoundNode { labels, properties });
+        value
+    }
+
+    fn relationship(&mut self, relationship: &BoltRelation) -> Value {
+        let found = FoundRelationship {
+            start: relationship.start_node_id.value,
+            end: relationship.end_node_id.value,
+            kind: relationship.typ.value.clone(),
+            properties: self.properties(&relationship.properties),
+        };
+        let value = json!({
+            "id": relationship.id.value,
+            "type": found.kind,
+            "start": found.start,
+            "end": found.end,
+            "properties": found.properties,
+        });
+        self.relationships.insert(relationship.id.value, found);
+        value
+    }
+
+    /// Walk a path's relationships, whose indices alternate between a
+    /// relationship, negative when traversed backwards, and the next node
+    fn path(&mut self, path: &BoltPath) -> Value {
+        let nodes: Vec<&BoltNode> = path
+            .nodes
+            .value
+            .iter()
+            .filter_map(|node| match node {
+                BoltType::Node(node) => Some(node),
+                _ => None,
+            })
+            .collect();
+        let node_values: Vec<Value> = nodes.iter().map(|node| self.node(node)).collect();
+        let indices: Vec<i64> = path
+            .indices
+            .value
+            .iter()
+            .filter_map(|index| match index {
+                BoltType::Integer(index) => Some(index.value),
+                _ => None,
+            })
+            .collect();
+
+        let mut relationships = Vec::new();
+        let mut previous = nodes.first().map(|node| node.id.value);
+        for step in indices.chunks_exact(2) {
+            let (Some(from), Some(BoltType::UnboundedRelation(relationship)), Some(next)) = (
+                previous,
+                (step[0].unsigned_abs() as usize).checked_sub(1).and_then(|index| path.rels.value.get(index)),
+                nodes.get(step[1] as usize),
+            ) else {
+                break;
+            };
+            let (start, end) = if step[0] > 0 { (from, next.id.value) } else { (next.id.value, from) };
+            relationships.push(self.relationship(&BoltRelation {
+                id: relationship.id.clone(),
+                start_node_id: start.into(),
+                end_node_id: end.into(),
+                typ: relationship.typ.clone(),
+                properties: relationship.properties.clone(),
+            }));
+            previous = Some(next.id.value);
+        }
+        json!({ "nodes": node_values, "relationships": relationships })
+    }
+
+    fn value(&mut self, value: &BoltType) -> Value {
+        match value {
+            BoltType::Null(_) => Value::Null,
+            BoltType::Boolean(b) => json!(b.value),
+            BoltType::Integer(i) => json!(i.value),
+            BoltType::Float(f) => json!(f.value),
+            BoltType::String(s) => json!(s.value),
+            BoltType::List(list) => Value::Array(list.value.iter().map(|item| self.value(item)).collect()),
+            BoltType::Map(map) => Value::Object(self.properties(map)),
+            BoltType::Node(node) => self.node(node),
+            BoltType::Relation(relationship) => self.relationship(relationship),
+            BoltType::UnboundedRelation(relationship) => json!({
+                "id": relationship.id.value,
+                "type": relationship.typ.value,
+                "properties": self.properties(&relationship.properties),
+            }),
+            BoltType::Path(path) => self.path(path),
+            other => json!(other.to_string()),
+        }
+    }
+
+    /// The nodes as graph nodes, and the relationships between them as links
+    ///
+    /// Nodes keep their `id` property as id where they have one, so results
+    /// line up with the analyzed graph; the rest get their Neo4j id.
+    fn into_graph(self) -> GraphData {
+        let text = |properties: &Map<String, Value>, keys: &[&str]| {
+            keys.iter()
+                .find_map(|key| properties.get(*key).and_then(Value::as_str))
+                .map(str::to_string)
+        };
+        let ids: HashMap<i64, String> = self
+            .nodes
+            .iter()
+            .map(|(neo4j_id, node)| {
+                let id = text(&node.properties, &["id"]).unwrap_or_else(|| format!("neo4j:{}", neo4j_id));
+                (*neo4j_id, id)
+            })
+            .collect();
+
+        let links: Vec<GraphLink> = self
+            .relationships
+            .values()
+            .filter_map(|relationship| {
+                Some(GraphLink {
+                    source: ids.get(&relationship.start)?.clone(),
+                    target: ids.get(&relationship.end)?.clone(),
+                    link_type: relationship.kind.to_lowercase(),
+                    value: relationship.properties.get("weight").and_then(Value::as_f64).unwrap_or(1.0),
+                    count: 1,
+                })
+            })
+            .collect();
+        let mut connections: HashMap<&str, usize> = HashMap::new();
+        for link in &links {
+            *connections.entry(link.source.as_str()).or_default() += 1;
+            *connections.entry(link.target.as_str()).or_default() += 1;
+        }
+
+        let nodes = self
+            .nodes
+            .iter()
+            .map(|(neo4j_id, node)| {
+                let id = ids[neo4j_id].clone();
+                GraphNode {
+                    name: text(&node.properties, &["name"]).unwrap_or_else(|| id.clone()),
+                    node_type: node.labels.first().map_or_else(|| "node".to_string(), |label| label.to_lowercase()),
+                    size: node.properties.get("size").and_then(Value::as_f64).unwrap_or(0.0),
+                    visual_size: (connections.get(id.as_str()).copied().unwrap_or(0) as f64 * 10.0).max(10.0),
+                    color: text(&node.properties, &["color"]).unwrap_or_else(|| DEFAULT_COLOR.to_string()),
+                    file_path: text(&node.properties, &["filePath", "file_path"]),
+                    attributes: Default::default(),
+                    id,
+                }
+            })
+            .collect();
+        GraphData::from_parts(nodes, links)
+    }
+}
+
+/// Connection to a Neo4j database
+pub struct Neo4jClient {
+    graph: Graph,
+    uri: String,
+}
+
+impl Neo4jClient {
+    /// Connect and check the credentials with a trivial query
+    pub async fn connect(uri: &str, username: &str, password: &str) -> Result<Self, CypherError> {
+        let graph = Graph::new(uri, username, password).await?;
+        graph.run(query("RETURN 1")).await?;
+        Ok(Self {
+            graph,
+            uri: uri.to_string(),
+        })
+    }
+
+    pub fn uri(&self) -> &str {
+        &self.uri
+    }
+
+    pub(crate) fn graph(&self) -> &Graph {
+        &self.graph
+    }
+
+    /// Run a Cypher query with parameters and shape its rows into a graph
+    ///
+    /// In read-only mode the query must pass [`check_read_only`], and its
+    /// transaction is rolled back rather than committed, so a write the
+    /// keyword check misses is not kept either.
+    pub async fn run_cypher(
+        &self,
+        cypher: &str,
+        params: &Map<String, Value>,
+        read_only: bool,
+    ) -> Result<CypherResult, CypherError> {
+        if read_only {
+            check_read_only(cypher)?;
+        }
+        let mut q = query(cypher);
+        for (name, value) in params {
+            let value = BoltType::try_from(value.clone()).map_err(|e| CypherError::Parameter {
+                name: name.clone(),
+                message: e.to_string(),
+            })?;
+            q = q.param(name, value);
+        }
+
+        let mut txn = self.graph.start_txn().await?;
+        let mut stream = txn.execute(q).await?;
+        let mut found = Found::default();
+        let mut rows = Vec::new();
+        let mut truncated = false;
+        while let Some(row) = stream.next(txn.handle()).await? {
+            if rows.len() == MAX_ROWS {
+                truncated = true;
+                continue;
+            }
+            let values: BTreeMap<String, BoltType> = row.to_strict().map_err(|e| CypherError::Row(e.to_string()))?;
+            rows.push(values.iter().map(|(column, value)| (column.clone(), found.value(value))).collect::<Map<_, _>>());
+        }
+        if read_only {
+            txn.rollback().await?;
+        } else {
+            txn.commit().await?;
+        }
+
+        Ok(CypherResult {
+            columns: rows.first().map(|row| row.keys().cloned().collect()).unwrap_or_default(),
+            rows,
+            graph: found.into_graph(),
+            truncated,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_only_check() {
+        assert!(check_read_only("MATCH (n:Function)-[r:CALLS]->(m) WHERE n.name = 'SET' RETURN n, r, m").is_ok());
+        assert!(check_read_only("MATCH (n) // DELETE n\nRETURN n.set /* CREATE */").is_ok());
+        assert!(check_read_only("CALL db.labels() YIELD label RETURN label").is_ok());
+        assert!(check_read_only("MATCH (n) CALL { WITH n MATCH (n)-->(m) RETURN m } RETURN m").is_ok());
+
+        assert!(matches!(
+            check_read_only("MATCH (n) detach delete n"),
+            Err(CypherError::ReadOnly(clause)) if clause == "DETACH"
+        ));
+        assert!(check_read_only("MATCH (n) SET n.x = 1").is_err());
+        assert!(check_read_only("LOAD CSV FROM 'file:///x' AS row RETURN row").is_err());
+        assert!(matches!(
+            check_read_only("CALL apoc.periodic.iterate('a', 'b', {})"),
+            Err(CypherError::ReadOnly(call)) if call == "CALL apoc.periodic.iterate"
+        ));
+    }
+
+    #[test]
+    fn test_rows_shaped_into_graph() {
+        let node = |id: i64, label: &str, properties: Value| {
+            BoltType::Node(BoltNode {
+                id: id.into(),
+                labels: vec![BoltType::from(label)].into(),
+                properties: match BoltType::try_from(properties).unwrap() {
+                    BoltType::Map(map) => map,
+                    _ => unreachable!(),
+                },
+            })
+        };
+        let mut found = Found::default();
+        let row = BoltType::List(
+            vec![
+                node(1, "Function", json!({ "id": "function:main", "name": "main" })),
+                node(2, "File", json!({ "filePath": "src/app.py" })),
+                BoltType::Relation(BoltRelation {
+                    id: 7.into(),
+                    start_node_id: 2.into(),
+                    end_node_id: 1.into(),
+                    typ: "CONTAINS".into(),
+                    properties: BoltMap::default(),
+                }),
+                BoltType::Relation(BoltRelation {
+                    id: 8.into(),
+                    start_node_id: 1.into(),
+                    end_node_id: 99.into(),
+                    typ: "CALLS".into(),
+                    properties: BoltMap::default(),
+                }),
+                BoltType::from(3.5),
+            ]
+            .into(),
+        );
+        let value = found.value(&row);
+        assert_eq!(value[0]["properties"]["name"], "main");
+        assert_eq!(value[4], 3.5);
+
+        let graph = found.into_graph();
+        let nodes: Vec<(&str, &str, &str)> =
+            graph.nodes.iter().map(|n| (n.id.as_str(), n.name.as_str(), n.node_type.as_str())).collect();
+        assert_eq!(nodes, vec![("function:main", "main", "function"), ("neo4j:2", "neo4j:2", "file")]);
+        assert_eq!(graph.nodes[1].file_path.as_deref(), Some("src/app.py"));
+        assert_eq!(graph.links.len(), 1);
+        assert_eq!(
+            (graph.links[0].source.as_str(), graph.links[0].target.as_str(), graph.links[0].link_type.as_str()),
+            ("neo4j:2", "function:main", "contains")
+        );
+    }
+}
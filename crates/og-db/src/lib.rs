@@ -1,2 +1,11 @@
 // Database module for Neo4j integration
-// TODO: Move Neo4j logic here from src-tauri
\ No newline at end of file
+// TODO: Move Neo4j logic here from src-tauri
+
+pub mod cypher;
+pub mod store;
+
+pub use cypher::{check_read_only, CypherError, CypherResult, Neo4jClient};
+pub use store::{
+    sync, GraphDelta, GraphStore, MemoryStore, Namespace, NamespaceInfo, Neo4jStore, SqliteStore, StoreBackend,
+    StoreError, SyncReport,
+};
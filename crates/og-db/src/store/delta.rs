@@ -0,0 +1,196 @@
+use super::{GraphStore, StoreBackend, StoreError};
+use og_graph::graph::{GraphData, GraphLink, GraphNode};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Identity of a link: source, target and type
+pub type LinkKey = (String, String, String);
+
+pub fn link_key(link: &GraphLink) -> LinkKey {
+    (link.source.clone(), link.target.clone(), link.link_type.clone())
+}
+
+/// Changes that turn one graph into another
+///
+/// Links are identified by [`LinkKey`], so parallel links of one type
+/// between the same nodes count as one.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphDelta {
+    pub added_nodes: Vec<GraphNode>,
+    /// Nodes whose name, type, size, color or file changed
+    pub updated_nodes: Vec<GraphNode>,
+    pub removed_nodes: Vec<String>,
+    pub added_links: Vec<GraphLink>,
+    /// Links whose value changed
+    pub updated_links: Vec<GraphLink>,
+    pub removed_links: Vec<GraphLink>,
+}
+
+impl GraphDelta {
+    pub fn between(previous: &GraphData, current: &GraphData) -> Self {
+        let mut delta = Self::default();
+
+        let old_nodes: HashMap<&str, &GraphNode> = previous.nodes.iter().map(|n| (n.id.as_str(), n)).collect();
+        let new_nodes: HashSet<&str> = current.nodes.iter().map(|n| n.id.as_str()).collect();
+        for node in &current.nodes {
+            match old_nodes.get(node.id.as_str()) {
+                None => delta.added_nodes.push(node.clone()),
+                Some(old) if *old != node => delta.updated_nodes.push(node.clone()),
+                Some(_) => {}
+            }
+        }
+        delta.removed_nodes = previous
+            .nodes
+            .iter()
+            .filter(|n| !new_nodes.contains(n.id.as_str()))
+            .map(|n| n.id.clone())
+            .collect();
+
+        let old_links: HashMap<LinkKey, &GraphLink> = previous.links.iter().map(|l| (link_key(l), l)).collect();
+        let mut new_links = HashSet::new();
+        for link in &current.links {
+            let key = link_key(link);
+            if !new_links.insert(key.clone()) {
+                continue;
+            }
+            match old_links.get(&key) {
+                None => delta.added_links.push(link.clone()),
+                Some(old) if old.value != link.value => delta.updated_links.push(link.clone()),
+                Some(_) => {}
+            }
+        }
+        let mut removed = HashSet::new();
+        delta.removed_links = previous
+            .links
+            .iter()
+            .filter(|l| {
+                let key = link_key(l);
+                !new_links.contains(&key) && removed.insert(key)
+            })
+            .cloned()
+            .collect();
+
+        delta
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added_nodes.is_empty()
+            && self.updated_nodes.is_empty()
+            && self.removed_nodes.is_empty()
+            && self.added_links.is_empty()
+            && self.updated_links.is_empty()
+            && self.removed_links.is_empty()
+    }
+
+    /// Added and updated nodes, which backends write the same way
+    pub fn upserted_nodes(&self) -> impl Iterator<Item = &GraphNode> {
+        self.added_nodes.iter().chain(&self.updated_nodes)
+    }
+
+    /// `graph` with this delta applied, keeping the order of surviving
+    /// nodes and links and appending new ones
+    pub fn apply_to(&self, graph: &GraphData) -> GraphData {
+        let removed_nodes: HashSet<&str> = self.removed_nodes.iter().map(String::as_str).collect();
+        let updated_nodes: HashMap<&str, &GraphNode> =
+            self.updated_nodes.iter().map(|n| (n.id.as_str(), n)).collect();
+        let nodes = graph
+            .nodes
+            .iter()
+            .filter(|n| !removed_nodes.contains(n.id.as_str()))
+            .map(|n| updated_nodes.get(n.id.as_str()).copied().unwrap_or(n).clone())
+            .chain(self.added_nodes.iter().cloned())
+            .collect();
+
+        let removed_links: HashSet<LinkKey> = self.removed_links.iter().map(link_key).collect();
+        let updated_links: HashMap<LinkKey, &GraphLink> =
+            self.updated_links.iter().map(|l| (link_key(l), l)).collect();
+        let links = graph
+            .links
+            .iter()
+            .filter(|l| !removed_links.contains(&link_key(l)))
+            .map(|l| updated_links.get(&link_key(l)).copied().unwrap_or(l).clone())
+            .chain(self.added_links.iter().cloned())
+            .collect();
+
+        GraphData::from_parts(nodes, links)
+    }
+}
+
+/// One synchronization of a store, for the sync log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncReport {
+    pub backend: StoreBackend,
+    /// Seconds since the Unix epoch
+    pub synced_at: u64,
+    /// Whether the whole graph was written because the store was empty
+    pub full: bool,
+    pub added_nodes: usize,
+    pub updated_nodes: usize,
+    pub removed_nodes: usize,
+    pub added_links: usize,
+    pub updated_links: usize,
+    pub removed_links: usize,
+    pub duration_ms: u64,
+}
+
+impl SyncReport {
+    fn new(backend: StoreBackend, delta: &GraphDelta, full: bool, duration: Duration) -> Self {
+        Self {
+            backend,
+            synced_at: SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()),
+            full,
+            added_nodes: delta.added_nodes.len(),
+            updated_nodes: delta.updated_nodes.len(),
+            removed_nodes: delta.removed_nodes.len(),
+            added_links: delta.added_links.len(),
+            updated_links: delta.updated_links.len(),
+            removed_links: delta.removed_links.len(),
+            duration_ms: duration.as_millis() as u64,
+        }
+    }
+}
+
+/// Bring `store` up to date with `current`, writing only what changed
+/// since `previous`, the graph last synchronized to it
+///
+/// Without `previous` the store's own copy is compared instead, and an
+/// empty store receives the whole graph.
+pub async fn sync(
+    store: &dyn GraphStore,
+    previous: Option<&GraphData>,
+    current: &GraphData,
+) -> Result<SyncReport, StoreError> {
+    let started = Instant::now();
+    let stored;
+    let previous = match previous {
+        Some(previous) => Some(previous),
+        None => {
+            stored = store.load().await?;
+            stored.as_ref()
+        }
+    };
+
+    let Some(previous) = previous else {
+        store.save(current).await?;
+        let delta = GraphDelta::between(&GraphData::from_parts(Vec::new(), Vec::new()), current);
+        return Ok(SyncReport::new(store.backend(), &delta, true, started.elapsed()));
+    };
+    let delta = GraphDelta::between(previous, current);
+    if !delta.is_empty() {
+        store.apply(&delta).await?;
+    }
+    tracing::debug!(
+        "Synchronized {:?} store: +{} ~{} -{} nodes, +{} ~{} -{} links",
+        store.backend(),
+        delta.added_nodes.len(),
+        delta.updated_nodes.len(),
+        delta.removed_nodes.len(),
+        delta.added_links.len(),
+        delta.updated_links.len(),
+        delta.removed_links.len()
+    );
+    Ok(SyncReport::new(store.backend(), &delta, false, started.elapsed()))
+}
@@ -0,0 +1,64 @@
+use super::{induced, reachable, GraphDelta, GraphStore, StoreBackend, StoreError};
+use async_trait::async_trait;
+use og_graph::graph::GraphData;
+use og_graph::slice::SliceDirection;
+use std::collections::HashSet;
+use std::sync::RwLock;
+
+/// Keeps the saved graph in memory, for when no database is set up
+#[derive(Default)]
+pub struct MemoryStore {
+    graph: RwLock<Option<GraphData>>,
+}
+
+#[async_trait]
+impl GraphStore for MemoryStore {
+    fn backend(&self) -> StoreBackend {
+        StoreBackend::Memory
+    }
+
+    async fn save(&self, graph: &GraphData) -> Result<(), StoreError> {
+        *self.graph.write().unwrap() = Some(graph.clone());
+        Ok(())
+    }
+
+    async fn load(&self) -> Result<Option<GraphData>, StoreError> {
+        Ok(self.graph.read().unwrap().clone())
+    }
+
+    async fn apply(&self, delta: &GraphDelta) -> Result<(), StoreError> {
+        let mut graph = self.graph.write().unwrap();
+        let applied = match graph.as_ref() {
+            Some(graph) => delta.apply_to(graph),
+            None => delta.apply_to(&GraphData::from_parts(Vec::new(), Vec::new())),
+        };
+        *graph = Some(applied);
+        Ok(())
+    }
+
+    async fn slice(
+        &self,
+        node_id: &str,
+        direction: SliceDirection,
+        max_depth: Option<usize>,
+    ) -> Result<Option<GraphData>, StoreError> {
+        let graph = self.graph.read().unwrap();
+        let Some(graph) = graph.as_ref().filter(|graph| graph.nodes.iter().any(|node| node.id == node_id)) else {
+            return Ok(None);
+        };
+
+        let mut keep = HashSet::from([node_id.to_string()]);
+        if matches!(direction, SliceDirection::Downstream | SliceDirection::Both) {
+            keep.extend(reachable(&graph.links, node_id, true, max_depth));
+        }
+        if matches!(direction, SliceDirection::Upstream | SliceDirection::Both) {
+            keep.extend(reachable(&graph.links, node_id, false, max_depth));
+        }
+        Ok(Some(induced(graph.nodes.clone(), graph.links.clone(), &keep)))
+    }
+
+    async fn clear(&self) -> Result<(), StoreError> {
+        *self.graph.write().unwrap() = None;
+        Ok(())
+    }
+}
@@ -0,0 +1,233 @@
+mod delta;
+mod memory;
+mod neo4j;
+mod sqlite;
+
+pub use delta::{link_key, sync, GraphDelta, LinkKey, SyncReport};
+pub use memory::MemoryStore;
+pub use neo4j::{Namespace, NamespaceInfo, Neo4jStore};
+pub use sqlite::SqliteStore;
+
+use async_trait::async_trait;
+use og_graph::attributes::AttrValue;
+use og_graph::graph::{GraphData, GraphLink, GraphNode};
+use og_graph::slice::SliceDirection;
+use og_types::CommandError;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use thiserror::Error;
+
+/// Where graphs are persisted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StoreBackend {
+    /// Kept in memory until the app closes
+    Memory,
+    /// A local SQLite file
+    Sqlite,
+    /// A Neo4j or other Bolt-speaking database such as Memgraph
+    Neo4j,
+}
+
+/// Why a graph store operation failed
+#[derive(Error, Debug)]
+pub enum StoreError {
+    #[error("SQLite error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+
+    #[error("Neo4j error: {0}")]
+    Neo4j(#[from] neo4rs::Error),
+
+    #[error("Unreadable stored graph: {0}")]
+    Corrupt(String),
+
+    #[error("Invalid namespace: {0}")]
+    Namespace(String),
+}
+
+impl From<StoreError> for CommandError {
+    fn from(error: StoreError) -> Self {
+        let message = error.to_string();
+        match error {
+            StoreError::Neo4j(neo4rs::Error::IOError { .. } | neo4rs::Error::ConnectionError) => Self::io(message),
+            StoreError::Namespace(_) => Self::invalid_input(message),
+            _ => Self::internal(message),
+        }
+    }
+}
+
+/// Persistence for analyzed graphs
+///
+/// Each store holds one graph, which `save` replaces as a whole.
+#[async_trait]
+pub trait GraphStore: Send + Sync {
+    fn backend(&self) -> StoreBackend;
+
+    /// Replace the stored graph
+    async fn save(&self, graph: &GraphData) -> Result<(), StoreError>;
+
+    /// The stored graph, or None if nothing was saved
+    async fn load(&self) -> Result<Option<GraphData>, StoreError>;
+
+    /// Apply changes to the stored graph, treating an empty store as an
+    /// empty graph
+    async fn apply(&self, delta: &GraphDelta) -> Result<(), StoreError>;
+
+    /// Transitive dependency slice of a stored node, as `CodeGraph::slice`
+    /// computes it, or None if the node is not stored
+    async fn slice(
+        &self,
+        node_id: &str,
+        direction: SliceDirection,
+        max_depth: Option<usize>,
+    ) -> Result<Option<GraphData>, StoreError>;
+
+    /// Remove the stored graph
+    async fn clear(&self) -> Result<(), StoreError>;
+}
+
+/// Node attributes as the JSON text both database stores keep them in
+fn attributes_json(attributes: &HashMap<String, AttrValue>) -> String {
+    serde_json::to_string(attributes).unwrap_or_else(|_| "{}".to_string())
+}
+
+/// Node attributes from their stored JSON text
+fn parse_attributes(json: &str) -> Result<HashMap<String, AttrValue>, StoreError> {
+    serde_json::from_str(json).map_err(|e| StoreError::Corrupt(format!("attributes: {}", e)))
+}
+
+/// Nodes reachable from `start` along links, forwards or backwards,
+/// within `max_depth` links when given
+fn reachable(links: &[GraphLink], start: &str, forwards: bool, max_depth: Option<usize>) -> HashSet<String> {
+    let mut next: HashMap<&str, Vec<&str>> = HashMap::new();
+    for link in links {
+        let (from, to) = if forwards { (&link.source, &link.target) } else { (&link.target, &link.source) };
+        next.entry(from.as_str()).or_default().push(to.as_str());
+    }
+
+    let mut seen = HashSet::from([start.to_string()]);
+    let mut queue = VecDeque::from([(start, 0)]);
+    while let Some((id, depth)) = queue.pop_front() {
+        if max_depth.is_some_and(|max| depth >= max) {
+            continue;
+        }
+        for to in next.get(id).into_iter().flatten() {
+            if seen.insert(to.to_string()) {
+                queue.push_back((to, depth + 1));
+            }
+        }
+    }
+    seen
+}
+
+/// The given nodes and the links between them
+fn induced(nodes: Vec<GraphNode>, links: Vec<GraphLink>, keep: &HashSet<String>) -> GraphData {
+    let nodes = nodes.into_iter().filter(|node| keep.contains(&node.id)).collect();
+    let links = links
+        .into_iter()
+        .filter(|link| keep.contains(&link.source) && keep.contains(&link.target))
+        .collect();
+    GraphData::from_parts(nodes, links)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use og_graph::testing::node;
+
+    fn graph() -> GraphData {
+        let node = |id: &str| GraphNode {
+            file_path: Some(format!("src/{}.py", id)),
+            ..node(id)
+        };
+        let link = |source: &str, target: &str| GraphLink {
+            source: source.to_string(),
+            target: target.to_string(),
+            link_type: "imports".to_string(),
+            value: 1.0,
+            count: 1,
+        };
+        GraphData::from_parts(
+            ["a", "b", "c", "d", "e"].into_iter().map(node).collect(),
+            vec![link("a", "b"), link("b", "c"), link("c", "a"), link("c", "d"), link("e", "a")],
+        )
+    }
+
+    fn ids(graph: &GraphData) -> Vec<&str> {
+        let mut ids: Vec<&str> = graph.nodes.iter().map(|node| node.id.as_str()).collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    async fn check_store(store: &dyn GraphStore) {
+        assert!(store.load().await.unwrap().is_none());
+        let mut saved = graph();
+        saved.nodes[3].set_attribute("loc", 42usize);
+        store.save(&saved).await.unwrap();
+
+        let loaded = store.load().await.unwrap().unwrap();
+        assert_eq!(ids(&loaded), vec!["a", "b", "c", "d", "e"]);
+        assert_eq!(loaded.links.len(), 5);
+        assert_eq!(loaded.nodes.iter().find(|n| n.id == "d").unwrap().file_path.as_deref(), Some("src/d.py"));
+        assert_eq!(loaded.nodes.iter().find(|n| n.id == "d").unwrap().number("loc"), Some(42.0));
+
+        let down = |depth| store.slice("a", SliceDirection::Downstream, depth);
+        assert_eq!(ids(&down(Some(1)).await.unwrap().unwrap()), vec!["a", "b"]);
+        assert_eq!(ids(&down(None).await.unwrap().unwrap()), vec!["a", "b", "c", "d"]);
+        let up = store.slice("a", SliceDirection::Upstream, Some(1)).await.unwrap().unwrap();
+        assert_eq!(ids(&up), vec!["a", "c", "e"]);
+        assert_eq!(up.links.len(), 2);
+        let both = store.slice("d", SliceDirection::Both, None).await.unwrap().unwrap();
+        assert_eq!(ids(&both), vec!["a", "b", "c", "d", "e"]);
+        assert!(store.slice("missing", SliceDirection::Both, None).await.unwrap().is_none());
+
+        let mut next = saved;
+        next.nodes.retain(|n| n.id != "e");
+        next.links.retain(|l| l.source != "e");
+        next.nodes[0].size = 20.0;
+        next.nodes[0].set_attribute("tags", vec!["entry".to_string()]);
+        next.links[0].value = 3.0;
+        next.links.push(GraphLink { source: "d".to_string(), ..next.links[0].clone() });
+        next.nodes.push(GraphNode { id: "f".to_string(), ..next.nodes[1].clone() });
+        let report = sync(store, None, &next).await.unwrap();
+        assert!(!report.full);
+        assert_eq!((report.added_nodes, report.updated_nodes, report.removed_nodes), (1, 1, 1));
+        assert_eq!((report.added_links, report.updated_links, report.removed_links), (1, 1, 1));
+        let synced = store.load().await.unwrap().unwrap();
+        assert_eq!(ids(&synced), vec!["a", "b", "c", "d", "f"]);
+        assert_eq!(synced.nodes.iter().find(|n| n.id == "a").unwrap().size, 20.0);
+        assert_eq!(synced.nodes.iter().find(|n| n.id == "a").unwrap().tags(), ["entry"]);
+        let mut links: Vec<_> = synced.links.iter().map(|l| (l.source.as_str(), l.target.as_str(), l.value)).collect();
+        links.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(links, vec![("a", "b", 3.0), ("b", "c", 1.0), ("c", "a", 1.0), ("c", "d", 1.0), ("d", "b", 3.0)]);
+        assert!(GraphDelta::between(&synced, &next).is_empty());
+
+        store.clear().await.unwrap();
+        assert!(store.load().await.unwrap().is_none());
+        assert!(sync(store, None, &next).await.unwrap().full);
+        store.clear().await.unwrap();
+    }
+
+    #[test]
+    fn test_namespace_requires_project_and_snapshot() {
+        assert!(Namespace::new("omnigraph", "main").is_ok());
+        assert!(matches!(Namespace::new("omnigraph", " "), Err(StoreError::Namespace(_))));
+        assert!(matches!(Namespace::new("", "main"), Err(StoreError::Namespace(_))));
+    }
+
+    #[tokio::test]
+    async fn test_memory_store() {
+        check_store(&MemoryStore::default()).await;
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_store() {
+        check_store(&SqliteStore::in_memory().unwrap()).await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("graph.db");
+        SqliteStore::open(&path).unwrap().save(&graph()).await.unwrap();
+        let reopened = SqliteStore::open(&path).unwrap().load().await.unwrap().unwrap();
+        assert_eq!(reopened.stats.link_count, 5);
+    }
+}
@@ -0,0 +1,355 @@
+use super::{attributes_json, parse_attributes, GraphDelta, GraphStore, StoreBackend, StoreError};
+use crate::cypher::Neo4jClient;
+use async_trait::async_trait;
+use neo4rs::{query, BoltType, Query, Row, Txn};
+use og_graph::graph::{GraphData, GraphLink, GraphNode};
+use og_graph::slice::SliceDirection;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+
+/// Nodes and links written per query when saving
+const BATCH_SIZE: usize = 1_000;
+
+const CREATE_INDEX: &str = "CREATE INDEX og_node_key IF NOT EXISTS FOR (n:OgNode) ON (n.project, n.snapshot, n.id)";
+
+const RETURN_NODE: &str = "RETURN n.id AS id, n.name AS name, n.type AS type, n.size AS size, \
+                           coalesce(n.visualSize, 10.0) AS visualSize, n.color AS color, n.filePath AS filePath, \
+                           coalesce(n.attributes, '{}') AS attributes";
+
+const CREATE_NODES: &str = "UNWIND $nodes AS n CREATE (:OgNode {project: $project, snapshot: $snapshot, id: n.id, \
+                            name: n.name, type: n.type, size: n.size, visualSize: n.visualSize, color: n.color, \
+                            filePath: n.filePath, attributes: n.attributes})";
+
+const MERGE_NODES: &str = "UNWIND $nodes AS n MERGE (o:OgNode {project: $project, snapshot: $snapshot, id: n.id}) \
+                           SET o.name = n.name, o.type = n.type, o.size = n.size, o.visualSize = n.visualSize, \
+                           o.color = n.color, o.filePath = n.filePath, o.attributes = n.attributes";
+
+const CREATE_LINKS: &str = "UNWIND $links AS l \
+                            MATCH (a:OgNode {project: $project, snapshot: $snapshot, id: l.source}), \
+                            (b:OgNode {project: $project, snapshot: $snapshot, id: l.target}) \
+                            CREATE (a)-[:OG_LINK {type: l.type, value: l.value}]->(b)";
+
+const UPDATE_LINKS: &str = "UNWIND $links AS l \
+                            MATCH (:OgNode {project: $project, snapshot: $snapshot, id: l.source})\
+                            -[r:OG_LINK {type: l.type}]->\
+                            (:OgNode {project: $project, snapshot: $snapshot, id: l.target}) \
+                            SET r.value = l.value";
+
+const DELETE_LINKS: &str = "UNWIND $links AS l \
+                            MATCH (:OgNode {project: $project, snapshot: $snapshot, id: l.source})\
+                            -[r:OG_LINK {type: l.type}]->\
+                            (:OgNode {project: $project, snapshot: $snapshot, id: l.target}) \
+                            DELETE r";
+
+const DELETE_NODES: &str = "UNWIND $ids AS id MATCH (n:OgNode {project: $project, snapshot: $snapshot, id: id}) \
+                            DETACH DELETE n";
+
+const MARK_SAVED: &str = "MERGE (m:OgMeta {project: $project, snapshot: $snapshot}) SET m.saved = true";
+
+const RETURN_LINK: &str = "RETURN a.id AS source, b.id AS target, r.type AS type, r.value AS value";
+
+/// The project and snapshot a stored graph belongs to
+///
+/// Graphs in different namespaces share a database without touching each
+/// other. Both parts are stored as properties on every node.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Namespace {
+    pub project: String,
+    pub snapshot: String,
+}
+
+impl Namespace {
+    pub fn new(project: &str, snapshot: &str) -> Result<Self, StoreError> {
+        if project.trim().is_empty() || snapshot.trim().is_empty() {
+            return Err(StoreError::Namespace("project and snapshot must not be empty".to_string()));
+        }
+        Ok(Self {
+            project: project.to_string(),
+            snapshot: snapshot.to_string(),
+        })
+    }
+}
+
+impl Default for Namespace {
+    fn default() -> Self {
+        Self {
+            project: "default".to_string(),
+            snapshot: "latest".to_string(),
+        }
+    }
+}
+
+/// A namespace found in the database
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NamespaceInfo {
+    #[serde(flatten)]
+    pub namespace: Namespace,
+    pub node_count: usize,
+}
+
+fn get<'row, T: Deserialize<'row>>(row: &'row Row, column: &str) -> Result<T, StoreError> {
+    row.get(column)
+        .map_err(|e| StoreError::Corrupt(format!("{}: {}", column, e)))
+}
+
+fn node_json(node: &GraphNode) -> serde_json::Value {
+    json!({
+        "id": node.id,
+        "name": node.name,
+        "type": node.node_type,
+        "size": node.size,
+        "visualSize": node.visual_size,
+        "color": node.color,
+        "filePath": node.file_path,
+        // Properties cannot hold maps, so attributes are kept as JSON text
+        "attributes": attributes_json(&node.attributes),
+    })
+}
+
+fn link_json(link: &GraphLink) -> serde_json::Value {
+    json!({ "source": link.source, "target": link.target, "type": link.link_type, "value": link.value })
+}
+
+/// Run `q` once per batch of `items`, bound to the `name` parameter
+async fn run_batched<'a, T: 'a>(
+    txn: &mut Txn,
+    q: Query,
+    name: &str,
+    items: impl IntoIterator<Item = &'a T>,
+    to_json: impl Fn(&T) -> serde_json::Value,
+) -> Result<(), StoreError> {
+    let items: Vec<&T> = items.into_iter().collect();
+    for chunk in items.chunks(BATCH_SIZE) {
+        let values = chunk
+            .iter()
+            .map(|item| BoltType::try_from(to_json(item)))
+            .collect::<Result<Vec<_>, _>>()?;
+        txn.run(q.clone().param(name, values)).await?;
+    }
+    Ok(())
+}
+
+impl Neo4jClient {
+    async fn rows(&self, q: Query) -> Result<Vec<Row>, StoreError> {
+        let mut stream = self.graph().execute(q).await?;
+        let mut rows = Vec::new();
+        while let Some(row) = stream.next().await? {
+            rows.push(row);
+        }
+        Ok(rows)
+    }
+
+    /// Namespaces holding a saved graph, by project then snapshot
+    pub async fn list_namespaces(&self) -> Result<Vec<NamespaceInfo>, StoreError> {
+        let q = query(
+            "MATCH (m:OgMeta) OPTIONAL MATCH (n:OgNode {project: m.project, snapshot: m.snapshot}) \
+             RETURN m.project AS project, m.snapshot AS snapshot, count(n) AS nodes \
+             ORDER BY project, snapshot",
+        );
+        self.rows(q)
+            .await?
+            .iter()
+            .map(|row| {
+                Ok(NamespaceInfo {
+                    namespace: Namespace {
+                        project: get(row, "project")?,
+                        snapshot: get(row, "snapshot")?,
+                    },
+                    node_count: get::<i64>(row, "nodes")? as usize,
+                })
+            })
+            .collect()
+    }
+
+    /// Delete one snapshot of a project, or all of them without `snapshot`,
+    /// returning how many snapshots were dropped
+    pub async fn drop_namespace(&self, project: &str, snapshot: Option<&str>) -> Result<usize, StoreError> {
+        let (filter, snapshot) = match snapshot {
+            Some(snapshot) => ("n.project = $project AND n.snapshot = $snapshot", snapshot),
+            None => ("n.project = $project", ""),
+        };
+        let count = format!("MATCH (n:OgMeta) WHERE {} RETURN count(n) AS dropped", filter);
+        let dropped = match self
+            .rows(query(&count).param("project", project).param("snapshot", snapshot))
+            .await?
+            .first()
+        {
+            Some(row) => get::<i64>(row, "dropped")? as usize,
+            None => 0,
+        };
+        let delete = format!("MATCH (n) WHERE (n:OgNode OR n:OgMeta) AND {} DETACH DELETE n", filter);
+        self.graph()
+            .run(query(&delete).param("project", project).param("snapshot", snapshot))
+            .await?;
+        Ok(dropped)
+    }
+}
+
+/// Stores graphs in one namespace of a Neo4j database, or any other
+/// Bolt-speaking database such as Memgraph
+pub struct Neo4jStore {
+    client: Arc<Neo4jClient>,
+    namespace: Namespace,
+}
+
+impl Neo4jStore {
+    pub fn new(client: Arc<Neo4jClient>, namespace: Namespace) -> Self {
+        Self { client, namespace }
+    }
+
+    pub fn namespace(&self) -> &Namespace {
+        &self.namespace
+    }
+
+    /// `cypher` with the namespace parameters bound
+    fn query(&self, cypher: &str) -> Query {
+        query(cypher)
+            .param("project", self.namespace.project.as_str())
+            .param("snapshot", self.namespace.snapshot.as_str())
+    }
+
+    /// Stored nodes passing `node_filter` on `n`, and the links among them
+    async fn read_graph(&self, node_filter: &str, ids: &[String]) -> Result<GraphData, StoreError> {
+        let cypher = format!(
+            "MATCH (n:OgNode {{project: $project, snapshot: $snapshot}}) WHERE {} {}",
+            node_filter, RETURN_NODE
+        );
+        let nodes = self
+            .client
+            .rows(self.query(&cypher).param("ids", ids.to_vec()))
+            .await?
+            .iter()
+            .map(|row| {
+                Ok(GraphNode {
+                    id: get(row, "id")?,
+                    name: get(row, "name")?,
+                    node_type: get(row, "type")?,
+                    size: get(row, "size")?,
+                    visual_size: get(row, "visualSize")?,
+                    color: get(row, "color")?,
+                    file_path: get(row, "filePath")?,
+                    attributes: parse_attributes(&get::<String>(row, "attributes")?)?,
+                })
+            })
+            .collect::<Result<Vec<_>, StoreError>>()?;
+        let link_filter = node_filter.replace("n.", "a.") + " AND " + &node_filter.replace("n.", "b.");
+        let cypher = format!(
+            "MATCH (a:OgNode {{project: $project, snapshot: $snapshot}})-[r:OG_LINK]->(b:OgNode) WHERE {} {}",
+            link_filter, RETURN_LINK
+        );
+        let links = self
+            .client
+            .rows(self.query(&cypher).param("ids", ids.to_vec()))
+            .await?
+            .iter()
+            .map(|row| {
+                Ok(GraphLink {
+                    source: get(row, "source")?,
+                    target: get(row, "target")?,
+                    link_type: get(row, "type")?,
+                    value: get(row, "value")?,
+                    count: 1,
+                })
+            })
+            .collect::<Result<Vec<_>, StoreError>>()?;
+        Ok(GraphData::from_parts(nodes, links))
+    }
+}
+
+#[async_trait]
+impl GraphStore for Neo4jStore {
+    fn backend(&self) -> StoreBackend {
+        StoreBackend::Neo4j
+    }
+
+    async fn save(&self, graph: &GraphData) -> Result<(), StoreError> {
+        self.client.graph().run(query(CREATE_INDEX)).await?;
+        let mut txn = self.client.graph().start_txn().await?;
+        txn.run(self.query("MATCH (n:OgNode {project: $project, snapshot: $snapshot}) DETACH DELETE n"))
+            .await?;
+        txn.run(self.query(MARK_SAVED)).await?;
+        run_batched(&mut txn, self.query(CREATE_NODES), "nodes", &graph.nodes, node_json).await?;
+        run_batched(&mut txn, self.query(CREATE_LINKS), "links", &graph.links, link_json).await?;
+        txn.commit().await?;
+        Ok(())
+    }
+
+    async fn apply(&self, delta: &GraphDelta) -> Result<(), StoreError> {
+        self.client.graph().run(query(CREATE_INDEX)).await?;
+        let mut txn = self.client.graph().start_txn().await?;
+        txn.run(self.query(MARK_SAVED)).await?;
+        run_batched(&mut txn, self.query(DELETE_LINKS), "links", &delta.removed_links, link_json).await?;
+        run_batched(&mut txn, self.query(DELETE_NODES), "ids", &delta.removed_nodes, |id| json!(id)).await?;
+        run_batched(&mut txn, self.query(MERGE_NODES), "nodes", delta.upserted_nodes(), node_json).await?;
+        run_batched(&mut txn, self.query(UPDATE_LINKS), "links", &delta.updated_links, link_json).await?;
+        run_batched(&mut txn, self.query(CREATE_LINKS), "links", &delta.added_links, link_json).await?;
+        txn.commit().await?;
+        Ok(())
+    }
+
+    async fn load(&self) -> Result<Option<GraphData>, StoreError> {
+        let saved = self
+            .client
+            .rows(self.query("MATCH (m:OgMeta {project: $project, snapshot: $snapshot}) RETURN m.saved AS saved"))
+            .await?;
+        if saved.is_empty() {
+            return Ok(None);
+        }
+        self.read_graph("true", &[]).await.map(Some)
+    }
+
+    async fn slice(
+        &self,
+        node_id: &str,
+        direction: SliceDirection,
+        max_depth: Option<usize>,
+    ) -> Result<Option<GraphData>, StoreError> {
+        let found = self
+            .client
+            .rows(
+                self.query("MATCH (n:OgNode {project: $project, snapshot: $snapshot, id: $id}) RETURN n.id AS id")
+                    .param("id", node_id),
+            )
+            .await?;
+        if found.is_empty() {
+            return Ok(None);
+        }
+
+        let mut ids = vec![node_id.to_string()];
+        let hops = match max_depth {
+            Some(0) => None,
+            Some(depth) => Some(format!("*1..{}", depth)),
+            None => Some("*1..".to_string()),
+        };
+        let mut patterns = Vec::new();
+        if matches!(direction, SliceDirection::Downstream | SliceDirection::Both) {
+            patterns.extend(hops.as_ref().map(|hops| format!("-[:OG_LINK{}]->", hops)));
+        }
+        if matches!(direction, SliceDirection::Upstream | SliceDirection::Both) {
+            patterns.extend(hops.as_ref().map(|hops| format!("<-[:OG_LINK{}]-", hops)));
+        }
+        // Links never cross namespaces, so the walk stays inside this one
+        for pattern in patterns {
+            let cypher = format!(
+                "MATCH (:OgNode {{project: $project, snapshot: $snapshot, id: $id}}){}(m:OgNode) \
+                 RETURN collect(DISTINCT m.id) AS ids",
+                pattern
+            );
+            for row in self.client.rows(self.query(&cypher).param("id", node_id)).await? {
+                ids.extend(get::<Vec<String>>(&row, "ids")?);
+            }
+        }
+        ids.sort_unstable();
+        ids.dedup();
+        self.read_graph("n.id IN $ids", &ids).await.map(Some)
+    }
+
+    async fn clear(&self) -> Result<(), StoreError> {
+        self.client
+            .drop_namespace(&self.namespace.project, Some(&self.namespace.snapshot))
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,282 @@
+use super::{attributes_json, parse_attributes, GraphDelta, GraphStore, StoreBackend, StoreError};
+use async_trait::async_trait;
+use og_graph::graph::{GraphData, GraphLink, GraphNode};
+use og_graph::slice::SliceDirection;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::Mutex;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS nodes (
+        id TEXT PRIMARY KEY,
+        name TEXT NOT NULL,
+        type TEXT NOT NULL,
+        size REAL NOT NULL,
+        visual_size REAL NOT NULL,
+        color TEXT NOT NULL,
+        file_path TEXT,
+        attributes TEXT NOT NULL DEFAULT '{}'
+    );
+    CREATE TABLE IF NOT EXISTS links (
+        source TEXT NOT NULL,
+        target TEXT NOT NULL,
+        type TEXT NOT NULL,
+        value REAL NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS links_source ON links (source);
+    CREATE INDEX IF NOT EXISTS links_target ON links (target);
+    CREATE TABLE IF NOT EXISTS meta (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+";
+
+/// Meta key present once a graph has been saved, so an empty graph can be
+/// told from none
+const SAVED_KEY: &str = "saved";
+
+/// Ids reachable from ?1 along links, following `from` to `to` columns,
+/// within ?2 links when `limited`
+///
+/// The unlimited form leaves out the depth so `UNION` stops at cycles.
+fn reachable_sql(forwards: bool, limited: bool) -> String {
+    let (from, to) = if forwards { ("source", "target") } else { ("target", "source") };
+    if limited {
+        format!(
+            "WITH RECURSIVE reach(id, depth) AS (
+                SELECT ?1, 0
+                UNION
+                SELECT links.{to}, reach.depth + 1 FROM links JOIN reach ON links.{from} = reach.id
+                WHERE reach.depth < ?2
+            )
+            SELECT DISTINCT id FROM reach"
+        )
+    } else {
+        format!(
+            "WITH RECURSIVE reach(id) AS (
+                SELECT ?1
+                UNION
+                SELECT links.{to} FROM links JOIN reach ON links.{from} = reach.id
+            )
+            SELECT id FROM reach"
+        )
+    }
+}
+
+/// Stores the graph in a SQLite file and walks it with recursive queries
+pub struct SqliteStore {
+    connection: Mutex<Connection>,
+}
+
+impl SqliteStore {
+    /// Open or create the store at `path`
+    pub fn open(path: &Path) -> Result<Self, StoreError> {
+        Self::with_connection(Connection::open(path)?)
+    }
+
+    /// A store that lives only as long as this value
+    pub fn in_memory() -> Result<Self, StoreError> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(connection: Connection) -> Result<Self, StoreError> {
+        connection.execute_batch(SCHEMA)?;
+        Ok(Self {
+            connection: Mutex::new(connection),
+        })
+    }
+
+    fn read_graph(connection: &Connection, nodes_sql: &str, links_sql: &str) -> Result<GraphData, StoreError> {
+        let nodes = connection
+            .prepare(nodes_sql)?
+            .query_map([], |row| {
+                let attributes: String = row.get(7)?;
+                Ok((
+                    GraphNode {
+                        id: row.get(0)?,
+                        name: row.get(1)?,
+                        node_type: row.get(2)?,
+                        size: row.get(3)?,
+                        visual_size: row.get(4)?,
+                        color: row.get(5)?,
+                        file_path: row.get(6)?,
+                        attributes: Default::default(),
+                    },
+                    attributes,
+                ))
+            })?
+            .map(|row| {
+                let (mut node, attributes) = row?;
+                node.attributes = parse_attributes(&attributes)?;
+                Ok(node)
+            })
+            .collect::<Result<Vec<_>, StoreError>>()?;
+        let links = connection
+            .prepare(links_sql)?
+            .query_map([], |row| {
+                Ok(GraphLink {
+                    source: row.get(0)?,
+                    target: row.get(1)?,
+                    link_type: row.get(2)?,
+                    value: row.get(3)?,
+                    count: 1,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(GraphData::from_parts(nodes, links))
+    }
+}
+
+#[async_trait]
+impl GraphStore for SqliteStore {
+    fn backend(&self) -> StoreBackend {
+        StoreBackend::Sqlite
+    }
+
+    async fn save(&self, graph: &GraphData) -> Result<(), StoreError> {
+        let mut connection = self.connection.lock().unwrap();
+        let tx = connection.transaction()?;
+        tx.execute_batch("DELETE FROM nodes; DELETE FROM links;")?;
+        {
+            let mut insert = tx.prepare("INSERT OR REPLACE INTO nodes VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)")?;
+            for node in &graph.nodes {
+                insert.execute(params![
+                    node.id,
+                    node.name,
+                    node.node_type,
+                    node.size,
+                    node.visual_size,
+                    node.color,
+                    node.file_path,
+                    attributes_json(&node.attributes)
+                ])?;
+            }
+            let mut insert = tx.prepare("INSERT INTO links VALUES (?1, ?2, ?3, ?4)")?;
+            for link in &graph.links {
+                insert.execute(params![link.source, link.target, link.link_type, link.value])?;
+            }
+        }
+        tx.execute("INSERT OR REPLACE INTO meta VALUES (?1, '1')", [SAVED_KEY])?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    async fn apply(&self, delta: &GraphDelta) -> Result<(), StoreError> {
+        let mut connection = self.connection.lock().unwrap();
+        let tx = connection.transaction()?;
+        {
+            let mut delete = tx.prepare("DELETE FROM links WHERE source = ?1 AND target = ?2 AND type = ?3")?;
+            for link in &delta.removed_links {
+                delete.execute(params![link.source, link.target, link.link_type])?;
+            }
+            let mut delete = tx.prepare("DELETE FROM nodes WHERE id = ?1")?;
+            for id in &delta.removed_nodes {
+                delete.execute([id])?;
+            }
+            // Upsert rather than replace so updated nodes keep their rowid and order
+            let mut upsert = tx.prepare(
+                "INSERT INTO nodes VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8) ON CONFLICT (id) DO UPDATE SET
+                 name = excluded.name, type = excluded.type, size = excluded.size,
+                 visual_size = excluded.visual_size, color = excluded.color, file_path = excluded.file_path,
+                 attributes = excluded.attributes",
+            )?;
+            for node in delta.upserted_nodes() {
+                upsert.execute(params![
+                    node.id,
+                    node.name,
+                    node.node_type,
+                    node.size,
+                    node.visual_size,
+                    node.color,
+                    node.file_path,
+                    attributes_json(&node.attributes)
+                ])?;
+            }
+            let mut update = tx.prepare("UPDATE links SET value = ?4 WHERE source = ?1 AND target = ?2 AND type = ?3")?;
+            for link in &delta.updated_links {
+                update.execute(params![link.source, link.target, link.link_type, link.value])?;
+            }
+            let mut insert = tx.prepare("INSERT INTO links VALUES (?1, ?2, ?3, ?4)")?;
+            for link in &delta.added_links {
+                insert.execute(params![link.source, link.target, link.link_type, link.value])?;
+            }
+        }
+        tx.execute("INSERT OR REPLACE INTO meta VALUES (?1, '1')", [SAVED_KEY])?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    async fn load(&self) -> Result<Option<GraphData>, StoreError> {
+        let connection = self.connection.lock().unwrap();
+        let saved = connection
+            .query_row("SELECT 1 FROM meta WHERE key = ?1", [SAVED_KEY], |_| Ok(()))
+            .optional()?;
+        if saved.is_none() {
+            return Ok(None);
+        }
+        Self::read_graph(
+            &connection,
+            "SELECT id, name, type, size, visual_size, color, file_path, attributes FROM nodes ORDER BY rowid",
+            "SELECT source, target, type, value FROM links ORDER BY rowid",
+        )
+        .map(Some)
+    }
+
+    async fn slice(
+        &self,
+        node_id: &str,
+        direction: SliceDirection,
+        max_depth: Option<usize>,
+    ) -> Result<Option<GraphData>, StoreError> {
+        let mut connection = self.connection.lock().unwrap();
+        let exists = connection
+            .query_row("SELECT 1 FROM nodes WHERE id = ?1", [node_id], |_| Ok(()))
+            .optional()?;
+        if exists.is_none() {
+            return Ok(None);
+        }
+
+        let mut keep = HashSet::from([node_id.to_string()]);
+        let mut walks = Vec::new();
+        if matches!(direction, SliceDirection::Downstream | SliceDirection::Both) {
+            walks.push(true);
+        }
+        if matches!(direction, SliceDirection::Upstream | SliceDirection::Both) {
+            walks.push(false);
+        }
+        for forwards in walks {
+            let mut statement = connection.prepare(&reachable_sql(forwards, max_depth.is_some()))?;
+            let ids: Vec<String> = match max_depth {
+                Some(depth) => statement
+                    .query_map(params![node_id, depth as i64], |row| row.get(0))?
+                    .collect::<Result<_, _>>()?,
+                None => statement.query_map([node_id], |row| row.get(0))?.collect::<Result<_, _>>()?,
+            };
+            keep.extend(ids);
+        }
+
+        let tx = connection.transaction()?;
+        tx.execute_batch("CREATE TEMP TABLE IF NOT EXISTS keep (id TEXT PRIMARY KEY); DELETE FROM keep;")?;
+        {
+            let mut insert = tx.prepare("INSERT INTO keep VALUES (?1)")?;
+            for id in &keep {
+                insert.execute([id])?;
+            }
+        }
+        let slice = Self::read_graph(
+            &tx,
+            "SELECT id, name, type, size, visual_size, color, file_path, attributes FROM nodes WHERE id IN (SELECT id FROM keep) ORDER BY rowid",
+            "SELECT source, target, type, value FROM links
+             WHERE source IN (SELECT id FROM keep) AND target IN (SELECT id FROM keep) ORDER BY rowid",
+        )?;
+        tx.execute_batch("DROP TABLE keep;")?;
+        tx.commit()?;
+        Ok(Some(slice))
+    }
+
+    async fn clear(&self) -> Result<(), StoreError> {
+        self.connection
+            .lock()
+            .unwrap()
+            .execute_batch("DELETE FROM nodes; DELETE FROM links; DELETE FROM meta;")?;
+        Ok(())
+    }
+}
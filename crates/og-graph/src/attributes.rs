@@ -0,0 +1,154 @@
+use crate::graph::GraphNode;
+use serde::{Deserialize, Serialize};
+
+/// Lines of code a node spans, as measured by its parser
+pub const LOC_ATTRIBUTE: &str = "loc";
+
+/// Line of its file the node starts on, counting from 1
+pub const LINE_ATTRIBUTE: &str = "line";
+
+/// Cyclomatic complexity of a function, method or file
+pub const COMPLEXITY_ATTRIBUTE: &str = "complexity";
+
+/// Commits touching the node's file
+pub const CHURN_ATTRIBUTE: &str = "churn";
+
+/// Share of the node's lines covered by tests, from 0 to 1
+pub const COVERAGE_ATTRIBUTE: &str = "coverage";
+
+/// Free-form labels attached to the node
+pub const TAGS_ATTRIBUTE: &str = "tags";
+
+/// Value of one per-node attribute
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum AttrValue {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Text(String),
+    List(Vec<String>),
+}
+
+impl AttrValue {
+    /// The value as a number, when it is one
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            AttrValue::Int(value) => Some(*value as f64),
+            AttrValue::Float(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            AttrValue::Text(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&[String]> {
+        match self {
+            AttrValue::List(values) => Some(values),
+            _ => None,
+        }
+    }
+}
+
+impl From<bool> for AttrValue {
+    fn from(value: bool) -> Self {
+        AttrValue::Bool(value)
+    }
+}
+
+impl From<i64> for AttrValue {
+    fn from(value: i64) -> Self {
+        AttrValue::Int(value)
+    }
+}
+
+impl From<usize> for AttrValue {
+    fn from(value: usize) -> Self {
+        AttrValue::Int(value as i64)
+    }
+}
+
+impl From<f64> for AttrValue {
+    fn from(value: f64) -> Self {
+        AttrValue::Float(value)
+    }
+}
+
+impl From<String> for AttrValue {
+    fn from(value: String) -> Self {
+        AttrValue::Text(value)
+    }
+}
+
+impl From<&str> for AttrValue {
+    fn from(value: &str) -> Self {
+        AttrValue::Text(value.to_string())
+    }
+}
+
+impl From<Vec<String>> for AttrValue {
+    fn from(values: Vec<String>) -> Self {
+        AttrValue::List(values)
+    }
+}
+
+impl GraphNode {
+    pub fn attribute(&self, key: &str) -> Option<&AttrValue> {
+        self.attributes.get(key)
+    }
+
+    /// Numeric value of an attribute, if it is set and a number
+    pub fn number(&self, key: &str) -> Option<f64> {
+        self.attribute(key).and_then(AttrValue::as_f64)
+    }
+
+    /// Set an attribute, returning the value it replaces
+    pub fn set_attribute(&mut self, key: impl Into<String>, value: impl Into<AttrValue>) -> Option<AttrValue> {
+        self.attributes.insert(key.into(), value.into())
+    }
+
+    pub fn remove_attribute(&mut self, key: &str) -> Option<AttrValue> {
+        self.attributes.remove(key)
+    }
+
+    /// Labels in the `tags` attribute, empty when there are none
+    pub fn tags(&self) -> &[String] {
+        self.attribute(TAGS_ATTRIBUTE).and_then(AttrValue::as_list).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    #[test]
+    fn test_attributes_round_trip_through_json() {
+        let mut node = GraphNode {
+            id: "file:a.py".to_string(),
+            size: 12.0,
+            ..testing::node("a.py")
+        };
+        assert_eq!(node.set_attribute(LOC_ATTRIBUTE, 12usize), None);
+        node.set_attribute(COVERAGE_ATTRIBUTE, 0.5);
+        node.set_attribute(TAGS_ATTRIBUTE, vec!["legacy".to_string()]);
+        assert_eq!(node.number(LOC_ATTRIBUTE), Some(12.0));
+        assert_eq!(node.tags(), ["legacy"]);
+
+        let json = serde_json::to_string(&node).unwrap();
+        let back: GraphNode = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, node);
+
+        let bare: GraphNode = serde_json::from_str(
+            r##"{"id": "a", "name": "a", "type": "file", "size": 1.0, "color": "#fff", "filePath": null}"##,
+        )
+        .unwrap();
+        assert!(bare.attributes.is_empty(), "nodes saved before attributes still load");
+        assert!(bare.tags().is_empty());
+    }
+}
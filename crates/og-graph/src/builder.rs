@@ -1,8 +1,46 @@
-use crate::graph::{CodeGraph, GraphEdge, GraphNode};
+use crate::graph::{CodeGraph, GraphEdge, GraphNode, ParallelEdgePolicy};
 use og_types::{AstNode, NodeType, ParsedFile, Relationship, RelationshipType};
 use std::collections::HashMap;
 use tracing::{debug, info};
 
+/// Lines a parsed node spans
+///
+/// File nodes end at their line count, other nodes at their last line, so
+/// only the latter include the end line.
+pub fn lines_of_code(node: &AstNode) -> f64 {
+    let lines = node.end_line.saturating_sub(node.start_line);
+    match node.node_type {
+        NodeType::File => lines as f64,
+        _ => (lines + 1) as f64,
+    }
+}
+
+/// Weight of a single relationship of the given type
+///
+/// Inheritance ties nodes together most tightly, imports and definitions
+/// next, then calls and data flow, while containment and plain references
+/// weigh least. Repeated relationships add their weights together.
+pub fn relationship_weight(rel_type: &RelationshipType) -> f64 {
+    match rel_type {
+        RelationshipType::Contains => 1.0,
+        RelationshipType::Imports => 2.0,
+        RelationshipType::Exports => 2.0,
+        RelationshipType::Extends => 3.0,
+        RelationshipType::Implements => 3.0,
+        RelationshipType::Calls => 1.5,
+        RelationshipType::References => 1.0,
+        RelationshipType::RoutesTo => 2.0,
+        RelationshipType::Decorates => 1.0,
+        RelationshipType::DependsOn => 1.5,
+        RelationshipType::DefinedIn => 2.0,
+        RelationshipType::Reads => 1.0,
+        RelationshipType::Writes => 1.5,
+        RelationshipType::Publishes => 1.5,
+        RelationshipType::Subscribes => 1.5,
+        RelationshipType::ResolvedBy => 2.0,
+    }
+}
+
 /// Graph builder that converts parsed files into a code graph
 pub struct GraphBuilder {
     graph: CodeGraph,
@@ -30,6 +68,12 @@ impl GraphBuilder {
         }
     }
 
+    /// Fold repeated relationships between the same nodes by the given policy
+    pub fn with_parallel_edges(mut self, policy: ParallelEdgePolicy) -> Self {
+        self.graph.parallel_edges = policy;
+        self
+    }
+
     /// Build graph from parsed files
     pub fn build_from_files(mut self, files: Vec<ParsedFile>) -> CodeGraph {
         info!("Building graph from {} parsed files", files.len());
@@ -60,7 +104,7 @@ impl GraphBuilder {
     /// Add an AST node to the graph
     fn add_ast_node(&mut self, node: &AstNode, file_path: &str) {
         let node_type_str = node.node_type.as_str();
-        let size = self.calculate_node_size(node);
+        let visual_size = self.calculate_visual_size(node);
         let color = self
             .color_map
             .get(node_type_str)
@@ -71,9 +115,11 @@ impl GraphBuilder {
             id: node.id.clone(),
             name: node.name.clone(),
             node_type: node_type_str.to_string(),
-            size,
+            size: lines_of_code(node),
+            visual_size,
             color,
             file_path: Some(file_path.to_string()),
+            attributes: HashMap::new(),
         };
 
         self.graph.add_node(graph_node);
@@ -88,7 +134,8 @@ impl GraphBuilder {
         {
             let edge = GraphEdge {
                 edge_type: relationship.relationship_type.as_str().to_string(),
-                weight: self.calculate_edge_weight(&relationship.relationship_type),
+                weight: relationship_weight(&relationship.relationship_type),
+                count: 1,
             };
 
             self.graph
@@ -109,7 +156,7 @@ impl GraphBuilder {
     }
 
     /// Calculate node size based on its type and metrics
-    fn calculate_node_size(&self, node: &AstNode) -> f64 {
+    fn calculate_visual_size(&self, node: &AstNode) -> f64 {
         let base_size = match node.node_type {
             NodeType::File | NodeType::Module => 20.0,
             NodeType::Class | NodeType::Interface => 15.0,
@@ -118,21 +165,7 @@ impl GraphBuilder {
         };
 
         // Scale by lines of code
-        let line_count = (node.end_line - node.start_line + 1) as f64;
-        base_size + (line_count / 10.0).min(10.0)
-    }
-
-    /// Calculate edge weight based on relationship type
-    fn calculate_edge_weight(&self, rel_type: &RelationshipType) -> f64 {
-        match rel_type {
-            RelationshipType::Contains => 1.0,
-            RelationshipType::Imports => 2.0,
-            RelationshipType::Exports => 2.0,
-            RelationshipType::Extends => 3.0,
-            RelationshipType::Implements => 3.0,
-            RelationshipType::Calls => 1.5,
-            RelationshipType::References => 1.0,
-        }
+        base_size + (lines_of_code(node) / 10.0).min(10.0)
     }
 }
 
@@ -0,0 +1,142 @@
+use crate::graph::{GraphData, GraphLink};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+/// Which edges a graph request returns
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EdgeMode {
+    /// Every link as it is
+    #[default]
+    Raw,
+    /// Links between directories merged into one edge per directory pair,
+    /// links within a directory as they are
+    Bundled,
+    /// Every link, plus the bundles they would merge into
+    Both,
+}
+
+/// All links from one directory to another, merged
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundledEdge {
+    /// Directory of the source nodes
+    pub source: String,
+    /// Directory of the target nodes
+    pub target: String,
+    /// Number of links merged
+    pub count: usize,
+    /// Sum of the merged links' values
+    pub weight: f64,
+    /// Number of merged links of each type
+    pub link_types: BTreeMap<String, usize>,
+}
+
+impl GraphData {
+    /// Split links into those kept as they are and bundles between directories
+    ///
+    /// Directories are read from the nodes of this graph, so `links` may be
+    /// any subset of its links. Links with an endpoint that has no file path
+    /// are never bundled. Bundles are ordered by directory pair.
+    pub fn bundle_links(&self, links: Vec<GraphLink>, mode: EdgeMode) -> (Vec<GraphLink>, Vec<BundledEdge>) {
+        if mode == EdgeMode::Raw {
+            return (links, Vec::new());
+        }
+
+        let directories: HashMap<&str, String> = self
+            .nodes
+            .iter()
+            .filter_map(|node| {
+                let dir = Path::new(node.file_path.as_deref()?).parent()?;
+                Some((node.id.as_str(), dir.display().to_string()))
+            })
+            .collect();
+
+        let mut kept = Vec::new();
+        let mut bundles: BTreeMap<(String, String), BundledEdge> = BTreeMap::new();
+        for link in links {
+            let pair = match (directories.get(link.source.as_str()), directories.get(link.target.as_str())) {
+                (Some(source), Some(target)) if source != target => (source.clone(), target.clone()),
+                _ => {
+                    kept.push(link);
+                    continue;
+                }
+            };
+            let bundle = bundles.entry(pair).or_insert_with_key(|(source, target)| BundledEdge {
+                source: source.clone(),
+                target: target.clone(),
+                count: 0,
+                weight: 0.0,
+                link_types: BTreeMap::new(),
+            });
+            bundle.count += 1;
+            bundle.weight += link.value;
+            *bundle.link_types.entry(link.link_type.clone()).or_insert(0) += 1;
+            if mode == EdgeMode::Both {
+                kept.push(link);
+            }
+        }
+
+        (kept, bundles.into_values().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::GraphNode;
+    use crate::testing;
+
+    fn node(id: &str, file_path: Option<&str>) -> GraphNode {
+        GraphNode {
+            file_path: file_path.map(str::to_string),
+            ..testing::node(id)
+        }
+    }
+
+    fn link(source: &str, target: &str, link_type: &str) -> GraphLink {
+        GraphLink {
+            source: source.to_string(),
+            target: target.to_string(),
+            link_type: link_type.to_string(),
+            value: 1.0,
+            count: 1,
+        }
+    }
+
+    #[test]
+    fn test_cross_directory_links_are_bundled() {
+        let data = GraphData::from_parts(
+            vec![
+                node("a1", Some("/repo/a/one.py")),
+                node("a2", Some("/repo/a/two.py")),
+                node("b1", Some("/repo/b/one.py")),
+                node("ext", None),
+            ],
+            vec![
+                link("a1", "b1", "imports"),
+                link("a2", "b1", "imports"),
+                link("a2", "b1", "calls"),
+                link("a1", "a2", "imports"),
+                link("a1", "ext", "imports"),
+            ],
+        );
+
+        let (raw, bundles) = data.bundle_links(data.links.clone(), EdgeMode::Raw);
+        assert_eq!(raw.len(), 5);
+        assert!(bundles.is_empty());
+
+        let (kept, bundles) = data.bundle_links(data.links.clone(), EdgeMode::Bundled);
+        assert_eq!(kept.len(), 2);
+        assert_eq!(bundles.len(), 1);
+        assert_eq!((bundles[0].source.as_str(), bundles[0].target.as_str()), ("/repo/a", "/repo/b"));
+        assert_eq!(bundles[0].count, 3);
+        assert_eq!(bundles[0].weight, 3.0);
+        assert_eq!(bundles[0].link_types["imports"], 2);
+
+        let (kept, bundles) = data.bundle_links(data.links.clone(), EdgeMode::Both);
+        assert_eq!(kept.len(), 5);
+        assert_eq!(bundles[0].count, 3);
+    }
+}
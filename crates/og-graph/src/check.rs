@@ -0,0 +1,270 @@
+use crate::graph::CodeGraph;
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Rules a change is checked against before it is committed or pushed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CheckRules {
+    /// Fail when a change adds a dependency cycle through a changed file
+    pub fail_on_cycles: bool,
+    /// Fail when a changed file or class grows into a god object
+    pub fail_on_god_objects: bool,
+    /// Members a file or class may contain before it counts as a god object
+    pub max_members: usize,
+    /// Distinct nodes a file or class may depend on before it counts as a god object
+    pub max_dependencies: usize,
+    /// Dependency hops around the changed files that are searched for cycles
+    pub neighborhood_depth: usize,
+}
+
+impl Default for CheckRules {
+    fn default() -> Self {
+        Self {
+            fail_on_cycles: true,
+            fail_on_god_objects: true,
+            max_members: 40,
+            max_dependencies: 25,
+            neighborhood_depth: 2,
+        }
+    }
+}
+
+/// Kind of rule a change broke
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ViolationKind {
+    Cycle,
+    GodObject,
+}
+
+/// A rule broken by a change
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckViolation {
+    pub kind: ViolationKind,
+    /// Ids of the nodes involved
+    pub nodes: Vec<String>,
+    pub message: String,
+}
+
+/// Result of checking a change
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckReport {
+    /// Nodes defined in the changed files
+    pub changed_nodes: usize,
+    /// Nodes in the dependency neighborhood that was searched
+    pub checked_nodes: usize,
+    pub violations: Vec<CheckViolation>,
+}
+
+impl CheckReport {
+    pub fn passed(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+fn is_contains(edge_type: &str) -> bool {
+    edge_type.eq_ignore_ascii_case("contains")
+}
+
+impl CodeGraph {
+    /// Check the nodes of changed files against the rules, compared with
+    /// `baseline`, the graph before the change
+    ///
+    /// Only problems the change introduces are reported. A cycle through a
+    /// changed file counts unless its nodes were already on one cycle in
+    /// `baseline`, and a god object defined in a changed file counts unless it
+    /// was already over the limits there. Cycles are searched within
+    /// `neighborhood_depth` hops of the changed files. Pass an empty baseline
+    /// to report every problem.
+    pub fn check_changes(&self, baseline: &CodeGraph, changed_files: &[String], rules: &CheckRules) -> CheckReport {
+        let changed_files: HashSet<&str> = changed_files.iter().map(String::as_str).collect();
+        let changed: Vec<_> = self
+            .graph
+            .node_indices()
+            .filter(|&idx| {
+                self.graph[idx]
+                    .file_path
+                    .as_deref()
+                    .is_some_and(|path| changed_files.contains(path))
+            })
+            .collect();
+
+        let mut neighborhood = HashSet::new();
+        for &idx in &changed {
+            neighborhood.extend(self.reachable(idx, Direction::Outgoing, Some(rules.neighborhood_depth)));
+            neighborhood.extend(self.reachable(idx, Direction::Incoming, Some(rules.neighborhood_depth)));
+        }
+
+        let mut violations = Vec::new();
+        if rules.fail_on_cycles {
+            let changed_ids: HashSet<&str> = changed.iter().map(|&idx| self.graph[idx].id.as_str()).collect();
+            let subgraph = CodeGraph::from_graph_data(&self.induced_subgraph(&neighborhood));
+            let existing: Vec<HashSet<String>> = baseline
+                .condensed()
+                .cycle_members
+                .into_values()
+                .map(|members| members.into_iter().collect())
+                .collect();
+            for members in subgraph.condensed().cycle_members.into_values() {
+                let predates = existing
+                    .iter()
+                    .any(|cycle| members.iter().all(|id| cycle.contains(id)));
+                if !predates && members.iter().any(|id| changed_ids.contains(id.as_str())) {
+                    violations.push(CheckViolation {
+                        kind: ViolationKind::Cycle,
+                        message: format!("Dependency cycle of {} nodes: {}", members.len(), members.join(", ")),
+                        nodes: members,
+                    });
+                }
+            }
+        }
+
+        if rules.fail_on_god_objects {
+            for &idx in &changed {
+                let node = &self.graph[idx];
+                if node.node_type != "file" && node.node_type != "class" {
+                    continue;
+                }
+                let Some((members, dependencies)) = self.oversized(idx, rules) else {
+                    continue;
+                };
+                let predates = baseline
+                    .node_map
+                    .get(&node.id)
+                    .is_some_and(|&before| baseline.oversized(before, rules).is_some());
+                if !predates {
+                    violations.push(CheckViolation {
+                        kind: ViolationKind::GodObject,
+                        nodes: vec![node.id.clone()],
+                        message: format!(
+                            "{} {} has {} members and {} dependencies (limits {} and {})",
+                            node.node_type,
+                            node.name,
+                            members,
+                            dependencies,
+                            rules.max_members,
+                            rules.max_dependencies
+                        ),
+                    });
+                }
+            }
+        }
+
+        CheckReport {
+            changed_nodes: changed.len(),
+            checked_nodes: neighborhood.len(),
+            violations,
+        }
+    }
+
+    /// Member and dependency counts of a file or class, if either is over its limit
+    fn oversized(&self, idx: NodeIndex, rules: &CheckRules) -> Option<(usize, usize)> {
+        let members: HashSet<_> = self
+            .graph
+            .edges(idx)
+            .filter(|edge| is_contains(&edge.weight().edge_type))
+            .map(|edge| edge.target())
+            .collect();
+        let dependencies: HashSet<_> = std::iter::once(idx)
+            .chain(members.iter().copied())
+            .flat_map(|source| self.graph.edges(source))
+            .filter(|edge| !is_contains(&edge.weight().edge_type))
+            .map(|edge| edge.target())
+            .filter(|target| *target != idx && !members.contains(target))
+            .collect();
+
+        (members.len() > rules.max_members || dependencies.len() > rules.max_dependencies)
+            .then_some((members.len(), dependencies.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::GraphNode;
+    use crate::testing::{edge, node};
+
+    fn add(graph: &mut CodeGraph, id: &str, node_type: &str, file: &str) {
+        graph.add_node(GraphNode {
+            node_type: node_type.to_string(),
+            file_path: Some(file.to_string()),
+            ..node(id)
+        });
+    }
+
+    fn link(graph: &mut CodeGraph, source: &str, target: &str, edge_type: &str) {
+        graph.add_edge(source, target, edge(edge_type));
+    }
+
+    // a <-> b form a cycle, c is unrelated and d is large
+    fn sample() -> CodeGraph {
+        let mut graph = CodeGraph::new();
+        for file in ["a", "b", "c", "d"] {
+            add(&mut graph, file, "file", file);
+        }
+        link(&mut graph, "a", "b", "imports");
+        link(&mut graph, "b", "a", "imports");
+        link(&mut graph, "c", "d", "imports");
+        for i in 0..5 {
+            let member = format!("d::f{}", i);
+            add(&mut graph, &member, "function", "d");
+            link(&mut graph, "d", &member, "contains");
+        }
+        graph
+    }
+
+    #[test]
+    fn test_cycle_through_changed_file_fails() {
+        let baseline = CodeGraph::new();
+        let report = sample().check_changes(&baseline, &["a".to_string()], &CheckRules::default());
+        assert!(!report.passed());
+        assert_eq!(report.violations[0].kind, ViolationKind::Cycle);
+        assert_eq!(report.violations[0].nodes, vec!["a", "b"]);
+
+        let report = sample().check_changes(&baseline, &["c".to_string()], &CheckRules::default());
+        assert!(report.passed());
+    }
+
+    #[test]
+    fn test_god_object_in_changed_file_fails() {
+        let rules = CheckRules {
+            max_members: 4,
+            ..CheckRules::default()
+        };
+        let report = sample().check_changes(&CodeGraph::new(), &["d".to_string()], &rules);
+        assert_eq!(report.changed_nodes, 6);
+        assert_eq!(report.violations.len(), 1);
+        assert_eq!(report.violations[0].kind, ViolationKind::GodObject);
+
+        let lenient = CheckRules {
+            fail_on_god_objects: false,
+            ..rules
+        };
+        assert!(sample().check_changes(&CodeGraph::new(), &["d".to_string()], &lenient).passed());
+    }
+
+    #[test]
+    fn test_problems_in_baseline_do_not_fail() {
+        let rules = CheckRules {
+            max_members: 4,
+            ..CheckRules::default()
+        };
+        let changed = ["a".to_string(), "d".to_string()];
+        assert!(sample().check_changes(&sample(), &changed, &rules).passed());
+
+        // Pulling c into the existing cycle makes a new, larger one
+        let mut grown = sample();
+        link(&mut grown, "b", "c", "imports");
+        link(&mut grown, "c", "a", "imports");
+        let report = grown.check_changes(&sample(), &changed, &rules);
+        assert_eq!(report.violations.len(), 1);
+        assert_eq!(report.violations[0].kind, ViolationKind::Cycle);
+        assert_eq!(report.violations[0].nodes, vec!["a", "b", "c"]);
+    }
+}
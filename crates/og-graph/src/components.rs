@@ -0,0 +1,122 @@
+use crate::graph::CodeGraph;
+use petgraph::unionfind::UnionFind;
+use petgraph::visit::EdgeRef;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Components at or below this size are reported as orphans by default
+pub const DEFAULT_ORPHAN_MAX_SIZE: usize = 2;
+
+/// Weakly connected component of the file import graph
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileComponent {
+    pub id: usize,
+    pub size: usize,
+    pub files: Vec<String>,
+    pub is_main: bool,
+}
+
+/// Size-ranked components plus files disconnected from the main application
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentReport {
+    pub total_files: usize,
+    pub components: Vec<FileComponent>,
+    pub orphan_files: Vec<String>,
+}
+
+impl CodeGraph {
+    /// Find weakly connected components of the file import graph
+    ///
+    /// Files in components no larger than `orphan_max_size` (other than the
+    /// main one) are listed as orphans: often dead code, scripts, or broken imports.
+    pub fn import_components(&self, orphan_max_size: usize) -> ComponentReport {
+        let files: Vec<_> = self
+            .graph
+            .node_indices()
+            .filter(|&idx| self.graph[idx].node_type == "file")
+            .collect();
+        let position: HashMap<_, _> = files.iter().enumerate().map(|(i, &idx)| (idx, i)).collect();
+
+        let mut union_find = UnionFind::new(files.len());
+        for edge in self.graph.edge_references() {
+            if !edge.weight().edge_type.eq_ignore_ascii_case("imports") {
+                continue;
+            }
+            if let (Some(&a), Some(&b)) = (position.get(&edge.source()), position.get(&edge.target())) {
+                union_find.union(a, b);
+            }
+        }
+
+        let mut groups: HashMap<usize, Vec<String>> = HashMap::new();
+        for (i, &idx) in files.iter().enumerate() {
+            groups
+                .entry(union_find.find(i))
+                .or_default()
+                .push(self.graph[idx].id.clone());
+        }
+
+        let mut groups: Vec<Vec<String>> = groups.into_values().collect();
+        for group in &mut groups {
+            group.sort();
+        }
+        // Largest first, ties broken by first file for stable output
+        groups.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a[0].cmp(&b[0])));
+
+        let mut orphan_files = Vec::new();
+        let components = groups
+            .into_iter()
+            .enumerate()
+            .map(|(id, files)| {
+                let is_main = id == 0;
+                if !is_main && files.len() <= orphan_max_size {
+                    orphan_files.extend(files.iter().cloned());
+                }
+                FileComponent {
+                    id,
+                    size: files.len(),
+                    files,
+                    is_main,
+                }
+            })
+            .collect();
+
+        ComponentReport {
+            total_files: files.len(),
+            components,
+            orphan_files,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{edge, graph};
+
+    #[test]
+    fn test_components_ranked_by_size() {
+        let graph = graph(
+            &["app.ts", "a.ts", "b.ts", "c.ts", "script.ts", "tool.ts", "lone.ts"],
+            &[("app.ts", "a.ts"), ("a.ts", "b.ts"), ("c.ts", "app.ts"), ("script.ts", "tool.ts")],
+        );
+
+        let report = graph.import_components(DEFAULT_ORPHAN_MAX_SIZE);
+        assert_eq!(report.total_files, 7);
+        let sizes: Vec<usize> = report.components.iter().map(|c| c.size).collect();
+        assert_eq!(sizes, vec![4, 2, 1]);
+        assert!(report.components[0].is_main);
+        assert_eq!(report.orphan_files, vec!["script.ts", "tool.ts", "lone.ts"]);
+    }
+
+    #[test]
+    fn test_non_import_edges_ignored() {
+        let mut graph = graph(&["a.py", "b.py"], &[]);
+        graph.add_edge("a.py", "b.py", edge("calls"));
+
+        let report = graph.import_components(DEFAULT_ORPHAN_MAX_SIZE);
+        assert_eq!(report.components.len(), 2);
+        assert_eq!(report.orphan_files.len(), 1);
+    }
+}
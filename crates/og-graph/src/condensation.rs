@@ -0,0 +1,130 @@
+use crate::graph::{CodeGraph, GraphData, GraphLink, GraphNode};
+use petgraph::algo::tarjan_scc;
+use petgraph::visit::EdgeRef;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Color used for collapsed cycle nodes
+const CYCLE_COLOR: &str = "#E74C3C";
+
+/// Acyclic view of a graph with each strongly connected component collapsed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CondensedGraph {
+    pub graph_data: GraphData,
+    /// Original node ids for every collapsed cycle node
+    pub cycle_members: BTreeMap<String, Vec<String>>,
+}
+
+impl CodeGraph {
+    /// Collapse every cycle into a single node, producing a DAG
+    ///
+    /// Nodes outside any cycle are kept as-is. Edges between the same pair of
+    /// condensed nodes with the same type are merged and their weights summed.
+    pub fn condensed(&self) -> CondensedGraph {
+        let mut sccs = tarjan_scc(&self.graph);
+        // Tarjan yields reverse topological order; flip it for a stable, readable layout
+        sccs.reverse();
+
+        let mut owner = HashMap::new();
+        let mut nodes = Vec::with_capacity(sccs.len());
+        let mut cycle_members = BTreeMap::new();
+
+        for (index, scc) in sccs.iter().enumerate() {
+            let id = if scc.len() == 1 {
+                let node = self.graph[scc[0]].clone();
+                let id = node.id.clone();
+                nodes.push(node);
+                id
+            } else {
+                let id = format!("cycle:{}", index);
+                let mut members: Vec<String> =
+                    scc.iter().map(|&idx| self.graph[idx].id.clone()).collect();
+                members.sort();
+                nodes.push(GraphNode {
+                    id: id.clone(),
+                    name: format!("Cycle of {} nodes", members.len()),
+                    node_type: "cycle".to_string(),
+                    size: scc.iter().map(|&idx| self.graph[idx].size).sum(),
+                    visual_size: (members.len() as f64 * 10.0).max(10.0),
+                    color: CYCLE_COLOR.to_string(),
+                    file_path: None,
+                    attributes: Default::default(),
+                });
+                cycle_members.insert(id.clone(), members);
+                id
+            };
+            for &idx in scc {
+                owner.insert(idx, id.clone());
+            }
+        }
+
+        let mut merged: BTreeMap<(String, String, String), (f64, usize)> = BTreeMap::new();
+        for edge in self.graph.edge_references() {
+            let source = &owner[&edge.source()];
+            let target = &owner[&edge.target()];
+            if source == target {
+                continue;
+            }
+            let weight = edge.weight();
+            let entry = merged
+                .entry((source.clone(), target.clone(), weight.edge_type.clone()))
+                .or_insert((0.0, 0));
+            entry.0 += weight.weight;
+            entry.1 += weight.count;
+        }
+
+        let links: Vec<GraphLink> = merged
+            .into_iter()
+            .map(|((source, target, link_type), (value, count))| GraphLink {
+                source,
+                target,
+                link_type,
+                value,
+                count,
+            })
+            .collect();
+
+        CondensedGraph {
+            graph_data: GraphData::from_parts(nodes, links),
+            cycle_members,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::graph;
+
+    #[test]
+    fn test_cycle_collapsed_into_single_node() {
+        let graph = graph(
+            &["a", "b", "c", "d"],
+            &[("a", "b"), ("b", "c"), ("c", "a"), ("c", "d"), ("a", "d")],
+        );
+
+        let condensed = graph.condensed();
+        assert_eq!(condensed.graph_data.nodes.len(), 2);
+        assert_eq!(condensed.cycle_members.len(), 1);
+
+        let (cycle_id, members) = condensed.cycle_members.iter().next().unwrap();
+        assert_eq!(members, &vec!["a", "b", "c"]);
+
+        // Both edges into "d" merge into one link
+        assert_eq!(condensed.graph_data.links.len(), 1);
+        let link = &condensed.graph_data.links[0];
+        assert_eq!(&link.source, cycle_id);
+        assert_eq!(link.target, "d");
+        assert_eq!(link.value, 2.0);
+    }
+
+    #[test]
+    fn test_acyclic_graph_unchanged() {
+        let graph = graph(&["a", "b", "c"], &[("a", "b"), ("b", "c")]);
+
+        let condensed = graph.condensed();
+        assert!(condensed.cycle_members.is_empty());
+        assert_eq!(condensed.graph_data.stats.node_count, 3);
+        assert_eq!(condensed.graph_data.stats.link_count, 2);
+    }
+}
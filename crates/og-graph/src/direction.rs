@@ -0,0 +1,143 @@
+use crate::graph::{CodeGraph, GraphEdge};
+use petgraph::graph::{DiGraph, NodeIndex};
+use petgraph::visit::EdgeRef;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+/// How a metric reads the direction of edges
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnalysisMode {
+    /// Edges point from dependent to dependency, as parsed
+    #[default]
+    Directed,
+    /// Every edge can be walked both ways
+    Undirected,
+    /// Edges point from dependency to dependent
+    Reverse,
+}
+
+impl CodeGraph {
+    /// The graph as a metric in `mode` walks it
+    ///
+    /// The undirected projection links each connected pair of nodes once in
+    /// each direction, so metrics that count neighbors or paths see every
+    /// neighbor once. Its edges carry the summed weight of all edges between
+    /// the pair, their summed count and the type of the heaviest one. Node
+    /// indices are the same
+    /// in every projection.
+    pub fn projected(&self, mode: AnalysisMode) -> Cow<'_, CodeGraph> {
+        match mode {
+            AnalysisMode::Directed => Cow::Borrowed(self),
+            AnalysisMode::Reverse => Cow::Owned(self.with_edges(
+                self.graph
+                    .edge_references()
+                    .map(|edge| (edge.target(), edge.source(), edge.weight().clone()))
+                    .collect(),
+            )),
+            AnalysisMode::Undirected => {
+                let mut pairs: BTreeMap<(NodeIndex, NodeIndex), (GraphEdge, f64, usize)> = BTreeMap::new();
+                for edge in self.graph.edge_references() {
+                    let key = (edge.source().min(edge.target()), edge.source().max(edge.target()));
+                    let weight = edge.weight();
+                    let entry = pairs.entry(key).or_insert_with(|| (weight.clone(), 0.0, 0));
+                    if weight.weight > entry.0.weight {
+                        entry.0.edge_type = weight.edge_type.clone();
+                    }
+                    entry.0.weight = entry.0.weight.max(weight.weight);
+                    entry.1 += weight.weight;
+                    entry.2 += weight.count;
+                }
+
+                let mut edges = Vec::with_capacity(pairs.len() * 2);
+                for ((a, b), (heaviest, total, count)) in pairs {
+                    let edge = GraphEdge {
+                        edge_type: heaviest.edge_type,
+                        weight: total,
+                        count,
+                    };
+                    if a != b {
+                        edges.push((b, a, edge.clone()));
+                    }
+                    edges.push((a, b, edge));
+                }
+                Cow::Owned(self.with_edges(edges))
+            }
+        }
+    }
+
+    /// Same nodes at the same indices, with the given edges
+    pub(crate) fn with_edges(&self, edges: Vec<(NodeIndex, NodeIndex, GraphEdge)>) -> CodeGraph {
+        let mut graph = DiGraph::with_capacity(self.graph.node_count(), edges.len());
+        for node in self.graph.node_weights() {
+            graph.add_node(node.clone());
+        }
+        for (source, target, edge) in edges {
+            graph.add_edge(source, target, edge);
+        }
+        CodeGraph {
+            graph,
+            node_map: self.node_map.clone(),
+            parallel_edges: self.parallel_edges,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{self, edge};
+    use petgraph::Direction;
+
+    fn graph(edges: &[(&str, &str, &str, f64)]) -> CodeGraph {
+        let mut graph = testing::graph(&["a", "b", "c"], &[]);
+        for (source, target, edge_type, weight) in edges {
+            graph.add_edge(
+                source,
+                target,
+                GraphEdge {
+                    weight: *weight,
+                    ..edge(edge_type)
+                },
+            );
+        }
+        graph
+    }
+
+    fn degree(graph: &CodeGraph, id: &str, direction: Direction) -> usize {
+        graph.graph.edges_directed(graph.node_map[id], direction).count()
+    }
+
+    #[test]
+    fn test_reverse_flips_edges() {
+        let graph = graph(&[("a", "b", "imports", 1.0)]);
+        let reversed = graph.projected(AnalysisMode::Reverse);
+        assert_eq!(degree(&reversed, "a", Direction::Incoming), 1);
+        assert_eq!(degree(&reversed, "b", Direction::Outgoing), 1);
+        assert_eq!(reversed.node_map, graph.node_map);
+    }
+
+    #[test]
+    fn test_undirected_merges_each_pair() {
+        let graph = graph(&[
+            ("a", "b", "imports", 1.0),
+            ("b", "a", "calls", 2.0),
+            ("a", "b", "calls", 1.5),
+            ("b", "c", "imports", 1.0),
+        ]);
+        let undirected = graph.projected(AnalysisMode::Undirected);
+        assert_eq!(undirected.graph.edge_count(), 4);
+        for id in ["a", "c"] {
+            assert_eq!(degree(&undirected, id, Direction::Outgoing), 1);
+            assert_eq!(degree(&undirected, id, Direction::Incoming), 1);
+        }
+
+        let (a, b) = (undirected.node_map["a"], undirected.node_map["b"]);
+        let edge = &undirected.graph[undirected.graph.find_edge(b, a).unwrap()];
+        assert_eq!(edge.weight, 4.5);
+        assert_eq!(edge.edge_type, "calls");
+
+        assert!(matches!(graph.projected(AnalysisMode::Directed), Cow::Borrowed(_)));
+    }
+}
@@ -0,0 +1,228 @@
+use crate::graph::{CodeGraph, GraphEdge};
+use petgraph::visit::EdgeRef;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Heaviest edges listed when no count is given
+pub const DEFAULT_HEAVY_EDGES: usize = 10;
+
+/// Edge counts and weight distributions per edge type
+///
+/// Repeated relationships between the same nodes are folded into a single
+/// edge by the graph's parallel edge policy, so with the default policy an
+/// edge's weight is the weight of one relationship times its count.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EdgeStats {
+    pub edge_count: usize,
+    /// Relationships the edges stand for, counting folded repeats
+    pub relationship_count: usize,
+    pub total_weight: f64,
+    /// Edge types, most common first
+    pub edge_types: Vec<EdgeTypeStats>,
+    /// Heaviest edges, heaviest first
+    pub heaviest: Vec<HeavyEdge>,
+}
+
+/// Weight distribution of the edges of one type
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EdgeTypeStats {
+    pub edge_type: String,
+    pub count: usize,
+    pub relationship_count: usize,
+    /// Weight of a single relationship, taken as the lightest of the type
+    pub base_weight: f64,
+    pub total_weight: f64,
+    pub min_weight: f64,
+    pub max_weight: f64,
+    pub mean_weight: f64,
+    pub median_weight: f64,
+    /// Edges standing for more than one relationship
+    pub merged_count: usize,
+}
+
+/// One heavy edge and how its weight came about
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeavyEdge {
+    pub source: String,
+    pub target: String,
+    pub edge_type: String,
+    pub weight: f64,
+    /// Weight of a single relationship of the edge's type
+    pub base_weight: f64,
+    /// Times the relationship occurs between the two nodes
+    pub occurrences: usize,
+}
+
+/// Weight one of the relationships behind an edge adds under the default policy
+fn single_weight(edge: &GraphEdge) -> f64 {
+    edge.weight / edge.count.max(1) as f64
+}
+
+fn median(sorted: &[f64]) -> f64 {
+    match sorted.len() {
+        0 => 0.0,
+        n if n % 2 == 1 => sorted[n / 2],
+        n => (sorted[n / 2 - 1] + sorted[n / 2]) / 2.0,
+    }
+}
+
+impl CodeGraph {
+    /// Counts and weight distributions per edge type, with the `top` heaviest edges
+    pub fn edge_stats(&self, top: usize) -> EdgeStats {
+        let mut by_type: BTreeMap<&str, Vec<&GraphEdge>> = BTreeMap::new();
+        for edge in self.graph.edge_references() {
+            by_type.entry(edge.weight().edge_type.as_str()).or_default().push(edge.weight());
+        }
+
+        let mut edge_types: Vec<EdgeTypeStats> = by_type
+            .into_iter()
+            .map(|(edge_type, edges)| {
+                let mut weights: Vec<f64> = edges.iter().map(|edge| edge.weight).collect();
+                weights.sort_by(f64::total_cmp);
+                let total_weight: f64 = weights.iter().sum();
+                EdgeTypeStats {
+                    edge_type: edge_type.to_string(),
+                    count: edges.len(),
+                    relationship_count: edges.iter().map(|edge| edge.count).sum(),
+                    base_weight: edges.iter().map(|&edge| single_weight(edge)).fold(f64::INFINITY, f64::min),
+                    total_weight,
+                    min_weight: weights[0],
+                    max_weight: weights[weights.len() - 1],
+                    mean_weight: total_weight / weights.len() as f64,
+                    median_weight: median(&weights),
+                    merged_count: edges.iter().filter(|edge| edge.count > 1).count(),
+                }
+            })
+            .collect();
+        // Sort is stable, so types with equal counts stay in name order
+        edge_types.sort_by_key(|stats| std::cmp::Reverse(stats.count));
+
+        let base: BTreeMap<&str, f64> = edge_types
+            .iter()
+            .map(|stats| (stats.edge_type.as_str(), stats.base_weight))
+            .collect();
+        let mut heaviest: Vec<HeavyEdge> = self
+            .graph
+            .edge_references()
+            .map(|edge| {
+                let weight = edge.weight();
+                let base_weight = base[weight.edge_type.as_str()];
+                HeavyEdge {
+                    source: self.graph[edge.source()].id.clone(),
+                    target: self.graph[edge.target()].id.clone(),
+                    edge_type: weight.edge_type.clone(),
+                    weight: weight.weight,
+                    base_weight,
+                    occurrences: weight.count,
+                }
+            })
+            .collect();
+        heaviest.sort_by(|a, b| {
+            b.weight
+                .total_cmp(&a.weight)
+                .then_with(|| a.source.cmp(&b.source))
+                .then_with(|| a.target.cmp(&b.target))
+                .then_with(|| a.edge_type.cmp(&b.edge_type))
+        });
+        heaviest.truncate(top);
+
+        EdgeStats {
+            edge_count: self.graph.edge_count(),
+            relationship_count: edge_types.iter().map(|stats| stats.relationship_count).sum(),
+            total_weight: edge_types.iter().map(|stats| stats.total_weight).sum(),
+            edge_types,
+            heaviest,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::GraphBuilder;
+    use og_types::{AstNode, FileMetrics, Language, NodeType, ParsedFile, Relationship, RelationshipType};
+    use std::path::PathBuf;
+
+    fn file(path: &str, imports: &[&str]) -> ParsedFile {
+        ParsedFile {
+            path: PathBuf::from(path),
+            language: Language::Python,
+            nodes: vec![AstNode {
+                id: path.to_string(),
+                node_type: NodeType::File,
+                name: path.to_string(),
+                start_line: 1,
+                end_line: 10,
+                children: Vec::new(),
+            }],
+            relationships: imports
+                .iter()
+                .map(|target| Relationship {
+                    source: path.to_string(),
+                    target: target.to_string(),
+                    relationship_type: RelationshipType::Imports,
+                })
+                .collect(),
+            metrics: FileMetrics::default(),
+        }
+    }
+
+    #[test]
+    fn test_repeated_imports_merge_into_one_edge() {
+        let graph = GraphBuilder::new().build_from_files(vec![
+            file("a.py", &["b.py", "b.py", "b.py", "c.py"]),
+            file("b.py", &["a.py"]),
+            file("c.py", &[]),
+        ]);
+        assert_eq!(graph.graph.edge_count(), 3);
+
+        let stats = graph.edge_stats(DEFAULT_HEAVY_EDGES);
+        assert_eq!((stats.edge_count, stats.relationship_count), (3, 5));
+        assert_eq!(stats.total_weight, 10.0);
+        let imports = &stats.edge_types[0];
+        assert_eq!((imports.edge_type.as_str(), imports.count, imports.merged_count), ("IMPORTS", 3, 1));
+        assert_eq!((imports.base_weight, imports.max_weight, imports.median_weight), (2.0, 6.0, 2.0));
+
+        let top = &stats.heaviest[0];
+        assert_eq!((top.source.as_str(), top.target.as_str(), top.weight, top.occurrences), ("a.py", "b.py", 6.0, 3));
+        assert_eq!(graph.edge_stats(1).heaviest.len(), 1);
+        assert!(CodeGraph::new().edge_stats(5).edge_types.is_empty());
+    }
+
+    #[test]
+    fn test_parallel_edge_policies() {
+        use crate::graph::{GraphData, GraphEdge, ParallelEdgePolicy};
+        use crate::testing::{edge, node};
+
+        let fold = |policy: ParallelEdgePolicy| {
+            let mut graph = CodeGraph::with_parallel_edges(policy);
+            for id in ["a", "b"] {
+                graph.add_node(node(id));
+            }
+            for (edge_type, weight) in [("imports", 2.0), ("imports", 5.0), ("calls", 1.0)] {
+                graph.add_edge("a", "b", GraphEdge { weight, ..edge(edge_type) });
+            }
+            graph
+        };
+        let imports = |graph: &CodeGraph| {
+            let edge = graph.graph.edge_weights().find(|edge| edge.edge_type == "imports").unwrap();
+            (edge.weight, edge.count)
+        };
+
+        let summed = fold(ParallelEdgePolicy::AggregateSum);
+        assert_eq!(summed.graph.edge_count(), 2, "types stay apart");
+        assert_eq!(imports(&summed), (7.0, 2));
+        assert_eq!(imports(&fold(ParallelEdgePolicy::AggregateMax)), (5.0, 2));
+        assert_eq!(imports(&fold(ParallelEdgePolicy::KeepFirst)), (2.0, 2));
+
+        // Counts survive the round trip through frontend data
+        let data = summed.to_frontend_format();
+        let json = serde_json::to_string(&data).unwrap();
+        let back = CodeGraph::from_graph_data(&serde_json::from_str::<GraphData>(&json).unwrap());
+        assert_eq!(imports(&back), (7.0, 2));
+        assert!(!json.contains(r#""count":1"#), "single relationships leave the count out");
+    }
+}
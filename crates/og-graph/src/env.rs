@@ -0,0 +1,114 @@
+use crate::graph::CodeGraph;
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+use serde::{Deserialize, Serialize};
+
+/// Name fragments that mark an environment variable as holding a secret
+const SECRET_MARKERS: &[&str] = &["SECRET", "TOKEN", "PASSWORD", "PASSWD", "API_KEY", "PRIVATE_KEY", "CREDENTIAL"];
+
+/// One environment variable and the code that reads it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvVarUsage {
+    pub env_id: String,
+    pub name: String,
+    /// Whether the name suggests the value is a secret
+    pub secret: bool,
+    pub readers: Vec<EnvReader>,
+}
+
+/// A function or file that reads an environment variable
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvReader {
+    pub node_id: String,
+    pub name: String,
+    pub node_type: String,
+    pub file_path: Option<String>,
+}
+
+fn is_secret(name: &str) -> bool {
+    let name = name.to_ascii_uppercase();
+    SECRET_MARKERS.iter().any(|marker| name.contains(marker))
+}
+
+impl CodeGraph {
+    /// Environment variables read in the graph with their readers, sorted by name
+    pub fn env_usage(&self) -> Vec<EnvVarUsage> {
+        let mut usage: Vec<EnvVarUsage> = self
+            .graph
+            .node_indices()
+            .filter(|&idx| self.graph[idx].node_type == "env_var")
+            .map(|idx| {
+                let node = &self.graph[idx];
+                let mut readers: Vec<EnvReader> = self
+                    .graph
+                    .edges_directed(idx, Direction::Incoming)
+                    .filter(|edge| edge.weight().edge_type.eq_ignore_ascii_case("reads"))
+                    .map(|edge| {
+                        let reader = &self.graph[edge.source()];
+                        EnvReader {
+                            node_id: reader.id.clone(),
+                            name: reader.name.clone(),
+                            node_type: reader.node_type.clone(),
+                            file_path: reader.file_path.clone(),
+                        }
+                    })
+                    .collect();
+                readers.sort_by(|a, b| a.file_path.cmp(&b.file_path).then(a.node_id.cmp(&b.node_id)));
+                readers.dedup_by(|a, b| a.node_id == b.node_id);
+
+                EnvVarUsage {
+                    env_id: node.id.clone(),
+                    name: node.name.clone(),
+                    secret: is_secret(&node.name),
+                    readers,
+                }
+            })
+            .collect();
+
+        usage.sort_by(|a, b| a.name.cmp(&b.name));
+        usage
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::GraphNode;
+    use crate::testing::{self, edge};
+
+    fn node(id: &str, name: &str, node_type: &str, file_path: Option<&str>) -> GraphNode {
+        GraphNode {
+            name: name.to_string(),
+            node_type: node_type.to_string(),
+            file_path: file_path.map(str::to_string),
+            ..testing::node(id)
+        }
+    }
+
+    fn reads(graph: &mut CodeGraph, source: &str, target: &str) {
+        graph.add_edge(source, target, edge("reads"));
+    }
+
+    #[test]
+    fn test_env_usage_lists_readers_and_secrets() {
+        let mut graph = CodeGraph::new();
+        graph.add_node(node("env:PORT", "PORT", "env_var", Some("server.js")));
+        graph.add_node(node("env:STRIPE_API_KEY", "STRIPE_API_KEY", "env_var", Some("pay.py")));
+        graph.add_node(node("function:server.js:listen", "listen", "function", Some("server.js")));
+        graph.add_node(node("function:pay.py:charge", "charge", "function", Some("pay.py")));
+        graph.add_node(node("file:pay.py", "pay.py", "file", Some("pay.py")));
+        reads(&mut graph, "function:server.js:listen", "env:PORT");
+        reads(&mut graph, "function:pay.py:charge", "env:STRIPE_API_KEY");
+        reads(&mut graph, "file:pay.py", "env:STRIPE_API_KEY");
+
+        let usage = graph.env_usage();
+        assert_eq!(usage.len(), 2);
+        assert_eq!(usage[0].name, "PORT");
+        assert!(!usage[0].secret);
+        assert_eq!(usage[0].readers[0].name, "listen");
+        assert!(usage[1].secret);
+        assert_eq!(usage[1].readers.len(), 2);
+    }
+}
@@ -0,0 +1,248 @@
+use crate::graph::{CodeGraph, GraphEdge, GraphNode};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+/// Type of the nodes that stand for third-party packages and imports
+pub const EXTERNAL_NODE_TYPE: &str = "external";
+
+const EXTERNAL_NODE_COLOR: &str = "#B0BEC5";
+
+/// How imports of third-party packages appear in the graph
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ExternalDependencies {
+    /// Leave them out of the graph
+    #[default]
+    Drop,
+    /// One node per npm or pip package, however many of its modules are imported
+    Package,
+    /// One node per imported specifier, such as `lodash/fp` or `os.path`
+    Import,
+}
+
+/// Package registry an external import comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Ecosystem {
+    Npm,
+    Pip,
+}
+
+impl Ecosystem {
+    fn as_str(self) -> &'static str {
+        match self {
+            Ecosystem::Npm => "npm",
+            Ecosystem::Pip => "pip",
+        }
+    }
+}
+
+/// An import of code outside the codebase
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExternalImport {
+    /// Node the import was found in
+    pub source: String,
+    pub ecosystem: Ecosystem,
+    /// Package the import belongs to, such as `@scope/name` or `requests`
+    pub package: String,
+    /// What was imported, such as `lodash/fp` or `os.path`
+    pub specifier: String,
+}
+
+impl ExternalImport {
+    /// The external import an unresolved import edge stands for, if any
+    ///
+    /// JavaScript and TypeScript parsers target bare specifiers as
+    /// `external:<specifier>`. Python imports target `module:<a/b>` and are
+    /// external when their top-level module is none of `local_modules`, the
+    /// directory and module names of the codebase's own Python files.
+    pub fn classify(source: &str, target: &str, local_modules: &HashSet<String>) -> Option<Self> {
+        if let Some(specifier) = target.strip_prefix("external:") {
+            return Some(Self {
+                source: source.to_string(),
+                ecosystem: Ecosystem::Npm,
+                package: npm_package(specifier).to_string(),
+                specifier: specifier.to_string(),
+            });
+        }
+        let module = target.strip_prefix("module:")?;
+        // Relative imports start with a slash once dots become separators
+        let top = module.split('/').next().filter(|top| !top.is_empty())?;
+        if local_modules.contains(top) {
+            return None;
+        }
+        Some(Self {
+            source: source.to_string(),
+            ecosystem: Ecosystem::Pip,
+            package: top.to_string(),
+            specifier: module.replace('/', "."),
+        })
+    }
+}
+
+/// The npm package of a bare specifier: `@scope/name` for scoped packages,
+/// otherwise the first path segment
+pub fn npm_package(specifier: &str) -> &str {
+    let mut segments = specifier.match_indices('/').map(|(i, _)| i);
+    let end = if specifier.starts_with('@') {
+        segments.nth(1)
+    } else {
+        segments.next()
+    };
+    &specifier[..end.unwrap_or(specifier.len())]
+}
+
+/// Third-party dependencies of one file
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalFanOut {
+    pub file: String,
+    /// Distinct packages imported, as `npm:name` or `pip:name`, sorted
+    pub packages: Vec<String>,
+    /// Distinct specifiers imported
+    pub imports: usize,
+}
+
+impl CodeGraph {
+    /// Add external imports to the graph as `mode` asks and report each
+    /// file's external fan-out, which is the same in every mode
+    ///
+    /// Grouped package edges weigh the number of distinct specifiers they
+    /// stand for. Fan-out is sorted by package count, highest first.
+    pub fn add_external_dependencies(
+        &mut self,
+        imports: &[ExternalImport],
+        mode: ExternalDependencies,
+    ) -> Vec<ExternalFanOut> {
+        let mut by_file: BTreeMap<String, (BTreeSet<String>, HashSet<&str>)> = BTreeMap::new();
+        let mut edges: HashMap<(&str, String), (String, HashSet<&str>)> = HashMap::new();
+        for import in imports {
+            let Some(&source) = self.node_map.get(&import.source) else {
+                continue;
+            };
+            let file = self.graph[source].file_path.clone().unwrap_or_else(|| import.source.clone());
+            let package = format!("{}:{}", import.ecosystem.as_str(), import.package);
+            let (packages, specifiers) = by_file.entry(file).or_default();
+            packages.insert(package.clone());
+            specifiers.insert(&import.specifier);
+
+            let (target, name) = match mode {
+                ExternalDependencies::Drop => continue,
+                ExternalDependencies::Package => (format!("external:{}", package), &import.package),
+                ExternalDependencies::Import => (
+                    format!("external:{}:{}", import.ecosystem.as_str(), import.specifier),
+                    &import.specifier,
+                ),
+            };
+            let (_, specifiers) = edges
+                .entry((import.source.as_str(), target))
+                .or_insert_with(|| (name.clone(), HashSet::new()));
+            specifiers.insert(&import.specifier);
+        }
+
+        let mut edges: Vec<_> = edges.into_iter().collect();
+        edges.sort_by(|(a, _), (b, _)| a.cmp(b));
+        for ((source, target), (name, specifiers)) in edges {
+            if !self.node_map.contains_key(&target) {
+                self.add_node(GraphNode {
+                    id: target.clone(),
+                    name,
+                    node_type: EXTERNAL_NODE_TYPE.to_string(),
+                    size: 0.0,
+                    visual_size: 10.0,
+                    color: EXTERNAL_NODE_COLOR.to_string(),
+                    file_path: None,
+                    attributes: Default::default(),
+                });
+            }
+            self.add_edge(
+                source,
+                &target,
+                GraphEdge {
+                    edge_type: "imports".to_string(),
+                    weight: specifiers.len() as f64,
+                    count: specifiers.len(),
+                },
+            );
+        }
+
+        let mut fan_out: Vec<ExternalFanOut> = by_file
+            .into_iter()
+            .map(|(file, (packages, specifiers))| ExternalFanOut {
+                file,
+                packages: packages.into_iter().collect(),
+                imports: specifiers.len(),
+            })
+            .collect();
+        fan_out.sort_by_key(|file| Reverse(file.packages.len()));
+        fan_out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::node;
+
+    fn graph() -> CodeGraph {
+        let mut graph = CodeGraph::new();
+        for (id, path) in [("file:a.ts", "a.ts"), ("file:b.py", "b.py")] {
+            graph.add_node(GraphNode {
+                id: id.to_string(),
+                ..node(path)
+            });
+        }
+        graph
+    }
+
+    fn imports() -> Vec<ExternalImport> {
+        let local = HashSet::from(["app".to_string()]);
+        [
+            ("file:a.ts", "external:lodash"),
+            ("file:a.ts", "external:lodash/fp"),
+            ("file:a.ts", "external:@scope/ui/button"),
+            ("file:b.py", "module:requests/adapters"),
+            ("file:b.py", "module:app/models"),
+            ("file:b.py", "module:/sibling"),
+        ]
+        .into_iter()
+        .filter_map(|(source, target)| ExternalImport::classify(source, target, &local))
+        .collect()
+    }
+
+    #[test]
+    fn test_npm_package() {
+        assert_eq!(npm_package("lodash/fp"), "lodash");
+        assert_eq!(npm_package("@scope/ui/button"), "@scope/ui");
+        assert_eq!(npm_package("@scope/ui"), "@scope/ui");
+        assert_eq!(npm_package("react"), "react");
+    }
+
+    #[test]
+    fn test_external_dependency_modes() {
+        let imports = imports();
+        assert_eq!(imports.len(), 4, "local and relative Python modules are not external");
+
+        let mut dropped = graph();
+        let fan_out = dropped.add_external_dependencies(&imports, ExternalDependencies::Drop);
+        assert_eq!(dropped.to_frontend_format().nodes.len(), 2);
+        assert_eq!(fan_out[0].file, "a.ts");
+        assert_eq!(fan_out[0].packages, vec!["npm:@scope/ui", "npm:lodash"]);
+        assert_eq!(fan_out[0].imports, 3);
+        assert_eq!(fan_out[1].packages, vec!["pip:requests"]);
+
+        let mut packages = graph();
+        assert_eq!(packages.add_external_dependencies(&imports, ExternalDependencies::Package), fan_out);
+        let data = packages.to_frontend_format();
+        assert_eq!(data.nodes.len(), 5);
+        let lodash = data.links.iter().find(|link| link.target == "external:npm:lodash").unwrap();
+        assert_eq!(lodash.value, 2.0);
+
+        let mut per_import = graph();
+        per_import.add_external_dependencies(&imports, ExternalDependencies::Import);
+        let data = per_import.to_frontend_format();
+        assert_eq!(data.nodes.len(), 6);
+        assert!(data.nodes.iter().any(|node| node.id == "external:pip:requests.adapters"));
+    }
+}
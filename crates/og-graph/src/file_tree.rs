@@ -0,0 +1,259 @@
+use crate::graph::GraphData;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Component, Path, PathBuf};
+
+/// A file or folder in the file explorer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileNode {
+    pub name: String,
+    /// Full path, for opening the file
+    pub path: String,
+    /// "file" or "folder"
+    #[serde(rename = "type")]
+    pub node_type: String,
+    /// Files and subfolders, folders first, None for files
+    pub children: Option<Vec<FileNode>>,
+    /// Size in bytes, summed over a folder's files
+    pub size: Option<usize>,
+    pub extension: Option<String>,
+    /// Lines of code, summed over a folder's files
+    pub line_count: Option<usize>,
+    /// Files below a folder, None for files
+    #[serde(default)]
+    pub file_count: Option<usize>,
+    /// Cyclomatic complexity, averaged over a folder's files
+    #[serde(default)]
+    pub avg_complexity: Option<f64>,
+    /// Code smells, summed over a folder's files
+    #[serde(default)]
+    pub smell_count: Option<usize>,
+}
+
+/// What is known about one file, by its path
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileStats {
+    pub size: Option<usize>,
+    pub line_count: Option<usize>,
+    pub complexity: Option<usize>,
+    pub smells: Option<usize>,
+}
+
+/// Stats of every file below a folder, added up
+#[derive(Default)]
+struct Totals {
+    files: usize,
+    size: Option<usize>,
+    line_count: Option<usize>,
+    /// Complexity summed over the files that have one, and how many do
+    complexity: Option<(usize, usize)>,
+    smells: Option<usize>,
+}
+
+/// Sum of two values where either may be unknown
+fn add(a: Option<usize>, b: Option<usize>) -> Option<usize> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a + b),
+        _ => a.or(b),
+    }
+}
+
+impl Totals {
+    fn add(&mut self, other: &Totals) {
+        self.files += other.files;
+        self.size = add(self.size, other.size);
+        self.line_count = add(self.line_count, other.line_count);
+        self.smells = add(self.smells, other.smells);
+        self.complexity = match (self.complexity, other.complexity) {
+            (Some((sum, n)), Some((other_sum, other_n))) => Some((sum + other_sum, n + other_n)),
+            (a, b) => a.or(b),
+        };
+    }
+
+    fn of_file(stats: FileStats) -> Self {
+        Self {
+            files: 1,
+            size: stats.size,
+            line_count: stats.line_count,
+            complexity: stats.complexity.map(|complexity| (complexity, 1)),
+            smells: stats.smells,
+        }
+    }
+
+    fn avg_complexity(&self) -> Option<f64> {
+        self.complexity.map(|(sum, n)| sum as f64 / n as f64)
+    }
+}
+
+/// Folder contents while the tree is being assembled
+#[derive(Default)]
+struct Folder {
+    folders: BTreeMap<String, Folder>,
+    /// File name and full path
+    files: Vec<(String, String)>,
+}
+
+/// Folders first, then by name ignoring case
+fn explorer_order(a: &FileNode, b: &FileNode) -> Ordering {
+    match (a.node_type.as_str(), b.node_type.as_str()) {
+        ("folder", "file") => Ordering::Less,
+        ("file", "folder") => Ordering::Greater,
+        _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+    }
+}
+
+/// Deepest directory containing every path
+fn common_base(paths: &BTreeSet<&str>) -> Option<PathBuf> {
+    let mut paths = paths.iter().map(Path::new);
+    let mut common = paths.next()?.parent()?.to_path_buf();
+    for path in paths {
+        while !path.starts_with(&common) {
+            common = common.parent()?.to_path_buf();
+        }
+    }
+    Some(common)
+}
+
+impl Folder {
+    /// Children sorted for the explorer, and the totals of every file below
+    fn into_children(self, path: &Path, stats: &HashMap<String, FileStats>) -> (Vec<FileNode>, Totals) {
+        let mut totals = Totals::default();
+        let mut children = Vec::with_capacity(self.folders.len() + self.files.len());
+        for (name, folder) in self.folders {
+            let path = path.join(&name);
+            let (grandchildren, below) = folder.into_children(&path, stats);
+            totals.add(&below);
+            children.push(FileNode {
+                name,
+                path: path.display().to_string(),
+                node_type: "folder".to_string(),
+                children: Some(grandchildren),
+                size: below.size,
+                extension: None,
+                line_count: below.line_count,
+                file_count: Some(below.files),
+                avg_complexity: below.avg_complexity(),
+                smell_count: below.smells,
+            });
+        }
+        for (name, path) in self.files {
+            let file = stats.get(&path).copied().unwrap_or_default();
+            totals.add(&Totals::of_file(file));
+            children.push(FileNode {
+                extension: Path::new(&name).extension().map(|e| e.to_string_lossy().into_owned()),
+                name,
+                path,
+                node_type: "file".to_string(),
+                children: None,
+                size: file.size,
+                line_count: file.line_count,
+                file_count: None,
+                avg_complexity: file.complexity.map(|complexity| complexity as f64),
+                smell_count: file.smells,
+            });
+        }
+        children.sort_by(explorer_order);
+        (children, totals)
+    }
+}
+
+/// Folder tree of the files in a graph, relative to their deepest common folder
+///
+/// `stats` holds what is known about each file by its path. A folder's
+/// size, line count and smell count are sums over the files below it that
+/// have them, and its complexity is their average.
+pub fn build_file_tree(graph_data: &GraphData, stats: &HashMap<String, FileStats>) -> Vec<FileNode> {
+    let file_paths: BTreeSet<&str> = graph_data
+        .nodes
+        .iter()
+        .filter(|node| node.node_type == "file")
+        .filter_map(|node| node.file_path.as_deref())
+        .collect();
+    let base = common_base(&file_paths).unwrap_or_default();
+
+    let mut root = Folder::default();
+    for file_path in file_paths {
+        let full_path = Path::new(file_path);
+        let relative = full_path.strip_prefix(&base).unwrap_or(full_path);
+        let mut names: Vec<String> = relative
+            .components()
+            .filter_map(|component| match component {
+                Component::Normal(name) => Some(name.to_string_lossy().into_owned()),
+                _ => None,
+            })
+            .collect();
+        let Some(file_name) = names.pop() else {
+            continue;
+        };
+        let folder = names
+            .into_iter()
+            .fold(&mut root, |folder, name| folder.folders.entry(name).or_default());
+        folder.files.push((file_name, file_path.to_string()));
+    }
+
+    root.into_children(&base, stats).0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{GraphData, GraphNode};
+    use crate::testing::node;
+
+    fn graph(files: &[&str]) -> GraphData {
+        let nodes = files
+            .iter()
+            .flat_map(|path| {
+                ["file", "function"].map(|node_type| GraphNode {
+                    id: format!("{}:{}", node_type, path),
+                    node_type: node_type.to_string(),
+                    ..node(path)
+                })
+            })
+            .collect();
+        GraphData::from_parts(nodes, Vec::new())
+    }
+
+    fn names(nodes: &[FileNode]) -> Vec<&str> {
+        nodes.iter().map(|node| node.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_nested_folders_under_common_base() {
+        let data = graph(&["/repo/src/b.py", "/repo/src/util/deep/x.py", "/repo/README.py", "/repo/src/A.py"]);
+        let tree = build_file_tree(&data, &HashMap::new());
+
+        assert_eq!(names(&tree), vec!["src", "README.py"]);
+        assert_eq!(tree[0].path, "/repo/src");
+        let src = tree[0].children.as_deref().unwrap();
+        assert_eq!(names(src), vec!["util", "A.py", "b.py"]);
+        let deep = src[0].children.as_deref().unwrap()[0].children.as_deref().unwrap();
+        assert_eq!(deep[0].path, "/repo/src/util/deep/x.py");
+        assert_eq!(deep[0].extension.as_deref(), Some("py"));
+        assert!(deep[0].children.is_none());
+    }
+
+    #[test]
+    fn test_folder_stats_sum_known_files() {
+        let data = graph(&["/repo/a/one.py", "/repo/a/two.py", "/repo/b/three.py"]);
+        let stats = HashMap::from([
+            (
+                "/repo/a/one.py".to_string(),
+                FileStats { size: Some(100), line_count: Some(10), complexity: Some(2), smells: Some(1) },
+            ),
+            (
+                "/repo/a/two.py".to_string(),
+                FileStats { line_count: Some(5), complexity: Some(7), ..FileStats::default() },
+            ),
+        ]);
+        let tree = build_file_tree(&data, &stats);
+
+        assert_eq!((tree[0].size, tree[0].line_count), (Some(100), Some(15)));
+        assert_eq!((tree[0].file_count, tree[0].avg_complexity, tree[0].smell_count), (Some(2), Some(4.5), Some(1)));
+        assert_eq!(tree[0].children.as_deref().unwrap()[1].avg_complexity, Some(7.0));
+        assert_eq!((tree[1].size, tree[1].line_count, tree[1].avg_complexity), (None, None, None));
+        assert_eq!(tree[1].file_count, Some(1));
+        assert!(build_file_tree(&GraphData::from_parts(Vec::new(), Vec::new()), &stats).is_empty());
+    }
+}
@@ -1,23 +1,69 @@
+use crate::attributes::AttrValue;
 use petgraph::graph::{DiGraph, NodeIndex};
 use petgraph::visit::EdgeRef;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+
+/// How an edge is added when one of the same type already joins the same nodes
+///
+/// Edges of different types always stay apart, so the policies differ only
+/// in the weight of the kept edge. Every policy counts the repeats on it, so
+/// a file importing another three times gives one edge with a count of 3.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ParallelEdgePolicy {
+    /// One edge carrying the summed weight of the repeats
+    #[default]
+    AggregateSum,
+    /// One edge carrying the largest weight among the repeats
+    AggregateMax,
+    /// One edge carrying the weight of the first, so repeats add no weight
+    KeepFirst,
+}
 
 /// Code graph representation using petgraph
 #[derive(Clone)]
 pub struct CodeGraph {
     pub graph: DiGraph<GraphNode, GraphEdge>,
     pub node_map: HashMap<String, NodeIndex>,
+    /// How repeated edges between the same nodes are folded together
+    pub parallel_edges: ParallelEdgePolicy,
 }
 
 impl CodeGraph {
     pub fn new() -> Self {
+        Self::with_parallel_edges(ParallelEdgePolicy::default())
+    }
+
+    /// An empty graph that folds repeated edges by the given policy
+    pub fn with_parallel_edges(parallel_edges: ParallelEdgePolicy) -> Self {
         Self {
             graph: DiGraph::new(),
             node_map: HashMap::new(),
+            parallel_edges,
         }
     }
 
+    /// Rebuild a code graph from frontend graph data
+    pub fn from_graph_data(data: &GraphData) -> Self {
+        let mut graph = Self::new();
+        for node in data.nodes.iter().filter(|n| !n.id.is_empty()) {
+            graph.add_node(node.clone());
+        }
+        for link in &data.links {
+            graph.add_edge(
+                &link.source,
+                &link.target,
+                GraphEdge {
+                    edge_type: link.link_type.clone(),
+                    weight: link.value,
+                    count: link.count,
+                },
+            );
+        }
+        graph
+    }
+
     /// Add a node to the graph
     pub fn add_node(&mut self, node: GraphNode) -> NodeIndex {
         let id = node.id.clone();
@@ -27,10 +73,83 @@ impl CodeGraph {
     }
 
     /// Add an edge to the graph
+    ///
+    /// An edge of the same type already joining the same nodes absorbs the
+    /// new one as the graph's parallel edge policy says, rather than gaining
+    /// a duplicate that would inflate weight-based metrics.
     pub fn add_edge(&mut self, source_id: &str, target_id: &str, edge: GraphEdge) {
-        if let (Some(&source), Some(&target)) = (self.node_map.get(source_id), self.node_map.get(target_id)) {
+        let (Some(&source), Some(&target)) = (self.node_map.get(source_id), self.node_map.get(target_id)) else {
+            return;
+        };
+        let existing = self
+            .graph
+            .edges_connecting(source, target)
+            .find(|existing| existing.weight().edge_type == edge.edge_type)
+            .map(|existing| existing.id());
+        let Some(id) = existing else {
             self.graph.add_edge(source, target, edge);
+            return;
+        };
+
+        let kept = &mut self.graph[id];
+        kept.count += edge.count;
+        match self.parallel_edges {
+            ParallelEdgePolicy::AggregateSum => kept.weight += edge.weight,
+            ParallelEdgePolicy::AggregateMax => kept.weight = kept.weight.max(edge.weight),
+            ParallelEdgePolicy::KeepFirst => {}
+        }
+    }
+
+    /// Remove a node and its edges, keeping the id index consistent
+    pub fn remove_node(&mut self, id: &str) -> Option<GraphNode> {
+        let idx = self.node_map.remove(id)?;
+        let node = self.graph.remove_node(idx)?;
+        // petgraph moves the last node into the freed slot
+        if let Some(moved) = self.graph.node_weight(idx) {
+            self.node_map.insert(moved.id.clone(), idx);
         }
+        Some(node)
+    }
+
+    /// Remove one edge matching source, target and type
+    pub fn remove_edge(&mut self, source_id: &str, target_id: &str, edge_type: &str) -> bool {
+        let (Some(&source), Some(&target)) = (self.node_map.get(source_id), self.node_map.get(target_id)) else {
+            return false;
+        };
+        let found = self
+            .graph
+            .edges_connecting(source, target)
+            .find(|edge| edge.weight().edge_type == edge_type)
+            .map(|edge| edge.id());
+        match found {
+            Some(edge) => self.graph.remove_edge(edge).is_some(),
+            None => false,
+        }
+    }
+
+    /// Graph data containing only the given nodes and the edges between them
+    pub fn induced_subgraph(&self, keep: &HashSet<NodeIndex>) -> GraphData {
+        let mut indices: Vec<NodeIndex> = keep.iter().copied().collect();
+        indices.sort();
+
+        let nodes = indices
+            .iter()
+            .filter_map(|&idx| self.graph.node_weight(idx).cloned())
+            .collect();
+        let links = self
+            .graph
+            .edge_references()
+            .filter(|edge| keep.contains(&edge.source()) && keep.contains(&edge.target()))
+            .map(|edge| GraphLink {
+                source: self.graph[edge.source()].id.clone(),
+                target: self.graph[edge.target()].id.clone(),
+                link_type: edge.weight().edge_type.clone(),
+                value: edge.weight().weight,
+                count: edge.weight().count,
+            })
+            .collect();
+
+        GraphData::from_parts(nodes, links)
     }
 
     /// Get all nodes
@@ -88,7 +207,7 @@ impl CodeGraph {
                                     rank += damping_factor * source_rank / outgoing_count as f64;
                                 } else {
                                     // Log warning but continue
-                                    eprintln!("Warning: PageRank - node {} not found in ranks", source_node.id);
+                                    tracing::warn!("PageRank - node {} not found in ranks", source_node.id);
                                     rank += damping_factor * initial_rank / outgoing_count as f64;
                                 }
                             }
@@ -105,6 +224,52 @@ impl CodeGraph {
         ranks
     }
 
+    /// PageRank of a random walk that restarts from the seed nodes
+    ///
+    /// The walk follows edges in both directions, since a dependency either
+    /// way ties a node to the seeds. Walkers on a node without edges jump
+    /// back to a seed. Scores sum to 1, and are empty when there are no seeds.
+    pub fn personalized_pagerank(&self, seeds: &[NodeIndex], iterations: usize, damping_factor: f64) -> HashMap<String, f64> {
+        let seeds: HashSet<NodeIndex> = seeds.iter().copied().filter(|&idx| idx.index() < self.graph.node_count()).collect();
+        if seeds.is_empty() {
+            return HashMap::new();
+        }
+
+        let restart = 1.0 / seeds.len() as f64;
+        let mut ranks = vec![0.0; self.graph.node_count()];
+        for &seed in &seeds {
+            ranks[seed.index()] = restart;
+        }
+
+        for _ in 0..iterations {
+            let mut next = vec![0.0; ranks.len()];
+            let mut stranded = 0.0;
+            for idx in self.graph.node_indices() {
+                let rank = ranks[idx.index()];
+                let degree = self.graph.neighbors_undirected(idx).count();
+                if degree == 0 {
+                    stranded += rank;
+                    continue;
+                }
+                let share = damping_factor * rank / degree as f64;
+                for neighbor in self.graph.neighbors_undirected(idx) {
+                    next[neighbor.index()] += share;
+                }
+            }
+            // Jumps back to the seeds, from restarts and from stranded walkers
+            let jump = (1.0 - damping_factor) + damping_factor * stranded;
+            for &seed in &seeds {
+                next[seed.index()] += jump * restart;
+            }
+            ranks = next;
+        }
+
+        self.graph
+            .node_indices()
+            .map(|idx| (self.graph[idx].id.clone(), ranks[idx.index()]))
+            .collect()
+    }
+
     /// Convert to frontend-compatible format
     pub fn to_frontend_format(&self) -> GraphData {
         // Calculate connection counts for each node
@@ -118,14 +283,14 @@ impl CodeGraph {
             }
         }
         
-        // Create nodes with size based on connections
+        // Create nodes with visual size based on connections
         let nodes: Vec<GraphNode> = self.graph
             .node_weights()
             .map(|node| {
                 let connections = node_connections.get(&node.id).unwrap_or(&0);
                 let mut node = node.clone();
-                // Set size based on number of connections
-                node.size = (*connections as f64 * 10.0).max(10.0);
+                // Set visual size based on number of connections, keeping lines of code
+                node.visual_size = (*connections as f64 * 10.0).max(10.0);
                 node
             })
             .collect();
@@ -145,6 +310,7 @@ impl CodeGraph {
                         target: target_node.id.clone(),
                         link_type: edge_weight.edge_type.clone(),
                         value: edge_weight.weight,
+                        count: edge_weight.count,
                     })
                 } else {
                     None
@@ -180,16 +346,27 @@ impl Default for CodeGraph {
 }
 
 /// Graph node for visualization
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GraphNode {
     pub id: String,
     pub name: String,
     #[serde(rename = "type")]
     pub node_type: String,
+    /// Lines of code the node spans
     pub size: f64,
+    /// Radius to render the node with, independent of its lines of code
+    #[serde(rename = "visualSize", default = "default_visual_size")]
+    pub visual_size: f64,
     pub color: String,
     #[serde(rename = "filePath")]
     pub file_path: Option<String>,
+    /// Measurements and enrichments by name, such as `loc`, `complexity` or `tags`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub attributes: HashMap<String, AttrValue>,
+}
+
+fn default_visual_size() -> f64 {
+    10.0
 }
 
 /// Graph edge
@@ -197,16 +374,29 @@ pub struct GraphNode {
 pub struct GraphEdge {
     pub edge_type: String,
     pub weight: f64,
+    /// Relationships the edge stands for, more than one once repeats are folded in
+    pub count: usize,
 }
 
 /// Graph link for frontend
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GraphLink {
     pub source: String,
     pub target: String,
     #[serde(rename = "type")]
     pub link_type: String,
     pub value: f64,
+    /// Relationships the link stands for, left out of JSON when it is one
+    #[serde(default = "default_count", skip_serializing_if = "is_single")]
+    pub count: usize,
+}
+
+fn default_count() -> usize {
+    1
+}
+
+fn is_single(count: &usize) -> bool {
+    *count == 1
 }
 
 /// Complete graph data for frontend
@@ -217,6 +407,20 @@ pub struct GraphData {
     pub stats: GraphStats,
 }
 
+impl GraphData {
+    /// Assemble graph data, deriving stats from the nodes and links
+    pub fn from_parts(nodes: Vec<GraphNode>, links: Vec<GraphLink>) -> Self {
+        let stats = GraphStats {
+            node_count: nodes.len(),
+            link_count: links.len(),
+            file_count: nodes.iter().filter(|n| n.node_type == "file").count(),
+            function_count: nodes.iter().filter(|n| n.node_type == "function").count(),
+            class_count: nodes.iter().filter(|n| n.node_type == "class").count(),
+        };
+        Self { nodes, links, stats }
+    }
+}
+
 /// Graph statistics
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GraphStats {
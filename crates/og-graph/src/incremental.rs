@@ -0,0 +1,548 @@
+use crate::graph::{CodeGraph, GraphEdge, GraphLink, GraphNode};
+use petgraph::graph::{EdgeReference, NodeIndex};
+use petgraph::visit::EdgeRef;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Maximum label propagation sweeps over the whole graph
+const MAX_PROPAGATION_ROUNDS: usize = 20;
+
+/// Rank recomputations per graph node a local PageRank update may make
+/// before it falls back to sweeping the whole graph
+const LOCAL_PAGERANK_BUDGET: usize = 4;
+
+/// Set of node and edge changes produced by a partial re-parse
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphChangeSet {
+    pub added_nodes: Vec<GraphNode>,
+    pub removed_nodes: Vec<String>,
+    pub added_edges: Vec<GraphLink>,
+    /// Matched by source, target and type
+    pub removed_edges: Vec<GraphLink>,
+}
+
+impl GraphChangeSet {
+    /// Changes that turn `before` into `after`, matching nodes by id and edges
+    /// by source, target and type
+    ///
+    /// Edges of removed nodes are listed as removed too. Changes to a node's
+    /// or an edge's other fields are not changes to the graph's shape and are
+    /// left out.
+    pub fn between(before: &CodeGraph, after: &CodeGraph) -> Self {
+        let before_edges: HashSet<_> = before.graph.edge_references().map(|edge| edge_key(before, edge)).collect();
+        let after_edges: HashSet<_> = after.graph.edge_references().map(|edge| edge_key(after, edge)).collect();
+        let removed_edges = before
+            .graph
+            .edge_references()
+            .filter(|&edge| !after_edges.contains(&edge_key(before, edge)))
+            .map(|edge| link(before, edge))
+            .collect();
+        let added_edges = after
+            .graph
+            .edge_references()
+            .filter(|&edge| !before_edges.contains(&edge_key(after, edge)))
+            .map(|edge| link(after, edge))
+            .collect();
+
+        Self {
+            added_nodes: after
+                .graph
+                .node_weights()
+                .filter(|node| !before.node_map.contains_key(&node.id))
+                .cloned()
+                .collect(),
+            removed_nodes: before
+                .graph
+                .node_weights()
+                .filter(|node| !after.node_map.contains_key(&node.id))
+                .map(|node| node.id.clone())
+                .collect(),
+            added_edges,
+            removed_edges,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added_nodes.is_empty()
+            && self.removed_nodes.is_empty()
+            && self.added_edges.is_empty()
+            && self.removed_edges.is_empty()
+    }
+}
+
+/// Work done by an incremental update
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IncrementalUpdateStats {
+    /// Sweeps over the whole graph, zero when PageRank was updated locally
+    pub pagerank_iterations: usize,
+    /// Ranks recomputed around the change
+    pub pagerank_nodes_updated: usize,
+    pub nodes_relabelled: usize,
+    pub community_nodes_visited: usize,
+}
+
+/// Degree, PageRank and community assignments kept up to date across deltas
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IncrementalMetrics {
+    pub in_degree: HashMap<String, usize>,
+    pub out_degree: HashMap<String, usize>,
+    pub pagerank: HashMap<String, f64>,
+    pub communities: HashMap<String, usize>,
+    pub damping_factor: f64,
+    pub tolerance: f64,
+    pub max_iterations: usize,
+}
+
+impl IncrementalMetrics {
+    /// Compute all metrics from scratch
+    pub fn compute(graph: &CodeGraph) -> Self {
+        let mut metrics = Self {
+            in_degree: HashMap::new(),
+            out_degree: HashMap::new(),
+            pagerank: HashMap::new(),
+            communities: HashMap::new(),
+            damping_factor: 0.85,
+            tolerance: 1e-6,
+            max_iterations: 100,
+        };
+
+        for idx in graph.graph.node_indices() {
+            let id = graph.graph[idx].id.clone();
+            metrics.in_degree.insert(
+                id.clone(),
+                graph.graph.edges_directed(idx, petgraph::Direction::Incoming).count(),
+            );
+            metrics.out_degree.insert(
+                id.clone(),
+                graph.graph.edges_directed(idx, petgraph::Direction::Outgoing).count(),
+            );
+            metrics.communities.insert(id, idx.index());
+        }
+
+        metrics.refresh_pagerank(graph);
+        let all: Vec<NodeIndex> = graph.graph.node_indices().collect();
+        metrics.propagate_labels(graph, all);
+        metrics
+    }
+
+    /// Apply a change set to the graph and update metrics in place
+    ///
+    /// Degrees are adjusted exactly, while PageRank and label propagation
+    /// only revisit nodes near the change.
+    pub fn update(&mut self, graph: &mut CodeGraph, changes: &GraphChangeSet) -> IncrementalUpdateStats {
+        let mut stats = IncrementalUpdateStats::default();
+        if changes.is_empty() {
+            return stats;
+        }
+        let previous_count = graph.graph.node_count();
+
+        let mut touched: HashSet<String> = HashSet::new();
+
+        for edge in &changes.removed_edges {
+            if graph.remove_edge(&edge.source, &edge.target, &edge.link_type) {
+                decrement(&mut self.out_degree, &edge.source);
+                decrement(&mut self.in_degree, &edge.target);
+                touched.insert(edge.source.clone());
+                touched.insert(edge.target.clone());
+            }
+        }
+
+        for id in &changes.removed_nodes {
+            let Some(&idx) = graph.node_map.get(id) else {
+                continue;
+            };
+            // Neighbours lose the edges that go away with the node
+            for edge in graph.graph.edges_directed(idx, petgraph::Direction::Outgoing) {
+                let target = graph.graph[edge.target()].id.clone();
+                decrement(&mut self.in_degree, &target);
+                touched.insert(target);
+            }
+            for edge in graph.graph.edges_directed(idx, petgraph::Direction::Incoming) {
+                let source = graph.graph[edge.source()].id.clone();
+                decrement(&mut self.out_degree, &source);
+                touched.insert(source);
+            }
+            graph.remove_node(id);
+            self.in_degree.remove(id);
+            self.out_degree.remove(id);
+            self.pagerank.remove(id);
+            self.communities.remove(id);
+            touched.remove(id);
+        }
+
+        let mut next_label = self.communities.values().copied().max().map_or(0, |max| max + 1);
+        for node in &changes.added_nodes {
+            if graph.node_map.contains_key(&node.id) {
+                continue;
+            }
+            graph.add_node(node.clone());
+            self.in_degree.insert(node.id.clone(), 0);
+            self.out_degree.insert(node.id.clone(), 0);
+            self.communities.insert(node.id.clone(), next_label);
+            next_label += 1;
+            touched.insert(node.id.clone());
+        }
+
+        for link in &changes.added_edges {
+            if !graph.node_map.contains_key(&link.source) || !graph.node_map.contains_key(&link.target) {
+                continue;
+            }
+            // A repeat of an existing edge is folded into it and adds no degree
+            let repeat = graph
+                .graph
+                .edges_connecting(graph.node_map[&link.source], graph.node_map[&link.target])
+                .any(|edge| edge.weight().edge_type == link.link_type);
+            graph.add_edge(
+                &link.source,
+                &link.target,
+                GraphEdge {
+                    edge_type: link.link_type.clone(),
+                    weight: link.value,
+                    count: link.count,
+                },
+            );
+            if repeat {
+                continue;
+            }
+            *self.out_degree.entry(link.source.clone()).or_insert(0) += 1;
+            *self.in_degree.entry(link.target.clone()).or_insert(0) += 1;
+            touched.insert(link.source.clone());
+            touched.insert(link.target.clone());
+        }
+
+        let seeds: Vec<NodeIndex> = touched
+            .iter()
+            .filter_map(|id| graph.node_map.get(id).copied())
+            .collect();
+        match self.update_pagerank_near(graph, previous_count, &seeds) {
+            Some(updated) => stats.pagerank_nodes_updated = updated,
+            None => stats.pagerank_iterations = self.refresh_pagerank(graph),
+        }
+
+        let (visited, relabelled) = self.propagate_labels(graph, seeds);
+        stats.community_nodes_visited = visited;
+        stats.nodes_relabelled = relabelled;
+        stats
+    }
+
+    /// Power iteration warm-started from the current ranks; returns iterations used
+    fn refresh_pagerank(&mut self, graph: &CodeGraph) -> usize {
+        let node_count = graph.graph.node_count();
+        if node_count == 0 {
+            self.pagerank.clear();
+            return 0;
+        }
+
+        let initial_rank = 1.0 / node_count as f64;
+        let mut ranks: Vec<f64> = graph
+            .graph
+            .node_indices()
+            .map(|idx| self.pagerank.get(&graph.graph[idx].id).copied().unwrap_or(initial_rank))
+            .collect();
+        normalize(&mut ranks);
+
+        let out_counts: Vec<usize> = graph
+            .graph
+            .node_indices()
+            .map(|idx| graph.graph.edges(idx).count())
+            .collect();
+
+        let mut iterations = 0;
+        while iterations < self.max_iterations {
+            iterations += 1;
+            let mut next = vec![(1.0 - self.damping_factor) / node_count as f64; node_count];
+            for edge in graph.graph.edge_references() {
+                let source = edge.source().index();
+                next[edge.target().index()] += self.damping_factor * ranks[source] / out_counts[source] as f64;
+            }
+            let delta: f64 = next.iter().zip(&ranks).map(|(a, b)| (a - b).abs()).sum();
+            ranks = next;
+            if delta < self.tolerance {
+                break;
+            }
+        }
+
+        self.pagerank = graph
+            .graph
+            .node_indices()
+            .map(|idx| (graph.graph[idx].id.clone(), ranks[idx.index()]))
+            .collect();
+        iterations
+    }
+
+    /// Recompute ranks outward from the nodes a change touched; returns the
+    /// ranks recomputed, or None when the change reaches too far to stay local
+    ///
+    /// Ranks solve a linear system whose constant term is `1 - d` over the node
+    /// count, so scaling the old ranks by the ratio of node counts solves it
+    /// for the old edges. From there, only nodes whose incoming rank changed
+    /// need recomputing, and each recomputation that moves a rank queues the
+    /// nodes it points to.
+    fn update_pagerank_near(&mut self, graph: &CodeGraph, previous_count: usize, seeds: &[NodeIndex]) -> Option<usize> {
+        let node_count = graph.graph.node_count();
+        if node_count == 0 || previous_count == 0 {
+            return None;
+        }
+
+        let scale = previous_count as f64 / node_count as f64;
+        if scale != 1.0 {
+            for rank in self.pagerank.values_mut() {
+                *rank *= scale;
+            }
+        }
+        // A touched node's own rank, and the rank it passes on, may have changed.
+        // Added nodes are among them and start from the rank nothing links to.
+        let base = (1.0 - self.damping_factor) / node_count as f64;
+        let mut queue: VecDeque<NodeIndex> = VecDeque::new();
+        let mut queued: HashSet<NodeIndex> = HashSet::new();
+        for &seed in seeds {
+            if !self.pagerank.contains_key(&graph.graph[seed].id) {
+                self.pagerank.insert(graph.graph[seed].id.clone(), base);
+            }
+            for idx in std::iter::once(seed).chain(graph.graph.neighbors(seed)) {
+                if queued.insert(idx) {
+                    queue.push_back(idx);
+                }
+            }
+        }
+
+        let threshold = self.tolerance / node_count as f64;
+        let budget = node_count * LOCAL_PAGERANK_BUDGET;
+        let mut updated = 0;
+        while let Some(idx) = queue.pop_front() {
+            queued.remove(&idx);
+            updated += 1;
+            if updated > budget {
+                return None;
+            }
+
+            let incoming: f64 = graph
+                .graph
+                .edges_directed(idx, petgraph::Direction::Incoming)
+                .map(|edge| {
+                    let source = edge.source();
+                    self.pagerank[&graph.graph[source].id] / graph.graph.edges(source).count() as f64
+                })
+                .sum();
+            let rank = base + self.damping_factor * incoming;
+            let previous = self.pagerank.insert(graph.graph[idx].id.clone(), rank).unwrap_or(base);
+            if (rank - previous).abs() > threshold {
+                for next in graph.graph.neighbors(idx) {
+                    if queued.insert(next) {
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+        Some(updated)
+    }
+
+    /// Label propagation over the undirected view, starting from `seeds`
+    ///
+    /// A node whose label changes queues its neighbours, so work stays local
+    /// to the region affected by a delta. Returns (visited, relabelled).
+    fn propagate_labels(&mut self, graph: &CodeGraph, seeds: Vec<NodeIndex>) -> (usize, usize) {
+        let budget = graph.graph.node_count() * MAX_PROPAGATION_ROUNDS;
+        let mut queued: HashSet<NodeIndex> = seeds.iter().copied().collect();
+        let mut queue: VecDeque<NodeIndex> = seeds.into_iter().collect();
+        let mut visited = 0;
+        let mut relabelled: HashSet<NodeIndex> = HashSet::new();
+
+        while let Some(idx) = queue.pop_front() {
+            queued.remove(&idx);
+            visited += 1;
+            if visited > budget {
+                break;
+            }
+
+            let mut weights: HashMap<usize, f64> = HashMap::new();
+            for edge in graph
+                .graph
+                .edges_directed(idx, petgraph::Direction::Outgoing)
+                .chain(graph.graph.edges_directed(idx, petgraph::Direction::Incoming))
+            {
+                let other = if edge.source() == idx { edge.target() } else { edge.source() };
+                if other == idx {
+                    continue;
+                }
+                let weight = if edge.weight().weight.is_finite() { edge.weight().weight } else { 1.0 };
+                *weights.entry(self.communities[&graph.graph[other].id]).or_insert(0.0) += weight;
+            }
+
+            let id = &graph.graph[idx].id;
+            let current = self.communities[id];
+            // Highest weight wins; ties go to the smallest label for determinism
+            let best = weights
+                .into_iter()
+                .max_by(|a, b| a.1.total_cmp(&b.1).then_with(|| b.0.cmp(&a.0)))
+                .map(|(label, _)| label);
+
+            if let Some(best) = best {
+                if best != current {
+                    self.communities.insert(id.clone(), best);
+                    relabelled.insert(idx);
+                    for neighbor in graph.graph.neighbors_undirected(idx) {
+                        if queued.insert(neighbor) {
+                            queue.push_back(neighbor);
+                        }
+                    }
+                }
+            }
+        }
+
+        (visited, relabelled.len())
+    }
+}
+
+fn edge_key(graph: &CodeGraph, edge: EdgeReference<'_, GraphEdge>) -> (String, String, String) {
+    (
+        graph.graph[edge.source()].id.clone(),
+        graph.graph[edge.target()].id.clone(),
+        edge.weight().edge_type.clone(),
+    )
+}
+
+fn link(graph: &CodeGraph, edge: EdgeReference<'_, GraphEdge>) -> GraphLink {
+    GraphLink {
+        source: graph.graph[edge.source()].id.clone(),
+        target: graph.graph[edge.target()].id.clone(),
+        link_type: edge.weight().edge_type.clone(),
+        value: edge.weight().weight,
+        count: edge.weight().count,
+    }
+}
+
+fn decrement(counts: &mut HashMap<String, usize>, id: &str) {
+    if let Some(count) = counts.get_mut(id) {
+        *count = count.saturating_sub(1);
+    }
+}
+
+fn normalize(values: &mut [f64]) {
+    let sum: f64 = values.iter().sum();
+    if sum > 0.0 {
+        for value in values.iter_mut() {
+            *value /= sum;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{graph, node};
+
+    fn link(source: &str, target: &str) -> GraphLink {
+        GraphLink {
+            source: source.to_string(),
+            target: target.to_string(),
+            link_type: "imports".to_string(),
+            value: 1.0,
+            count: 1,
+        }
+    }
+
+    #[test]
+    fn test_incremental_matches_full_recompute() {
+        let mut graph = graph(
+            &["a", "b", "c", "d"],
+            &[("a", "b"), ("b", "c"), ("c", "a"), ("c", "d")],
+        );
+        let mut metrics = IncrementalMetrics::compute(&graph);
+
+        let changes = GraphChangeSet {
+            added_nodes: vec![node("e")],
+            removed_nodes: vec!["a".to_string()],
+            added_edges: vec![link("d", "e"), link("e", "b")],
+            removed_edges: vec![link("c", "d")],
+        };
+        metrics.update(&mut graph, &changes);
+
+        let full = IncrementalMetrics::compute(&graph);
+        assert_eq!(metrics.in_degree, full.in_degree);
+        assert_eq!(metrics.out_degree, full.out_degree);
+        assert!(!metrics.pagerank.contains_key("a"));
+        for (id, rank) in &full.pagerank {
+            assert!((metrics.pagerank[id] - rank).abs() < 1e-4, "rank mismatch for {}", id);
+        }
+    }
+
+    #[test]
+    fn test_update_after_cycle_closed() {
+        let ids: Vec<String> = (0..200).map(|i| format!("n{}", i)).collect();
+        let id_refs: Vec<&str> = ids.iter().map(String::as_str).collect();
+        let edges: Vec<(&str, &str)> = (0..199).map(|i| (id_refs[i], id_refs[i + 1])).collect();
+        let mut graph = graph(&id_refs, &edges);
+        let mut metrics = IncrementalMetrics::compute(&graph);
+
+        let changes = GraphChangeSet {
+            added_edges: vec![link("n199", "n0")],
+            ..Default::default()
+        };
+        let stats = metrics.update(&mut graph, &changes);
+        assert!(stats.pagerank_iterations < metrics.max_iterations);
+        assert_eq!(metrics.in_degree["n0"], 1);
+        assert_eq!(metrics.out_degree["n199"], 1);
+    }
+
+    #[test]
+    fn test_local_update_beats_full_recompute() {
+        // 400 packages of 50 files, each file importing two others of its package
+        let ids: Vec<String> = (0..20_000).map(|i| format!("p{}/f{}", i / 50, i % 50)).collect();
+        let id_refs: Vec<&str> = ids.iter().map(String::as_str).collect();
+        let edges: Vec<(&str, &str)> = (0..20_000)
+            .flat_map(|i| [(i, 2 * (i % 50) + 1), (i, 2 * (i % 50) + 2)])
+            .filter(|(_, child)| *child < 50)
+            .map(|(i, child)| (id_refs[i], id_refs[i / 50 * 50 + child]))
+            .collect();
+        let mut graph = graph(&id_refs, &edges);
+        let mut metrics = IncrementalMetrics::compute(&graph);
+
+        let changes = GraphChangeSet {
+            added_nodes: vec![node("p0/new")],
+            added_edges: vec![link("p0/f3", "p0/new"), link("p0/new", "p0/f40")],
+            removed_edges: vec![link("p0/f3", "p0/f7")],
+            ..Default::default()
+        };
+        let started = std::time::Instant::now();
+        let stats = metrics.update(&mut graph, &changes);
+        let update_time = started.elapsed();
+
+        let started = std::time::Instant::now();
+        let full = IncrementalMetrics::compute(&graph);
+        let compute_time = started.elapsed();
+
+        assert_eq!(stats.pagerank_iterations, 0);
+        assert!(stats.pagerank_nodes_updated < 50, "{:?}", stats);
+        assert!(update_time < compute_time, "update {:?}, compute {:?}", update_time, compute_time);
+        assert!(update_time < std::time::Duration::from_secs(1));
+        for (id, rank) in &full.pagerank {
+            assert!((metrics.pagerank[id] - rank).abs() < 1e-6, "rank mismatch for {}", id);
+        }
+    }
+
+    #[test]
+    fn test_change_set_between_graphs() {
+        let mut before = graph(&["a", "b", "c"], &[("a", "b"), ("b", "c"), ("c", "a")]);
+        let after = graph(&["a", "b", "d"], &[("a", "b"), ("b", "d"), ("d", "a")]);
+        let mut metrics = IncrementalMetrics::compute(&before);
+
+        let changes = GraphChangeSet::between(&before, &after);
+        assert_eq!(changes.removed_nodes, vec!["c"]);
+        assert_eq!(changes.added_nodes.len(), 1);
+        assert_eq!(changes.added_edges.len(), 2);
+        assert_eq!(changes.removed_edges.len(), 2);
+        assert!(GraphChangeSet::between(&after, &after).is_empty());
+
+        metrics.update(&mut before, &changes);
+        let full = IncrementalMetrics::compute(&after);
+        assert_eq!(metrics.in_degree, full.in_degree);
+        assert_eq!(metrics.out_degree, full.out_degree);
+        for (id, rank) in &full.pagerank {
+            assert!((metrics.pagerank[id] - rank).abs() < 1e-4, "rank mismatch for {}", id);
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use crate::graph::CodeGraph;
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+/// Dependencies of one file on another in a layer its own layer may not use
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LayerViolation {
+    pub source_file: String,
+    pub source_layer: String,
+    pub target_file: String,
+    pub target_layer: String,
+    /// Edges from nodes of the source file to nodes of the target file
+    pub edges: usize,
+}
+
+impl CodeGraph {
+    /// Dependencies between files that cross layers against the rules
+    ///
+    /// `layer_of` maps file paths to their layer and `allowed` each layer to
+    /// the other layers it may depend on. A layer may always depend on itself,
+    /// and files in no layer are not checked. Containment is not a dependency.
+    pub fn layer_violations(
+        &self,
+        layer_of: &HashMap<String, String>,
+        allowed: &HashMap<String, HashSet<String>>,
+    ) -> Vec<LayerViolation> {
+        let layer = |idx: NodeIndex| {
+            let file = self.graph[idx].file_path.as_deref()?;
+            Some((file, layer_of.get(file)?.as_str()))
+        };
+
+        let mut crossings: BTreeMap<(&str, &str), (&str, &str, usize)> = BTreeMap::new();
+        for edge in self.graph.edge_references() {
+            if edge.weight().edge_type.eq_ignore_ascii_case("contains") {
+                continue;
+            }
+            let (Some((source_file, source_layer)), Some((target_file, target_layer))) =
+                (layer(edge.source()), layer(edge.target()))
+            else {
+                continue;
+            };
+            let permitted = source_layer == target_layer
+                || allowed.get(source_layer).is_some_and(|layers| layers.contains(target_layer));
+            if !permitted {
+                crossings.entry((source_file, target_file)).or_insert((source_layer, target_layer, 0)).2 += 1;
+            }
+        }
+
+        crossings
+            .into_iter()
+            .map(|((source_file, target_file), (source_layer, target_layer, edges))| LayerViolation {
+                source_file: source_file.to_string(),
+                source_layer: source_layer.to_string(),
+                target_file: target_file.to_string(),
+                target_layer: target_layer.to_string(),
+                edges,
+            })
+            .collect()
+    }
+
+    /// Files no dependency path from the entry point files leads to, sorted
+    ///
+    /// Empty when no entry points are given, since nothing is then known to
+    /// be reachable.
+    pub fn unreachable_files(&self, entry_files: &HashSet<String>) -> Vec<String> {
+        if entry_files.is_empty() {
+            return Vec::new();
+        }
+
+        let mut reached = HashSet::new();
+        for idx in self.graph.node_indices() {
+            let is_entry = self.graph[idx]
+                .file_path
+                .as_ref()
+                .is_some_and(|file| entry_files.contains(file));
+            if is_entry && !reached.contains(&idx) {
+                reached.extend(self.reachable(idx, Direction::Outgoing, None));
+            }
+        }
+
+        let reached_files: HashSet<&str> = reached
+            .iter()
+            .filter_map(|&idx| self.graph[idx].file_path.as_deref())
+            .collect();
+        let files: BTreeSet<&str> = self
+            .graph
+            .node_weights()
+            .filter_map(|node| node.file_path.as_deref())
+            .filter(|file| !reached_files.contains(file))
+            .collect();
+        files.into_iter().map(str::to_string).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::GraphNode;
+    use crate::testing::{edge, node};
+
+    fn add(graph: &mut CodeGraph, id: &str, file: &str) {
+        graph.add_node(GraphNode {
+            node_type: if id == file { "file" } else { "function" }.to_string(),
+            file_path: Some(file.to_string()),
+            ..node(id)
+        });
+    }
+
+    fn link(graph: &mut CodeGraph, source: &str, target: &str, edge_type: &str) {
+        graph.add_edge(source, target, edge(edge_type));
+    }
+
+    // ui.ts -> service.ts -> db.ts, db.ts::query -> ui.ts, and lone.ts on its own
+    fn sample() -> CodeGraph {
+        let mut graph = CodeGraph::new();
+        for file in ["ui.ts", "service.ts", "db.ts", "lone.ts"] {
+            add(&mut graph, file, file);
+        }
+        add(&mut graph, "db.ts::query", "db.ts");
+        link(&mut graph, "ui.ts", "service.ts", "imports");
+        link(&mut graph, "service.ts", "db.ts", "imports");
+        link(&mut graph, "db.ts", "db.ts::query", "contains");
+        link(&mut graph, "db.ts::query", "ui.ts", "calls");
+        graph
+    }
+
+    #[test]
+    fn test_layer_violations() {
+        let layer_of: HashMap<String, String> = [("ui.ts", "ui"), ("service.ts", "domain"), ("db.ts", "data")]
+            .into_iter()
+            .map(|(file, layer)| (file.to_string(), layer.to_string()))
+            .collect();
+        let allowed = HashMap::from([
+            ("ui".to_string(), HashSet::from(["domain".to_string()])),
+            ("domain".to_string(), HashSet::from(["data".to_string()])),
+        ]);
+
+        let violations = sample().layer_violations(&layer_of, &allowed);
+        assert_eq!(
+            violations,
+            vec![LayerViolation {
+                source_file: "db.ts".to_string(),
+                source_layer: "data".to_string(),
+                target_file: "ui.ts".to_string(),
+                target_layer: "ui".to_string(),
+                edges: 1,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_unreachable_files_from_entry_points() {
+        let graph = sample();
+        assert_eq!(graph.unreachable_files(&HashSet::from(["ui.ts".to_string()])), vec!["lone.ts"]);
+        assert_eq!(
+            graph.unreachable_files(&HashSet::from(["db.ts".to_string()])),
+            vec!["lone.ts"],
+            "a call from a contained function reaches on"
+        );
+        assert!(graph.unreachable_files(&HashSet::new()).is_empty());
+    }
+}
@@ -1,5 +1,51 @@
+pub mod attributes;
 pub mod builder;
+pub mod bundling;
+pub mod check;
+pub mod components;
+pub mod condensation;
+pub mod direction;
+pub mod edge_stats;
+pub mod env;
+pub mod external;
+pub mod file_tree;
 pub mod graph;
+pub mod incremental;
+pub mod layers;
+pub mod overrides;
+pub mod paging;
+pub mod plantuml;
+pub mod projection;
+pub mod pruning;
+pub mod routes;
+pub mod slice;
+pub mod stats;
+pub mod subtree;
+#[cfg(any(test, feature = "test-util"))]
+pub mod testing;
+pub mod viewport;
 
+pub use attributes::*;
 pub use builder::*;
-pub use graph::*;
\ No newline at end of file
+pub use bundling::*;
+pub use check::*;
+pub use components::*;
+pub use condensation::*;
+pub use direction::*;
+pub use edge_stats::*;
+pub use env::*;
+pub use external::*;
+pub use file_tree::*;
+pub use graph::*;
+pub use incremental::*;
+pub use layers::*;
+pub use overrides::*;
+pub use paging::*;
+pub use plantuml::*;
+pub use projection::*;
+pub use pruning::*;
+pub use routes::*;
+pub use slice::*;
+pub use stats::*;
+pub use subtree::*;
+pub use viewport::*;
@@ -0,0 +1,197 @@
+use crate::graph::{CodeGraph, GraphEdge, GraphNode};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// File under a codebase root that holds manual graph overrides
+pub const OVERRIDES_PATH: &str = ".omnigraph/overrides.json";
+
+/// Color of nodes declared by hand rather than found by the analysis
+const MANUAL_NODE_COLOR: &str = "#9E9E9E";
+
+/// A node the analysis cannot see, such as an external service
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeOverride {
+    pub id: String,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub node_type: String,
+    /// Why the node was declared
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// A dependency between two node ids, added or suppressed by hand
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EdgeOverride {
+    pub source: String,
+    pub target: String,
+    /// Edge type, such as `imports` or `calls`
+    #[serde(rename = "type")]
+    pub edge_type: String,
+    /// Why the edge was added or suppressed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// Manual corrections merged into the graph after every analysis
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Overrides {
+    pub nodes: Vec<NodeOverride>,
+    /// Dependencies the static analysis misses, such as runtime imports
+    pub added_edges: Vec<EdgeOverride>,
+    /// False-positive dependencies to drop
+    pub suppressed_edges: Vec<EdgeOverride>,
+}
+
+/// Which overrides changed the graph, for marking overridden elements
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverrideReport {
+    pub added_nodes: Vec<NodeOverride>,
+    pub added_edges: Vec<EdgeOverride>,
+    pub suppressed_edges: Vec<EdgeOverride>,
+    /// Edge overrides naming a node or edge the graph does not have
+    pub unmatched_edges: Vec<EdgeOverride>,
+}
+
+impl Overrides {
+    fn path(base_path: &Path) -> PathBuf {
+        base_path.join(OVERRIDES_PATH)
+    }
+
+    /// Overrides of a codebase, empty if none were saved or the file is unreadable
+    pub fn load(base_path: &Path) -> Self {
+        let path = Self::path(base_path);
+        let Ok(bytes) = std::fs::read(&path) else {
+            return Self::default();
+        };
+        serde_json::from_slice(&bytes).unwrap_or_else(|e| {
+            tracing::warn!("Ignoring unreadable overrides in {}: {}", path.display(), e);
+            Self::default()
+        })
+    }
+
+    pub fn save(&self, base_path: &Path) -> std::io::Result<()> {
+        let path = Self::path(base_path);
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let json = serde_json::to_vec_pretty(self).map_err(std::io::Error::other)?;
+        std::fs::write(path, json)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty() && self.added_edges.is_empty() && self.suppressed_edges.is_empty()
+    }
+}
+
+impl CodeGraph {
+    /// Merge manual overrides into the graph
+    ///
+    /// Declared nodes are added unless the analysis found them, then
+    /// suppressed edges are removed and added edges created with weight 1.
+    /// An added edge the graph already has is left alone.
+    pub fn apply_overrides(&mut self, overrides: &Overrides) -> OverrideReport {
+        let mut report = OverrideReport::default();
+        for node in &overrides.nodes {
+            if self.node_map.contains_key(&node.id) {
+                continue;
+            }
+            self.add_node(GraphNode {
+                id: node.id.clone(),
+                name: node.name.clone(),
+                node_type: node.node_type.clone(),
+                size: 0.0,
+                visual_size: 10.0,
+                color: MANUAL_NODE_COLOR.to_string(),
+                file_path: None,
+                attributes: Default::default(),
+            });
+            report.added_nodes.push(node.clone());
+        }
+
+        for edge in &overrides.suppressed_edges {
+            let mut removed = false;
+            while self.remove_edge(&edge.source, &edge.target, &edge.edge_type) {
+                removed = true;
+            }
+            if removed {
+                report.suppressed_edges.push(edge.clone());
+            } else {
+                report.unmatched_edges.push(edge.clone());
+            }
+        }
+
+        for edge in &overrides.added_edges {
+            let (Some(&source), Some(&target)) = (self.node_map.get(&edge.source), self.node_map.get(&edge.target))
+            else {
+                report.unmatched_edges.push(edge.clone());
+                continue;
+            };
+            let exists = self
+                .graph
+                .edges_connecting(source, target)
+                .any(|existing| existing.weight().edge_type == edge.edge_type);
+            if !exists {
+                self.graph.add_edge(
+                    source,
+                    target,
+                    GraphEdge {
+                        edge_type: edge.edge_type.clone(),
+                        weight: 1.0,
+                        count: 1,
+                    },
+                );
+                report.added_edges.push(edge.clone());
+            }
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn edge(source: &str, target: &str) -> EdgeOverride {
+        EdgeOverride {
+            source: source.to_string(),
+            target: target.to_string(),
+            edge_type: "imports".to_string(),
+            reason: None,
+        }
+    }
+
+    #[test]
+    fn test_apply_overrides() {
+        let mut graph = testing::graph(&["a", "b"], &[("a", "b"), ("a", "b")]);
+
+        let overrides = Overrides {
+            nodes: vec![NodeOverride {
+                id: "svc".to_string(),
+                name: "payments".to_string(),
+                node_type: "service".to_string(),
+                reason: Some("called over HTTP".to_string()),
+            }],
+            added_edges: vec![edge("b", "svc"), edge("b", "missing")],
+            suppressed_edges: vec![edge("a", "b"), edge("b", "a")],
+        };
+        let report = graph.apply_overrides(&overrides);
+
+        assert_eq!(report.added_nodes.len(), 1);
+        assert_eq!(report.added_edges, vec![edge("b", "svc")]);
+        assert_eq!(report.suppressed_edges, vec![edge("a", "b")]);
+        assert_eq!(report.unmatched_edges, vec![edge("b", "a"), edge("b", "missing")]);
+        let links = graph.to_frontend_format().links;
+        assert_eq!(links.len(), 1);
+        assert_eq!((links[0].source.as_str(), links[0].target.as_str()), ("b", "svc"));
+
+        // Applying again finds the manual node and edge already present
+        let again = graph.apply_overrides(&overrides);
+        assert!(again.added_nodes.is_empty() && again.added_edges.is_empty());
+    }
+}
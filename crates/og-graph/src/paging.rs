@@ -0,0 +1,114 @@
+use crate::bundling::BundledEdge;
+use crate::graph::{GraphData, GraphLink, GraphNode};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// One chunk of a graph for progressive loading
+///
+/// Links are delivered with the page that loads the later of their two
+/// endpoints, so after pages `0..=n` every link between loaded nodes is present.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphPage {
+    pub offset: usize,
+    pub nodes: Vec<GraphNode>,
+    pub links: Vec<GraphLink>,
+    pub total_nodes: usize,
+    pub total_links: usize,
+    pub has_more: bool,
+    /// Links of this page between directories, merged, when requested
+    #[serde(default)]
+    pub bundles: Vec<BundledEdge>,
+}
+
+impl GraphData {
+    /// Page of nodes starting at `offset`, with the links they complete
+    pub fn page(&self, offset: usize, limit: usize) -> GraphPage {
+        let start = offset.min(self.nodes.len());
+        let end = offset.saturating_add(limit).min(self.nodes.len());
+
+        let position: HashMap<&str, usize> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (node.id.as_str(), i))
+            .collect();
+
+        let links = self
+            .links
+            .iter()
+            .filter(|link| {
+                match (position.get(link.source.as_str()), position.get(link.target.as_str())) {
+                    (Some(&source), Some(&target)) => (start..end).contains(&source.max(target)),
+                    _ => false,
+                }
+            })
+            .cloned()
+            .collect();
+
+        GraphPage {
+            offset: start,
+            nodes: self.nodes[start..end].to_vec(),
+            links,
+            total_nodes: self.nodes.len(),
+            total_links: self.links.len(),
+            has_more: end < self.nodes.len(),
+            bundles: Vec::new(),
+        }
+    }
+
+    /// Nodes in `start..end`, clamped to the node count
+    pub fn node_range(&self, start: usize, end: usize) -> &[GraphNode] {
+        let end = end.min(self.nodes.len());
+        &self.nodes[start.min(end)..end]
+    }
+
+    /// Links in `start..end`, clamped to the link count
+    pub fn link_range(&self, start: usize, end: usize) -> &[GraphLink] {
+        let end = end.min(self.links.len());
+        &self.links[start.min(end)..end]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::CodeGraph;
+    use crate::testing::graph;
+
+    fn chain(ids: &[&str]) -> CodeGraph {
+        let links: Vec<(&str, &str)> = ids.windows(2).map(|pair| (pair[0], pair[1])).collect();
+        graph(ids, &links)
+    }
+
+    #[test]
+    fn test_pages_deliver_each_link_once() {
+        let data = chain(&["a", "b", "c", "d", "e"]).to_frontend_format();
+
+        let first = data.page(0, 2);
+        assert_eq!(first.nodes.len(), 2);
+        assert_eq!(first.links.len(), 1);
+        assert!(first.has_more);
+
+        let mut links = first.links.len();
+        let mut offset = 2;
+        loop {
+            let page = data.page(offset, 2);
+            links += page.links.len();
+            offset += page.nodes.len();
+            if !page.has_more {
+                break;
+            }
+        }
+        assert_eq!(offset, 5);
+        assert_eq!(links, data.links.len());
+    }
+
+    #[test]
+    fn test_ranges_clamp_to_bounds() {
+        let data = chain(&["a", "b", "c"]).to_frontend_format();
+        assert_eq!(data.node_range(1, 10).len(), 2);
+        assert!(data.node_range(5, 10).is_empty());
+        assert_eq!(data.link_range(0, 1).len(), 1);
+        assert!(data.page(10, 5).nodes.is_empty());
+    }
+}
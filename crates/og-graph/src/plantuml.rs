@@ -0,0 +1,222 @@
+use crate::graph::CodeGraph;
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::fmt::Write;
+
+/// Neighbor hops included around the chosen class when no depth is given
+pub const DEFAULT_DIAGRAM_DEPTH: usize = 1;
+
+fn is_type(node_type: &str) -> bool {
+    matches!(node_type, "class" | "interface")
+}
+
+/// Relation drawn between two classes, strongest first so a class that both
+/// extends and calls another is only drawn as extending it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Relation {
+    Extends,
+    Implements,
+    Depends,
+}
+
+impl Relation {
+    fn arrow(self) -> &'static str {
+        match self {
+            Relation::Extends => "--|>",
+            Relation::Implements => "..|>",
+            Relation::Depends => "..>",
+        }
+    }
+}
+
+fn quote(name: &str) -> String {
+    name.replace('"', "'")
+}
+
+impl CodeGraph {
+    /// Class or interface each method and property belongs to
+    fn member_owners(&self) -> HashMap<NodeIndex, NodeIndex> {
+        self.graph
+            .edge_references()
+            .filter(|edge| edge.weight().edge_type.eq_ignore_ascii_case("contains"))
+            .filter(|edge| is_type(&self.graph[edge.source()].node_type))
+            .map(|edge| (edge.target(), edge.source()))
+            .collect()
+    }
+
+    /// Relations between classes, with member-level edges lifted to their owners
+    fn class_relations(&self, owners: &HashMap<NodeIndex, NodeIndex>) -> BTreeMap<(NodeIndex, NodeIndex), Relation> {
+        let class_of = |idx: NodeIndex| {
+            if is_type(&self.graph[idx].node_type) {
+                Some(idx)
+            } else {
+                owners.get(&idx).copied()
+            }
+        };
+
+        let mut relations: BTreeMap<(NodeIndex, NodeIndex), Relation> = BTreeMap::new();
+        for edge in self.graph.edge_references() {
+            let edge_type = edge.weight().edge_type.to_ascii_lowercase();
+            let relation = match edge_type.as_str() {
+                "contains" => continue,
+                "extends" => Relation::Extends,
+                "implements" => Relation::Implements,
+                _ => Relation::Depends,
+            };
+            let (Some(source), Some(target)) = (class_of(edge.source()), class_of(edge.target())) else {
+                continue;
+            };
+            if source == target {
+                continue;
+            }
+            let entry = relations.entry((source, target)).or_insert(relation);
+            *entry = (*entry).min(relation);
+        }
+        relations
+    }
+
+    /// PlantUML class diagram of a class and its neighbors within `depth` hops
+    ///
+    /// A file starts from every class it contains, and a method from its class.
+    /// Returns None if the node does not exist or has no class to start from.
+    pub fn plantuml_class_diagram(&self, node_id: &str, depth: usize) -> Option<String> {
+        let start = *self.node_map.get(node_id)?;
+        let owners = self.member_owners();
+
+        let seeds: Vec<NodeIndex> = match self.graph[start].node_type.as_str() {
+            t if is_type(t) => vec![start],
+            "file" => self
+                .graph
+                .edges(start)
+                .filter(|edge| edge.weight().edge_type.eq_ignore_ascii_case("contains"))
+                .map(|edge| edge.target())
+                .filter(|&idx| is_type(&self.graph[idx].node_type))
+                .collect(),
+            _ => owners.get(&start).copied().into_iter().collect(),
+        };
+        if seeds.is_empty() {
+            return None;
+        }
+
+        let relations = self.class_relations(&owners);
+        let mut neighbors: HashMap<NodeIndex, Vec<NodeIndex>> = HashMap::new();
+        for &(source, target) in relations.keys() {
+            neighbors.entry(source).or_default().push(target);
+            neighbors.entry(target).or_default().push(source);
+        }
+
+        let mut included: BTreeSet<NodeIndex> = seeds.iter().copied().collect();
+        let mut queue: VecDeque<(NodeIndex, usize)> = seeds.iter().map(|&idx| (idx, 0)).collect();
+        while let Some((idx, hops)) = queue.pop_front() {
+            if hops >= depth {
+                continue;
+            }
+            for &next in neighbors.get(&idx).into_iter().flatten() {
+                if included.insert(next) {
+                    queue.push_back((next, hops + 1));
+                }
+            }
+        }
+
+        let mut members: HashMap<NodeIndex, Vec<String>> = HashMap::new();
+        for (&member, &owner) in &owners {
+            let node = &self.graph[member];
+            let line = match node.node_type.as_str() {
+                "method" | "function" => format!("  +{}()", node.name),
+                "property" | "variable" => format!("  {}", node.name),
+                _ => continue,
+            };
+            members.entry(owner).or_default().push(line);
+        }
+
+        let alias = |idx: NodeIndex| format!("n{}", idx.index());
+        let mut out = String::from("@startuml\n");
+        for &idx in &included {
+            let node = &self.graph[idx];
+            let keyword = if node.node_type == "interface" { "interface" } else { "class" };
+            let _ = write!(out, "{} \"{}\" as {}", keyword, quote(&node.name), alias(idx));
+            match members.get_mut(&idx) {
+                Some(lines) if !lines.is_empty() => {
+                    lines.sort();
+                    let _ = writeln!(out, " {{\n{}\n}}", lines.join("\n"));
+                }
+                _ => out.push('\n'),
+            }
+        }
+        for (&(source, target), relation) in &relations {
+            if included.contains(&source) && included.contains(&target) {
+                let _ = writeln!(out, "{} {} {}", alias(source), relation.arrow(), alias(target));
+            }
+        }
+        out.push_str("@enduml\n");
+        Some(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::GraphNode;
+    use crate::testing::{edge, node};
+
+    fn graph() -> CodeGraph {
+        let mut graph = CodeGraph::new();
+        for (id, node_type) in [
+            ("file:shapes.ts", "file"),
+            ("Shape", "interface"),
+            ("Base", "class"),
+            ("Circle", "class"),
+            ("area", "method"),
+            ("radius", "property"),
+            ("Canvas", "class"),
+            ("draw", "method"),
+            ("Far", "class"),
+        ] {
+            graph.add_node(GraphNode {
+                node_type: node_type.to_string(),
+                file_path: Some("shapes.ts".to_string()),
+                ..node(id)
+            });
+        }
+        for (source, target, edge_type) in [
+            ("file:shapes.ts", "Circle", "contains"),
+            ("Circle", "area", "contains"),
+            ("Circle", "radius", "contains"),
+            ("Circle", "Base", "extends"),
+            ("Circle", "Shape", "implements"),
+            ("Canvas", "draw", "contains"),
+            ("draw", "area", "calls"),
+            ("Far", "Canvas", "references"),
+        ] {
+            graph.add_edge(source, target, edge(edge_type));
+        }
+        graph
+    }
+
+    #[test]
+    fn test_class_diagram_with_immediate_neighbors() {
+        let graph = graph();
+        let uml = graph.plantuml_class_diagram("Circle", 1).unwrap();
+        let alias = |id: &str| format!("n{}", graph.node_map[id].index());
+
+        assert!(uml.starts_with("@startuml\n") && uml.ends_with("@enduml\n"));
+        assert!(uml.contains(&format!("class \"Circle\" as {} {{\n  +area()\n  radius\n}}", alias("Circle"))));
+        assert!(uml.contains(&format!("interface \"Shape\" as {}", alias("Shape"))));
+        assert!(uml.contains(&format!("{} --|> {}", alias("Circle"), alias("Base"))));
+        assert!(uml.contains(&format!("{} ..|> {}", alias("Circle"), alias("Shape"))));
+        // Method calls are lifted to a dependency between their classes
+        assert!(uml.contains(&format!("{} ..> {}", alias("Canvas"), alias("Circle"))));
+        assert!(!uml.contains("\"Far\""));
+
+        assert!(graph.plantuml_class_diagram("Circle", 2).unwrap().contains("\"Far\""));
+    }
+
+    #[test]
+    fn test_class_diagram_from_file_and_method() {
+        let graph = graph();
+        assert_eq!(graph.plantuml_class_diagram("file:shapes.ts", 0), graph.plantuml_class_diagram("Circle", 0));
+        assert_eq!(graph.plantuml_class_diagram("area", 1), graph.plantuml_class_diagram("Circle", 1));
+        assert!(graph.plantuml_class_diagram("missing", 1).is_none());
+    }
+}
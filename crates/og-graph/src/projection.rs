@@ -0,0 +1,118 @@
+use crate::direction::AnalysisMode;
+use crate::graph::CodeGraph;
+use petgraph::visit::EdgeRef;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+/// Edges left out of the graph before a metric reads it
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct EdgeFilter {
+    /// Leave out edges from a node to itself
+    pub drop_self_loops: bool,
+    /// Edge types to leave out, matched case-insensitively
+    pub exclude_edge_types: Vec<String>,
+}
+
+impl EdgeFilter {
+    pub fn is_empty(&self) -> bool {
+        !self.drop_self_loops && self.exclude_edge_types.is_empty()
+    }
+}
+
+impl CodeGraph {
+    /// The graph with only edges of the given types, matched case-insensitively
+    ///
+    /// An empty list keeps every edge. Node indices are the same as in the
+    /// full graph, so results can be read back against it.
+    pub fn project<S: AsRef<str>>(&self, edge_types: &[S]) -> Cow<'_, CodeGraph> {
+        if edge_types.is_empty() {
+            return Cow::Borrowed(self);
+        }
+        let keeps = |edge_type: &str| edge_types.iter().any(|kept| kept.as_ref().eq_ignore_ascii_case(edge_type));
+        Cow::Owned(self.with_edges(
+            self.graph
+                .edge_references()
+                .filter(|edge| keeps(&edge.weight().edge_type))
+                .map(|edge| (edge.source(), edge.target(), edge.weight().clone()))
+                .collect(),
+        ))
+    }
+
+    /// The graph without the edges the filter leaves out
+    ///
+    /// Node indices are the same as in the full graph.
+    pub fn filtered(&self, filter: &EdgeFilter) -> Cow<'_, CodeGraph> {
+        if filter.is_empty() {
+            return Cow::Borrowed(self);
+        }
+        let excluded = |edge_type: &str| {
+            filter
+                .exclude_edge_types
+                .iter()
+                .any(|excluded| excluded.eq_ignore_ascii_case(edge_type))
+        };
+        Cow::Owned(self.with_edges(
+            self.graph
+                .edge_references()
+                .filter(|edge| !(filter.drop_self_loops && edge.source() == edge.target()))
+                .filter(|edge| !excluded(&edge.weight().edge_type))
+                .map(|edge| (edge.source(), edge.target(), edge.weight().clone()))
+                .collect(),
+        ))
+    }
+
+    /// The graph a metric reads: filtered first, then walked in `mode`
+    pub fn metric_view(&self, filter: &EdgeFilter, mode: AnalysisMode) -> Cow<'_, CodeGraph> {
+        match self.filtered(filter) {
+            Cow::Borrowed(graph) => graph.projected(mode),
+            Cow::Owned(graph) => Cow::Owned(graph.projected(mode).into_owned()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::{edge, graph};
+    use std::borrow::Cow;
+
+    #[test]
+    fn test_project_keeps_listed_edge_types() {
+        let mut graph = graph(&["file:a", "fn:a", "file:b"], &[]);
+        for (source, target, edge_type) in [("file:a", "fn:a", "CONTAINS"), ("file:a", "file:b", "IMPORTS"), ("fn:a", "file:b", "CALLS")] {
+            graph.add_edge(source, target, edge(edge_type));
+        }
+
+        let imports = graph.project(&["imports"]);
+        assert_eq!(imports.graph.edge_count(), 1);
+        assert_eq!(imports.node_map, graph.node_map);
+        assert!(imports
+            .graph
+            .find_edge(graph.node_map["file:a"], graph.node_map["file:b"])
+            .is_some());
+
+        assert_eq!(graph.project(&["CALLS", "IMPORTS"]).graph.edge_count(), 2);
+        assert!(matches!(graph.project::<&str>(&[]), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_filtered_drops_self_loops_and_excluded_types() {
+        use super::EdgeFilter;
+        use crate::direction::AnalysisMode;
+
+        let mut graph = graph(&["a", "b"], &[]);
+        for (source, target, edge_type) in [("a", "a", "CALLS"), ("a", "b", "CONTAINS"), ("b", "a", "IMPORTS")] {
+            graph.add_edge(source, target, edge(edge_type));
+        }
+
+        let filter = EdgeFilter { drop_self_loops: true, exclude_edge_types: vec!["contains".to_string()] };
+        let filtered = graph.filtered(&filter);
+        assert_eq!(filtered.graph.edge_count(), 1);
+        assert_eq!(filtered.node_map, graph.node_map);
+        assert!(matches!(graph.filtered(&EdgeFilter::default()), Cow::Borrowed(_)));
+
+        // The one import left is walked both ways
+        assert_eq!(graph.metric_view(&filter, AnalysisMode::Undirected).graph.edge_count(), 2);
+        assert_eq!(graph.metric_view(&EdgeFilter::default(), AnalysisMode::Directed).graph.edge_count(), 3);
+    }
+}
@@ -0,0 +1,152 @@
+use crate::graph::{CodeGraph, GraphData, GraphLink};
+use petgraph::graph::NodeIndex;
+use std::collections::{BTreeSet, HashSet, VecDeque};
+
+/// Type of the links that stand in for paths through pruned nodes
+pub const PRUNED_PATH_LINK: &str = "pruned_path";
+
+impl CodeGraph {
+    /// The graph's backbone, without nodes too minor to draw
+    ///
+    /// A node is kept when its type is in `keep_types`, or when it has at
+    /// least `min_degree` edges and a PageRank of at least `min_pagerank`.
+    /// Kept nodes that were only connected through pruned ones are linked
+    /// directly by a `pruned_path` link, so the structure stays connected.
+    pub fn prune_graph(&self, min_degree: usize, min_pagerank: f64, keep_types: &[String]) -> GraphData {
+        let ranks = self.calculate_pagerank(30, 0.85);
+        let keep: HashSet<NodeIndex> = self
+            .graph
+            .node_indices()
+            .filter(|&idx| {
+                let node = &self.graph[idx];
+                keep_types.contains(&node.node_type)
+                    || (self.graph.neighbors_undirected(idx).count() >= min_degree
+                        && ranks.get(&node.id).copied().unwrap_or(0.0) >= min_pagerank)
+            })
+            .collect();
+
+        let mut data = self.induced_subgraph(&keep);
+        let linked: HashSet<(NodeIndex, NodeIndex)> = data
+            .links
+            .iter()
+            .map(|link| (self.node_map[&link.source], self.node_map[&link.target]))
+            .collect();
+
+        // Sorted so the added links come out in the same order every time
+        let mut bridged = BTreeSet::new();
+        for &start in &keep {
+            for end in self.reachable_through_pruned(start, &keep) {
+                if end != start && !linked.contains(&(start, end)) {
+                    bridged.insert((self.graph[start].id.clone(), self.graph[end].id.clone()));
+                }
+            }
+        }
+        data.links.extend(bridged.into_iter().map(|(source, target)| GraphLink {
+            source,
+            target,
+            link_type: PRUNED_PATH_LINK.to_string(),
+            value: 1.0,
+            count: 1,
+        }));
+
+        GraphData::from_parts(data.nodes, data.links)
+    }
+
+    /// Kept nodes reached from `start` by paths whose inner nodes were all pruned
+    fn reachable_through_pruned(&self, start: NodeIndex, keep: &HashSet<NodeIndex>) -> HashSet<NodeIndex> {
+        let mut seen = HashSet::from([start]);
+        let mut found = HashSet::new();
+        let mut queue: VecDeque<NodeIndex> = self.graph.neighbors(start).filter(|next| !keep.contains(next)).collect();
+        seen.extend(queue.iter().copied());
+
+        while let Some(idx) = queue.pop_front() {
+            for next in self.graph.neighbors(idx) {
+                if keep.contains(&next) {
+                    found.insert(next);
+                } else if seen.insert(next) {
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::GraphNode;
+    use crate::testing::{edge, node};
+
+    // app -> helper -> db, both files -> Model, plus three leaves hanging off app
+    fn sample() -> CodeGraph {
+        let mut graph = CodeGraph::new();
+        for (id, node_type) in [
+            ("app", "file"),
+            ("helper", "function"),
+            ("db", "file"),
+            ("Model", "class"),
+            ("leaf1", "function"),
+            ("leaf2", "function"),
+            ("leaf3", "function"),
+        ] {
+            graph.add_node(GraphNode {
+                node_type: node_type.to_string(),
+                ..node(id)
+            });
+        }
+        for (source, target) in [
+            ("app", "helper"),
+            ("helper", "db"),
+            ("app", "Model"),
+            ("db", "Model"),
+            ("app", "leaf1"),
+            ("app", "leaf2"),
+            ("app", "leaf3"),
+        ] {
+            graph.add_edge(source, target, edge("imports"));
+        }
+        graph
+    }
+
+    fn ids(data: &GraphData) -> Vec<&str> {
+        let mut ids: Vec<&str> = data.nodes.iter().map(|node| node.id.as_str()).collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    #[test]
+    fn test_prune_graph_keeps_backbone_connected() {
+        let graph = sample();
+        let pruned = graph.prune_graph(2, 0.0, &[]);
+
+        assert_eq!(ids(&pruned), vec!["Model", "app", "db", "helper"]);
+        assert_eq!(pruned.stats.node_count, 4);
+        assert!(!pruned.links.iter().any(|link| link.link_type == PRUNED_PATH_LINK));
+
+        // Dropping the helper links app to db directly
+        assert_eq!(ids(&graph.prune_graph(3, 0.0, &[])), vec!["app"]);
+        let pruned = graph.prune_graph(3, 0.0, &["file".to_string()]);
+        assert_eq!(ids(&pruned), vec!["app", "db"]);
+        let bridges: Vec<(&str, &str)> = pruned
+            .links
+            .iter()
+            .filter(|link| link.link_type == PRUNED_PATH_LINK)
+            .map(|link| (link.source.as_str(), link.target.as_str()))
+            .collect();
+        assert_eq!(bridges, vec![("app", "db")]);
+    }
+
+    #[test]
+    fn test_prune_graph_by_pagerank() {
+        let graph = sample();
+        let ranks = graph.calculate_pagerank(30, 0.85);
+        let pruned = graph.prune_graph(0, ranks["Model"], &[]);
+
+        // Model collects rank from both files, more than any leaf
+        assert!(ids(&pruned).contains(&"Model"));
+        assert!(!ids(&pruned).contains(&"leaf1"));
+        assert_eq!(graph.prune_graph(0, 0.0, &[]).nodes.len(), graph.graph.node_count());
+    }
+}
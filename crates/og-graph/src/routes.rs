@@ -0,0 +1,94 @@
+use crate::graph::CodeGraph;
+use petgraph::visit::EdgeRef;
+use serde::{Deserialize, Serialize};
+
+/// One HTTP endpoint and the code that serves it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RouteEndpoint {
+    pub route_id: String,
+    pub method: String,
+    pub path: String,
+    pub file_path: Option<String>,
+    /// Handler node, if it could be resolved
+    pub handler_id: Option<String>,
+    pub handler_name: Option<String>,
+}
+
+impl CodeGraph {
+    /// HTTP endpoints found in the graph, sorted by path then method
+    pub fn route_map(&self) -> Vec<RouteEndpoint> {
+        let mut routes: Vec<RouteEndpoint> = self
+            .graph
+            .node_indices()
+            .filter(|&idx| self.graph[idx].node_type == "route")
+            .map(|idx| {
+                let node = &self.graph[idx];
+                let (method, path) = node.name.split_once(' ').unwrap_or(("ANY", node.name.as_str()));
+                let handler = self
+                    .graph
+                    .edges(idx)
+                    .find(|edge| edge.weight().edge_type.eq_ignore_ascii_case("routes_to"))
+                    .map(|edge| &self.graph[edge.target()]);
+
+                RouteEndpoint {
+                    route_id: node.id.clone(),
+                    method: method.to_string(),
+                    path: path.to_string(),
+                    file_path: node.file_path.clone(),
+                    handler_id: handler.map(|h| h.id.clone()),
+                    handler_name: handler.map(|h| h.name.clone()),
+                }
+            })
+            .collect();
+
+        routes.sort_by(|a, b| a.path.cmp(&b.path).then(a.method.cmp(&b.method)));
+        routes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{GraphEdge, GraphNode};
+    use crate::testing::{self, edge};
+
+    fn node(id: &str, name: &str, node_type: &str) -> GraphNode {
+        GraphNode {
+            name: name.to_string(),
+            node_type: node_type.to_string(),
+            file_path: Some("api.py".to_string()),
+            ..testing::node(id)
+        }
+    }
+
+    #[test]
+    fn test_route_map_links_handlers() {
+        let mut graph = CodeGraph::new();
+        graph.add_node(node("route:api.py:POST /users", "POST /users", "route"));
+        graph.add_node(node("route:api.py:GET /users", "GET /users", "route"));
+        graph.add_node(node("function:api.py:users", "users", "function"));
+        graph.add_edge(
+            "route:api.py:GET /users",
+            "function:api.py:users",
+            GraphEdge {
+                weight: 2.0,
+                ..edge("routes_to")
+            },
+        );
+
+        let routes = graph.route_map();
+        assert_eq!(routes.len(), 2);
+        assert_eq!(routes[0].method, "GET");
+        assert_eq!(routes[0].path, "/users");
+        assert_eq!(routes[0].handler_name.as_deref(), Some("users"));
+        assert!(routes[1].handler_id.is_none());
+    }
+
+    #[test]
+    fn test_route_map_ignores_other_nodes() {
+        let mut graph = CodeGraph::new();
+        graph.add_node(node("function:api.py:users", "users", "function"));
+        assert!(graph.route_map().is_empty());
+    }
+}
@@ -0,0 +1,98 @@
+use crate::graph::{CodeGraph, GraphData};
+use petgraph::graph::NodeIndex;
+use petgraph::Direction;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+
+/// Which way to follow dependencies from the sliced node
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SliceDirection {
+    /// Nodes that depend on the start node
+    Upstream,
+    /// Nodes the start node depends on
+    Downstream,
+    Both,
+}
+
+impl CodeGraph {
+    /// Transitive dependency slice of a node, or None if it does not exist
+    ///
+    /// `max_depth` of None follows edges until the slice stops growing.
+    pub fn slice(&self, node_id: &str, direction: SliceDirection, max_depth: Option<usize>) -> Option<GraphData> {
+        let start = *self.node_map.get(node_id)?;
+
+        let mut keep = HashSet::from([start]);
+        if matches!(direction, SliceDirection::Downstream | SliceDirection::Both) {
+            keep.extend(self.reachable(start, Direction::Outgoing, max_depth));
+        }
+        if matches!(direction, SliceDirection::Upstream | SliceDirection::Both) {
+            keep.extend(self.reachable(start, Direction::Incoming, max_depth));
+        }
+
+        Some(self.induced_subgraph(&keep))
+    }
+
+    pub(crate) fn reachable(&self, start: NodeIndex, direction: Direction, max_depth: Option<usize>) -> HashSet<NodeIndex> {
+        let mut seen = HashSet::from([start]);
+        let mut queue = VecDeque::from([(start, 0)]);
+
+        while let Some((idx, depth)) = queue.pop_front() {
+            if max_depth.is_some_and(|max| depth >= max) {
+                continue;
+            }
+            for next in self.graph.neighbors_directed(idx, direction) {
+                if seen.insert(next) {
+                    queue.push_back((next, depth + 1));
+                }
+            }
+        }
+
+        seen
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::graph;
+
+    // a -> b -> c -> d, plus x -> b
+    fn sample() -> CodeGraph {
+        graph(
+            &["a", "b", "c", "d", "x"],
+            &[("a", "b"), ("b", "c"), ("c", "d"), ("x", "b")],
+        )
+    }
+
+    fn ids(data: &GraphData) -> Vec<&str> {
+        let mut ids: Vec<&str> = data.nodes.iter().map(|n| n.id.as_str()).collect();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn test_slice_directions() {
+        let graph = sample();
+
+        let down = graph.slice("b", SliceDirection::Downstream, None).unwrap();
+        assert_eq!(ids(&down), vec!["b", "c", "d"]);
+        assert_eq!(down.links.len(), 2);
+
+        let up = graph.slice("b", SliceDirection::Upstream, None).unwrap();
+        assert_eq!(ids(&up), vec!["a", "b", "x"]);
+
+        let both = graph.slice("b", SliceDirection::Both, None).unwrap();
+        assert_eq!(both.nodes.len(), 5);
+        assert_eq!(both.links.len(), 4);
+    }
+
+    #[test]
+    fn test_slice_depth_limit_and_missing_node() {
+        let graph = sample();
+
+        let shallow = graph.slice("a", SliceDirection::Downstream, Some(1)).unwrap();
+        assert_eq!(ids(&shallow), vec!["a", "b"]);
+        assert!(graph.slice("missing", SliceDirection::Both, None).is_none());
+    }
+}
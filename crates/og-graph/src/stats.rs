@@ -0,0 +1,253 @@
+use crate::graph::CodeGraph;
+use petgraph::graph::NodeIndex;
+use petgraph::unionfind::UnionFind;
+use petgraph::visit::EdgeRef;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+/// Number of BFS sweeps used when estimating the diameter
+const DIAMETER_SWEEPS: usize = 4;
+
+/// Global structural statistics for a code graph
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StructuralStats {
+    pub node_count: usize,
+    pub edge_count: usize,
+    pub density: f64,
+    pub avg_degree: f64,
+    pub max_degree: usize,
+    pub degree_distribution: Vec<DegreeBucket>,
+    pub component_count: usize,
+    pub component_sizes: Vec<usize>,
+    pub largest_component_ratio: f64,
+    pub approx_diameter: usize,
+    pub assortativity: f64,
+    pub self_loops: usize,
+    pub edge_types: BTreeMap<String, usize>,
+}
+
+/// Number of nodes sharing a given total degree
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DegreeBucket {
+    pub degree: usize,
+    pub count: usize,
+}
+
+impl CodeGraph {
+    /// Compute global structural statistics in roughly O(V + E)
+    pub fn structural_stats(&self) -> StructuralStats {
+        let node_count = self.graph.node_count();
+        let edge_count = self.graph.edge_count();
+
+        let density = if node_count > 1 {
+            edge_count as f64 / (node_count as f64 * (node_count - 1) as f64)
+        } else {
+            0.0
+        };
+
+        // Total degree (in + out) per node
+        let degrees: HashMap<NodeIndex, usize> = self
+            .graph
+            .node_indices()
+            .map(|idx| {
+                let degree = self
+                    .graph
+                    .edges_directed(idx, petgraph::Direction::Incoming)
+                    .count()
+                    + self
+                        .graph
+                        .edges_directed(idx, petgraph::Direction::Outgoing)
+                        .count();
+                (idx, degree)
+            })
+            .collect();
+
+        let mut histogram: BTreeMap<usize, usize> = BTreeMap::new();
+        for &degree in degrees.values() {
+            *histogram.entry(degree).or_insert(0) += 1;
+        }
+        let degree_distribution = histogram
+            .into_iter()
+            .map(|(degree, count)| DegreeBucket { degree, count })
+            .collect();
+
+        let max_degree = degrees.values().copied().max().unwrap_or(0);
+        let avg_degree = if node_count > 0 {
+            degrees.values().sum::<usize>() as f64 / node_count as f64
+        } else {
+            0.0
+        };
+
+        let component_sizes = self.weak_component_sizes();
+        let largest_component_ratio = if node_count > 0 {
+            component_sizes.first().copied().unwrap_or(0) as f64 / node_count as f64
+        } else {
+            0.0
+        };
+
+        let mut edge_types = BTreeMap::new();
+        let mut self_loops = 0;
+        for edge in self.graph.edge_references() {
+            *edge_types.entry(edge.weight().edge_type.clone()).or_insert(0) += 1;
+            if edge.source() == edge.target() {
+                self_loops += 1;
+            }
+        }
+
+        StructuralStats {
+            node_count,
+            edge_count,
+            density,
+            avg_degree,
+            max_degree,
+            degree_distribution,
+            component_count: component_sizes.len(),
+            component_sizes,
+            largest_component_ratio,
+            approx_diameter: self.approximate_diameter(),
+            assortativity: self.degree_assortativity(&degrees),
+            self_loops,
+            edge_types,
+        }
+    }
+
+    /// Sizes of weakly connected components, largest first
+    fn weak_component_sizes(&self) -> Vec<usize> {
+        let mut union_find = UnionFind::new(self.graph.node_count());
+        for edge in self.graph.edge_references() {
+            union_find.union(edge.source().index(), edge.target().index());
+        }
+
+        let mut sizes: HashMap<usize, usize> = HashMap::new();
+        for idx in self.graph.node_indices() {
+            *sizes.entry(union_find.find(idx.index())).or_insert(0) += 1;
+        }
+
+        let mut sizes: Vec<usize> = sizes.into_values().collect();
+        sizes.sort_unstable_by(|a, b| b.cmp(a));
+        sizes
+    }
+
+    /// Estimate the undirected diameter with repeated double-sweep BFS
+    ///
+    /// Each sweep starts from the farthest node found by the previous one,
+    /// which gives a lower bound that is usually tight on sparse graphs.
+    fn approximate_diameter(&self) -> usize {
+        let Some(mut start) = self
+            .graph
+            .node_indices()
+            .max_by_key(|&idx| self.graph.neighbors_undirected(idx).count())
+        else {
+            return 0;
+        };
+
+        let mut best = 0;
+        for _ in 0..DIAMETER_SWEEPS {
+            let (farthest, distance) = self.farthest_undirected(start);
+            if distance <= best && best > 0 {
+                break;
+            }
+            best = best.max(distance);
+            start = farthest;
+        }
+        best
+    }
+
+    /// BFS over the undirected view, returning the farthest node and its distance
+    fn farthest_undirected(&self, start: NodeIndex) -> (NodeIndex, usize) {
+        let mut distances: HashMap<NodeIndex, usize> = HashMap::new();
+        let mut queue = VecDeque::new();
+        distances.insert(start, 0);
+        queue.push_back(start);
+
+        let mut farthest = (start, 0);
+        while let Some(current) = queue.pop_front() {
+            let distance = distances[&current];
+            if distance > farthest.1 {
+                farthest = (current, distance);
+            }
+            for neighbor in self.graph.neighbors_undirected(current) {
+                if let Entry::Vacant(entry) = distances.entry(neighbor) {
+                    entry.insert(distance + 1);
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        farthest
+    }
+
+    /// Pearson correlation of total degrees across edge endpoints
+    fn degree_assortativity(&self, degrees: &HashMap<NodeIndex, usize>) -> f64 {
+        let mut pairs = Vec::with_capacity(self.graph.edge_count() * 2);
+        for edge in self.graph.edge_references() {
+            if edge.source() == edge.target() {
+                continue;
+            }
+            let a = degrees[&edge.source()] as f64;
+            let b = degrees[&edge.target()] as f64;
+            // Count both orientations so the measure is symmetric
+            pairs.push((a, b));
+            pairs.push((b, a));
+        }
+
+        if pairs.is_empty() {
+            return 0.0;
+        }
+
+        let n = pairs.len() as f64;
+        let mean_x = pairs.iter().map(|p| p.0).sum::<f64>() / n;
+        let mean_y = pairs.iter().map(|p| p.1).sum::<f64>() / n;
+        let mut covariance = 0.0;
+        let mut variance_x = 0.0;
+        let mut variance_y = 0.0;
+        for (x, y) in &pairs {
+            covariance += (x - mean_x) * (y - mean_y);
+            variance_x += (x - mean_x).powi(2);
+            variance_y += (y - mean_y).powi(2);
+        }
+
+        let denominator = (variance_x * variance_y).sqrt();
+        if denominator > 0.0 {
+            (covariance / denominator).clamp(-1.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{edge, node};
+
+    #[test]
+    fn test_empty_graph_stats() {
+        let stats = CodeGraph::new().structural_stats();
+        assert_eq!(stats.node_count, 0);
+        assert_eq!(stats.component_count, 0);
+        assert_eq!(stats.approx_diameter, 0);
+        assert_eq!(stats.density, 0.0);
+    }
+
+    #[test]
+    fn test_path_graph_stats() {
+        let mut graph = CodeGraph::new();
+        for id in ["a", "b", "c", "d", "e"] {
+            graph.add_node(node(id));
+        }
+        graph.add_edge("a", "b", edge("imports"));
+        graph.add_edge("b", "c", edge("imports"));
+        graph.add_edge("c", "d", edge("calls"));
+
+        let stats = graph.structural_stats();
+        assert_eq!(stats.component_sizes, vec![4, 1]);
+        assert_eq!(stats.approx_diameter, 3);
+        assert_eq!(stats.max_degree, 2);
+        assert_eq!(stats.edge_types.get("imports"), Some(&2));
+        assert_eq!(stats.edge_types.get("calls"), Some(&1));
+        assert!((stats.density - 3.0 / 20.0).abs() < 1e-9);
+    }
+}
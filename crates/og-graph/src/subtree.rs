@@ -0,0 +1,110 @@
+use crate::graph::{CodeGraph, GraphEdge};
+use petgraph::visit::EdgeRef;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+
+/// What replacing a subtree changed in a graph
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubtreeMerge {
+    /// Nodes of the old subtree that were dropped
+    pub removed_nodes: usize,
+    /// Nodes taken from the fresh parse
+    pub added_nodes: usize,
+    /// Edges between the subtree and the rest of the graph kept from before
+    pub preserved_edges: usize,
+}
+
+impl CodeGraph {
+    /// Swap the nodes of files under `dir` for a fresh parse of that directory
+    ///
+    /// Every node whose file is under `dir` is replaced by the nodes of
+    /// `fresh`. Edges between the subtree and the rest of the graph are kept
+    /// when both ends still exist, since files outside `dir` were not
+    /// re-parsed and the fresh parse cannot see all of them.
+    pub fn replace_subtree(&mut self, dir: &Path, fresh: &CodeGraph) -> SubtreeMerge {
+        let old: HashSet<_> = self
+            .graph
+            .node_indices()
+            .filter(|&idx| {
+                self.graph[idx]
+                    .file_path
+                    .as_deref()
+                    .is_some_and(|path| Path::new(path).starts_with(dir))
+            })
+            .collect();
+        let crossing: Vec<(String, String, GraphEdge)> = self
+            .graph
+            .edge_references()
+            .filter(|edge| old.contains(&edge.source()) != old.contains(&edge.target()))
+            .map(|edge| {
+                (
+                    self.graph[edge.source()].id.clone(),
+                    self.graph[edge.target()].id.clone(),
+                    edge.weight().clone(),
+                )
+            })
+            .collect();
+
+        let old_ids: Vec<String> = old.iter().map(|&idx| self.graph[idx].id.clone()).collect();
+        for id in &old_ids {
+            self.remove_node(id);
+        }
+
+        let mut merge = SubtreeMerge {
+            removed_nodes: old_ids.len(),
+            ..SubtreeMerge::default()
+        };
+        for node in fresh.graph.node_weights() {
+            if !self.node_map.contains_key(&node.id) {
+                self.add_node(node.clone());
+                merge.added_nodes += 1;
+            }
+        }
+
+        let mut seen: HashSet<(String, String, String)> = HashSet::new();
+        for edge in fresh.graph.edge_references() {
+            let source = &fresh.graph[edge.source()].id;
+            let target = &fresh.graph[edge.target()].id;
+            if seen.insert((source.clone(), target.clone(), edge.weight().edge_type.clone())) {
+                self.add_edge(source, target, edge.weight().clone());
+            }
+        }
+        for (source, target, edge) in crossing {
+            let both_exist = self.node_map.contains_key(&source) && self.node_map.contains_key(&target);
+            if both_exist && seen.insert((source.clone(), target.clone(), edge.edge_type.clone())) {
+                self.add_edge(&source, &target, edge);
+                merge.preserved_edges += 1;
+            }
+        }
+
+        merge
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::graph;
+
+    #[test]
+    fn test_replace_subtree_keeps_edges_to_rest_of_project() {
+        let mut cached = graph(
+            &["/p/app.py", "/p/lib/a.py", "/p/lib/old.py"],
+            &[("/p/app.py", "/p/lib/a.py"), ("/p/lib/old.py", "/p/app.py")],
+        );
+        // old.py was deleted and b.py added; the subtree parse cannot see app.py
+        let fresh = graph(&["/p/lib/a.py", "/p/lib/b.py"], &[("/p/lib/b.py", "/p/lib/a.py")]);
+
+        let merge = cached.replace_subtree(Path::new("/p/lib"), &fresh);
+        assert_eq!(merge.removed_nodes, 2);
+        assert_eq!(merge.added_nodes, 2);
+        assert_eq!(merge.preserved_edges, 1);
+
+        assert!(cached.node_map.contains_key("/p/lib/b.py"));
+        assert!(!cached.node_map.contains_key("/p/lib/old.py"));
+        assert_eq!(cached.graph.node_count(), 3);
+        assert_eq!(cached.graph.edge_count(), 2);
+    }
+}
@@ -0,0 +1,38 @@
+//! Nodes, edges and graphs for tests, here and in the crates built on this one
+
+use crate::graph::{CodeGraph, GraphEdge, GraphNode};
+
+/// A file node whose name and path are its id
+pub fn node(id: &str) -> GraphNode {
+    GraphNode {
+        id: id.to_string(),
+        name: id.to_string(),
+        node_type: "file".to_string(),
+        size: 10.0,
+        visual_size: 10.0,
+        color: "#4A90E2".to_string(),
+        file_path: Some(id.to_string()),
+        attributes: Default::default(),
+    }
+}
+
+/// An edge standing for one relationship, with weight 1
+pub fn edge(edge_type: &str) -> GraphEdge {
+    GraphEdge {
+        edge_type: edge_type.to_string(),
+        weight: 1.0,
+        count: 1,
+    }
+}
+
+/// File nodes joined by `imports` edges
+pub fn graph(ids: &[&str], imports: &[(&str, &str)]) -> CodeGraph {
+    let mut graph = CodeGraph::new();
+    for id in ids {
+        graph.add_node(node(id));
+    }
+    for (source, target) in imports {
+        graph.add_edge(source, target, edge("imports"));
+    }
+    graph
+}
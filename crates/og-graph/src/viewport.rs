@@ -0,0 +1,163 @@
+use crate::bundling::{BundledEdge, EdgeMode};
+use crate::graph::{CodeGraph, GraphData};
+use petgraph::graph::NodeIndex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Node budget at zoom level 1.0
+pub const DEFAULT_VIEWPORT_NODES: usize = 500;
+
+/// What the frontend is currently looking at
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ViewportRequest {
+    /// 1.0 is the default zoom; the node budget scales linearly with it
+    pub zoom: f64,
+    /// Nodes whose neighbourhoods must stay visible
+    #[serde(default)]
+    pub focus_nodes: Vec<String>,
+    #[serde(default = "default_viewport_nodes")]
+    pub max_nodes: usize,
+    #[serde(default = "default_neighborhood_depth")]
+    pub neighborhood_depth: usize,
+    /// Whether links between directories come back raw, bundled or both
+    #[serde(default)]
+    pub edges: EdgeMode,
+}
+
+fn default_viewport_nodes() -> usize {
+    DEFAULT_VIEWPORT_NODES
+}
+
+fn default_neighborhood_depth() -> usize {
+    1
+}
+
+impl ViewportRequest {
+    /// Number of nodes to return at this zoom level
+    pub fn node_budget(&self) -> usize {
+        ((self.max_nodes as f64 * self.zoom.clamp(0.1, 10.0)).round() as usize).max(1)
+    }
+}
+
+/// Visible part of a graph for one viewport
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ViewportGraph {
+    pub graph_data: GraphData,
+    pub total_nodes: usize,
+    pub total_links: usize,
+    /// True when nodes were left out to stay within the budget
+    pub truncated: bool,
+    /// Links between directories, merged, when requested
+    #[serde(default)]
+    pub bundles: Vec<BundledEdge>,
+}
+
+impl CodeGraph {
+    /// Focus neighbourhoods first, then the highest-ranked remaining nodes
+    pub fn viewport(&self, request: &ViewportRequest, ranks: &HashMap<String, f64>) -> ViewportGraph {
+        let budget = request.node_budget();
+        let rank = |idx: NodeIndex| ranks.get(&self.graph[idx].id).copied().unwrap_or(0.0);
+        let mut selected: HashSet<NodeIndex> = HashSet::new();
+
+        // Breadth-first so nearer neighbours win when the budget runs out
+        let mut queue: VecDeque<(NodeIndex, usize)> = request
+            .focus_nodes
+            .iter()
+            .filter_map(|id| self.node_map.get(id))
+            .map(|&idx| (idx, 0))
+            .collect();
+        while let Some((idx, depth)) = queue.pop_front() {
+            if selected.len() >= budget {
+                break;
+            }
+            if !selected.insert(idx) || depth >= request.neighborhood_depth {
+                continue;
+            }
+            let mut neighbors: Vec<NodeIndex> = self
+                .graph
+                .neighbors_undirected(idx)
+                .filter(|n| !selected.contains(n))
+                .collect();
+            neighbors.sort_by(|a, b| rank(*b).total_cmp(&rank(*a)));
+            queue.extend(neighbors.into_iter().map(|n| (n, depth + 1)));
+        }
+
+        if selected.len() < budget {
+            let mut remaining: Vec<NodeIndex> = self
+                .graph
+                .node_indices()
+                .filter(|idx| !selected.contains(idx))
+                .collect();
+            remaining.sort_by(|a, b| rank(*b).total_cmp(&rank(*a)).then(a.cmp(b)));
+            let room = budget - selected.len();
+            selected.extend(remaining.into_iter().take(room));
+        }
+
+        let mut visible = self.induced_subgraph(&selected);
+        let links = std::mem::take(&mut visible.links);
+        let (links, bundles) = visible.bundle_links(links, request.edges);
+
+        ViewportGraph {
+            graph_data: GraphData::from_parts(visible.nodes, links),
+            total_nodes: self.graph.node_count(),
+            total_links: self.graph.edge_count(),
+            truncated: selected.len() < self.graph.node_count(),
+            bundles,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{edge, node};
+
+    fn star(leaves: usize) -> CodeGraph {
+        let mut graph = CodeGraph::new();
+        for i in 0..=leaves {
+            graph.add_node(node(&format!("n{}", i)));
+        }
+        for i in 1..=leaves {
+            graph.add_edge(&format!("n{}", i), "n0", edge("imports"));
+        }
+        graph
+    }
+
+    fn request(max_nodes: usize, focus: &[&str]) -> ViewportRequest {
+        ViewportRequest {
+            zoom: 1.0,
+            focus_nodes: focus.iter().map(|id| id.to_string()).collect(),
+            max_nodes,
+            neighborhood_depth: 1,
+            edges: EdgeMode::Raw,
+        }
+    }
+
+    #[test]
+    fn test_top_ranked_nodes_fill_budget() {
+        let graph = star(5);
+        let ranks = graph.calculate_pagerank(20, 0.85);
+
+        let view = graph.viewport(&request(1, &[]), &ranks);
+        assert!(view.truncated);
+        assert_eq!(view.graph_data.nodes.len(), 1);
+        assert_eq!(view.graph_data.nodes[0].id, "n0");
+    }
+
+    #[test]
+    fn test_focus_neighbourhood_kept() {
+        let graph = star(5);
+        let ranks = graph.calculate_pagerank(20, 0.85);
+
+        let view = graph.viewport(&request(2, &["n3"]), &ranks);
+        let ids: HashSet<&str> = view.graph_data.nodes.iter().map(|n| n.id.as_str()).collect();
+        assert_eq!(ids, HashSet::from(["n3", "n0"]));
+        assert_eq!(view.graph_data.links.len(), 1);
+
+        let full = graph.viewport(&request(100, &[]), &ranks);
+        assert!(!full.truncated);
+        assert_eq!(full.graph_data.links.len(), 5);
+    }
+}
@@ -0,0 +1,117 @@
+//! Analysis core without the desktop shell
+//!
+//! Takes source files from memory instead of walking a directory, parses
+//! them one by one and runs the metrics on the calling thread, so it builds
+//! for `wasm32-unknown-unknown` and backs the browser Lite mode. The `wasm`
+//! feature exports `analyze` to JavaScript, which takes the files as a JSON
+//! object of path to source and returns the [`analyze_files`] report as JSON.
+
+use anyhow::Result;
+use og_analytics::{analyze_graph, to_ui_metrics, AnalyticsConfig};
+use og_graph::builder::GraphBuilder;
+use og_graph::graph::GraphData;
+use og_parser::ParserEngine;
+use og_types::metrics::UINodeMetricsV1;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::pin;
+use std::task::{Context, Poll, Waker};
+
+#[cfg(feature = "wasm")]
+mod wasm;
+
+/// A file that could not be analyzed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiteError {
+    pub path: String,
+    pub message: String,
+}
+
+/// Graph and metrics of an in-memory project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiteReport {
+    pub graph: GraphData,
+    pub metrics: Vec<UINodeMetricsV1>,
+    /// Files without a parser or that failed to parse, by path
+    pub errors: Vec<LiteError>,
+}
+
+/// Drive a future that never waits on I/O to completion on this thread
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let mut cx = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+/// Analyze source files given as a map of path to content
+///
+/// Imports are resolved against the file system where there is one, so in
+/// the browser only the graph's own nodes and calls link files together.
+pub fn analyze_files(files: &BTreeMap<String, String>) -> Result<LiteReport> {
+    let engine = ParserEngine::with_base_path(PathBuf::from("."));
+    let mut parsed = Vec::with_capacity(files.len());
+    let mut errors = Vec::new();
+    for (path, content) in files {
+        if !engine.can_parse(Path::new(path)) {
+            continue;
+        }
+        match engine.parse_file(Path::new(path), content) {
+            Ok(file) => parsed.push(file),
+            Err(e) => errors.push(LiteError {
+                path: path.clone(),
+                message: e.to_string(),
+            }),
+        }
+    }
+
+    let code_graph = GraphBuilder::new().build_from_files(parsed);
+    let metrics = if code_graph.node_map.is_empty() {
+        Vec::new()
+    } else {
+        let config = AnalyticsConfig {
+            parallel: false,
+            ..AnalyticsConfig::default()
+        };
+        let report = block_on(analyze_graph(&code_graph, Some(config)))?;
+        to_ui_metrics(&report, &code_graph)
+    };
+    Ok(LiteReport {
+        graph: code_graph.to_frontend_format(),
+        metrics,
+        errors,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_analyze_in_memory_files() {
+        let files = BTreeMap::from([
+            (
+                "src/app.py".to_string(),
+                "from util import helper\n\ndef main():\n    helper()\n".to_string(),
+            ),
+            ("src/util.py".to_string(), "def helper():\n    return 1\n".to_string()),
+            ("README.md".to_string(), "# Not source\n".to_string()),
+        ]);
+        let report = analyze_files(&files).unwrap();
+
+        assert_eq!(report.graph.stats.file_count, 2);
+        assert!(report.graph.nodes.iter().any(|node| node.name == "helper"));
+        assert_eq!(report.metrics.len(), report.graph.nodes.len());
+        assert!(report.errors.is_empty());
+
+        let empty = analyze_files(&BTreeMap::new()).unwrap();
+        assert!(empty.graph.nodes.is_empty() && empty.metrics.is_empty());
+    }
+}
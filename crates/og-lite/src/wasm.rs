@@ -0,0 +1,10 @@
+use std::collections::BTreeMap;
+use wasm_bindgen::prelude::*;
+
+/// Analyze a JSON object of path to source text, returning the report as JSON
+#[wasm_bindgen(js_name = analyze)]
+pub fn analyze(files: &str) -> Result<String, JsError> {
+    let files: BTreeMap<String, String> = serde_json::from_str(files)?;
+    let report = crate::analyze_files(&files).map_err(|e| JsError::new(&e.to_string()))?;
+    Ok(serde_json::to_string(&report)?)
+}
@@ -1,18 +1,98 @@
 use anyhow::Result;
 // Removed unused nalgebra imports - can add back if needed for eigenvector
-use og_graph::graph::CodeGraph;
+use og_graph::graph::{CodeGraph, GraphEdge};
+use og_types::MetricSchema;
 use petgraph::graph::NodeIndex;
 use petgraph::visit::EdgeRef;
 use petgraph::Direction;
-use std::collections::HashMap;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use tracing::{debug, warn};
 
+/// Seed for sampled betweenness when none is configured
+pub const DEFAULT_SAMPLING_SEED: u64 = 42;
+
+/// Additive error sampled betweenness aims for, on scores normalized to [0, 1]
+const SAMPLING_EPSILON: f64 = 0.1;
+
+/// Chance that some node's sampled betweenness misses `SAMPLING_EPSILON`
+const SAMPLING_DELTA: f64 = 0.1;
+
+/// Articulation point ids and bridges as (source id, target id) pairs
+pub type CutPoints = (Vec<String>, Vec<(String, String)>);
+
+/// Edge lengths used by shortest-path centrality (closeness and betweenness)
+///
+/// By default every edge has length 1 and paths are counted in hops. With
+/// `use_edge_weights` an edge's length is 1 / weight, so heavily weighted,
+/// strongly coupled edges make shorter paths. `type_costs` then multiplies
+/// the length of edges of a type, such as `contains = 0.5` to treat
+/// containment as closer than a call; unlisted types keep a factor of 1.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PathWeighting {
+    pub use_edge_weights: bool,
+    /// Length factor per edge type, keyed by lowercase type name
+    pub type_costs: BTreeMap<String, f64>,
+}
+
+impl PathWeighting {
+    /// Length of an edge on a shortest path, always positive and finite
+    pub fn length(&self, edge: &GraphEdge) -> f64 {
+        let base = if self.use_edge_weights && edge.weight.is_finite() && edge.weight > 0.0 {
+            1.0 / edge.weight
+        } else {
+            1.0
+        };
+        let factor = self
+            .type_costs
+            .get(&edge.edge_type.to_lowercase())
+            .copied()
+            .filter(|cost| cost.is_finite() && *cost > 0.0)
+            .unwrap_or(1.0);
+        base * factor
+    }
+}
+
+/// Sources needed to estimate betweenness on `node_count` nodes, capped at `max`
+///
+/// Uses the Hoeffding bound with a union over all nodes, ln(2n/δ) / (2ε²),
+/// so the sample grows with the graph but only logarithmically.
+pub fn adaptive_sample_size(node_count: usize, max: usize) -> usize {
+    if node_count == 0 {
+        return 0;
+    }
+    let needed = ((2.0 * node_count as f64 / SAMPLING_DELTA).ln() / (2.0 * SAMPLING_EPSILON * SAMPLING_EPSILON)).ceil();
+    (needed as usize).clamp(1, max.max(1)).min(node_count)
+}
+
+/// Distinct source nodes picked uniformly at random
+///
+/// Nodes are ordered by id before shuffling, so the same graph and seed give
+/// the same sources regardless of the order nodes were added in.
+pub fn sample_sources(graph: &CodeGraph, count: usize, seed: u64) -> Vec<NodeIndex> {
+    let mut nodes: Vec<NodeIndex> = graph.graph.node_indices().collect();
+    nodes.sort_by(|&a, &b| graph.graph[a].id.cmp(&graph.graph[b].id));
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let (sampled, _) = nodes.partial_shuffle(&mut rng, count);
+    sampled.to_vec()
+}
+
 /// Centrality metrics with robust error handling
+#[derive(Clone)]
 pub struct CentralityMetrics {
     pub max_iterations: usize,
     pub convergence_threshold: f64,
     pub use_sampling: bool,
+    /// Most betweenness sources to sample, fewer on small graphs
     pub sample_size: usize,
+    /// Seed that picks the sampled betweenness sources
+    pub seed: u64,
+    /// Edge lengths for closeness and betweenness
+    pub path_weighting: PathWeighting,
 }
 
 impl Default for CentralityMetrics {
@@ -22,6 +102,8 @@ impl Default for CentralityMetrics {
             convergence_threshold: 1e-6,
             use_sampling: true,
             sample_size: 1000,
+            seed: DEFAULT_SAMPLING_SEED,
+            path_weighting: PathWeighting::default(),
         }
     }
 }
@@ -74,6 +156,25 @@ impl CentralityMetrics {
             }
         }
 
+        match self.calculate_k_truss(graph) {
+            Ok(k_truss) => results.k_truss = k_truss,
+            Err(e) => {
+                warn!("K-truss decomposition failed: {}", e);
+                results.errors.push(format!("K-truss: {}", e));
+            }
+        }
+
+        match self.find_cut_points(graph) {
+            Ok((articulation_points, bridges)) => {
+                results.articulation_points = articulation_points;
+                results.bridges = bridges;
+            }
+            Err(e) => {
+                warn!("Articulation point detection failed: {}", e);
+                results.errors.push(format!("Articulation points: {}", e));
+            }
+        }
+
         Ok(results)
     }
 
@@ -196,12 +297,12 @@ impl CentralityMetrics {
         }
 
         // Use sampling for large graphs
-        let should_sample = self.use_sampling && node_count > self.sample_size;
-        let sample_size = if should_sample {
-            self.sample_size.min(node_count)
+        let sample_size = if self.use_sampling {
+            adaptive_sample_size(node_count, self.sample_size)
         } else {
             node_count
         };
+        let should_sample = sample_size < node_count;
 
         debug!("Calculating betweenness with {} samples", sample_size);
 
@@ -214,21 +315,16 @@ impl CentralityMetrics {
             }
         }
 
-        // Sample nodes for BFS
-        let node_indices: Vec<NodeIndex> = graph.graph.node_indices().collect();
-        let step = if should_sample {
-            node_count / sample_size
+        // Sample source nodes at random so the estimate is unbiased
+        let sources: Vec<NodeIndex> = if should_sample {
+            sample_sources(graph, sample_size, self.seed)
         } else {
-            1
+            graph.graph.node_indices().collect()
         };
 
-        for (i, &source) in node_indices.iter().step_by(step.max(1)).enumerate() {
-            if i >= sample_size {
-                break;
-            }
-
+        for source in sources {
             // Run single-source shortest path
-            let paths: HashMap<petgraph::graph::NodeIndex, f64> = petgraph::algo::dijkstra(&graph.graph, source, None, |_| 1.0);
+            let paths: HashMap<petgraph::graph::NodeIndex, f64> = petgraph::algo::dijkstra(&graph.graph, source, None, |edge| self.path_weighting.length(edge.weight()));
             
             // Count paths through intermediate nodes (simplified)
             for (&target, &dist) in &paths {
@@ -281,7 +377,7 @@ impl CentralityMetrics {
         }
 
         for source in graph.graph.node_indices() {
-            let paths: HashMap<petgraph::graph::NodeIndex, f64> = petgraph::algo::dijkstra(&graph.graph, source, None, |_| 1.0);
+            let paths: HashMap<petgraph::graph::NodeIndex, f64> = petgraph::algo::dijkstra(&graph.graph, source, None, |edge| self.path_weighting.length(edge.weight()));
             
             let mut total_distance = 0.0;
             let mut reachable_nodes = 0;
@@ -314,18 +410,179 @@ impl CentralityMetrics {
 
         Ok(closeness)
     }
+
+    /// Largest k of a k-truss each node belongs to
+    ///
+    /// A k-truss keeps only edges that close at least k - 2 triangles within
+    /// it, so high trussness marks tightly knit groups of files. Edges are
+    /// read undirected, and nodes without edges get 0.
+    pub fn calculate_k_truss(&self, graph: &CodeGraph) -> Result<HashMap<String, usize>> {
+        let mut adjacency: Vec<HashSet<usize>> = undirected_adjacency(graph)
+            .into_iter()
+            .map(|neighbors| neighbors.into_iter().collect())
+            .collect();
+        let edge = |a: usize, b: usize| (a.min(b), a.max(b));
+
+        let mut support: HashMap<(usize, usize), usize> = HashMap::new();
+        for (u, neighbors) in adjacency.iter().enumerate() {
+            for &v in neighbors.iter().filter(|&&v| u < v) {
+                support.insert((u, v), neighbors.intersection(&adjacency[v]).count());
+            }
+        }
+
+        // Peel edges short of k - 2 triangles; each peeled edge's trussness is k - 1
+        let mut trussness = vec![0; adjacency.len()];
+        let mut k = 3;
+        while !support.is_empty() {
+            let mut peel: Vec<(usize, usize)> = support
+                .iter()
+                .filter(|(_, &triangles)| triangles < k - 2)
+                .map(|(&e, _)| e)
+                .collect();
+            while let Some((u, v)) = peel.pop() {
+                if support.remove(&(u, v)).is_none() {
+                    continue;
+                }
+                trussness[u] = usize::max(trussness[u], k - 1);
+                trussness[v] = usize::max(trussness[v], k - 1);
+                let common: Vec<usize> = adjacency[u].intersection(&adjacency[v]).copied().collect();
+                for w in common {
+                    for side in [edge(u, w), edge(v, w)] {
+                        if let Some(triangles) = support.get_mut(&side) {
+                            *triangles = triangles.saturating_sub(1);
+                            if *triangles < k - 2 {
+                                peel.push(side);
+                            }
+                        }
+                    }
+                }
+                adjacency[u].remove(&v);
+                adjacency[v].remove(&u);
+            }
+            k += 1;
+        }
+
+        Ok(graph
+            .graph
+            .node_indices()
+            .map(|idx| (graph.graph[idx].id.clone(), trussness[idx.index()]))
+            .collect())
+    }
+
+    /// Articulation points and bridges of the graph, read undirected
+    ///
+    /// Removing an articulation point or a bridge splits its connected
+    /// component, so these are the files and dependencies everything else
+    /// hangs on. Both lists are sorted by id.
+    pub fn find_cut_points(&self, graph: &CodeGraph) -> Result<CutPoints> {
+        let adjacency = undirected_adjacency(graph);
+        let node_count = adjacency.len();
+        let mut discovered = vec![usize::MAX; node_count];
+        let mut low = vec![0; node_count];
+        let mut parent: Vec<Option<usize>> = vec![None; node_count];
+        let mut articulation = HashSet::new();
+        let mut bridges = Vec::new();
+        let mut time = 0;
+
+        // Iterative depth-first search, so long dependency chains cannot overflow the stack
+        for root in 0..node_count {
+            if discovered[root] != usize::MAX {
+                continue;
+            }
+            discovered[root] = time;
+            low[root] = time;
+            time += 1;
+            let mut root_children = 0;
+            let mut stack = vec![(root, 0)];
+
+            while let Some((u, next)) = stack.last_mut() {
+                let u = *u;
+                if let Some(&w) = adjacency[u].get(*next) {
+                    *next += 1;
+                    if discovered[w] == usize::MAX {
+                        parent[w] = Some(u);
+                        discovered[w] = time;
+                        low[w] = time;
+                        time += 1;
+                        if u == root {
+                            root_children += 1;
+                        }
+                        stack.push((w, 0));
+                    } else if parent[u] != Some(w) {
+                        low[u] = low[u].min(discovered[w]);
+                    }
+                } else {
+                    stack.pop();
+                    if let Some(p) = parent[u] {
+                        low[p] = low[p].min(low[u]);
+                        if low[u] > discovered[p] {
+                            bridges.push((p, u));
+                        }
+                        if p != root && low[u] >= discovered[p] {
+                            articulation.insert(p);
+                        }
+                    }
+                }
+            }
+            if root_children > 1 {
+                articulation.insert(root);
+            }
+        }
+
+        let id = |idx: usize| graph.graph[NodeIndex::new(idx)].id.clone();
+        let mut articulation_points: Vec<String> = articulation.into_iter().map(id).collect();
+        articulation_points.sort();
+        let mut bridges: Vec<(String, String)> = bridges
+            .into_iter()
+            .map(|(a, b)| {
+                let (a, b) = (id(a), id(b));
+                if a <= b { (a, b) } else { (b, a) }
+            })
+            .collect();
+        bridges.sort();
+        Ok((articulation_points, bridges))
+    }
+}
+
+/// Neighbours of every node by index with edges read both ways, without
+/// self-loops or repeated edges
+fn undirected_adjacency(graph: &CodeGraph) -> Vec<Vec<usize>> {
+    let mut adjacency = vec![Vec::new(); graph.graph.node_count()];
+    for edge in graph.graph.edge_references() {
+        let (a, b) = (edge.source().index(), edge.target().index());
+        if a != b {
+            adjacency[a].push(b);
+            adjacency[b].push(a);
+        }
+    }
+    for neighbors in &mut adjacency {
+        neighbors.sort_unstable();
+        neighbors.dedup();
+    }
+    adjacency
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct CentralityResults {
+    pub schema: MetricSchema,
     pub degree: HashMap<String, DegreeMetrics>,
     pub pagerank: HashMap<String, f64>,
     pub betweenness: HashMap<String, f64>,
     pub closeness: HashMap<String, f64>,
+    #[serde(default)]
+    pub k_truss: HashMap<String, usize>,
+    /// Ids of nodes whose removal disconnects the graph
+    #[serde(default)]
+    pub articulation_points: Vec<String>,
+    /// Ids at either end of edges whose removal disconnects the graph
+    #[serde(default)]
+    pub bridges: Vec<(String, String)>,
     pub errors: Vec<String>,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct DegreeMetrics {
     pub in_degree: f64,
     pub out_degree: f64,
@@ -336,6 +593,7 @@ pub struct DegreeMetrics {
 mod tests {
     use super::*;
     use og_graph::graph::GraphNode;
+    use og_graph::testing::node;
 
     #[test]
     fn test_empty_graph() {
@@ -350,12 +608,10 @@ mod tests {
     fn test_single_node() {
         let mut graph = CodeGraph::new();
         graph.add_node(GraphNode {
-            id: "node1".to_string(),
             name: "Node 1".to_string(),
-            node_type: "file".to_string(),
+            size: 100.0,
             file_path: Some("/test.js".to_string()),
-            size: 100,
-            color: None,
+            ..node("node1")
         });
         
         let metrics = CentralityMetrics::new();
@@ -1,15 +1,20 @@
 use anyhow::{Result, Context};
 use og_graph::graph::CodeGraph;
+use og_types::MetricSchema;
 use petgraph::graph::NodeIndex;
 use petgraph::visit::EdgeRef;
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use tracing::{debug, warn};
 
 /// Community detection algorithms with robust error handling
+#[derive(Clone)]
 pub struct CommunityDetection {
     pub resolution: f64,
     pub max_iterations: usize,
     pub min_modularity_gain: f64,
+    /// Edge types that link nodes into communities, all of them when empty
+    pub edge_types: Vec<String>,
 }
 
 impl Default for CommunityDetection {
@@ -18,6 +23,7 @@ impl Default for CommunityDetection {
             resolution: 1.0,
             max_iterations: 100,
             min_modularity_gain: 1e-6,
+            edge_types: Vec::new(),
         }
     }
 }
@@ -37,6 +43,8 @@ impl CommunityDetection {
     /// Run community detection with error recovery
     pub fn detect_communities(&self, graph: &CodeGraph) -> Result<CommunityResults> {
         let mut results = CommunityResults::default();
+        let projected = graph.project(&self.edge_types);
+        let graph = projected.as_ref();
         
         // Validate input
         if graph.graph.node_count() == 0 {
@@ -330,8 +338,10 @@ impl CommunityDetection {
     }
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct CommunityResults {
+    pub schema: MetricSchema,
     pub communities: HashMap<String, usize>,
     pub num_communities: usize,
     pub modularity: f64,
@@ -341,7 +351,8 @@ pub struct CommunityResults {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use og_graph::graph::{GraphNode, GraphEdge};
+    use og_graph::graph::GraphNode;
+    use og_graph::testing::{edge, node};
 
     #[test]
     fn test_empty_graph() {
@@ -359,24 +370,16 @@ mod tests {
         // Add connected nodes
         for i in 0..3 {
             graph.add_node(GraphNode {
-                id: format!("node{}", i),
                 name: format!("Node {}", i),
-                node_type: "file".to_string(),
+                size: 100.0,
                 file_path: Some(format!("/test{}.js", i)),
-                size: 100,
-                color: None,
+                ..node(&format!("node{}", i))
             });
         }
         
         // Connect them
-        graph.add_edge("node0", "node1", GraphEdge {
-            edge_type: "imports".to_string(),
-            weight: 1.0,
-        });
-        graph.add_edge("node1", "node2", GraphEdge {
-            edge_type: "imports".to_string(),
-            weight: 1.0,
-        });
+        graph.add_edge("node0", "node1", edge("imports"));
+        graph.add_edge("node1", "node2", edge("imports"));
         
         let detector = CommunityDetection::new();
         let results = detector.detect_communities(&graph).unwrap();
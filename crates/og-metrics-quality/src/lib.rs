@@ -1,8 +1,12 @@
 use anyhow::Result;
+use og_graph::attributes::COMPLEXITY_ATTRIBUTE;
 use og_graph::graph::CodeGraph;
+use og_types::{FunctionMetrics, Language, MetricSchema, Suppressions};
 use petgraph::Direction;
 use petgraph::visit::EdgeRef;
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
+use std::path::Path;
 use tracing::{debug, warn};
 
 /// Quality metrics analyzer with robust error handling
@@ -10,6 +14,59 @@ pub struct QualityAnalyzer {
     pub complexity_threshold: f64,
     pub cohesion_threshold: f64,
     pub size_threshold: usize,
+    /// Fewest client files before an interface or module is checked for segregation
+    pub segregation_min_clients: usize,
+    /// Mean share of members a client may use before the interface counts as fat
+    pub segregation_max_usage: f64,
+    /// Most parameters a function may declare before its parameter list counts as long
+    pub max_parameters: usize,
+    /// Deepest a function may nest branches and loops before it counts as deeply nested
+    pub max_nesting: usize,
+    /// Shape of each function and method body, by node id, from parsing
+    pub function_metrics: HashMap<String, FunctionMetrics>,
+    /// Smells silenced by inline comments
+    pub suppressions: Suppressions,
+    /// Thresholds of each language, by name, where its idioms differ from the defaults
+    pub language_profiles: HashMap<String, QualityProfile>,
+}
+
+/// Smell thresholds of one language, falling back to the analyzer's where unset
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QualityProfile {
+    pub size_threshold: Option<usize>,
+    pub max_parameters: Option<usize>,
+    pub max_nesting: Option<usize>,
+}
+
+/// Thresholds a node is judged against once its language is known
+struct Limits {
+    size_threshold: usize,
+    max_parameters: usize,
+    max_nesting: usize,
+}
+
+/// Baselines for languages that are terser or wordier than average
+///
+/// Python says in 400 lines what C spreads over 1000, C headers and Rust
+/// modules run long by convention, and shell scripts grow hard to follow early.
+pub fn default_quality_profiles() -> HashMap<String, QualityProfile> {
+    let profile = |size_threshold, max_parameters, max_nesting| QualityProfile {
+        size_threshold: Some(size_threshold),
+        max_parameters: Some(max_parameters),
+        max_nesting: Some(max_nesting),
+    };
+    [
+        (Language::Python, profile(400, 5, 4)),
+        (Language::JavaScript, profile(400, 4, 4)),
+        (Language::TypeScript, profile(500, 4, 4)),
+        (Language::Rust, profile(800, 6, 4)),
+        (Language::C, profile(1000, 6, 5)),
+        (Language::Shell, profile(300, 5, 3)),
+        (Language::Css, profile(1000, 5, 4)),
+    ]
+    .into_iter()
+    .map(|(language, profile)| (language.as_str().to_string(), profile))
+    .collect()
 }
 
 impl Default for QualityAnalyzer {
@@ -18,15 +75,51 @@ impl Default for QualityAnalyzer {
             complexity_threshold: 10.0,
             cohesion_threshold: 0.5,
             size_threshold: 500,
+            segregation_min_clients: 3,
+            segregation_max_usage: 0.5,
+            max_parameters: 5,
+            max_nesting: 4,
+            function_metrics: HashMap::new(),
+            suppressions: Suppressions::default(),
+            language_profiles: default_quality_profiles(),
         }
     }
 }
 
+/// How the clients of one interface, class or module use its members
+#[derive(Debug, Clone)]
+pub struct SegregationUsage {
+    pub members: usize,
+    /// Files outside the owner that use at least one member
+    pub clients: usize,
+    /// Mean share of members each client uses
+    pub mean_usage: f64,
+    /// Mean Jaccard overlap between the member sets of pairs of clients
+    pub mean_overlap: f64,
+}
+
+fn is_structural_edge(edge_type: &str) -> bool {
+    edge_type.eq_ignore_ascii_case("contains")
+}
+
 impl QualityAnalyzer {
     pub fn new() -> Self {
         Self::default()
     }
 
+    /// Thresholds for a node, from the profile of its file's language if any
+    fn limits(&self, file_path: Option<&str>) -> Limits {
+        let profile = file_path
+            .and_then(|path| Language::from_path(Path::new(path)))
+            .and_then(|language| self.language_profiles.get(language.as_str()));
+        let profile = profile.cloned().unwrap_or_default();
+        Limits {
+            size_threshold: profile.size_threshold.unwrap_or(self.size_threshold),
+            max_parameters: profile.max_parameters.unwrap_or(self.max_parameters),
+            max_nesting: profile.max_nesting.unwrap_or(self.max_nesting),
+        }
+    }
+
     /// Analyze all quality metrics with error recovery
     pub fn analyze_quality(&self, graph: &CodeGraph) -> Result<QualityResults> {
         let mut results = QualityResults::default();
@@ -98,6 +191,14 @@ impl QualityAnalyzer {
     fn calculate_complexity_metrics(&self, graph: &CodeGraph) -> Result<HashMap<String, ComplexityMetrics>> {
         let mut complexity_map = HashMap::new();
 
+        // Files add up the decisions of their parsed functions
+        let mut file_complexity: HashMap<&str, f64> = HashMap::new();
+        for node in graph.graph.node_weights() {
+            if let (Some(function), Some(file_path)) = (self.function_metrics.get(&node.id), &node.file_path) {
+                *file_complexity.entry(file_path.as_str()).or_insert(1.0) += function.complexity.saturating_sub(1) as f64;
+            }
+        }
+
         for node_idx in graph.graph.node_indices() {
             if let Some(node) = graph.graph.node_weight(node_idx) {
                 // Calculate cyclomatic complexity (simplified)
@@ -111,8 +212,13 @@ impl QualityAnalyzer {
                     .edges_directed(node_idx, Direction::Incoming)
                     .count();
 
-                // Estimate cyclomatic complexity based on node type and connections
-                let cyclomatic = match node.node_type.as_str() {
+                // Parsed complexity where there is one, otherwise an estimate from node type and connections
+                let parsed = node.number(COMPLEXITY_ATTRIBUTE).or_else(|| match node.node_type.as_str() {
+                    "function" | "method" => self.function_metrics.get(&node.id).map(|f| f.complexity as f64),
+                    "file" => node.file_path.as_deref().and_then(|path| file_complexity.get(path).copied()),
+                    _ => None,
+                });
+                let cyclomatic = parsed.unwrap_or_else(|| match node.node_type.as_str() {
                     "function" | "method" => {
                         // Functions: complexity increases with branches
                         (1.0 + out_degree as f64).min(50.0)
@@ -126,7 +232,7 @@ impl QualityAnalyzer {
                         ((out_degree + in_degree) as f64 / 3.0).min(50.0)
                     }
                     _ => 1.0,
-                };
+                });
 
                 // Calculate cognitive complexity (simplified)
                 let cognitive = cyclomatic * 1.2; // Slightly higher than cyclomatic
@@ -215,13 +321,89 @@ impl QualityAnalyzer {
         Ok(cohesion_map)
     }
 
+    /// Member usage of interfaces, classes and files by clients in other files
+    ///
+    /// Relies on symbol-level edges (calls, references, extends) into the
+    /// owner's members; clients that only import the owner as a whole are not
+    /// counted because the members they use are unknown.
+    pub fn segregation_usage(&self, graph: &CodeGraph) -> HashMap<String, SegregationUsage> {
+        let mut usage_map = HashMap::new();
+
+        for owner_idx in graph.graph.node_indices() {
+            let owner = &graph.graph[owner_idx];
+            if !matches!(owner.node_type.as_str(), "interface" | "class" | "file") {
+                continue;
+            }
+
+            let members: Vec<_> = graph
+                .graph
+                .edges_directed(owner_idx, Direction::Outgoing)
+                .filter(|edge| is_structural_edge(&edge.weight().edge_type))
+                .map(|edge| edge.target())
+                .collect();
+            if members.len() < 3 {
+                continue;
+            }
+
+            // Members used by each client file
+            let mut clients: HashMap<&str, HashSet<usize>> = HashMap::new();
+            for (position, &member) in members.iter().enumerate() {
+                for edge in graph.graph.edges_directed(member, Direction::Incoming) {
+                    if is_structural_edge(&edge.weight().edge_type) {
+                        continue;
+                    }
+                    let Some(client_file) = graph.graph[edge.source()].file_path.as_deref() else {
+                        continue;
+                    };
+                    if owner.file_path.as_deref() != Some(client_file) {
+                        clients.entry(client_file).or_default().insert(position);
+                    }
+                }
+            }
+            if clients.len() < self.segregation_min_clients {
+                continue;
+            }
+
+            let used: Vec<&HashSet<usize>> = clients.values().collect();
+            let mean_usage = used.iter().map(|set| set.len() as f64).sum::<f64>()
+                / (used.len() * members.len()) as f64;
+
+            let mut overlap_sum = 0.0;
+            let mut pairs = 0;
+            for (i, a) in used.iter().enumerate() {
+                for b in &used[i + 1..] {
+                    let union = a.union(b).count();
+                    if union > 0 {
+                        overlap_sum += a.intersection(b).count() as f64 / union as f64;
+                    }
+                    pairs += 1;
+                }
+            }
+            let mean_overlap = if pairs > 0 { overlap_sum / pairs as f64 } else { 1.0 };
+
+            usage_map.insert(
+                owner.id.clone(),
+                SegregationUsage {
+                    members: members.len(),
+                    clients: clients.len(),
+                    mean_usage,
+                    mean_overlap,
+                },
+            );
+        }
+
+        usage_map
+    }
+
     /// Detect code smells
     fn analyze_code_smells(&self, graph: &CodeGraph) -> Result<HashMap<String, CodeSmells>> {
         let mut smells_map = HashMap::new();
+        let segregation = self.segregation_usage(graph);
 
         for node_idx in graph.graph.node_indices() {
             if let Some(node) = graph.graph.node_weight(node_idx) {
                 let mut smells = Vec::new();
+                let limits = self.limits(node.file_path.as_deref());
 
                 // God class/module detection
                 let out_degree = graph
@@ -246,15 +428,18 @@ impl QualityAnalyzer {
                 }
 
                 // Long method/file detection
-                if node.size as usize > self.size_threshold {
+                if node.size as usize > limits.size_threshold {
                     smells.push(CodeSmell {
                         smell_type: "Large File".to_string(),
-                        severity: if node.size as usize > self.size_threshold * 2 { 
+                        severity: if node.size as usize > limits.size_threshold * 2 { 
                             "High".to_string() 
                         } else { 
                             "Medium".to_string() 
                         },
-                        description: format!("File size: {} lines", node.size as usize),
+                        description: format!(
+                            "File size: {} lines, over the {} line limit",
+                            node.size as usize, limits.size_threshold
+                        ),
                     });
                 }
 
@@ -276,6 +461,55 @@ impl QualityAnalyzer {
                     });
                 }
 
+                // Interface segregation: many clients, each using a small, different slice
+                if let Some(usage) = segregation.get(&node.id) {
+                    if usage.mean_usage <= self.segregation_max_usage && usage.mean_overlap < 0.5 {
+                        let kind = if node.node_type == "file" { "Wide Module" } else { "Fat Interface" };
+                        smells.push(CodeSmell {
+                            smell_type: format!("Interface Segregation ({})", kind),
+                            severity: if usage.clients >= self.segregation_min_clients * 2 {
+                                "High".to_string()
+                            } else {
+                                "Medium".to_string()
+                            },
+                            description: format!(
+                                "{} clients each use {:.0}% of {} members on average, with {:.0}% overlap",
+                                usage.clients,
+                                usage.mean_usage * 100.0,
+                                usage.members,
+                                usage.mean_overlap * 100.0,
+                            ),
+                        });
+                    }
+                }
+
+                // Function shape from parsing: long parameter lists and deep nesting
+                if let Some(function) = self.function_metrics.get(&node.id) {
+                    if function.parameters > limits.max_parameters {
+                        smells.push(CodeSmell {
+                            smell_type: "Long Parameter List".to_string(),
+                            severity: if function.parameters > limits.max_parameters * 2 {
+                                "High".to_string()
+                            } else {
+                                "Medium".to_string()
+                            },
+                            description: format!("{} parameters", function.parameters),
+                        });
+                    }
+                    if function.max_nesting > limits.max_nesting {
+                        smells.push(CodeSmell {
+                            smell_type: "Deep Nesting".to_string(),
+                            severity: if function.max_nesting > limits.max_nesting + 2 {
+                                "High".to_string()
+                            } else {
+                                "Medium".to_string()
+                            },
+                            description: format!("Branches and loops nested {} levels deep", function.max_nesting),
+                        });
+                    }
+                }
+
+                smells.retain(|smell| !self.suppressions.is_suppressed(&node.id, &smell.smell_type));
                 if !smells.is_empty() {
                     smells_map.insert(
                         node.id.clone(),
@@ -340,8 +574,10 @@ impl QualityAnalyzer {
     }
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct QualityResults {
+    pub schema: MetricSchema,
     pub complexity_metrics: HashMap<String, ComplexityMetrics>,
     pub cohesion_metrics: HashMap<String, CohesionMetrics>,
     pub code_smells: HashMap<String, CodeSmells>,
@@ -352,7 +588,8 @@ pub struct QualityResults {
     pub errors: Vec<String>,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ComplexityMetrics {
     pub cyclomatic_complexity: f64,
     pub cognitive_complexity: f64,
@@ -360,7 +597,8 @@ pub struct ComplexityMetrics {
     pub depth_of_inheritance: f64,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct CohesionMetrics {
     pub cohesion_score: f64,
     pub lcom: f64, // Lack of Cohesion of Methods
@@ -368,12 +606,14 @@ pub struct CohesionMetrics {
     pub external_connections: usize,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct CodeSmells {
     pub smells: Vec<CodeSmell>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct CodeSmell {
     pub smell_type: String,
     pub severity: String,
@@ -383,7 +623,8 @@ pub struct CodeSmell {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use og_graph::graph::{GraphNode, GraphEdge};
+    use og_graph::graph::GraphNode;
+    use og_graph::testing::{edge, node};
 
     #[test]
     fn test_empty_graph() {
@@ -400,30 +641,24 @@ mod tests {
         
         // Create a god object with many connections
         graph.add_node(GraphNode {
-            id: "god".to_string(),
             name: "GodObject".to_string(),
             node_type: "class".to_string(),
+            size: 1000.0,
             file_path: Some("/god.js".to_string()),
-            size: 1000,
-            color: None,
+            ..node("god")
         });
         
         // Add many dependencies
         for i in 0..35 {
             let node_id = format!("dep{}", i);
             graph.add_node(GraphNode {
-                id: node_id.clone(),
                 name: format!("Dep{}", i),
-                node_type: "file".to_string(),
+                size: 100.0,
                 file_path: Some(format!("/dep{}.js", i)),
-                size: 100,
-                color: None,
+                ..node(&node_id)
             });
             
-            graph.add_edge("god", &node_id, GraphEdge {
-                edge_type: "imports".to_string(),
-                weight: 1.0,
-            });
+            graph.add_edge("god", &node_id, edge("imports"));
         }
         
         let analyzer = QualityAnalyzer::new();
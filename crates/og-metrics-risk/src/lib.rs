@@ -1,16 +1,69 @@
 use anyhow::Result;
 use og_graph::graph::CodeGraph;
+use og_types::{Language, MetricSchema, Suppressions};
 use petgraph::algo::tarjan_scc;
+use petgraph::graph::NodeIndex;
 use petgraph::Direction;
+use serde::{Deserialize, Serialize};
 // Removed unused EdgeRef import
 use std::collections::HashMap;
+use std::path::Path;
 use tracing::{debug, warn};
 
+/// Weight of each factor in the overall risk score
+pub const COMPLEXITY_WEIGHT: f64 = 0.3;
+pub const CENTRALITY_WEIGHT: f64 = 0.3;
+pub const BOTTLENECK_WEIGHT: f64 = 0.2;
+pub const COUPLING_WEIGHT: f64 = 0.2;
+
+/// Complexity of a node estimated from its type and outgoing edges
+pub fn complexity_estimate(node_type: &str, out_degree: usize) -> f64 {
+    match node_type {
+        "function" | "method" => ((out_degree + 1) as f64).min(50.0),
+        "class" => ((out_degree * 2) as f64).min(100.0),
+        "file" | "module" => ((out_degree as f64).sqrt() * 5.0).min(50.0),
+        _ => 1.0,
+    }
+}
+
 /// Risk analysis with robust error handling
 pub struct RiskAnalyzer {
     pub complexity_threshold: f64,
     pub high_coupling_threshold: usize,
     pub bottleneck_threshold: usize,
+    /// Findings silenced by inline comments
+    pub suppressions: Suppressions,
+    /// Thresholds of each language, by name, where its idioms differ from the defaults
+    pub language_profiles: HashMap<String, RiskProfile>,
+}
+
+/// Risk thresholds of one language, falling back to the analyzer's where unset
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RiskProfile {
+    pub complexity_threshold: Option<f64>,
+    pub high_coupling_threshold: Option<usize>,
+}
+
+/// Baselines for languages whose files are coupled more or less than average
+///
+/// C headers are included widely by design, and Python and shell modules
+/// usually import fewer dependencies than JavaScript ones.
+pub fn default_risk_profiles() -> HashMap<String, RiskProfile> {
+    let profile = |complexity_threshold, high_coupling_threshold| RiskProfile {
+        complexity_threshold: Some(complexity_threshold),
+        high_coupling_threshold: Some(high_coupling_threshold),
+    };
+    [
+        (Language::Python, profile(12.0, 10)),
+        (Language::JavaScript, profile(15.0, 12)),
+        (Language::TypeScript, profile(15.0, 12)),
+        (Language::Rust, profile(20.0, 12)),
+        (Language::C, profile(20.0, 20)),
+        (Language::Shell, profile(10.0, 8)),
+    ]
+    .into_iter()
+    .map(|(language, profile)| (language.as_str().to_string(), profile))
+    .collect()
 }
 
 impl Default for RiskAnalyzer {
@@ -19,6 +72,8 @@ impl Default for RiskAnalyzer {
             complexity_threshold: 15.0,
             high_coupling_threshold: 10,
             bottleneck_threshold: 5,
+            suppressions: Suppressions::default(),
+            language_profiles: default_risk_profiles(),
         }
     }
 }
@@ -28,6 +83,21 @@ impl RiskAnalyzer {
         Self::default()
     }
 
+    /// Complexity and coupling thresholds for a node, from its file's language if profiled
+    pub fn thresholds(&self, file_path: Option<&str>) -> (f64, usize) {
+        let profile = file_path
+            .and_then(|path| Language::from_path(Path::new(path)))
+            .and_then(|language| self.language_profiles.get(language.as_str()));
+        (
+            profile
+                .and_then(|p| p.complexity_threshold)
+                .unwrap_or(self.complexity_threshold),
+            profile
+                .and_then(|p| p.high_coupling_threshold)
+                .unwrap_or(self.high_coupling_threshold),
+        )
+    }
+
     /// Analyze all risk factors with error recovery
     pub fn analyze_risks(&self, graph: &CodeGraph) -> Result<RiskResults> {
         let mut results = RiskResults::default();
@@ -72,8 +142,8 @@ impl RiskAnalyzer {
         }
 
         // Calculate summary statistics
-        results.high_risk_count = results.risk_scores.values()
-            .filter(|score| score.overall > 0.7)
+        results.high_risk_count = results.risk_scores.iter()
+            .filter(|(id, score)| score.overall > 0.7 && !self.suppressions.is_suppressed(id, "High Risk"))
             .count();
         
         results.total_circular_deps = results.circular_dependencies.len();
@@ -91,73 +161,70 @@ impl RiskAnalyzer {
     /// Identify high-risk nodes with validation
     fn identify_high_risk_nodes(&self, graph: &CodeGraph) -> Result<HashMap<String, RiskScore>> {
         let mut risk_scores = HashMap::new();
-        let node_count = graph.graph.node_count().max(1) as f64;
 
         for node_idx in graph.graph.node_indices() {
             if let Some(node) = graph.graph.node_weight(node_idx) {
-                // Calculate degree centrality
-                let in_degree = graph
-                    .graph
-                    .edges_directed(node_idx, Direction::Incoming)
-                    .count();
-                let out_degree = graph
-                    .graph
-                    .edges_directed(node_idx, Direction::Outgoing)
-                    .count();
-                
-                let total_degree = in_degree + out_degree;
-                let normalized_degree = (total_degree as f64 / node_count).clamp(0.0, 1.0);
-
-                // Estimate complexity based on node type and connections
-                let complexity_estimate = match node.node_type.as_str() {
-                    "function" | "method" => ((out_degree + 1) as f64).min(50.0),
-                    "class" => ((out_degree * 2) as f64).min(100.0),
-                    "file" | "module" => ((out_degree as f64).sqrt() * 5.0).min(50.0),
-                    _ => 1.0,
-                };
-
-                // Calculate risk factors with bounds
-                let complexity_risk = (complexity_estimate / self.complexity_threshold)
-                    .clamp(0.0, 1.0);
-                let centrality_risk = normalized_degree.clamp(0.0, 1.0);
-                
-                // Check if node is a bottleneck
-                let is_bottleneck = in_degree > self.bottleneck_threshold 
-                    && out_degree > self.bottleneck_threshold;
-                let bottleneck_risk = if is_bottleneck { 0.5 } else { 0.0 };
-
-                // Check coupling risk
-                let coupling_risk = if total_degree > self.high_coupling_threshold {
-                    ((total_degree - self.high_coupling_threshold) as f64 / 10.0)
-                        .clamp(0.0, 1.0)
-                } else {
-                    0.0
-                };
-
-                // Combined risk score with weights
-                let overall_risk = (
-                    complexity_risk * 0.3 + 
-                    centrality_risk * 0.3 + 
-                    bottleneck_risk * 0.2 +
-                    coupling_risk * 0.2
-                ).clamp(0.0, 1.0);
-
-                risk_scores.insert(
-                    node.id.clone(),
-                    RiskScore {
-                        overall: overall_risk,
-                        complexity: complexity_risk,
-                        centrality: centrality_risk,
-                        bottleneck: bottleneck_risk,
-                        coupling: coupling_risk,
-                    },
-                );
+                risk_scores.insert(node.id.clone(), self.score(graph, node_idx));
             }
         }
 
         Ok(risk_scores)
     }
 
+    /// Risk factors of one node
+    pub fn score(&self, graph: &CodeGraph, node_idx: NodeIndex) -> RiskScore {
+        let node = &graph.graph[node_idx];
+        let node_count = graph.graph.node_count().max(1) as f64;
+
+        // Calculate degree centrality
+        let in_degree = graph
+            .graph
+            .edges_directed(node_idx, Direction::Incoming)
+            .count();
+        let out_degree = graph
+            .graph
+            .edges_directed(node_idx, Direction::Outgoing)
+            .count();
+
+        let total_degree = in_degree + out_degree;
+        let normalized_degree = (total_degree as f64 / node_count).clamp(0.0, 1.0);
+
+        // Calculate risk factors with bounds
+        let (complexity_threshold, high_coupling_threshold) = self.thresholds(node.file_path.as_deref());
+        let complexity_risk = (complexity_estimate(&node.node_type, out_degree) / complexity_threshold)
+            .clamp(0.0, 1.0);
+        let centrality_risk = normalized_degree.clamp(0.0, 1.0);
+
+        // Check if node is a bottleneck
+        let is_bottleneck = in_degree > self.bottleneck_threshold
+            && out_degree > self.bottleneck_threshold;
+        let bottleneck_risk = if is_bottleneck { 0.5 } else { 0.0 };
+
+        // Check coupling risk
+        let coupling_risk = if total_degree > high_coupling_threshold {
+            ((total_degree - high_coupling_threshold) as f64 / 10.0)
+                .clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+
+        // Combined risk score with weights
+        let overall_risk = (
+            complexity_risk * COMPLEXITY_WEIGHT +
+            centrality_risk * CENTRALITY_WEIGHT +
+            bottleneck_risk * BOTTLENECK_WEIGHT +
+            coupling_risk * COUPLING_WEIGHT
+        ).clamp(0.0, 1.0);
+
+        RiskScore {
+            overall: overall_risk,
+            complexity: complexity_risk,
+            centrality: centrality_risk,
+            bottleneck: bottleneck_risk,
+            coupling: coupling_risk,
+        }
+    }
+
     /// Find architectural chokepoints with safety checks
     fn find_chokepoints(&self, graph: &CodeGraph) -> Result<HashMap<String, f64>> {
         let mut chokepoints = HashMap::new();
@@ -184,7 +251,7 @@ impl RiskAnalyzer {
                     // Chokepoint score is product of in and out scores
                     let chokepoint_score = (in_score * out_score).sqrt().clamp(0.0, 1.0);
                     
-                    if chokepoint_score > 0.1 {
+                    if chokepoint_score > 0.1 && !self.suppressions.is_suppressed(&node.id, "Chokepoint") {
                         chokepoints.insert(node.id.clone(), chokepoint_score);
                     }
                 }
@@ -214,7 +281,11 @@ impl RiskAnalyzer {
                     }
                 }
                 
-                if !cycle_nodes.is_empty() {
+                // A cycle is silenced when any of its members acknowledges it
+                let silenced = cycle_nodes
+                    .iter()
+                    .any(|id| self.suppressions.is_suppressed(id, "Circular Dependency"));
+                if !cycle_nodes.is_empty() && !silenced {
                     // Sort for consistent output
                     cycle_nodes.sort();
                     cycles.push(cycle_nodes);
@@ -275,8 +346,10 @@ impl RiskAnalyzer {
     }
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct RiskResults {
+    pub schema: MetricSchema,
     pub risk_scores: HashMap<String, RiskScore>,
     pub chokepoints: HashMap<String, f64>,
     pub circular_dependencies: Vec<Vec<String>>,
@@ -287,7 +360,8 @@ pub struct RiskResults {
     pub errors: Vec<String>,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct RiskScore {
     pub overall: f64,
     pub complexity: f64,
@@ -296,7 +370,8 @@ pub struct RiskScore {
     pub coupling: f64,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct CouplingMetrics {
     pub afferent_coupling: usize,
     pub efferent_coupling: usize,
@@ -307,7 +382,8 @@ pub struct CouplingMetrics {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use og_graph::graph::{GraphNode, GraphEdge};
+    use og_graph::graph::GraphNode;
+    use og_graph::testing::{edge, node};
 
     #[test]
     fn test_empty_graph() {
@@ -325,27 +401,16 @@ mod tests {
         // Create a cycle: A -> B -> C -> A
         for i in 0..3 {
             graph.add_node(GraphNode {
-                id: format!("node{}", i),
                 name: format!("Node {}", i),
-                node_type: "file".to_string(),
+                size: 100.0,
                 file_path: Some(format!("/test{}.js", i)),
-                size: 100,
-                color: None,
+                ..node(&format!("node{}", i))
             });
         }
         
-        graph.add_edge("node0", "node1", GraphEdge {
-            edge_type: "imports".to_string(),
-            weight: 1.0,
-        });
-        graph.add_edge("node1", "node2", GraphEdge {
-            edge_type: "imports".to_string(),
-            weight: 1.0,
-        });
-        graph.add_edge("node2", "node0", GraphEdge {
-            edge_type: "imports".to_string(),
-            weight: 1.0,
-        });
+        graph.add_edge("node0", "node1", edge("imports"));
+        graph.add_edge("node1", "node2", edge("imports"));
+        graph.add_edge("node2", "node0", edge("imports"));
         
         let analyzer = RiskAnalyzer::new();
         let results = analyzer.analyze_risks(&graph).unwrap();
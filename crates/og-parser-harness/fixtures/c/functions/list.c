@@ -0,0 +1,20 @@
+#include <stdlib.h>
+#include "list.h"
+
+struct node {
+    int value;
+    struct node *next;
+};
+
+static struct node *make_node(int value) {
+    struct node *n = malloc(sizeof(*n));
+    n->value = value;
+    return n;
+}
+
+int list_push(struct node **head, int value) {
+    struct node *n = make_node(value);
+    n->next = *head;
+    *head = n;
+    return 0;
+}
@@ -0,0 +1,14 @@
+import { helper } from './util';
+const fs = require('fs');
+
+export class App extends Base {
+  start() {
+    helper();
+    return fs.readFileSync('x');
+  }
+}
+
+export function main() {
+  const app = new App();
+  app.start();
+}
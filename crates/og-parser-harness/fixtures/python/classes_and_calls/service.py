@@ -0,0 +1,15 @@
+import os
+from .models import User
+
+
+class Service:
+    def __init__(self):
+        self.root = os.environ.get("ROOT")
+
+    def load(self, user_id):
+        return User.get(user_id)
+
+
+def run():
+    service = Service()
+    service.load(1)
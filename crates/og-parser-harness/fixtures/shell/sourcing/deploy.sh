@@ -0,0 +1,13 @@
+#!/bin/bash
+source ./env.sh
+
+build() {
+  make all
+}
+
+deploy() {
+  build
+  ./scripts/upload.sh "$TARGET"
+}
+
+deploy
@@ -0,0 +1,21 @@
+import { Logger } from './logger';
+
+export interface Shape {
+  area(): number;
+}
+
+export type Id = string;
+
+export enum Kind {
+  Circle,
+  Square,
+}
+
+export class Circle implements Shape {
+  constructor(private radius: number, private log: Logger) {}
+
+  area(): number {
+    this.log.info('area');
+    return Math.PI * this.radius * this.radius;
+  }
+}
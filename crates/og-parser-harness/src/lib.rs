@@ -0,0 +1,192 @@
+//! Golden-file fixtures for the parsers
+//!
+//! Each fixture is a directory `fixtures/<language>/<case>/` holding one
+//! source file and an `expected.json` with the nodes and relationships the
+//! parser should produce for it. Set `OMNIGRAPH_UPDATE_GOLDEN=1` to rewrite
+//! the expected files from the current parser output.
+
+use anyhow::{bail, Context, Result};
+use og_parser::ParserEngine;
+use og_types::ParsedFile;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Name of the expected output in a fixture directory
+pub const EXPECTED_FILE: &str = "expected.json";
+
+/// Environment variable that rewrites expected files instead of comparing
+pub const UPDATE_ENV: &str = "OMNIGRAPH_UPDATE_GOLDEN";
+
+/// Root of the fixture directories shipped with this crate
+pub fn fixtures_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures")
+}
+
+/// A node as recorded in a golden file
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct GoldenNode {
+    pub id: String,
+    pub node_type: String,
+    pub name: String,
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+/// A relationship as recorded in a golden file
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct GoldenRelationship {
+    pub source: String,
+    pub target: String,
+    pub kind: String,
+}
+
+/// Parser output in a stable order, as stored in `expected.json`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Golden {
+    pub nodes: Vec<GoldenNode>,
+    pub relationships: Vec<GoldenRelationship>,
+}
+
+impl From<&ParsedFile> for Golden {
+    fn from(parsed: &ParsedFile) -> Self {
+        let mut nodes: Vec<GoldenNode> = parsed
+            .nodes
+            .iter()
+            .map(|node| GoldenNode {
+                id: node.id.clone(),
+                node_type: node.node_type.as_str().to_string(),
+                name: node.name.clone(),
+                start_line: node.start_line,
+                end_line: node.end_line,
+            })
+            .collect();
+        nodes.sort();
+
+        let mut relationships: Vec<GoldenRelationship> = parsed
+            .relationships
+            .iter()
+            .map(|rel| GoldenRelationship {
+                source: rel.source.clone(),
+                target: rel.target.clone(),
+                kind: rel.relationship_type.as_str().to_string(),
+            })
+            .collect();
+        relationships.sort();
+
+        Self { nodes, relationships }
+    }
+}
+
+impl Golden {
+    /// Lines describing what `actual` has that `self` lacks and the reverse
+    pub fn diff(&self, actual: &Golden) -> Vec<String> {
+        let mut lines = Vec::new();
+        for node in &self.nodes {
+            if !actual.nodes.contains(node) {
+                lines.push(format!("- node {:?}", node));
+            }
+        }
+        for node in &actual.nodes {
+            if !self.nodes.contains(node) {
+                lines.push(format!("+ node {:?}", node));
+            }
+        }
+        for rel in &self.relationships {
+            if !actual.relationships.contains(rel) {
+                lines.push(format!("- relationship {:?}", rel));
+            }
+        }
+        for rel in &actual.relationships {
+            if !self.relationships.contains(rel) {
+                lines.push(format!("+ relationship {:?}", rel));
+            }
+        }
+        lines
+    }
+}
+
+/// One fixture directory
+#[derive(Debug, Clone)]
+pub struct Fixture {
+    /// `<language>/<case>`, used in failure messages
+    pub name: String,
+    pub source: PathBuf,
+    pub expected: PathBuf,
+}
+
+impl Fixture {
+    /// Source path relative to the fixtures root, so node ids do not depend on the checkout
+    fn relative_source(&self, root: &Path) -> PathBuf {
+        self.source.strip_prefix(root).unwrap_or(&self.source).to_path_buf()
+    }
+
+    /// Parse the fixture's source file
+    pub fn parse(&self, root: &Path) -> Result<Golden> {
+        let content = std::fs::read_to_string(&self.source)
+            .with_context(|| format!("reading {}", self.source.display()))?;
+        let parsed = ParserEngine::new()
+            .parse_file(&self.relative_source(root), &content)
+            .with_context(|| format!("parsing {}", self.name))?;
+        Ok(Golden::from(&parsed))
+    }
+
+    /// Compare the parser output with `expected.json`, or rewrite it in update mode
+    pub fn check(&self, root: &Path) -> Result<()> {
+        let actual = self.parse(root)?;
+        if std::env::var_os(UPDATE_ENV).is_some() {
+            let json = serde_json::to_string_pretty(&actual)?;
+            std::fs::write(&self.expected, json + "\n")
+                .with_context(|| format!("writing {}", self.expected.display()))?;
+            return Ok(());
+        }
+
+        let expected: Golden = serde_json::from_str(
+            &std::fs::read_to_string(&self.expected)
+                .with_context(|| format!("reading {}", self.expected.display()))?,
+        )
+        .with_context(|| format!("decoding {}", self.expected.display()))?;
+        let diff = expected.diff(&actual);
+        if !diff.is_empty() {
+            bail!("{} differs from {}:\n{}", self.name, EXPECTED_FILE, diff.join("\n"));
+        }
+        Ok(())
+    }
+}
+
+fn sorted_dirs(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut dirs = Vec::new();
+    for entry in std::fs::read_dir(dir).with_context(|| format!("listing {}", dir.display()))? {
+        let path = entry?.path();
+        if path.is_dir() {
+            dirs.push(path);
+        }
+    }
+    dirs.sort();
+    Ok(dirs)
+}
+
+/// Every fixture under `root`, ordered by language then case
+pub fn fixtures(root: &Path) -> Result<Vec<Fixture>> {
+    let mut fixtures = Vec::new();
+    for language in sorted_dirs(root)? {
+        for case in sorted_dirs(&language)? {
+            let mut sources = Vec::new();
+            for entry in std::fs::read_dir(&case)? {
+                let path = entry?.path();
+                if path.is_file() && path.file_name().is_some_and(|name| name != EXPECTED_FILE) {
+                    sources.push(path);
+                }
+            }
+            let name = case.strip_prefix(root).unwrap_or(&case).display().to_string();
+            let [source] = sources.as_slice() else {
+                bail!("fixture {} needs exactly one source file, found {}", name, sources.len());
+            };
+            fixtures.push(Fixture {
+                name,
+                source: source.clone(),
+                expected: case.join(EXPECTED_FILE),
+            });
+        }
+    }
+    Ok(fixtures)
+}
@@ -0,0 +1,56 @@
+use og_parser::ParserEngine;
+use proptest::prelude::*;
+use std::path::Path;
+
+/// One file name per parser, so every parser sees the generated input
+const FILES: &[&str] = &[
+    "fuzz.js",
+    "fuzz.ts",
+    "fuzz.tsx",
+    "fuzz.py",
+    "fuzz.c",
+    "fuzz.h",
+    "fuzz.sh",
+    "Makefile",
+    "Dockerfile",
+    "docker-compose.yml",
+    "fuzz.scss",
+    "fuzz.graphql",
+];
+
+/// Fragments of real syntax, so generated input gets past the first token
+const FRAGMENTS: &[&str] = &[
+    "import ", "from ", "export ", "class ", "def ", "function ", "interface ", "#include ",
+    "require(", "source ", "FROM ", "COPY ", "RUN ", "all: ", "\t", "{", "}", "(", ")", "[", "]",
+    ":", ";", "\"", "'", "`", "\n", "    ", "=>", "@", "*", "/*", "//", "#", "\\", "$(", "x", "é", "🦀",
+];
+
+fn fragments() -> impl Strategy<Value = String> {
+    prop::collection::vec(prop::sample::select(FRAGMENTS), 0..200).prop_map(|parts| parts.concat())
+}
+
+fn parse_everywhere(content: &str) {
+    let engine = ParserEngine::new();
+    for file in FILES {
+        let path = Path::new(file);
+        if let Ok(parsed) = engine.parse_file(path, content) {
+            for node in &parsed.nodes {
+                assert!(node.start_line <= node.end_line || node.start_line == 0, "{}: {:?}", file, node);
+            }
+        }
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn test_parsers_accept_arbitrary_utf8(content in any::<String>()) {
+        parse_everywhere(&content);
+    }
+
+    #[test]
+    fn test_parsers_accept_syntax_fragments(content in fragments()) {
+        parse_everywhere(&content);
+    }
+}
@@ -0,0 +1,28 @@
+use og_parser_harness::{fixtures, fixtures_root};
+
+#[test]
+fn test_fixtures_match_golden_files() {
+    let root = fixtures_root();
+    let fixtures = fixtures(&root).unwrap();
+    assert!(!fixtures.is_empty(), "no fixtures under {}", root.display());
+
+    let failures: Vec<String> = fixtures
+        .iter()
+        .filter_map(|fixture| fixture.check(&root).err())
+        .map(|e| format!("{:#}", e))
+        .collect();
+    assert!(failures.is_empty(), "{}", failures.join("\n\n"));
+}
+
+#[test]
+fn test_every_parser_has_a_fixture() {
+    let root = fixtures_root();
+    let languages: Vec<String> = fixtures(&root)
+        .unwrap()
+        .iter()
+        .filter_map(|fixture| fixture.name.split('/').next().map(str::to_string))
+        .collect();
+    for language in ["c", "css", "docker", "graphql", "javascript", "make", "python", "shell", "typescript"] {
+        assert!(languages.iter().any(|l| l == language), "no fixture for {}", language);
+    }
+}
@@ -3,15 +3,24 @@ use og_types::{
     EngineResult, EngineError, FileMetrics, ParseError,
 };
 use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
 use std::path::{Path, PathBuf};
-use std::sync::Mutex;
-use tree_sitter::{Node, Parser as TSParser, TreeCursor};
+use tree_sitter::{Node, Tree, TreeCursor};
 use tracing::{debug, trace};
 
+use crate::concurrency;
+use crate::docs;
+use crate::env_vars;
+use crate::sql;
+use crate::function_metrics;
+use crate::pool;
+use crate::shell::normalize;
+
 pub struct CParser {
-    parser: Mutex<TSParser>,
     base_path: PathBuf,
+    /// Directories searched for includes after the including file's own directory
+    include_dirs: Vec<PathBuf>,
 }
 
 impl CParser {
@@ -20,14 +29,25 @@ impl CParser {
     }
     
     pub fn with_base_path(base_path: PathBuf) -> Self {
-        let mut parser = TSParser::new();
-        parser
-            .set_language(tree_sitter_c::language())
-            .expect("Error loading C grammar");
+        #[cfg(feature = "fs")]
+        let include_dirs = crate::compile_commands::load_include_dirs(&base_path);
+        #[cfg(not(feature = "fs"))]
+        let include_dirs = Vec::new();
         Self {
-            parser: Mutex::new(parser),
             base_path,
+            include_dirs,
+        }
+    }
+
+    /// Search these directories before those from compile_commands.json
+    pub fn with_include_dirs(mut self, include_dirs: Vec<PathBuf>) -> Self {
+        let discovered = std::mem::replace(&mut self.include_dirs, include_dirs);
+        for dir in discovered {
+            if !self.include_dirs.contains(&dir) {
+                self.include_dirs.push(dir);
+            }
         }
+        self
     }
 
     fn generate_unique_id(file_path: &str, node_type: &str, counter: usize) -> String {
@@ -48,7 +68,7 @@ impl CParser {
         
         // Generate unique file ID based on file path
         let file_id = Self::generate_unique_id(file_path, "file", 0);
-        println!("[C_PARSER] Parsing file: {} with ID: {}", file_path, file_id);
+        tracing::debug!("[C_PARSER] Parsing file: {} with ID: {}", file_path, file_id);
         
         let mut node_counter = 0;
         
@@ -97,6 +117,7 @@ impl CParser {
                 "preproc_include" => {
                     if let Some((include_node, include_path)) = self.extract_include_with_path(node, source, file_path, node_counter, file_path) {
                         let include_id = include_node.id.clone();
+                        let is_system = include_node.name.contains('<');
                         
                         // Add include node
                         nodes.push(include_node);
@@ -109,8 +130,8 @@ impl CParser {
                         });
                         
                         // Create import relationship to the actual file
-                        if let Some(resolved_path) = self.resolve_include_path(&include_path, file_path) {
-                            println!("[C_PARSER] Creating import: {} -> {} (from include: {})", 
+                        if let Some(resolved_path) = self.resolve_include_path(&include_path, file_path, is_system) {
+                            tracing::debug!("[C_PARSER] Creating import: {} -> {} (from include: {})", 
                                      parent_id, resolved_path, include_path);
                             relationships.push(Relationship {
                                 source: parent_id.to_string(),
@@ -118,7 +139,22 @@ impl CParser {
                                 relationship_type: RelationshipType::Imports,
                             });
                         } else {
-                            println!("[C_PARSER] Could not resolve include path: {}", include_path);
+                            // Headers outside the project share one node per name
+                            let header_id = format!("system_header:{}", include_path);
+                            tracing::debug!("[C_PARSER] Treating {} as a system header", include_path);
+                            nodes.push(AstNode {
+                                id: header_id.clone(),
+                                node_type: NodeType::SystemHeader,
+                                name: include_path.clone(),
+                                start_line: 0,
+                                end_line: 0,
+                                children: Vec::new(),
+                            });
+                            relationships.push(Relationship {
+                                source: parent_id.to_string(),
+                                target: header_id,
+                                relationship_type: RelationshipType::Imports,
+                            });
                         }
                     }
                 }
@@ -195,7 +231,7 @@ impl CParser {
                 "declaration" => {
                     // Check for function declarations (prototypes)
                     if self.is_function_declaration(node, source) {
-                        if let Some(decl_node) = self.extract_function_declaration(node, source, node_counter) {
+                        if let Some(decl_node) = self.extract_function_declaration(node, source, node_counter, file_path) {
                             let decl_id = decl_node.id.clone();
                             nodes.push(decl_node);
                             relationships.push(Relationship {
@@ -206,7 +242,7 @@ impl CParser {
                         }
                     }
                     // Check for global variables
-                    else if let Some(var_node) = self.extract_global_variable(node, source, node_counter) {
+                    else if let Some(var_node) = self.extract_global_variable(node, source, node_counter, file_path) {
                         let var_id = var_node.id.clone();
                         
                         // Add variable node
@@ -220,7 +256,7 @@ impl CParser {
                         });
                     }
                 }
-                "preproc_def" => {
+                "preproc_def" | "preproc_function_def" => {
                     // Extract macro definitions
                     if let Some(macro_node) = self.extract_macro_definition(node, source, node_counter, file_path) {
                         let macro_id = macro_node.id.clone();
@@ -230,6 +266,26 @@ impl CParser {
                             target: macro_id.clone(),
                             relationship_type: RelationshipType::Contains,
                         });
+                        
+                        // Record what the macro body calls so call sites can be expanded
+                        let body = node
+                            .child_by_field_name("value")
+                            .and_then(|value| value.utf8_text(source.as_bytes()).ok())
+                            .unwrap_or_default();
+                        let parameters = node
+                            .child_by_field_name("parameters")
+                            .and_then(|params| params.utf8_text(source.as_bytes()).ok())
+                            .unwrap_or_default();
+                        let called = Self::macro_body_calls(body)
+                            .into_iter()
+                            .filter(|name| !parameters.split(|c: char| !c.is_ascii_alphanumeric() && c != '_').any(|p| p == name));
+                        for called_func in called {
+                            relationships.push(Relationship {
+                                source: macro_id.clone(),
+                                target: format!("function_{}", called_func),
+                                relationship_type: RelationshipType::Calls,
+                            });
+                        }
                     }
                 }
                 "type_definition" => {
@@ -314,14 +370,9 @@ impl CParser {
         Some((node, path))
     }
 
-    fn resolve_include_path(&self, include_path: &str, current_file: &str) -> Option<String> {
+    fn resolve_include_path(&self, include_path: &str, current_file: &str, is_system: bool) -> Option<String> {
         // Generate a file ID that matches how we create file IDs
         // This should match the ID of the target file when it's parsed
-        
-        // For system includes like <stdio.h>, we might not have the actual file
-        // For local includes, we need to resolve to the actual file path
-        
-        // Try to resolve the include path to an actual file path
         let current_dir = Path::new(current_file).parent()?;
         
         // Remove quotes or angle brackets from include path
@@ -331,13 +382,21 @@ impl CParser {
             .trim_start_matches('<')
             .trim_end_matches('>');
         
-        // Try to resolve relative to the current file's directory
-        let resolved_path = if clean_path.starts_with('/') {
-            // Absolute path
-            PathBuf::from(clean_path)
-        } else {
-            // Relative path - resolve relative to current file's directory
-            current_dir.join(clean_path)
+        // Quoted includes look next to the current file first, then every
+        // include directory; angle includes only use the include directories
+        let local = (!is_system).then(|| current_dir.to_path_buf());
+        let candidates: Vec<PathBuf> = local
+            .iter()
+            .chain(self.include_dirs.iter())
+            .map(|dir| normalize(&dir.join(clean_path)))
+            .collect();
+        
+        let resolved_path = match candidates.iter().find(|path| path.is_file()) {
+            Some(path) => path.clone(),
+            // Headers outside the project are handled by the caller
+            None if is_system => return None,
+            // Keep the old relative guess so quoted includes still link when the file is parsed
+            None => normalize(&current_dir.join(clean_path)),
         };
         
         // Convert to a string path that matches how files are parsed
@@ -346,7 +405,7 @@ impl CParser {
         // Generate the same ID that would be generated when this file is parsed
         let file_id = Self::generate_unique_id(path_str, "file", 0);
         
-        println!("[C_PARSER] Resolved include '{}' to path '{}' with ID '{}'", 
+        tracing::debug!("[C_PARSER] Resolved include '{}' to path '{}' with ID '{}'", 
                  include_path, path_str, file_id);
         
         Some(file_id)
@@ -402,6 +461,36 @@ impl CParser {
         None
     }
     
+    /// Functions a macro body calls, or the function an alias macro names
+    fn macro_body_calls(body: &str) -> Vec<String> {
+        const KEYWORDS: &[&str] = &["if", "while", "for", "switch", "return", "sizeof", "do", "defined"];
+        let is_ident = |c: char| c.is_ascii_alphanumeric() || c == '_';
+        let body = body.trim();
+        
+        // `#define LOG log_write` makes every LOG(...) a call to log_write
+        if !body.is_empty() && body.chars().all(is_ident) && !body.starts_with(|c: char| c.is_ascii_digit()) {
+            return vec![body.to_string()];
+        }
+        
+        let mut calls: Vec<String> = Vec::new();
+        let mut rest = body;
+        while let Some(start) = rest.find(|c: char| is_ident(c)) {
+            rest = &rest[start..];
+            let end = rest.find(|c: char| !is_ident(c)).unwrap_or(rest.len());
+            let (word, after) = rest.split_at(end);
+            let is_call = after.trim_start().starts_with('(');
+            if is_call
+                && !word.starts_with(|c: char| c.is_ascii_digit())
+                && !KEYWORDS.contains(&word)
+                && !calls.iter().any(|c| c == word)
+            {
+                calls.push(word.to_string());
+            }
+            rest = after;
+        }
+        calls
+    }
+    
     fn is_function_declaration(&self, node: Node, source: &str) -> bool {
         // Check if this declaration contains a function declarator
         let mut cursor = node.walk();
@@ -425,6 +514,7 @@ impl CParser {
         node: Node,
         source: &str,
         node_counter: &mut usize,
+        file_path: &str,
     ) -> Option<AstNode> {
         let mut cursor = node.walk();
         let mut function_name = None;
@@ -457,13 +547,13 @@ impl CParser {
         
         let name = function_name?;
         
-        let id = format!("func_decl_{}", node_counter);
+        let id = Self::generate_unique_id(file_path, "func_decl", *node_counter);
         *node_counter += 1;
         
         Some(AstNode {
             id,
             node_type: NodeType::Function,
-            name: format!("{} (declaration)", name),
+            name: format!("{}{}", name, DECLARATION_SUFFIX),
             start_line: node.start_position().row + 1,
             end_line: node.end_position().row + 1,
             children: Vec::new(),
@@ -666,6 +756,7 @@ impl CParser {
         node: Node,
         source: &str,
         node_counter: &mut usize,
+        file_path: &str,
     ) -> Option<AstNode> {
         // Skip typedef declarations
         let text = node.utf8_text(source.as_bytes()).ok()?;
@@ -707,7 +798,7 @@ impl CParser {
         
         let name = var_name?;
         
-        let id = format!("variable_{}", node_counter);
+        let id = Self::generate_unique_id(file_path, "variable", *node_counter);
         *node_counter += 1;
         
         Some(AstNode {
@@ -748,11 +839,16 @@ impl CParser {
         
         FileMetrics {
             lines_of_code: code_lines,
-            complexity: 1, // Basic complexity
+            complexity: 1, // Set from function shapes once they are measured
             functions,
             classes,
             imports,
             exports: 0, // C doesn't have explicit exports
+            dynamic_imports: 0,
+            function_metrics: HashMap::new(),
+            comments: Default::default(),
+            documented: HashMap::new(),
+            concurrency: HashMap::new(),
         }
     }
 }
@@ -767,12 +863,18 @@ impl crate::parser_trait::Parser for CParser {
     }
 
     fn parse(&self, path: &Path, content: &str) -> EngineResult<ParsedFile> {
-        let mut parser = self.parser.lock().unwrap();
-        
+        self.parse_incremental(path, content, None).map(|(parsed, _)| parsed)
+    }
+
+    fn parse_incremental(
+        &self,
+        path: &Path,
+        content: &str,
+        old_tree: Option<&Tree>,
+    ) -> EngineResult<(ParsedFile, Option<Tree>)> {
         debug!("Parsing C file: {:?}", path);
         
-        let tree = parser
-            .parse(content, None)
+        let tree = pool::parse("C", tree_sitter_c::language, content, old_tree)
             .ok_or_else(|| EngineError::Parse(ParseError::ParseFailed(format!("Failed to parse {:?}", path))))?;
         
         let mut cursor = tree.root_node().walk();
@@ -790,13 +892,150 @@ impl crate::parser_trait::Parser for CParser {
         // Calculate metrics
         let metrics = self.calculate_metrics(content, &nodes);
         
-        Ok(ParsedFile {
+        let mut parsed = ParsedFile {
             path: path.to_path_buf(),
             language: Language::C,
             nodes,
             relationships,
             metrics,
-        })
+        };
+        env_vars::apply(&mut parsed, env_vars::find_c(tree.root_node(), content));
+        sql::apply(&mut parsed, sql::find_c(tree.root_node(), content));
+        function_metrics::apply(&mut parsed, function_metrics::find_c(tree.root_node(), content));
+        docs::apply(&mut parsed, docs::find_c(tree.root_node(), content));
+        concurrency::apply(&mut parsed, concurrency::find_c(tree.root_node(), content));
+        drop(cursor);
+        Ok((parsed, Some(tree)))
+    }
+}
+
+/// Suffix the parser gives to prototype nodes
+const DECLARATION_SUFFIX: &str = " (declaration)";
+
+/// Function definition ids in one source file, by name
+type Definitions = HashMap<String, String>;
+
+/// Link prototypes in `foo.h` to the matching definitions in `foo.c`
+///
+/// A `.c` file in the header's own directory is preferred; otherwise a single
+/// `.c` file with the same stem anywhere in the project is used.
+pub fn link_declarations(files: &mut [ParsedFile]) {
+    let is_ext = |file: &ParsedFile, ext: &str| file.path.extension().is_some_and(|e| e == ext);
+    let stem = |path: &Path| path.file_stem().and_then(|s| s.to_str()).unwrap_or_default().to_string();
+
+    // Definitions per source file, grouped by file stem
+    let mut sources: HashMap<String, Vec<(PathBuf, Definitions)>> = HashMap::new();
+    for file in files.iter().filter(|file| is_ext(file, "c")) {
+        let definitions = file
+            .nodes
+            .iter()
+            .filter(|node| node.node_type == NodeType::Function && !node.name.ends_with(DECLARATION_SUFFIX))
+            .map(|node| (node.name.clone(), node.id.clone()))
+            .collect();
+        sources
+            .entry(stem(&file.path))
+            .or_default()
+            .push((file.path.parent().unwrap_or(Path::new("")).to_path_buf(), definitions));
+    }
+
+    for header in files.iter_mut().filter(|file| is_ext(file, "h")) {
+        let Some(candidates) = sources.get(&stem(&header.path)) else {
+            continue;
+        };
+        let dir = header.path.parent().unwrap_or(Path::new(""));
+        let definitions = match candidates.iter().find(|(source_dir, _)| source_dir == dir) {
+            Some((_, definitions)) => definitions,
+            None if candidates.len() == 1 => &candidates[0].1,
+            None => continue,
+        };
+
+        let links: Vec<Relationship> = header
+            .nodes
+            .iter()
+            .filter_map(|node| {
+                let name = node.name.strip_suffix(DECLARATION_SUFFIX)?;
+                Some(Relationship {
+                    source: node.id.clone(),
+                    target: definitions.get(name)?.clone(),
+                    relationship_type: RelationshipType::DefinedIn,
+                })
+            })
+            .collect();
+        header.relationships.extend(links);
+    }
+}
+
+/// Point C calls at the functions they name, expanding macros one level
+///
+/// Calls are first recorded against `function_<name>` placeholders. With
+/// `expand_macros`, a call to a function-like or alias macro is replaced by the
+/// calls in that macro's body. Placeholders resolve to a definition in the same
+/// file, or to the only definition of that name in the project.
+pub fn resolve_calls(files: &mut [ParsedFile], expand_macros: bool) {
+    let is_c = |file: &ParsedFile| matches!(file.language, Language::C);
+
+    let mut definitions: HashMap<String, Vec<(usize, String)>> = HashMap::new();
+    let mut macro_ids: HashMap<String, String> = HashMap::new();
+    for (index, file) in files.iter().enumerate().filter(|(_, file)| is_c(file)) {
+        for node in &file.nodes {
+            if let Some(name) = node.name.strip_prefix("#define ") {
+                macro_ids.insert(node.id.clone(), name.to_string());
+            } else if node.node_type == NodeType::Function && !node.name.ends_with(DECLARATION_SUFFIX) {
+                definitions.entry(node.name.clone()).or_default().push((index, node.id.clone()));
+            }
+        }
+    }
+
+    // Placeholders each macro's body calls, by macro name
+    let mut expansions: HashMap<String, Vec<String>> = HashMap::new();
+    for rel in files.iter().filter(|file| is_c(file)).flat_map(|file| &file.relationships) {
+        if let (RelationshipType::Calls, Some(name)) = (rel.relationship_type, macro_ids.get(&rel.source)) {
+            expansions.entry(name.clone()).or_default().push(rel.target.clone());
+        }
+    }
+
+    for (index, file) in files.iter_mut().enumerate().filter(|(_, file)| is_c(file)) {
+        let mut resolved = Vec::with_capacity(file.relationships.len());
+        for rel in file.relationships.drain(..) {
+            let name = match (rel.relationship_type, rel.target.strip_prefix("function_")) {
+                (RelationshipType::Calls, Some(name)) => name.to_string(),
+                _ => {
+                    resolved.push(rel);
+                    continue;
+                }
+            };
+
+            let targets = match expansions.get(&name) {
+                Some(body) if expand_macros && !definitions.contains_key(&name) && !macro_ids.contains_key(&rel.source) => {
+                    body.clone()
+                }
+                _ => vec![rel.target.clone()],
+            };
+
+            for target in targets {
+                let callee = target.strip_prefix("function_").unwrap_or(&target);
+                let target = match definitions.get(callee).map(Vec::as_slice) {
+                    Some([(_, id)]) => id.clone(),
+                    Some(candidates) => candidates
+                        .iter()
+                        .find(|(file_index, _)| *file_index == index)
+                        .map(|(_, id)| id.clone())
+                        .unwrap_or(target),
+                    None => target,
+                };
+                let duplicate = resolved.iter().any(|r: &Relationship| {
+                    r.source == rel.source && r.target == target && matches!(r.relationship_type, RelationshipType::Calls)
+                });
+                if !duplicate {
+                    resolved.push(Relationship {
+                        source: rel.source.clone(),
+                        target,
+                        relationship_type: RelationshipType::Calls,
+                    });
+                }
+            }
+        }
+        file.relationships = resolved;
     }
 }
 
@@ -917,4 +1156,70 @@ enum Status {
         assert!(include_names.contains(&"stdlib.h"));
         assert!(include_names.contains(&"myheader.h"));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_include_dirs_and_system_headers() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("include/lib")).unwrap();
+        std::fs::write(dir.path().join("include/lib/api.h"), "int api(void);\n").unwrap();
+
+        let parser = CParser::new().with_include_dirs(vec![dir.path().join("include")]);
+        let source = "#include <lib/api.h>\n#include <stdio.h>\n";
+        let path = dir.path().join("src/main.c");
+        let result = parser.parse(&path, source).unwrap();
+
+        let header = dir.path().join("include/lib/api.h");
+        let header_id = CParser::generate_unique_id(header.to_str().unwrap(), "file", 0);
+        let imports: Vec<&str> = result.relationships.iter()
+            .filter(|r| matches!(r.relationship_type, RelationshipType::Imports))
+            .map(|r| r.target.as_str())
+            .collect();
+        assert_eq!(imports, vec![header_id.as_str(), "system_header:stdio.h"]);
+
+        let system: Vec<_> = result.nodes.iter()
+            .filter(|n| n.node_type == NodeType::SystemHeader)
+            .collect();
+        assert_eq!(system.len(), 1);
+        assert_eq!(system[0].name, "stdio.h");
+    }
+
+    #[test]
+    fn test_link_declarations_to_definitions() {
+        let parser = CParser::new();
+        let mut files = vec![
+            parser.parse(Path::new("/repo/include/list.h"), "int list_len(void);\nvoid list_free(void);\n").unwrap(),
+            parser.parse(Path::new("/repo/src/list.c"), "int list_len(void) { return 0; }\n").unwrap(),
+        ];
+        link_declarations(&mut files);
+
+        let definition = files[1].nodes.iter().find(|n| n.name == "list_len").unwrap();
+        let links: Vec<&Relationship> = files[0].relationships.iter()
+            .filter(|r| matches!(r.relationship_type, RelationshipType::DefinedIn))
+            .collect();
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].target, definition.id);
+    }
+
+    #[test]
+    fn test_calls_through_macros() {
+        let parser = CParser::new();
+        let mut files = vec![
+            parser.parse(
+                Path::new("/repo/log.h"),
+                "#define LOG(msg) log_write(__FILE__, msg)\n#define FLUSH log_flush\n",
+            ).unwrap(),
+            parser.parse(
+                Path::new("/repo/log.c"),
+                "void log_write(const char *f, const char *m) {}\nvoid log_flush(void) {}\nvoid run(void) { LOG(\"x\"); FLUSH(); }\n",
+            ).unwrap(),
+        ];
+        resolve_calls(&mut files, true);
+
+        let id = |name: &str| files[1].nodes.iter().find(|n| n.name == name).unwrap().id.clone();
+        let calls: Vec<&str> = files[1].relationships.iter()
+            .filter(|r| r.source == id("run") && matches!(r.relationship_type, RelationshipType::Calls))
+            .map(|r| r.target.as_str())
+            .collect();
+        assert_eq!(calls, vec![id("log_write"), id("log_flush")]);
+    }
+}
@@ -0,0 +1,98 @@
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+use crate::shell::normalize;
+
+/// Locations checked for a compilation database, relative to the project root
+const DATABASE_PATHS: &[&str] = &["compile_commands.json", "build/compile_commands.json"];
+
+/// One entry of a clang compilation database
+#[derive(Debug, Deserialize)]
+struct CompileCommand {
+    directory: PathBuf,
+    #[serde(default)]
+    command: Option<String>,
+    #[serde(default)]
+    arguments: Option<Vec<String>>,
+}
+
+/// Include directories from the project's compile_commands.json, if it has one
+pub fn load_include_dirs(base_path: &Path) -> Vec<PathBuf> {
+    DATABASE_PATHS
+        .iter()
+        .map(|name| base_path.join(name))
+        .find_map(|path| std::fs::read_to_string(path).ok())
+        .map(|json| include_dirs(&json))
+        .unwrap_or_default()
+}
+
+/// `-I`, `-iquote` and `-isystem` directories in a compilation database, in first-seen order
+pub fn include_dirs(json: &str) -> Vec<PathBuf> {
+    let commands: Vec<CompileCommand> = match serde_json::from_str(json) {
+        Ok(commands) => commands,
+        Err(e) => {
+            tracing::warn!("Ignoring invalid compile_commands.json: {}", e);
+            return Vec::new();
+        }
+    };
+
+    let mut dirs = Vec::new();
+    for command in commands {
+        let args = command
+            .arguments
+            .unwrap_or_else(|| command.command.unwrap_or_default().split_whitespace().map(str::to_string).collect());
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let dir = ["-I", "-iquote", "-isystem"].iter().find_map(|flag| {
+                let rest = arg.strip_prefix(flag)?;
+                if rest.is_empty() {
+                    args.next().map(String::as_str)
+                } else {
+                    Some(rest)
+                }
+            });
+            if let Some(dir) = dir {
+                let dir = normalize(&command.directory.join(dir.trim_matches('"')));
+                if !dirs.contains(&dir) {
+                    dirs.push(dir);
+                }
+            }
+        }
+    }
+    dirs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_include_dirs_from_command_and_arguments() {
+        let json = r#"[
+            {"directory": "/repo/build", "command": "cc -I../include -isystem /opt/sdk -c ../src/a.c", "file": "../src/a.c"},
+            {"directory": "/repo", "arguments": ["cc", "-I", "include", "-iquote", "src/gen", "-c", "src/b.c"], "file": "src/b.c"}
+        ]"#;
+        assert_eq!(
+            include_dirs(json),
+            vec![
+                PathBuf::from("/repo/include"),
+                PathBuf::from("/opt/sdk"),
+                PathBuf::from("/repo/src/gen"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_load_include_dirs_from_build_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("build")).unwrap();
+        std::fs::write(
+            dir.path().join("build/compile_commands.json"),
+            r#"[{"directory": "/repo", "command": "cc -Iinc -c a.c", "file": "a.c"}]"#,
+        )
+        .unwrap();
+        assert_eq!(load_include_dirs(dir.path()), vec![PathBuf::from("/repo/inc")]);
+        assert!(load_include_dirs(&dir.path().join("missing")).is_empty());
+    }
+}
@@ -0,0 +1,200 @@
+use og_types::{ConcurrencyPrimitive, ParsedFile};
+use tree_sitter::Node;
+
+use crate::env_vars::{enclosing_function, text, walk};
+
+use ConcurrencyPrimitive::{Lock, Task, Thread};
+
+/// Callees that start or guard concurrent work in Python, matched on the
+/// last segments of the called name
+const PYTHON_CALLS: &[(&str, ConcurrencyPrimitive)] = &[
+    ("Thread", Thread),
+    ("start_new_thread", Thread),
+    ("Process", Thread),
+    ("ThreadPoolExecutor", Thread),
+    ("ProcessPoolExecutor", Thread),
+    ("multiprocessing.Pool", Thread),
+    ("run_in_executor", Thread),
+    ("asyncio.to_thread", Thread),
+    ("create_task", Task),
+    ("ensure_future", Task),
+    ("asyncio.gather", Task),
+    ("TaskGroup", Task),
+    ("run_coroutine_threadsafe", Task),
+    ("Lock", Lock),
+    ("RLock", Lock),
+    ("Semaphore", Lock),
+    ("BoundedSemaphore", Lock),
+    ("Condition", Lock),
+];
+
+/// Callees and constructors that start or guard concurrent work in
+/// JavaScript or TypeScript
+const JAVASCRIPT_CALLS: &[(&str, ConcurrencyPrimitive)] = &[
+    ("Worker", Thread),
+    ("SharedWorker", Thread),
+    ("cluster.fork", Thread),
+    ("child_process.fork", Thread),
+    ("Promise.all", Task),
+    ("Promise.allSettled", Task),
+    ("Promise.race", Task),
+    ("Promise.any", Task),
+    ("queueMicrotask", Task),
+    ("Atomics.wait", Lock),
+    ("Atomics.waitAsync", Lock),
+    ("Atomics.compareExchange", Lock),
+    ("locks.request", Lock),
+    ("Mutex", Lock),
+    ("Semaphore", Lock),
+    ("runExclusive", Lock),
+];
+
+/// Functions that start threads or take locks in C
+const C_CALLS: &[(&str, ConcurrencyPrimitive)] = &[
+    ("pthread_create", Thread),
+    ("thrd_create", Thread),
+    ("fork", Thread),
+    ("CreateThread", Thread),
+    ("_beginthreadex", Thread),
+    ("pthread_mutex_lock", Lock),
+    ("pthread_mutex_trylock", Lock),
+    ("pthread_rwlock_rdlock", Lock),
+    ("pthread_rwlock_wrlock", Lock),
+    ("pthread_spin_lock", Lock),
+    ("mtx_lock", Lock),
+    ("mtx_trylock", Lock),
+    ("sem_wait", Lock),
+    ("EnterCriticalSection", Lock),
+];
+
+/// A concurrency primitive used at a line of a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConcurrencyUse {
+    pub primitive: ConcurrencyPrimitive,
+    pub line: usize,
+}
+
+/// Primitive of the first known callee `callee` ends with, segment by segment
+fn primitive(callee: &str, known: &[(&str, ConcurrencyPrimitive)]) -> Option<ConcurrencyPrimitive> {
+    let callee = callee.strip_prefix("new ").unwrap_or(callee).trim();
+    known.iter().find_map(|(name, primitive)| {
+        let before = callee.strip_suffix(name)?;
+        (before.is_empty() || before.ends_with('.')).then_some(*primitive)
+    })
+}
+
+/// Uses of `known` callees by the `calls` node kinds, and by constructors
+fn find(root: Node, source: &str, calls: &[&str], known: &[(&str, ConcurrencyPrimitive)]) -> Vec<ConcurrencyUse> {
+    let mut uses = Vec::new();
+    walk(root, &mut |node| {
+        if !calls.contains(&node.kind()) {
+            return;
+        }
+        let field = if node.kind() == "new_expression" { "constructor" } else { "function" };
+        if let Some(primitive) = text(node.child_by_field_name(field), source).and_then(|callee| primitive(callee, known)) {
+            uses.push(ConcurrencyUse {
+                primitive,
+                line: node.start_position().row + 1,
+            });
+        }
+    });
+    uses
+}
+
+/// Threads, processes, asyncio tasks and locks in Python
+pub fn find_python(root: Node, source: &str) -> Vec<ConcurrencyUse> {
+    find(root, source, &["call"], PYTHON_CALLS)
+}
+
+/// Workers, forked processes, concurrent promises and locks in JavaScript
+/// or TypeScript
+pub fn find_javascript(root: Node, source: &str) -> Vec<ConcurrencyUse> {
+    find(root, source, &["call_expression", "new_expression"], JAVASCRIPT_CALLS)
+}
+
+/// Threads, forked processes and lock calls in C
+pub fn find_c(root: Node, source: &str) -> Vec<ConcurrencyUse> {
+    find(root, source, &["call_expression"], C_CALLS)
+}
+
+/// Add each use to the concurrency profile of the innermost function
+/// around it, or of the file when it is at the top level
+pub fn apply(parsed: &mut ParsedFile, uses: Vec<ConcurrencyUse>) {
+    for found in uses {
+        let Some(id) = enclosing_function(parsed, found.line) else {
+            continue;
+        };
+        parsed.metrics.concurrency.entry(id).or_default().add(found.primitive);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tree_sitter::Parser as TSParser;
+
+    fn primitives(uses: &[ConcurrencyUse]) -> Vec<(ConcurrencyPrimitive, usize)> {
+        uses.iter().map(|u| (u.primitive, u.line)).collect()
+    }
+
+    #[cfg(feature = "python")]
+    #[test]
+    fn test_find_python_concurrency() {
+        let source = "import asyncio, threading\nlock = threading.Lock()\nasync def main():\n    task = asyncio.create_task(work())\n    await asyncio.gather(task)\n    threading.Thread(target=run).start()\n    pool.Process()\n    Locker()\n";
+        let mut parser = TSParser::new();
+        parser.set_language(tree_sitter_python::language()).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+
+        assert_eq!(
+            primitives(&find_python(tree.root_node(), source)),
+            vec![(Lock, 2), (Task, 4), (Task, 5), (Thread, 6), (Thread, 7)]
+        );
+    }
+
+    #[cfg(feature = "js")]
+    #[test]
+    fn test_find_javascript_concurrency() {
+        let source = "const { Worker } = require('worker_threads');\nconst w = new Worker('./job.js');\nawait Promise.all(jobs);\nconst m = new Mutex();\nPromise.resolve(1);\n";
+        let mut parser = TSParser::new();
+        parser.set_language(tree_sitter_javascript::language()).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+
+        assert_eq!(
+            primitives(&find_javascript(tree.root_node(), source)),
+            vec![(Thread, 2), (Task, 3), (Lock, 4)]
+        );
+    }
+
+    #[cfg(feature = "c")]
+    #[test]
+    fn test_c_concurrency_profiles() {
+        let source = "void *run(void *arg) {\n  pthread_mutex_lock(&m);\n  pthread_mutex_unlock(&m);\n  return 0;\n}\nint main(void) {\n  pthread_create(&t, 0, run, 0);\n  pthread_create(&u, 0, run, 0);\n}\n";
+        let mut parser = TSParser::new();
+        parser.set_language(tree_sitter_c::language()).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+
+        let node = |id: &str, node_type, start_line, end_line| og_types::AstNode {
+            id: id.to_string(),
+            node_type,
+            name: id.to_string(),
+            start_line,
+            end_line,
+            children: vec![],
+        };
+        let mut parsed = ParsedFile {
+            path: "main.c".into(),
+            language: og_types::Language::C,
+            nodes: vec![
+                node("file:main.c", og_types::NodeType::File, 1, 9),
+                node("function:run", og_types::NodeType::Function, 1, 5),
+                node("function:main", og_types::NodeType::Function, 6, 9),
+            ],
+            relationships: vec![],
+            metrics: Default::default(),
+        };
+        apply(&mut parsed, find_c(tree.root_node(), source));
+        let profile = |id: &str| parsed.metrics.concurrency[id];
+        assert_eq!((profile("function:run").locks, profile("function:run").threads), (1, 0));
+        assert_eq!(profile("function:main").threads, 2);
+    }
+}
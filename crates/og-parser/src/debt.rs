@@ -0,0 +1,139 @@
+use og_types::{DebtMarker, NodeType, ParsedFile, DEBT_KINDS};
+
+/// Comment openers a marker may follow, across supported languages
+const COMMENT_OPENERS: &[&str] = &["//", "#", "/*", "*", "--", "<!--"];
+
+/// A marker comment found in a source file, before it is attached to a node
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Marker {
+    pub kind: &'static str,
+    pub text: String,
+    /// 1-based line of the comment
+    pub line: usize,
+}
+
+/// The marker word starting `rest`, when it is a whole word
+fn marker_kind(rest: &str) -> Option<&'static str> {
+    DEBT_KINDS.iter().copied().find(|kind| {
+        rest.strip_prefix(kind)
+            .is_some_and(|after| !after.starts_with(|c: char| c.is_alphanumeric() || c == '_'))
+    })
+}
+
+/// TODO, FIXME, HACK and XXX comments in a source file
+///
+/// The marker must be the first word of the comment, in capitals, so
+/// identifiers and prose that mention a TODO are not counted.
+pub fn markers(source: &str) -> Vec<Marker> {
+    source
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let (pos, kind) = line
+                .char_indices()
+                .filter(|(pos, _)| {
+                    let before = line[..*pos].trim_end();
+                    COMMENT_OPENERS.iter().any(|opener| before.ends_with(opener))
+                })
+                .find_map(|(pos, _)| marker_kind(&line[pos..]).map(|kind| (pos, kind)))?;
+            let text = &line[pos + kind.len()..];
+            let text = text.split("*/").next().unwrap_or(text);
+            let text = text.split("-->").next().unwrap_or(text);
+            Some(Marker {
+                kind,
+                text: text.trim_start_matches([':', '-', ' ', '\t']).trim_end().to_string(),
+                line: index + 1,
+            })
+        })
+        .collect()
+}
+
+/// Attach each marker to the innermost node spanning its line
+///
+/// A marker outside every declaration goes to the file node.
+pub fn attach(parsed: &ParsedFile, markers: Vec<Marker>) -> Vec<DebtMarker> {
+    let file_path = parsed.path.display().to_string();
+    let file_node = parsed
+        .nodes
+        .iter()
+        .find(|node| node.node_type == NodeType::File)
+        .or_else(|| parsed.nodes.first());
+    markers
+        .into_iter()
+        .filter_map(|marker| {
+            let node = parsed
+                .nodes
+                .iter()
+                .filter(|node| {
+                    node.node_type != NodeType::File
+                        && node.start_line <= marker.line
+                        && marker.line <= node.end_line
+                })
+                .min_by_key(|node| node.end_line.saturating_sub(node.start_line))
+                .or(file_node)?;
+            Some(DebtMarker {
+                kind: marker.kind.to_string(),
+                text: marker.text,
+                file_path: file_path.clone(),
+                line: marker.line,
+                node_id: node.id.clone(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use og_types::{AstNode, Language};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_markers_in_comments() {
+        let source = "# TODO: split this module\n\
+                      todo_list = []  # FIXME(ana) handle empty\n\
+                      TODO = 'not a comment'\n\
+                      // TODOS are fine\n\
+                      /* HACK */\n\
+                      <!-- XXX - remove -->\n";
+        let found = markers(source);
+        let found: Vec<(&str, &str, usize)> = found.iter().map(|m| (m.kind, m.text.as_str(), m.line)).collect();
+        assert_eq!(
+            found,
+            vec![
+                ("TODO", "split this module", 1),
+                ("FIXME", "(ana) handle empty", 2),
+                ("HACK", "", 5),
+                ("XXX", "remove", 6),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_attach_to_innermost_node() {
+        let node = |id: &str, node_type, start_line, end_line| AstNode {
+            id: id.to_string(),
+            node_type,
+            name: id.to_string(),
+            start_line,
+            end_line,
+            children: vec![],
+        };
+        let parsed = ParsedFile {
+            path: PathBuf::from("app.py"),
+            language: Language::Python,
+            nodes: vec![
+                node("file:app.py", NodeType::File, 1, 30),
+                node("class:Store", NodeType::Class, 3, 20),
+                node("method:Store.save", NodeType::Method, 5, 10),
+            ],
+            relationships: vec![],
+            metrics: Default::default(),
+        };
+
+        let attached = attach(&parsed, markers("# TODO: a\n\n\n\n\n\n# FIXME: b\n\n\n\n\n# HACK: c\n"));
+        let nodes: Vec<(&str, usize)> = attached.iter().map(|m| (m.node_id.as_str(), m.line)).collect();
+        assert_eq!(nodes, vec![("file:app.py", 1), ("method:Store.save", 7), ("class:Store", 12)]);
+        assert_eq!(attached[0].file_path, "app.py");
+    }
+}
@@ -0,0 +1,385 @@
+use og_types::{AstNode, EngineError, EngineResult, Language, NodeType, ParsedFile, Relationship, RelationshipType};
+use serde_yaml::Value;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use crate::parser_trait::Parser;
+use crate::shell::{invocation_relationship, invocations, normalize, Invocation};
+
+/// File names recognised as docker-compose files
+const COMPOSE_NAMES: &[&str] = &["docker-compose.yml", "docker-compose.yaml", "compose.yml", "compose.yaml"];
+
+fn container_id(dockerfile: &Path) -> String {
+    format!("container:{}", dockerfile.display())
+}
+
+fn directory_id(dir: &Path) -> String {
+    format!("dir:{}", dir.display())
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().and_then(|n| n.to_str()).unwrap_or("unknown").to_string()
+}
+
+fn directory_node(dir: &Path) -> AstNode {
+    AstNode {
+        id: directory_id(dir),
+        name: file_name(dir),
+        node_type: NodeType::Module,
+        start_line: 0,
+        end_line: 0,
+        children: vec![],
+    }
+}
+
+fn relationship(source: &str, target: String, relationship_type: RelationshipType) -> Relationship {
+    Relationship {
+        source: source.to_string(),
+        target,
+        relationship_type,
+    }
+}
+
+/// Parser for Dockerfiles producing a container node linked to the code it copies
+pub struct DockerfileParser;
+
+impl DockerfileParser {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for DockerfileParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Dockerfile instructions with line continuations joined
+fn instructions(content: &str) -> Vec<(usize, String, String)> {
+    let mut instructions = Vec::new();
+    let mut pending: Option<(usize, String)> = None;
+
+    for (i, line) in content.lines().enumerate() {
+        let trimmed = line.trim();
+        if pending.is_none() && (trimmed.is_empty() || trimmed.starts_with('#')) {
+            continue;
+        }
+        let (start, mut text) = pending.take().unwrap_or((i + 1, String::new()));
+        match trimmed.strip_suffix('\\') {
+            Some(continued) => {
+                text.push_str(continued);
+                text.push(' ');
+                pending = Some((start, text));
+            }
+            None => {
+                text.push_str(trimmed);
+                if let Some((keyword, args)) = text.split_once(char::is_whitespace) {
+                    instructions.push((start, keyword.to_uppercase(), args.trim().to_string()));
+                }
+            }
+        }
+    }
+
+    instructions
+}
+
+/// Arguments in either exec form (`["a", "b"]`) or shell form
+fn arguments(args: &str) -> Vec<String> {
+    match serde_json::from_str::<Vec<String>>(args) {
+        Ok(list) => list,
+        Err(_) => args.split_whitespace().map(str::to_string).collect(),
+    }
+}
+
+impl Parser for DockerfileParser {
+    fn supported_extensions(&self) -> &[&str] {
+        &[".dockerfile"]
+    }
+
+    fn language(&self) -> Language {
+        Language::Docker
+    }
+
+    fn can_parse(&self, path: &Path) -> bool {
+        let name = file_name(path);
+        name == "Dockerfile" || name.starts_with("Dockerfile.") || name.ends_with(".dockerfile")
+    }
+
+    fn parse(&self, path: &Path, content: &str) -> EngineResult<ParsedFile> {
+        let context = path.parent().unwrap_or(Path::new("")).to_path_buf();
+        let id = container_id(path);
+        let dir_name = file_name(&context);
+        let name = match file_name(path).strip_prefix("Dockerfile.") {
+            Some(variant) => format!("{}:{}", dir_name, variant),
+            None => dir_name,
+        };
+
+        let mut nodes = vec![AstNode {
+            id: id.clone(),
+            name,
+            node_type: NodeType::Container,
+            start_line: 0,
+            end_line: content.lines().count(),
+            children: vec![],
+        }];
+        let mut relationships = Vec::new();
+        let mut directories = HashSet::new();
+
+        // Host paths copied into the image, keyed by their path inside it
+        let mut copies: Vec<(PathBuf, PathBuf)> = Vec::new();
+        let mut workdir = PathBuf::from("/");
+
+        for (_, keyword, args) in instructions(content) {
+            match keyword.as_str() {
+                "WORKDIR" => workdir = normalize(&workdir.join(args)),
+                "COPY" | "ADD" => {
+                    let args = arguments(&args);
+                    // Files copied from another build stage are not in the repository
+                    if args.iter().any(|a| a.starts_with("--from")) {
+                        continue;
+                    }
+                    let args: Vec<&String> = args.iter().filter(|a| !a.starts_with("--")).collect();
+                    let Some((dest, sources)) = args.split_last() else {
+                        continue;
+                    };
+                    let dest = normalize(&workdir.join(dest));
+                    for source in sources.iter().filter(|s| !s.contains("://")) {
+                        let host = normalize(&context.join(source));
+                        let is_file = Path::new(source.as_str()).extension().is_some();
+                        let target = if is_file {
+                            format!("file:{}", host.display())
+                        } else {
+                            if directories.insert(host.clone()) {
+                                nodes.push(directory_node(&host));
+                            }
+                            directory_id(&host)
+                        };
+                        relationships.push(relationship(&id, target, RelationshipType::References));
+                        copies.push((host, dest.clone()));
+                    }
+                }
+                "ENTRYPOINT" | "CMD" => {
+                    let command = arguments(&args).join(" ");
+                    for invocation in invocations(&command, &workdir) {
+                        // Map the in-image path back to the host file that was copied there
+                        let Invocation::Script(in_image) = invocation else {
+                            continue;
+                        };
+                        let host = copies.iter().rev().find_map(|(host, dest)| {
+                            in_image.strip_prefix(dest).ok().map(|rest| {
+                                if rest.as_os_str().is_empty() {
+                                    host.clone()
+                                } else {
+                                    host.join(rest)
+                                }
+                            })
+                        });
+                        if let Some(host) = host {
+                            relationships.push(invocation_relationship(&id, &Invocation::Script(host)));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(ParsedFile {
+            path: path.to_path_buf(),
+            language: Language::Docker,
+            nodes,
+            relationships,
+            metrics: Default::default(),
+        })
+    }
+}
+
+/// Parser for docker-compose files producing service nodes
+pub struct ComposeParser;
+
+impl ComposeParser {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for ComposeParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Strings of a compose list, or the keys of its long-form mapping
+fn string_list(value: Option<&Value>) -> Vec<String> {
+    match value {
+        Some(Value::Sequence(items)) => items.iter().filter_map(|v| v.as_str().map(str::to_string)).collect(),
+        Some(Value::Mapping(map)) => map.keys().filter_map(|k| k.as_str().map(str::to_string)).collect(),
+        _ => Vec::new(),
+    }
+}
+
+impl Parser for ComposeParser {
+    fn supported_extensions(&self) -> &[&str] {
+        &[]
+    }
+
+    fn language(&self) -> Language {
+        Language::Docker
+    }
+
+    fn can_parse(&self, path: &Path) -> bool {
+        COMPOSE_NAMES.contains(&file_name(path).as_str())
+    }
+
+    fn parse(&self, path: &Path, content: &str) -> EngineResult<ParsedFile> {
+        let compose: Value = serde_yaml::from_str(content).map_err(|e| EngineError::ParseError {
+            file: path.display().to_string(),
+            message: e.to_string(),
+        })?;
+        let base = path.parent().unwrap_or(Path::new(""));
+        let file_id = format!("file:{}", path.display());
+        let service_id = |name: &str| format!("service:{}:{}", path.display(), name);
+
+        let mut nodes = vec![AstNode {
+            id: file_id.clone(),
+            name: file_name(path),
+            node_type: NodeType::File,
+            start_line: 0,
+            end_line: content.lines().count(),
+            children: vec![],
+        }];
+        let mut relationships = Vec::new();
+        let mut directories = HashSet::new();
+
+        let services = compose.get("services").and_then(Value::as_mapping);
+        for (name, service) in services.into_iter().flatten() {
+            let Some(name) = name.as_str() else {
+                continue;
+            };
+            let id = service_id(name);
+            nodes.push(AstNode {
+                id: id.clone(),
+                name: name.to_string(),
+                node_type: NodeType::Service,
+                start_line: 0,
+                end_line: 0,
+                children: vec![],
+            });
+            relationships.push(relationship(&file_id, id.clone(), RelationshipType::Contains));
+
+            let (context, dockerfile) = match service.get("build") {
+                Some(Value::String(context)) => (Some(context.as_str()), None),
+                Some(build) => (
+                    build.get("context").and_then(Value::as_str),
+                    build.get("dockerfile").and_then(Value::as_str),
+                ),
+                None => (None, None),
+            };
+            if let Some(context) = context {
+                let dockerfile = normalize(&base.join(context).join(dockerfile.unwrap_or("Dockerfile")));
+                relationships.push(relationship(&id, container_id(&dockerfile), RelationshipType::Contains));
+            }
+
+            for dependency in string_list(service.get("depends_on")) {
+                relationships.push(relationship(&id, service_id(&dependency), RelationshipType::DependsOn));
+            }
+
+            // Bind mounts of repository directories, e.g. `./src:/app/src`
+            let volumes = service.get("volumes").and_then(Value::as_sequence);
+            for volume in volumes.into_iter().flatten() {
+                let source = match volume {
+                    Value::String(spec) => spec.split(':').next(),
+                    other => other.get("source").and_then(Value::as_str),
+                };
+                let Some(source) = source.filter(|s| s.starts_with('.') || s.starts_with('/')) else {
+                    continue;
+                };
+                let host = normalize(&base.join(source));
+                if directories.insert(host.clone()) {
+                    nodes.push(directory_node(&host));
+                }
+                relationships.push(relationship(&id, directory_id(&host), RelationshipType::References));
+            }
+        }
+
+        Ok(ParsedFile {
+            path: path.to_path_buf(),
+            language: Language::Docker,
+            nodes,
+            relationships,
+            metrics: Default::default(),
+        })
+    }
+}
+
+/// Connect directory nodes to the parsed files directly inside them
+pub fn link_directories(files: &mut [ParsedFile]) {
+    let directories: HashSet<String> = files
+        .iter()
+        .flat_map(|file| &file.nodes)
+        .filter(|node| node.id.starts_with("dir:"))
+        .map(|node| node.id.clone())
+        .collect();
+    if directories.is_empty() {
+        return;
+    }
+
+    for file in files.iter_mut() {
+        let Some(parent) = file.path.parent() else {
+            continue;
+        };
+        let dir = directory_id(parent);
+        if directories.contains(&dir) {
+            let target = format!("file:{}", file.path.display());
+            file.relationships.push(relationship(&dir, target, RelationshipType::Contains));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn targets(parsed: &ParsedFile, relationship_type: RelationshipType) -> Vec<&str> {
+        parsed
+            .relationships
+            .iter()
+            .filter(|r| r.relationship_type.as_str() == relationship_type.as_str())
+            .map(|r| r.target.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_dockerfile_copies_and_entrypoint() {
+        let dockerfile = "FROM python:3.12 AS base\nWORKDIR /app\nCOPY requirements.txt .\nCOPY src/ ./src/\nCOPY --from=base /x /y\nENTRYPOINT [\"python\", \"src/main.py\"]\n";
+        let parsed = DockerfileParser::new().parse(Path::new("/repo/api/Dockerfile"), dockerfile).unwrap();
+
+        assert_eq!(parsed.nodes[0].node_type, NodeType::Container);
+        assert_eq!(parsed.nodes[0].name, "api");
+        assert_eq!(
+            targets(&parsed, RelationshipType::References),
+            vec!["file:/repo/api/requirements.txt", "dir:/repo/api/src"]
+        );
+        assert_eq!(targets(&parsed, RelationshipType::Calls), vec!["file:/repo/api/src/main.py"]);
+    }
+
+    #[test]
+    fn test_compose_services() {
+        let compose = "services:\n  api:\n    build: ./api\n    depends_on: [db]\n    volumes:\n      - ./api/src:/app/src\n      - data:/var/lib\n  worker:\n    build:\n      context: .\n      dockerfile: Dockerfile.worker\n  db:\n    image: postgres\n";
+        let parsed = ComposeParser::new().parse(Path::new("/repo/docker-compose.yml"), compose).unwrap();
+
+        let services: Vec<&str> = parsed.nodes.iter()
+            .filter(|n| n.node_type == NodeType::Service)
+            .map(|n| n.name.as_str())
+            .collect();
+        assert_eq!(services, vec!["api", "worker", "db"]);
+        assert_eq!(
+            targets(&parsed, RelationshipType::DependsOn),
+            vec!["service:/repo/docker-compose.yml:db"]
+        );
+        let contains = targets(&parsed, RelationshipType::Contains);
+        assert!(contains.contains(&"container:/repo/api/Dockerfile"));
+        assert!(contains.contains(&"container:/repo/Dockerfile.worker"));
+        assert_eq!(targets(&parsed, RelationshipType::References), vec!["dir:/repo/api/src"]);
+    }
+}
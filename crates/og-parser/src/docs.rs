@@ -0,0 +1,300 @@
+use og_types::{CommentDensity, NodeType, ParsedFile};
+use std::collections::BTreeSet;
+use tree_sitter::{Node, Range};
+
+use crate::env_vars::{text, walk};
+
+/// A public function, method or type declared at a line of a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicSymbol {
+    pub line: usize,
+    /// Preceded by a doc comment, or opened by a docstring
+    pub documented: bool,
+}
+
+/// Comments of a file and the public symbols it declares
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Documentation {
+    pub comments: CommentDensity,
+    pub symbols: Vec<PublicSymbol>,
+}
+
+/// Count comment lines, and the non-blank lines that are more than a comment
+///
+/// `comments` are the spans of the comments and docstrings. Only the rows
+/// `counted` accepts are counted.
+fn density(source: &str, comments: &[Range], counted: impl Fn(usize) -> bool) -> CommentDensity {
+    let lines: Vec<&str> = source.lines().collect();
+    let mut commented = BTreeSet::new();
+    let mut comment_only = BTreeSet::new();
+    for comment in comments {
+        let (start, end) = (comment.start_point, comment.end_point);
+        for row in (start.row..=end.row).filter(|row| counted(*row)) {
+            commented.insert(row);
+            let indent = lines.get(row).map_or(0, |line| line.len() - line.trim_start().len());
+            let after_code = row == start.row && start.column > indent;
+            let before_code = row == end.row && lines.get(row).is_some_and(|line| !line[end.column.min(line.len())..].trim().is_empty());
+            if !after_code && !before_code {
+                comment_only.insert(row);
+            }
+        }
+    }
+    CommentDensity {
+        comment_lines: commented.len(),
+        code_lines: lines
+            .iter()
+            .enumerate()
+            .filter(|(row, line)| counted(*row) && !line.trim().is_empty() && !comment_only.contains(row))
+            .count(),
+    }
+}
+
+/// Whether a comment ends on the line before `node` starts
+fn doc_comment_before(node: Node, source: &str, is_doc: impl Fn(&str) -> bool) -> bool {
+    node.prev_named_sibling().is_some_and(|comment| {
+        comment.kind() == "comment"
+            && comment.end_position().row + 1 >= node.start_position().row
+            && text(Some(comment), source).is_some_and(&is_doc)
+    })
+}
+
+/// String opening a Python module, class or function body
+fn docstring(body: Node) -> Option<Node> {
+    let first = body.named_child(0).filter(|n| n.kind() == "expression_statement")?;
+    first.named_child(0).filter(|n| n.kind() == "string").map(|_| first)
+}
+
+/// Top-level and class-level definitions whose names do not start with `_`
+pub fn find_python(root: Node, source: &str) -> Documentation {
+    let mut comments = Vec::new();
+    let mut symbols = Vec::new();
+    comments.extend(docstring(root).map(|n| n.range()));
+    python_symbols(root, source, &mut comments, &mut symbols);
+    Documentation {
+        comments: density(source, &comments, |_| true),
+        symbols,
+    }
+}
+
+/// [`find_python`] for some top-level items of a file, counting only their lines
+pub fn find_python_items(items: &[Node], source: &str) -> Documentation {
+    let mut comments = Vec::new();
+    let mut symbols = Vec::new();
+    for item in items {
+        python_symbols(*item, source, &mut comments, &mut symbols);
+    }
+    let rows: Vec<(usize, usize)> = items.iter().map(|item| (item.start_position().row, item.end_position().row)).collect();
+    Documentation {
+        comments: density(source, &comments, |row| rows.iter().any(|(start, end)| (*start..=*end).contains(&row))),
+        symbols,
+    }
+}
+
+fn python_symbols(root: Node, source: &str, comments: &mut Vec<Range>, symbols: &mut Vec<PublicSymbol>) {
+    walk(root, &mut |node| match node.kind() {
+        "comment" => comments.push(node.range()),
+        "function_definition" | "class_definition" => {
+            let body = node.child_by_field_name("body");
+            let docs = body.and_then(docstring);
+            comments.extend(docs.map(|n| n.range()));
+
+            let mut public = text(node.child_by_field_name("name"), source).is_some_and(|name| !name.starts_with('_'));
+            let mut ancestor = node.parent();
+            while let Some(parent) = ancestor {
+                match parent.kind() {
+                    "function_definition" | "lambda" => public = false,
+                    "class_definition" => {
+                        public &= text(parent.child_by_field_name("name"), source).is_some_and(|name| !name.starts_with('_'));
+                    }
+                    _ => {}
+                }
+                ancestor = parent.parent();
+            }
+            if public {
+                symbols.push(PublicSymbol {
+                    line: node.start_position().row + 1,
+                    documented: docs.is_some(),
+                });
+            }
+        }
+        _ => {}
+    });
+}
+
+/// Exported declarations, and the public methods of exported classes, in
+/// JavaScript or TypeScript, documented by a `/** ... */` comment
+pub fn find_javascript(root: Node, source: &str) -> Documentation {
+    let is_jsdoc = |comment: &str| comment.starts_with("/**");
+    let mut comments = Vec::new();
+    let mut symbols = Vec::new();
+    walk(root, &mut |node| match node.kind() {
+        "comment" => comments.push(node.range()),
+        "export_statement" => {
+            let Some(declaration) = node.child_by_field_name("declaration") else {
+                return;
+            };
+            symbols.push(PublicSymbol {
+                line: declaration.start_position().row + 1,
+                documented: doc_comment_before(node, source, is_jsdoc),
+            });
+            let Some(body) = declaration.child_by_field_name("body").filter(|b| b.kind() == "class_body") else {
+                return;
+            };
+            let mut cursor = body.walk();
+            for member in body.named_children(&mut cursor) {
+                if member.kind() != "method_definition" {
+                    continue;
+                }
+                let hidden = text(member.child_by_field_name("name"), source)
+                    .is_none_or(|name| name.starts_with('#') || name.starts_with('_') || name == "constructor");
+                let mut modifiers = member.walk();
+                let restricted = member
+                    .children(&mut modifiers)
+                    .any(|m| m.kind() == "accessibility_modifier" && text(Some(m), source) != Some("public"));
+                if !hidden && !restricted {
+                    symbols.push(PublicSymbol {
+                        line: member.start_position().row + 1,
+                        documented: doc_comment_before(member, source, is_jsdoc),
+                    });
+                }
+            }
+        }
+        _ => {}
+    });
+    Documentation {
+        comments: density(source, &comments, |_| true),
+        symbols,
+    }
+}
+
+/// Whether a C declaration or definition is `static`
+fn is_static(node: Node, source: &str) -> bool {
+    let mut cursor = node.walk();
+    let is_static = node
+        .children(&mut cursor)
+        .any(|child| child.kind() == "storage_class_specifier" && text(Some(child), source) == Some("static"));
+    is_static
+}
+
+/// Whether a C declarator declares a function
+fn declares_function(node: Node) -> bool {
+    let mut declarator = node.child_by_field_name("declarator");
+    while let Some(d) = declarator {
+        if d.kind() == "function_declarator" {
+            return true;
+        }
+        declarator = d.child_by_field_name("declarator");
+    }
+    false
+}
+
+/// Functions that are not `static`, defined or declared at the top level,
+/// documented by any comment on the lines just above
+pub fn find_c(root: Node, source: &str) -> Documentation {
+    let mut comments = Vec::new();
+    walk(root, &mut |node| {
+        if node.kind() == "comment" {
+            comments.push(node.range());
+        }
+    });
+
+    let mut symbols = Vec::new();
+    let mut cursor = root.walk();
+    for node in root.named_children(&mut cursor) {
+        if matches!(node.kind(), "function_definition" | "declaration") && declares_function(node) && !is_static(node, source) {
+            symbols.push(PublicSymbol {
+                line: node.start_position().row + 1,
+                documented: doc_comment_before(node, source, |_| true),
+            });
+        }
+    }
+    Documentation {
+        comments: density(source, &comments, |_| true),
+        symbols,
+    }
+}
+
+/// Record the file's comment density, and whether each public function,
+/// method or type node starting on a public symbol's line is documented
+pub fn apply(parsed: &mut ParsedFile, docs: Documentation) {
+    parsed.metrics.comments = docs.comments;
+    for symbol in docs.symbols {
+        let node = parsed.nodes.iter().find(|node| {
+            node.start_line == symbol.line
+                && matches!(
+                    node.node_type,
+                    NodeType::Function | NodeType::Method | NodeType::Class | NodeType::Interface | NodeType::TypeAlias | NodeType::Enum
+                )
+        });
+        if let Some(node) = node {
+            parsed.metrics.documented.insert(node.id.clone(), symbol.documented);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tree_sitter::Parser as TSParser;
+
+    #[cfg(feature = "python")]
+    #[test]
+    fn test_python_docstrings() {
+        let source = "\"\"\"Storage helpers.\"\"\"\n\n# Default timeout\nTIMEOUT = 5  # seconds\n\n\ndef save(key):\n    \"\"\"Save a key.\"\"\"\n    def inner():\n        pass\n    return key\n\n\nclass Store:\n    def get(self):\n        return 1\n\n    def _hidden(self):\n        pass\n\n\ndef _private():\n    pass\n";
+        let mut parser = TSParser::new();
+        parser.set_language(tree_sitter_python::language()).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+
+        let docs = find_python(tree.root_node(), source);
+        let symbols: Vec<(usize, bool)> = docs.symbols.iter().map(|s| (s.line, s.documented)).collect();
+        assert_eq!(symbols, vec![(7, true), (14, false), (15, false)]);
+        assert_eq!(docs.comments.comment_lines, 4);
+        assert_eq!(docs.comments.code_lines, 12);
+    }
+
+    #[cfg(feature = "js")]
+    #[test]
+    fn test_javascript_jsdoc() {
+        let source = "/** Adds numbers. */\nexport function add(a, b) {\n  return a + b; // sum\n}\n\n// Not a doc comment\nexport class Box {\n  /** Opens it. */\n  open() {}\n  close() {}\n  #seal() {}\n}\n\nfunction local() {}\n";
+        let mut parser = TSParser::new();
+        parser.set_language(tree_sitter_javascript::language()).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+
+        let docs = find_javascript(tree.root_node(), source);
+        let symbols: Vec<(usize, bool)> = docs.symbols.iter().map(|s| (s.line, s.documented)).collect();
+        assert_eq!(symbols, vec![(2, true), (7, false), (9, true), (10, false)]);
+        assert_eq!(docs.comments, CommentDensity { comment_lines: 4, code_lines: 9 });
+    }
+
+    #[cfg(feature = "c")]
+    #[test]
+    fn test_c_doc_comments_link_nodes() {
+        let source = "/* Parse a config file. */\nint parse(const char *path);\n\nstatic int helper(void) { return 0; }\n\nint run(void) {\n  return helper();\n}\n";
+        let mut parser = TSParser::new();
+        parser.set_language(tree_sitter_c::language()).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let docs = find_c(tree.root_node(), source);
+        let symbols: Vec<(usize, bool)> = docs.symbols.iter().map(|s| (s.line, s.documented)).collect();
+        assert_eq!(symbols, vec![(2, true), (6, false)]);
+
+        let node = |id: &str, node_type, start_line| og_types::AstNode {
+            id: id.to_string(),
+            node_type,
+            name: id.to_string(),
+            start_line,
+            end_line: start_line,
+            children: vec![],
+        };
+        let mut parsed = ParsedFile {
+            path: "main.c".into(),
+            language: og_types::Language::C,
+            nodes: vec![node("file:main.c", NodeType::File, 1), node("function:run", NodeType::Function, 6)],
+            relationships: vec![],
+            metrics: Default::default(),
+        };
+        apply(&mut parsed, docs);
+        assert_eq!(parsed.metrics.documented.len(), 1);
+        assert!(!parsed.metrics.documented["function:run"]);
+        assert_eq!(parsed.metrics.comments.comment_lines, 1);
+    }
+}
@@ -0,0 +1,161 @@
+use og_types::{ParsedFile, Relationship, RelationshipType};
+use tree_sitter::Node;
+
+/// Python calls that import a module named at runtime
+const PYTHON_LOADERS: &[&str] = &["importlib.import_module", "import_module", "__import__"];
+
+/// Specifier passed to a dynamic import
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DynamicImport {
+    /// A string literal that can be resolved like a static import
+    Constant(String),
+    /// A computed specifier the static graph cannot follow
+    Unresolved,
+}
+
+/// Contents of a string literal without interpolation
+pub(crate) fn string_literal(node: Node, source: &str) -> Option<String> {
+    let mut cursor = node.walk();
+    let interpolated = node
+        .named_children(&mut cursor)
+        .any(|child| matches!(child.kind(), "template_substitution" | "interpolation"));
+    if !matches!(node.kind(), "string" | "template_string" | "string_literal") || interpolated {
+        return None;
+    }
+
+    let text = node.utf8_text(source.as_bytes()).ok()?;
+    let text = text.trim_start_matches(|c: char| c.is_ascii_alphabetic());
+    Some(text.trim_matches(|c| c == '"' || c == '\'' || c == '`').to_string())
+}
+
+/// Specifier of a string literal argument, or Unresolved if it is computed
+fn specifier(argument: Option<Node>, source: &str) -> DynamicImport {
+    argument
+        .and_then(|argument| string_literal(argument, source))
+        .map_or(DynamicImport::Unresolved, DynamicImport::Constant)
+}
+
+pub(crate) fn first_argument(call: Node) -> Option<Node> {
+    let arguments = call.child_by_field_name("arguments")?;
+    let mut cursor = arguments.walk();
+    let first = arguments
+        .named_children(&mut cursor)
+        .find(|arg| !matches!(arg.kind(), "comment" | "keyword_argument"));
+    first
+}
+
+fn collect(node: Node, imports: &mut Vec<DynamicImport>, matcher: &dyn Fn(Node) -> Option<DynamicImport>) {
+    if let Some(import) = matcher(node) {
+        imports.push(import);
+    }
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect(child, imports, matcher);
+    }
+}
+
+/// `import(...)` and `require(...)` calls in JavaScript or TypeScript, including
+/// those wrapped by lazy loaders such as `React.lazy(() => import(...))`
+pub fn find_javascript(root: Node, source: &str) -> Vec<DynamicImport> {
+    let mut imports = Vec::new();
+    collect(root, &mut imports, &|node| {
+        if node.kind() != "call_expression" {
+            return None;
+        }
+        let function = node.child_by_field_name("function")?;
+        let is_loader = function.kind() == "import"
+            || (function.kind() == "identifier" && function.utf8_text(source.as_bytes()).ok() == Some("require"));
+        is_loader.then(|| specifier(first_argument(node), source))
+    });
+    imports
+}
+
+/// `importlib.import_module(...)` and `__import__(...)` calls in Python
+pub fn find_python(root: Node, source: &str) -> Vec<DynamicImport> {
+    let mut imports = Vec::new();
+    collect(root, &mut imports, &|node| {
+        if node.kind() != "call" {
+            return None;
+        }
+        let function = node.child_by_field_name("function")?.utf8_text(source.as_bytes()).ok()?;
+        PYTHON_LOADERS
+            .contains(&function)
+            .then(|| specifier(first_argument(node), source))
+    });
+    imports
+}
+
+/// Add import edges for constant specifiers and count the rest in the file metrics
+///
+/// `resolve` maps a specifier to the target id a static import would use.
+pub fn apply(parsed: &mut ParsedFile, imports: Vec<DynamicImport>, resolve: impl Fn(&str) -> Option<String>) {
+    let file_id = format!("file:{}", parsed.path.display());
+    for import in imports {
+        match import {
+            DynamicImport::Constant(specifier) => {
+                let Some(target) = resolve(&specifier) else {
+                    continue;
+                };
+                let exists = parsed.relationships.iter().any(|r| {
+                    r.source == file_id && r.target == target && matches!(r.relationship_type, RelationshipType::Imports)
+                });
+                if !exists {
+                    parsed.relationships.push(Relationship {
+                        source: file_id.clone(),
+                        target,
+                        relationship_type: RelationshipType::Imports,
+                    });
+                }
+            }
+            DynamicImport::Unresolved => parsed.metrics.dynamic_imports += 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tree_sitter::Parser as TSParser;
+
+    #[cfg(feature = "js")]
+    #[test]
+    fn test_find_javascript_dynamic_imports() {
+        let source = "const a = require('./a');\nconst b = require(name);\nconst C = React.lazy(() => import(`./pages/${page}`));\nimport('./d').then(m => m.run());\n";
+        let mut parser = TSParser::new();
+        parser.set_language(tree_sitter_javascript::language()).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+
+        assert_eq!(
+            find_javascript(tree.root_node(), source),
+            vec![
+                DynamicImport::Constant("./a".to_string()),
+                DynamicImport::Unresolved,
+                DynamicImport::Unresolved,
+                DynamicImport::Constant("./d".to_string()),
+            ]
+        );
+    }
+
+    #[cfg(feature = "python")]
+    #[test]
+    fn test_python_dynamic_imports_flag_file() {
+        let source = "import importlib\nplugins = importlib.import_module('app.plugins')\nhandler = __import__(f'handlers.{kind}')\n";
+        let mut parser = TSParser::new();
+        parser.set_language(tree_sitter_python::language()).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let imports = find_python(tree.root_node(), source);
+
+        let mut parsed = ParsedFile {
+            path: "main.py".into(),
+            language: og_types::Language::Python,
+            nodes: vec![],
+            relationships: vec![],
+            metrics: Default::default(),
+        };
+        apply(&mut parsed, imports, |module| Some(format!("module:{}", module.replace('.', "/"))));
+
+        assert_eq!(parsed.metrics.dynamic_imports, 1);
+        assert_eq!(parsed.relationships.len(), 1);
+        assert_eq!(parsed.relationships[0].target, "module:app/plugins");
+    }
+}
@@ -1,15 +1,58 @@
+use crate::import_resolver::ImportResolver;
+use crate::incremental::IncrementalParser;
 use crate::Parser;
-use og_types::{ParsedFile, EngineResult, EngineError};
+use og_types::{ParsedFile, EngineResult, EngineError, Language, Message};
 use og_utils::ProgressReporter;
+#[cfg(feature = "parallel")]
 use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 use tracing::{instrument, info};
+use tree_sitter::Tree;
+use web_time::Instant;
+
+/// Files of one language parsed in a batch, and the time spent on them
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanguageThroughput {
+    pub language: String,
+    pub files: usize,
+    pub bytes: u64,
+    /// Time spent parsing, summed over the worker threads
+    pub busy_ms: u64,
+}
+
+impl LanguageThroughput {
+    /// Files parsed per second of one thread's time
+    pub fn files_per_second(&self) -> f64 {
+        if self.busy_ms == 0 {
+            0.0
+        } else {
+            self.files as f64 * 1000.0 / self.busy_ms as f64
+        }
+    }
+
+    /// Bytes parsed per second of one thread's time
+    pub fn bytes_per_second(&self) -> f64 {
+        if self.busy_ms == 0 {
+            0.0
+        } else {
+            self.bytes as f64 * 1000.0 / self.busy_ms as f64
+        }
+    }
+}
 
 /// Main parser engine that orchestrates language-specific parsers
 pub struct ParserEngine {
     parsers: Vec<Box<dyn Parser>>,
     base_path: PathBuf,
+    /// Import resolution shared by the JavaScript and TypeScript parsers
+    resolver: Arc<ImportResolver>,
 }
 
 impl ParserEngine {
@@ -20,16 +63,23 @@ impl ParserEngine {
     
     /// Create a new parser engine with a specific base path
     pub fn with_base_path(base_path: PathBuf) -> Self {
+        Self::with_include_dirs(base_path, Vec::new())
+    }
+
+    /// Create a new parser engine with extra C include search directories
+    #[cfg_attr(not(feature = "c"), allow(unused_variables))]
+    pub fn with_include_dirs(base_path: PathBuf, include_dirs: Vec<PathBuf>) -> Self {
         let mut parsers: Vec<Box<dyn Parser>> = vec![];
+        let resolver = Arc::new(ImportResolver::new(base_path.clone()));
         
         #[cfg(feature = "js")]
         {
-            parsers.push(Box::new(crate::javascript::JavaScriptParser::with_base_path(base_path.clone())));
+            parsers.push(Box::new(crate::javascript::JavaScriptParser::with_resolver(resolver.clone())));
         }
         
         #[cfg(feature = "ts")]
         {
-            parsers.push(Box::new(crate::typescript::TypeScriptParser::with_base_path(base_path.clone())));
+            parsers.push(Box::new(crate::typescript::TypeScriptParser::with_resolver(resolver.clone())));
         }
         
         #[cfg(feature = "python")]
@@ -39,10 +89,30 @@ impl ParserEngine {
         
         #[cfg(feature = "c")]
         {
-            parsers.push(Box::new(crate::c::CParser::with_base_path(base_path.clone())));
+            parsers.push(Box::new(crate::c::CParser::with_base_path(base_path.clone()).with_include_dirs(include_dirs)));
         }
         
-        Self { parsers, base_path }
+        parsers.push(Box::new(crate::shell::ShellParser::new()));
+        parsers.push(Box::new(crate::make::MakefileParser::new()));
+        parsers.push(Box::new(crate::docker::DockerfileParser::new()));
+        parsers.push(Box::new(crate::docker::ComposeParser::new()));
+        parsers.push(Box::new(crate::stylesheet::StylesheetParser::new()));
+        parsers.push(Box::new(crate::graphql::GraphqlParser::new()));
+        
+        Self { parsers, base_path, resolver }
+    }
+
+    /// Check whether any parser handles the given file
+    pub fn can_parse(&self, path: &Path) -> bool {
+        self.parsers.iter().any(|parser| parser.can_parse(path))
+    }
+
+    /// Language of the parser that would handle the given file
+    pub fn language_for(&self, path: &Path) -> Option<Language> {
+        self.parsers
+            .iter()
+            .find(|parser| parser.can_parse(path))
+            .map(|parser| parser.language())
     }
     
     /// Parse a single file
@@ -61,29 +131,133 @@ impl ParserEngine {
         })
     }
     
+    /// Parse a new version of a file from its previous, edited syntax tree
+    pub fn parse_file_incremental(
+        &self,
+        path: &Path,
+        content: &str,
+        old_tree: Option<&Tree>,
+    ) -> EngineResult<(ParsedFile, Option<Tree>)> {
+        let parser = self.parser_for(path)?;
+        // An edit may have added or removed the files its imports resolve to
+        self.resolver.clear();
+        parser.parse_incremental(path, content, old_tree)
+    }
+
+    /// Parser that handles a file
+    pub(crate) fn parser_for(&self, path: &Path) -> EngineResult<&dyn Parser> {
+        let parser = self.parsers.iter().find(|parser| parser.can_parse(path)).ok_or_else(|| EngineError::ParseError {
+            file: path.display().to_string(),
+            message: "No parser found for file type".to_string(),
+        })?;
+        Ok(parser.as_ref())
+    }
+
+    /// Forget how imports resolved, after files may have been added or removed
+    pub(crate) fn clear_resolved_imports(&self) {
+        self.resolver.clear();
+    }
+
     /// Parse multiple files in parallel
     #[instrument(skip(self, files, progress))]
-    pub fn parse_batch<'a>(
+    pub fn parse_batch(
         &self,
         files: Vec<(String, String)>, // (path, content)
         progress: Option<Arc<dyn ProgressReporter>>,
     ) -> Vec<EngineResult<ParsedFile>> {
+        self.parse_batch_with_throughput(files, progress).0
+    }
+
+    /// Parse multiple files in parallel, with the throughput of each language
+    ///
+    /// Every file is its own unit of work that idle threads steal, and the
+    /// largest files start first, so a language with heavy files does not
+    /// hold back the others and no large file is left running alone at the
+    /// end. Results are in the order of `files`.
+    ///
+    /// Imports resolve against a snapshot of the file system taken during
+    /// the batch, so files created while it runs may not be seen.
+    #[instrument(skip(self, files, progress))]
+    pub fn parse_batch_with_throughput(
+        &self,
+        files: Vec<(String, String)>, // (path, content)
+        progress: Option<Arc<dyn ProgressReporter>>,
+    ) -> (Vec<EngineResult<ParsedFile>>, Vec<LanguageThroughput>) {
+        self.parse_batch_with(files, progress, |path, content| self.parse_file(path, content))
+    }
+
+    /// Parse multiple files in parallel like [`Self::parse_batch_with_throughput`],
+    /// reparsing the files `cache` has seen from their previous syntax trees
+    #[instrument(skip(self, files, cache, progress))]
+    pub fn reparse_batch_with_throughput(
+        &self,
+        files: Vec<(String, String)>, // (path, content)
+        cache: &IncrementalParser,
+        progress: Option<Arc<dyn ProgressReporter>>,
+    ) -> (Vec<EngineResult<ParsedFile>>, Vec<LanguageThroughput>) {
+        self.parse_batch_with(files, progress, |path, content| {
+            cache.reparse(self, path, content).map(|update| update.parsed)
+        })
+    }
+
+    fn parse_batch_with(
+        &self,
+        files: Vec<(String, String)>,
+        progress: Option<Arc<dyn ProgressReporter>>,
+        parse: impl Fn(&Path, &str) -> EngineResult<ParsedFile> + Sync,
+    ) -> (Vec<EngineResult<ParsedFile>>, Vec<LanguageThroughput>) {
+        self.resolver.clear();
         let total = files.len();
-        
-        files
-            .into_par_iter()
-            .enumerate()
-            .map(|(idx, (path_str, content))| {
-                let path = Path::new(&path_str);
-                
+        let done = AtomicUsize::new(0);
+        let mut order: Vec<usize> = (0..total).collect();
+        order.sort_by_key(|&idx| Reverse(files[idx].1.len()));
+
+        #[cfg(feature = "parallel")]
+        let order = order.into_par_iter().with_max_len(1);
+        #[cfg(not(feature = "parallel"))]
+        let order = order.into_iter();
+        let mut parsed: Vec<(usize, EngineResult<ParsedFile>, Option<Language>, Duration)> = order
+            .map(|idx| {
+                let (path_str, content) = &files[idx];
+                let path = Path::new(path_str);
+                let started = Instant::now();
+                let result = parse(path, content);
+                let elapsed = started.elapsed();
+
                 if let Some(ref reporter) = progress {
-                    let percentage = ((idx + 1) as f32 / total as f32) * 100.0;
-                    reporter.report(&format!("Parsing {}", path.display()), percentage);
+                    let percentage = (done.fetch_add(1, Ordering::Relaxed) + 1) as f32 / total as f32 * 100.0;
+                    reporter.report_message(&Message::ParsingFile { path: path.display().to_string() }, percentage);
                 }
-                
-                self.parse_file(path, &content)
+                (idx, result, self.language_for(path), elapsed)
             })
-            .collect()
+            .collect();
+        parsed.sort_by_key(|(idx, ..)| *idx);
+
+        let mut throughput: BTreeMap<&str, (LanguageThroughput, Duration)> = BTreeMap::new();
+        for (idx, _, language, elapsed) in &parsed {
+            let Some(language) = language else {
+                continue;
+            };
+            let (entry, busy) = throughput.entry(language.as_str()).or_insert_with(|| {
+                let entry = LanguageThroughput {
+                    language: language.as_str().to_string(),
+                    ..LanguageThroughput::default()
+                };
+                (entry, Duration::ZERO)
+            });
+            entry.files += 1;
+            entry.bytes += files[*idx].1.len() as u64;
+            *busy += *elapsed;
+        }
+        let throughput = throughput
+            .into_values()
+            .map(|(entry, busy)| LanguageThroughput {
+                busy_ms: busy.as_millis() as u64,
+                ..entry
+            })
+            .collect();
+
+        (parsed.into_iter().map(|(_, result, ..)| result).collect(), throughput)
     }
     
     /// Get supported extensions across all parsers
@@ -99,4 +273,30 @@ impl Default for ParserEngine {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(all(feature = "python", feature = "c"))]
+    #[test]
+    fn test_batch_keeps_order_and_counts_languages() {
+        let files = vec![
+            ("a.py".to_string(), "def f():\n    pass\n".to_string()),
+            ("big.c".to_string(), "int main(void) {\n  return 0;\n}\n".repeat(50)),
+            ("notes.txt".to_string(), "plain text".to_string()),
+            ("b.py".to_string(), "x = 1\n".to_string()),
+        ];
+        let (results, throughput) = ParserEngine::new().parse_batch_with_throughput(files, None);
+
+        let paths: Vec<String> = results
+            .iter()
+            .map(|r| r.as_ref().map_or_else(|_| "error".to_string(), |f| f.path.display().to_string()))
+            .collect();
+        assert_eq!(paths, vec!["a.py", "big.c", "error", "b.py"]);
+        let counted: Vec<(&str, usize)> = throughput.iter().map(|t| (t.language.as_str(), t.files)).collect();
+        assert_eq!(counted, vec![("c", 1), ("python", 2)]);
+        assert_eq!(throughput[1].bytes, 24);
+    }
+}
@@ -0,0 +1,231 @@
+use og_types::{AstNode, NodeType, ParsedFile, Relationship, RelationshipType};
+use tree_sitter::Node;
+
+use crate::dynamic_imports::{first_argument, string_literal};
+
+/// Objects whose properties are environment variables in JavaScript
+const JAVASCRIPT_ENV_OBJECTS: &[&str] = &["process.env", "import.meta.env"];
+
+/// Mappings of environment variables in Python
+const PYTHON_ENV_OBJECTS: &[&str] = &["os.environ", "environ"];
+
+/// Python calls that read an environment variable
+const PYTHON_GETTERS: &[&str] = &["os.getenv", "getenv", "os.environ.get", "environ.get"];
+
+/// C functions that read an environment variable
+const C_GETTERS: &[&str] = &["getenv", "secure_getenv"];
+
+/// An environment variable read at a line of a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvRead {
+    pub name: String,
+    pub line: usize,
+}
+
+/// Id of the node for an environment variable, shared by every file that reads it
+pub fn env_var_id(name: &str) -> String {
+    format!("env:{}", name)
+}
+
+pub(crate) fn walk(node: Node, visit: &mut dyn FnMut(Node)) {
+    visit(node);
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        walk(child, visit);
+    }
+}
+
+pub(crate) fn text<'a>(node: Option<Node>, source: &'a str) -> Option<&'a str> {
+    node?.utf8_text(source.as_bytes()).ok()
+}
+
+fn read(name: String, node: Node) -> EnvRead {
+    EnvRead {
+        name,
+        line: node.start_position().row + 1,
+    }
+}
+
+/// `process.env.X`, `process.env["X"]` and `const { X } = process.env` in JavaScript or TypeScript
+pub fn find_javascript(root: Node, source: &str) -> Vec<EnvRead> {
+    let is_env = |node: Option<Node>| text(node, source).is_some_and(|t| JAVASCRIPT_ENV_OBJECTS.contains(&t));
+    let mut reads = Vec::new();
+    walk(root, &mut |node| match node.kind() {
+        "member_expression" if is_env(node.child_by_field_name("object")) => {
+            if let Some(name) = text(node.child_by_field_name("property"), source) {
+                reads.push(read(name.to_string(), node));
+            }
+        }
+        "subscript_expression" if is_env(node.child_by_field_name("object")) => {
+            if let Some(name) = node.child_by_field_name("index").and_then(|index| string_literal(index, source)) {
+                reads.push(read(name, node));
+            }
+        }
+        "variable_declarator" if is_env(node.child_by_field_name("value")) => {
+            let Some(pattern) = node.child_by_field_name("name").filter(|n| n.kind() == "object_pattern") else {
+                return;
+            };
+            let mut cursor = pattern.walk();
+            for property in pattern.named_children(&mut cursor) {
+                let key = match property.kind() {
+                    "shorthand_property_identifier_pattern" => Some(property),
+                    "pair_pattern" => property.child_by_field_name("key"),
+                    "object_assignment_pattern" => property.child_by_field_name("left"),
+                    _ => None,
+                };
+                if let Some(name) = text(key, source) {
+                    reads.push(read(name.to_string(), property));
+                }
+            }
+        }
+        _ => {}
+    });
+    reads
+}
+
+/// `os.environ["X"]`, `os.environ.get("X")` and `os.getenv("X")` in Python
+pub fn find_python(root: Node, source: &str) -> Vec<EnvRead> {
+    let mut reads = Vec::new();
+    walk(root, &mut |node| {
+        let name = match node.kind() {
+            "subscript" if text(node.child_by_field_name("value"), source).is_some_and(|t| PYTHON_ENV_OBJECTS.contains(&t)) => {
+                node.child_by_field_name("subscript").and_then(|key| string_literal(key, source))
+            }
+            "call" if text(node.child_by_field_name("function"), source).is_some_and(|t| PYTHON_GETTERS.contains(&t)) => {
+                first_argument(node).and_then(|key| string_literal(key, source))
+            }
+            _ => None,
+        };
+        if let Some(name) = name {
+            reads.push(read(name, node));
+        }
+    });
+    reads
+}
+
+/// `getenv("X")` calls in C
+pub fn find_c(root: Node, source: &str) -> Vec<EnvRead> {
+    let mut reads = Vec::new();
+    walk(root, &mut |node| {
+        if node.kind() != "call_expression"
+            || !text(node.child_by_field_name("function"), source).is_some_and(|t| C_GETTERS.contains(&t))
+        {
+            return;
+        }
+        if let Some(name) = first_argument(node).and_then(|key| string_literal(key, source)) {
+            reads.push(read(name, node));
+        }
+    });
+    reads
+}
+
+/// Innermost function containing a line, or the file for top-level code
+pub(crate) fn enclosing_function(parsed: &ParsedFile, line: usize) -> Option<String> {
+    parsed
+        .nodes
+        .iter()
+        .filter(|n| matches!(n.node_type, NodeType::Function | NodeType::Method))
+        .filter(|n| n.start_line <= line && line <= n.end_line)
+        .min_by_key(|n| n.end_line - n.start_line)
+        .or_else(|| parsed.nodes.iter().find(|n| matches!(n.node_type, NodeType::File)))
+        .map(|n| n.id.clone())
+}
+
+/// Add a node unless the file already has one with the same id
+pub(crate) fn add_node_once(parsed: &mut ParsedFile, id: &str, node_type: NodeType, name: &str, line: usize) {
+    if !parsed.nodes.iter().any(|n| n.id == id) {
+        parsed.nodes.push(AstNode {
+            id: id.to_string(),
+            node_type,
+            name: name.to_string(),
+            start_line: line,
+            end_line: line,
+            children: vec![],
+        });
+    }
+}
+
+/// Add an edge unless the file already has the same one
+pub(crate) fn relate_once(parsed: &mut ParsedFile, source: String, target: String, relationship_type: RelationshipType) {
+    let exists = parsed.relationships.iter().any(|r| {
+        r.source == source && r.target == target && r.relationship_type.as_str() == relationship_type.as_str()
+    });
+    if !exists {
+        parsed.relationships.push(Relationship {
+            source,
+            target,
+            relationship_type,
+        });
+    }
+}
+
+/// Add an env var node for each variable read, linked from the innermost
+/// function containing the read, or from the file for top-level reads
+pub fn apply(parsed: &mut ParsedFile, reads: Vec<EnvRead>) {
+    for read in reads {
+        if read.name.is_empty() {
+            continue;
+        }
+        let Some(reader) = enclosing_function(parsed, read.line) else {
+            continue;
+        };
+
+        let env_id = env_var_id(&read.name);
+        add_node_once(parsed, &env_id, NodeType::EnvVar, &read.name, read.line);
+        relate_once(parsed, reader, env_id, RelationshipType::Reads);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tree_sitter::Parser as TSParser;
+
+    #[cfg(feature = "js")]
+    #[test]
+    fn test_find_javascript_env_reads() {
+        let source = "const url = process.env.DATABASE_URL;\nconst key = process.env['API_KEY'];\nconst { PORT, HOST: host = 'localhost' } = process.env;\nconst mode = import.meta.env.MODE;\nconst other = process.env[name];\n";
+        let mut parser = TSParser::new();
+        parser.set_language(tree_sitter_javascript::language()).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+
+        let names: Vec<String> = find_javascript(tree.root_node(), source).into_iter().map(|r| r.name).collect();
+        assert_eq!(names, vec!["DATABASE_URL", "API_KEY", "PORT", "HOST", "MODE"]);
+    }
+
+    #[cfg(feature = "python")]
+    #[test]
+    fn test_python_env_reads_link_functions() {
+        let source = "import os\nDEBUG = os.environ.get('DEBUG')\n\ndef connect():\n    return os.environ['DATABASE_URL'], os.getenv('DATABASE_URL')\n";
+        let mut parser = TSParser::new();
+        parser.set_language(tree_sitter_python::language()).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let reads = find_python(tree.root_node(), source);
+        assert_eq!(reads.len(), 3);
+
+        let node = |id: &str, node_type, start_line, end_line| AstNode {
+            id: id.to_string(),
+            node_type,
+            name: id.to_string(),
+            start_line,
+            end_line,
+            children: vec![],
+        };
+        let mut parsed = ParsedFile {
+            path: "db.py".into(),
+            language: og_types::Language::Python,
+            nodes: vec![node("file:db.py", NodeType::File, 1, 5), node("function:connect", NodeType::Function, 4, 5)],
+            relationships: vec![],
+            metrics: Default::default(),
+        };
+        apply(&mut parsed, reads);
+
+        let edges: Vec<(&str, &str)> = parsed
+            .relationships
+            .iter()
+            .map(|r| (r.source.as_str(), r.target.as_str()))
+            .collect();
+        assert_eq!(edges, vec![("file:db.py", "env:DEBUG"), ("function:connect", "env:DATABASE_URL")]);
+        assert_eq!(parsed.nodes.iter().filter(|n| matches!(n.node_type, NodeType::EnvVar)).count(), 2);
+    }
+}
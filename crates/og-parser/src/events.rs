@@ -0,0 +1,228 @@
+use og_types::{NodeType, ParsedFile, RelationshipType};
+use tree_sitter::Node;
+
+use crate::dynamic_imports::string_literal;
+use crate::env_vars::{add_node_once, enclosing_function, relate_once, text, walk};
+
+/// Calls that publish an event, message or action
+const PUBLISH_METHODS: &[&str] = &["emit", "publish", "dispatch", "trigger", "sendToQueue", "send", "basic_publish", "produce"];
+
+/// Calls that subscribe to an event, message or topic
+const SUBSCRIBE_METHODS: &[&str] = &["on", "once", "addListener", "subscribe", "psubscribe", "consume", "basic_consume"];
+
+/// Calls too generic to name a topic by their first string argument alone
+const KEYED_ONLY_METHODS: &[&str] = &["send", "dispatch"];
+
+/// Object keys and keyword arguments that name a topic, queue or action type
+const TOPIC_KEYS: &[&str] = &["topic", "topics", "queue", "routing_key", "routingKey", "channel", "type"];
+
+/// Which side of an event a call is on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventRole {
+    Publish,
+    Subscribe,
+}
+
+/// An event published or subscribed to at a line of a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventUse {
+    pub name: String,
+    pub role: EventRole,
+    pub line: usize,
+}
+
+/// Id of the node for an event, shared by every file that uses it
+pub fn event_id(name: &str) -> String {
+    format!("event:{}", name)
+}
+
+/// A constant such as `ADD_TODO` or `ActionTypes.ADD_TODO`, named by its last segment
+fn constant_name(node: Node, source: &str) -> Option<String> {
+    if !matches!(node.kind(), "identifier" | "member_expression" | "attribute") {
+        return None;
+    }
+    let text = text(Some(node), source)?;
+    let name = text.rsplit('.').next()?;
+    let is_constant = name.chars().any(|c| c.is_ascii_uppercase())
+        && name.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_');
+    is_constant.then(|| name.to_string())
+}
+
+/// Topic names in a string, list of strings or constant
+fn topic_names(node: Node, source: &str) -> Vec<String> {
+    match node.kind() {
+        "array" | "list" | "tuple" => {
+            let mut cursor = node.walk();
+            let names = node
+                .named_children(&mut cursor)
+                .filter_map(|item| string_literal(item, source))
+                .collect();
+            names
+        }
+        _ => string_literal(node, source)
+            .or_else(|| constant_name(node, source))
+            .into_iter()
+            .collect(),
+    }
+}
+
+/// Topic named by a key of an object or dict argument, or a keyword argument
+fn keyed_topics(argument: Node, source: &str) -> Vec<String> {
+    let pairs: Vec<Node> = match argument.kind() {
+        "object" | "dictionary" => {
+            let mut cursor = argument.walk();
+            let pairs = argument.named_children(&mut cursor).filter(|p| p.kind() == "pair").collect();
+            pairs
+        }
+        "keyword_argument" => vec![argument],
+        _ => return Vec::new(),
+    };
+
+    pairs
+        .into_iter()
+        .filter_map(|pair| {
+            let key = pair.child_by_field_name("key").or_else(|| pair.child_by_field_name("name"))?;
+            let key = string_literal(key, source).or_else(|| text(Some(key), source).map(str::to_string))?;
+            TOPIC_KEYS.contains(&key.as_str()).then(|| pair.child_by_field_name("value")).flatten()
+        })
+        .flat_map(|value| topic_names(value, source))
+        .collect()
+}
+
+/// Events named by a publish or subscribe call
+fn call_events(node: Node, source: &str, uses: &mut Vec<EventUse>) {
+    let Some(function) = text(node.child_by_field_name("function"), source) else {
+        return;
+    };
+    let method = function.rsplit('.').next().unwrap_or(function);
+    let role = if PUBLISH_METHODS.contains(&method) {
+        EventRole::Publish
+    } else if SUBSCRIBE_METHODS.contains(&method) {
+        EventRole::Subscribe
+    } else {
+        return;
+    };
+    let Some(arguments) = node.child_by_field_name("arguments") else {
+        return;
+    };
+
+    let mut cursor = arguments.walk();
+    let arguments: Vec<Node> = arguments.named_children(&mut cursor).filter(|a| a.kind() != "comment").collect();
+    let mut names: Vec<String> = arguments.iter().flat_map(|&argument| keyed_topics(argument, source)).collect();
+    if names.is_empty() && !KEYED_ONLY_METHODS.contains(&method) {
+        if let Some(&first) = arguments.first() {
+            names = topic_names(first, source);
+        }
+    }
+
+    let line = node.start_position().row + 1;
+    uses.extend(names.into_iter().filter(|name| !name.is_empty()).map(|name| EventUse { name, role, line }));
+}
+
+/// Emitter, message queue and Redux action events in JavaScript or TypeScript
+///
+/// Reducer `case` labels in a `switch` over an action type count as subscriptions.
+pub fn find_javascript(root: Node, source: &str) -> Vec<EventUse> {
+    let mut uses = Vec::new();
+    walk(root, &mut |node| match node.kind() {
+        "call_expression" => call_events(node, source, &mut uses),
+        "switch_statement" => {
+            let on_action_type = text(node.child_by_field_name("value"), source).is_some_and(|v| v.contains(".type"));
+            let Some(body) = node.child_by_field_name("body").filter(|_| on_action_type) else {
+                return;
+            };
+            let mut cursor = body.walk();
+            for case in body.named_children(&mut cursor).filter(|c| c.kind() == "switch_case") {
+                for name in case.child_by_field_name("value").map(|v| topic_names(v, source)).unwrap_or_default() {
+                    uses.push(EventUse {
+                        name,
+                        role: EventRole::Subscribe,
+                        line: case.start_position().row + 1,
+                    });
+                }
+            }
+        }
+        _ => {}
+    });
+    uses
+}
+
+/// Message queue and pub/sub events in Python
+pub fn find_python(root: Node, source: &str) -> Vec<EventUse> {
+    let mut uses = Vec::new();
+    walk(root, &mut |node| {
+        if node.kind() == "call" {
+            call_events(node, source, &mut uses);
+        }
+    });
+    uses
+}
+
+/// Add an event node for each event used, linked from the innermost function
+/// that publishes or subscribes to it
+pub fn apply(parsed: &mut ParsedFile, uses: Vec<EventUse>) {
+    for event in uses {
+        let Some(source) = enclosing_function(parsed, event.line) else {
+            continue;
+        };
+        let id = event_id(&event.name);
+        add_node_once(parsed, &id, NodeType::Event, &event.name, event.line);
+        let relationship_type = match event.role {
+            EventRole::Publish => RelationshipType::Publishes,
+            EventRole::Subscribe => RelationshipType::Subscribes,
+        };
+        relate_once(parsed, source, id, relationship_type);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tree_sitter::Parser as TSParser;
+
+    fn names(uses: &[EventUse], role: EventRole) -> Vec<&str> {
+        uses.iter().filter(|u| u.role == role).map(|u| u.name.as_str()).collect()
+    }
+
+    #[cfg(feature = "js")]
+    #[test]
+    fn test_find_javascript_events() {
+        let source = r#"
+bus.emit('order.created', order);
+bus.on("order.created", notify);
+store.dispatch({ type: 'todos/add', payload });
+await producer.send({ topic: 'payments', messages });
+res.send('ok');
+channel.consume(QUEUE_NAME, handler);
+function todos(state, action) {
+  switch (action.type) {
+    case 'todos/add': return [...state];
+    case ActionTypes.CLEAR: return [];
+  }
+}
+"#;
+        let mut parser = TSParser::new();
+        parser.set_language(tree_sitter_javascript::language()).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let uses = find_javascript(tree.root_node(), source);
+
+        assert_eq!(names(&uses, EventRole::Publish), vec!["order.created", "todos/add", "payments"]);
+        assert_eq!(
+            names(&uses, EventRole::Subscribe),
+            vec!["order.created", "QUEUE_NAME", "todos/add", "CLEAR"]
+        );
+    }
+
+    #[cfg(feature = "python")]
+    #[test]
+    fn test_find_python_events() {
+        let source = "channel.basic_publish(exchange='', routing_key='jobs', body=data)\nchannel.basic_consume(queue='jobs', on_message_callback=run)\nconsumer.subscribe(['clicks', 'views'])\nredis.publish('alerts', msg)\n";
+        let mut parser = TSParser::new();
+        parser.set_language(tree_sitter_python::language()).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let uses = find_python(tree.root_node(), source);
+
+        assert_eq!(names(&uses, EventRole::Publish), vec!["jobs", "alerts"]);
+        assert_eq!(names(&uses, EventRole::Subscribe), vec!["jobs", "clicks", "views"]);
+    }
+}
@@ -0,0 +1,518 @@
+use og_types::{AstNode, NodeType, ParsedFile, Relationship, RelationshipType};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use tree_sitter::Node;
+
+/// Target prefix for handlers named through another module, resolved after parsing
+pub const UNRESOLVED_HANDLER_PREFIX: &str = "handler:";
+
+/// HTTP method decorators shared by NestJS and FastAPI-style routers
+const HTTP_METHODS: &[&str] = &["get", "post", "put", "patch", "delete", "options", "head", "all"];
+
+/// Framework nodes and edges discovered from decorators
+#[derive(Debug, Default)]
+pub struct FrameworkItems {
+    pub nodes: Vec<AstNode>,
+    pub relationships: Vec<Relationship>,
+}
+
+impl FrameworkItems {
+    /// Merge into a parsed file, skipping nodes it already has
+    pub fn append_to(self, parsed: &mut ParsedFile) {
+        let existing: HashSet<String> = parsed.nodes.iter().map(|n| n.id.clone()).collect();
+        parsed
+            .nodes
+            .extend(self.nodes.into_iter().filter(|n| !existing.contains(&n.id)));
+        parsed.relationships.extend(self.relationships);
+    }
+
+    fn add_node(&mut self, id: &str, name: String, node_type: NodeType, node: Node) {
+        if self.nodes.iter().any(|n| n.id == id) {
+            return;
+        }
+        self.nodes.push(AstNode {
+            id: id.to_string(),
+            name,
+            node_type,
+            start_line: node.start_position().row + 1,
+            end_line: node.end_position().row + 1,
+            children: vec![],
+        });
+    }
+
+    fn relate(&mut self, source: &str, target: &str, relationship_type: RelationshipType) {
+        self.relationships.push(Relationship {
+            source: source.to_string(),
+            target: target.to_string(),
+            relationship_type,
+        });
+    }
+
+    /// Add a route node for `method path` handled by `handler_id`
+    fn add_route(&mut self, file_path: &Path, method: &str, path: &str, handler_id: &str, node: Node) -> String {
+        let name = format!("{} {}", method.to_uppercase(), path);
+        let route_id = format!("route:{}:{}", file_path.display(), name);
+        self.add_node(&route_id, name, NodeType::Route, node);
+        self.relate(&route_id, handler_id, RelationshipType::RoutesTo);
+        route_id
+    }
+}
+
+/// A decorator split into its callee and call arguments
+struct Decorator<'a> {
+    /// Object the decorator is accessed on, e.g. `app` in `@app.get`
+    receiver: Option<String>,
+    name: String,
+    arguments: Option<Node<'a>>,
+}
+
+impl<'a> Decorator<'a> {
+    fn parse(node: Node<'a>, source: &str) -> Option<Self> {
+        let expr = node.named_child(0)?;
+        let (callee, arguments) = match expr.kind() {
+            "call_expression" | "call" => (expr.child_by_field_name("function")?, expr.child_by_field_name("arguments")),
+            _ => (expr, None),
+        };
+
+        let text = callee.utf8_text(source.as_bytes()).ok()?;
+        let (receiver, name) = match text.rsplit_once('.') {
+            Some((receiver, name)) => (Some(receiver.to_string()), name.to_string()),
+            None => (None, text.to_string()),
+        };
+
+        Some(Self {
+            receiver,
+            name,
+            arguments,
+        })
+    }
+
+    /// First positional string argument, unquoted
+    fn first_string(&self, source: &str) -> Option<String> {
+        let arguments = self.arguments?;
+        let mut cursor = arguments.walk();
+        let first = arguments.named_children(&mut cursor).next()?;
+        string_literal(first, source)
+    }
+
+    /// String items of a keyword argument list such as `methods=["GET"]`
+    fn keyword_strings(&self, keyword: &str, source: &str) -> Vec<String> {
+        let Some(arguments) = self.arguments else {
+            return Vec::new();
+        };
+        let mut cursor = arguments.walk();
+        let arguments: Vec<Node> = arguments.named_children(&mut cursor).collect();
+        keyword_value(&arguments, keyword, source)
+            .map(|value| string_items(value, source))
+            .unwrap_or_default()
+    }
+}
+
+/// Value of `keyword=...` among call arguments
+fn keyword_value<'a>(arguments: &[Node<'a>], keyword: &str, source: &str) -> Option<Node<'a>> {
+    arguments
+        .iter()
+        .filter(|arg| arg.kind() == "keyword_argument")
+        .find(|arg| node_text(arg.child_by_field_name("name"), source) == Some(keyword))
+        .and_then(|arg| arg.child_by_field_name("value"))
+}
+
+/// String literals inside a list or tuple
+fn string_items(node: Node, source: &str) -> Vec<String> {
+    let mut cursor = node.walk();
+    let items: Vec<Node> = node.named_children(&mut cursor).collect();
+    items
+        .into_iter()
+        .filter_map(|item| string_literal(item, source))
+        .collect()
+}
+
+/// Text of a string literal node without quotes or prefixes
+fn string_literal(node: Node, source: &str) -> Option<String> {
+    if !matches!(node.kind(), "string" | "template_string") {
+        return None;
+    }
+    let text = node.utf8_text(source.as_bytes()).ok()?;
+    let text = text.trim_start_matches(|c: char| c.is_ascii_alphabetic());
+    Some(text.trim_matches(|c| c == '"' || c == '\'' || c == '`').to_string())
+}
+
+/// Join a controller prefix and a route path into a single absolute path
+fn join_route(prefix: &str, path: &str) -> String {
+    let segments: Vec<&str> = prefix
+        .split('/')
+        .chain(path.split('/'))
+        .filter(|segment| !segment.is_empty())
+        .collect();
+    format!("/{}", segments.join("/"))
+}
+
+fn decorator_nodes(node: Node) -> Vec<Node> {
+    let mut cursor = node.walk();
+    let decorators: Vec<Node> = node
+        .children(&mut cursor)
+        .filter(|child| child.kind() == "decorator")
+        .collect();
+    decorators
+}
+
+fn node_text<'a>(node: Option<Node>, source: &'a str) -> Option<&'a str> {
+    node?.utf8_text(source.as_bytes()).ok()
+}
+
+/// NestJS and Angular classes, providers and route handlers
+pub fn extract_typescript(root: Node, source: &str, file_path: &Path) -> FrameworkItems {
+    let mut items = FrameworkItems::default();
+    visit(root, &mut |node| {
+        if matches!(node.kind(), "class_declaration" | "abstract_class_declaration") {
+            typescript_class(node, source, file_path, &mut items);
+        }
+    });
+    items
+}
+
+fn typescript_class(class: Node, source: &str, file_path: &Path, items: &mut FrameworkItems) {
+    let Some(class_name) = node_text(class.child_by_field_name("name"), source) else {
+        return;
+    };
+    let class_id = format!("class:{}:{}", file_path.display(), class_name);
+
+    // `@Controller() export class` attaches the decorator to the export statement
+    let mut decorators = decorator_nodes(class);
+    if let Some(parent) = class.parent().filter(|p| p.kind() == "export_statement") {
+        decorators.extend(decorator_nodes(parent));
+    }
+
+    let mut prefix = None;
+    for decorator in decorators.iter().filter_map(|d| Decorator::parse(*d, source)) {
+        let (node_type, id_prefix) = match decorator.name.as_str() {
+            "Controller" | "Component" => (NodeType::Controller, "controller"),
+            "Injectable" => (NodeType::Provider, "provider"),
+            _ => continue,
+        };
+        if decorator.name == "Controller" {
+            prefix = Some(decorator.first_string(source).unwrap_or_default());
+        }
+        let role_id = format!("{}:{}", id_prefix, class_id);
+        items.add_node(&role_id, class_name.to_string(), node_type, class);
+        items.relate(&role_id, &class_id, RelationshipType::Decorates);
+    }
+
+    let Some(prefix) = prefix else {
+        return;
+    };
+    let Some(body) = class.child_by_field_name("body") else {
+        return;
+    };
+
+    // Member decorators are siblings that precede the method they apply to
+    let mut pending: Vec<Node> = Vec::new();
+    let mut cursor = body.walk();
+    for member in body.named_children(&mut cursor) {
+        if member.kind() == "decorator" {
+            pending.push(member);
+            continue;
+        }
+        let member_decorators = std::mem::take(&mut pending);
+        if member.kind() != "method_definition" {
+            continue;
+        }
+        let Some(method_name) = node_text(member.child_by_field_name("name"), source) else {
+            continue;
+        };
+
+        let handler_id = format!("function:{}:{}.{}", file_path.display(), class_name, method_name);
+        for decorator in member_decorators.iter().filter_map(|d| Decorator::parse(*d, source)) {
+            let method = decorator.name.to_lowercase();
+            if !HTTP_METHODS.contains(&method.as_str()) {
+                continue;
+            }
+            items.add_node(&handler_id, method_name.to_string(), NodeType::Method, member);
+            items.relate(&class_id, &handler_id, RelationshipType::Contains);
+
+            let path = join_route(&prefix, &decorator.first_string(source).unwrap_or_default());
+            let route_id = items.add_route(file_path, &method, &path, &handler_id, member);
+            items.relate(&format!("controller:{}", class_id), &route_id, RelationshipType::Contains);
+        }
+    }
+}
+
+/// Flask, FastAPI and Django route registrations
+pub fn extract_python(root: Node, source: &str, file_path: &Path) -> FrameworkItems {
+    let prefixes = python_router_prefixes(root, source);
+    let mut items = FrameworkItems::default();
+    visit(root, &mut |node| match node.kind() {
+        "decorated_definition" => python_decorated(node, source, file_path, &prefixes, &mut items),
+        "call" => python_route_call(node, source, file_path, &prefixes, &mut items),
+        _ => {}
+    });
+    items
+}
+
+/// URL prefix of each router or blueprint defined and mounted in this file
+fn python_router_prefixes(root: Node, source: &str) -> HashMap<String, String> {
+    let mut prefixes: HashMap<String, String> = HashMap::new();
+
+    // `router = APIRouter(prefix="/items")` or `bp = Blueprint("bp", __name__, url_prefix="/bp")`
+    visit(root, &mut |node| {
+        if node.kind() != "assignment" {
+            return;
+        }
+        let (Some(name), Some(value)) = (
+            node_text(node.child_by_field_name("left"), source),
+            node.child_by_field_name("right"),
+        ) else {
+            return;
+        };
+        let Some(call) = PythonCall::parse(value, source) else {
+            return;
+        };
+        let keyword = match call.name.as_str() {
+            "APIRouter" => "prefix",
+            "Blueprint" => "url_prefix",
+            _ => return,
+        };
+        prefixes.insert(name.to_string(), call.keyword_string(keyword, source).unwrap_or_default());
+    });
+
+    // `app.include_router(router, prefix="/api")` nests a local router under another prefix
+    visit(root, &mut |node| {
+        let Some(call) = PythonCall::parse(node, source) else {
+            return;
+        };
+        let keyword = match call.name.as_str() {
+            "include_router" => "prefix",
+            "register_blueprint" => "url_prefix",
+            _ => return,
+        };
+        let Some(router) = call.positional(0).and_then(|arg| node_text(Some(arg), source)) else {
+            return;
+        };
+        if let (Some(mount), Some(own)) = (call.keyword_string(keyword, source), prefixes.get(router)) {
+            let joined = join_route(&mount, own);
+            prefixes.insert(router.to_string(), joined);
+        }
+    });
+
+    prefixes
+}
+
+fn python_decorated(
+    node: Node,
+    source: &str,
+    file_path: &Path,
+    prefixes: &HashMap<String, String>,
+    items: &mut FrameworkItems,
+) {
+    let Some(definition) = node.child_by_field_name("definition") else {
+        return;
+    };
+    if definition.kind() != "function_definition" {
+        return;
+    }
+    let Some(func_name) = node_text(definition.child_by_field_name("name"), source) else {
+        return;
+    };
+    let handler_id = format!("function:{}:{}", file_path.display(), func_name);
+
+    for decorator in decorator_nodes(node).into_iter().filter_map(|d| Decorator::parse(d, source)) {
+        // Routes are always registered on an app, blueprint or router object
+        let Some(receiver) = decorator.receiver.as_deref() else {
+            continue;
+        };
+        let Some(path) = decorator.first_string(source) else {
+            continue;
+        };
+
+        let methods = if decorator.name == "route" {
+            let methods = decorator.keyword_strings("methods", source);
+            if methods.is_empty() {
+                vec!["GET".to_string()]
+            } else {
+                methods
+            }
+        } else if HTTP_METHODS.contains(&decorator.name.as_str()) {
+            vec![decorator.name.clone()]
+        } else {
+            continue;
+        };
+
+        let path = join_route(prefixes.get(receiver).map_or("", String::as_str), &path);
+        for method in methods {
+            items.add_route(file_path, &method, &path, &handler_id, definition);
+        }
+    }
+}
+
+/// `app.add_url_rule(...)` and Django `path(...)` / `re_path(...)` registrations
+fn python_route_call(
+    node: Node,
+    source: &str,
+    file_path: &Path,
+    prefixes: &HashMap<String, String>,
+    items: &mut FrameworkItems,
+) {
+    let Some(call) = PythonCall::parse(node, source) else {
+        return;
+    };
+    let Some(rule) = call.positional(0).and_then(|arg| string_literal(arg, source)) else {
+        return;
+    };
+
+    let (view, methods, prefix) = match (call.name.as_str(), call.receiver.as_deref()) {
+        ("add_url_rule", Some(receiver)) => {
+            let view = call.keyword("view_func", source).or_else(|| call.positional(2));
+            let methods = call.keyword_strings("methods", source);
+            let methods = if methods.is_empty() { vec!["GET".to_string()] } else { methods };
+            (view, methods, prefixes.get(receiver).cloned().unwrap_or_default())
+        }
+        ("path" | "re_path" | "url", None) => (call.positional(1), vec!["ANY".to_string()], String::new()),
+        _ => return,
+    };
+
+    // Django `include()` mounts another urlconf rather than a view
+    let Some((handler, is_class_view)) = view.and_then(|view| python_view_name(view, source)) else {
+        return;
+    };
+    let handler_id = if is_class_view || handler.contains('.') {
+        format!("{}{}", UNRESOLVED_HANDLER_PREFIX, handler)
+    } else {
+        format!("function:{}:{}", file_path.display(), handler)
+    };
+
+    let path = join_route(&prefix, &rule);
+    for method in methods {
+        items.add_route(file_path, &method, &path, &handler_id, node);
+    }
+}
+
+/// Dotted name of a view argument and whether it is a Django `View.as_view()` class
+fn python_view_name(view: Node, source: &str) -> Option<(String, bool)> {
+    match view.kind() {
+        "identifier" | "attribute" => node_text(Some(view), source).map(|name| (name.to_string(), false)),
+        "call" => {
+            let function = view.child_by_field_name("function")?;
+            let text = node_text(Some(function), source)?;
+            text.strip_suffix(".as_view").map(|name| (name.to_string(), true))
+        }
+        _ => None,
+    }
+}
+
+/// A Python call split into callee and arguments
+struct PythonCall<'a> {
+    receiver: Option<String>,
+    name: String,
+    arguments: Vec<Node<'a>>,
+}
+
+impl<'a> PythonCall<'a> {
+    fn parse(node: Node<'a>, source: &str) -> Option<Self> {
+        if node.kind() != "call" {
+            return None;
+        }
+        let text = node_text(node.child_by_field_name("function"), source)?;
+        let (receiver, name) = match text.rsplit_once('.') {
+            Some((receiver, name)) => (Some(receiver.to_string()), name.to_string()),
+            None => (None, text.to_string()),
+        };
+        let arguments = node.child_by_field_name("arguments")?;
+        let mut cursor = arguments.walk();
+        let arguments = arguments.named_children(&mut cursor).collect();
+
+        Some(Self {
+            receiver,
+            name,
+            arguments,
+        })
+    }
+
+    fn positional(&self, index: usize) -> Option<Node<'a>> {
+        self.arguments
+            .iter()
+            .filter(|arg| arg.kind() != "keyword_argument")
+            .nth(index)
+            .copied()
+    }
+
+    fn keyword(&self, keyword: &str, source: &str) -> Option<Node<'a>> {
+        keyword_value(&self.arguments, keyword, source)
+    }
+
+    fn keyword_string(&self, keyword: &str, source: &str) -> Option<String> {
+        self.keyword(keyword, source)
+            .and_then(|value| string_literal(value, source))
+    }
+
+    fn keyword_strings(&self, keyword: &str, source: &str) -> Vec<String> {
+        self.keyword(keyword, source)
+            .map(|value| string_items(value, source))
+            .unwrap_or_default()
+    }
+}
+
+/// Point route handlers named through other modules at the node that defines them
+///
+/// `views.article_list` resolves to an `article_list` function, preferring one in
+/// a file named `views`. Ambiguous names are left unresolved.
+pub fn resolve_handlers(files: &mut [ParsedFile]) {
+    let mut by_name: HashMap<String, Vec<(String, String)>> = HashMap::new();
+    for file in files.iter() {
+        let stem = file.path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+        for node in &file.nodes {
+            if matches!(node.node_type, NodeType::Function | NodeType::Method | NodeType::Class) {
+                by_name
+                    .entry(node.name.clone())
+                    .or_default()
+                    .push((node.id.clone(), stem.to_string()));
+            }
+        }
+    }
+
+    for rel in files.iter_mut().flat_map(|file| file.relationships.iter_mut()) {
+        let Some(dotted) = rel.target.strip_prefix(UNRESOLVED_HANDLER_PREFIX) else {
+            continue;
+        };
+        let mut segments = dotted.rsplit('.');
+        let name = segments.next().unwrap_or_default();
+        let module = segments.next();
+        let Some(candidates) = by_name.get(name) else {
+            continue;
+        };
+
+        let preferred: Vec<&(String, String)> = candidates
+            .iter()
+            .filter(|(_, stem)| Some(stem.as_str()) == module)
+            .collect();
+        let resolved = match (preferred.as_slice(), candidates.as_slice()) {
+            ([(id, _)], _) => Some(id.clone()),
+            ([], [(id, _)]) => Some(id.clone()),
+            _ => None,
+        };
+        if let Some(id) = resolved {
+            rel.target = id;
+        }
+    }
+}
+
+/// Depth-first walk calling `f` on every node
+fn visit<'a>(node: Node<'a>, f: &mut impl FnMut(Node<'a>)) {
+    f(node);
+    let mut cursor = node.walk();
+    let children: Vec<Node<'a>> = node.children(&mut cursor).collect();
+    for child in children {
+        visit(child, f);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_join_route() {
+        assert_eq!(join_route("cats", ":id"), "/cats/:id");
+        assert_eq!(join_route("/api/", "/users/"), "/api/users");
+        assert_eq!(join_route("", ""), "/");
+    }
+}
@@ -0,0 +1,331 @@
+use og_types::{FunctionMetrics, NodeType, ParsedFile};
+use tree_sitter::Node;
+
+use crate::env_vars::{text, walk};
+
+/// Node kinds of one grammar that shape metrics look at
+struct Grammar {
+    /// Function and method definitions
+    functions: &'static [&'static str],
+    /// Definitions whose bodies belong to another function or class
+    nested: &'static [&'static str],
+    /// Branches and loops that add a level of nesting
+    control: &'static [&'static str],
+    /// Nodes that each add a path through a function, besides `&&` and `||`
+    decisions: &'static [&'static str],
+}
+
+const PYTHON: Grammar = Grammar {
+    functions: &["function_definition"],
+    nested: &["function_definition", "lambda", "class_definition"],
+    control: &[
+        "if_statement",
+        "for_statement",
+        "while_statement",
+        "try_statement",
+        "with_statement",
+        "match_statement",
+    ],
+    decisions: &[
+        "if_statement",
+        "elif_clause",
+        "for_statement",
+        "while_statement",
+        "except_clause",
+        "case_clause",
+        "conditional_expression",
+        "boolean_operator",
+        "for_in_clause",
+        "if_clause",
+    ],
+};
+
+const JAVASCRIPT: Grammar = Grammar {
+    functions: &[
+        "function_declaration",
+        "function_expression",
+        "function",
+        "generator_function_declaration",
+        "generator_function",
+        "arrow_function",
+        "method_definition",
+    ],
+    nested: &[
+        "function_declaration",
+        "function_expression",
+        "function",
+        "generator_function_declaration",
+        "generator_function",
+        "arrow_function",
+        "method_definition",
+        "class_declaration",
+        "class",
+    ],
+    control: &[
+        "if_statement",
+        "for_statement",
+        "for_in_statement",
+        "while_statement",
+        "do_statement",
+        "switch_statement",
+        "try_statement",
+    ],
+    decisions: &[
+        "if_statement",
+        "for_statement",
+        "for_in_statement",
+        "while_statement",
+        "do_statement",
+        "switch_case",
+        "catch_clause",
+        "ternary_expression",
+    ],
+};
+
+const C: Grammar = Grammar {
+    functions: &["function_definition"],
+    nested: &[],
+    control: &[
+        "if_statement",
+        "for_statement",
+        "while_statement",
+        "do_statement",
+        "switch_statement",
+    ],
+    decisions: &[
+        "if_statement",
+        "for_statement",
+        "while_statement",
+        "do_statement",
+        "case_statement",
+        "conditional_expression",
+    ],
+};
+
+/// Shape of a function defined at some lines of a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionShape {
+    pub start_line: usize,
+    pub end_line: usize,
+    pub metrics: FunctionMetrics,
+}
+
+/// An `if` in the `else` branch of another, which reads as a sibling
+fn is_else_if(node: Node) -> bool {
+    let Some(parent) = node.parent() else {
+        return false;
+    };
+    parent.kind() == "else_clause"
+        || (parent.kind() == "if_statement" && parent.child_by_field_name("alternative") == Some(node))
+}
+
+/// Whether a node adds a path through its function
+fn is_decision(node: Node, grammar: &Grammar) -> bool {
+    match node.kind() {
+        // `default:` shares the node kind of C cases but has no value
+        "case_statement" => node.child_by_field_name("value").is_some(),
+        "binary_expression" => node
+            .child_by_field_name("operator")
+            .is_some_and(|operator| matches!(operator.kind(), "&&" | "||" | "??")),
+        kind => grammar.decisions.contains(&kind),
+    }
+}
+
+/// Add the nesting, returns and decisions below `node` to `metrics`, skipping nested functions
+fn measure(node: Node, grammar: &Grammar, depth: usize, metrics: &mut FunctionMetrics) {
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        if grammar.nested.contains(&child.kind()) {
+            continue;
+        }
+        if child.kind() == "return_statement" {
+            metrics.returns += 1;
+        }
+        if is_decision(child, grammar) {
+            metrics.complexity += 1;
+        }
+        let depth = if grammar.control.contains(&child.kind()) && !(child.kind() == "if_statement" && is_else_if(child)) {
+            depth + 1
+        } else {
+            depth
+        };
+        metrics.max_nesting = metrics.max_nesting.max(depth);
+        measure(child, grammar, depth, metrics);
+    }
+}
+
+fn find(root: Node, grammar: &Grammar, parameters: impl Fn(Node) -> usize) -> Vec<FunctionShape> {
+    let mut shapes = Vec::new();
+    walk(root, &mut |node| {
+        if !grammar.functions.contains(&node.kind()) {
+            return;
+        }
+        let Some(body) = node.child_by_field_name("body") else {
+            return;
+        };
+        let start_line = node.start_position().row + 1;
+        let end_line = node.end_position().row + 1;
+        let mut metrics = FunctionMetrics {
+            length: end_line - start_line + 1,
+            parameters: parameters(node),
+            complexity: 1,
+            ..FunctionMetrics::default()
+        };
+        measure(body, grammar, 0, &mut metrics);
+        shapes.push(FunctionShape {
+            start_line,
+            end_line,
+            metrics,
+        });
+    });
+    shapes
+}
+
+/// Shapes of the `def`s in Python, leaving out `self` and `cls`
+pub fn find_python(root: Node, source: &str) -> Vec<FunctionShape> {
+    find(root, &PYTHON, |node| {
+        let Some(parameters) = node.child_by_field_name("parameters") else {
+            return 0;
+        };
+        let mut cursor = parameters.walk();
+        parameters
+            .named_children(&mut cursor)
+            .filter(|p| !matches!(p.kind(), "keyword_separator" | "positional_separator" | "comment"))
+            .enumerate()
+            .filter(|(i, p)| {
+                let name = if p.kind() == "typed_parameter" { p.named_child(0) } else { Some(*p) };
+                *i > 0 || !matches!(text(name, source), Some("self" | "cls"))
+            })
+            .count()
+    })
+}
+
+/// Shapes of functions, arrow functions and methods in JavaScript or TypeScript
+pub fn find_javascript(root: Node) -> Vec<FunctionShape> {
+    find(root, &JAVASCRIPT, |node| {
+        if node.child_by_field_name("parameter").is_some() {
+            return 1;
+        }
+        let Some(parameters) = node.child_by_field_name("parameters") else {
+            return 0;
+        };
+        let mut cursor = parameters.walk();
+        parameters.named_children(&mut cursor).filter(|p| p.kind() != "comment").count()
+    })
+}
+
+/// Shapes of function definitions in C, where `(void)` takes no parameters
+pub fn find_c(root: Node, source: &str) -> Vec<FunctionShape> {
+    find(root, &C, |node| {
+        let mut declarator = node.child_by_field_name("declarator");
+        while let Some(d) = declarator.filter(|d| d.kind() != "function_declarator") {
+            declarator = d.child_by_field_name("declarator");
+        }
+        let Some(parameters) = declarator.and_then(|d| d.child_by_field_name("parameters")) else {
+            return 0;
+        };
+        let mut cursor = parameters.walk();
+        let declared: Vec<Node> = parameters.named_children(&mut cursor).filter(|p| p.kind() != "comment").collect();
+        match declared.as_slice() {
+            [only] if text(Some(*only), source) == Some("void") => 0,
+            _ => declared.len(),
+        }
+    })
+}
+
+/// Record the shape of each function and method node in the file's metrics
+///
+/// A node takes the shape starting on its first line, the one ending
+/// closest to its last line when several do. The file's complexity counts
+/// the decisions of all its functions, matched to a node or not.
+pub fn apply(parsed: &mut ParsedFile, shapes: Vec<FunctionShape>) {
+    parsed.metrics.complexity = 1 + shapes.iter().map(|shape| shape.metrics.complexity - 1).sum::<usize>();
+    for node in &parsed.nodes {
+        if !matches!(node.node_type, NodeType::Function | NodeType::Method) {
+            continue;
+        }
+        let shape = shapes
+            .iter()
+            .filter(|shape| shape.start_line == node.start_line)
+            .min_by_key(|shape| shape.end_line.abs_diff(node.end_line));
+        if let Some(shape) = shape {
+            parsed.metrics.function_metrics.insert(node.id.clone(), shape.metrics);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tree_sitter::Parser as TSParser;
+
+    #[cfg(feature = "python")]
+    #[test]
+    fn test_python_function_shapes() {
+        let source = "class Store:\n    def save(self, key, value, *, ttl=None):\n        if key:\n            for v in value:\n                if v:\n                    return 1\n        elif ttl:\n            return 2\n        def inner():\n            if True:\n                if True:\n                    return 3\n        return 0\n";
+        let mut parser = TSParser::new();
+        parser.set_language(tree_sitter_python::language()).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+
+        let shapes = find_python(tree.root_node(), source);
+        assert_eq!(shapes.len(), 2);
+        assert_eq!((shapes[0].start_line, shapes[0].end_line), (2, 13));
+        assert_eq!(
+            shapes[0].metrics,
+            FunctionMetrics {
+                length: 12,
+                parameters: 3,
+                max_nesting: 3,
+                returns: 3,
+                complexity: 5,
+            }
+        );
+        assert_eq!((shapes[1].metrics.max_nesting, shapes[1].metrics.complexity), (2, 3));
+    }
+
+    #[cfg(feature = "js")]
+    #[test]
+    fn test_javascript_function_shapes() {
+        let source = "function pick(a, b, c) {\n  if (a) {\n    return b;\n  } else if (b) {\n    return c;\n  }\n  const f = x => x + 1;\n  return f(a);\n}\n";
+        let mut parser = TSParser::new();
+        parser.set_language(tree_sitter_javascript::language()).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+
+        let shapes = find_javascript(tree.root_node());
+        assert_eq!(shapes.len(), 2);
+        assert_eq!((shapes[0].metrics.parameters, shapes[0].metrics.max_nesting, shapes[0].metrics.returns), (3, 1, 3));
+        assert_eq!(shapes[0].metrics.complexity, 3);
+        assert_eq!((shapes[1].metrics.parameters, shapes[1].metrics.length), (1, 1));
+    }
+
+    #[cfg(feature = "c")]
+    #[test]
+    fn test_c_function_shapes_link_nodes() {
+        let source = "int main(void) {\n  return 0;\n}\n\nstatic char *join(const char *a, const char *b, ...) {\n  while (*a) {\n    a++;\n  }\n  return 0;\n}\n";
+        let mut parser = TSParser::new();
+        parser.set_language(tree_sitter_c::language()).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let shapes = find_c(tree.root_node(), source);
+        assert_eq!(shapes.iter().map(|s| s.metrics.parameters).collect::<Vec<_>>(), vec![0, 3]);
+
+        let node = |id: &str, node_type, start_line, end_line| og_types::AstNode {
+            id: id.to_string(),
+            node_type,
+            name: id.to_string(),
+            start_line,
+            end_line,
+            children: vec![],
+        };
+        let mut parsed = ParsedFile {
+            path: "main.c".into(),
+            language: og_types::Language::C,
+            nodes: vec![node("file:main.c", NodeType::File, 1, 10), node("function:join", NodeType::Function, 5, 10)],
+            relationships: vec![],
+            metrics: Default::default(),
+        };
+        apply(&mut parsed, shapes);
+        assert_eq!(parsed.metrics.complexity, 2);
+        assert_eq!(parsed.metrics.function_metrics.len(), 1);
+        assert_eq!(parsed.metrics.function_metrics["function:join"].max_nesting, 1);
+    }
+}
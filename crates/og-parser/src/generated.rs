@@ -0,0 +1,78 @@
+use std::path::Path;
+
+/// Lines at the top of a file searched for a generator's header
+const HEADER_LINES: usize = 20;
+
+/// Header text generators leave, matched case-insensitively
+const HEADER_MARKERS: &[&str] = &[
+    "@generated",
+    "do not edit",
+    "code generated by",
+    "autogenerated by",
+    "auto-generated by",
+    "this file was automatically generated",
+];
+
+/// Directories that only hold generated code
+const GENERATED_DIRS: &[&str] = &["__generated__", "gen-py", "gen-cpp", "gen-js", "gen-go", "gen-java"];
+
+/// File name endings of protobuf, gRPC and Thrift outputs
+const GENERATED_SUFFIXES: &[&str] = &[
+    ".pb.go",
+    ".pb.h",
+    ".pb.cc",
+    ".pb.c",
+    "_pb2.py",
+    "_pb2_grpc.py",
+    "_pb.js",
+    "_pb.d.ts",
+    "_grpc_pb.js",
+    ".min.js",
+    ".min.css",
+    "_types.thrift.js",
+];
+
+/// Whether a file was written by a code generator rather than by hand
+///
+/// Looks at the path for generator output directories and file names, then
+/// at the first lines for a header such as `@generated` or `DO NOT EDIT`.
+pub fn is_generated(path: &Path, content: &str) -> bool {
+    let in_generated_dir = path
+        .components()
+        .any(|component| GENERATED_DIRS.iter().any(|dir| component.as_os_str() == *dir));
+    let generated_name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| GENERATED_SUFFIXES.iter().any(|suffix| name.ends_with(suffix)));
+    if in_generated_dir || generated_name {
+        return true;
+    }
+
+    content.lines().take(HEADER_LINES).any(|line| {
+        let line = line.to_ascii_lowercase();
+        HEADER_MARKERS.iter().any(|marker| line.contains(marker))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detects_generated_files() {
+        assert!(is_generated(Path::new("api/user.pb.go"), "package api\n"));
+        assert!(is_generated(Path::new("proto/user_pb2.py"), ""));
+        assert!(is_generated(Path::new("src/__generated__/schema.ts"), ""));
+        assert!(is_generated(Path::new("gen-py/service/ttypes.py"), ""));
+        assert!(is_generated(Path::new("static/app.min.js"), ""));
+        assert!(is_generated(
+            Path::new("src/parser.c"),
+            "/* Code generated by bison. DO NOT EDIT. */\nint yyparse(void);\n"
+        ));
+        assert!(is_generated(Path::new("schema.ts"), "// @generated\nexport type Id = string;\n"));
+
+        assert!(!is_generated(Path::new("src/generator.py"), "def generate():\n    pass\n"));
+        let late_marker = format!("{}# @generated\n", "x = 1\n".repeat(HEADER_LINES));
+        assert!(!is_generated(Path::new("src/app.py"), &late_marker));
+    }
+}
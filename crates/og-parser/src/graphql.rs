@@ -0,0 +1,762 @@
+use og_types::{AstNode, EngineResult, Language, NodeType, ParsedFile, Relationship, RelationshipType};
+use std::collections::HashMap;
+use std::path::Path;
+use tree_sitter::Node;
+
+use crate::dynamic_imports::string_literal;
+use crate::env_vars::{add_node_once, enclosing_function, relate_once, text, walk};
+use crate::parser_trait::Parser;
+
+/// Prefix of the placeholder node standing for a root field until resolvers are linked
+pub const UNRESOLVED_FIELD_PREFIX: &str = "graphql:";
+
+/// Template tags whose contents are GraphQL documents
+const TAGS: &[&str] = &["gql", "graphql"];
+
+/// Root operation type a field is selected on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperationKind {
+    Query,
+    Mutation,
+    Subscription,
+}
+
+impl OperationKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OperationKind::Query => "query",
+            OperationKind::Mutation => "mutation",
+            OperationKind::Subscription => "subscription",
+        }
+    }
+
+    /// Kind named by an operation keyword such as `mutation`
+    fn from_keyword(keyword: &str) -> Option<Self> {
+        match keyword {
+            "query" => Some(OperationKind::Query),
+            "mutation" => Some(OperationKind::Mutation),
+            "subscription" => Some(OperationKind::Subscription),
+            _ => None,
+        }
+    }
+
+    /// Kind named by a root type, resolver map key or decorator such as `Mutation`
+    fn from_type_name(name: &str) -> Option<Self> {
+        Self::from_keyword(&name.to_ascii_lowercase())
+    }
+}
+
+/// A query, mutation or subscription and the root fields it selects
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Operation {
+    pub kind: OperationKind,
+    pub name: Option<String>,
+    pub line: usize,
+    pub fields: Vec<String>,
+}
+
+/// A server function resolving a root field
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Resolver {
+    pub kind: OperationKind,
+    pub field: String,
+    /// Name of the function node that resolves the field
+    pub function: String,
+    pub line: usize,
+}
+
+/// Client operations and server resolvers found in one file
+#[derive(Debug, Clone, Default)]
+pub struct GraphqlItems {
+    pub operations: Vec<Operation>,
+    pub resolvers: Vec<Resolver>,
+}
+
+/// Id of the placeholder for a root field, shared by every file that selects or resolves it
+pub fn field_id(kind: OperationKind, field: &str) -> String {
+    format!("{}{}.{}", UNRESOLVED_FIELD_PREFIX, kind.as_str(), field)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token<'a> {
+    Name(&'a str),
+    Punct(char),
+    Spread,
+}
+
+/// Names and punctuators of a GraphQL document with their 0-based lines,
+/// leaving out comments, strings and commas
+fn tokens(document: &str) -> Vec<(Token<'_>, usize)> {
+    let mut tokens = Vec::new();
+    let mut line = 0;
+    let mut chars = document.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        match c {
+            '\n' => line += 1,
+            '#' => {
+                while chars.next_if(|&(_, c)| c != '\n').is_some() {}
+            }
+            '"' => {
+                let block = document[start..].starts_with("\"\"\"");
+                let close = if block { "\"\"\"" } else { "\"" };
+                let body = start + close.len();
+                let end = document[body..]
+                    .match_indices(close)
+                    .map(|(i, _)| body + i)
+                    .find(|&i| block || !document[..i].ends_with('\\'))
+                    .map_or(document.len(), |i| i + close.len());
+                line += document[start..end].matches('\n').count();
+                while chars.next_if(|&(i, _)| i < end).is_some() {}
+            }
+            '.' if document[start..].starts_with("...") => {
+                chars.next();
+                chars.next();
+                tokens.push((Token::Spread, line));
+            }
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut end = start + c.len_utf8();
+                while let Some((i, c)) = chars.next_if(|&(_, c)| c.is_alphanumeric() || c == '_') {
+                    end = i + c.len_utf8();
+                }
+                tokens.push((Token::Name(&document[start..end]), line));
+            }
+            c if c.is_whitespace() || c == ',' => {}
+            c => tokens.push((Token::Punct(c), line)),
+        }
+    }
+    tokens
+}
+
+/// Index just past the group opened at `start`
+fn skip_group(tokens: &[(Token, usize)], start: usize) -> usize {
+    let (open, close) = match tokens[start].0 {
+        Token::Punct('(') => ('(', ')'),
+        _ => ('{', '}'),
+    };
+    let mut depth = 0;
+    for (i, (token, _)) in tokens.iter().enumerate().skip(start) {
+        if *token == Token::Punct(open) {
+            depth += 1;
+        } else if *token == Token::Punct(close) {
+            depth -= 1;
+            if depth == 0 {
+                return i + 1;
+            }
+        }
+    }
+    tokens.len()
+}
+
+/// Index of the `{` opening the body of the definition starting at `start`
+fn body(tokens: &[(Token, usize)], mut i: usize) -> Option<usize> {
+    while i < tokens.len() {
+        match tokens[i].0 {
+            Token::Punct('{') => return Some(i),
+            Token::Punct('(') => i = skip_group(tokens, i),
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+/// Fields of the selection set opened at `start`, without aliases, and the index past it
+fn root_fields(tokens: &[(Token, usize)], start: usize) -> (Vec<String>, usize) {
+    let mut fields: Vec<String> = Vec::new();
+    let mut depth = 0;
+    let mut i = start;
+    while i < tokens.len() {
+        match tokens[i].0 {
+            Token::Punct('{') => depth += 1,
+            Token::Punct('}') => {
+                depth -= 1;
+                if depth == 0 {
+                    return (fields, i + 1);
+                }
+            }
+            Token::Punct('(') => {
+                i = skip_group(tokens, i);
+                continue;
+            }
+            // Skip the directive or fragment name that follows
+            Token::Punct('@') => i += 1,
+            Token::Spread => {
+                if let Some((Token::Name(next), _)) = tokens.get(i + 1) {
+                    i += if *next == "on" { 2 } else { 1 };
+                }
+            }
+            Token::Name(name) if depth == 1 => {
+                let field = match (tokens.get(i + 1), tokens.get(i + 2)) {
+                    (Some((Token::Punct(':'), _)), Some((Token::Name(field), _))) => {
+                        i += 2;
+                        *field
+                    }
+                    _ => name,
+                };
+                if !fields.iter().any(|f| f == field) {
+                    fields.push(field.to_string());
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    (fields, i)
+}
+
+/// Operations in a GraphQL document, with 0-based lines
+///
+/// Fragments and schema definitions are skipped. A bare selection set is an
+/// anonymous query.
+pub fn operations(document: &str) -> Vec<Operation> {
+    let tokens = tokens(document);
+    let mut operations = Vec::new();
+    let mut in_definition = false;
+    let mut i = 0;
+    while i < tokens.len() {
+        let (token, line) = tokens[i];
+        let (kind, name) = match token {
+            Token::Name(keyword) => match OperationKind::from_keyword(keyword) {
+                Some(kind) => match tokens.get(i + 1) {
+                    Some((Token::Name(name), _)) => (kind, Some(name.to_string())),
+                    _ => (kind, None),
+                },
+                None => {
+                    in_definition = true;
+                    i += 1;
+                    continue;
+                }
+            },
+            Token::Punct('{') if !in_definition => (OperationKind::Query, None),
+            Token::Punct('{') => {
+                in_definition = false;
+                i = skip_group(&tokens, i);
+                continue;
+            }
+            _ => {
+                i += 1;
+                continue;
+            }
+        };
+
+        let Some(start) = body(&tokens, i) else {
+            break;
+        };
+        let (fields, end) = root_fields(&tokens, start);
+        operations.push(Operation { kind, name, line, fields });
+        in_definition = false;
+        i = end;
+    }
+    operations
+}
+
+/// Contents of a template literal with its substitutions blanked out
+fn template_text(template: Node, source: &str) -> Option<String> {
+    let raw = text(Some(template), source)?;
+    let mut document = raw.to_string();
+    let mut cursor = template.walk();
+    for substitution in template.named_children(&mut cursor).filter(|c| c.kind() == "template_substitution") {
+        let range = substitution.start_byte() - template.start_byte()..substitution.end_byte() - template.start_byte();
+        let blank: String = raw[range.clone()].chars().map(|c| if c == '\n' { '\n' } else { ' ' }).collect();
+        document.replace_range(range, &blank);
+    }
+    Some(document.trim_matches('`').to_string())
+}
+
+/// Key of an object pair, without quotes
+fn key_name(pair: Node, source: &str) -> Option<String> {
+    let key = pair.child_by_field_name("key")?;
+    string_literal(key, source).or_else(|| text(Some(key), source).map(str::to_string))
+}
+
+/// Resolvers of a `Query: { ... }` style resolver map entry
+fn resolver_map(kind: OperationKind, map: Node, source: &str, resolvers: &mut Vec<Resolver>) {
+    let mut cursor = map.walk();
+    for entry in map.named_children(&mut cursor) {
+        let line = entry.start_position().row + 1;
+        let (field, function) = match entry.kind() {
+            "pair" => {
+                let Some(field) = key_name(entry, source) else {
+                    continue;
+                };
+                let function = match entry.child_by_field_name("value") {
+                    Some(value) if value.kind() == "identifier" => text(Some(value), source).map(str::to_string),
+                    Some(value) if matches!(value.kind(), "arrow_function" | "function" | "function_expression") => {
+                        Some(field.clone())
+                    }
+                    _ => None,
+                };
+                let Some(function) = function else {
+                    continue;
+                };
+                (field, function)
+            }
+            "method_definition" | "shorthand_property_identifier" => {
+                let name = entry.child_by_field_name("name").or(Some(entry));
+                let Some(name) = text(name, source) else {
+                    continue;
+                };
+                (name.to_string(), name.to_string())
+            }
+            _ => continue,
+        };
+        resolvers.push(Resolver { kind, field, function, line });
+    }
+}
+
+/// Operation kind of a `@Query()` style decorator on a class method
+fn decorator_kind(method: Node, source: &str) -> Option<OperationKind> {
+    let mut decorators = Vec::new();
+    let mut cursor = method.walk();
+    decorators.extend(method.children(&mut cursor).filter(|c| c.kind() == "decorator"));
+    let mut previous = method.prev_named_sibling();
+    while let Some(decorator) = previous.filter(|p| p.kind() == "decorator") {
+        decorators.push(decorator);
+        previous = decorator.prev_named_sibling();
+    }
+    decorators.into_iter().find_map(|decorator| {
+        let name = text(Some(decorator), source)?.trim_start_matches('@');
+        let name = name.split(['(', ' ']).next()?;
+        OperationKind::from_type_name(name).filter(|_| name.starts_with(char::is_uppercase))
+    })
+}
+
+/// `gql` tagged operations, resolver maps and decorated resolver methods in
+/// JavaScript or TypeScript
+pub fn find_javascript(root: Node, source: &str) -> GraphqlItems {
+    let mut items = GraphqlItems::default();
+    walk(root, &mut |node| match node.kind() {
+        "call_expression" => {
+            let tagged = text(node.child_by_field_name("function"), source).is_some_and(|f| TAGS.contains(&f));
+            let Some(template) = node.child_by_field_name("arguments").filter(|a| tagged && a.kind() == "template_string")
+            else {
+                return;
+            };
+            let Some(document) = template_text(template, source) else {
+                return;
+            };
+            let offset = template.start_position().row + 1;
+            items.operations.extend(operations(&document).into_iter().map(|operation| Operation {
+                line: operation.line + offset,
+                ..operation
+            }));
+        }
+        "pair" => {
+            let kind = key_name(node, source).and_then(|key| OperationKind::from_type_name(&key));
+            if let (Some(kind), Some(map)) = (kind, node.child_by_field_name("value").filter(|v| v.kind() == "object")) {
+                resolver_map(kind, map, source, &mut items.resolvers);
+            }
+        }
+        "method_definition" => {
+            let Some(kind) = decorator_kind(node, source) else {
+                return;
+            };
+            if let Some(name) = text(node.child_by_field_name("name"), source) {
+                items.resolvers.push(Resolver {
+                    kind,
+                    field: name.to_string(),
+                    function: name.to_string(),
+                    line: node.start_position().row + 1,
+                });
+            }
+        }
+        _ => {}
+    });
+    items
+}
+
+/// `created_at` as the `createdAt` field Graphene and Strawberry expose
+fn camel_case(name: &str) -> String {
+    let mut parts = name.split('_').filter(|part| !part.is_empty());
+    let mut camel = parts.next().unwrap_or_default().to_string();
+    for part in parts {
+        let mut chars = part.chars();
+        camel.extend(chars.next().map(|c| c.to_ascii_uppercase()));
+        camel.push_str(chars.as_str());
+    }
+    camel
+}
+
+/// Decorators and function definition of a possibly decorated definition
+fn decorated(node: Node) -> (Vec<Node>, Option<Node>) {
+    if node.kind() != "decorated_definition" {
+        return (Vec::new(), Some(node).filter(|n| n.kind() == "function_definition"));
+    }
+    let mut cursor = node.walk();
+    let decorators = node.named_children(&mut cursor).filter(|c| c.kind() == "decorator").collect();
+    let definition = node.child_by_field_name("definition").filter(|d| d.kind() == "function_definition");
+    (decorators, definition)
+}
+
+/// Ariadne `@query.field("name")` functions and Graphene or Strawberry
+/// resolvers on `Query`, `Mutation` and `Subscription` classes in Python
+pub fn find_python(root: Node, source: &str) -> GraphqlItems {
+    let mut items = GraphqlItems::default();
+    walk(root, &mut |node| match node.kind() {
+        "decorated_definition" => {
+            let (decorators, Some(function)) = decorated(node) else {
+                return;
+            };
+            let Some(name) = text(function.child_by_field_name("name"), source) else {
+                return;
+            };
+            for decorator in decorators {
+                let Some(call) = decorator.named_child(0).filter(|c| c.kind() == "call") else {
+                    continue;
+                };
+                let Some((object, method)) = text(call.child_by_field_name("function"), source).and_then(|f| f.rsplit_once('.'))
+                else {
+                    continue;
+                };
+                let kind = OperationKind::from_keyword(object).filter(|_| method == "field");
+                let field = call
+                    .child_by_field_name("arguments")
+                    .and_then(|arguments| arguments.named_child(0))
+                    .and_then(|argument| string_literal(argument, source));
+                if let (Some(kind), Some(field)) = (kind, field) {
+                    items.resolvers.push(Resolver {
+                        kind,
+                        field,
+                        function: name.to_string(),
+                        line: function.start_position().row + 1,
+                    });
+                }
+            }
+        }
+        "class_definition" => {
+            let Some(kind) = text(node.child_by_field_name("name"), source).and_then(OperationKind::from_type_name) else {
+                return;
+            };
+            let Some(body) = node.child_by_field_name("body") else {
+                return;
+            };
+            let mut cursor = body.walk();
+            for member in body.named_children(&mut cursor) {
+                let (decorators, Some(function)) = decorated(member) else {
+                    continue;
+                };
+                let Some(name) = text(function.child_by_field_name("name"), source) else {
+                    continue;
+                };
+                let exposed = decorators.iter().any(|decorator| {
+                    text(Some(*decorator), source).is_some_and(|d| {
+                        let d = d.split('(').next().unwrap_or(d);
+                        ["field", "mutation", "subscription"].iter().any(|suffix| d.ends_with(suffix))
+                    })
+                });
+                let field = match name.strip_prefix("resolve_") {
+                    Some(field) => field,
+                    None if exposed => name,
+                    None => continue,
+                };
+                items.resolvers.push(Resolver {
+                    kind,
+                    field: camel_case(field),
+                    function: name.to_string(),
+                    line: function.start_position().row + 1,
+                });
+            }
+        }
+        _ => {}
+    });
+    items
+}
+
+/// Add a node for each operation with a placeholder edge per root field, and
+/// a placeholder edge from each resolved field to its resolver function, or
+/// to the class declaring it when the function has no node
+pub fn apply(parsed: &mut ParsedFile, items: GraphqlItems) {
+    let path = parsed.path.display().to_string();
+    for operation in items.operations {
+        let Some(parent) = enclosing_function(parsed, operation.line) else {
+            continue;
+        };
+        let kind = operation.kind.as_str();
+        let id = match &operation.name {
+            Some(name) => format!("operation:{}:{}", path, name),
+            None => format!("operation:{}:{}@{}", path, kind, operation.line),
+        };
+        add_node_once(parsed, &id, NodeType::Operation, operation.name.as_deref().unwrap_or(kind), operation.line);
+        relate_once(parsed, parent, id.clone(), RelationshipType::Contains);
+        for field in &operation.fields {
+            relate_once(parsed, id.clone(), field_id(operation.kind, field), RelationshipType::ResolvedBy);
+        }
+    }
+
+    for resolver in items.resolvers {
+        let function = parsed
+            .nodes
+            .iter()
+            .filter(|n| matches!(n.node_type, NodeType::Function | NodeType::Method) && n.name == resolver.function)
+            .min_by_key(|n| n.start_line.abs_diff(resolver.line))
+            .or_else(|| {
+                // Parsers that skip methods still have the resolver class
+                parsed
+                    .nodes
+                    .iter()
+                    .filter(|n| matches!(n.node_type, NodeType::Class))
+                    .filter(|n| n.start_line <= resolver.line && resolver.line <= n.end_line)
+                    .min_by_key(|n| n.end_line - n.start_line)
+            })
+            .map(|n| n.id.clone());
+        if let Some(function) = function {
+            relate_once(parsed, field_id(resolver.kind, &resolver.field), function, RelationshipType::ResolvedBy);
+        }
+    }
+}
+
+/// Point each operation straight at the resolvers of the root fields it selects
+///
+/// Fields without a known resolver are dropped, and a field resolved in
+/// several places links to all of them.
+pub fn link_resolvers(files: &mut [ParsedFile]) {
+    let mut resolvers: HashMap<String, Vec<String>> = HashMap::new();
+    for file in files.iter_mut() {
+        file.relationships.retain(|rel| {
+            if !rel.source.starts_with(UNRESOLVED_FIELD_PREFIX) {
+                return true;
+            }
+            resolvers.entry(rel.source.clone()).or_default().push(rel.target.clone());
+            false
+        });
+    }
+
+    for file in files.iter_mut() {
+        let mut resolved = Vec::new();
+        file.relationships.retain(|rel| {
+            if !rel.target.starts_with(UNRESOLVED_FIELD_PREFIX) {
+                return true;
+            }
+            for resolver in resolvers.get(&rel.target).into_iter().flatten() {
+                resolved.push(Relationship {
+                    source: rel.source.clone(),
+                    target: resolver.clone(),
+                    relationship_type: RelationshipType::ResolvedBy,
+                });
+            }
+            false
+        });
+        file.relationships.extend(resolved);
+    }
+}
+
+/// Parser for `.graphql` and `.gql` documents
+///
+/// Produces a file node and an operation node per query, mutation and
+/// subscription. Schema definitions and fragments are not represented.
+pub struct GraphqlParser;
+
+impl GraphqlParser {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for GraphqlParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Parser for GraphqlParser {
+    fn supported_extensions(&self) -> &[&str] {
+        Language::Graphql.extensions()
+    }
+
+    fn language(&self) -> Language {
+        Language::Graphql
+    }
+
+    fn parse(&self, path: &Path, content: &str) -> EngineResult<ParsedFile> {
+        let mut parsed = ParsedFile {
+            path: path.to_path_buf(),
+            language: Language::Graphql,
+            nodes: vec![AstNode {
+                id: format!("file:{}", path.display()),
+                name: path.file_name().and_then(|n| n.to_str()).unwrap_or_default().to_string(),
+                node_type: NodeType::File,
+                start_line: 0,
+                end_line: content.lines().count(),
+                children: vec![],
+            }],
+            relationships: Vec::new(),
+            metrics: Default::default(),
+        };
+        let operations = operations(content)
+            .into_iter()
+            .map(|operation| Operation {
+                line: operation.line + 1,
+                ..operation
+            })
+            .collect();
+        apply(
+            &mut parsed,
+            GraphqlItems {
+                operations,
+                resolvers: Vec::new(),
+            },
+        );
+        Ok(parsed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tree_sitter::Parser as TSParser;
+
+    fn fields(operation: &Operation) -> Vec<&str> {
+        operation.fields.iter().map(String::as_str).collect()
+    }
+
+    fn resolved(resolvers: &[Resolver]) -> Vec<(&str, &str, &str)> {
+        resolvers
+            .iter()
+            .map(|r| (r.kind.as_str(), r.field.as_str(), r.function.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_operations_select_root_fields() {
+        let document = r#"
+# Current user and their posts
+query Viewer($id: ID!, $filter: Filter = { limit: 10 }) {
+  me: user(id: $id) { name posts { title } }
+  ...ViewerFields
+  settings @include(if: true) { theme }
+}
+
+fragment ViewerFields on Query { notifications { id } }
+
+type Query { user(id: ID!): User }
+
+mutation { createPost(input: { title: "query { hidden }" }) { id } }
+
+{ health }
+"#;
+        let operations = operations(document);
+
+        assert_eq!(operations.len(), 3);
+        assert_eq!((operations[0].kind, operations[0].name.as_deref(), operations[0].line), (OperationKind::Query, Some("Viewer"), 2));
+        assert_eq!(fields(&operations[0]), vec!["user", "settings"]);
+        assert_eq!((operations[1].kind, operations[1].name.as_deref()), (OperationKind::Mutation, None));
+        assert_eq!(fields(&operations[1]), vec!["createPost"]);
+        assert_eq!((operations[2].kind, fields(&operations[2])), (OperationKind::Query, vec!["health"]));
+    }
+
+    #[cfg(feature = "js")]
+    #[test]
+    fn test_find_javascript_operations_and_resolvers() {
+        let source = r#"
+const GET_USER = gql`
+  query GetUser { user(id: 1) { ...${USER_FIELDS} } }
+`;
+const resolvers = {
+  Query: {
+    user: (parent, args) => db.user(args.id),
+    posts: listPosts,
+    async feed() { return []; },
+  },
+  Mutation: { 'createUser': function (parent, args) { return db.insert(args); } },
+  User: { name: (user) => user.name },
+};
+"#;
+        let mut parser = TSParser::new();
+        parser.set_language(tree_sitter_javascript::language()).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let items = find_javascript(tree.root_node(), source);
+
+        assert_eq!(items.operations.len(), 1);
+        assert_eq!((items.operations[0].name.as_deref(), items.operations[0].line), (Some("GetUser"), 3));
+        assert_eq!(fields(&items.operations[0]), vec!["user"]);
+        assert_eq!(
+            resolved(&items.resolvers),
+            vec![
+                ("query", "user", "user"),
+                ("query", "posts", "listPosts"),
+                ("query", "feed", "feed"),
+                ("mutation", "createUser", "createUser"),
+            ]
+        );
+    }
+
+    #[cfg(feature = "python")]
+    #[test]
+    fn test_find_python_resolvers() {
+        let source = r#"
+@query.field("user")
+def resolve_user_field(obj, info, id):
+    return db.get(id)
+
+class Query(graphene.ObjectType):
+    def resolve_created_posts(self, info):
+        return []
+
+    def helper(self):
+        pass
+
+@strawberry.type
+class Mutation:
+    @strawberry.mutation
+    def add_book(self, title: str) -> Book:
+        return Book(title)
+"#;
+        let mut parser = TSParser::new();
+        parser.set_language(tree_sitter_python::language()).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let items = find_python(tree.root_node(), source);
+
+        assert_eq!(
+            resolved(&items.resolvers),
+            vec![
+                ("query", "user", "resolve_user_field"),
+                ("query", "createdPosts", "resolve_created_posts"),
+                ("mutation", "addBook", "add_book"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_link_resolvers_connects_operations_across_files() {
+        let document = "query Feed { feed { id } me { id } }\nmutation Like { like(id: 1) }\n";
+        let client = GraphqlParser::new().parse(Path::new("web/feed.graphql"), document).unwrap();
+
+        let mut server = GraphqlParser::new().parse(Path::new("api/resolvers.js"), "").unwrap();
+        for (name, line) in [("feed", 3), ("likePost", 7)] {
+            server.nodes.push(AstNode {
+                id: format!("function:api/resolvers.js:{}", name),
+                name: name.to_string(),
+                node_type: NodeType::Function,
+                start_line: line,
+                end_line: line,
+                children: vec![],
+            });
+        }
+        apply(
+            &mut server,
+            GraphqlItems {
+                operations: Vec::new(),
+                resolvers: vec![
+                    Resolver { kind: OperationKind::Query, field: "feed".into(), function: "feed".into(), line: 3 },
+                    Resolver { kind: OperationKind::Mutation, field: "like".into(), function: "likePost".into(), line: 7 },
+                ],
+            },
+        );
+
+        let mut files = vec![client, server];
+        link_resolvers(&mut files);
+
+        let links: Vec<(&str, &str)> = files
+            .iter()
+            .flat_map(|file| &file.relationships)
+            .filter(|rel| rel.relationship_type.as_str() == "RESOLVED_BY")
+            .map(|rel| (rel.source.as_str(), rel.target.as_str()))
+            .collect();
+        assert_eq!(
+            links,
+            vec![
+                ("operation:web/feed.graphql:Feed", "function:api/resolvers.js:feed"),
+                ("operation:web/feed.graphql:Like", "function:api/resolvers.js:likePost"),
+            ]
+        );
+    }
+}
@@ -1,14 +1,37 @@
-use std::path::{Path, PathBuf};
+use std::collections::HashMap;
+use std::ffi::OsString;
 use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Entries of a directory, true for subdirectories, None if it cannot be read
+type Listing = Option<Arc<HashMap<OsString, bool>>>;
 
 /// Resolves import paths to actual file paths
+///
+/// One resolver is shared by every file of a parse run. It memoizes each
+/// resolution by importing directory and specifier, and reads each directory
+/// once, so the files it sees are a snapshot taken during the run; call
+/// [`ImportResolver::clear`] before a run that must see later changes.
 pub struct ImportResolver {
     base_path: PathBuf,
+    resolved: Mutex<HashMap<(PathBuf, String), Option<PathBuf>>>,
+    listings: Mutex<HashMap<PathBuf, Listing>>,
 }
 
 impl ImportResolver {
     pub fn new(base_path: PathBuf) -> Self {
-        Self { base_path }
+        Self {
+            base_path,
+            resolved: Mutex::new(HashMap::new()),
+            listings: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Forget memoized resolutions and directory listings
+    pub fn clear(&self) {
+        self.resolved.lock().unwrap().clear();
+        self.listings.lock().unwrap().clear();
     }
 
     /// Resolve an import path relative to a source file
@@ -19,24 +42,70 @@ impl ImportResolver {
         }
 
         let source_dir = source_file.parent()?;
-        
-        // Try to resolve the import path
-        if let Some(resolved) = self.resolve_path(import_path, source_dir) {
-            // Convert to a file ID format matching what we use for nodes
-            return Some(format!("file:{}", resolved.display()));
-        }
+        let key = (source_dir.to_path_buf(), import_path.to_string());
+        let cached = self.resolved.lock().unwrap().get(&key).cloned();
+        let resolved = match cached {
+            Some(resolved) => resolved,
+            None => {
+                let resolved = self.resolve_path(import_path, source_dir);
+                self.resolved.lock().unwrap().insert(key, resolved.clone());
+                resolved
+            }
+        };
 
-        None
+        // Convert to a file ID format matching what we use for nodes
+        resolved.map(|resolved| format!("file:{}", resolved.display()))
     }
 
     /// Check if this is an external/node_modules import
-    fn is_external_import(&self, import_path: &str) -> bool {
+    pub fn is_external_import(&self, import_path: &str) -> bool {
         // External imports don't start with './' or '../' or '/'
         !import_path.starts_with('.')
             && !import_path.starts_with('/')
             && !import_path.starts_with('~')
     }
 
+    /// Entries of a directory, read once per snapshot
+    fn listing(&self, dir: &Path) -> Listing {
+        if let Some(listing) = self.listings.lock().unwrap().get(dir) {
+            return listing.clone();
+        }
+        let listing = fs::read_dir(dir).ok().map(|entries| {
+            let entries = entries
+                .filter_map(Result::ok)
+                .map(|entry| {
+                    // Follow symlinks, as the metadata of the path itself would
+                    let is_dir = match entry.file_type() {
+                        Ok(file_type) if file_type.is_symlink() => entry.path().is_dir(),
+                        Ok(file_type) => file_type.is_dir(),
+                        Err(_) => false,
+                    };
+                    (entry.file_name(), is_dir)
+                })
+                .collect();
+            Arc::new(entries)
+        });
+        self.listings.lock().unwrap().insert(dir.to_path_buf(), listing.clone());
+        listing
+    }
+
+    /// Whether a path is a directory (true) or other file (false), if it exists
+    fn entry_kind(&self, path: &Path) -> Option<bool> {
+        match (path.parent(), path.file_name()) {
+            (Some(dir), Some(name)) => self.listing(dir)?.get(name).copied(),
+            // Paths ending in `..` or a root are looked up directly
+            _ => fs::metadata(path).ok().map(|metadata| metadata.is_dir()),
+        }
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        self.entry_kind(path) == Some(false)
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        self.entry_kind(path) == Some(true)
+    }
+
     /// Try to resolve a path with various strategies
     fn resolve_path(&self, import_path: &str, source_dir: &Path) -> Option<PathBuf> {
         let base_path = if import_path.starts_with('/') {
@@ -55,45 +124,45 @@ impl ImportResolver {
         let extensions = [".ts", ".tsx", ".js", ".jsx", ".mjs", ".cjs"];
         
         // Strategy 1: Exact match
-        if candidate_base.exists() && candidate_base.is_file() {
+        if self.is_file(&candidate_base) {
             return Some(self.normalize_path(candidate_base));
         }
 
         // Strategy 2: Try with extensions
         for ext in &extensions {
             let with_ext = PathBuf::from(format!("{}{}", candidate_base.display(), ext));
-            if with_ext.exists() && with_ext.is_file() {
+            if self.is_file(&with_ext) {
                 return Some(self.normalize_path(with_ext));
             }
         }
 
+        if !self.is_dir(&candidate_base) {
+            return None;
+        }
+
         // Strategy 3: Directory with index file
-        if candidate_base.exists() && candidate_base.is_dir() {
-            for ext in &extensions {
-                let index_path = candidate_base.join(format!("index{}", ext));
-                if index_path.exists() && index_path.is_file() {
-                    return Some(self.normalize_path(index_path));
-                }
+        for ext in &extensions {
+            let index_path = candidate_base.join(format!("index{}", ext));
+            if self.is_file(&index_path) {
+                return Some(self.normalize_path(index_path));
             }
         }
 
         // Strategy 4: Check package.json for main field (for local packages)
-        if candidate_base.exists() && candidate_base.is_dir() {
-            let package_json = candidate_base.join("package.json");
-            if package_json.exists() {
-                if let Ok(contents) = fs::read_to_string(&package_json) {
-                    if let Ok(json) = serde_json::from_str::<serde_json::Value>(&contents) {
-                        if let Some(main) = json.get("main").and_then(|m| m.as_str()) {
-                            let main_path = candidate_base.join(main);
-                            if main_path.exists() {
-                                return Some(self.normalize_path(main_path));
-                            }
-                            // Try with extensions
-                            for ext in &extensions {
-                                let with_ext = PathBuf::from(format!("{}{}", main_path.display(), ext));
-                                if with_ext.exists() {
-                                    return Some(self.normalize_path(with_ext));
-                                }
+        let package_json = candidate_base.join("package.json");
+        if self.entry_kind(&package_json).is_some() {
+            if let Ok(contents) = fs::read_to_string(&package_json) {
+                if let Ok(json) = serde_json::from_str::<serde_json::Value>(&contents) {
+                    if let Some(main) = json.get("main").and_then(|m| m.as_str()) {
+                        let main_path = candidate_base.join(main);
+                        if self.entry_kind(&main_path).is_some() {
+                            return Some(self.normalize_path(main_path));
+                        }
+                        // Try with extensions
+                        for ext in &extensions {
+                            let with_ext = PathBuf::from(format!("{}{}", main_path.display(), ext));
+                            if self.entry_kind(&with_ext).is_some() {
+                                return Some(self.normalize_path(with_ext));
                             }
                         }
                     }
@@ -185,4 +254,21 @@ mod tests {
         assert_eq!(resolver.resolve_import("@types/node", &source_file), None);
         assert_eq!(resolver.resolve_import("lodash/debounce", &source_file), None);
     }
+
+    #[test]
+    fn test_resolutions_are_a_snapshot_until_cleared() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path().to_path_buf();
+        let source_file = base_path.join("main.ts");
+        fs::write(&source_file, "import { a } from './a';").unwrap();
+
+        let resolver = ImportResolver::new(base_path.clone());
+        assert_eq!(resolver.resolve_import("./a", &source_file), None);
+
+        // A file created mid-run is not seen until the caches are cleared
+        fs::write(base_path.join("a.ts"), "export const a = 1;").unwrap();
+        assert_eq!(resolver.resolve_import("./a", &source_file), None);
+        resolver.clear();
+        assert!(resolver.resolve_import("./a", &source_file).unwrap().ends_with("a.ts"));
+    }
 }
\ No newline at end of file
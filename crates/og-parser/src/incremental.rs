@@ -0,0 +1,580 @@
+use og_types::{AstNode, EngineResult, NodeType, ParsedFile, Relationship, RelationshipType};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tree_sitter::{InputEdit, Node, Point, Tree};
+
+use crate::{Parser, ParserEngine};
+
+/// Row and column of a byte offset, for tree-sitter edits
+fn point(source: &str, byte: usize) -> Point {
+    let before = &source[..byte];
+    let row = before.matches('\n').count();
+    let column = before.rfind('\n').map_or(byte, |newline| byte - newline - 1);
+    Point::new(row, column)
+}
+
+/// The single edit that turns `old` into `new`
+///
+/// The edit spans everything between the longest common prefix and the
+/// longest common suffix, kept on character boundaries. Returns `None`
+/// when the sources are equal.
+pub fn input_edit(old: &str, new: &str) -> Option<InputEdit> {
+    if old == new {
+        return None;
+    }
+    let mut prefix = old.bytes().zip(new.bytes()).take_while(|(a, b)| a == b).count();
+    while !old.is_char_boundary(prefix) || !new.is_char_boundary(prefix) {
+        prefix -= 1;
+    }
+    let max_suffix = old.len().min(new.len()) - prefix;
+    let mut suffix = old
+        .bytes()
+        .rev()
+        .zip(new.bytes().rev())
+        .take(max_suffix)
+        .take_while(|(a, b)| a == b)
+        .count();
+    while !old.is_char_boundary(old.len() - suffix) || !new.is_char_boundary(new.len() - suffix) {
+        suffix -= 1;
+    }
+
+    let old_end_byte = old.len() - suffix;
+    let new_end_byte = new.len() - suffix;
+    Some(InputEdit {
+        start_byte: prefix,
+        old_end_byte,
+        new_end_byte,
+        start_position: point(old, prefix),
+        old_end_position: point(old, old_end_byte),
+        new_end_position: point(new, new_end_byte),
+    })
+}
+
+/// Result of reparsing one edited file
+#[derive(Debug, Clone)]
+pub struct IncrementalUpdate {
+    pub parsed: ParsedFile,
+    /// 1-based inclusive line ranges of the new source whose syntax changed
+    pub dirty_lines: Vec<(usize, usize)>,
+    /// Nodes inside the dirty lines, and nodes added or removed by the edit
+    pub changed_nodes: Vec<String>,
+    /// Whether the previous syntax tree was reused
+    pub reused_tree: bool,
+    /// Top-level items extracted again, or `None` when the whole file was
+    pub extracted_items: Option<usize>,
+}
+
+struct CachedFile {
+    source: String,
+    tree: Option<Tree>,
+    parsed: ParsedFile,
+}
+
+/// Keeps the last source, syntax tree and extraction of each file so edits
+/// reparse and re-extract only what they touch
+#[derive(Default)]
+pub struct IncrementalParser {
+    files: Mutex<HashMap<PathBuf, CachedFile>>,
+}
+
+/// Sorted, merged line ranges
+fn merge(mut ranges: Vec<(usize, usize)>) -> Vec<(usize, usize)> {
+    ranges.sort_unstable();
+    let mut merged: Vec<(usize, usize)> = Vec::with_capacity(ranges.len());
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 + 1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+impl IncrementalParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse the new content of a file
+    ///
+    /// The first version of a file is parsed from scratch. Later versions
+    /// edit the cached tree to match and let tree-sitter reuse its unchanged
+    /// subtrees, then extract again only the top-level items the edit touched
+    /// when the file's parser can.
+    pub fn update(&self, engine: &ParserEngine, path: &Path, content: &str) -> EngineResult<IncrementalUpdate> {
+        // An edit may have added or removed the files its imports resolve to
+        engine.clear_resolved_imports();
+        self.reparse(engine, path, content)
+    }
+
+    /// [`Self::update`] for one file of a batch, which forgets resolved imports once
+    pub(crate) fn reparse(&self, engine: &ParserEngine, path: &Path, content: &str) -> EngineResult<IncrementalUpdate> {
+        let cached = self.files.lock().unwrap().remove(path);
+        let (update, cached) = reparse(engine.parser_for(path)?, path, content, cached)?;
+        self.files.lock().unwrap().insert(path.to_path_buf(), cached);
+        Ok(update)
+    }
+
+    /// Forget a file, so its next version is parsed from scratch
+    pub fn remove(&self, path: &Path) {
+        self.files.lock().unwrap().remove(path);
+    }
+}
+
+fn reparse(
+    parser: &dyn Parser,
+    path: &Path,
+    content: &str,
+    cached: Option<CachedFile>,
+) -> EngineResult<(IncrementalUpdate, CachedFile)> {
+    let Some(cached) = cached else {
+        let (parsed, tree) = parser.parse_incremental(path, content, None)?;
+        let last_line = content.lines().count().max(1);
+        let update = IncrementalUpdate {
+            changed_nodes: parsed.nodes.iter().map(|node| node.id.clone()).collect(),
+            dirty_lines: vec![(1, last_line)],
+            parsed: parsed.clone(),
+            reused_tree: false,
+            extracted_items: None,
+        };
+        let cached = CachedFile {
+            source: content.to_string(),
+            tree,
+            parsed,
+        };
+        return Ok((update, cached));
+    };
+
+    let Some(edit) = input_edit(&cached.source, content) else {
+        // Imports may resolve to other files by now, so only the tree is reused
+        let (parsed, tree) = parser.parse_incremental(path, content, cached.tree.as_ref())?;
+        let update = IncrementalUpdate {
+            parsed: parsed.clone(),
+            dirty_lines: Vec::new(),
+            changed_nodes: Vec::new(),
+            reused_tree: cached.tree.is_some(),
+            extracted_items: None,
+        };
+        let cached = CachedFile { tree, parsed, ..cached };
+        return Ok((update, cached));
+    };
+
+    let mut edited = cached.tree.clone();
+    if let Some(tree) = &mut edited {
+        tree.edit(&edit);
+    }
+    let reparsed = match (&cached.tree, &edited) {
+        (Some(old), Some(edited)) => parser.reparse(content, edited).map(|tree| {
+            let items = ItemChange::between(old, edited, &tree, &edit);
+            let extracted = items.and_then(|items| items.extract(parser, path, &cached, content, &tree, &edit));
+            (tree, extracted)
+        }),
+        _ => None,
+    };
+    let (parsed, tree, extracted_items) = match reparsed {
+        Some((tree, Some(extracted))) => {
+            let (parsed, items) = extracted?;
+            (parsed, Some(tree), Some(items))
+        }
+        // The new tree already matches the content, so it is reused whole
+        Some((tree, None)) => {
+            let (parsed, tree) = parser.parse_incremental(path, content, Some(&tree))?;
+            (parsed, tree, None)
+        }
+        None => {
+            let (parsed, tree) = parser.parse_incremental(path, content, edited.as_ref())?;
+            (parsed, tree, None)
+        }
+    };
+
+    let mut dirty = vec![(edit.start_position.row + 1, edit.new_end_position.row + 1)];
+    if let (Some(edited), Some(tree)) = (&edited, &tree) {
+        dirty.extend(
+            edited
+                .changed_ranges(tree)
+                .map(|range| (range.start_point.row + 1, range.end_point.row + 1)),
+        );
+    }
+    let dirty_lines = merge(dirty);
+
+    let old_ids: BTreeSet<&str> = cached.parsed.nodes.iter().map(|node| node.id.as_str()).collect();
+    let new_ids: BTreeSet<&str> = parsed.nodes.iter().map(|node| node.id.as_str()).collect();
+    let mut changed: BTreeSet<String> = parsed
+        .nodes
+        .iter()
+        .filter(|node| {
+            dirty_lines
+                .iter()
+                .any(|(start, end)| node.start_line <= *end && *start <= node.end_line)
+        })
+        .map(|node| node.id.clone())
+        .collect();
+    changed.extend(old_ids.symmetric_difference(&new_ids).map(|id| id.to_string()));
+
+    let update = IncrementalUpdate {
+        parsed: parsed.clone(),
+        dirty_lines,
+        changed_nodes: changed.into_iter().collect(),
+        reused_tree: edited.is_some(),
+        extracted_items,
+    };
+    let cached = CachedFile {
+        source: content.to_string(),
+        tree,
+        parsed,
+    };
+    Ok((update, cached))
+}
+
+/// Whether an item shares a line with the item before or after it
+fn shares_line(item: &Node) -> bool {
+    let before = item.prev_sibling().is_some_and(|prev| prev.end_position().row == item.start_position().row);
+    let after = item.next_sibling().is_some_and(|next| next.start_position().row == item.end_position().row);
+    before || after
+}
+
+/// Top-level items an edit touched, before and after it
+struct ItemChange<'a> {
+    old: Vec<Node<'a>>,
+    new: Vec<Node<'a>>,
+    /// First line of the untouched items after the edit, before it
+    shift_from: Option<usize>,
+}
+
+impl<'a> ItemChange<'a> {
+    /// Items of the old and new trees whose text or syntax changed
+    ///
+    /// Returns `None` when a changed item shares a line with another, as
+    /// nodes are told apart by line.
+    fn between(old_tree: &'a Tree, edited: &Tree, tree: &'a Tree, edit: &InputEdit) -> Option<Self> {
+        let top_level = |tree: &'a Tree| {
+            let root = tree.root_node();
+            let mut cursor = root.walk();
+            root.children(&mut cursor).collect::<Vec<_>>()
+        };
+        let old_items = top_level(old_tree);
+        let changed: Vec<_> = edited.changed_ranges(tree).collect();
+        let old_spans: HashSet<(usize, usize)> = old_items.iter().map(|item| (item.start_byte(), item.end_byte())).collect();
+
+        let mut untouched = HashSet::new();
+        let mut new = Vec::new();
+        for item in top_level(tree) {
+            let (start, end) = (item.start_byte(), item.end_byte());
+            let before = if end <= edit.start_byte {
+                Some((start, end))
+            } else if start >= edit.new_end_byte {
+                let moved = |byte: usize| byte - edit.new_end_byte + edit.old_end_byte;
+                Some((moved(start), moved(end)))
+            } else {
+                None
+            };
+            let reparsed = changed.iter().any(|range| range.start_byte < end && start < range.end_byte);
+            match before.filter(|span| !reparsed && old_spans.contains(span)) {
+                Some(span) => {
+                    untouched.insert(span);
+                }
+                None => new.push(item),
+            }
+        }
+        let (old, kept): (Vec<Node>, Vec<Node>) = old_items
+            .into_iter()
+            .partition(|item| !untouched.contains(&(item.start_byte(), item.end_byte())));
+        if old.iter().chain(&new).any(shares_line) {
+            return None;
+        }
+
+        let shift_from = kept
+            .iter()
+            .filter(|item| item.start_byte() >= edit.old_end_byte)
+            .map(|item| item.start_position().row + 1)
+            .min();
+        Some(Self { old, new, shift_from })
+    }
+
+    /// Extract the changed items alone and splice them into the file's last
+    /// extraction, returning the number of items extracted
+    fn extract(
+        &self,
+        parser: &dyn Parser,
+        path: &Path,
+        cached: &CachedFile,
+        content: &str,
+        tree: &Tree,
+        edit: &InputEdit,
+    ) -> Option<EngineResult<(ParsedFile, usize)>> {
+        let old_tree = cached.tree.as_ref()?;
+        let before = match parser.extract_items(path, &cached.source, old_tree, &self.old, &cached.parsed)? {
+            Ok(before) => before,
+            Err(error) => return Some(Err(error)),
+        };
+        let after = match parser.extract_items(path, content, tree, &self.new, &cached.parsed)? {
+            Ok(after) => after,
+            Err(error) => return Some(Err(error)),
+        };
+
+        let splice = Splice {
+            dirty: self
+                .old
+                .iter()
+                .map(|item| (item.start_position().row + 1, item.end_position().row + 1))
+                .collect(),
+            shift_from: self.shift_from,
+            line_delta: edit.new_end_position.row as isize - edit.old_end_position.row as isize,
+            last_line: content.lines().count(),
+        };
+        let parsed = splice.apply(&cached.parsed, before, after)?;
+        Some(Ok((parsed, self.new.len())))
+    }
+}
+
+/// Where the changed items were, and how far the items after them moved
+struct Splice {
+    /// 1-based inclusive line ranges of the changed items, before the edit
+    dirty: Vec<(usize, usize)>,
+    shift_from: Option<usize>,
+    line_delta: isize,
+    last_line: usize,
+}
+
+/// Identity of a relationship, for counting
+fn key(rel: &Relationship) -> (&str, &str, &'static str) {
+    (&rel.source, &rel.target, rel.relationship_type.as_str())
+}
+
+/// Relationships counted by identity
+fn counts<'a>(relationships: impl IntoIterator<Item = &'a Relationship>) -> HashMap<(&'a str, &'a str, &'static str), usize> {
+    let mut counts = HashMap::new();
+    for rel in relationships {
+        *counts.entry(key(rel)).or_default() += 1;
+    }
+    counts
+}
+
+/// Sorted ids of the nodes other than the file
+fn node_ids(parsed: &ParsedFile) -> Vec<&str> {
+    let mut ids: Vec<&str> = parsed
+        .nodes
+        .iter()
+        .filter(|node| !matches!(node.node_type, NodeType::File))
+        .map(|node| node.id.as_str())
+        .collect();
+    ids.sort_unstable();
+    ids
+}
+
+impl Splice {
+    fn is_dirty(&self, line: usize) -> bool {
+        self.dirty.iter().any(|(start, end)| (*start..=*end).contains(&line))
+    }
+
+    fn shift(&self, line: usize) -> usize {
+        line.saturating_add_signed(self.line_delta)
+    }
+
+    /// Swap the changed items' extraction before the edit for the one after
+    ///
+    /// Returns `None` when the two could differ elsewhere in the file: the
+    /// edit added, removed or renamed nodes, moved definitions between
+    /// classes, or changed edges from the file that other items may share.
+    fn apply(&self, cached: &ParsedFile, before: ParsedFile, after: ParsedFile) -> Option<ParsedFile> {
+        let is_file = |node: &AstNode| matches!(node.node_type, NodeType::File);
+        let file_id = cached.nodes.iter().find(|node| is_file(node))?.id.clone();
+        let structural = |rel: &&Relationship| {
+            matches!(rel.relationship_type, RelationshipType::Contains | RelationshipType::Extends)
+        };
+        let shared = |rel: &Relationship| rel.source == file_id && !matches!(rel.relationship_type, RelationshipType::Contains);
+        let mut cached_ids: HashMap<&str, usize> = HashMap::new();
+        for node in &cached.nodes {
+            *cached_ids.entry(&node.id).or_default() += 1;
+        }
+        let ids = node_ids(&before);
+        let unique = ids.iter().all(|id| cached_ids.get(id) == Some(&1));
+        let concurrent_file = |parsed: &ParsedFile| parsed.metrics.concurrency.contains_key(&file_id);
+        if ids != node_ids(&after)
+            || !unique
+            || counts(before.relationships.iter().filter(structural)) != counts(after.relationships.iter().filter(structural))
+            || counts(before.relationships.iter().filter(|rel| shared(rel)))
+                != counts(after.relationships.iter().filter(|rel| shared(rel)))
+            || concurrent_file(&before)
+            || concurrent_file(&after)
+        {
+            return None;
+        }
+
+        let fresh: HashMap<&str, &AstNode> = after.nodes.iter().filter(|node| !is_file(node)).map(|node| (node.id.as_str(), node)).collect();
+        let nodes = cached
+            .nodes
+            .iter()
+            .map(|node| {
+                if is_file(node) {
+                    return AstNode {
+                        end_line: self.last_line,
+                        ..node.clone()
+                    };
+                }
+                let dirty = self.is_dirty(node.start_line);
+                let mut node = node.clone();
+                if !dirty && self.shift_from.is_some_and(|from| node.start_line >= from) {
+                    node.start_line = self.shift(node.start_line);
+                    node.end_line = self.shift(node.end_line);
+                }
+                // Nodes shared with untouched items keep their first line
+                match fresh.get(node.id.as_str()) {
+                    Some(fresh) if dirty || fresh.start_line < node.start_line => (*fresh).clone(),
+                    _ => node,
+                }
+            })
+            .collect();
+
+        let mut removed = counts(before.relationships.iter().filter(|rel| !shared(rel)));
+        let mut relationships: Vec<Relationship> = cached
+            .relationships
+            .iter()
+            .filter(|rel| match removed.get_mut(&key(rel)) {
+                Some(count) if *count > 0 => {
+                    *count -= 1;
+                    false
+                }
+                _ => true,
+            })
+            .cloned()
+            .collect();
+        // An edge of the old extraction missing from the file means items were not independent
+        if removed.values().any(|count| *count > 0) {
+            return None;
+        }
+        relationships.extend(after.relationships.iter().filter(|rel| !shared(rel)).cloned());
+
+        let (old, new) = (&before.metrics, &after.metrics);
+        let mut metrics = cached.metrics.clone();
+        let adjust = |total: usize, old: usize, new: usize| (total + new).checked_sub(old);
+        metrics.lines_of_code = adjust(metrics.lines_of_code, old.lines_of_code, new.lines_of_code)?;
+        metrics.complexity = adjust(metrics.complexity, old.complexity, new.complexity)?;
+        metrics.functions = adjust(metrics.functions, old.functions, new.functions)?;
+        metrics.classes = adjust(metrics.classes, old.classes, new.classes)?;
+        metrics.imports = adjust(metrics.imports, old.imports, new.imports)?;
+        metrics.exports = adjust(metrics.exports, old.exports, new.exports)?;
+        metrics.dynamic_imports = adjust(metrics.dynamic_imports, old.dynamic_imports, new.dynamic_imports)?;
+        metrics.comments.comment_lines = adjust(metrics.comments.comment_lines, old.comments.comment_lines, new.comments.comment_lines)?;
+        metrics.comments.code_lines = adjust(metrics.comments.code_lines, old.comments.code_lines, new.comments.code_lines)?;
+        for id in old.function_metrics.keys() {
+            metrics.function_metrics.remove(id);
+        }
+        for id in old.documented.keys() {
+            metrics.documented.remove(id);
+        }
+        for id in old.concurrency.keys() {
+            metrics.concurrency.remove(id);
+        }
+        metrics.function_metrics.extend(new.function_metrics.clone());
+        metrics.documented.extend(new.documented.clone());
+        metrics.concurrency.extend(new.concurrency.clone());
+
+        Some(ParsedFile {
+            path: cached.path.clone(),
+            language: cached.language,
+            nodes,
+            relationships,
+            metrics,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_edit_spans_the_change() {
+        let edit = input_edit("let a = 1;\nlet b = 2;\n", "let a = 1;\nlet bc = 2;\n").unwrap();
+        assert_eq!((edit.start_byte, edit.old_end_byte, edit.new_end_byte), (16, 16, 17));
+        assert_eq!(edit.start_position, Point::new(1, 5));
+        assert_eq!(edit.new_end_position, Point::new(1, 6));
+
+        let edit = input_edit("é1", "é2").unwrap();
+        assert_eq!((edit.start_byte, edit.old_end_byte), (2, 3));
+        assert!(input_edit("same", "same").is_none());
+    }
+
+    /// Nodes, edges and metrics of a file, in an order that does not depend on how it was extracted
+    #[cfg(feature = "python")]
+    fn summary(parsed: &ParsedFile) -> String {
+        use std::collections::BTreeMap;
+        let mut nodes: Vec<_> = parsed.nodes.iter().map(|node| (&node.id, node.start_line, node.end_line)).collect();
+        nodes.sort();
+        let mut relationships: Vec<_> = parsed.relationships.iter().map(key).collect();
+        relationships.sort();
+        let metrics = &parsed.metrics;
+        format!(
+            "{:#?}",
+            (
+                nodes,
+                relationships,
+                (metrics.complexity, metrics.dynamic_imports, metrics.comments),
+                metrics.function_metrics.iter().collect::<BTreeMap<_, _>>(),
+                metrics.documented.iter().collect::<BTreeMap<_, _>>(),
+                metrics.concurrency.iter().collect::<BTreeMap<_, _>>(),
+            )
+        )
+    }
+
+    #[cfg(feature = "python")]
+    #[test]
+    fn test_update_matches_full_parse() {
+        let engine = ParserEngine::new();
+        let path = Path::new("app.py");
+        let before = "def first():\n    return 1\n\n\ndef second():\n    return 2\n";
+        let after = "def first():\n    return 1\n\n\ndef second():\n    return 22\n\n\ndef third():\n    pass\n";
+
+        let incremental = IncrementalParser::new();
+        let first = incremental.update(&engine, path, before).unwrap();
+        assert!(!first.reused_tree);
+
+        let update = incremental.update(&engine, path, after).unwrap();
+        assert!(update.reused_tree);
+        let full = engine.parse_file(path, after).unwrap();
+        assert_eq!(summary(&update.parsed), summary(&full));
+        assert_eq!(update.dirty_lines.first().map(|range| range.0), Some(6));
+        assert!(update.changed_nodes.iter().any(|id| id.contains("second")));
+        assert!(update.changed_nodes.iter().any(|id| id.contains("third")));
+        assert!(!update.changed_nodes.iter().any(|id| id.contains("first")));
+
+        let unchanged = incremental.update(&engine, path, after).unwrap();
+        assert!(unchanged.dirty_lines.is_empty() && unchanged.changed_nodes.is_empty());
+    }
+
+    #[cfg(feature = "python")]
+    #[test]
+    fn test_edits_re_extract_only_the_items_they_touch() {
+        let engine = ParserEngine::new();
+        let path = Path::new("/repo/app/store.py");
+        let mut source = String::from(
+            "import os\nfrom pkg.util import helper\n\n\ndef load(key):\n    \"\"\"Load a key.\"\"\"\n    return os.environ['STORE_URL']\n\n\n# Keeps keys\nclass Store:\n    def get(self, key):\n        if key:\n            return load(key)\n        return None\n\n\ndef save(key):\n    helper(key)\n    return os.getenv('STORE_URL')\n",
+        );
+        let incremental = IncrementalParser::new();
+        incremental.update(&engine, path, &source).unwrap();
+
+        let edits: [(&str, &str, Option<usize>); 6] = [
+            // A new branch and call in one method
+            ("        return None\n", "        if not key:\n            helper(key)\n        return None\n", Some(1)),
+            // A docstring removed, moving everything after it up
+            ("    \"\"\"Load a key.\"\"\"\n", "", Some(1)),
+            // A comment edited
+            ("# Keeps keys", "# Keeps every key", Some(1)),
+            // A call to another function of the file
+            ("    helper(key)\n    return", "    helper(key)\n    load(key)\n    return", Some(1)),
+            // A new function has to be extracted with the whole file
+            ("\n\ndef save", "\n\ndef drop(key):\n    pass\n\n\ndef save", None),
+            // So does a new import
+            ("import os\n", "import os\nimport sys\n", None),
+        ];
+        for (old, new, extracted) in edits {
+            source = source.replacen(old, new, 1);
+            let update = incremental.update(&engine, path, &source).unwrap();
+            let full = engine.parse_file(path, &source).unwrap();
+            assert_eq!(update.extracted_items, extracted, "{:?}", new);
+            assert_eq!(summary(&update.parsed), summary(&full), "{:?}", new);
+        }
+    }
+}
@@ -3,14 +3,22 @@ use og_types::{
     EngineResult, EngineError, FileMetrics,
 };
 use std::path::{Path, PathBuf};
-use std::sync::Mutex;
-use tree_sitter::{Node, Parser as TSParser, TreeCursor};
+use std::sync::Arc;
+use tree_sitter::{Node, Tree, TreeCursor};
 use tracing::{debug, trace};
+use crate::concurrency::{self, ConcurrencyUse};
+use crate::docs::{self, PublicSymbol};
+use crate::dynamic_imports;
+use crate::env_vars;
+use crate::events;
+use crate::function_metrics::{self, FunctionShape};
+use crate::graphql;
+use crate::sql;
 use crate::import_resolver::ImportResolver;
+use crate::pool;
 
 pub struct JavaScriptParser {
-    parser: Mutex<TSParser>,
-    base_path: PathBuf,
+    resolver: Arc<ImportResolver>,
 }
 
 impl JavaScriptParser {
@@ -19,14 +27,12 @@ impl JavaScriptParser {
     }
     
     pub fn with_base_path(base_path: PathBuf) -> Self {
-        let mut parser = TSParser::new();
-        parser
-            .set_language(tree_sitter_javascript::language())
-            .expect("Error loading JavaScript grammar");
-        Self {
-            parser: Mutex::new(parser),
-            base_path,
-        }
+        Self::with_resolver(Arc::new(ImportResolver::new(base_path)))
+    }
+
+    /// Resolve imports through a resolver shared with other parsers
+    pub fn with_resolver(resolver: Arc<ImportResolver>) -> Self {
+        Self { resolver }
     }
 
     fn extract_nodes(
@@ -40,7 +46,7 @@ impl JavaScriptParser {
         let mut node_counter = 0;
 
         // Create file node
-        let file_id = format!("file_{}", node_counter);
+        let file_id = format!("file:{}", file_path);
         node_counter += 1;
         
         nodes.push(AstNode {
@@ -229,11 +235,11 @@ impl JavaScriptParser {
         }
     }
 
-    fn extract_import(&self, node: Node, source: &str, _file_path: &str, counter: &mut usize) -> Option<AstNode> {
+    fn extract_import(&self, node: Node, source: &str, file_path: &str, counter: &mut usize) -> Option<AstNode> {
         let start_pos = node.start_position();
         let end_pos = node.end_position();
         
-        let id = format!("import_{}", *counter);
+        let id = format!("import:{}:{}", file_path, *counter);
         *counter += 1;
 
         Some(AstNode {
@@ -262,7 +268,7 @@ impl JavaScriptParser {
         
         if let Some(import_path) = import_path {
             // Resolve the import path
-            let resolver = ImportResolver::new(self.base_path.clone());
+            let resolver = &self.resolver;
             let file_path = Path::new(file_path);
             
             if let Some(resolved_path) = resolver.resolve_import(&import_path, file_path) {
@@ -271,6 +277,13 @@ impl JavaScriptParser {
                     target: resolved_path,
                     relationship_type: RelationshipType::Imports,
                 });
+            } else if resolver.is_external_import(&import_path) {
+                // Package imports are kept so graph building can group or drop them
+                relationships.push(Relationship {
+                    source: parent_id.to_string(),
+                    target: format!("external:{}", import_path),
+                    relationship_type: RelationshipType::Imports,
+                });
             }
         }
     }
@@ -340,11 +353,11 @@ impl JavaScriptParser {
         None
     }
 
-    fn extract_export(&self, node: Node, source: &str, _file_path: &str, counter: &mut usize) -> Option<AstNode> {
+    fn extract_export(&self, node: Node, source: &str, file_path: &str, counter: &mut usize) -> Option<AstNode> {
         let start_pos = node.start_position();
         let end_pos = node.end_position();
         
-        let id = format!("export_{}", *counter);
+        let id = format!("export:{}:{}", file_path, *counter);
         *counter += 1;
 
         Some(AstNode {
@@ -362,7 +375,7 @@ impl JavaScriptParser {
         })
     }
 
-    fn extract_function(&self, node: Node, source: &str, _file_path: &str, counter: &mut usize) -> Option<AstNode> {
+    fn extract_function(&self, node: Node, source: &str, file_path: &str, counter: &mut usize) -> Option<AstNode> {
         let start_pos = node.start_position();
         let end_pos = node.end_position();
         
@@ -390,7 +403,7 @@ impl JavaScriptParser {
             }
         });
         
-        let id = format!("function_{}", *counter);
+        let id = format!("function:{}:{}", file_path, *counter);
         *counter += 1;
 
         Some(AstNode {
@@ -403,7 +416,7 @@ impl JavaScriptParser {
         })
     }
 
-    fn extract_class(&self, node: Node, source: &str, _file_path: &str, counter: &mut usize) -> Option<AstNode> {
+    fn extract_class(&self, node: Node, source: &str, file_path: &str, counter: &mut usize) -> Option<AstNode> {
         let start_pos = node.start_position();
         let end_pos = node.end_position();
         
@@ -413,7 +426,7 @@ impl JavaScriptParser {
             .unwrap_or("AnonymousClass")
             .to_string();
         
-        let id = format!("class_{}", *counter);
+        let id = format!("class:{}:{}", file_path, *counter);
         *counter += 1;
 
         Some(AstNode {
@@ -582,7 +595,7 @@ impl JavaScriptParser {
         None
     }
 
-    fn extract_variable(&self, node: Node, source: &str, _file_path: &str, counter: &mut usize) -> Option<AstNode> {
+    fn extract_variable(&self, node: Node, source: &str, file_path: &str, counter: &mut usize) -> Option<AstNode> {
         let start_pos = node.start_position();
         let end_pos = node.end_position();
         
@@ -611,9 +624,9 @@ impl JavaScriptParser {
             };
             
             let id = if is_function {
-                format!("function_{}", *counter)
+                format!("function:{}:{}", file_path, *counter)
             } else {
-                format!("variable_{}", *counter)
+                format!("variable:{}:{}", file_path, *counter)
             };
             *counter += 1;
 
@@ -641,12 +654,20 @@ impl crate::Parser for JavaScriptParser {
     }
 
     fn parse(&self, path: &Path, content: &str) -> EngineResult<ParsedFile> {
-        let start_time = std::time::Instant::now();
+        self.parse_incremental(path, content, None).map(|(parsed, _)| parsed)
+    }
+
+    fn parse_incremental(
+        &self,
+        path: &Path,
+        content: &str,
+        old_tree: Option<&Tree>,
+    ) -> EngineResult<(ParsedFile, Option<Tree>)> {
+        let start_time = web_time::Instant::now();
         
         debug!("Parsing JavaScript file: {}", path.display());
         
-        let mut parser = self.parser.lock().unwrap();
-        let tree = parser.parse(content, None).ok_or_else(|| {
+        let tree = pool::parse("JavaScript", tree_sitter_javascript::language, content, old_tree).ok_or_else(|| {
             EngineError::ParseError {
                 file: path.display().to_string(),
                 message: "Failed to parse JavaScript file".to_string(),
@@ -667,19 +688,58 @@ impl crate::Parser for JavaScriptParser {
 
         let metrics = FileMetrics {
             lines_of_code: content.lines().count(),
-            complexity: 0, // TODO: Calculate complexity
+            complexity: 1, // Set from function shapes once they are measured
             functions: nodes.iter().filter(|n| matches!(n.node_type, NodeType::Function)).count(),
             classes: nodes.iter().filter(|n| matches!(n.node_type, NodeType::Class)).count(),
             imports: nodes.iter().filter(|n| matches!(n.node_type, NodeType::Import)).count(),
             exports: nodes.iter().filter(|n| matches!(n.node_type, NodeType::Export)).count(),
+            dynamic_imports: 0,
+            function_metrics: Default::default(),
+            comments: Default::default(),
+            documented: Default::default(),
+            concurrency: Default::default(),
         };
 
-        Ok(ParsedFile {
+        let mut parsed = ParsedFile {
             path: path.to_path_buf(),
             language: Language::JavaScript,
             nodes,
             relationships,
             metrics,
-        })
+        };
+        let resolver = &self.resolver;
+        dynamic_imports::apply(
+            &mut parsed,
+            dynamic_imports::find_javascript(tree.root_node(), content),
+            |specifier| resolver.resolve_import(specifier, path),
+        );
+        env_vars::apply(&mut parsed, env_vars::find_javascript(tree.root_node(), content));
+        sql::apply(&mut parsed, sql::find_javascript(tree.root_node(), content));
+        events::apply(&mut parsed, events::find_javascript(tree.root_node(), content));
+        graphql::apply(&mut parsed, graphql::find_javascript(tree.root_node(), content));
+        // Nodes of this parser number lines from zero
+        let shapes = function_metrics::find_javascript(tree.root_node())
+            .into_iter()
+            .map(|shape| FunctionShape {
+                start_line: shape.start_line - 1,
+                end_line: shape.end_line - 1,
+                ..shape
+            })
+            .collect();
+        function_metrics::apply(&mut parsed, shapes);
+        let mut documentation = docs::find_javascript(tree.root_node(), content);
+        documentation.symbols = documentation
+            .symbols
+            .into_iter()
+            .map(|symbol| PublicSymbol { line: symbol.line - 1, ..symbol })
+            .collect();
+        docs::apply(&mut parsed, documentation);
+        let uses = concurrency::find_javascript(tree.root_node(), content)
+            .into_iter()
+            .map(|found| ConcurrencyUse { line: found.line - 1, ..found })
+            .collect();
+        concurrency::apply(&mut parsed, uses);
+        drop(cursor);
+        Ok((parsed, Some(tree)))
     }
 }
\ No newline at end of file
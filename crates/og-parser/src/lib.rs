@@ -1,6 +1,30 @@
 pub mod parser_trait;
 pub mod engine;
+#[cfg(feature = "fs")]
+pub mod compile_commands;
+pub mod concurrency;
+pub mod debt;
+pub mod docker;
+pub mod docs;
+pub mod dynamic_imports;
+pub mod env_vars;
+pub mod events;
+pub mod frameworks;
+pub mod function_metrics;
+pub mod generated;
+pub mod graphql;
 pub mod import_resolver;
+pub mod incremental;
+pub mod license;
+#[cfg(feature = "fs")]
+pub mod lsp;
+pub mod make;
+pub mod minified;
+mod pool;
+pub mod shell;
+pub mod sql;
+pub mod stylesheet;
+pub mod suppressions;
 #[cfg(feature = "js")]
 pub mod javascript;
 #[cfg(feature = "ts")]
@@ -0,0 +1,135 @@
+use og_types::LicenseHeader;
+
+/// Lines at the top of a file searched for a license header
+const HEADER_LINES: usize = 40;
+
+/// Tag that names a license in machine-readable headers
+const SPDX_TAG: &str = "SPDX-License-Identifier:";
+
+/// Comment openers and decorations stripped from header lines
+const COMMENT_OPENERS: &[&str] = &["//", "#", "/*", "*", "--", "<!--", ";"];
+
+/// Phrases that identify a license text, lowercase, most specific first
+const LICENSE_PHRASES: &[(&str, &str)] = &[
+    ("gnu affero general public license", "AGPL-3.0"),
+    ("gnu lesser general public license", "LGPL"),
+    ("gnu general public license", "GPL"),
+    ("apache license", "Apache-2.0"),
+    ("mozilla public license", "MPL-2.0"),
+    ("permission is hereby granted, free of charge", "MIT"),
+    ("boost software license", "BSL-1.0"),
+    ("released into the public domain", "Unlicense"),
+    (
+        "permission to use, copy, modify, and/or distribute this software for any purpose",
+        "ISC",
+    ),
+    ("redistribution and use in source and binary forms", "BSD"),
+];
+
+/// Names of files that hold the license of the directory they are in
+pub fn is_license_file(name: &str) -> bool {
+    let stem = name.split('.').next().unwrap_or(name).to_ascii_uppercase();
+    let stem = stem.split(['-', '_']).next().unwrap_or(&stem);
+    matches!(stem, "LICENSE" | "LICENCE" | "COPYING" | "UNLICENSE")
+}
+
+/// Licenses a license text grants, by SPDX identifier
+///
+/// GPL and BSD variants are told apart by their version or clauses where
+/// the text says.
+pub fn identify(text: &str) -> Vec<String> {
+    let lower = text.to_lowercase();
+    let mut licenses: Vec<String> = Vec::new();
+    for (phrase, license) in LICENSE_PHRASES {
+        if !lower.contains(phrase) {
+            continue;
+        }
+        // The GPL phrase also occurs inside the lesser and affero ones
+        if *license == "GPL" && licenses.iter().any(|l| l.starts_with("LGPL") || l.starts_with("AGPL")) {
+            continue;
+        }
+        let license = match *license {
+            "GPL" | "LGPL" => {
+                let version = if lower.contains("version 3") {
+                    "-3.0"
+                } else if lower.contains("version 2.1") {
+                    "-2.1"
+                } else if lower.contains("version 2") {
+                    "-2.0"
+                } else {
+                    ""
+                };
+                format!("{}{}", license, version)
+            }
+            "BSD" if lower.contains("neither the name") => "BSD-3-Clause".to_string(),
+            "BSD" => "BSD-2-Clause".to_string(),
+            _ => license.to_string(),
+        };
+        licenses.push(license);
+    }
+    licenses
+}
+
+/// A header line without its comment opener or closer
+fn strip_comment(line: &str) -> &str {
+    let mut line = line.trim();
+    while let Some(opener) = COMMENT_OPENERS.iter().find(|opener| line.starts_with(*opener)) {
+        line = line[opener.len()..].trim_start();
+    }
+    line.trim_end_matches("-->").trim_end_matches("*/").trim_end()
+}
+
+/// License and copyright notice in the first lines of a source file
+///
+/// An `SPDX-License-Identifier` tag is taken as written, otherwise the
+/// licenses are recognized from the header's wording.
+pub fn header(source: &str) -> LicenseHeader {
+    let lines: Vec<&str> = source.lines().take(HEADER_LINES).map(strip_comment).collect();
+    let copyright = lines
+        .iter()
+        .find(|line| line.to_lowercase().starts_with("copyright") || line.starts_with("(c)") || line.starts_with('©'))
+        .map(|line| line.to_string());
+    let spdx: Vec<String> = lines
+        .iter()
+        .filter_map(|line| line.split_once(SPDX_TAG))
+        .map(|(_, license)| license.trim().to_string())
+        .filter(|license| !license.is_empty())
+        .collect();
+    let licenses = if spdx.is_empty() { identify(&lines.join(" ")) } else { spdx };
+    LicenseHeader { licenses, copyright }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spdx_and_worded_headers() {
+        let tagged = header("// SPDX-License-Identifier: MIT OR Apache-2.0\n// Copyright 2024 Example Corp\nfn main() {}\n");
+        assert_eq!(tagged.licenses, vec!["MIT OR Apache-2.0"]);
+        assert_eq!(tagged.copyright.as_deref(), Some("Copyright 2024 Example Corp"));
+
+        let worded = header(
+            "/*\n * Copyright (c) 2019 Someone\n *\n * Licensed under the Apache License, Version 2.0 (the \"License\");\n */\n",
+        );
+        assert_eq!(worded.licenses, vec!["Apache-2.0"]);
+
+        let gpl = header("# This program is free software under the GNU Lesser General Public License\n# version 2.1 or later\n");
+        assert_eq!(gpl.licenses, vec!["LGPL-2.1"]);
+
+        assert_eq!(header("import os\n\nprint('hi')\n"), LicenseHeader::default());
+    }
+
+    #[test]
+    fn test_license_files() {
+        assert!(is_license_file("LICENSE"));
+        assert!(is_license_file("license.md"));
+        assert!(is_license_file("LICENSE-APACHE"));
+        assert!(is_license_file("COPYING.txt"));
+        assert!(!is_license_file("licenses.rs"));
+        assert_eq!(
+            identify("Redistribution and use in source and binary forms ... Neither the name of the copyright holder"),
+            vec!["BSD-3-Clause"]
+        );
+    }
+}
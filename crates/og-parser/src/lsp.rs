@@ -0,0 +1,550 @@
+use og_types::{Language, NodeType, ParsedFile, Relationship, RelationshipType};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::time::{Duration, Instant};
+
+use crate::env_vars::enclosing_function;
+
+/// Language server settings, read from the `[lsp]` section of the project config
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LspSettings {
+    /// Off by default since servers can take a while to index a codebase
+    pub enabled: bool,
+    /// Server command per language name, replacing the default server
+    pub servers: BTreeMap<String, Vec<String>>,
+    /// How long to wait for each server response
+    pub timeout_ms: u64,
+    /// Most definitions to look up references for, per server
+    pub max_queries: usize,
+}
+
+impl Default for LspSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            servers: BTreeMap::new(),
+            timeout_ms: 5000,
+            max_queries: 2000,
+        }
+    }
+}
+
+impl LspSettings {
+    /// Command that starts the server for a language, if it has one
+    pub fn command(&self, language: Language) -> Option<Vec<String>> {
+        if let Some(command) = self.servers.get(language.as_str()) {
+            return (!command.is_empty()).then(|| command.clone());
+        }
+        let default: &[&str] = match language {
+            Language::JavaScript | Language::TypeScript => &["typescript-language-server", "--stdio"],
+            Language::Python => &["pyright-langserver", "--stdio"],
+            Language::C => &["clangd"],
+            _ => return None,
+        };
+        Some(default.iter().map(|part| part.to_string()).collect())
+    }
+}
+
+/// What the language servers changed in a run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LspReport {
+    /// Servers that answered, by program name
+    pub servers: Vec<String>,
+    /// Servers that could not be started or failed to initialize
+    pub unavailable: Vec<String>,
+    /// Edges the servers found that heuristics missed
+    pub edges_added: usize,
+    /// Heuristic edges to a same-named symbol that the servers contradicted
+    pub edges_removed: usize,
+}
+
+/// A function, method or type that references are looked up for
+#[derive(Debug, Clone)]
+struct Definition {
+    file: usize,
+    id: String,
+    name: String,
+    node_type: NodeType,
+    line: usize,
+    column: usize,
+}
+
+/// Minimal JSON-RPC client for a language server over stdio
+struct LspClient {
+    child: Child,
+    stdin: ChildStdin,
+    messages: Receiver<Value>,
+    next_id: i64,
+    timeout: Duration,
+}
+
+impl LspClient {
+    fn start(command: &[String], root: &Path, timeout: Duration) -> io::Result<Self> {
+        let (program, args) = command.split_first().ok_or_else(|| io::Error::other("Empty server command"))?;
+        let mut child = Command::new(program)
+            .args(args)
+            .current_dir(root)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+        let stdin = child.stdin.take().ok_or_else(|| io::Error::other("Server has no stdin"))?;
+        let stdout = child.stdout.take().ok_or_else(|| io::Error::other("Server has no stdout"))?;
+
+        // Reads block, so a thread forwards messages and requests wait with a timeout
+        let (sender, messages) = mpsc::channel();
+        std::thread::spawn(move || {
+            let mut reader = BufReader::new(stdout);
+            while let Ok(Some(message)) = read_message(&mut reader) {
+                if sender.send(message).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut client = Self {
+            child,
+            stdin,
+            messages,
+            next_id: 1,
+            timeout,
+        };
+        let root_uri = path_to_uri(root);
+        client.request(
+            "initialize",
+            json!({
+                "processId": std::process::id(),
+                "rootUri": root_uri,
+                "workspaceFolders": [{ "uri": root_uri, "name": "root" }],
+                "capabilities": { "textDocument": { "references": {}, "synchronization": {} } },
+            }),
+        )?;
+        client.notify("initialized", json!({}))?;
+        Ok(client)
+    }
+
+    fn send(&mut self, message: Value) -> io::Result<()> {
+        self.stdin.write_all(&frame(&message))?;
+        self.stdin.flush()
+    }
+
+    fn notify(&mut self, method: &str, params: Value) -> io::Result<()> {
+        self.send(json!({ "jsonrpc": "2.0", "method": method, "params": params }))
+    }
+
+    fn request(&mut self, method: &str, params: Value) -> io::Result<Value> {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.send(json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }))?;
+
+        let deadline = Instant::now() + self.timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let message = self
+                .messages
+                .recv_timeout(remaining)
+                .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, format!("{} timed out", method)))?;
+
+            if let Some(server_method) = message.get("method").and_then(Value::as_str) {
+                // Answer server requests so the server doesn't stall waiting on us
+                if let Some(server_id) = message.get("id") {
+                    let result = match server_method {
+                        "workspace/configuration" => {
+                            let items = message["params"]["items"].as_array().map_or(0, Vec::len);
+                            Value::Array(vec![Value::Null; items])
+                        }
+                        _ => Value::Null,
+                    };
+                    self.send(json!({ "jsonrpc": "2.0", "id": server_id, "result": result }))?;
+                }
+                continue;
+            }
+            if message.get("id").and_then(Value::as_i64) != Some(id) {
+                continue;
+            }
+            if let Some(error) = message.get("error") {
+                return Err(io::Error::other(format!("{} failed: {}", method, error)));
+            }
+            return Ok(message.get("result").cloned().unwrap_or(Value::Null));
+        }
+    }
+
+    fn shutdown(mut self) {
+        if self.request("shutdown", Value::Null).is_ok() {
+            let _ = self.notify("exit", Value::Null);
+        }
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Encode a message with its Content-Length header
+fn frame(message: &Value) -> Vec<u8> {
+    let body = message.to_string();
+    let mut framed = format!("Content-Length: {}\r\n\r\n", body.len()).into_bytes();
+    framed.extend_from_slice(body.as_bytes());
+    framed
+}
+
+/// Read one message, or None at the end of the stream
+fn read_message(reader: &mut impl BufRead) -> io::Result<Option<Value>> {
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+    let length = length.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Missing Content-Length"))?;
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    serde_json::from_slice(&body).map(Some).map_err(io::Error::other)
+}
+
+/// `file://` URI for a path, percent-encoding anything outside the unreserved set
+fn path_to_uri(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let mut uri = String::from("file://");
+    if !path.starts_with('/') {
+        uri.push('/');
+    }
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' | b':' => uri.push(byte as char),
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    uri
+}
+
+/// Path of a `file://` URI
+fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    let encoded = uri.strip_prefix("file://")?;
+    let mut bytes = Vec::with_capacity(encoded.len());
+    let mut rest = encoded.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        match (byte, tail.get(..2).and_then(|hex| std::str::from_utf8(hex).ok())) {
+            (b'%', Some(hex)) if u8::from_str_radix(hex, 16).is_ok() => {
+                bytes.push(u8::from_str_radix(hex, 16).ok()?);
+                rest = &tail[2..];
+            }
+            _ => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+    let path = String::from_utf8(bytes).ok()?;
+    // `/C:/src` on Windows
+    let path = match path.as_bytes() {
+        [b'/', drive, b':', ..] if drive.is_ascii_alphabetic() => path[1..].to_string(),
+        _ => path,
+    };
+    Some(PathBuf::from(path))
+}
+
+/// Definitions to look up, with the column of their name on their first line
+fn definitions(files: &[ParsedFile], indices: &[usize], sources: &HashMap<usize, String>) -> Vec<Definition> {
+    let mut definitions = Vec::new();
+    for &file in indices {
+        let Some(source) = sources.get(&file) else {
+            continue;
+        };
+        let lines: Vec<&str> = source.lines().collect();
+        for node in &files[file].nodes {
+            if !matches!(node.node_type, NodeType::Function | NodeType::Method | NodeType::Class | NodeType::Interface) {
+                continue;
+            }
+            let Some(column) = lines.get(node.start_line.saturating_sub(1)).and_then(|line| name_column(line, &node.name))
+            else {
+                continue;
+            };
+            definitions.push(Definition {
+                file,
+                id: node.id.clone(),
+                name: node.name.clone(),
+                node_type: node.node_type,
+                line: node.start_line,
+                column,
+            });
+        }
+    }
+    definitions
+}
+
+/// Character offset of `name` as a whole identifier in a line
+fn name_column(line: &str, name: &str) -> Option<usize> {
+    if name.is_empty() {
+        return None;
+    }
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+    line.match_indices(name).find_map(|(start, _)| {
+        let before = line[..start].chars().next_back();
+        let after = line[start + name.len()..].chars().next();
+        (!before.is_some_and(is_ident) && !after.is_some_and(is_ident)).then(|| line[..start].chars().count())
+    })
+}
+
+/// Replace the edges to a definition with the ones its references imply
+///
+/// Each site is a file index and line that references the definition. The
+/// function or file around the site gets a `Calls` edge to functions and
+/// methods, or a `References` edge to types, and loses edges of the same
+/// kind to other symbols that merely share the definition's name.
+fn upgrade_edges(
+    files: &mut [ParsedFile],
+    definition: &Definition,
+    sites: &[(usize, usize)],
+    names: &HashMap<String, String>,
+    report: &mut LspReport,
+) {
+    let relationship_type = match definition.node_type {
+        NodeType::Function | NodeType::Method => RelationshipType::Calls,
+        _ => RelationshipType::References,
+    };
+    let same_type = |rel: &Relationship| rel.relationship_type.as_str() == relationship_type.as_str();
+    let name_of = |id: &str| names.get(id).cloned().unwrap_or_else(|| id.rsplit(':').next().unwrap_or(id).to_string());
+
+    for &(file, line) in sites {
+        let Some(source) = enclosing_function(&files[file], line) else {
+            continue;
+        };
+        if source == definition.id {
+            continue;
+        }
+
+        let relationships = &mut files[file].relationships;
+        let before = relationships.len();
+        relationships.retain(|rel| {
+            !(rel.source == source && same_type(rel) && rel.target != definition.id && name_of(&rel.target) == definition.name)
+        });
+        report.edges_removed += before - relationships.len();
+
+        if !relationships.iter().any(|rel| rel.source == source && rel.target == definition.id && same_type(rel)) {
+            relationships.push(Relationship {
+                source,
+                target: definition.id.clone(),
+                relationship_type,
+            });
+            report.edges_added += 1;
+        }
+    }
+}
+
+/// Sites in a references response, as file indices and lines
+fn reference_sites(result: &Value, file_index: &HashMap<PathBuf, usize>) -> Vec<(usize, usize)> {
+    let mut sites = Vec::new();
+    for location in result.as_array().into_iter().flatten() {
+        let Some(path) = location["uri"].as_str().and_then(uri_to_path) else {
+            continue;
+        };
+        let path = path.canonicalize().unwrap_or(path);
+        let (Some(&file), Some(line)) = (file_index.get(&path), location["range"]["start"]["line"].as_u64()) else {
+            continue;
+        };
+        let site = (file, line as usize + 1);
+        if !sites.contains(&site) {
+            sites.push(site);
+        }
+    }
+    sites
+}
+
+/// Resolve references with language servers and upgrade the heuristic edges
+///
+/// Heuristic resolution only matches names, so the references a server reports
+/// for each definition replace the guessed `Calls` and `References` edges. Files are grouped by the server that handles them. Servers that are not
+/// installed or fail to start are skipped, leaving those files as parsed.
+pub fn resolve_with_servers(files: &mut [ParsedFile], root: &Path, settings: &LspSettings) -> LspReport {
+    let mut report = LspReport::default();
+    let timeout = Duration::from_millis(settings.timeout_ms);
+
+    let absolute = |path: &Path| {
+        let path = root.join(path);
+        path.canonicalize().unwrap_or(path)
+    };
+    let file_index: HashMap<PathBuf, usize> =
+        files.iter().enumerate().map(|(index, file)| (absolute(&file.path), index)).collect();
+    let names: HashMap<String, String> = files
+        .iter()
+        .flat_map(|file| file.nodes.iter().map(|node| (node.id.clone(), node.name.clone())))
+        .collect();
+
+    let mut by_server: BTreeMap<Vec<String>, Vec<usize>> = BTreeMap::new();
+    for (index, file) in files.iter().enumerate() {
+        if let Some(command) = settings.command(file.language) {
+            by_server.entry(command).or_default().push(index);
+        }
+    }
+
+    for (command, indices) in by_server {
+        let program = command[0].clone();
+        let mut client = match LspClient::start(&command, root, timeout) {
+            Ok(client) => client,
+            Err(e) => {
+                tracing::info!("Language server {} unavailable: {}", program, e);
+                report.unavailable.push(program);
+                continue;
+            }
+        };
+
+        let mut sources = HashMap::new();
+        for &index in &indices {
+            let path = absolute(&files[index].path);
+            let Ok(text) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+            let opened = client.notify(
+                "textDocument/didOpen",
+                json!({
+                    "textDocument": {
+                        "uri": path_to_uri(&path),
+                        "languageId": files[index].language.as_str(),
+                        "version": 1,
+                        "text": text,
+                    }
+                }),
+            );
+            if opened.is_err() {
+                break;
+            }
+            sources.insert(index, text);
+        }
+
+        let mut answered = false;
+        for definition in definitions(files, &indices, &sources).into_iter().take(settings.max_queries) {
+            let uri = path_to_uri(&absolute(&files[definition.file].path));
+            let result = client.request(
+                "textDocument/references",
+                json!({
+                    "textDocument": { "uri": uri },
+                    "position": { "line": definition.line - 1, "character": definition.column },
+                    "context": { "includeDeclaration": false },
+                }),
+            );
+            match result {
+                Ok(result) => {
+                    answered = true;
+                    let sites = reference_sites(&result, &file_index);
+                    upgrade_edges(files, &definition, &sites, &names, &mut report);
+                }
+                Err(e) if e.kind() == io::ErrorKind::TimedOut => {
+                    tracing::debug!("{} did not answer for {}: {}", program, definition.id, e);
+                }
+                Err(e) => {
+                    tracing::warn!("Language server {} stopped answering: {}", program, e);
+                    break;
+                }
+            }
+        }
+
+        client.shutdown();
+        if answered {
+            report.servers.push(program);
+        } else {
+            report.unavailable.push(program);
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use og_types::AstNode;
+
+    fn node(id: &str, name: &str, node_type: NodeType, start_line: usize, end_line: usize) -> AstNode {
+        AstNode {
+            id: id.to_string(),
+            node_type,
+            name: name.to_string(),
+            start_line,
+            end_line,
+            children: vec![],
+        }
+    }
+
+    #[test]
+    fn test_message_framing_and_uris() {
+        let message = json!({ "jsonrpc": "2.0", "id": 1, "result": null });
+        let mut reader = io::Cursor::new(frame(&message));
+        assert_eq!(read_message(&mut reader).unwrap(), Some(message));
+        assert_eq!(read_message(&mut reader).unwrap(), None);
+
+        let path = Path::new("/repo/my app/ünï.ts");
+        let uri = path_to_uri(path);
+        assert_eq!(uri, "file:///repo/my%20app/%C3%BCn%C3%AF.ts");
+        assert_eq!(uri_to_path(&uri).unwrap(), path);
+
+        assert_eq!(name_column("export function loadUser(id) {", "loadUser"), Some(16));
+        assert_eq!(name_column("def user(): return users()", "users"), Some(19));
+    }
+
+    #[test]
+    fn test_upgrade_edges_replaces_same_named_guess() {
+        let file = |path: &str, nodes: Vec<AstNode>, relationships: Vec<Relationship>| ParsedFile {
+            path: PathBuf::from(path),
+            language: Language::TypeScript,
+            nodes,
+            relationships,
+            metrics: Default::default(),
+        };
+        let calls = |source: &str, target: &str| Relationship {
+            source: source.to_string(),
+            target: target.to_string(),
+            relationship_type: RelationshipType::Calls,
+        };
+        let mut files = vec![
+            file(
+                "/repo/a.ts",
+                vec![
+                    node("file:a.ts", "a.ts", NodeType::File, 1, 20),
+                    node("fn:a:run", "run", NodeType::Function, 2, 6),
+                ],
+                vec![calls("fn:a:run", "fn:c:save")],
+            ),
+            file("/repo/b.ts", vec![node("fn:b:save", "save", NodeType::Function, 1, 3)], vec![]),
+            file("/repo/c.ts", vec![node("fn:c:save", "save", NodeType::Function, 1, 3)], vec![]),
+        ];
+        let names = files
+            .iter()
+            .flat_map(|f| f.nodes.iter().map(|n| (n.id.clone(), n.name.clone())))
+            .collect();
+        let definition = Definition {
+            file: 1,
+            id: "fn:b:save".to_string(),
+            name: "save".to_string(),
+            node_type: NodeType::Function,
+            line: 1,
+            column: 16,
+        };
+
+        let mut report = LspReport::default();
+        upgrade_edges(&mut files, &definition, &[(0, 4), (0, 12)], &names, &mut report);
+
+        let targets: Vec<(&str, &str)> = files[0]
+            .relationships
+            .iter()
+            .map(|r| (r.source.as_str(), r.target.as_str()))
+            .collect();
+        assert_eq!(targets, vec![("fn:a:run", "fn:b:save"), ("file:a.ts", "fn:b:save")]);
+        assert_eq!((report.edges_added, report.edges_removed), (2, 1));
+    }
+}
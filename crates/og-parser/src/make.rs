@@ -0,0 +1,190 @@
+use og_types::{AstNode, EngineResult, Language, NodeType, ParsedFile, Relationship, RelationshipType};
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::parser_trait::Parser;
+use crate::shell::{invocation_relationship, invocations, normalize};
+
+/// File names recognised as Makefiles regardless of extension
+const MAKEFILE_NAMES: &[&str] = &["Makefile", "makefile", "GNUmakefile"];
+
+/// Line-based parser producing a node per Makefile target
+pub struct MakefileParser;
+
+impl MakefileParser {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for MakefileParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Targets and prerequisites of a rule line, or None for anything else
+fn parse_rule(line: &str) -> Option<(Vec<&str>, Vec<&str>)> {
+    if line.starts_with('\t') || line.trim_start().starts_with('#') {
+        return None;
+    }
+    let (targets, rest) = line.split_once(':')?;
+    // Skip `:=` and `::=` assignments
+    if rest.starts_with('=') || rest.starts_with(":=") || targets.contains('=') {
+        return None;
+    }
+    let rest = rest.trim_start_matches(':');
+    let prerequisites = rest.split(['|', ';']).next().unwrap_or_default();
+
+    let targets: Vec<&str> = targets
+        .split_whitespace()
+        // Special targets such as .PHONY are directives, not build steps
+        .filter(|t| !t.starts_with('.') || t.contains('/'))
+        .filter(|t| !t.contains('$') && !t.contains('%'))
+        .collect();
+    let prerequisites = prerequisites
+        .split_whitespace()
+        .filter(|p| !p.contains('$') && !p.contains('%'))
+        .collect();
+
+    (!targets.is_empty()).then_some((targets, prerequisites))
+}
+
+impl Parser for MakefileParser {
+    fn supported_extensions(&self) -> &[&str] {
+        &[".mk"]
+    }
+
+    fn language(&self) -> Language {
+        Language::Make
+    }
+
+    fn can_parse(&self, path: &Path) -> bool {
+        let is_named = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|name| MAKEFILE_NAMES.contains(&name));
+        is_named || path.extension().is_some_and(|ext| ext == "mk")
+    }
+
+    fn parse(&self, path: &Path, content: &str) -> EngineResult<ParsedFile> {
+        let file_id = format!("file:{}", path.display());
+        let working_dir = path.parent().unwrap_or(Path::new(""));
+        let target_id = |name: &str| format!("target:{}:{}", path.display(), name);
+
+        let mut nodes = vec![AstNode {
+            id: file_id.clone(),
+            name: path.file_name().and_then(|n| n.to_str()).unwrap_or("Makefile").to_string(),
+            node_type: NodeType::File,
+            start_line: 0,
+            end_line: content.lines().count(),
+            children: vec![],
+        }];
+        let mut relationships = Vec::new();
+
+        // Collect targets first so prerequisites can tell targets from files
+        let rules: Vec<(usize, Vec<&str>, Vec<&str>)> = content
+            .lines()
+            .enumerate()
+            .filter_map(|(i, line)| parse_rule(line).map(|(t, p)| (i, t, p)))
+            .collect();
+        let known: HashSet<&str> = rules.iter().flat_map(|(_, targets, _)| targets.iter().copied()).collect();
+
+        let mut seen = HashSet::new();
+        for (line, targets, prerequisites) in &rules {
+            for target in targets {
+                let id = target_id(target);
+                if seen.insert(id.clone()) {
+                    nodes.push(AstNode {
+                        id: id.clone(),
+                        name: target.to_string(),
+                        node_type: NodeType::Target,
+                        start_line: line + 1,
+                        end_line: line + 1,
+                        children: vec![],
+                    });
+                    relationships.push(Relationship {
+                        source: file_id.clone(),
+                        target: id.clone(),
+                        relationship_type: RelationshipType::Contains,
+                    });
+                }
+
+                for prerequisite in prerequisites {
+                    let target = if known.contains(prerequisite) {
+                        target_id(prerequisite)
+                    } else {
+                        format!("file:{}", normalize(&working_dir.join(prerequisite)).display())
+                    };
+                    relationships.push(Relationship {
+                        source: id.clone(),
+                        target,
+                        relationship_type: RelationshipType::DependsOn,
+                    });
+                }
+            }
+        }
+
+        // Recipe lines belong to the most recent rule
+        let mut current: Vec<String> = Vec::new();
+        for (i, line) in content.lines().enumerate() {
+            if let Some(recipe) = line.strip_prefix('\t') {
+                for invocation in invocations(recipe.trim_start_matches(['@', '-', '+']), working_dir) {
+                    for source in &current {
+                        relationships.push(invocation_relationship(source, &invocation));
+                    }
+                }
+            } else if let Some((_, targets, _)) = rules.iter().find(|(line, _, _)| *line == i) {
+                current = targets.iter().map(|t| target_id(t)).collect();
+            } else if !line.trim().is_empty() && !line.trim_start().starts_with('#') {
+                current.clear();
+            }
+        }
+
+        Ok(ParsedFile {
+            path: path.to_path_buf(),
+            language: Language::Make,
+            nodes,
+            relationships,
+            metrics: Default::default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAKEFILE: &str = ".PHONY: build test\nVERSION := 1.0\n\nbuild: gen\n\t@./scripts/build.sh\n\ngen: schema.json\n\tpython gen.py\n\ntest: build\n\t$(MAKE) -C web test\n";
+
+    #[test]
+    fn test_targets_and_prerequisites() {
+        let parsed = MakefileParser::new().parse(Path::new("/repo/Makefile"), MAKEFILE).unwrap();
+        let targets: Vec<&str> = parsed.nodes.iter()
+            .filter(|n| n.node_type == NodeType::Target)
+            .map(|n| n.name.as_str())
+            .collect();
+        assert_eq!(targets, vec!["build", "gen", "test"]);
+
+        let depends: Vec<(&str, &str)> = parsed.relationships.iter()
+            .filter(|r| matches!(r.relationship_type, RelationshipType::DependsOn))
+            .map(|r| (r.source.as_str(), r.target.as_str()))
+            .collect();
+        assert!(depends.contains(&("target:/repo/Makefile:build", "target:/repo/Makefile:gen")));
+        assert!(depends.contains(&("target:/repo/Makefile:gen", "file:/repo/schema.json")));
+    }
+
+    #[test]
+    fn test_recipes_link_invoked_scripts() {
+        let parsed = MakefileParser::new().parse(Path::new("/repo/Makefile"), MAKEFILE).unwrap();
+        let calls: Vec<(&str, &str)> = parsed.relationships.iter()
+            .filter(|r| matches!(r.relationship_type, RelationshipType::Calls))
+            .map(|r| (r.source.as_str(), r.target.as_str()))
+            .collect();
+        assert_eq!(calls, vec![
+            ("target:/repo/Makefile:build", "file:/repo/scripts/build.sh"),
+            ("target:/repo/Makefile:gen", "file:/repo/gen.py"),
+            ("target:/repo/Makefile:test", "target:/repo/web/Makefile:test"),
+        ]);
+    }
+}
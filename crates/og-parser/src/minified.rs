@@ -0,0 +1,48 @@
+use std::path::Path;
+
+/// Lines longer than this are left by minifiers and bundlers, not people
+const MAX_HAND_WRITTEN_LINE: usize = 1_000;
+
+/// Files smaller than this are never treated as minified
+const MIN_MINIFIED_BYTES: usize = 1_024;
+
+/// Whether a file looks minified or bundled
+///
+/// A `.min.` file name always counts. Otherwise most of the content has to
+/// sit on lines too long to have been written by hand.
+pub fn is_minified(path: &Path, content: &str) -> bool {
+    let named_minified = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.contains(".min."));
+    if named_minified {
+        return true;
+    }
+    if content.len() < MIN_MINIFIED_BYTES {
+        return false;
+    }
+
+    let long_bytes: usize = content
+        .lines()
+        .map(str::len)
+        .filter(|&len| len > MAX_HAND_WRITTEN_LINE)
+        .sum();
+    long_bytes * 2 > content.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detects_minified_content() {
+        let readable = "function add(a, b) {\n    return a + b;\n}\n".repeat(100);
+        assert!(!is_minified(Path::new("add.js"), &readable));
+
+        let bundled = format!("// bundle\n{}\n", "var a=function(b){return b+1};".repeat(200));
+        assert!(is_minified(Path::new("bundle.js"), &bundled));
+
+        assert!(is_minified(Path::new("vendor/jquery.min.js"), "x"));
+        assert!(!is_minified(Path::new("short.js"), &"x".repeat(900)));
+    }
+}
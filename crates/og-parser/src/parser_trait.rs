@@ -1,5 +1,6 @@
 use og_types::{ParsedFile, Language, EngineResult};
 use std::path::Path;
+use tree_sitter::{Node, Tree};
 
 /// Trait for language-specific parsers
 pub trait Parser: Send + Sync {
@@ -21,4 +22,45 @@ pub trait Parser: Send + Sync {
     
     /// Parse a single file
     fn parse(&self, path: &Path, content: &str) -> EngineResult<ParsedFile>;
-}
\ No newline at end of file
+
+    /// Parse a new version of a file, reusing the unchanged parts of its
+    /// previous syntax tree
+    ///
+    /// `old_tree` must already be edited to match `content`. Returns the
+    /// new tree for the next edit, or `None` when the parser keeps no tree.
+    fn parse_incremental(
+        &self,
+        path: &Path,
+        content: &str,
+        old_tree: Option<&Tree>,
+    ) -> EngineResult<(ParsedFile, Option<Tree>)> {
+        let _ = old_tree;
+        Ok((self.parse(path, content)?, None))
+    }
+
+    /// Syntax tree of a new version of a file, for [`Parser::extract_items`]
+    ///
+    /// `old_tree` must already be edited to match `content`. Returns `None`
+    /// when the parser can only extract whole files.
+    fn reparse(&self, content: &str, old_tree: &Tree) -> Option<Tree> {
+        let _ = (content, old_tree);
+        None
+    }
+
+    /// Nodes, relationships and metrics of some top-level items of a file
+    /// alone, as if the rest of the file were blank
+    ///
+    /// `context` is the file's last extraction, which names resolve against.
+    /// Returns `None` when the items cannot be extracted on their own.
+    fn extract_items(
+        &self,
+        path: &Path,
+        content: &str,
+        tree: &Tree,
+        items: &[Node],
+        context: &ParsedFile,
+    ) -> Option<EngineResult<ParsedFile>> {
+        let _ = (path, content, tree, items, context);
+        None
+    }
+}
@@ -0,0 +1,33 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use tree_sitter::{Parser as TSParser, Tree};
+
+thread_local! {
+    /// Tree-sitter parsers of the current thread, by grammar name
+    static PARSERS: RefCell<HashMap<&'static str, TSParser>> = RefCell::new(HashMap::new());
+}
+
+/// Parse `content` with the current thread's parser for a grammar
+///
+/// Each worker thread makes its own parser the first time it meets a
+/// grammar, so files of one language parse in parallel instead of queuing
+/// on a shared parser. `old_tree` is the tree of the file's previous
+/// version, edited to match `content`, whose unchanged parts are reused.
+pub(crate) fn parse(
+    name: &'static str,
+    grammar: fn() -> tree_sitter::Language,
+    content: &str,
+    old_tree: Option<&Tree>,
+) -> Option<Tree> {
+    PARSERS.with(|parsers| {
+        let mut parsers = parsers.borrow_mut();
+        let parser = parsers.entry(name).or_insert_with(|| {
+            let mut parser = TSParser::new();
+            parser
+                .set_language(grammar())
+                .unwrap_or_else(|e| panic!("Error loading {} grammar: {}", name, e));
+            parser
+        });
+        parser.parse(content, old_tree)
+    })
+}
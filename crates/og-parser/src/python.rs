@@ -1,25 +1,34 @@
 use og_types::{
     AstNode, Language, NodeType, ParseError, ParsedFile, Relationship, RelationshipType,
 };
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
-use std::sync::Mutex;
-use tree_sitter::{Node, Parser as TSParser, TreeCursor};
+use tree_sitter::{Node, Tree, TreeCursor};
 
+use crate::concurrency;
+use crate::docs;
+use crate::dynamic_imports;
+use crate::env_vars;
+use crate::events;
+use crate::function_metrics;
+use crate::graphql;
+use crate::sql;
+use crate::frameworks;
+use crate::pool;
 use crate::parser_trait::Parser;
 use og_types::EngineResult;
 
-pub struct PythonParser {
-    parser: Mutex<TSParser>,
-}
+/// Target prefix for calls not yet resolved within their file
+const UNRESOLVED_CALL_PREFIX: &str = "pycall:";
+
+/// Target prefix for calls through an import, as a dotted qualified name
+pub const IMPORTED_CALL_PREFIX: &str = "pyimport:";
+
+pub struct PythonParser;
 
 impl PythonParser {
     pub fn new() -> Self {
-        let mut parser = TSParser::new();
-        parser
-            .set_language(tree_sitter_python::language())
-            .expect("Error loading Python grammar");
-        Self { parser: Mutex::new(parser) }
+        Self
     }
 
     fn extract_nodes(
@@ -31,7 +40,6 @@ impl PythonParser {
         let mut nodes = Vec::new();
         let mut relationships = Vec::new();
         let mut import_map = HashMap::new();
-        let mut complexity = 1;
 
         self.walk_tree(
             cursor,
@@ -40,9 +48,10 @@ impl PythonParser {
             &mut nodes,
             &mut relationships,
             &mut import_map,
-            &mut complexity,
             None,
         )?;
+        let ids = nodes.iter().map(|node| node.id.as_str()).collect();
+        Self::resolve_local_calls(&mut relationships, &ids, &import_map, file_path);
 
         Ok(ParsedFile {
             path: file_path.to_path_buf(),
@@ -61,7 +70,6 @@ impl PythonParser {
         nodes: &mut Vec<AstNode>,
         relationships: &mut Vec<Relationship>,
         import_map: &mut HashMap<String, String>,
-        complexity: &mut usize,
         parent_id: Option<String>,
     ) -> EngineResult<()> {
         let node = cursor.node();
@@ -70,20 +78,9 @@ impl PythonParser {
         match node_type {
             "module" => {
                 // Root module node
-                let file_id = format!("file:{}", file_path.display());
-                let file_name = file_path
-                    .file_name()
-                    .and_then(|n| n.to_str())
-                    .unwrap_or("unknown");
-                
-                nodes.push(AstNode {
-                    id: file_id.clone(),
-                    name: file_name.to_string(),
-                    node_type: NodeType::File,
-                    start_line: 0,
-                    end_line: source.lines().count(),
-                    children: vec![],
-                });
+                let file = file_node(file_path, source);
+                let file_id = file.id.clone();
+                nodes.push(file);
 
                 if cursor.goto_first_child() {
                     loop {
@@ -94,7 +91,6 @@ impl PythonParser {
                             nodes,
                             relationships,
                             import_map,
-                            complexity,
                             Some(file_id.clone()),
                         )?;
                         if !cursor.goto_next_sibling() {
@@ -115,7 +111,6 @@ impl PythonParser {
                     nodes,
                     relationships,
                     import_map,
-                    complexity,
                     &parent_id,
                 )?;
             }
@@ -127,7 +122,6 @@ impl PythonParser {
                     nodes,
                     relationships,
                     import_map,
-                    complexity,
                     &parent_id,
                     false,
                 )?;
@@ -146,7 +140,6 @@ impl PythonParser {
                                     nodes,
                                     relationships,
                                     import_map,
-                                    complexity,
                                     &parent_id,
                                     true,
                                 )?;
@@ -159,7 +152,6 @@ impl PythonParser {
                                     nodes,
                                     relationships,
                                     import_map,
-                                    complexity,
                                     &parent_id,
                                 )?;
                             }
@@ -173,7 +165,6 @@ impl PythonParser {
                 }
             }
             "if_statement" | "while_statement" | "for_statement" => {
-                *complexity += 1;
                 if cursor.goto_first_child() {
                     loop {
                         self.walk_tree(
@@ -183,7 +174,6 @@ impl PythonParser {
                             nodes,
                             relationships,
                             import_map,
-                            complexity,
                             parent_id.clone(),
                         )?;
                         if !cursor.goto_next_sibling() {
@@ -194,14 +184,8 @@ impl PythonParser {
                 }
             }
             "try_statement" => {
-                // Each except clause adds to complexity
-                *complexity += 1;
                 if cursor.goto_first_child() {
                     loop {
-                        let child = cursor.node();
-                        if child.kind() == "except_clause" {
-                            *complexity += 1;
-                        }
                         self.walk_tree(
                             cursor,
                             source,
@@ -209,7 +193,6 @@ impl PythonParser {
                             nodes,
                             relationships,
                             import_map,
-                            complexity,
                             parent_id.clone(),
                         )?;
                         if !cursor.goto_next_sibling() {
@@ -230,7 +213,6 @@ impl PythonParser {
                             nodes,
                             relationships,
                             import_map,
-                            complexity,
                             parent_id.clone(),
                         )?;
                         if !cursor.goto_next_sibling() {
@@ -285,7 +267,12 @@ impl PythonParser {
                                     target: format!("module:{}", module_path),
                                     relationship_type: RelationshipType::Imports,
                                 });
-                                import_map.insert(module_name.to_string(), parent.clone());
+                                let local_name = cursor
+                                    .node()
+                                    .child_by_field_name("alias")
+                                    .and_then(|alias| alias.utf8_text(source.as_bytes()).ok())
+                                    .unwrap_or(module_name);
+                                import_map.insert(local_name.to_string(), module_name.to_string());
                             }
                             if !cursor.goto_next_sibling() {
                                 break;
@@ -302,8 +289,12 @@ impl PythonParser {
                         loop {
                             match cursor.node().kind() {
                                 "dotted_name" | "relative_import" => {
+                                    let text = cursor.node().utf8_text(source.as_bytes()).map_err(ParseError::from)?.to_string();
                                     if module_name.is_none() {
-                                        module_name = Some(cursor.node().utf8_text(source.as_bytes()).map_err(ParseError::from)?.to_string());
+                                        module_name = Some(text);
+                                    } else {
+                                        // Names after the module are the imported symbols
+                                        imported_names.push(text);
                                     }
                                 }
                                 "import" => {
@@ -327,7 +318,12 @@ impl PythonParser {
                             target: format!("module:{}", module_path),
                             relationship_type: RelationshipType::Imports,
                         });
-                        import_map.insert(module, parent.clone());
+                        for imported in imported_names {
+                            let (name, local_name) = imported.split_once(" as ").unwrap_or((&imported, &imported));
+                            // `from . import x` must not gain an extra leading dot
+                            let separator = if module.ends_with('.') { "" } else { "." };
+                            import_map.insert(local_name.trim().to_string(), format!("{}{}{}", module, separator, name.trim()));
+                        }
                     }
                 }
                 _ => {}
@@ -336,6 +332,122 @@ impl PythonParser {
         Ok(())
     }
 
+    /// Dotted callee names of calls in `node`, not descending into nested definitions
+    fn collect_calls(node: Node, source: &str, callees: &mut Vec<String>) {
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            match child.kind() {
+                "function_definition" | "class_definition" => continue,
+                "call" => {
+                    let callee = child
+                        .child_by_field_name("function")
+                        .filter(|f| matches!(f.kind(), "identifier" | "attribute"))
+                        .and_then(|f| f.utf8_text(source.as_bytes()).ok())
+                        .map(|text| text.split_whitespace().collect::<String>());
+                    if let Some(callee) = callee {
+                        let is_dotted_name = callee.split('.').all(|part| {
+                            !part.is_empty() && part.chars().all(|c| c.is_alphanumeric() || c == '_')
+                        });
+                        if is_dotted_name && !callees.contains(&callee) {
+                            callees.push(callee);
+                        }
+                    }
+                }
+                _ => {}
+            }
+            Self::collect_calls(child, source, callees);
+        }
+    }
+
+    /// Resolve call placeholders against this file's definitions and imports
+    ///
+    /// `self.m()` and `cls.m()` resolve to a method of the enclosing class or a
+    /// same-file base class. Calls through imports become [`IMPORTED_CALL_PREFIX`]
+    /// placeholders for [`resolve_imported_calls`]. Anything else, such as
+    /// builtins, is dropped.
+    fn resolve_local_calls(
+        relationships: &mut Vec<Relationship>,
+        ids: &HashSet<&str>,
+        import_map: &HashMap<String, String>,
+        file_path: &Path,
+    ) {
+        let function_id = |name: &str| format!("function:{}:{}", file_path.display(), name);
+        let class_id = |name: &str| format!("class:{}:{}", file_path.display(), name);
+        let exists = |id: &str| ids.contains(id);
+
+        let mut members: HashMap<&str, Vec<&str>> = HashMap::new();
+        let mut container: HashMap<&str, &str> = HashMap::new();
+        let mut bases: HashMap<&str, Vec<&str>> = HashMap::new();
+        for rel in relationships.iter() {
+            match rel.relationship_type {
+                RelationshipType::Contains if rel.source.starts_with("class:") => {
+                    members.entry(&rel.source).or_default().push(&rel.target);
+                    container.insert(&rel.target, &rel.source);
+                }
+                RelationshipType::Extends => bases.entry(&rel.source).or_default().push(&rel.target),
+                _ => {}
+            }
+        }
+        let method_of = |class: &str, name: &str| -> Option<String> {
+            let id = function_id(name);
+            let own = std::iter::once(class);
+            let inherited = bases.get(class).into_iter().flatten().copied();
+            own.chain(inherited)
+                .any(|c| members.get(c).is_some_and(|m| m.contains(&id.as_str())))
+                .then_some(id)
+        };
+
+        let resolved: Vec<Option<String>> = relationships
+            .iter()
+            .map(|rel| {
+                let callee = rel.target.strip_prefix(UNRESOLVED_CALL_PREFIX)?;
+                let (head, rest) = callee.split_once('.').unwrap_or((callee, ""));
+
+                if head == "self" || head == "cls" {
+                    let class = container.get(rel.source.as_str())?;
+                    return (!rest.contains('.')).then(|| method_of(class, rest)).flatten();
+                }
+                if rest.is_empty() && exists(&function_id(head)) {
+                    return Some(function_id(head));
+                }
+                if exists(&class_id(head)) {
+                    return if rest.is_empty() {
+                        Some(class_id(head))
+                    } else {
+                        method_of(&class_id(head), rest)
+                    };
+                }
+
+                // Longest imported prefix, so `os.path.join` prefers an `os.path` import
+                let parts: Vec<&str> = callee.split('.').collect();
+                (1..=parts.len()).rev().find_map(|len| {
+                    let qualified = import_map.get(&parts[..len].join("."))?;
+                    let mut target = format!("{}{}", IMPORTED_CALL_PREFIX, qualified);
+                    for part in &parts[len..] {
+                        target.push('.');
+                        target.push_str(part);
+                    }
+                    Some(target)
+                })
+            })
+            .collect();
+
+        let mut resolved = resolved.into_iter();
+        relationships.retain_mut(|rel| {
+            if !rel.target.starts_with(UNRESOLVED_CALL_PREFIX) {
+                resolved.next();
+                return true;
+            }
+            match resolved.next().flatten() {
+                Some(target) => {
+                    rel.target = target;
+                    true
+                }
+                None => false,
+            }
+        });
+    }
+
     fn process_class(
         &self,
         cursor: &mut TreeCursor,
@@ -344,7 +456,6 @@ impl PythonParser {
         nodes: &mut Vec<AstNode>,
         relationships: &mut Vec<Relationship>,
         import_map: &mut HashMap<String, String>,
-        complexity: &mut usize,
         parent_id: &Option<String>,
     ) -> EngineResult<()> {
         let node = cursor.node();
@@ -429,7 +540,6 @@ impl PythonParser {
                                 nodes,
                                 relationships,
                                 import_map,
-                                complexity,
                                 Some(class_id.clone()),
                             )?;
                             if !block_cursor.goto_next_sibling() {
@@ -457,7 +567,6 @@ impl PythonParser {
         nodes: &mut Vec<AstNode>,
         relationships: &mut Vec<Relationship>,
         import_map: &mut HashMap<String, String>,
-        complexity: &mut usize,
         parent_id: &Option<String>,
         is_decorated: bool,
     ) -> EngineResult<()> {
@@ -515,7 +624,20 @@ impl PythonParser {
             });
         }
 
-        // Process function body for complexity
+        // Calls are resolved once the whole file has been walked
+        if let Some(body) = node.child_by_field_name("body") {
+            let mut callees = Vec::new();
+            Self::collect_calls(body, source, &mut callees);
+            for callee in callees {
+                relationships.push(Relationship {
+                    source: func_id.clone(),
+                    target: format!("{}{}", UNRESOLVED_CALL_PREFIX, callee),
+                    relationship_type: RelationshipType::Calls,
+                });
+            }
+        }
+
+        // Process function body for nested definitions
         if cursor.goto_first_child() {
             loop {
                 if cursor.node().kind() == "block" {
@@ -529,7 +651,6 @@ impl PythonParser {
                                 nodes,
                                 relationships,
                                 import_map,
-                                complexity,
                                 Some(func_id.clone()),
                             )?;
                             if !block_cursor.goto_next_sibling() {
@@ -560,14 +681,260 @@ impl Parser for PythonParser {
     }
 
     fn parse(&self, path: &Path, content: &str) -> EngineResult<ParsedFile> {
-        let tree = self
-            .parser
-            .lock()
-            .unwrap()
-            .parse(content, None)
+        self.parse_incremental(path, content, None).map(|(parsed, _)| parsed)
+    }
+
+    fn parse_incremental(
+        &self,
+        path: &Path,
+        content: &str,
+        old_tree: Option<&Tree>,
+    ) -> EngineResult<(ParsedFile, Option<Tree>)> {
+        let tree = pool::parse("Python", tree_sitter_python::language, content, old_tree)
             .ok_or_else(|| ParseError::ParseFailed("Failed to parse Python file".to_string()))?;
 
         let mut cursor = tree.root_node().walk();
-        self.extract_nodes(&mut cursor, content, path)
+        let mut parsed = self.extract_nodes(&mut cursor, content, path)?;
+        frameworks::extract_python(tree.root_node(), content, path).append_to(&mut parsed);
+        dynamic_imports::apply(&mut parsed, dynamic_imports::find_python(tree.root_node(), content), module_target);
+        env_vars::apply(&mut parsed, env_vars::find_python(tree.root_node(), content));
+        let file_id = format!("file:{}", path.display());
+        sql::apply(&mut parsed, sql::find_python(tree.root_node(), content, &file_id));
+        events::apply(&mut parsed, events::find_python(tree.root_node(), content));
+        graphql::apply(&mut parsed, graphql::find_python(tree.root_node(), content));
+        function_metrics::apply(&mut parsed, function_metrics::find_python(tree.root_node(), content));
+        docs::apply(&mut parsed, docs::find_python(tree.root_node(), content));
+        concurrency::apply(&mut parsed, concurrency::find_python(tree.root_node(), content));
+        drop(cursor);
+        Ok((parsed, Some(tree)))
+    }
+
+    fn reparse(&self, content: &str, old_tree: &Tree) -> Option<Tree> {
+        pool::parse("Python", tree_sitter_python::language, content, Some(old_tree))
+    }
+
+    /// Definitions and comments extract on their own, unless they register
+    /// routes or GraphQL resolvers, which depend on the rest of the file
+    fn extract_items(
+        &self,
+        path: &Path,
+        content: &str,
+        tree: &Tree,
+        items: &[Node],
+        context: &ParsedFile,
+    ) -> Option<EngineResult<ParsedFile>> {
+        if !items.iter().all(is_definition) {
+            return None;
+        }
+        let mut routes = items.iter().map(|item| frameworks::extract_python(*item, content, path));
+        if routes.any(|found| !found.nodes.is_empty() || !found.relationships.is_empty()) {
+            return None;
+        }
+        let graphql: Vec<_> = items.iter().map(|item| graphql::find_python(*item, content)).collect();
+        if graphql.iter().any(|found| !found.resolvers.is_empty()) {
+            return None;
+        }
+        Some(self.extract_definitions(path, content, tree, items, context, graphql))
     }
-}
\ No newline at end of file
+}
+
+impl PythonParser {
+    fn extract_definitions(
+        &self,
+        path: &Path,
+        content: &str,
+        tree: &Tree,
+        items: &[Node],
+        context: &ParsedFile,
+        graphql: Vec<graphql::GraphqlItems>,
+    ) -> EngineResult<ParsedFile> {
+        let file = file_node(path, content);
+        let file_id = file.id.clone();
+
+        // Calls resolve through every import of the file, which only sit
+        // outside definitions unless the last extraction saw one inside
+        let nested_imports = context.relationships.iter().any(|rel| {
+            matches!(rel.relationship_type, RelationshipType::Imports) && rel.source != file_id
+        });
+        let root = tree.root_node();
+        let mut cursor = root.walk();
+        let scopes: Vec<Node> = if nested_imports {
+            vec![root]
+        } else {
+            root.children(&mut cursor).filter(|child| !is_definition(child)).collect()
+        };
+        let mut import_map = HashMap::new();
+        let mut imports = Vec::new();
+        let mut found = Ok(());
+        for scope in scopes {
+            env_vars::walk(scope, &mut |node| {
+                if matches!(node.kind(), "import_statement" | "import_from_statement") && found.is_ok() {
+                    found = self.process_import(node, content, &Some(file_id.clone()), &mut imports, &mut import_map);
+                }
+            });
+        }
+        found?;
+
+        let mut nodes = vec![file];
+        let mut relationships = Vec::new();
+        for item in items {
+            let mut cursor = item.walk();
+            self.walk_tree(
+                &mut cursor,
+                content,
+                path,
+                &mut nodes,
+                &mut relationships,
+                &mut import_map,
+                Some(file_id.clone()),
+            )?;
+        }
+
+        // Resolve against the classes and functions of the whole file
+        let mut all: Vec<Relationship> = context
+            .relationships
+            .iter()
+            .filter(|rel| matches!(rel.relationship_type, RelationshipType::Contains | RelationshipType::Extends))
+            .cloned()
+            .collect();
+        let structure = all.len();
+        all.append(&mut relationships);
+        let ids = context.nodes.iter().chain(&nodes).map(|node| node.id.as_str()).collect();
+        Self::resolve_local_calls(&mut all, &ids, &import_map, path);
+        let relationships = all.split_off(structure);
+
+        let mut parsed = ParsedFile {
+            path: path.to_path_buf(),
+            language: Language::Python,
+            nodes,
+            relationships,
+            metrics: Default::default(),
+        };
+        for item in items {
+            dynamic_imports::apply(&mut parsed, dynamic_imports::find_python(*item, content), module_target);
+            env_vars::apply(&mut parsed, env_vars::find_python(*item, content));
+            sql::apply(&mut parsed, sql::find_python(*item, content, &file_id));
+            events::apply(&mut parsed, events::find_python(*item, content));
+        }
+        for found in graphql {
+            graphql::apply(&mut parsed, found);
+        }
+        let shapes = items.iter().flat_map(|item| function_metrics::find_python(*item, content)).collect();
+        function_metrics::apply(&mut parsed, shapes);
+        docs::apply(&mut parsed, docs::find_python_items(items, content));
+        for item in items {
+            concurrency::apply(&mut parsed, concurrency::find_python(*item, content));
+        }
+        Ok(parsed)
+    }
+}
+
+/// Node of the file itself
+fn file_node(path: &Path, source: &str) -> AstNode {
+    AstNode {
+        id: format!("file:{}", path.display()),
+        name: path.file_name().and_then(|n| n.to_str()).unwrap_or("unknown").to_string(),
+        node_type: NodeType::File,
+        start_line: 0,
+        end_line: source.lines().count(),
+        children: vec![],
+    }
+}
+
+/// Top-level items whose nodes do not depend on the rest of the file
+fn is_definition(item: &Node) -> bool {
+    matches!(item.kind(), "function_definition" | "class_definition" | "decorated_definition" | "comment")
+}
+
+/// Target of an import of a dotted module name
+fn module_target(module: &str) -> Option<String> {
+    Some(format!("module:{}", module.replace('.', "/")))
+}
+
+/// Dotted module names a Python file can be imported as, e.g. `pkg.util` and `util`
+fn module_names(path: &Path) -> Vec<String> {
+    let mut parts: Vec<&str> = path
+        .iter()
+        .filter_map(|part| part.to_str())
+        .filter(|part| *part != "/")
+        .collect();
+    let Some(last) = parts.pop() else {
+        return Vec::new();
+    };
+    let stem = last.trim_end_matches(".pyi").trim_end_matches(".py");
+    if stem != "__init__" {
+        parts.push(stem);
+    }
+    (0..parts.len()).map(|start| parts[start..].join(".")).collect()
+}
+
+/// Resolve calls through imports to functions, classes and methods in other files
+///
+/// Relative modules resolve against the importing file's package; absolute
+/// ones match any file whose path ends with the module path, when exactly one
+/// does. Calls that cannot be resolved are dropped.
+pub fn resolve_imported_calls(files: &mut [ParsedFile]) {
+    let is_python = |file: &ParsedFile| matches!(file.language, Language::Python);
+
+    let mut by_module: HashMap<String, Vec<usize>> = HashMap::new();
+    let mut ids: HashSet<String> = HashSet::new();
+    for (index, file) in files.iter().enumerate().filter(|(_, file)| is_python(file)) {
+        for name in module_names(&file.path) {
+            by_module.entry(name).or_default().push(index);
+        }
+        ids.extend(file.nodes.iter().map(|node| node.id.clone()));
+    }
+
+    let paths: Vec<std::path::PathBuf> = files.iter().map(|file| file.path.clone()).collect();
+    let module_file = |module: &str, importer: &Path| -> Option<usize> {
+        let dots = module.len() - module.trim_start_matches('.').len();
+        if dots == 0 {
+            return match by_module.get(module).map(Vec::as_slice) {
+                Some([index]) => Some(*index),
+                _ => None,
+            };
+        }
+        let mut dir = importer.parent()?.to_path_buf();
+        for _ in 1..dots {
+            dir = dir.parent()?.to_path_buf();
+        }
+        let relative = module.trim_start_matches('.').replace('.', "/");
+        let base = if relative.is_empty() { dir } else { dir.join(relative) };
+        let candidates = [base.with_extension("py"), base.join("__init__.py")];
+        paths.iter().position(|path| candidates.contains(path))
+    };
+
+    for file in files.iter_mut().filter(|file| is_python(file)) {
+        let importer = file.path.clone();
+        file.relationships.retain_mut(|rel| {
+            let Some(qualified) = rel.target.strip_prefix(IMPORTED_CALL_PREFIX) else {
+                return true;
+            };
+            // Leading dots belong to the module, not to a separator
+            let dots = qualified.len() - qualified.trim_start_matches('.').len();
+            let (prefix, dotted) = qualified.split_at(dots);
+            let parts: Vec<&str> = dotted.split('.').collect();
+
+            let target = (0..parts.len()).rev().find_map(|len| {
+                let module = format!("{}{}", prefix, parts[..len].join("."));
+                let path = &paths[module_file(&module, &importer)?];
+                let candidate = match &parts[len..] {
+                    [name] => [format!("function:{}:{}", path.display(), name), format!("class:{}:{}", path.display(), name)]
+                        .into_iter()
+                        .find(|id| ids.contains(id)),
+                    [_, method] => Some(format!("function:{}:{}", path.display(), method)).filter(|id| ids.contains(id)),
+                    _ => None,
+                };
+                candidate
+            });
+
+            match target {
+                Some(target) => {
+                    rel.target = target;
+                    true
+                }
+                None => false,
+            }
+        });
+    }
+}
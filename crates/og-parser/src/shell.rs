@@ -0,0 +1,198 @@
+use og_types::{AstNode, EngineResult, Language, NodeType, ParsedFile, Relationship, RelationshipType};
+use std::path::{Component, Path, PathBuf};
+
+use crate::parser_trait::Parser;
+
+/// Programs whose first file argument is the script they run
+const INTERPRETERS: &[&str] = &[
+    "bash", "sh", "zsh", "source", ".", "python", "python3", "node", "deno", "ts-node", "ruby", "perl",
+];
+
+/// Command prefixes that run the rest of the line unchanged
+const WRAPPERS: &[&str] = &["sudo", "exec", "env", "time", "nohup"];
+
+/// Something a shell command line runs that may exist in the graph
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Invocation {
+    /// A script or source file, resolved against the working directory
+    Script(PathBuf),
+    /// `make <target>` in the given directory
+    MakeTarget { dir: PathBuf, target: String },
+}
+
+/// Find scripts and make targets run by one line of shell
+pub fn invocations(line: &str, working_dir: &Path) -> Vec<Invocation> {
+    let line = line.split(" #").next().unwrap_or_default();
+    let mut found = Vec::new();
+
+    for command in line.split(['&', '|', ';']).map(str::trim).filter(|c| !c.is_empty()) {
+        let mut words = command
+            .split_whitespace()
+            .map(unquote)
+            .filter(|word| !word.contains('=') && !WRAPPERS.contains(word));
+
+        let Some(program) = words.next() else {
+            continue;
+        };
+        let program = program.trim_start_matches(['@', '-', '+']);
+
+        if program == "make" || program == "$(MAKE)" || program == "${MAKE}" {
+            let mut dir = working_dir.to_path_buf();
+            while let Some(word) = words.next() {
+                if word == "-C" {
+                    if let Some(sub) = words.next() {
+                        dir = normalize(&dir.join(sub));
+                    }
+                } else if !word.starts_with('-') && !word.contains('$') {
+                    found.push(Invocation::MakeTarget {
+                        dir: dir.clone(),
+                        target: word.to_string(),
+                    });
+                }
+            }
+        } else if INTERPRETERS.contains(&program) {
+            if let Some(script) = words.find(|word| !word.starts_with('-')) {
+                if is_path(script) || script.contains('.') {
+                    found.push(Invocation::Script(normalize(&working_dir.join(script))));
+                }
+            }
+        } else if is_path(program) {
+            found.push(Invocation::Script(normalize(&working_dir.join(program))));
+        }
+    }
+
+    found
+}
+
+/// Strip quotes and subshell parentheses, keeping `$(VAR)` intact
+fn unquote(word: &str) -> &str {
+    let word = word.trim_matches(|c| c == '"' || c == '\'');
+    if word.starts_with("$(") {
+        word
+    } else {
+        word.trim_matches(|c| c == '(' || c == ')')
+    }
+}
+
+fn is_path(word: &str) -> bool {
+    !word.contains('$') && (word.contains('/') || word.ends_with(".sh"))
+}
+
+/// Resolve `.` and `..` without touching the filesystem
+pub fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    normalized.push("..");
+                }
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// Edge from `source` to whatever an invocation runs
+pub fn invocation_relationship(source: &str, invocation: &Invocation) -> Relationship {
+    let target = match invocation {
+        Invocation::Script(path) => format!("file:{}", path.display()),
+        Invocation::MakeTarget { dir, target } => format!("target:{}:{}", dir.join("Makefile").display(), target),
+    };
+    Relationship {
+        source: source.to_string(),
+        target,
+        relationship_type: RelationshipType::Calls,
+    }
+}
+
+/// Line-based parser for shell scripts
+pub struct ShellParser;
+
+impl ShellParser {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for ShellParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Parser for ShellParser {
+    fn supported_extensions(&self) -> &[&str] {
+        &[".sh", ".bash"]
+    }
+
+    fn language(&self) -> Language {
+        Language::Shell
+    }
+
+    fn parse(&self, path: &Path, content: &str) -> EngineResult<ParsedFile> {
+        let script_id = format!("file:{}", path.display());
+        let working_dir = path.parent().unwrap_or(Path::new(""));
+
+        let nodes = vec![AstNode {
+            id: script_id.clone(),
+            name: path.file_name().and_then(|n| n.to_str()).unwrap_or("unknown").to_string(),
+            node_type: NodeType::Script,
+            start_line: 0,
+            end_line: content.lines().count(),
+            children: vec![],
+        }];
+
+        let relationships = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.starts_with('#'))
+            .flat_map(|line| invocations(line, working_dir))
+            .map(|invocation| invocation_relationship(&script_id, &invocation))
+            .collect();
+
+        Ok(ParsedFile {
+            path: path.to_path_buf(),
+            language: Language::Shell,
+            nodes,
+            relationships,
+            metrics: Default::default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invocations() {
+        let dir = Path::new("/repo/scripts");
+        assert_eq!(
+            invocations("FOO=1 python3 -u ../gen.py --out x && ./post.sh", dir),
+            vec![
+                Invocation::Script(PathBuf::from("/repo/gen.py")),
+                Invocation::Script(PathBuf::from("/repo/scripts/post.sh")),
+            ]
+        );
+        assert_eq!(
+            invocations("make -C .. build test", dir),
+            vec![
+                Invocation::MakeTarget { dir: PathBuf::from("/repo"), target: "build".to_string() },
+                Invocation::MakeTarget { dir: PathBuf::from("/repo"), target: "test".to_string() },
+            ]
+        );
+        assert!(invocations("echo done", dir).is_empty());
+    }
+
+    #[test]
+    fn test_shell_script_node() {
+        let parsed = ShellParser::new()
+            .parse(Path::new("/repo/build.sh"), "#!/bin/sh\nbash scripts/setup.sh\n")
+            .unwrap();
+        assert_eq!(parsed.nodes[0].node_type, NodeType::Script);
+        assert_eq!(parsed.relationships[0].target, "file:/repo/scripts/setup.sh");
+    }
+}
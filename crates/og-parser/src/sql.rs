@@ -0,0 +1,454 @@
+use og_types::{NodeType, ParsedFile, RelationshipType};
+use std::collections::HashSet;
+use tree_sitter::Node;
+
+use crate::env_vars::{add_node_once, enclosing_function, relate_once, text, walk};
+
+/// Statements that start an embedded SQL query, with a keyword the query must also contain
+const SQL_STATEMENTS: &[(&str, &str)] = &[
+    ("SELECT", "FROM"),
+    ("INSERT", "INTO"),
+    ("UPDATE", "SET"),
+    ("DELETE", "FROM"),
+    ("WITH", "AS"),
+    ("MERGE", "INTO"),
+    ("REPLACE", "INTO"),
+];
+
+/// Keywords that can follow FROM or JOIN without being a table name
+const NOT_TABLES: &[&str] = &["SELECT", "LATERAL", "ONLY", "UNNEST"];
+
+/// Words after FROM or INTO that mean the string is prose rather than SQL
+const PROSE_WORDS: &[&str] = &["THE", "A", "AN", "THIS", "THAT", "THESE", "THOSE", "YOUR", "OUR", "ITS", "EACH", "EVERY"];
+
+/// Keywords that end a comma-separated FROM list
+const CLAUSE_KEYWORDS: &[&str] = &[
+    "WHERE", "JOIN", "LEFT", "RIGHT", "INNER", "OUTER", "FULL", "CROSS", "NATURAL", "ON", "USING", "GROUP",
+    "ORDER", "HAVING", "LIMIT", "OFFSET", "UNION", "INTERSECT", "EXCEPT", "SET", "VALUES", "RETURNING",
+    "WINDOW", "FOR", "SELECT",
+];
+
+/// Prisma client methods that only read
+const PRISMA_READS: &[&str] = &[
+    "findMany", "findUnique", "findUniqueOrThrow", "findFirst", "findFirstOrThrow", "count", "aggregate", "groupBy",
+];
+
+/// Prisma client methods that write
+const PRISMA_WRITES: &[&str] = &["create", "createMany", "update", "updateMany", "upsert", "delete", "deleteMany"];
+
+/// SQLAlchemy constructs and session methods that read a model's table
+const SQLALCHEMY_READS: &[&str] = &["select", "query", "get"];
+
+/// SQLAlchemy constructs and session methods that write a model's table
+const SQLALCHEMY_WRITES: &[&str] = &["insert", "update", "delete", "add", "merge"];
+
+/// Whether code reads or writes a table or model
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    Read,
+    Write,
+}
+
+impl Access {
+    fn relationship_type(self) -> RelationshipType {
+        match self {
+            Access::Read => RelationshipType::Reads,
+            Access::Write => RelationshipType::Writes,
+        }
+    }
+}
+
+/// Database object that code depends on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DataTarget {
+    /// Table named in a SQL query
+    Table(String),
+    /// ORM model class
+    Model(String),
+}
+
+impl DataTarget {
+    /// Node id shared by every file that uses the table or model
+    pub fn id(&self) -> String {
+        match self {
+            DataTarget::Table(name) => format!("table:{}", name),
+            DataTarget::Model(name) => format!("model:{}", name),
+        }
+    }
+}
+
+/// A read or write of a table or model at a line of a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataAccess {
+    pub target: DataTarget,
+    pub access: Access,
+    pub line: usize,
+}
+
+/// An ORM model declared in a file, and the table it maps to if known
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelDefinition {
+    pub name: String,
+    pub table: Option<String>,
+    /// Class declaring the model, if the model comes from a class
+    pub class_id: Option<String>,
+    pub line: usize,
+}
+
+/// Data-layer models and accesses found in one file
+#[derive(Debug, Default)]
+pub struct DataLayer {
+    pub models: Vec<ModelDefinition>,
+    pub accesses: Vec<DataAccess>,
+}
+
+fn line(node: Node) -> usize {
+    node.start_position().row + 1
+}
+
+/// Split SQL into identifiers and the punctuation that matters for finding tables
+fn tokens(sql: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    for c in sql.chars() {
+        if c.is_alphanumeric() || matches!(c, '_' | '.' | '$') {
+            current.push(c);
+            continue;
+        }
+        if !current.is_empty() {
+            tokens.push(std::mem::take(&mut current));
+        }
+        if matches!(c, '(' | ')' | ',') {
+            tokens.push(c.to_string());
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+/// Tables a SQL statement reads and writes, lowercased, or nothing if the text is not SQL
+pub fn sql_tables(sql: &str) -> Vec<(String, Access)> {
+    let tokens = tokens(sql);
+    let upper: Vec<String> = tokens.iter().map(|t| t.to_ascii_uppercase()).collect();
+    let Some(first) = upper.first() else {
+        return Vec::new();
+    };
+    let is_sql = SQL_STATEMENTS
+        .iter()
+        .any(|(statement, required)| first == statement && upper[1..].iter().any(|t| t == required));
+    if !is_sql {
+        return Vec::new();
+    }
+
+    // Common table expressions look like tables when they are queried
+    let ctes: HashSet<&str> = upper
+        .windows(3)
+        .filter(|w| w[1] == "AS" && w[2] == "(")
+        .map(|w| w[0].as_str())
+        .collect();
+
+    let mut tables: Vec<(String, Access)> = Vec::new();
+    let mut expect: Option<Access> = None;
+    let mut from_list: Option<Access> = None;
+    let mut deleting = false;
+    for (token, keyword) in tokens.iter().zip(&upper) {
+        match keyword.as_str() {
+            "FROM" => {
+                expect = Some(if deleting { Access::Write } else { Access::Read });
+                deleting = false;
+            }
+            "JOIN" => expect = Some(Access::Read),
+            "INTO" | "UPDATE" => expect = Some(Access::Write),
+            "DELETE" => deleting = true,
+            "," => expect = from_list,
+            "(" | ")" => {
+                expect = None;
+                from_list = None;
+            }
+            _ if CLAUSE_KEYWORDS.contains(&keyword.as_str()) => from_list = None,
+            _ => {
+                let Some(access) = expect.take() else {
+                    continue;
+                };
+                if PROSE_WORDS.contains(&keyword.as_str()) {
+                    return Vec::new();
+                }
+                if NOT_TABLES.contains(&keyword.as_str()) || ctes.contains(keyword.as_str()) {
+                    continue;
+                }
+                let entry = (token.to_lowercase(), access);
+                if !tables.contains(&entry) {
+                    tables.push(entry);
+                }
+                from_list = Some(access);
+            }
+        }
+    }
+    tables
+}
+
+/// String contents with prefixes and quotes removed, keeping any interpolation
+fn string_contents(node: Node, source: &str) -> Option<String> {
+    let text = match node.kind() {
+        "string" | "template_string" | "string_literal" | "raw_string_literal" => node.utf8_text(source.as_bytes()).ok()?,
+        _ => return None,
+    };
+    let text = text.trim_start_matches(|c: char| c.is_ascii_alphabetic());
+    Some(text.trim_matches(|c| c == '"' || c == '\'' || c == '`').to_string())
+}
+
+/// SQL queries embedded in string literals
+fn find_sql(root: Node, source: &str, layer: &mut DataLayer) {
+    walk(root, &mut |node| {
+        let Some(sql) = string_contents(node, source) else {
+            return;
+        };
+        for (table, access) in sql_tables(&sql) {
+            layer.accesses.push(DataAccess {
+                target: DataTarget::Table(table),
+                access,
+                line: line(node),
+            });
+        }
+    });
+}
+
+/// Embedded SQL and Prisma client calls such as `prisma.user.findMany()` in JavaScript or TypeScript
+pub fn find_javascript(root: Node, source: &str) -> DataLayer {
+    let mut layer = DataLayer::default();
+    find_sql(root, source, &mut layer);
+
+    walk(root, &mut |node| {
+        if node.kind() != "call_expression" {
+            return;
+        }
+        let Some(method) = node.child_by_field_name("function").filter(|f| f.kind() == "member_expression") else {
+            return;
+        };
+        let Some(delegate) = method.child_by_field_name("object").filter(|o| o.kind() == "member_expression") else {
+            return;
+        };
+        let is_client = text(delegate.child_by_field_name("object"), source)
+            .is_some_and(|client| client.rsplit('.').next().is_some_and(|c| c.eq_ignore_ascii_case("prisma")));
+        let (Some(operation), Some(model)) = (
+            text(method.child_by_field_name("property"), source),
+            text(delegate.child_by_field_name("property"), source),
+        ) else {
+            return;
+        };
+        let access = if PRISMA_READS.contains(&operation) {
+            Access::Read
+        } else if PRISMA_WRITES.contains(&operation) {
+            Access::Write
+        } else {
+            return;
+        };
+        if !is_client || model.starts_with('$') {
+            return;
+        }
+
+        // Prisma exposes model `User` as the delegate `user`
+        let mut chars = model.chars();
+        let name: String = chars.next().map(|c| c.to_ascii_uppercase()).into_iter().chain(chars).collect();
+        layer.models.push(ModelDefinition {
+            name: name.clone(),
+            table: None,
+            class_id: None,
+            line: line(node),
+        });
+        layer.accesses.push(DataAccess {
+            target: DataTarget::Model(name),
+            access,
+            line: line(node),
+        });
+    });
+    layer
+}
+
+/// Class that an argument names, such as `User` in `select(User)` or `User.id`
+fn model_argument<'a>(argument: Node, source: &'a str) -> Option<&'a str> {
+    let name = match argument.kind() {
+        "identifier" => text(Some(argument), source)?,
+        "attribute" => text(argument.child_by_field_name("object"), source)?,
+        "call" => text(argument.child_by_field_name("function"), source)?,
+        _ => return None,
+    };
+    (name.starts_with(|c: char| c.is_ascii_uppercase()) && !name.contains('.')).then_some(name)
+}
+
+/// Embedded SQL, SQLAlchemy models and the queries that use them in Python
+pub fn find_python(root: Node, source: &str, file_id: &str) -> DataLayer {
+    let mut layer = DataLayer::default();
+    find_sql(root, source, &mut layer);
+    let class_prefix = file_id.replacen("file:", "class:", 1);
+
+    walk(root, &mut |node| match node.kind() {
+        "class_definition" => {
+            let Some(name) = text(node.child_by_field_name("name"), source) else {
+                return;
+            };
+            let Some(body) = node.child_by_field_name("body") else {
+                return;
+            };
+            let mut cursor = body.walk();
+            let table = body.named_children(&mut cursor).find_map(|statement| {
+                let assignment = statement.named_child(0).filter(|a| a.kind() == "assignment")?;
+                (text(assignment.child_by_field_name("left"), source)? == "__tablename__")
+                    .then(|| assignment.child_by_field_name("right"))
+                    .flatten()
+                    .and_then(|right| string_contents(right, source))
+            });
+            let is_flask_model = text(node.child_by_field_name("superclasses"), source)
+                .is_some_and(|bases| bases.contains("db.Model"));
+            if table.is_some() || is_flask_model {
+                layer.models.push(ModelDefinition {
+                    name: name.to_string(),
+                    table: table.map(|t| t.to_lowercase()),
+                    class_id: Some(format!("{}:{}", class_prefix, name)),
+                    line: line(node),
+                });
+            }
+        }
+        "call" => {
+            let Some(function) = text(node.child_by_field_name("function"), source) else {
+                return;
+            };
+            let operation = function.rsplit('.').next().unwrap_or(function);
+            let access = if SQLALCHEMY_READS.contains(&operation) {
+                Access::Read
+            } else if SQLALCHEMY_WRITES.contains(&operation) {
+                Access::Write
+            } else {
+                return;
+            };
+            let Some(arguments) = node.child_by_field_name("arguments") else {
+                return;
+            };
+            let mut cursor = arguments.walk();
+            for argument in arguments.named_children(&mut cursor) {
+                if let Some(model) = model_argument(argument, source) {
+                    layer.accesses.push(DataAccess {
+                        target: DataTarget::Model(model.to_string()),
+                        access,
+                        line: line(node),
+                    });
+                }
+            }
+        }
+        _ => {}
+    });
+    layer
+}
+
+/// SQL queries embedded in C string literals
+pub fn find_c(root: Node, source: &str) -> DataLayer {
+    let mut layer = DataLayer::default();
+    find_sql(root, source, &mut layer);
+    layer
+}
+
+/// Add table and model nodes and link the functions that use them
+///
+/// Tables named in SQL always get a node. Models only get one where they are
+/// declared, so accesses to names that are not models are dropped with the
+/// rest of the dangling edges when the graph is built.
+pub fn apply(parsed: &mut ParsedFile, layer: DataLayer) {
+    for model in layer.models {
+        let model_id = DataTarget::Model(model.name.clone()).id();
+        add_node_once(parsed, &model_id, NodeType::Model, &model.name, model.line);
+        if let Some(class_id) = model.class_id {
+            relate_once(parsed, model_id.clone(), class_id, RelationshipType::Decorates);
+        }
+        if let Some(table) = model.table {
+            let table_id = DataTarget::Table(table.clone()).id();
+            add_node_once(parsed, &table_id, NodeType::Table, &table, model.line);
+            relate_once(parsed, model_id, table_id, RelationshipType::References);
+        }
+    }
+
+    for access in layer.accesses {
+        let Some(source) = enclosing_function(parsed, access.line) else {
+            continue;
+        };
+        if let DataTarget::Table(name) = &access.target {
+            add_node_once(parsed, &access.target.id(), NodeType::Table, name, access.line);
+        }
+        relate_once(parsed, source, access.target.id(), access.access.relationship_type());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tree_sitter::Parser as TSParser;
+
+    #[test]
+    fn test_sql_tables() {
+        assert_eq!(
+            sql_tables("SELECT u.id, o.total FROM users u, audit a JOIN orders o ON o.user_id = u.id WHERE u.id = $1"),
+            vec![
+                ("users".to_string(), Access::Read),
+                ("audit".to_string(), Access::Read),
+                ("orders".to_string(), Access::Read),
+            ]
+        );
+        assert_eq!(
+            sql_tables("INSERT INTO public.Events (id) SELECT id FROM staging"),
+            vec![("public.events".to_string(), Access::Write), ("staging".to_string(), Access::Read)]
+        );
+        assert_eq!(
+            sql_tables("WITH recent AS (SELECT * FROM logs) DELETE FROM sessions WHERE id IN (SELECT id FROM recent)"),
+            vec![("logs".to_string(), Access::Read), ("sessions".to_string(), Access::Write)]
+        );
+        assert_eq!(sql_tables("update profile set name = ? where id = ?"), vec![("profile".to_string(), Access::Write)]);
+        assert!(sql_tables("Select an option from the list").is_empty());
+        assert!(sql_tables("Please select a file").is_empty());
+    }
+
+    #[cfg(feature = "js")]
+    #[test]
+    fn test_find_javascript_prisma_and_sql() {
+        let source = "async function list() {\n  const users = await prisma.user.findMany();\n  await this.prisma.auditLog.create({ data: {} });\n  return db.query(`SELECT * FROM orders WHERE id = ${id}`);\n}\n";
+        let mut parser = TSParser::new();
+        parser.set_language(tree_sitter_javascript::language()).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let layer = find_javascript(tree.root_node(), source);
+
+        let targets: Vec<(String, Access)> = layer.accesses.iter().map(|a| (a.target.id(), a.access)).collect();
+        assert_eq!(
+            targets,
+            vec![
+                ("table:orders".to_string(), Access::Read),
+                ("model:User".to_string(), Access::Read),
+                ("model:AuditLog".to_string(), Access::Write),
+            ]
+        );
+        assert_eq!(layer.models.len(), 2);
+    }
+
+    #[cfg(feature = "python")]
+    #[test]
+    fn test_find_python_sqlalchemy_models() {
+        let source = "class User(Base):\n    __tablename__ = 'users'\n\ndef rename(session, name):\n    user = session.execute(select(User).where(User.name == name))\n    session.add(User(name=name))\n    session.delete(user)\n";
+        let mut parser = TSParser::new();
+        parser.set_language(tree_sitter_python::language()).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let layer = find_python(tree.root_node(), source, "file:models.py");
+
+        assert_eq!(
+            layer.models,
+            vec![ModelDefinition {
+                name: "User".to_string(),
+                table: Some("users".to_string()),
+                class_id: Some("class:models.py:User".to_string()),
+                line: 1,
+            }]
+        );
+        let accesses: Vec<(String, Access)> = layer.accesses.iter().map(|a| (a.target.id(), a.access)).collect();
+        assert!(accesses.contains(&("model:User".to_string(), Access::Read)));
+        assert!(accesses.contains(&("model:User".to_string(), Access::Write)));
+        assert_eq!(accesses.len(), 2);
+    }
+}
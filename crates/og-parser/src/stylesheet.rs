@@ -0,0 +1,326 @@
+use og_types::{AstNode, EngineResult, Language, NodeType, ParsedFile, Relationship, RelationshipType};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::parser_trait::Parser;
+use crate::shell::normalize;
+
+/// Extensions tried, in order, for an import written without one
+const STYLE_EXTENSIONS: &[&str] = &["scss", "sass", "css", "less"];
+
+/// Line-based parser for CSS, Sass and Less files
+///
+/// Produces a file node per stylesheet, a style node per mixin, function and
+/// placeholder selector, and an import edge per `@import`, `@use`, `@forward`
+/// or CSS-module `composes ... from` that points at another stylesheet.
+pub struct StylesheetParser;
+
+impl StylesheetParser {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for StylesheetParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Blank out `/* */` comments and whole-line `//` comments, keeping line numbers
+fn strip_comments(content: &str) -> String {
+    let mut stripped = String::with_capacity(content.len());
+    let mut rest = content;
+    while let Some(start) = rest.find("/*") {
+        stripped.push_str(&rest[..start]);
+        let end = rest[start..].find("*/").map_or(rest.len(), |end| start + end + 2);
+        stripped.extend(rest[start..end].chars().filter(|&c| c == '\n'));
+        rest = &rest[end..];
+    }
+    stripped.push_str(rest);
+
+    stripped
+        .lines()
+        .map(|line| if line.trim_start().starts_with("//") { "" } else { line })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Quoted strings and `url()` arguments in a statement, in order
+fn specifiers(statement: &str) -> Vec<&str> {
+    let mut found = Vec::new();
+    let mut rest = statement;
+    loop {
+        let quote = rest.find(['"', '\'']);
+        let url = rest.find("url(");
+        match (quote, url) {
+            (Some(q), u) if u.is_none_or(|u| q < u) => {
+                let delimiter = &rest[q..q + 1];
+                let Some(len) = rest[q + 1..].find(delimiter) else {
+                    break;
+                };
+                found.push(&rest[q + 1..q + 1 + len]);
+                rest = &rest[q + len + 2..];
+            }
+            (_, Some(u)) => {
+                let Some(len) = rest[u + 4..].find(')') else {
+                    break;
+                };
+                found.push(rest[u + 4..u + 4 + len].trim().trim_matches(['"', '\'']));
+                rest = &rest[u + 5 + len..];
+            }
+            _ => break,
+        }
+    }
+    found
+}
+
+/// Imported stylesheet specifiers with their 1-based lines
+fn imports(content: &str) -> Vec<(usize, &str)> {
+    let mut imports = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let trimmed = line.trim_start();
+        let statement = trimmed.split(';').next().unwrap_or(trimmed);
+        let found = if let Some(rest) = statement.strip_prefix("@import") {
+            specifiers(rest)
+        } else if let Some(rest) = statement.strip_prefix("@use").or_else(|| statement.strip_prefix("@forward")) {
+            specifiers(rest).into_iter().take(1).collect()
+        } else if let Some((_, source)) = statement.strip_prefix("composes:").and_then(|rest| rest.rsplit_once(" from ")) {
+            specifiers(source)
+        } else {
+            continue;
+        };
+        imports.extend(found.into_iter().map(|specifier| (index + 1, specifier)));
+    }
+    imports
+}
+
+/// Whether a specifier names a stylesheet outside the project or a built-in module
+fn is_external(specifier: &str) -> bool {
+    specifier.is_empty()
+        || specifier.starts_with('~')
+        || specifier.starts_with("//")
+        || ["http:", "https:", "data:", "sass:"].iter().any(|scheme| specifier.starts_with(scheme))
+}
+
+/// Whether a path already ends in a stylesheet extension
+fn has_style_extension(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| STYLE_EXTENSIONS.contains(&ext))
+}
+
+/// Files an import may refer to, following Sass partial and index rules
+fn candidates(base: &Path) -> Vec<PathBuf> {
+    let Some(name) = base.file_name().and_then(|name| name.to_str()) else {
+        return vec![];
+    };
+    let partial = |name: &str| base.with_file_name(format!("_{}", name));
+    if has_style_extension(base) {
+        return vec![base.to_path_buf(), partial(name)];
+    }
+
+    let mut candidates = Vec::new();
+    for ext in STYLE_EXTENSIONS {
+        candidates.push(base.with_file_name(format!("{}.{}", name, ext)));
+        candidates.push(partial(&format!("{}.{}", name, ext)));
+    }
+    for ext in STYLE_EXTENSIONS {
+        candidates.push(base.join(format!("_index.{}", ext)));
+        candidates.push(base.join(format!("index.{}", ext)));
+    }
+    candidates
+}
+
+/// Path an import resolves to: the first candidate on disk, otherwise the
+/// specifier as written, given the importing file's extension if it has none
+fn resolve(dir: &Path, specifier: &str, source_extension: &str) -> PathBuf {
+    let base = normalize(&dir.join(specifier));
+    if let Some(found) = candidates(&base).into_iter().find(|candidate| candidate.is_file()) {
+        return found;
+    }
+    if has_style_extension(&base) {
+        return base;
+    }
+    let name = base.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+    base.with_file_name(format!("{}.{}", name, source_extension))
+}
+
+/// Name declared by a mixin, function or placeholder selector line
+fn declaration(line: &str) -> Option<&str> {
+    let trimmed = line.trim_start();
+    let name = if let Some(rest) = trimmed.strip_prefix("@mixin ").or_else(|| trimmed.strip_prefix("@function ")) {
+        rest.trim_start()
+    } else if trimmed.starts_with('%') {
+        trimmed
+    } else {
+        return None;
+    };
+    let end = name
+        .char_indices()
+        .skip(1)
+        .find(|(_, c)| !(c.is_alphanumeric() || *c == '-' || *c == '_'))
+        .map_or(name.len(), |(i, _)| i);
+    let name = &name[..end];
+    (!name.is_empty() && name != "%").then_some(name)
+}
+
+/// Mixin or placeholder a line includes or extends
+fn usage(line: &str) -> Option<&str> {
+    let trimmed = line.trim_start();
+    let name = trimmed
+        .strip_prefix("@include ")
+        .or_else(|| trimmed.strip_prefix("@extend "))?
+        .trim_start();
+    let end = name.find(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_' || c == '%')).unwrap_or(name.len());
+    (end > 0).then_some(&name[..end])
+}
+
+impl Parser for StylesheetParser {
+    fn supported_extensions(&self) -> &[&str] {
+        Language::Css.extensions()
+    }
+
+    fn language(&self) -> Language {
+        Language::Css
+    }
+
+    fn parse(&self, path: &Path, content: &str) -> EngineResult<ParsedFile> {
+        let file_id = format!("file:{}", path.display());
+        let dir = path.parent().unwrap_or(Path::new(""));
+        let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or("css");
+        let content = strip_comments(content);
+
+        let mut nodes = vec![AstNode {
+            id: file_id.clone(),
+            name: path.file_name().and_then(|n| n.to_str()).unwrap_or_default().to_string(),
+            node_type: NodeType::File,
+            start_line: 0,
+            end_line: content.lines().count(),
+            children: vec![],
+        }];
+        let mut relationships = Vec::new();
+
+        for (_, specifier) in imports(&content) {
+            if is_external(specifier) {
+                continue;
+            }
+            relationships.push(Relationship {
+                source: file_id.clone(),
+                target: format!("file:{}", resolve(dir, specifier, extension).display()),
+                relationship_type: RelationshipType::Imports,
+            });
+        }
+
+        let mut declared: HashMap<&str, String> = HashMap::new();
+        for (index, line) in content.lines().enumerate() {
+            let Some(name) = declaration(line) else {
+                continue;
+            };
+            if declared.contains_key(name) {
+                continue;
+            }
+            let id = format!("style:{}:{}", path.display(), name);
+            nodes[0].children.push(id.clone());
+            nodes.push(AstNode {
+                id: id.clone(),
+                name: name.to_string(),
+                node_type: NodeType::Style,
+                start_line: index + 1,
+                end_line: index + 1,
+                children: vec![],
+            });
+            relationships.push(Relationship {
+                source: file_id.clone(),
+                target: id.clone(),
+                relationship_type: RelationshipType::Contains,
+            });
+            declared.insert(name, id);
+        }
+
+        for line in content.lines() {
+            if let Some(target) = usage(line).and_then(|name| declared.get(name)) {
+                relationships.push(Relationship {
+                    source: file_id.clone(),
+                    target: target.clone(),
+                    relationship_type: RelationshipType::References,
+                });
+            }
+        }
+
+        Ok(ParsedFile {
+            path: path.to_path_buf(),
+            language: Language::Css,
+            nodes,
+            relationships,
+            metrics: Default::default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn targets(parsed: &ParsedFile, kind: &str) -> Vec<String> {
+        parsed
+            .relationships
+            .iter()
+            .filter(|rel| rel.relationship_type.as_str() == kind)
+            .map(|rel| rel.target.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_imports_resolve_partials_and_skip_external() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir(dir.path().join("theme")).unwrap();
+        fs::write(dir.path().join("_variables.scss"), "$gap: 4px;").unwrap();
+        fs::write(dir.path().join("theme/_index.scss"), "").unwrap();
+        fs::write(dir.path().join("reset.css"), "").unwrap();
+
+        let path = dir.path().join("app.scss");
+        let content = "/* @import 'commented'; */\n\
+                       @use 'sass:math';\n\
+                       @use \"variables\" as v;\n\
+                       @import 'theme', url(\"reset.css\");\n\
+                       @import url(https://fonts.example.com/inter.css);\n\
+                       // @import 'also-commented';\n\
+                       @forward \"../shared/buttons\";\n";
+        let parsed = StylesheetParser::new().parse(&path, content).unwrap();
+
+        let file = |name: &str| format!("file:{}", dir.path().join(name).display());
+        assert_eq!(
+            targets(&parsed, "IMPORTS"),
+            vec![
+                file("_variables.scss"),
+                file("theme/_index.scss"),
+                file("reset.css"),
+                format!("file:{}", normalize(&dir.path().join("../shared/buttons.scss")).display()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_style_nodes_and_css_module_composes() {
+        let content = "@mixin card($radius) {\n  border-radius: $radius;\n}\n\
+                       %panel { padding: 1rem; }\n\
+                       .box {\n  @include card(4px);\n  @extend %panel;\n  composes: base from './base.module.css';\n}\n";
+        let parsed = StylesheetParser::new().parse(Path::new("ui/box.module.scss"), content).unwrap();
+
+        let styles: Vec<(&str, usize)> = parsed
+            .nodes
+            .iter()
+            .filter(|node| node.node_type == NodeType::Style)
+            .map(|node| (node.name.as_str(), node.start_line))
+            .collect();
+        assert_eq!(styles, vec![("card", 1), ("%panel", 4)]);
+        assert_eq!(
+            targets(&parsed, "REFERENCES"),
+            vec!["style:ui/box.module.scss:card", "style:ui/box.module.scss:%panel"]
+        );
+        assert_eq!(targets(&parsed, "IMPORTS"), vec!["file:ui/base.module.css"]);
+    }
+}
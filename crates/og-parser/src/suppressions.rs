@@ -0,0 +1,168 @@
+use og_types::{NodeType, ParsedFile, Suppressions, ALL_FINDINGS};
+
+/// Word that starts every suppression comment
+const MARKER: &str = "omnigraph";
+
+/// Comment openers a suppression may follow, across supported languages
+const COMMENT_OPENERS: &[&str] = &["//", "#", "/*", "*", "--", "<!--"];
+
+/// A suppression comment found in a source file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Directive {
+    /// `omnigraph-ignore-file`: leave the whole file out of the graph
+    IgnoreFile,
+    /// `omnigraph: ignore-smell=god-object,large-file`: silence findings of these kinds
+    IgnoreFindings { line: usize, kinds: Vec<String> },
+}
+
+fn parse_directive(text: &str, line: usize) -> Option<Directive> {
+    let text = text.trim_start_matches(['-', ':']).trim_start();
+    let text = text.split("*/").next().unwrap_or(text);
+    let text = text.split("-->").next().unwrap_or(text).trim_end();
+
+    if text.starts_with("ignore-file") {
+        return Some(Directive::IgnoreFile);
+    }
+    let list = ["ignore-smell", "ignore"]
+        .iter()
+        .find_map(|prefix| text.strip_prefix(prefix))?;
+    if !(list.is_empty() || list.starts_with(['=', ':', ' ', '\t'])) {
+        return None;
+    }
+
+    let mut kinds: Vec<String> = list
+        .trim_start_matches(['=', ':'])
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|kind| !kind.is_empty())
+        .map(str::to_string)
+        .collect();
+    if kinds.is_empty() {
+        kinds.push(ALL_FINDINGS.to_string());
+    }
+    Some(Directive::IgnoreFindings { line, kinds })
+}
+
+/// Suppression comments in a source file, with 1-based lines
+pub fn directives(source: &str) -> Vec<Directive> {
+    source
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let pos = line.find(MARKER)?;
+            let before = line[..pos].trim_end();
+            if !COMMENT_OPENERS
+                .iter()
+                .any(|opener| before.ends_with(opener))
+            {
+                return None;
+            }
+            parse_directive(&line[pos + MARKER.len()..], index + 1)
+        })
+        .collect()
+}
+
+/// Whether a file asks to be left out of the graph
+pub fn ignores_file(directives: &[Directive]) -> bool {
+    directives.contains(&Directive::IgnoreFile)
+}
+
+/// Findings silenced by a file's comments, keyed by the ids of its nodes
+///
+/// A comment applies to the declarations starting on its line or the next
+/// one, and to every node in the file when no declaration is next to it.
+pub fn attach(parsed: &ParsedFile, directives: &[Directive]) -> Suppressions {
+    let mut suppressions = Suppressions::default();
+    for directive in directives {
+        let Directive::IgnoreFindings { line, kinds } = directive else {
+            continue;
+        };
+        let declared: Vec<&str> = parsed
+            .nodes
+            .iter()
+            .filter(|node| {
+                node.node_type != NodeType::File
+                    && (node.start_line == *line || node.start_line == line + 1)
+            })
+            .map(|node| node.id.as_str())
+            .collect();
+        let targets = if declared.is_empty() {
+            parsed.nodes.iter().map(|node| node.id.as_str()).collect()
+        } else {
+            declared
+        };
+        for id in targets {
+            for kind in kinds {
+                suppressions.insert(id, kind);
+            }
+        }
+    }
+    suppressions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use og_types::{AstNode, Language};
+    use std::path::PathBuf;
+
+    fn node(id: &str, node_type: NodeType, start_line: usize) -> AstNode {
+        AstNode {
+            id: id.to_string(),
+            node_type,
+            name: id.to_string(),
+            start_line,
+            end_line: start_line + 5,
+            children: vec![],
+        }
+    }
+
+    #[test]
+    fn test_directives_in_comments() {
+        let source = "// omnigraph-ignore-file\n\
+                      x = 'omnigraph: ignore'\n\
+                      # omnigraph: ignore-smell=god-object, large-file\n\
+                      /* omnigraph: ignore */\n";
+        assert_eq!(
+            directives(source),
+            vec![
+                Directive::IgnoreFile,
+                Directive::IgnoreFindings {
+                    line: 3,
+                    kinds: vec!["god-object".to_string(), "large-file".to_string()],
+                },
+                Directive::IgnoreFindings {
+                    line: 4,
+                    kinds: vec![ALL_FINDINGS.to_string()],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_attach_to_next_declaration_or_file() {
+        let parsed = ParsedFile {
+            path: PathBuf::from("app.py"),
+            language: Language::Python,
+            nodes: vec![
+                node("file:app.py", NodeType::File, 0),
+                node("class:Big", NodeType::Class, 5),
+                node("class:Small", NodeType::Class, 20),
+            ],
+            relationships: vec![],
+            metrics: Default::default(),
+        };
+
+        let source = "\n\n\n# omnigraph: ignore-smell=god-object\nclass Big:\n";
+        let suppressions = attach(&parsed, &directives(source));
+        assert!(suppressions.is_suppressed("class:Big", "God Object"));
+        assert!(!suppressions.is_suppressed("class:Small", "God Object"));
+        assert!(!suppressions.is_suppressed("class:Big", "Large File"));
+
+        let file_wide = attach(
+            &parsed,
+            &directives("# omnigraph: ignore-smell=large-file\n"),
+        );
+        assert!(file_wide.is_suppressed("class:Small", "Large File"));
+        assert!(file_wide.is_suppressed("file:app.py", "Large File"));
+    }
+}
@@ -3,16 +3,25 @@ use og_types::{
 };
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use std::sync::Mutex;
-use tree_sitter::{Node, Parser as TSParser, TreeCursor};
+use std::sync::Arc;
+use tree_sitter::{Node, Tree, TreeCursor};
 
+use crate::concurrency;
+use crate::docs;
+use crate::dynamic_imports;
+use crate::env_vars;
+use crate::events;
+use crate::function_metrics;
+use crate::graphql;
+use crate::sql;
 use crate::import_resolver::ImportResolver;
+use crate::pool;
+use crate::frameworks;
 use crate::parser_trait::Parser;
 use og_types::EngineResult;
 
 pub struct TypeScriptParser {
-    parser: Mutex<TSParser>,
-    base_path: PathBuf,
+    resolver: Arc<ImportResolver>,
 }
 
 impl TypeScriptParser {
@@ -21,14 +30,12 @@ impl TypeScriptParser {
     }
     
     pub fn with_base_path(base_path: PathBuf) -> Self {
-        let mut parser = TSParser::new();
-        parser
-            .set_language(tree_sitter_typescript::language_typescript())
-            .expect("Error loading TypeScript grammar");
-        Self {
-            parser: Mutex::new(parser),
-            base_path,
-        }
+        Self::with_resolver(Arc::new(ImportResolver::new(base_path)))
+    }
+
+    /// Resolve imports through a resolver shared with other parsers
+    pub fn with_resolver(resolver: Arc<ImportResolver>) -> Self {
+        Self { resolver }
     }
 
     fn extract_nodes(
@@ -41,7 +48,6 @@ impl TypeScriptParser {
         let mut relationships = Vec::new();
         let mut import_map = HashMap::new();
         let mut export_map = HashMap::new();
-        let mut complexity = 1;
 
         self.walk_tree(
             cursor,
@@ -51,7 +57,6 @@ impl TypeScriptParser {
             &mut relationships,
             &mut import_map,
             &mut export_map,
-            &mut complexity,
             None,
         )?;
 
@@ -73,7 +78,6 @@ impl TypeScriptParser {
         relationships: &mut Vec<Relationship>,
         import_map: &mut HashMap<String, String>,
         export_map: &mut HashMap<String, String>,
-        complexity: &mut usize,
         parent_id: Option<String>,
     ) -> EngineResult<()> {
         let node = cursor.node();
@@ -107,7 +111,6 @@ impl TypeScriptParser {
                             relationships,
                             import_map,
                             export_map,
-                            complexity,
                             Some(file_id.clone()),
                         )?;
                         if !cursor.goto_next_sibling() {
@@ -129,7 +132,6 @@ impl TypeScriptParser {
                     relationships,
                     import_map,
                     export_map,
-                    complexity,
                     &parent_id,
                 )?;
             }
@@ -142,7 +144,6 @@ impl TypeScriptParser {
                     relationships,
                     import_map,
                     export_map,
-                    complexity,
                     &parent_id,
                 )?;
             }
@@ -155,15 +156,14 @@ impl TypeScriptParser {
                     relationships,
                     import_map,
                     export_map,
-                    complexity,
                     &parent_id,
                 )?;
             }
             "type_alias_declaration" => {
-                self.process_type_alias(node, source, nodes, &parent_id, relationships)?;
+                self.process_type_alias(node, source, file_path, nodes, &parent_id, relationships)?;
             }
             "enum_declaration" => {
-                self.process_enum(node, source, nodes, &parent_id, relationships)?;
+                self.process_enum(node, source, file_path, nodes, &parent_id, relationships)?;
             }
             "function_declaration" | "function_expression" | "arrow_function" => {
                 self.process_function(
@@ -174,7 +174,6 @@ impl TypeScriptParser {
                     relationships,
                     import_map,
                     export_map,
-                    complexity,
                     &parent_id,
                 )?;
             }
@@ -187,12 +186,10 @@ impl TypeScriptParser {
                     relationships,
                     import_map,
                     export_map,
-                    complexity,
                     &parent_id,
                 )?;
             }
             "if_statement" | "while_statement" | "for_statement" | "do_statement" => {
-                *complexity += 1;
                 if cursor.goto_first_child() {
                     loop {
                         self.walk_tree(
@@ -203,7 +200,6 @@ impl TypeScriptParser {
                             relationships,
                             import_map,
                             export_map,
-                            complexity,
                             parent_id.clone(),
                         )?;
                         if !cursor.goto_next_sibling() {
@@ -225,7 +221,6 @@ impl TypeScriptParser {
                             relationships,
                             import_map,
                             export_map,
-                            complexity,
                             parent_id.clone(),
                         )?;
                         if !cursor.goto_next_sibling() {
@@ -259,7 +254,7 @@ impl TypeScriptParser {
                         let import_path = import_path.trim_matches(|c| c == '"' || c == '\'');
                         
                         // Resolve the import path
-                        let resolver = ImportResolver::new(self.base_path.clone());
+                        let resolver = &self.resolver;
                         if let Some(resolved_path) = resolver.resolve_import(import_path, file_path) {
                             relationships.push(Relationship {
                                 source: parent.clone(),
@@ -267,11 +262,25 @@ impl TypeScriptParser {
                                 relationship_type: RelationshipType::Imports,
                             });
                             
+                            // Map imported names to their defining file so inheritance can resolve
+                            let defining_file = resolved_path.trim_start_matches("file:");
+                            for (local, imported) in self.import_bindings(node, source)? {
+                                import_map.insert(local, format!("{}:{}", defining_file, imported));
+                            }
+
                             // Store import mapping for later reference resolution
                             import_map.insert(import_path.to_string(), resolved_path);
                         } else {
                             // If we can't resolve it, store the original path (for external imports)
-                            import_map.insert(import_path.to_string(), format!("external:{}", import_path));
+                            let external = format!("external:{}", import_path);
+                            if resolver.is_external_import(import_path) {
+                                relationships.push(Relationship {
+                                    source: parent.clone(),
+                                    target: external.clone(),
+                                    relationship_type: RelationshipType::Imports,
+                                });
+                            }
+                            import_map.insert(import_path.to_string(), external);
                         }
                         break;
                     }
@@ -284,6 +293,59 @@ impl TypeScriptParser {
         Ok(())
     }
 
+    /// Local and exported names bound by an import statement
+    fn import_bindings(&self, node: Node, source: &str) -> EngineResult<Vec<(String, String)>> {
+        let mut bindings = Vec::new();
+        let Some(clause) = self.find_child_by_type(&node, "import_clause") else {
+            return Ok(bindings);
+        };
+
+        let mut cursor = clause.walk();
+        for child in clause.children(&mut cursor) {
+            match child.kind() {
+                // Default import, assumed to share the exported name
+                "identifier" => {
+                    let name = child.utf8_text(source.as_bytes()).map_err(ParseError::from)?;
+                    bindings.push((name.to_string(), name.to_string()));
+                }
+                "named_imports" => {
+                    let mut spec_cursor = child.walk();
+                    for spec in child.children(&mut spec_cursor) {
+                        if spec.kind() != "import_specifier" {
+                            continue;
+                        }
+                        let Some(name) = spec.child_by_field_name("name") else {
+                            continue;
+                        };
+                        let imported = name.utf8_text(source.as_bytes()).map_err(ParseError::from)?;
+                        let local = match spec.child_by_field_name("alias") {
+                            Some(alias) => alias.utf8_text(source.as_bytes()).map_err(ParseError::from)?,
+                            None => imported,
+                        };
+                        bindings.push((local.to_string(), imported.to_string()));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(bindings)
+    }
+
+    /// Id of a class or interface, following imports to the file that defines it
+    fn type_reference_id(
+        &self,
+        kind: &str,
+        name: &str,
+        import_map: &HashMap<String, String>,
+        file_path: &Path,
+    ) -> String {
+        match import_map.get(name) {
+            Some(imported) => format!("{}:{}", kind, imported),
+            None => format!("{}:{}:{}", kind, file_path.display(), name),
+        }
+    }
+
     fn process_export(
         &self,
         cursor: &mut TreeCursor,
@@ -293,7 +355,6 @@ impl TypeScriptParser {
         relationships: &mut Vec<Relationship>,
         import_map: &mut HashMap<String, String>,
         export_map: &mut HashMap<String, String>,
-        complexity: &mut usize,
         parent_id: &Option<String>,
     ) -> EngineResult<()> {
         if cursor.goto_first_child() {
@@ -309,7 +370,6 @@ impl TypeScriptParser {
                             relationships,
                             import_map,
                             export_map,
-                            complexity,
                             parent_id,
                         )?;
                     }
@@ -322,7 +382,6 @@ impl TypeScriptParser {
                             relationships,
                             import_map,
                             export_map,
-                            complexity,
                             parent_id,
                         )?;
                     }
@@ -335,12 +394,11 @@ impl TypeScriptParser {
                             relationships,
                             import_map,
                             export_map,
-                            complexity,
                             parent_id,
                         )?;
                     }
                     "type_alias_declaration" => {
-                        self.process_type_alias(node, source, nodes, parent_id, relationships)?;
+                        self.process_type_alias(node, source, file_path, nodes, parent_id, relationships)?;
                     }
                     _ => {}
                 }
@@ -362,7 +420,6 @@ impl TypeScriptParser {
         relationships: &mut Vec<Relationship>,
         import_map: &mut HashMap<String, String>,
         export_map: &mut HashMap<String, String>,
-        complexity: &mut usize,
         parent_id: &Option<String>,
     ) -> EngineResult<()> {
         let node = cursor.node();
@@ -380,22 +437,32 @@ impl TypeScriptParser {
                             class_name = child.utf8_text(source.as_bytes()).map_err(ParseError::from)?.to_string();
                         }
                     }
-                    "extends_clause" => {
-                        if let Some(extends_node) = self.find_child_by_type(&child, "identifier") {
-                            extends_class = Some(extends_node.utf8_text(source.as_bytes()).map_err(ParseError::from)?.to_string());
-                        }
-                    }
-                    "implements_clause" => {
-                        let mut impl_cursor = child.walk();
-                        if impl_cursor.goto_first_child() {
-                            loop {
-                                if impl_cursor.node().kind() == "type_identifier" {
-                                    let interface_name = impl_cursor.node().utf8_text(source.as_bytes()).map_err(ParseError::from)?;
-                                    implements_interfaces.push(interface_name.to_string());
+                    // Clauses sit under class_heritage in the TypeScript grammar
+                    "class_heritage" | "extends_clause" | "implements_clause" => {
+                        let clauses = if child.kind() == "class_heritage" {
+                            let mut heritage_cursor = child.walk();
+                            child.children(&mut heritage_cursor).collect()
+                        } else {
+                            vec![child]
+                        };
+
+                        for clause in clauses {
+                            match clause.kind() {
+                                "extends_clause" => {
+                                    if let Some(extends_node) = self.find_child_by_type(&clause, "identifier") {
+                                        extends_class = Some(extends_node.utf8_text(source.as_bytes()).map_err(ParseError::from)?.to_string());
+                                    }
                                 }
-                                if !impl_cursor.goto_next_sibling() {
-                                    break;
+                                "implements_clause" => {
+                                    let mut impl_cursor = clause.walk();
+                                    for implemented in clause.children(&mut impl_cursor) {
+                                        if implemented.kind() == "type_identifier" {
+                                            let interface_name = implemented.utf8_text(source.as_bytes()).map_err(ParseError::from)?;
+                                            implements_interfaces.push(interface_name.to_string());
+                                        }
+                                    }
                                 }
+                                _ => {}
                             }
                         }
                     }
@@ -433,7 +500,7 @@ impl TypeScriptParser {
         if let Some(base_class) = extends_class {
             relationships.push(Relationship {
                 source: class_id.clone(),
-                target: format!("class:{}:{}", file_path.display(), base_class),
+                target: self.type_reference_id("class", &base_class, import_map, file_path),
                 relationship_type: RelationshipType::Extends,
             });
         }
@@ -441,7 +508,7 @@ impl TypeScriptParser {
         for interface in implements_interfaces {
             relationships.push(Relationship {
                 source: class_id.clone(),
-                target: format!("interface:{}:{}", file_path.display(), interface),
+                target: self.type_reference_id("interface", &interface, import_map, file_path),
                 relationship_type: RelationshipType::Implements,
             });
         }
@@ -461,7 +528,6 @@ impl TypeScriptParser {
                                 relationships,
                                 import_map,
                                 export_map,
-                                complexity,
                                 Some(class_id.clone()),
                             )?;
                             if !body_cursor.goto_next_sibling() {
@@ -490,7 +556,6 @@ impl TypeScriptParser {
         relationships: &mut Vec<Relationship>,
         import_map: &mut HashMap<String, String>,
         export_map: &mut HashMap<String, String>,
-        complexity: &mut usize,
         parent_id: &Option<String>,
     ) -> EngineResult<()> {
         let node = cursor.node();
@@ -555,7 +620,7 @@ impl TypeScriptParser {
         for parent_interface in extends_interfaces {
             relationships.push(Relationship {
                 source: interface_id.clone(),
-                target: format!("interface:{}:{}", file_path.display(), parent_interface),
+                target: self.type_reference_id("interface", &parent_interface, import_map, file_path),
                 relationship_type: RelationshipType::Extends,
             });
         }
@@ -567,6 +632,7 @@ impl TypeScriptParser {
         &self,
         node: Node,
         source: &str,
+        file_path: &Path,
         nodes: &mut Vec<AstNode>,
         parent_id: &Option<String>,
         relationships: &mut Vec<Relationship>,
@@ -587,7 +653,7 @@ impl TypeScriptParser {
             }
         }
 
-        let type_id = format!("type:{}", type_name);
+        let type_id = format!("type:{}:{}", file_path.display(), type_name);
         let line_start = node.start_position().row + 1;
         let line_end = node.end_position().row + 1;
 
@@ -615,6 +681,7 @@ impl TypeScriptParser {
         &self,
         node: Node,
         source: &str,
+        file_path: &Path,
         nodes: &mut Vec<AstNode>,
         parent_id: &Option<String>,
         relationships: &mut Vec<Relationship>,
@@ -635,7 +702,7 @@ impl TypeScriptParser {
             }
         }
 
-        let enum_id = format!("enum:{}", enum_name);
+        let enum_id = format!("enum:{}:{}", file_path.display(), enum_name);
         let line_start = node.start_position().row + 1;
         let line_end = node.end_position().row + 1;
 
@@ -668,7 +735,6 @@ impl TypeScriptParser {
         relationships: &mut Vec<Relationship>,
         import_map: &mut HashMap<String, String>,
         export_map: &mut HashMap<String, String>,
-        complexity: &mut usize,
         parent_id: &Option<String>,
     ) -> EngineResult<()> {
         let node = cursor.node();
@@ -716,7 +782,7 @@ impl TypeScriptParser {
             });
         }
 
-        // Process function body for complexity
+        // Process function body for nested definitions
         if cursor.goto_first_child() {
             loop {
                 if cursor.node().kind() == "statement_block" {
@@ -731,7 +797,6 @@ impl TypeScriptParser {
                                 relationships,
                                 import_map,
                                 export_map,
-                                complexity,
                                 Some(func_id.clone()),
                             )?;
                             if !body_cursor.goto_next_sibling() {
@@ -760,7 +825,6 @@ impl TypeScriptParser {
         relationships: &mut Vec<Relationship>,
         import_map: &mut HashMap<String, String>,
         export_map: &mut HashMap<String, String>,
-        complexity: &mut usize,
         parent_id: &Option<String>,
     ) -> EngineResult<()> {
         // Process variable declarators
@@ -790,7 +854,6 @@ impl TypeScriptParser {
                                     relationships,
                                     import_map,
                                     export_map,
-                                    complexity,
                                     parent_id,
                                 )?;
                             }
@@ -973,14 +1036,35 @@ impl Parser for TypeScriptParser {
     }
 
     fn parse(&self, path: &Path, content: &str) -> EngineResult<ParsedFile> {
-        let tree = self
-            .parser
-            .lock()
-            .unwrap()
-            .parse(content, None)
+        self.parse_incremental(path, content, None).map(|(parsed, _)| parsed)
+    }
+
+    fn parse_incremental(
+        &self,
+        path: &Path,
+        content: &str,
+        old_tree: Option<&Tree>,
+    ) -> EngineResult<(ParsedFile, Option<Tree>)> {
+        let tree = pool::parse("TypeScript", tree_sitter_typescript::language_typescript, content, old_tree)
             .ok_or_else(|| ParseError::ParseFailed("Failed to parse TypeScript file".to_string()))?;
 
         let mut cursor = tree.root_node().walk();
-        self.extract_nodes(&mut cursor, content, path)
+        let mut parsed = self.extract_nodes(&mut cursor, content, path)?;
+        frameworks::extract_typescript(tree.root_node(), content, path).append_to(&mut parsed);
+        let resolver = &self.resolver;
+        dynamic_imports::apply(
+            &mut parsed,
+            dynamic_imports::find_javascript(tree.root_node(), content),
+            |specifier| resolver.resolve_import(specifier, path),
+        );
+        env_vars::apply(&mut parsed, env_vars::find_javascript(tree.root_node(), content));
+        sql::apply(&mut parsed, sql::find_javascript(tree.root_node(), content));
+        events::apply(&mut parsed, events::find_javascript(tree.root_node(), content));
+        graphql::apply(&mut parsed, graphql::find_javascript(tree.root_node(), content));
+        function_metrics::apply(&mut parsed, function_metrics::find_javascript(tree.root_node()));
+        docs::apply(&mut parsed, docs::find_javascript(tree.root_node(), content));
+        concurrency::apply(&mut parsed, concurrency::find_javascript(tree.root_node(), content));
+        drop(cursor);
+        Ok((parsed, Some(tree)))
     }
 }
\ No newline at end of file
@@ -0,0 +1,117 @@
+use og_parser::python::PythonParser;
+use og_parser::typescript::TypeScriptParser;
+use og_parser::Parser;
+use og_types::{NodeType, ParsedFile, RelationshipType};
+use std::path::Path;
+
+fn names(parsed: &ParsedFile, node_type: NodeType) -> Vec<String> {
+    let mut names: Vec<String> = parsed.nodes.iter()
+        .filter(|n| n.node_type == node_type)
+        .map(|n| n.name.clone())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn test_nestjs_controller_routes() {
+    let source = r#"
+@Injectable()
+export class CatsService {}
+
+@Controller('cats')
+export class CatsController {
+    constructor(private readonly cats: CatsService) {}
+
+    @Get()
+    findAll() { return []; }
+
+    @Post(':id/adopt')
+    adopt() {}
+}
+"#;
+    let parser = TypeScriptParser::new();
+    let parsed = parser.parse(Path::new("cats.controller.ts"), source).unwrap();
+
+    assert_eq!(names(&parsed, NodeType::Controller), vec!["CatsController"]);
+    assert_eq!(names(&parsed, NodeType::Provider), vec!["CatsService"]);
+    assert_eq!(names(&parsed, NodeType::Route), vec!["GET /cats", "POST /cats/:id/adopt"]);
+
+    let handlers: Vec<&str> = parsed.relationships.iter()
+        .filter(|r| matches!(r.relationship_type, RelationshipType::RoutesTo))
+        .map(|r| r.target.as_str())
+        .collect();
+    assert!(handlers.iter().all(|h| parsed.nodes.iter().any(|n| n.id == *h)));
+}
+
+#[test]
+fn test_flask_and_fastapi_routes() {
+    let source = r#"
+@app.route("/users", methods=["GET", "POST"])
+def users():
+    pass
+
+@router.delete("/users/{user_id}")
+async def delete_user(user_id: int):
+    pass
+
+@functools.cache
+def helper():
+    pass
+"#;
+    let parser = PythonParser::new();
+    let parsed = parser.parse(Path::new("api.py"), source).unwrap();
+
+    assert_eq!(
+        names(&parsed, NodeType::Route),
+        vec!["DELETE /users/{user_id}", "GET /users", "POST /users"]
+    );
+    assert!(parsed.relationships.iter().any(|r| {
+        matches!(r.relationship_type, RelationshipType::RoutesTo) && r.target == "function:api.py:users"
+    }));
+}
+
+#[test]
+fn test_python_route_calls_and_router_prefixes() {
+    let source = r#"
+from fastapi import APIRouter, FastAPI
+
+app = FastAPI()
+router = APIRouter(prefix="/items")
+
+@router.get("/{item_id}")
+def read_item(item_id: int):
+    pass
+
+app.include_router(router, prefix="/api")
+
+def legacy():
+    pass
+
+app.add_url_rule("/legacy", "legacy", legacy, methods=["POST"])
+
+urlpatterns = [
+    path("articles/", views.article_list),
+    path("about/", AboutView.as_view()),
+    path("blog/", include("blog.urls")),
+]
+"#;
+    let parser = PythonParser::new();
+    let mut files = vec![
+        parser.parse(Path::new("urls.py"), source).unwrap(),
+        parser.parse(Path::new("views.py"), "def article_list(request):\n    pass\n\nclass AboutView:\n    pass\n").unwrap(),
+    ];
+    og_parser::frameworks::resolve_handlers(&mut files);
+
+    assert_eq!(
+        names(&files[0], NodeType::Route),
+        vec!["ANY /about", "ANY /articles", "GET /api/items/{item_id}", "POST /legacy"]
+    );
+    let target = |route: &str| files[0].relationships.iter()
+        .find(|r| matches!(r.relationship_type, RelationshipType::RoutesTo) && r.source.ends_with(route))
+        .map(|r| r.target.clone())
+        .unwrap();
+    assert_eq!(target("POST /legacy"), "function:urls.py:legacy");
+    assert_eq!(target("ANY /articles"), "function:views.py:article_list");
+    assert_eq!(target("ANY /about"), "class:views.py:AboutView");
+}
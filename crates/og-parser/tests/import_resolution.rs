@@ -155,4 +155,107 @@ module.exports = {{
         assert!(imports.iter().any(|r| r.target.contains("config/index.js")),
                 "Should find config/index.js import");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_typescript_cross_file_inheritance() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path().to_path_buf();
+
+        fs::write(base_path.join("shapes.ts"), "export interface Shape {}\nexport class Base {}\n").unwrap();
+        let main_path = base_path.join("main.ts");
+        fs::write(&main_path, r#"
+import { Shape as IShape, Base } from './shapes';
+
+interface Local {}
+
+export class Circle extends Base implements IShape, Local {}
+"#).unwrap();
+
+        let parser = TypeScriptParser::with_base_path(base_path.clone());
+        let content = fs::read_to_string(&main_path).unwrap();
+        let result = parser.parse(&main_path, &content).unwrap();
+
+        let targets = |kind: fn(&og_types::RelationshipType) -> bool| {
+            result.relationships.iter()
+                .filter(|r| kind(&r.relationship_type))
+                .map(|r| r.target.clone())
+                .collect::<Vec<_>>()
+        };
+        let implements = targets(|t| matches!(t, og_types::RelationshipType::Implements));
+        let extends = targets(|t| matches!(t, og_types::RelationshipType::Extends));
+
+        assert!(implements.iter().any(|t| t.ends_with("shapes.ts:Shape")), "{:?}", implements);
+        assert!(implements.iter().any(|t| t.ends_with("main.ts:Local")), "{:?}", implements);
+        assert!(extends.iter().any(|t| t.starts_with("class:") && t.ends_with("shapes.ts:Base")), "{:?}", extends);
+    }
+
+    #[test]
+    fn test_typescript_type_and_enum_ids_are_per_file() {
+        let parser = TypeScriptParser::new();
+        let source = "type Id = string;\nenum Color { Red }\n";
+        let a = parser.parse(&PathBuf::from("/repo/a.ts"), source).unwrap();
+        let b = parser.parse(&PathBuf::from("/repo/b.ts"), source).unwrap();
+
+        let ids = |parsed: &og_types::ParsedFile| -> Vec<String> {
+            parsed.nodes.iter()
+                .filter(|n| matches!(n.node_type, og_types::NodeType::TypeAlias | og_types::NodeType::Enum))
+                .map(|n| n.id.clone())
+                .collect()
+        };
+        assert_eq!(ids(&a), vec!["type:/repo/a.ts:Id", "enum:/repo/a.ts:Color"]);
+        assert_eq!(ids(&b), vec!["type:/repo/b.ts:Id", "enum:/repo/b.ts:Color"]);
+    }
+
+    #[test]
+    fn test_python_call_resolution() {
+        use og_parser::python::{resolve_imported_calls, PythonParser};
+
+        let parser = PythonParser::new();
+        let util = r#"
+def helper():
+    pass
+
+class Store:
+    def load(self):
+        pass
+"#;
+        let main = r#"
+from pkg.util import helper, Store as S
+from . import sibling
+
+class Base:
+    def setup(self):
+        pass
+
+class App(Base):
+    def run(self):
+        self.setup()
+        self.step()
+        helper()
+        S.load()
+        sibling.ping()
+        print("done")
+
+    def step(self):
+        pass
+"#;
+        let mut files = vec![
+            parser.parse(&PathBuf::from("/repo/pkg/util.py"), util).unwrap(),
+            parser.parse(&PathBuf::from("/repo/app/main.py"), main).unwrap(),
+            parser.parse(&PathBuf::from("/repo/app/sibling.py"), "def ping():\n    pass\n").unwrap(),
+        ];
+        resolve_imported_calls(&mut files);
+
+        let calls: Vec<&str> = files[1].relationships.iter()
+            .filter(|r| matches!(r.relationship_type, og_types::RelationshipType::Calls))
+            .map(|r| r.target.as_str())
+            .collect();
+        assert_eq!(calls, vec![
+            "function:/repo/app/main.py:setup",
+            "function:/repo/app/main.py:step",
+            "function:/repo/pkg/util.py:helper",
+            "function:/repo/pkg/util.py:load",
+            "function:/repo/app/sibling.py:ping",
+        ]);
+    }
+}
@@ -0,0 +1,173 @@
+//! Python bindings, built into the `pyomnigraph` module with maturin
+//!
+//! ```python
+//! import networkx as nx
+//! import pandas as pd
+//! import pyomnigraph
+//!
+//! result = pyomnigraph.analyze("path/to/repo")
+//! graph = nx.node_link_graph(result["graph"], edges="links")
+//! metrics = pd.DataFrame(result["metrics"])
+//! ```
+//!
+//! The conversions below build plain JSON-like values, so they are tested
+//! without a Python interpreter.
+
+use anyhow::{bail, Result};
+use ignore::WalkBuilder;
+use og_analytics::analysis::distribution::{metric_extractor, metric_names};
+use og_analytics::{analyze_graph, to_ui_metrics, AnalyticsConfig};
+use og_graph::builder::GraphBuilder;
+use og_graph::graph::GraphData;
+use og_parser::ParserEngine;
+use og_types::metrics::UINodeMetricsV1;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+
+mod python;
+
+/// Ignore file read alongside `.gitignore`, as in the desktop app
+const IGNORE_FILE: &str = ".omnigraphignore";
+
+/// Graph and per-node metrics of an analyzed directory
+pub struct Analysis {
+    pub graph: GraphData,
+    pub metrics: Vec<UINodeMetricsV1>,
+}
+
+/// Source files below `root` that a parser handles, as (path, content)
+///
+/// Ignored, unreadable and non-UTF-8 files are skipped.
+fn discover(root: &Path, engine: &ParserEngine) -> Vec<(String, String)> {
+    WalkBuilder::new(root)
+        .hidden(false)
+        .git_ignore(true)
+        .add_custom_ignore_filename(IGNORE_FILE)
+        .sort_by_file_name(|a, b| a.cmp(b))
+        .build()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_some_and(|kind| kind.is_file()) && engine.can_parse(entry.path()))
+        .filter_map(|entry| {
+            let content = std::fs::read_to_string(entry.path()).ok()?;
+            Some((entry.path().display().to_string(), content))
+        })
+        .collect()
+}
+
+/// Parse a directory, build its graph and compute metrics
+///
+/// `metrics` limits the run to the named metrics, all of them when unset.
+pub fn analyze_path(root: &Path, metrics: Option<Vec<String>>) -> Result<Analysis> {
+    if !root.is_dir() {
+        bail!("{} is not a directory", root.display());
+    }
+    let engine = ParserEngine::with_base_path(PathBuf::from(root));
+    let parsed = engine
+        .parse_batch(discover(root, &engine), None)
+        .into_iter()
+        .filter_map(Result::ok)
+        .collect();
+    let code_graph = GraphBuilder::new().build_from_files(parsed);
+
+    let metrics = if code_graph.node_map.is_empty() {
+        Vec::new()
+    } else {
+        let config = AnalyticsConfig {
+            enabled_metrics: metrics,
+            ..AnalyticsConfig::default()
+        };
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_time().build()?;
+        let report = runtime.block_on(analyze_graph(&code_graph, Some(config)))?;
+        to_ui_metrics(&report, &code_graph)
+    };
+    Ok(Analysis {
+        graph: code_graph.to_frontend_format(),
+        metrics,
+    })
+}
+
+/// The graph in networkx's node-link format, as a directed multigraph
+pub fn node_link_data(graph: &GraphData) -> Value {
+    let nodes: Vec<Value> = graph
+        .nodes
+        .iter()
+        .map(|node| {
+            json!({
+                "id": node.id,
+                "name": node.name,
+                "type": node.node_type,
+                "size": node.size,
+                "file_path": node.file_path,
+            })
+        })
+        .collect();
+    let links: Vec<Value> = graph
+        .links
+        .iter()
+        .map(|link| {
+            json!({
+                "source": link.source,
+                "target": link.target,
+                "type": link.link_type,
+                "weight": link.value,
+            })
+        })
+        .collect();
+    json!({
+        "directed": true,
+        "multigraph": true,
+        "graph": {},
+        "nodes": nodes,
+        "links": links,
+    })
+}
+
+/// One column per node field and metric, in the order of the metrics table
+///
+/// A metric column holds null where a node lacks the metric.
+pub fn metric_columns(metrics: &[UINodeMetricsV1]) -> Vec<(String, Vec<Value>)> {
+    let column = |get: fn(&UINodeMetricsV1) -> Value| metrics.iter().map(get).collect::<Vec<Value>>();
+    let mut columns = vec![
+        ("path".to_string(), column(|m| json!(m.path))),
+        ("name".to_string(), column(|m| json!(m.name))),
+        ("node_type".to_string(), column(|m| json!(m.node_type))),
+        ("community".to_string(), column(|m| json!(m.community))),
+        ("generated".to_string(), column(|m| json!(m.generated))),
+    ];
+    for name in metric_names() {
+        if let Some(extract) = metric_extractor(name) {
+            columns.push((name.to_string(), metrics.iter().map(|m| json!(extract(m))).collect()));
+        }
+    }
+    columns
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_analyze_directory_to_networkx_and_columns() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("util.py"), "def helper():\n    return 1\n").unwrap();
+        std::fs::write(
+            dir.path().join("app.py"),
+            "from util import helper\n\ndef main():\n    helper()\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "not source\n").unwrap();
+
+        let analysis = analyze_path(dir.path(), None).unwrap();
+        let data = node_link_data(&analysis.graph);
+        assert_eq!(data["nodes"].as_array().unwrap().len(), analysis.graph.nodes.len());
+        assert_eq!(data["links"].as_array().unwrap().len(), analysis.graph.links.len());
+        assert!(data["nodes"].as_array().unwrap().iter().any(|node| node["name"] == "helper"));
+
+        let columns = metric_columns(&analysis.metrics);
+        assert_eq!(columns[0].0, "path");
+        assert!(columns.iter().all(|(_, values)| values.len() == analysis.metrics.len()));
+        assert!(columns.len() > 5);
+
+        assert!(analyze_path(&dir.path().join("missing"), None).is_err());
+    }
+}
@@ -0,0 +1,67 @@
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyDict, PyList, PyString};
+use serde_json::Value;
+use std::path::PathBuf;
+
+/// A JSON value as the matching Python object
+fn to_python<'py>(py: Python<'py>, value: &Value) -> PyResult<Bound<'py, PyAny>> {
+    Ok(match value {
+        Value::Null => py.None().into_bound(py),
+        Value::Bool(b) => PyBool::new(py, *b).to_owned().into_any(),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => i.into_pyobject(py)?.into_any(),
+            None => n.as_f64().unwrap_or(f64::NAN).into_pyobject(py)?.into_any(),
+        },
+        Value::String(s) => PyString::new(py, s).into_any(),
+        Value::Array(items) => {
+            let items = items.iter().map(|item| to_python(py, item)).collect::<PyResult<Vec<_>>>()?;
+            PyList::new(py, items)?.into_any()
+        }
+        Value::Object(map) => {
+            let dict = PyDict::new(py);
+            for (key, item) in map {
+                dict.set_item(key, to_python(py, item)?)?;
+            }
+            dict.into_any()
+        }
+    })
+}
+
+/// Analyze a directory
+///
+/// Returns a dict with "graph", in networkx node-link format, and
+/// "metrics", a dict of equally long columns for `pandas.DataFrame`.
+/// `metrics` limits the run to the named metrics.
+#[pyfunction]
+#[pyo3(signature = (path, metrics=None))]
+fn analyze(py: Python<'_>, path: PathBuf, metrics: Option<Vec<String>>) -> PyResult<Py<PyDict>> {
+    if !path.is_dir() {
+        return Err(PyValueError::new_err(format!("{} is not a directory", path.display())));
+    }
+    let analysis = py
+        .allow_threads(|| crate::analyze_path(&path, metrics))
+        .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+
+    let columns = PyDict::new(py);
+    for (name, values) in crate::metric_columns(&analysis.metrics) {
+        columns.set_item(name, to_python(py, &Value::Array(values))?)?;
+    }
+    let result = PyDict::new(py);
+    result.set_item("graph", to_python(py, &crate::node_link_data(&analysis.graph))?)?;
+    result.set_item("metrics", columns)?;
+    Ok(result.unbind())
+}
+
+/// Names of the metrics `analyze` can be limited to
+#[pyfunction]
+fn metric_names() -> Vec<&'static str> {
+    og_analytics::DEFAULT_METRICS.to_vec()
+}
+
+#[pymodule]
+fn pyomnigraph(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_function(wrap_pyfunction!(analyze, module)?)?;
+    module.add_function(wrap_pyfunction!(metric_names, module)?)?;
+    Ok(())
+}
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Parsed file representation
 #[derive(Debug, Clone)]
@@ -39,6 +39,27 @@ pub enum NodeType {
     Export,
     TypeAlias,
     Enum,
+    Route,
+    Provider,
+    Controller,
+    Target,
+    Script,
+    Container,
+    Service,
+    /// Header included from outside the project, such as `<stdio.h>`
+    SystemHeader,
+    /// Environment variable read by the code
+    EnvVar,
+    /// Database table named in a query
+    Table,
+    /// ORM model mapped to a table
+    Model,
+    /// Event, message topic or action type that decouples publishers from subscribers
+    Event,
+    /// Mixin, function or placeholder selector declared in a stylesheet
+    Style,
+    /// GraphQL query, mutation or subscription sent by a client
+    Operation,
 }
 
 impl NodeType {
@@ -56,6 +77,20 @@ impl NodeType {
             NodeType::Export => "export",
             NodeType::TypeAlias => "type",
             NodeType::Enum => "enum",
+            NodeType::Route => "route",
+            NodeType::Provider => "provider",
+            NodeType::Controller => "controller",
+            NodeType::Target => "target",
+            NodeType::Script => "script",
+            NodeType::Container => "container",
+            NodeType::Service => "service",
+            NodeType::SystemHeader => "system_header",
+            NodeType::EnvVar => "env_var",
+            NodeType::Table => "table",
+            NodeType::Model => "model",
+            NodeType::Event => "event",
+            NodeType::Style => "style",
+            NodeType::Operation => "operation",
         }
     }
 }
@@ -78,6 +113,24 @@ pub enum RelationshipType {
     Extends,
     Implements,
     References,
+    /// Route to the function that handles it
+    RoutesTo,
+    /// Framework role to the class or function it was declared on
+    Decorates,
+    /// Build target to a prerequisite target or file
+    DependsOn,
+    /// Declaration to the definition it refers to, such as a C prototype
+    DefinedIn,
+    /// Code to an external value it reads, such as an environment variable
+    Reads,
+    /// Code to a table or model it writes
+    Writes,
+    /// Code to an event it emits or publishes
+    Publishes,
+    /// Code to an event it listens for or consumes
+    Subscribes,
+    /// GraphQL operation to the resolver of a field it selects
+    ResolvedBy,
 }
 
 impl RelationshipType {
@@ -90,6 +143,15 @@ impl RelationshipType {
             RelationshipType::Extends => "EXTENDS",
             RelationshipType::Implements => "IMPLEMENTS",
             RelationshipType::References => "REFERENCES",
+            RelationshipType::RoutesTo => "ROUTES_TO",
+            RelationshipType::Decorates => "DECORATES",
+            RelationshipType::DependsOn => "DEPENDS_ON",
+            RelationshipType::DefinedIn => "DEFINED_IN",
+            RelationshipType::Reads => "READS",
+            RelationshipType::Writes => "WRITES",
+            RelationshipType::Publishes => "PUBLISHES",
+            RelationshipType::Subscribes => "SUBSCRIBES",
+            RelationshipType::ResolvedBy => "RESOLVED_BY",
         }
     }
 }
@@ -102,9 +164,28 @@ pub enum Language {
     Python,
     Rust,
     C,
+    Shell,
+    Make,
+    Docker,
+    Css,
+    Graphql,
 }
 
 impl Language {
+    /// Every supported language
+    pub const ALL: &'static [Language] = &[
+        Language::JavaScript,
+        Language::TypeScript,
+        Language::Python,
+        Language::Rust,
+        Language::C,
+        Language::Shell,
+        Language::Make,
+        Language::Docker,
+        Language::Css,
+        Language::Graphql,
+    ];
+
     pub fn as_str(&self) -> &'static str {
         match self {
             Language::JavaScript => "javascript",
@@ -112,6 +193,11 @@ impl Language {
             Language::Python => "python",
             Language::Rust => "rust",
             Language::C => "c",
+            Language::Shell => "shell",
+            Language::Make => "make",
+            Language::Docker => "docker",
+            Language::Css => "css",
+            Language::Graphql => "graphql",
         }
     }
 
@@ -122,7 +208,23 @@ impl Language {
             Language::Python => &[".py", ".pyi"],
             Language::Rust => &[".rs"],
             Language::C => &[".c", ".h"],
+            Language::Shell => &[".sh", ".bash"],
+            Language::Make => &[".mk"],
+            Language::Docker => &[".dockerfile"],
+            Language::Css => &[".css", ".scss", ".sass", ".less"],
+            Language::Graphql => &[".graphql", ".gql"],
         }
     }
+
+    /// Language of a file, judged by its extension alone
+    pub fn from_path(path: &Path) -> Option<Language> {
+        let extension = path.extension()?.to_str()?;
+        Language::ALL.iter().copied().find(|language| {
+            language
+                .extensions()
+                .iter()
+                .any(|known| known[1..].eq_ignore_ascii_case(extension))
+        })
+    }
 }
 
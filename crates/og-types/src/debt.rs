@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+/// Words that mark technical debt in a comment
+pub const DEBT_KINDS: &[&str] = &["TODO", "FIXME", "HACK", "XXX"];
+
+/// A TODO, FIXME, HACK or XXX comment, attached to the node it sits in
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DebtMarker {
+    /// One of [`DEBT_KINDS`]
+    pub kind: String,
+    /// What the comment says after the marker word
+    pub text: String,
+    pub file_path: String,
+    /// 1-based line of the comment
+    pub line: usize,
+    /// Innermost node spanning the line, or the file node
+    pub node_id: String,
+}
@@ -1,3 +1,5 @@
+use crate::message::Message;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 /// Parse error type
@@ -45,4 +47,137 @@ pub enum EngineError {
 }
 
 /// Result type alias for engine operations
-pub type EngineResult<T> = Result<T, EngineError>;
\ No newline at end of file
+pub type EngineResult<T> = Result<T, EngineError>;
+
+/// Machine-readable category for errors surfaced to the frontend
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ErrorCode {
+    Io,
+    Parse,
+    AnalysisTimeout,
+    InvalidState,
+    InvalidInput,
+    Cancelled,
+    Internal,
+}
+
+/// Serializable error returned by frontend commands
+#[derive(Error, Debug, Clone, Serialize, Deserialize)]
+#[error("{message}")]
+pub struct CommandError {
+    pub code: ErrorCode,
+    /// English text of the error
+    pub message: String,
+    pub details: Option<String>,
+    pub retryable: bool,
+    /// The message as a code and params the frontend can translate, when it has one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub localized: Option<Message>,
+}
+
+impl CommandError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        let retryable = matches!(code, ErrorCode::Io | ErrorCode::AnalysisTimeout | ErrorCode::Cancelled);
+        Self {
+            code,
+            message: message.into(),
+            details: None,
+            retryable,
+            localized: None,
+        }
+    }
+
+    /// An error whose text the frontend can translate
+    pub fn from_message(code: ErrorCode, message: Message) -> Self {
+        Self {
+            localized: Some(message.clone()),
+            ..Self::new(code, message.to_string())
+        }
+    }
+
+    pub fn io(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Io, message)
+    }
+
+    pub fn parse(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Parse, message)
+    }
+
+    pub fn analysis_timeout(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::AnalysisTimeout, message)
+    }
+
+    pub fn invalid_state(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::InvalidState, message)
+    }
+
+    pub fn invalid_input(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::InvalidInput, message)
+    }
+
+    pub fn cancelled(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Cancelled, message)
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Internal, message)
+    }
+
+    /// Attach extra context, such as the underlying error
+    pub fn with_details(mut self, details: impl Into<String>) -> Self {
+        self.details = Some(details.into());
+        self
+    }
+}
+
+impl From<EngineError> for CommandError {
+    fn from(error: EngineError) -> Self {
+        let message = error.to_string();
+        match error {
+            EngineError::IoError(_) => Self::io(message),
+            EngineError::Parse(_) | EngineError::ParseError { .. } => Self::parse(message),
+            EngineError::ServiceNotInitialized | EngineError::NodeNotFound(_) => Self::invalid_state(message),
+            _ => Self::internal(message),
+        }
+    }
+}
+
+impl From<std::io::Error> for CommandError {
+    fn from(error: std::io::Error) -> Self {
+        Self::io(error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_error_serialization() {
+        let error = CommandError::analysis_timeout("Analysis timed out").with_details("30s limit");
+        let json = serde_json::to_value(&error).unwrap();
+        assert_eq!(json["code"], "analysis-timeout");
+        assert_eq!(json["retryable"], true);
+        assert_eq!(json["details"], "30s limit");
+    }
+
+    #[test]
+    fn test_localized_command_error() {
+        let error = CommandError::from_message(ErrorCode::InvalidInput, Message::NodeNotFound { id: "a.py".to_string() });
+        let json = serde_json::to_value(&error).unwrap();
+        assert_eq!(json["message"], "Node not found: a.py");
+        assert_eq!(json["localized"]["code"], "node-not-found");
+        assert_eq!(json["localized"]["params"]["id"], "a.py");
+
+        let plain = serde_json::to_value(CommandError::internal("boom")).unwrap();
+        assert!(plain.get("localized").is_none());
+    }
+
+    #[test]
+    fn test_engine_error_mapping() {
+        let error: CommandError = EngineError::ServiceNotInitialized.into();
+        assert_eq!(error.code, ErrorCode::InvalidState);
+        assert!(!error.retryable);
+    }
+}
@@ -1,9 +1,17 @@
 pub mod ast;
+pub mod debt;
 pub mod error;
 pub mod graph;
+pub mod license;
+pub mod message;
 pub mod metrics;
+pub mod suppression;
 
 pub use ast::*;
+pub use debt::*;
 pub use error::*;
 pub use graph::*;
-pub use metrics::*;
\ No newline at end of file
+pub use license::*;
+pub use message::*;
+pub use metrics::*;
+pub use suppression::*;
@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+/// License and copyright notice at the top of a source file
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LicenseHeader {
+    /// SPDX identifiers or expressions, empty when the header names no license
+    pub licenses: Vec<String>,
+    /// Text of the first copyright line, without the comment opener
+    pub copyright: Option<String>,
+}
+
+/// A LICENSE or COPYING file and the licenses it grants
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LicenseFile {
+    pub path: String,
+    /// SPDX identifiers, empty when the text is not recognized
+    pub licenses: Vec<String>,
+}
@@ -0,0 +1,121 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A user-facing message, as a code the frontend can translate and the
+/// values to fill into it
+///
+/// Serializes as `{"code": "graph-built", "params": {"nodes": 3, "edges": 2}}`,
+/// with no params for messages that take none. `Display` gives the English
+/// text, for the CLI and for frontends without a translation of the code.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "code", content = "params", rename_all = "kebab-case")]
+pub enum Message {
+    DiscoveringFiles,
+    ParsingFiles { count: usize },
+    ParsingFile { path: String },
+    ResolvingSymbols,
+    GraphTooLarge { kept: usize, total: usize },
+    FilesParsed,
+    BuildingGraph,
+    GraphBuilt { nodes: usize, edges: usize },
+    PreparingVisualization,
+    Complete,
+    PreparingAnalysis,
+    StartingAnalysis,
+    ComputingPagerank,
+    PagerankFailed,
+    RunningAnalysisSuite,
+    AnalyzingStructure,
+    ComputingMetrics,
+    FinalizingAnalysis,
+    AnalysisFailed,
+    ConvertingMetrics,
+    FinalizingMetrics,
+    AnalysisComplete,
+    NoGraph,
+    NoAnalysis,
+    EngineNotInitialized,
+    NotConnectedToNeo4j,
+    NodeNotFound { id: String },
+    UnknownNode { id: String },
+    NotADirectory { path: String },
+    InvalidRevision { revision: String },
+    PageLimitZero,
+    SeedsRequired,
+    /// Text with no code of its own, shown as is
+    Text { text: String },
+}
+
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Message::DiscoveringFiles => write!(f, "Discovering files"),
+            Message::ParsingFiles { count } => write!(f, "Parsing {} files", count),
+            Message::ParsingFile { path } => write!(f, "Parsing {}", path),
+            Message::ResolvingSymbols => write!(f, "Resolving symbols with language servers"),
+            Message::GraphTooLarge { kept, total } => {
+                write!(f, "Graph too large, keeping {} of {} nodes", kept, total)
+            }
+            Message::FilesParsed => write!(f, "Files parsed"),
+            Message::BuildingGraph => write!(f, "Building dependency graph"),
+            Message::GraphBuilt { nodes, edges } => write!(f, "Graph built: {} nodes, {} edges", nodes, edges),
+            Message::PreparingVisualization => write!(f, "Preparing visualization"),
+            Message::Complete => write!(f, "Complete"),
+            Message::PreparingAnalysis => write!(f, "Preparing for analysis"),
+            Message::StartingAnalysis => write!(f, "Starting analysis phase"),
+            Message::ComputingPagerank => write!(f, "Computing PageRank"),
+            Message::PagerankFailed => write!(f, "PageRank failed, skipping metrics"),
+            Message::RunningAnalysisSuite => write!(f, "Running analysis suite"),
+            Message::AnalyzingStructure => write!(f, "Analyzing graph structure"),
+            Message::ComputingMetrics => write!(f, "Computing metrics"),
+            Message::FinalizingAnalysis => write!(f, "Finalizing analysis"),
+            Message::AnalysisFailed => write!(f, "Analysis failed, continuing without metrics"),
+            Message::ConvertingMetrics => write!(f, "Converting metrics for UI"),
+            Message::FinalizingMetrics => write!(f, "Finalizing metrics"),
+            Message::AnalysisComplete => write!(f, "Analysis complete"),
+            Message::NoGraph => write!(f, "No parsed data available. Please parse a codebase first."),
+            Message::NoAnalysis => write!(f, "No analysis has been run yet."),
+            Message::EngineNotInitialized => write!(f, "Engine not initialized"),
+            Message::NotConnectedToNeo4j => write!(f, "Not connected to Neo4j"),
+            Message::NodeNotFound { id } => write!(f, "Node not found: {}", id),
+            Message::UnknownNode { id } => write!(f, "Unknown node: {}", id),
+            Message::NotADirectory { path } => write!(f, "Not a directory: {}", path),
+            Message::InvalidRevision { revision } => write!(f, "Invalid revision: {}", revision),
+            Message::PageLimitZero => write!(f, "Page limit must be greater than zero"),
+            Message::SeedsRequired => write!(f, "At least one seed node is required"),
+            Message::Text { text } => write!(f, "{}", text),
+        }
+    }
+}
+
+impl From<&str> for Message {
+    fn from(text: &str) -> Self {
+        Message::Text { text: text.to_string() }
+    }
+}
+
+impl From<String> for Message {
+    fn from(text: String) -> Self {
+        Message::Text { text }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_code_and_params() {
+        let message = Message::GraphBuilt { nodes: 3, edges: 2 };
+        let json = serde_json::to_value(&message).unwrap();
+        assert_eq!(json["code"], "graph-built");
+        assert_eq!(json["params"]["nodes"], 3);
+        assert_eq!(message.to_string(), "Graph built: 3 nodes, 2 edges");
+
+        let json = serde_json::to_value(Message::NoGraph).unwrap();
+        assert_eq!(json, serde_json::json!({ "code": "no-graph" }));
+        let back: Message = serde_json::from_value(json).unwrap();
+        assert_eq!(back, Message::NoGraph);
+        assert_eq!(Message::from("as is").to_string(), "as is");
+    }
+}
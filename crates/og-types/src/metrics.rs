@@ -1,14 +1,133 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// File-level metrics
 #[derive(Debug, Clone, Default)]
 pub struct FileMetrics {
     pub lines_of_code: usize,
+    /// Cyclomatic complexity of the file's functions taken as one program
     pub complexity: usize,
     pub functions: usize,
     pub classes: usize,
     pub imports: usize,
     pub exports: usize,
+    /// Dynamic imports whose target could not be determined statically
+    pub dynamic_imports: usize,
+    /// Shape of each function and method body, by node id
+    pub function_metrics: HashMap<String, FunctionMetrics>,
+    /// Lines with comments or docstrings, and lines with code
+    pub comments: CommentDensity,
+    /// Whether each public function, method or type has a doc comment, by node id
+    pub documented: HashMap<String, bool>,
+    /// Threads, tasks and locks each function uses, by node id
+    pub concurrency: HashMap<String, ConcurrencyProfile>,
+}
+
+/// How much of a file is commented
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentDensity {
+    /// Lines with a comment or docstring, trailing comments included
+    pub comment_lines: usize,
+    /// Non-blank lines with something other than a comment
+    pub code_lines: usize,
+}
+
+impl CommentDensity {
+    /// Comment lines per line of code, 0 for a file without code
+    pub fn ratio(&self) -> f64 {
+        if self.code_lines == 0 {
+            0.0
+        } else {
+            self.comment_lines as f64 / self.code_lines as f64
+        }
+    }
+}
+
+/// Size and shape of one function or method body
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FunctionMetrics {
+    /// Lines the function spans, signature included
+    pub length: usize,
+    /// Declared parameters, not counting `self` or `cls`
+    pub parameters: usize,
+    /// Deepest nesting of branches and loops, 0 for straight-line code
+    pub max_nesting: usize,
+    /// Return statements, not counting those of nested functions
+    pub returns: usize,
+    /// Cyclomatic complexity: 1 plus each branch, loop, case, catch and
+    /// short-circuit operator, not counting those of nested functions
+    pub complexity: usize,
+}
+
+/// Kind of concurrent work a call starts or guards
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConcurrencyPrimitive {
+    /// An OS thread, worker or process
+    Thread,
+    /// An async task or future run alongside others
+    Task,
+    /// A mutex, semaphore or other lock
+    Lock,
+}
+
+/// Concurrency primitives used in one function, or summed over a module
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConcurrencyProfile {
+    pub threads: usize,
+    pub tasks: usize,
+    pub locks: usize,
+}
+
+impl ConcurrencyProfile {
+    pub fn add(&mut self, primitive: ConcurrencyPrimitive) {
+        match primitive {
+            ConcurrencyPrimitive::Thread => self.threads += 1,
+            ConcurrencyPrimitive::Task => self.tasks += 1,
+            ConcurrencyPrimitive::Lock => self.locks += 1,
+        }
+    }
+
+    pub fn merge(&mut self, other: &ConcurrencyProfile) {
+        self.threads += other.threads;
+        self.tasks += other.tasks;
+        self.locks += other.locks;
+    }
+
+    pub fn total(&self) -> usize {
+        self.threads + self.tasks + self.locks
+    }
+}
+
+/// Layout version of the typed metric results, bumped on breaking changes
+pub const METRIC_SCHEMA_VERSION: u8 = 1;
+
+/// Marker serialized as `METRIC_SCHEMA_VERSION` in each metric result
+///
+/// Reading results written with another version fails instead of silently
+/// misreading fields.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MetricSchema;
+
+impl Serialize for MetricSchema {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(METRIC_SCHEMA_VERSION)
+    }
+}
+
+impl<'de> Deserialize<'de> for MetricSchema {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match u8::deserialize(deserializer)? {
+            METRIC_SCHEMA_VERSION => Ok(MetricSchema),
+            version => Err(serde::de::Error::custom(format!(
+                "unsupported metric schema version {}, expected {}",
+                version, METRIC_SCHEMA_VERSION
+            ))),
+        }
+    }
 }
 
 /// UI-facing stable contract for node metrics
@@ -25,6 +144,9 @@ pub struct UINodeMetricsV1 {
     pub payoff: f32,
     pub raw: RawMetrics,
     pub normalized: NormalizedMetrics,
+    /// Declared in a file written by a code generator
+    #[serde(default)]
+    pub generated: bool,
     #[serde(default = "default_version")]
     pub version: u8,
 }
@@ -41,6 +163,12 @@ pub struct RawMetrics {
     pub indegree: i64,
     pub outdegree: i64,
     pub k_core: i64,
+    /// Largest k of a k-truss the node belongs to
+    #[serde(default)]
+    pub k_truss: i64,
+    /// Removing the node would disconnect the graph
+    #[serde(default)]
+    pub articulation_point: bool,
     pub clustering: f64,
     pub betweenness: f64,
     pub churn: i64,
@@ -73,9 +201,27 @@ pub struct CompositeOutputs {
     pub payoff: f64,
 }
 
+/// How raw metric values are scaled to 0-1 for the UI
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NormalizationMode {
+    /// Linear between the smallest and largest value
+    #[default]
+    MinMax,
+    /// Share of values below this one, robust to skewed distributions
+    Percentile,
+    /// Standard deviations from the mean, clamped and mapped to 0-1
+    ZScore,
+}
+
 /// Normalization ranges for metrics
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct NormalizationRanges {
+    /// Strategy the normalized metrics were computed with
+    pub mode: NormalizationMode,
+    /// Standard deviations z-scores are clamped to before mapping to 0-1
+    pub z_clamp: f64,
     pub pagerank_imports: (f64, f64),
     pub pagerank_calls: (f64, f64),
     pub k_core: (f64, f64),
@@ -86,4 +232,23 @@ pub struct NormalizationRanges {
     pub complexity: (f64, f64),
     pub owners: (f64, f64),
     pub coverage: (f64, f64),
-}
\ No newline at end of file
+}
+
+impl NormalizationRanges {
+    /// Range of a metric by its field name, such as `"complexity"`
+    pub fn range(&self, metric: &str) -> Option<(f64, f64)> {
+        Some(match metric {
+            "pagerank_imports" => self.pagerank_imports,
+            "pagerank_calls" => self.pagerank_calls,
+            "k_core" => self.k_core,
+            "indegree" => self.indegree,
+            "clustering" => self.clustering,
+            "betweenness" => self.betweenness,
+            "churn" => self.churn,
+            "complexity" => self.complexity,
+            "owners" => self.owners,
+            "coverage" => self.coverage,
+            _ => return None,
+        })
+    }
+}
@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Finding kind that silences every finding on a node
+pub const ALL_FINDINGS: &str = "all";
+
+/// Kind of a finding as written in suppression comments
+///
+/// Labels are lowercased and joined with dashes, and any parenthesised
+/// detail is dropped, so "God Object" becomes `god-object` and
+/// "Interface Segregation (Wide Module)" becomes `interface-segregation`.
+pub fn finding_kind(label: &str) -> String {
+    let label = label.split(" (").next().unwrap_or(label);
+    label
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Findings silenced by inline comments, by node id
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Suppressions {
+    by_node: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl Suppressions {
+    /// Silence a finding kind on a node
+    pub fn insert(&mut self, node_id: impl Into<String>, kind: &str) {
+        self.by_node
+            .entry(node_id.into())
+            .or_default()
+            .insert(finding_kind(kind));
+    }
+
+    /// Whether a finding with this label is silenced on the node
+    pub fn is_suppressed(&self, node_id: &str, label: &str) -> bool {
+        self.by_node
+            .get(node_id)
+            .is_some_and(|kinds| kinds.contains(ALL_FINDINGS) || kinds.contains(&finding_kind(label)))
+    }
+
+    pub fn extend(&mut self, other: Suppressions) {
+        for (node_id, kinds) in other.by_node {
+            self.by_node.entry(node_id).or_default().extend(kinds);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_node.is_empty()
+    }
+
+    /// Nodes with at least one silenced finding
+    pub fn len(&self) -> usize {
+        self.by_node.len()
+    }
+}
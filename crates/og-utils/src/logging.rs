@@ -1,5 +1,19 @@
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::collections::{BTreeMap, VecDeque};
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 
+/// File name prefix for per-session log files
+const SESSION_LOG_PREFIX: &str = "omnigraph-";
+
 /// Initialize the logging system with sensible defaults
 pub fn init_logging() {
     let env_filter = EnvFilter::try_from_default_env()
@@ -51,4 +65,252 @@ macro_rules! log_metric_computation {
             "Computed metric"
         );
     };
-}
\ No newline at end of file
+}
+/// Single captured log record
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogEntry {
+    /// Unix timestamp in milliseconds
+    pub timestamp: u64,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+    pub fields: BTreeMap<String, String>,
+    /// Enclosing span names, outermost first
+    pub spans: Vec<String>,
+}
+
+/// Callback invoked for every captured entry
+pub type LogListener = Box<dyn Fn(&LogEntry) + Send + Sync>;
+
+thread_local! {
+    static IN_LISTENER: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Bounded in-memory buffer of recent log entries
+pub struct LogBuffer {
+    entries: Mutex<VecDeque<LogEntry>>,
+    capacity: usize,
+    listener: RwLock<Option<LogListener>>,
+}
+
+impl LogBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity,
+            listener: RwLock::new(None),
+        }
+    }
+
+    /// Store an entry, evicting the oldest when full, and notify the listener
+    pub fn push(&self, entry: LogEntry) {
+        {
+            let mut entries = self.entries.lock().unwrap();
+            if entries.len() == self.capacity {
+                entries.pop_front();
+            }
+            entries.push_back(entry.clone());
+        }
+
+        // Logging from inside the listener must not recurse back into it
+        if IN_LISTENER.with(|flag| flag.replace(true)) {
+            return;
+        }
+        if let Some(listener) = self.listener.read().unwrap().as_ref() {
+            listener(&entry);
+        }
+        IN_LISTENER.with(|flag| flag.set(false));
+    }
+
+    /// Most recent entries at or above `min_level`, oldest first
+    pub fn recent(&self, min_level: Level, limit: usize) -> Vec<LogEntry> {
+        let entries = self.entries.lock().unwrap();
+        let mut matching: Vec<LogEntry> = entries
+            .iter()
+            .rev()
+            .filter(|entry| {
+                entry
+                    .level
+                    .parse::<Level>()
+                    .map(|level| level <= min_level)
+                    .unwrap_or(false)
+            })
+            .take(limit)
+            .cloned()
+            .collect();
+        matching.reverse();
+        matching
+    }
+
+    /// Install or remove the live listener
+    pub fn set_listener(&self, listener: Option<LogListener>) {
+        *self.listener.write().unwrap() = listener;
+    }
+}
+
+/// Tracing layer that records events into a [`LogBuffer`]
+pub struct LogBufferLayer {
+    buffer: Arc<LogBuffer>,
+}
+
+impl LogBufferLayer {
+    pub fn new(buffer: Arc<LogBuffer>) -> Self {
+        Self { buffer }
+    }
+}
+
+impl<S> Layer<S> for LogBufferLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let mut visitor = FieldCollector::default();
+        event.record(&mut visitor);
+
+        let spans = ctx
+            .event_scope(event)
+            .map(|scope| scope.from_root().map(|span| span.name().to_string()).collect())
+            .unwrap_or_default();
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+
+        self.buffer.push(LogEntry {
+            timestamp,
+            level: event.metadata().level().to_string(),
+            target: event.metadata().target().to_string(),
+            message: visitor.message,
+            fields: visitor.fields,
+            spans,
+        });
+    }
+}
+
+#[derive(Default)]
+struct FieldCollector {
+    message: String,
+    fields: BTreeMap<String, String>,
+}
+
+impl Visit for FieldCollector {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        } else {
+            self.fields.insert(field.name().to_string(), format!("{:?}", value));
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            self.fields.insert(field.name().to_string(), value.to_string());
+        }
+    }
+}
+
+/// Create a new log file for this session in `dir`, keeping only the newest `keep` files
+pub fn open_session_log(dir: &Path, keep: usize) -> std::io::Result<(PathBuf, File)> {
+    fs::create_dir_all(dir)?;
+
+    let mut existing: Vec<PathBuf> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .map(|name| name.starts_with(SESSION_LOG_PREFIX) && name.ends_with(".log"))
+                .unwrap_or(false)
+        })
+        .collect();
+    // Timestamped names sort chronologically
+    existing.sort();
+
+    let remove_count = (existing.len() + 1).saturating_sub(keep.max(1));
+    for old in existing.iter().take(remove_count) {
+        let _ = fs::remove_file(old);
+    }
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    let path = dir.join(format!("{}{:016}.log", SESSION_LOG_PREFIX, timestamp));
+    let file = File::create(&path)?;
+    Ok((path, file))
+}
+
+/// Initialize logging to stdout, an optional session file, and an in-memory buffer
+///
+/// Returns false if a global subscriber was already installed.
+pub fn init_logging_with_sinks(filter: &str, log_file: Option<File>, buffer: Arc<LogBuffer>) -> bool {
+    let env_filter = EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| EnvFilter::new(filter));
+
+    let file_layer = log_file.map(|file| {
+        tracing_subscriber::fmt::layer()
+            .with_ansi(false)
+            .with_writer(Mutex::new(file))
+    });
+
+    tracing_subscriber::registry()
+        .with(env_filter)
+        .with(tracing_subscriber::fmt::layer())
+        .with(file_layer)
+        .with(LogBufferLayer::new(buffer))
+        .try_init()
+        .is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(level: Level, message: &str) -> LogEntry {
+        LogEntry {
+            timestamp: 0,
+            level: level.to_string(),
+            target: "test".to_string(),
+            message: message.to_string(),
+            fields: BTreeMap::new(),
+            spans: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_log_buffer_filters_and_evicts() {
+        let buffer = LogBuffer::new(3);
+        buffer.push(entry(Level::INFO, "first"));
+        buffer.push(entry(Level::DEBUG, "second"));
+        buffer.push(entry(Level::WARN, "third"));
+        buffer.push(entry(Level::ERROR, "fourth"));
+
+        let all = buffer.recent(Level::TRACE, 10);
+        let messages: Vec<&str> = all.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(messages, vec!["second", "third", "fourth"]);
+
+        let warnings = buffer.recent(Level::WARN, 10);
+        assert_eq!(warnings.len(), 2);
+
+        let latest = buffer.recent(Level::TRACE, 1);
+        assert_eq!(latest[0].message, "fourth");
+    }
+
+    #[test]
+    fn test_session_logs_rotate() {
+        let dir = std::env::temp_dir().join(format!("og-utils-logs-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        for _ in 0..4 {
+            open_session_log(&dir, 2).unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(2));
+        }
+
+        let count = fs::read_dir(&dir).unwrap().count();
+        assert_eq!(count, 2);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
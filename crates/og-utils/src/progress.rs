@@ -1,9 +1,17 @@
+use og_types::Message;
 use std::sync::Arc;
 
 /// Trait for reporting progress of long-running operations
 pub trait ProgressReporter: Send + Sync {
     /// Report progress with a message and percentage
     fn report(&self, message: &str, percentage: f32);
+
+    /// Report progress with a message the frontend can translate
+    ///
+    /// Reporters that only show text get the English default.
+    fn report_message(&self, message: &Message, percentage: f32) {
+        self.report(&message.to_string(), percentage);
+    }
     
     /// Mark operation as complete
     fn complete(&self, message: Option<&str>);
@@ -0,0 +1,361 @@
+use crate::engine_v2::{AnalyzedGraph, Engine};
+use crate::limits::AnalysisLimits;
+use crate::project_config::ProjectConfig;
+use crate::revision;
+use og_analytics::analysis::pr_summary::{AnalysisSide, PrSummary, DEFAULT_PR_FILES};
+use og_graph::graph::CodeGraph;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const USAGE: &str = "usage: omnigraph check [--path DIR] [--staged | --pushed] [--json] [FILE...]\n\nFILE paths are relative to DIR; without them the staged changes are checked.\nOnly problems the changes add are reported, compared with HEAD or, with --pushed, the upstream merge base.";
+
+const PR_SUMMARY_USAGE: &str = "usage: omnigraph pr-summary --baseline DIR [--path DIR] [--limit N] [--json]\n\nCompares the checkout at --path with the baseline checkout and prints a Markdown PR comment.";
+
+const DIFF_USAGE: &str = "usage: omnigraph diff [--path DIR] [--limit N] [--json] [--check] REV_A REV_B\n\nCompares the codebase at two commits or branches without touching the working copy.\nWith --check, exits with 1 when REV_B adds dependency cycles.";
+
+/// Exit code when the check ran and found violations
+const EXIT_VIOLATIONS: i32 = 1;
+
+/// Exit code when the check could not run
+const EXIT_ERROR: i32 = 2;
+
+/// Changes a check covers
+enum Changes {
+    Staged,
+    Pushed,
+    Files(Vec<String>),
+}
+
+/// Run a command-line subcommand, or None to start the app
+pub fn run_cli(args: &[String]) -> Option<i32> {
+    match args.get(1).map(String::as_str) {
+        Some("check") => Some(check(&args[2..])),
+        Some("pr-summary") => Some(pr_summary(&args[2..])),
+        Some("diff") => Some(diff(&args[2..])),
+        _ => None,
+    }
+}
+
+fn check(args: &[String]) -> i32 {
+    let mut root = PathBuf::from(".");
+    let mut json = false;
+    let mut changes = Changes::Staged;
+    let mut files = Vec::new();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--path" => match args.next() {
+                Some(path) => root = PathBuf::from(path),
+                None => {
+                    eprintln!("{}", USAGE);
+                    return EXIT_ERROR;
+                }
+            },
+            "--staged" => changes = Changes::Staged,
+            "--pushed" => changes = Changes::Pushed,
+            "--json" => json = true,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return 0;
+            }
+            flag if flag.starts_with('-') => {
+                eprintln!("unknown option {}\n{}", flag, USAGE);
+                return EXIT_ERROR;
+            }
+            file => files.push(file.to_string()),
+        }
+    }
+    if !files.is_empty() {
+        changes = Changes::Files(files);
+    }
+
+    let changed = match changed_files(&root, &changes) {
+        Ok(Some(changed)) => changed,
+        Ok(None) => {
+            eprintln!("omnigraph: no upstream branch to compare against, skipping check");
+            return 0;
+        }
+        Err(e) => {
+            eprintln!("omnigraph: {}", e);
+            return EXIT_ERROR;
+        }
+    };
+    if changed.is_empty() {
+        return 0;
+    }
+
+    let rules = ProjectConfig::load(&root).check;
+    let (before, after) = match tauri::async_runtime::block_on(before_and_after(&root, &changes)) {
+        Ok(graphs) => graphs,
+        Err(e) => {
+            eprintln!("omnigraph: analysis failed: {:#}", e);
+            return EXIT_ERROR;
+        }
+    };
+    let report = after.check_changes(&before, &changed, &rules);
+
+    if json {
+        match serde_json::to_string_pretty(&report) {
+            Ok(out) => println!("{}", out),
+            Err(e) => {
+                eprintln!("omnigraph: {}", e);
+                return EXIT_ERROR;
+            }
+        }
+    } else {
+        for violation in &report.violations {
+            eprintln!("omnigraph: {}", violation.message);
+        }
+        eprintln!(
+            "omnigraph: {} violation(s) in {} changed file(s), {} nodes checked",
+            report.violations.len(),
+            changed.len(),
+            report.checked_nodes
+        );
+    }
+
+    if report.passed() {
+        0
+    } else {
+        EXIT_VIOLATIONS
+    }
+}
+
+/// The codebase before and after the changes a check covers
+///
+/// Staged changes are read from the index rather than the working copy and
+/// compared with HEAD. Pushed changes are HEAD compared with where it left
+/// its upstream branch, and listed files are the working copy compared with
+/// HEAD. Without a commit to compare against, the graph before is empty.
+async fn before_and_after(root: &Path, changes: &Changes) -> anyhow::Result<(CodeGraph, CodeGraph)> {
+    let limits = AnalysisLimits::default();
+    let (base, after) = match changes {
+        Changes::Staged => (
+            "HEAD".to_string(),
+            revision::analyze_staged(root, limits.clone(), &[]).await?.graph_data,
+        ),
+        Changes::Pushed => (
+            revision::merge_base(root, "@{upstream}", "HEAD")?,
+            revision::analyze_at(root, "HEAD", limits.clone(), &[]).await?.1.graph_data,
+        ),
+        Changes::Files(_) => (
+            "HEAD".to_string(),
+            Engine::new(root.to_path_buf()).analyze_codebase(None).await?.0,
+        ),
+    };
+
+    let before = if revision::resolves(root, &base) {
+        let (_, analyzed) = revision::analyze_at(root, &base, limits, &[]).await?;
+        CodeGraph::from_graph_data(&analyzed.graph_data)
+    } else {
+        CodeGraph::new()
+    };
+    Ok((before, CodeGraph::from_graph_data(&after)))
+}
+
+/// Changed files as the engine names them, or None when there is nothing to compare against
+fn changed_files(root: &Path, changes: &Changes) -> Result<Option<Vec<String>>, String> {
+    let relative = match changes {
+        Changes::Files(files) => files.clone(),
+        Changes::Staged => match git_diff(root, &["--cached"])? {
+            Some(files) => files,
+            None => return Err("failed to list staged files".to_string()),
+        },
+        Changes::Pushed => match git_diff(root, &["@{upstream}...HEAD"])? {
+            Some(files) => files,
+            None => return Ok(None),
+        },
+    };
+
+    Ok(Some(
+        relative
+            .iter()
+            .map(|file| root.join(file).to_string_lossy().into_owned())
+            .collect(),
+    ))
+}
+
+/// Added, copied, modified or renamed files relative to `root`, None if git failed
+fn git_diff(root: &Path, range: &[&str]) -> Result<Option<Vec<String>>, String> {
+    let output = Command::new("git")
+        .args(["diff", "--name-only", "--relative", "--diff-filter=ACMR"])
+        .args(range)
+        .current_dir(root)
+        .output()
+        .map_err(|e| format!("failed to run git: {}", e))?;
+    if !output.status.success() {
+        return Ok(None);
+    }
+    Ok(Some(
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect(),
+    ))
+}
+
+/// Compare two analyses by their paths relative to each analyzed root
+pub(crate) fn summarize(baseline: &AnalyzedGraph, current: &AnalyzedGraph, limit: usize) -> PrSummary {
+    let baseline_graph = CodeGraph::from_graph_data(&baseline.graph_data);
+    let current_graph = CodeGraph::from_graph_data(&current.graph_data);
+    PrSummary::build(
+        &AnalysisSide {
+            root: &baseline.run_info.analyzed_path,
+            graph: &baseline_graph,
+            metrics: &baseline.metrics,
+        },
+        &AnalysisSide {
+            root: &current.run_info.analyzed_path,
+            graph: &current_graph,
+            metrics: &current.metrics,
+        },
+        limit,
+    )
+}
+
+fn pr_summary(args: &[String]) -> i32 {
+    let mut root = PathBuf::from(".");
+    let mut baseline = None;
+    let mut limit = DEFAULT_PR_FILES;
+    let mut json = false;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--json" => json = true,
+            "-h" | "--help" => {
+                println!("{}", PR_SUMMARY_USAGE);
+                return 0;
+            }
+            option => {
+                let Some(value) = args.next() else {
+                    eprintln!("{}", PR_SUMMARY_USAGE);
+                    return EXIT_ERROR;
+                };
+                match option {
+                    "--path" => root = PathBuf::from(value),
+                    "--baseline" => baseline = Some(PathBuf::from(value)),
+                    "--limit" => match value.parse() {
+                        Ok(n) => limit = n,
+                        Err(_) => {
+                            eprintln!("invalid --limit {}\n{}", value, PR_SUMMARY_USAGE);
+                            return EXIT_ERROR;
+                        }
+                    },
+                    _ => {
+                        eprintln!("unknown option {}\n{}", option, PR_SUMMARY_USAGE);
+                        return EXIT_ERROR;
+                    }
+                }
+            }
+        }
+    }
+    let Some(baseline) = baseline else {
+        eprintln!("{}", PR_SUMMARY_USAGE);
+        return EXIT_ERROR;
+    };
+
+    let analyze = |path: PathBuf| tauri::async_runtime::block_on(Engine::new(path).analyze_with_metrics(None));
+    let (baseline, current) = match (analyze(baseline), analyze(root)) {
+        (Ok(baseline), Ok(current)) => (baseline, current),
+        (Err(e), _) | (_, Err(e)) => {
+            eprintln!("omnigraph: analysis failed: {}", e);
+            return EXIT_ERROR;
+        }
+    };
+
+    let summary = summarize(&baseline, &current, limit);
+    if json {
+        match serde_json::to_string_pretty(&summary) {
+            Ok(out) => println!("{}", out),
+            Err(e) => {
+                eprintln!("omnigraph: {}", e);
+                return EXIT_ERROR;
+            }
+        }
+    } else {
+        print!("{}", summary.to_markdown());
+    }
+    0
+}
+
+fn diff(args: &[String]) -> i32 {
+    let mut root = PathBuf::from(".");
+    let mut limit = DEFAULT_PR_FILES;
+    let mut json = false;
+    let mut check = false;
+    let mut revs = Vec::new();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--json" => json = true,
+            "--check" => check = true,
+            "-h" | "--help" => {
+                println!("{}", DIFF_USAGE);
+                return 0;
+            }
+            option @ ("--path" | "--limit") => {
+                let Some(value) = args.next() else {
+                    eprintln!("{}", DIFF_USAGE);
+                    return EXIT_ERROR;
+                };
+                if option == "--path" {
+                    root = PathBuf::from(value);
+                } else {
+                    match value.parse() {
+                        Ok(n) => limit = n,
+                        Err(_) => {
+                            eprintln!("invalid --limit {}\n{}", value, DIFF_USAGE);
+                            return EXIT_ERROR;
+                        }
+                    }
+                }
+            }
+            flag if flag.starts_with('-') => {
+                eprintln!("unknown option {}\n{}", flag, DIFF_USAGE);
+                return EXIT_ERROR;
+            }
+            rev => revs.push(rev.to_string()),
+        }
+    }
+    let [rev_a, rev_b] = revs.as_slice() else {
+        eprintln!("{}", DIFF_USAGE);
+        return EXIT_ERROR;
+    };
+
+    let diff = match tauri::async_runtime::block_on(revision::diff_revisions(
+        &root,
+        rev_a,
+        rev_b,
+        AnalysisLimits::default(),
+        &[],
+        limit,
+    )) {
+        Ok(diff) => diff,
+        Err(e) => {
+            eprintln!("omnigraph: {:#}", e);
+            return EXIT_ERROR;
+        }
+    };
+
+    if json {
+        match serde_json::to_string_pretty(&diff) {
+            Ok(out) => println!("{}", out),
+            Err(e) => {
+                eprintln!("omnigraph: {}", e);
+                return EXIT_ERROR;
+            }
+        }
+    } else {
+        print!("{}", diff.to_markdown());
+    }
+
+    if check && !diff.changes.new_cycles.is_empty() {
+        EXIT_VIOLATIONS
+    } else {
+        0
+    }
+}
@@ -0,0 +1,99 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Command;
+
+/// Editor that nodes are opened in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EditorKind {
+    /// `code --goto file:line`
+    VsCode,
+    /// A JetBrains launcher such as `idea` or `pycharm` with `--line`
+    JetBrains,
+    /// `$VISUAL` or `$EDITOR` with `+line file`, as vim, emacs and nano accept
+    Environment,
+    /// The configured command with `{file}` and `{line}` substituted
+    Custom,
+}
+
+/// How to open a source location in the user's editor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EditorSettings {
+    pub editor: EditorKind,
+    /// Executable or command template, the editor's default when unset
+    pub command: Option<String>,
+}
+
+impl Default for EditorSettings {
+    fn default() -> Self {
+        Self {
+            editor: EditorKind::VsCode,
+            command: None,
+        }
+    }
+}
+
+impl EditorSettings {
+    /// Check that the settings can produce a command
+    pub fn validate(&self) -> Result<(), String> {
+        match (&self.editor, self.command.as_deref().map(str::trim)) {
+            (EditorKind::Custom, None | Some("")) => Err("A custom editor needs a command".to_string()),
+            (EditorKind::Custom, Some(template)) if !template.contains("{file}") => {
+                Err("A custom editor command must contain {file}".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Program and arguments that open `file` at `line`
+    pub fn command_line(&self, file: &Path, line: usize) -> Result<(String, Vec<String>), String> {
+        self.validate()?;
+        let file = file.display().to_string();
+        let configured = self.command.as_deref().map(str::trim).filter(|c| !c.is_empty());
+
+        let (program, args) = match self.editor {
+            EditorKind::VsCode => (
+                configured.unwrap_or("code").to_string(),
+                vec!["--goto".to_string(), format!("{}:{}", file, line)],
+            ),
+            EditorKind::JetBrains => (
+                configured.unwrap_or("idea").to_string(),
+                vec!["--line".to_string(), line.to_string(), file],
+            ),
+            EditorKind::Environment => {
+                let editor = configured
+                    .map(str::to_string)
+                    .or_else(|| std::env::var("VISUAL").ok())
+                    .or_else(|| std::env::var("EDITOR").ok())
+                    .filter(|e| !e.trim().is_empty())
+                    .ok_or_else(|| "Neither $VISUAL nor $EDITOR is set".to_string())?;
+                let mut parts = editor.split_whitespace().map(str::to_string);
+                let program = parts.next().unwrap_or_default();
+                let mut args: Vec<String> = parts.collect();
+                args.push(format!("+{}", line));
+                args.push(file);
+                (program, args)
+            }
+            EditorKind::Custom => {
+                let mut parts = configured.unwrap_or_default().split_whitespace().map(|part| {
+                    part.replace("{file}", &file).replace("{line}", &line.to_string())
+                });
+                let program = parts.next().unwrap_or_default();
+                (program, parts.collect())
+            }
+        };
+        Ok((program, args))
+    }
+
+    /// Launch the editor at `file:line` without waiting for it to exit
+    pub fn open(&self, file: &Path, line: usize) -> Result<(), String> {
+        let (program, args) = self.command_line(file, line)?;
+        tracing::info!("Opening {}:{} with {} {:?}", file.display(), line, program, args);
+        Command::new(&program)
+            .args(&args)
+            .spawn()
+            .map(|_| ())
+            .map_err(|e| format!("Failed to launch {}: {}", program, e))
+    }
+}
@@ -1,37 +1,199 @@
 use anyhow::Result;
-use og_analytics::{analyze_graph, to_ui_metrics, AnalyticsConfig};
+use og_analytics::analysis::distribution::DEFAULT_HISTOGRAM_BINS;
+use og_analytics::analysis::evolution::file_graph;
+use og_analytics::{analyze_graph, analyze_graph_modular, dependency_cycles, metric_distributions, to_ui_metrics, Alert, AnalyticsConfig, AnalyticsConfigV2, MetricDistribution, ModularAnalysisReport, DEFAULT_SAMPLING_SEED};
+use og_graph::attributes::{COMPLEXITY_ATTRIBUTE, LINE_ATTRIBUTE, LOC_ATTRIBUTE};
+use og_graph::external::{ExternalDependencies, ExternalFanOut, ExternalImport};
+use og_graph::builder::lines_of_code;
+use og_graph::file_tree::FileStats;
+use og_graph::incremental::{GraphChangeSet, IncrementalMetrics};
+use og_graph::overrides::Overrides;
 use og_graph::graph::{CodeGraph, GraphNode, GraphEdge, GraphData};
+use og_parser::incremental::IncrementalParser;
 use og_parser::ParserEngine;
-use og_types::{ParsedFile, NodeType, RelationshipType};
+use og_types::{Language, LicenseFile, Message, NormalizationRanges, ParsedFile, NodeType, RelationshipType, ALL_FINDINGS};
 use og_utils::ProgressReporter;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tracing::info;
 
+use crate::history;
+use crate::limits::{AnalysisLimits, LargeFileStrategy, ParseBudget, SourceText};
+use crate::performance::PerformanceReport;
+use crate::project_config::ProjectConfig;
+use crate::run_info::{RunConfig, RunInfo};
+
+/// Maximum time allowed for the analytics suite
+const ANALYSIS_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The analytics suite ran past its time limit
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("Analysis timed out after {} seconds", .0.as_secs())]
+pub struct AnalysisTimedOut(pub Duration);
+
+/// Ignore file, in gitignore syntax, for files left out of analysis but not of git
+pub const IGNORE_FILE: &str = ".omnigraphignore";
+
+/// Files read and parsed at a time, so node and edge limits apply while parsing
+const PARSE_BATCH_FILES: usize = 256;
+
+/// Progress through one batch of files, reported as progress through all of them
+struct BatchProgress {
+    inner: Arc<dyn ProgressReporter>,
+    /// Files in earlier batches
+    done: usize,
+    len: usize,
+    total: usize,
+}
+
+impl BatchProgress {
+    fn overall(&self, percentage: f32) -> f32 {
+        (self.done as f32 + self.len as f32 * percentage / 100.0) / self.total.max(1) as f32 * 100.0
+    }
+}
+
+impl ProgressReporter for BatchProgress {
+    fn report(&self, message: &str, percentage: f32) {
+        self.inner.report(message, self.overall(percentage));
+    }
+
+    fn report_message(&self, message: &Message, percentage: f32) {
+        self.inner.report_message(message, self.overall(percentage));
+    }
+
+    fn complete(&self, message: Option<&str>) {
+        self.inner.complete(message);
+    }
+
+    fn error(&self, message: &str, error: Option<&dyn std::error::Error>) {
+        self.inner.error(message, error);
+    }
+}
+
+/// Identity of the file a path resolves to, shared by every link to it
+#[cfg(unix)]
+fn file_identity(path: &Path) -> std::io::Result<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    let metadata = std::fs::metadata(path)?;
+    Ok((metadata.dev(), metadata.ino()))
+}
+
+/// Identity of the file a path resolves to, shared by every symlink to it
+#[cfg(not(unix))]
+fn file_identity(path: &Path) -> std::io::Result<PathBuf> {
+    path.canonicalize()
+}
+
+/// Whether a walk error is a symlink loop or a broken link, rather than a failure
+fn is_skippable_walk_error(error: &ignore::Error) -> bool {
+    match error {
+        ignore::Error::Loop { .. } => true,
+        ignore::Error::WithPath { err, .. } | ignore::Error::WithDepth { err, .. } => is_skippable_walk_error(err),
+        ignore::Error::Io(e) => e.kind() == std::io::ErrorKind::NotFound,
+        _ => false,
+    }
+}
+
 /// Main engine that orchestrates parsing, graph building, and analytics
 #[derive(Clone)]
 pub struct Engine {
     parser: Arc<ParserEngine>,
     base_path: PathBuf,
+    limits: AnalysisLimits,
+    external: ExternalDependencies,
+    config: Arc<ProjectConfig>,
+    /// Last syntax tree of each file a partial re-parse has seen
+    parse_cache: Arc<IncrementalParser>,
 }
 
 impl Engine {
-    /// Create a new engine for analyzing a codebase
+    /// Create a new engine for analyzing a codebase, reading its `.omnigraph/config.toml`
     pub fn new(base_path: PathBuf) -> Self {
+        let config = ProjectConfig::load(&base_path);
         Self {
             parser: Arc::new(ParserEngine::with_base_path(base_path.clone())),
             base_path,
+            limits: config.apply_thresholds(AnalysisLimits::default()),
+            external: config.external_dependencies.unwrap_or_default(),
+            config: Arc::new(config),
+            parse_cache: Arc::default(),
         }
     }
 
-    /// Analyze a codebase and return the graph data
+    /// Search these directories for C includes, relative to the codebase root
+    pub fn with_include_dirs(mut self, include_dirs: &[PathBuf]) -> Self {
+        let include_dirs = include_dirs.iter().map(|dir| self.base_path.join(dir)).collect();
+        self.parser = Arc::new(ParserEngine::with_include_dirs(self.base_path.clone(), include_dirs));
+        self
+    }
+
+    /// Use custom size limits for this engine, unless the project config sets them
+    pub fn with_limits(mut self, limits: AnalysisLimits) -> Self {
+        self.limits = self.config.apply_thresholds(limits);
+        self
+    }
+
+    /// Show third-party imports as `external` asks, unless the project config sets it
+    pub fn with_external_dependencies(mut self, external: ExternalDependencies) -> Self {
+        self.external = self.config.external_dependencies.unwrap_or(external);
+        self
+    }
+
+    /// Reparse edited files from the syntax trees of an earlier engine for the same codebase
+    pub fn with_parse_cache(mut self, parse_cache: Arc<IncrementalParser>) -> Self {
+        self.parse_cache = parse_cache;
+        self
+    }
+
+    /// Syntax trees partial re-parses keep, for the next engine of this codebase
+    pub fn parse_cache(&self) -> Arc<IncrementalParser> {
+        self.parse_cache.clone()
+    }
+
+    /// Analyze a codebase and return the graph data with run metadata
+    #[tracing::instrument(skip_all, fields(path = %self.base_path.display()))]
     pub async fn analyze_codebase(
         &self,
         progress: Option<Arc<dyn ProgressReporter>>,
-    ) -> Result<GraphData> {
-        self.analyze_codebase_internal(progress, false).await
+    ) -> Result<(GraphData, RunInfo)> {
+        let started = Instant::now();
+        let (graph_data, mut run_info) = self.analyze_codebase_internal(progress, false, &self.base_path).await?;
+        run_info.total_duration_ms = started.elapsed().as_millis() as u64;
+        Ok((graph_data, run_info))
+    }
+
+    /// Re-parse one directory and splice it into a cached graph of the whole codebase
+    ///
+    /// Only files under `subpath`, relative to the codebase root, are parsed.
+    /// Edges between the directory and the rest of the codebase are kept from
+    /// the cached graph.
+    #[tracing::instrument(skip_all, fields(path = %self.base_path.display()))]
+    pub async fn analyze_subtree(
+        &self,
+        subpath: &Path,
+        cached: &GraphData,
+        progress: Option<Arc<dyn ProgressReporter>>,
+    ) -> Result<(GraphData, RunInfo)> {
+        let started = Instant::now();
+        let dir = self.base_path.join(subpath);
+        let (fresh, mut run_info) = self.analyze_codebase_internal(progress, false, &dir).await?;
+
+        let stage_start = Instant::now();
+        let mut graph = CodeGraph::from_graph_data(cached);
+        let merge = graph.replace_subtree(&dir, &CodeGraph::from_graph_data(&fresh));
+        let graph_data = graph.to_frontend_format();
+        run_info.record_stage("merge_subtree", stage_start.elapsed());
+        info!(
+            "Replaced {} nodes under {:?} with {}, keeping {} edges to the rest of the codebase",
+            merge.removed_nodes, dir, merge.added_nodes, merge.preserved_edges
+        );
+
+        run_info.partial_path = Some(dir.display().to_string());
+        run_info.total_duration_ms = started.elapsed().as_millis() as u64;
+        Ok((graph_data, run_info))
     }
     
     /// Internal method for analyze_codebase with progress control
@@ -39,98 +201,200 @@ impl Engine {
         &self,
         progress: Option<Arc<dyn ProgressReporter>>,
         with_metrics: bool,
-    ) -> Result<GraphData> {
+        dir: &Path,
+    ) -> Result<(GraphData, RunInfo)> {
+        let mut run_info = RunInfo::start(&self.base_path);
+
         // Adjust progress percentages based on whether we're doing metrics
         let parse_end = if with_metrics { 30.0 } else { 40.0 };
         let graph_end = if with_metrics { 60.0 } else { 80.0 };
         let viz_end = if with_metrics { 70.0 } else { 95.0 };
         
         // 1. Discover files
-        info!("Discovering files in {:?}", self.base_path);
+        info!("Discovering files in {:?}", dir);
         if let Some(ref reporter) = progress {
-            reporter.report("Discovering files", 5.0);
+            reporter.report_message(&Message::DiscoveringFiles, 5.0);
         }
-        let files = self.discover_files(&self.base_path)?;
+        let stage_start = Instant::now();
+        let files = self.discover_files(dir)?;
+        run_info.record_stage("discover", stage_start.elapsed());
         info!("Found {} files", files.len());
+        self.limits.check_files(files.len())?;
 
         // 2. Parse files
         if let Some(ref reporter) = progress {
-            reporter.report(&format!("Parsing {} files", files.len()), 10.0);
+            reporter.report_message(&Message::ParsingFiles { count: files.len() }, 10.0);
         }
-        let parsed_files = self.parse_files(files, progress.clone())?;
+        let stage_start = Instant::now();
+        let mut budget = self.limits.budget();
+        // Partial re-parses are edits, which reparse only what changed
+        let cache = (dir != self.base_path).then_some(self.parse_cache.as_ref());
+        let mut parsed_files = self.parse_files(files, cache, &mut run_info, &mut budget, progress.clone())?;
+        Self::link_files(&mut parsed_files);
+        run_info.record_stage("parse", stage_start.elapsed());
         info!("Parsed {} files", parsed_files.len());
+
+        if self.config.lsp.enabled {
+            if let Some(ref reporter) = progress {
+                reporter.report_message(&Message::ResolvingSymbols, parse_end - 5.0);
+            }
+            let stage_start = Instant::now();
+            let report = og_parser::lsp::resolve_with_servers(&mut parsed_files, &self.base_path, &self.config.lsp);
+            run_info.record_stage("lsp", stage_start.elapsed());
+            info!(
+                "Language servers {:?} added {} edges and removed {}, unavailable: {:?}",
+                report.servers, report.edges_added, report.edges_removed, report.unavailable
+            );
+        }
+
+        // Shrink oversized results before they become a graph
+        run_info.degradation = budget.finish(&mut parsed_files)?;
+        if let (Some(degradation), Some(reporter)) = (&run_info.degradation, &progress) {
+            reporter.report_message(
+                &Message::GraphTooLarge { kept: degradation.kept_nodes, total: degradation.original_nodes },
+                parse_end,
+            );
+        }
+
+        for file in &parsed_files {
+            *run_info
+                .files_by_language
+                .entry(file.language.as_str().to_string())
+                .or_insert(0) += 1;
+            if file.metrics.dynamic_imports > 0 {
+                run_info.dynamic_import_files.push(file.path.display().to_string());
+            }
+            run_info.file_stats.insert(
+                file.path.display().to_string(),
+                FileStats {
+                    size: std::fs::metadata(&file.path).ok().map(|m| m.len() as usize),
+                    line_count: Some(file.metrics.lines_of_code),
+                    complexity: Some(file.metrics.complexity),
+                    smells: None,
+                },
+            );
+            run_info
+                .function_metrics
+                .extend(file.metrics.function_metrics.iter().map(|(id, shape)| (id.clone(), *shape)));
+            run_info.comments.insert(file.path.display().to_string(), file.metrics.comments);
+            run_info
+                .documented
+                .extend(file.metrics.documented.iter().map(|(id, documented)| (id.clone(), *documented)));
+            run_info
+                .concurrency
+                .extend(file.metrics.concurrency.iter().map(|(id, profile)| (id.clone(), *profile)));
+        }
         
         if let Some(ref reporter) = progress {
-            reporter.report("Files parsed", parse_end);
+            reporter.report_message(&Message::FilesParsed, parse_end);
         }
 
         // 3. Build graph
         if let Some(ref reporter) = progress {
-            reporter.report("Building dependency graph", parse_end + 5.0);
+            reporter.report_message(&Message::BuildingGraph, parse_end + 5.0);
+        }
+        let stage_start = Instant::now();
+        let (mut graph, external_fan_out) = self.build_graph(parsed_files, self.external)?;
+        run_info.external_fan_out = external_fan_out;
+        run_info.overrides = graph.apply_overrides(&Overrides::load(&self.base_path));
+        for edge in &run_info.overrides.unmatched_edges {
+            tracing::warn!(
+                "Override {} -> {} ({}) matches nothing in the graph",
+                edge.source,
+                edge.target,
+                edge.edge_type
+            );
         }
-        let graph = self.build_graph(parsed_files)?;
+        self.check_architecture(&graph, &mut run_info);
+        run_info.record_stage("build_graph", stage_start.elapsed());
         info!("Built graph with {} nodes and {} edges", 
               graph.node_map.len(), 
               graph.graph.edge_count());
         
         if let Some(ref reporter) = progress {
-            reporter.report(&format!("Graph built: {} nodes, {} edges", 
-                graph.node_map.len(), graph.graph.edge_count()), graph_end);
+            reporter.report_message(&Message::GraphBuilt {
+                nodes: graph.node_map.len(),
+                edges: graph.graph.edge_count(),
+            }, graph_end);
         }
 
         // 4. Convert to frontend format
         if let Some(ref reporter) = progress {
-            reporter.report("Preparing visualization", viz_end);
+            reporter.report_message(&Message::PreparingVisualization, viz_end);
         }
+        let stage_start = Instant::now();
         let graph_data = graph.to_frontend_format();
+        run_info.record_stage("prepare_visualization", stage_start.elapsed());
 
         // Only mark complete if we're not doing metrics
         if !with_metrics {
             if let Some(ref reporter) = progress {
-                reporter.report("Complete", 100.0);
+                reporter.report_message(&Message::Complete, 100.0);
             }
         }
 
-        Ok(graph_data)
+        Ok((graph_data, run_info))
     }
 
     /// Analyze codebase with analytics
+    #[tracing::instrument(skip_all, fields(path = %self.base_path.display()))]
     pub async fn analyze_with_metrics(
         &self,
         progress: Option<Arc<dyn ProgressReporter>>,
     ) -> Result<AnalyzedGraph> {
         tracing::info!("[ENGINE] Starting analyze_with_metrics");
+        let started = Instant::now();
         
         // Get basic graph (this will go to 70%)
-        let graph_data = match self.analyze_codebase_internal(progress.clone(), true).await {
-            Ok(data) => data,
+        let (graph_data, mut run_info) = match self.analyze_codebase_internal(progress.clone(), true, &self.base_path).await {
+            Ok(result) => result,
             Err(e) => {
                 tracing::error!("Failed to analyze codebase: {}", e);
                 return Err(e);
             }
         };
+
+        // Run analytics sequentially to identify which metric fails
+        let config = AnalyticsConfig {
+            parallel: false,
+            weights: self.config.weights.clone(),
+            enabled_metrics: self.config.metrics.clone(),
+            normalization: self.config.normalization,
+            betweenness_seed: self.config.betweenness_seed.unwrap_or(DEFAULT_SAMPLING_SEED),
+            path_weighting: self.config.path_weighting.clone(),
+            modes: self.config.modes.clone(),
+            community_edge_types: self.config.community_edge_types.clone(),
+            drop_self_loops: self.config.drop_self_loops,
+            excluded_edge_types: self.config.excluded_edge_types.clone(),
+            ..AnalyticsConfig::default()
+        };
+        run_info.config = Some(RunConfig::new(&config, ANALYSIS_TIMEOUT));
+
+        let mut result = self.run_analytics(graph_data, config, run_info, progress).await?;
+        result.run_info.total_duration_ms = started.elapsed().as_millis() as u64;
+        result.performance.record_stages(&result.run_info);
+
+        Ok(result)
+    }
+
+    /// Run the analytics suite over already-built graph data
+    async fn run_analytics(
+        &self,
+        graph_data: GraphData,
+        mut config: AnalyticsConfig,
+        mut run_info: RunInfo,
+        progress: Option<Arc<dyn ProgressReporter>>,
+    ) -> Result<AnalyzedGraph> {
         
         // Check if we have any data to analyze
         if graph_data.nodes.is_empty() {
             tracing::warn!("No nodes found in graph data, returning empty analysis");
-            return Ok(AnalyzedGraph {
-                graph_data,
-                metrics: Vec::new(),
-                summary: AnalysisSummary {
-                    total_nodes: 0,
-                    total_edges: 0,
-                    num_communities: 0,
-                    modularity: 0.0,
-                    avg_complexity: 0.0,
-                    high_risk_count: 0,
-                    circular_dependencies: 0,
-                },
-            });
+            return Ok(AnalyzedGraph::without_metrics(graph_data, 0, 0, run_info));
         }
         
         // Build CodeGraph for analytics
         if let Some(ref reporter) = progress {
-            reporter.report("Preparing for analysis", 72.0);
+            reporter.report_message(&Message::PreparingAnalysis, 72.0);
         }
         
         let mut code_graph = CodeGraph::new();
@@ -158,6 +422,7 @@ impl Engine {
                     GraphEdge {
                         edge_type: link.link_type.clone(),
                         weight: link.value,
+                        count: link.count,
                     },
                 );
                 valid_edges += 1;
@@ -171,28 +436,14 @@ impl Engine {
         // Check if we have a valid graph
         if code_graph.graph.node_count() == 0 {
             tracing::warn!("Graph has no nodes, returning empty metrics");
-            return Ok(AnalyzedGraph {
-                graph_data,
-                metrics: Vec::new(),
-                summary: AnalysisSummary {
-                    total_nodes: 0,
-                    total_edges: 0,
-                    num_communities: 0,
-                    modularity: 0.0,
-                    avg_complexity: 0.0,
-                    high_risk_count: 0,
-                    circular_dependencies: 0,
-                },
-            });
+            return Ok(AnalyzedGraph::without_metrics(graph_data, 0, 0, run_info));
         }
 
         // Run analytics with detailed progress and error handling
         if let Some(ref reporter) = progress {
-            reporter.report("Starting analysis phase", 75.0);
+            reporter.report_message(&Message::StartingAnalysis, 75.0);
         }
         
-        let config = AnalyticsConfig::default();
-        
         // Log graph statistics before analysis
         tracing::info!("Starting analysis on graph with {} nodes and {} edges", 
                       code_graph.graph.node_count(), 
@@ -200,7 +451,7 @@ impl Engine {
         
         // Try to compute PageRank separately first to catch any issues
         if let Some(ref reporter) = progress {
-            reporter.report("Computing PageRank", 78.0);
+            reporter.report_message(&Message::ComputingPagerank, 78.0);
         }
         
         // Test PageRank calculation (clone for thread safety)
@@ -228,59 +479,57 @@ impl Engine {
         if !pagerank_works {
             tracing::error!("PageRank calculation would fail, skipping metrics");
             if let Some(ref reporter) = progress {
-                reporter.report("PageRank failed, skipping metrics", 85.0);
+                reporter.report_message(&Message::PagerankFailed, 85.0);
             }
             
             // Return graph without metrics if PageRank fails
-            return Ok(AnalyzedGraph {
-                graph_data,
-                metrics: Vec::new(),
-                summary: AnalysisSummary {
-                    total_nodes: code_graph.graph.node_count(),
-                    total_edges: code_graph.graph.edge_count(),
-                    num_communities: 0,
-                    modularity: 0.0,
-                    avg_complexity: 0.0,
-                    high_risk_count: 0,
-                    circular_dependencies: 0,
-                },
-            });
+            return Ok(AnalyzedGraph::without_metrics(graph_data, code_graph.graph.node_count(), code_graph.graph.edge_count(), run_info));
         }
         
         if let Some(ref reporter) = progress {
-            reporter.report("Running analysis suite", 80.0);
+            reporter.report_message(&Message::RunningAnalysisSuite, 80.0);
         }
         
         tracing::info!("[ENGINE] About to run analysis suite");
         
-        // Try to run analysis with comprehensive error handling
+        // Both engines score from the same parsed shapes and suppressions
+        config.suppressions = run_info.suppressions.clone();
+        config.function_metrics = run_info.function_metrics.clone();
+        let modular_config = AnalyticsConfigV2 {
+            betweenness_seed: config.betweenness_seed,
+            path_weighting: config.path_weighting.clone(),
+            community_edge_types: config.community_edge_types.clone(),
+            drop_self_loops: config.drop_self_loops,
+            excluded_edge_types: config.excluded_edge_types.clone(),
+            suppressions: config.suppressions.clone(),
+            function_metrics: config.function_metrics.clone(),
+            ..AnalyticsConfigV2::default()
+        };
+
+        // Try to run analysis with comprehensive error handling. Timed on its
+        // own so the stages after it do not count twice toward the total.
+        let stage_start = Instant::now();
         let analysis_result = {
-            // Create a custom config that runs sequentially for better debugging
-            let mut config = AnalyticsConfig::default();
-            config.parallel = false; // Run sequentially to identify which metric fails
-            
             tracing::info!("Attempting analysis with config: parallel={}, use_cache={}", 
                          config.parallel, config.use_cache);
             
             // Report progress during analysis
             if let Some(ref reporter) = progress {
-                reporter.report("Analyzing graph structure", 82.0);
+                reporter.report_message(&Message::AnalyzingStructure, 82.0);
             }
             
             // Try the analysis with timeout (simpler approach without spawning)
             let analysis_future = analyze_graph(&code_graph, Some(config));
-            let timeout_duration = std::time::Duration::from_secs(30); // Increased timeout
-            
             // Update progress while waiting
             if let Some(ref reporter) = progress {
-                reporter.report("Computing metrics", 85.0);
+                reporter.report_message(&Message::ComputingMetrics, 85.0);
             }
             
-            let timeout_result = tokio::time::timeout(timeout_duration, analysis_future).await;
+            let timeout_result = tokio::time::timeout(ANALYSIS_TIMEOUT, analysis_future).await;
             
             // Report near completion
             if let Some(ref reporter) = progress {
-                reporter.report("Finalizing analysis", 88.0);
+                reporter.report_message(&Message::FinalizingAnalysis, 88.0);
             }
             
             match timeout_result {
@@ -293,11 +542,12 @@ impl Engine {
                     Err(e)
                 },
                 Err(_) => {
-                    tracing::error!("[ENGINE] Analysis timed out after 30 seconds");
-                    Err(anyhow::anyhow!("Analysis timed out"))
+                    tracing::error!("[ENGINE] Analysis timed out after {} seconds", ANALYSIS_TIMEOUT.as_secs());
+                    Err(AnalysisTimedOut(ANALYSIS_TIMEOUT).into())
                 }
             }
         };
+        run_info.record_stage("analytics", stage_start.elapsed());
         
         // Run analysis with error handling and fallback
         let (analysis, metrics_available) = match analysis_result {
@@ -308,7 +558,7 @@ impl Engine {
             Err(e) => {
                 tracing::error!("Analysis failed: {}, returning graph without metrics", e);
                 if let Some(ref reporter) = progress {
-                    reporter.report("Analysis failed, continuing without metrics", 85.0);
+                    reporter.report_message(&Message::AnalysisFailed, 85.0);
                 }
                 (None, false)
             }
@@ -316,28 +566,17 @@ impl Engine {
         
         // If analysis failed, return graph without metrics
         if !metrics_available {
-            return Ok(AnalyzedGraph {
-                graph_data,
-                metrics: Vec::new(),
-                summary: AnalysisSummary {
-                    total_nodes: code_graph.graph.node_count(),
-                    total_edges: code_graph.graph.edge_count(),
-                    num_communities: 0,
-                    modularity: 0.0,
-                    avg_complexity: 0.0,
-                    high_risk_count: 0,
-                    circular_dependencies: 0,
-                },
-            });
+            return Ok(AnalyzedGraph::without_metrics(graph_data, code_graph.graph.node_count(), code_graph.graph.edge_count(), run_info));
         }
         
         let analysis = analysis.unwrap();
         
         if let Some(ref reporter) = progress {
-            reporter.report("Converting metrics for UI", 90.0);
+            reporter.report_message(&Message::ConvertingMetrics, 90.0);
         }
         
-        let ui_metrics = match std::panic::catch_unwind(|| {
+        let stage_start = Instant::now();
+        let mut ui_metrics = match std::panic::catch_unwind(|| {
             to_ui_metrics(&analysis, &code_graph)
         }) {
             Ok(metrics) => metrics,
@@ -348,9 +587,33 @@ impl Engine {
         };
         
         if let Some(ref reporter) = progress {
-            reporter.report("Finalizing metrics", 95.0);
+            reporter.report_message(&Message::FinalizingMetrics, 95.0);
+        }
+
+        let generated: HashSet<&str> = run_info.generated_files.iter().map(String::as_str).collect();
+        for metrics in &mut ui_metrics {
+            metrics.generated = generated.contains(metrics.path.as_str());
         }
 
+        let distributions = metric_distributions(&ui_metrics, DEFAULT_HISTOGRAM_BINS);
+        run_info.record_stage("serialize_metrics", stage_start.elapsed());
+
+        // Typed results of each metric family, next to the flattened UI metrics
+        let stage_start = Instant::now();
+        let results = match analyze_graph_modular(&code_graph, Some(modular_config)).await {
+            Ok(report) => Some(report),
+            Err(e) => {
+                tracing::warn!("Typed metric results unavailable: {}", e);
+                None
+            }
+        };
+        run_info.record_stage("metric_results", stage_start.elapsed());
+
+        // New cycles are those the last stored snapshot did not have
+        let cycles = dependency_cycles(&code_graph);
+        let previous_cycles = history::latest_snapshot(&self.base_path).and_then(|snapshot| snapshot.cycles);
+        let alerts = self.config.alerts.evaluate(&ui_metrics, &cycles, previous_cycles.as_deref());
+
         let result = AnalyzedGraph {
             graph_data,
             metrics: ui_metrics,
@@ -362,33 +625,71 @@ impl Engine {
                 avg_complexity: analysis.summary.avg_complexity,
                 high_risk_count: analysis.summary.high_risk_count,
                 circular_dependencies: analysis.summary.circular_dependencies,
+                normalization: Some(analysis.normalization_ranges.clone()),
+                distributions,
+                alerts,
             },
+            cycles,
+            results,
+            run_info,
+            performance: PerformanceReport::from_metric_timings(&analysis.timings),
         };
         
         if let Some(ref reporter) = progress {
-            reporter.report("Analysis complete", 100.0);
+            reporter.report_message(&Message::AnalysisComplete, 100.0);
         }
         
         Ok(result)
     }
 
     /// Discover files in the codebase
+    ///
+    /// Files matched by `.gitignore` or `.omnigraphignore` are skipped, with
+    /// `.omnigraphignore` taking precedence where the two disagree. Symlinks
+    /// are only followed when the config asks for it, and a file reached
+    /// through several symlinks or hardlinks is returned once.
     fn discover_files(&self, path: &Path) -> Result<Vec<PathBuf>> {
         use ignore::WalkBuilder;
         
         let mut files = Vec::new();
+        let mut seen = HashSet::new();
+        let filter = self.config.file_filter();
         let walker = WalkBuilder::new(path)
             .hidden(false)
             .git_ignore(true)
+            .add_custom_ignore_filename(IGNORE_FILE)
+            .follow_links(self.config.follow_symlinks)
+            // A stable order decides which of several links to a file is kept
+            .sort_by_file_name(|a, b| a.cmp(b))
             .build();
 
         for entry in walker {
-            let entry = entry?;
+            let entry = match entry {
+                Ok(entry) => entry,
+                // The walker does not descend into loops, and broken links have nothing to parse
+                Err(e) if is_skippable_walk_error(&e) => {
+                    tracing::warn!("Skipping during discovery: {}", e);
+                    continue;
+                }
+                Err(e) => return Err(e.into()),
+            };
             let path = entry.path();
+            if entry.path_is_symlink() && !self.config.follow_symlinks {
+                continue;
+            }
             
-            if path.is_file() && self.is_supported_file(path) {
-                tracing::debug!("Found supported file: {:?}", path);
-                files.push(path.to_path_buf());
+            let relative = path.strip_prefix(&self.base_path).unwrap_or(path);
+            if path.is_file() && filter.matches(relative) && self.is_supported_file(path) {
+                match file_identity(path) {
+                    Ok(identity) if !seen.insert(identity) => {
+                        tracing::debug!("Skipping {:?}, already found through another link", path);
+                    }
+                    Ok(_) => {
+                        tracing::debug!("Found supported file: {:?}", path);
+                        files.push(path.to_path_buf());
+                    }
+                    Err(e) => tracing::warn!("Skipping {:?}: {}", path, e),
+                }
             }
         }
         
@@ -397,88 +698,325 @@ impl Engine {
         Ok(files)
     }
 
-    /// Check if a file is supported
+    /// Check if a file is supported and its language is enabled
     fn is_supported_file(&self, path: &Path) -> bool {
-        let extensions = self.parser.supported_extensions();
-        if let Some(ext) = path.extension().and_then(|s| s.to_str()) {
-            // Extensions from parsers include the dot (e.g., ".js")
-            // but path.extension() returns without dot (e.g., "js")
-            let ext_with_dot = format!(".{}", ext);
-            extensions.contains(&ext_with_dot.as_str())
-        } else {
-            false
-        }
+        self.parser
+            .language_for(path)
+            .is_some_and(|language| self.config.language_enabled(language))
     }
 
-    /// Parse files in parallel
+    /// Parse files in parallel, honoring inline suppression comments
+    ///
+    /// Files marked `omnigraph-ignore-file` are skipped, and findings silenced
+    /// on the remaining nodes and their TODO-style markers are recorded in the
+    /// run info, with license headers and files when licenses are scanned.
+    /// Files over the
+    /// size cap or that look minified are handled as the limits say, and
+    /// findings in generated files are silenced unless the config analyzes them.
+    /// Files are read and parsed in batches, and `budget` shrinks them as soon
+    /// as they exceed the node and edge limits. Files `cache` has seen are
+    /// reparsed from their previous syntax trees.
+    #[tracing::instrument(skip_all, fields(files = files.len()))]
     fn parse_files(
         &self,
         files: Vec<PathBuf>,
+        cache: Option<&IncrementalParser>,
+        run_info: &mut RunInfo,
+        budget: &mut ParseBudget<'_>,
         progress: Option<Arc<dyn ProgressReporter>>,
     ) -> Result<Vec<ParsedFile>> {
-        let mut file_contents = Vec::new();
-        
         tracing::info!("Preparing to parse {} files", files.len());
-        
-        for path in &files {
-            let content = std::fs::read_to_string(path)?;
-            file_contents.push((path.display().to_string(), content));
-        }
 
-        let results = self.parser.parse_batch(file_contents, progress);
-        
-        // Collect successful parses
         let mut parsed = Vec::new();
-        for result in results {
-            match result {
-                Ok(file) => {
-                    tracing::debug!("Successfully parsed: {} with {} nodes and {} relationships", 
-                                   file.path.display(), 
-                                   file.nodes.len(), 
-                                   file.relationships.len());
-                    parsed.push(file);
-                },
-                Err(e) => {
-                    // Log error but continue
-                    tracing::warn!("Failed to parse file: {}", e);
+        let mut throughput: BTreeMap<String, og_parser::LanguageThroughput> = BTreeMap::new();
+        for (index, batch) in files.chunks(PARSE_BATCH_FILES).enumerate() {
+            let mut file_contents = Vec::new();
+            let mut directives = Vec::new();
+            for path in batch {
+                let SourceText { content, large } = self.limits.read_source(path)?;
+                if let Some(large) = large {
+                    let skipped = large.handling == LargeFileStrategy::Skip;
+                    run_info.large_files.push(large);
+                    if skipped {
+                        continue;
+                    }
+                }
+                let found = og_parser::suppressions::directives(&content);
+                if og_parser::suppressions::ignores_file(&found) {
+                    run_info.ignored_files.push(path.display().to_string());
+                    continue;
+                }
+                let generated = og_parser::generated::is_generated(path, &content);
+                if generated {
+                    run_info.generated_files.push(path.display().to_string());
+                }
+                if self.config.scan_licenses {
+                    run_info
+                        .license_headers
+                        .insert(path.display().to_string(), og_parser::license::header(&content));
+                }
+                directives.push((found, og_parser::debt::markers(&content), generated));
+                file_contents.push((path.display().to_string(), content));
+            }
+
+            let reporter = progress.clone().map(|inner| {
+                Arc::new(BatchProgress {
+                    inner,
+                    done: index * PARSE_BATCH_FILES,
+                    len: batch.len(),
+                    total: files.len(),
+                }) as Arc<dyn ProgressReporter>
+            });
+            let (results, batch_throughput) = match cache {
+                Some(cache) => self.parser.reparse_batch_with_throughput(file_contents, cache, reporter),
+                None => self.parser.parse_batch_with_throughput(file_contents, reporter),
+            };
+            for language in batch_throughput {
+                let entry = throughput.entry(language.language.clone()).or_default();
+                entry.language = language.language;
+                entry.files += language.files;
+                entry.bytes += language.bytes;
+                entry.busy_ms += language.busy_ms;
+            }
+
+            // Collect successful parses
+            let before = parsed.len();
+            for (result, (directives, markers, generated)) in results.into_iter().zip(directives) {
+                match result {
+                    Ok(file) => {
+                        tracing::debug!("Successfully parsed: {} with {} nodes and {} relationships", 
+                                       file.path.display(), 
+                                       file.nodes.len(), 
+                                       file.relationships.len());
+                        run_info
+                            .suppressions
+                            .extend(og_parser::suppressions::attach(&file, &directives));
+                        run_info.debt_markers.extend(og_parser::debt::attach(&file, markers));
+                        if generated && !self.config.analyze_generated {
+                            for node in &file.nodes {
+                                run_info.suppressions.insert(&node.id, ALL_FINDINGS);
+                            }
+                        }
+                        parsed.push(file);
+                    },
+                    Err(e) => {
+                        // Log error but continue
+                        tracing::warn!("Failed to parse file: {}", e);
+                    }
                 }
             }
+            let admitted = parsed.len() - before;
+            budget.admit(&mut parsed, admitted)?;
+        }
+
+        for language in throughput.values() {
+            tracing::info!(
+                "Parsed {} {} files ({} bytes) in {}ms of thread time, {:.0} files/s",
+                language.files,
+                language.language,
+                language.bytes,
+                language.busy_ms,
+                language.files_per_second()
+            );
         }
+        run_info.parse_throughput = throughput.into_values().collect();
         
         tracing::info!("Successfully parsed {} files", parsed.len());
+        if self.config.scan_licenses {
+            run_info.license_files = self.license_files(&files);
+        }
 
         Ok(parsed)
     }
 
+    /// License files in the folders of `files` and every folder above them
+    /// up to the codebase root
+    fn license_files(&self, files: &[PathBuf]) -> Vec<LicenseFile> {
+        let dirs: BTreeSet<&Path> = files
+            .iter()
+            .flat_map(|path| path.ancestors().skip(1))
+            .filter(|dir| dir.starts_with(&self.base_path))
+            .collect();
+        let mut found = Vec::new();
+        for dir in dirs {
+            let Ok(entries) = std::fs::read_dir(dir) else {
+                continue;
+            };
+            let mut paths: Vec<PathBuf> = entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| {
+                    path.is_file()
+                        && path
+                            .file_name()
+                            .is_some_and(|name| og_parser::license::is_license_file(&name.to_string_lossy()))
+                })
+                .collect();
+            paths.sort();
+            for path in paths {
+                let text = std::fs::read_to_string(&path).unwrap_or_default();
+                found.push(LicenseFile {
+                    path: path.display().to_string(),
+                    licenses: og_parser::license::identify(&text),
+                });
+            }
+        }
+        found
+    }
+
+    /// Resolve edges that cross files once every file is parsed
+    fn link_files(parsed_files: &mut [ParsedFile]) {
+        og_parser::frameworks::resolve_handlers(parsed_files);
+        og_parser::docker::link_directories(parsed_files);
+        og_parser::c::link_declarations(parsed_files);
+        og_parser::c::resolve_calls(parsed_files, true);
+        og_parser::python::resolve_imported_calls(parsed_files);
+        og_parser::graphql::link_resolvers(parsed_files);
+    }
+
+    /// The paths, relative to the codebase root, that an analysis would include
+    pub fn select_files(&self, relative_paths: Vec<String>) -> Vec<String> {
+        let filter = self.config.file_filter();
+        relative_paths
+            .into_iter()
+            .filter(|path| filter.matches(Path::new(path)) && self.is_supported_file(Path::new(path)))
+            .collect()
+    }
+
+    /// File graph of contents read from another revision, keyed by relative path
+    ///
+    /// Files marked `omnigraph-ignore-file` are left out, files that fail to
+    /// parse are skipped and the rest are shrunk to the analysis limits, as in
+    /// a full analysis.
+    pub fn file_graph_of(&self, files: Vec<(String, String)>) -> Result<CodeGraph> {
+        let files = files
+            .into_iter()
+            .filter(|(_, content)| {
+                !og_parser::suppressions::ignores_file(&og_parser::suppressions::directives(content))
+            })
+            .map(|(path, content)| (self.base_path.join(path).display().to_string(), content))
+            .collect();
+        let mut parsed_files: Vec<ParsedFile> = self
+            .parser
+            .parse_batch(files, None)
+            .into_iter()
+            .filter_map(Result::ok)
+            .collect();
+        Self::link_files(&mut parsed_files);
+        self.limits.enforce(&mut parsed_files)?;
+        let (graph, _) = self.build_graph(parsed_files, ExternalDependencies::Drop)?;
+        Ok(file_graph(&graph, &self.base_path.display().to_string()))
+    }
+
+    /// Record dependencies against the configured layers and files the entry points miss
+    fn check_architecture(&self, graph: &CodeGraph, run_info: &mut RunInfo) {
+        if !self.config.layers.is_empty() {
+            let files = graph.graph.node_weights().filter_map(|node| node.file_path.as_deref());
+            let layer_of = self.config.layers_of(&self.base_path, files);
+            run_info.layer_violations = graph.layer_violations(&layer_of, &self.config.allowed_layers());
+            for violation in &run_info.layer_violations {
+                tracing::warn!(
+                    "Layer {} may not depend on {}: {} -> {}",
+                    violation.source_layer,
+                    violation.target_layer,
+                    violation.source_file,
+                    violation.target_file
+                );
+            }
+        }
+        if !self.config.entry_points.is_empty() {
+            let files = graph.graph.node_weights().filter_map(|node| node.file_path.as_deref());
+            let entry_files = self.config.entry_files(&self.base_path, files);
+            if entry_files.is_empty() {
+                tracing::warn!("No file matches the configured entry points");
+            }
+            run_info.unreachable_files = graph.unreachable_files(&entry_files);
+        }
+    }
+
     /// Build graph from parsed files
-    fn build_graph(&self, parsed_files: Vec<ParsedFile>) -> Result<CodeGraph> {
-        let mut graph = CodeGraph::new();
+    ///
+    /// Imports of third-party packages are added as `external` asks, and each
+    /// file's external fan-out is returned either way.
+    fn build_graph(
+        &self,
+        parsed_files: Vec<ParsedFile>,
+        external: ExternalDependencies,
+    ) -> Result<(CodeGraph, Vec<ExternalFanOut>)> {
+        let mut graph = CodeGraph::with_parallel_edges(self.config.parallel_edges);
         
+        // Add every node before any edge so cross-file edges find their targets
+        let mut relationships = Vec::new();
+        let mut local_modules = HashSet::new();
         for file in parsed_files {
-            // Convert nodes
+            if matches!(file.language, Language::Python) {
+                local_modules.extend(self.python_module_names(&file.path));
+            }
+            // Convert nodes, skipping ids already emitted by another file
             for node in file.nodes {
-                let graph_node = GraphNode {
+                if graph.node_map.contains_key(&node.id) {
+                    continue;
+                }
+                let mut graph_node = GraphNode {
                     id: node.id.clone(),
                     name: node.name.clone(),
                     node_type: Self::convert_node_type(&node.node_type),
-                    size: 10.0, // Default size
+                    size: lines_of_code(&node),
+                    visual_size: 10.0, // Default size, set from connections for display
                     color: Self::get_node_color(&node.node_type),
                     file_path: Some(file.path.display().to_string()),
+                    attributes: Default::default(),
+                };
+                let lines = match node.node_type {
+                    NodeType::File if file.metrics.lines_of_code > 0 => file.metrics.lines_of_code,
+                    _ => node.end_line.saturating_sub(node.start_line) + 1,
                 };
+                graph_node.set_attribute(LOC_ATTRIBUTE, lines);
+                if node.start_line > 0 {
+                    graph_node.set_attribute(LINE_ATTRIBUTE, node.start_line);
+                }
+                let complexity = match node.node_type {
+                    NodeType::File => Some(file.metrics.complexity),
+                    _ => file.metrics.function_metrics.get(&node.id).map(|shape| shape.complexity),
+                };
+                if let Some(complexity) = complexity {
+                    graph_node.set_attribute(COMPLEXITY_ATTRIBUTE, complexity);
+                }
                 graph.add_node(graph_node);
             }
+            relationships.extend(file.relationships);
+        }
 
-            // Convert relationships to edges
-            for rel in file.relationships {
-                let edge = GraphEdge {
-                    edge_type: Self::convert_relationship_type(&rel.relationship_type),
-                    weight: 1.0,
-                };
-                graph.add_edge(&rel.source, &rel.target, edge);
+        // Convert relationships to edges
+        let mut external_imports = Vec::new();
+        for rel in relationships {
+            if !graph.node_map.contains_key(&rel.target) && matches!(rel.relationship_type, RelationshipType::Imports) {
+                external_imports.extend(ExternalImport::classify(&rel.source, &rel.target, &local_modules));
+                continue;
             }
+            let edge = GraphEdge {
+                edge_type: Self::convert_relationship_type(&rel.relationship_type),
+                weight: 1.0,
+                count: 1,
+            };
+            graph.add_edge(&rel.source, &rel.target, edge);
         }
+        let fan_out = graph.add_external_dependencies(&external_imports, external);
 
-        Ok(graph)
+        Ok((graph, fan_out))
+    }
+
+    /// Directory and module names along a Python file's path in the codebase,
+    /// which imports of the codebase's own packages start with
+    fn python_module_names(&self, path: &Path) -> Vec<String> {
+        let relative = path.strip_prefix(&self.base_path).unwrap_or(path);
+        let mut names: Vec<String> = relative
+            .parent()
+            .into_iter()
+            .flat_map(Path::components)
+            .map(|component| component.as_os_str().to_string_lossy().into_owned())
+            .collect();
+        names.extend(relative.file_stem().map(|stem| stem.to_string_lossy().into_owned()));
+        names
     }
 
     /// Convert NodeType from og-types to string
@@ -496,6 +1034,20 @@ impl Engine {
             NodeType::Property => "property",
             NodeType::TypeAlias => "type_alias",
             NodeType::Enum => "enum",
+            NodeType::Route => "route",
+            NodeType::Provider => "provider",
+            NodeType::Controller => "controller",
+            NodeType::Target => "target",
+            NodeType::Script => "script",
+            NodeType::Container => "container",
+            NodeType::Service => "service",
+            NodeType::SystemHeader => "system_header",
+            NodeType::EnvVar => "env_var",
+            NodeType::Table => "table",
+            NodeType::Model => "model",
+            NodeType::Event => "event",
+            NodeType::Style => "style",
+            NodeType::Operation => "operation",
         }.to_string()
     }
 
@@ -514,6 +1066,20 @@ impl Engine {
             NodeType::Property => "#F7DC6F",
             NodeType::TypeAlias => "#BB8FCE",
             NodeType::Enum => "#85C1E2",
+            NodeType::Route => "#E67E22",
+            NodeType::Provider => "#16A085",
+            NodeType::Controller => "#C0392B",
+            NodeType::Target => "#8E44AD",
+            NodeType::Script => "#2C3E50",
+            NodeType::Container => "#2496ED",
+            NodeType::Service => "#0DB7ED",
+            NodeType::SystemHeader => "#95A5A6",
+            NodeType::EnvVar => "#27AE60",
+            NodeType::Table => "#D35400",
+            NodeType::Model => "#E59866",
+            NodeType::Event => "#F1C40F",
+            NodeType::Style => "#E91E63",
+            NodeType::Operation => "#E535AB",
         }.to_string()
     }
 
@@ -527,16 +1093,100 @@ impl Engine {
             RelationshipType::Implements => "implements",
             RelationshipType::Contains => "contains",
             RelationshipType::References => "references",
+            RelationshipType::RoutesTo => "routes_to",
+            RelationshipType::Decorates => "decorates",
+            RelationshipType::DependsOn => "depends_on",
+            RelationshipType::DefinedIn => "defined_in",
+            RelationshipType::Reads => "reads",
+            RelationshipType::Writes => "writes",
+            RelationshipType::Publishes => "publishes",
+            RelationshipType::Subscribes => "subscribes",
+            RelationshipType::ResolvedBy => "resolved_by",
         }.to_string()
     }
 }
 
+/// Degree, PageRank and communities of `after`, carried over from `before`
+///
+/// Only the nodes and edges that differ are applied, so after a partial
+/// re-parse this is far cheaper than computing the metrics again. Without
+/// `previous` metrics of `before` they are computed first, which a full
+/// analysis avoids by seeding them with [`seed_live_metrics`].
+pub fn update_live_metrics(
+    previous: Option<IncrementalMetrics>,
+    before: &GraphData,
+    after: &GraphData,
+) -> IncrementalMetrics {
+    let started = Instant::now();
+    let mut graph = CodeGraph::from_graph_data(before);
+    let mut metrics = previous.unwrap_or_else(|| IncrementalMetrics::compute(&graph));
+    let changes = GraphChangeSet::between(&graph, &CodeGraph::from_graph_data(after));
+    let stats = metrics.update(&mut graph, &changes);
+    info!(
+        "Updated live metrics for {} added and {} removed nodes in {:?} ({} ranks updated, {} PageRank iterations, {} nodes relabelled)",
+        changes.added_nodes.len(),
+        changes.removed_nodes.len(),
+        started.elapsed(),
+        stats.pagerank_nodes_updated,
+        stats.pagerank_iterations,
+        stats.nodes_relabelled
+    );
+    metrics
+}
+
+/// Live metrics of a fully analyzed graph, for partial re-parses to update
+pub fn seed_live_metrics(graph_data: &GraphData) -> IncrementalMetrics {
+    let started = Instant::now();
+    let metrics = IncrementalMetrics::compute(&CodeGraph::from_graph_data(graph_data));
+    info!("Computed live metrics for {} nodes in {:?}", graph_data.nodes.len(), started.elapsed());
+    metrics
+}
+
 /// Graph data with analytics
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AnalyzedGraph {
     pub graph_data: GraphData,
     pub metrics: Vec<og_types::metrics::UINodeMetricsV1>,
     pub summary: AnalysisSummary,
+    /// Members of each dependency cycle, stored with the metric snapshot
+    #[serde(skip)]
+    pub cycles: Vec<Vec<String>>,
+    /// Centrality, community, risk and quality results as computed, when analytics ran
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub results: Option<ModularAnalysisReport>,
+    pub run_info: RunInfo,
+    pub performance: PerformanceReport,
+}
+
+impl AnalyzedGraph {
+    /// Graph result for runs where analytics were skipped or failed
+    pub fn without_metrics(
+        graph_data: GraphData,
+        total_nodes: usize,
+        total_edges: usize,
+        run_info: RunInfo,
+    ) -> Self {
+        Self {
+            graph_data,
+            metrics: Vec::new(),
+            summary: AnalysisSummary {
+                total_nodes,
+                total_edges,
+                num_communities: 0,
+                modularity: 0.0,
+                avg_complexity: 0.0,
+                high_risk_count: 0,
+                circular_dependencies: 0,
+                normalization: None,
+                distributions: Vec::new(),
+                alerts: Vec::new(),
+            },
+            cycles: Vec::new(),
+            results: None,
+            run_info,
+            performance: PerformanceReport::default(),
+        }
+    }
 }
 
 /// Analysis summary
@@ -549,4 +1199,113 @@ pub struct AnalysisSummary {
     pub avg_complexity: f64,
     pub high_risk_count: usize,
     pub circular_dependencies: usize,
-}
\ No newline at end of file
+    /// How node metrics were scaled, absent when analytics did not run
+    pub normalization: Option<NormalizationRanges>,
+    /// Histogram and quartiles of each node metric, empty without analytics
+    #[serde(default)]
+    pub distributions: Vec<MetricDistribution>,
+    /// Thresholds from the project config that this analysis crossed
+    #[serde(default)]
+    pub alerts: Vec<Alert>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_live_metrics_follow_a_partial_reparse() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("lib")).unwrap();
+        std::fs::write(
+            dir.path().join("main.ts"),
+            "import { helper } from './lib/util';\n\nexport function main() {\n    helper();\n}\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("lib/util.ts"), "export function helper() {}\n").unwrap();
+
+        let engine = Engine::new(dir.path().to_path_buf());
+        let (before, _) = engine.analyze_codebase(None).await.unwrap();
+        let metrics = seed_live_metrics(&before);
+
+        std::fs::write(
+            dir.path().join("lib/util.ts"),
+            "import { extra } from './extra';\n\nexport function helper() {\n    extra();\n}\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("lib/extra.ts"), "export function extra() {}\n").unwrap();
+        let (after, _) = engine.analyze_subtree(Path::new("lib"), &before, None).await.unwrap();
+
+        let live = update_live_metrics(Some(metrics), &before, &after);
+        let full = IncrementalMetrics::compute(&CodeGraph::from_graph_data(&after));
+        assert!(live.in_degree.keys().any(|id| id.contains("extra.ts")));
+        assert_eq!(live.in_degree, full.in_degree);
+        assert_eq!(live.out_degree, full.out_degree);
+        for (id, rank) in &full.pagerank {
+            assert!((live.pagerank[id] - rank).abs() < 1e-4, "rank mismatch for {}", id);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_repeated_partial_reparses_match_a_full_analysis() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("lib")).unwrap();
+        let store = dir.path().join("lib/store.py");
+        std::fs::write(&store, "def load(key):\n    return key\n\n\ndef save(key):\n    return key\n").unwrap();
+
+        let engine = Engine::new(dir.path().to_path_buf());
+        let (mut graph, _) = engine.analyze_codebase(None).await.unwrap();
+        for source in [
+            "def load(key):\n    return key\n\n\ndef save(key):\n    load(key)\n    return key\n",
+            "def load(key):\n    if key:\n        return key\n\n\ndef save(key):\n    load(key)\n    return key\n",
+        ] {
+            std::fs::write(&store, source).unwrap();
+            graph = engine.analyze_subtree(Path::new("lib"), &graph, None).await.unwrap().0;
+        }
+
+        let (full, _) = Engine::new(dir.path().to_path_buf()).analyze_codebase(None).await.unwrap();
+        let shape = |graph: &GraphData| {
+            let mut nodes: Vec<String> = graph
+                .nodes
+                .iter()
+                .map(|node| format!("{} {:?}", node.id, node.attributes.get(LINE_ATTRIBUTE)))
+                .collect();
+            let mut links: Vec<String> = graph
+                .links
+                .iter()
+                .map(|link| format!("{} -{}-> {}", link.source, link.link_type, link.target))
+                .collect();
+            nodes.sort();
+            links.sort();
+            (nodes, links)
+        };
+        assert_eq!(shape(&graph), shape(&full));
+        assert!(graph.links.iter().any(|link| link.source.ends_with(":save") && link.target.ends_with(":load")));
+    }
+
+    #[tokio::test]
+    async fn test_nodes_keep_their_start_line() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("main.ts"), "const x = 1;\n\nexport function main() {\n    return x;\n}\n").unwrap();
+
+        let (graph_data, _) = Engine::new(dir.path().to_path_buf()).analyze_codebase(None).await.unwrap();
+        let main = graph_data.nodes.iter().find(|node| node.name == "main").unwrap();
+        assert_eq!(main.number(LINE_ATTRIBUTE), Some(3.0));
+    }
+
+    #[tokio::test]
+    async fn test_stage_durations_do_not_overlap() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("main.ts"), "import { helper } from './util';\n\nhelper();\n").unwrap();
+        std::fs::write(dir.path().join("util.ts"), "export function helper() {}\n").unwrap();
+
+        let result = Engine::new(dir.path().to_path_buf()).analyze_with_metrics(None).await.unwrap();
+        let stages: Vec<&str> = result.run_info.stage_durations_ms.iter().map(|s| s.stage.as_str()).collect();
+        assert!(stages.contains(&"analytics") && stages.contains(&"serialize_metrics"), "{:?}", stages);
+
+        let summed: u64 = result.run_info.stage_durations_ms.iter().map(|s| s.duration_ms).sum();
+        assert!(summed <= result.run_info.total_duration_ms);
+        let shares: f64 = result.performance.stages.iter().map(|s| s.share).sum();
+        assert!(shares <= 1.0 + 1e-9, "stage shares sum to {}", shares);
+    }
+}
@@ -228,7 +228,7 @@ impl GraphStore {
         // TEMPORARY: Create some synthetic edges for demo purposes
         // This shows that edges work when they exist
         if edges.is_empty() && nodes.len() > 1 {
-            println!("[GRAPH_STORE] No import edges found, creating demo edges");
+            tracing::warn!("[GRAPH_STORE] No import edges found, creating demo edges");
             // Create edges between consecutive files for visualization
             for i in 0..nodes.len().min(5) {
                 if i + 1 < nodes.len() {
@@ -0,0 +1,163 @@
+use crate::engine_v2::AnalyzedGraph;
+use og_analytics::analysis::distribution::Extractor;
+use og_types::metrics::UINodeMetricsV1;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Directory under a codebase root that holds metric snapshots
+pub const SNAPSHOT_DIR: &str = ".omnigraph/snapshots";
+
+/// Snapshots kept per codebase before the oldest are removed
+pub const MAX_SNAPSHOTS: usize = 200;
+
+/// Node metrics of one analysis run, stored for trend tracking
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricSnapshot {
+    /// Unix timestamp in milliseconds
+    pub taken_at: u64,
+    pub git_commit: Option<String>,
+    pub metrics: Vec<UINodeMetricsV1>,
+    /// Members of each dependency cycle, None in snapshots taken before cycles were stored
+    #[serde(default)]
+    pub cycles: Option<Vec<Vec<String>>>,
+}
+
+/// Nodes a trend is computed over
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum TrendTarget {
+    /// A single node, matched by location, name and type
+    Node { path: String, name: String, node_type: String },
+    /// Every node under a directory, averaged
+    Package { path: String },
+}
+
+impl TrendTarget {
+    fn matches(&self, metrics: &UINodeMetricsV1) -> bool {
+        match self {
+            TrendTarget::Node { path, name, node_type } => {
+                metrics.path == *path && metrics.name == *name && metrics.node_type == *node_type
+            }
+            TrendTarget::Package { path } => Path::new(&metrics.path).starts_with(path),
+        }
+    }
+}
+
+/// Value of a metric in one snapshot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrendPoint {
+    pub taken_at: u64,
+    pub git_commit: Option<String>,
+    /// None when no matching node had the metric in this snapshot
+    pub value: Option<f64>,
+    /// Matching nodes the value was computed from
+    pub nodes: usize,
+}
+
+/// A metric's values across stored snapshots, oldest first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricTrend {
+    pub metric: String,
+    pub target: TrendTarget,
+    pub points: Vec<TrendPoint>,
+}
+
+impl MetricSnapshot {
+    /// Snapshot of an analysis, None if it produced no metrics
+    pub fn from_analysis(analyzed: &AnalyzedGraph) -> Option<Self> {
+        if analyzed.metrics.is_empty() {
+            return None;
+        }
+        Some(Self {
+            taken_at: analyzed.run_info.started_at,
+            git_commit: analyzed.run_info.git_commit.clone(),
+            metrics: analyzed.metrics.clone(),
+            cycles: Some(analyzed.cycles.clone()),
+        })
+    }
+
+    /// Write the snapshot into a codebase's snapshot directory, pruning old ones
+    pub fn save(&self, base_path: &Path) -> std::io::Result<PathBuf> {
+        let dir = base_path.join(SNAPSHOT_DIR);
+        std::fs::create_dir_all(&dir)?;
+        // Zero-padded names sort chronologically
+        let path = dir.join(format!("{:016}.json", self.taken_at));
+        let json = serde_json::to_vec(self).map_err(std::io::Error::other)?;
+        std::fs::write(&path, json)?;
+
+        let files = snapshot_files(&dir)?;
+        for old in files.iter().take(files.len().saturating_sub(MAX_SNAPSHOTS)) {
+            std::fs::remove_file(old)?;
+        }
+        Ok(path)
+    }
+}
+
+fn snapshot_files(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Most recent stored snapshot of a codebase, if it is readable
+pub fn latest_snapshot(base_path: &Path) -> Option<MetricSnapshot> {
+    let path = snapshot_files(&base_path.join(SNAPSHOT_DIR)).ok()?.pop()?;
+    let bytes = std::fs::read(&path).ok()?;
+    serde_json::from_slice(&bytes)
+        .inspect_err(|e| tracing::warn!("Skipping snapshot {}: {}", path.display(), e))
+        .ok()
+}
+
+/// Stored snapshots of a codebase, oldest first, skipping unreadable files
+pub fn load_snapshots(base_path: &Path) -> Vec<MetricSnapshot> {
+    let Ok(files) = snapshot_files(&base_path.join(SNAPSHOT_DIR)) else {
+        return Vec::new();
+    };
+    let mut snapshots: Vec<MetricSnapshot> = files
+        .iter()
+        .filter_map(|path| {
+            let parsed = std::fs::read(path)
+                .map_err(|e| e.to_string())
+                .and_then(|bytes| serde_json::from_slice(&bytes).map_err(|e| e.to_string()));
+            parsed
+                .inspect_err(|e| tracing::warn!("Skipping snapshot {}: {}", path.display(), e))
+                .ok()
+        })
+        .collect();
+    snapshots.sort_by_key(|s| s.taken_at);
+    snapshots
+}
+
+/// A metric's value for a target in each snapshot, averaged over packages
+pub fn metric_trend(snapshots: &[MetricSnapshot], metric: &str, extract: Extractor, target: TrendTarget) -> MetricTrend {
+    let points = snapshots
+        .iter()
+        .map(|snapshot| {
+            let values: Vec<f64> = snapshot
+                .metrics
+                .iter()
+                .filter(|m| target.matches(m))
+                .filter_map(extract)
+                .filter(|v| v.is_finite())
+                .collect();
+            TrendPoint {
+                taken_at: snapshot.taken_at,
+                git_commit: snapshot.git_commit.clone(),
+                value: (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64),
+                nodes: values.len(),
+            }
+        })
+        .collect();
+
+    MetricTrend {
+        metric: metric.to_string(),
+        target,
+        points,
+    }
+}
@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// First line after the shebang of every hook this module writes
+const MARKER: &str = "# Installed by omnigraph";
+
+/// Git hooks that can run the omnigraph check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum GitHook {
+    PreCommit,
+    PrePush,
+}
+
+impl GitHook {
+    fn file_name(self) -> &'static str {
+        match self {
+            GitHook::PreCommit => "pre-commit",
+            GitHook::PrePush => "pre-push",
+        }
+    }
+
+    /// Flag telling the check which changes the hook guards
+    fn changes_flag(self) -> &'static str {
+        match self {
+            GitHook::PreCommit => "--staged",
+            GitHook::PrePush => "--pushed",
+        }
+    }
+}
+
+/// Hooks directory of the repository containing `path`, honoring `core.hooksPath`
+fn hooks_dir(path: &Path) -> Result<PathBuf, String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--path-format=absolute", "--git-path", "hooks"])
+        .current_dir(path)
+        .output()
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("Not a git repository: {}", path.display()));
+    }
+    Ok(PathBuf::from(String::from_utf8_lossy(&output.stdout).trim()))
+}
+
+/// `path` as a single-quoted shell word, which the shell takes literally
+fn shell_quote(path: &Path) -> String {
+    format!("'{}'", path.display().to_string().replace('\'', "'\\''"))
+}
+
+/// Shell script that runs the check against the codebase at `path`
+fn script(executable: &Path, path: &Path, hook: GitHook) -> String {
+    format!(
+        "#!/bin/sh\n{}\n# Blocks changes that add dependency cycles or god objects; bypass with --no-verify\nexec {} check --path {} {}\n",
+        MARKER,
+        shell_quote(executable),
+        shell_quote(path),
+        hook.changes_flag()
+    )
+}
+
+/// Install a git hook that runs the omnigraph check, returning its path
+///
+/// An existing hook that omnigraph did not write is only replaced when
+/// `overwrite` is set.
+pub fn install(path: &Path, hook: GitHook, overwrite: bool) -> Result<PathBuf, String> {
+    let path = path
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve {}: {}", path.display(), e))?;
+    let dir = hooks_dir(&path)?;
+    let hook_path = dir.join(hook.file_name());
+
+    if let Ok(existing) = std::fs::read_to_string(&hook_path) {
+        if !overwrite && !existing.contains(MARKER) {
+            return Err(format!("{} already exists; pass overwrite to replace it", hook_path.display()));
+        }
+    }
+
+    let executable = std::env::current_exe().map_err(|e| format!("Failed to locate omnigraph: {}", e))?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    std::fs::write(&hook_path, script(&executable, &path, hook))
+        .map_err(|e| format!("Failed to write {}: {}", hook_path.display(), e))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&hook_path, std::fs::Permissions::from_mode(0o755))
+            .map_err(|e| format!("Failed to make {} executable: {}", hook_path.display(), e))?;
+    }
+
+    Ok(hook_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_script_quotes_paths_literally() {
+        let executable = Path::new("/opt/omni graph/omnigraph");
+        let path = Path::new("/src/it's \"$HOME\" `id`");
+        let script = script(executable, path, GitHook::PreCommit);
+        assert!(script.contains(
+            "exec '/opt/omni graph/omnigraph' check --path '/src/it'\\''s \"$HOME\" `id`' --staged\n"
+        ));
+
+        // The shell hands the paths back unchanged
+        let line = script.lines().last().unwrap().replacen("exec ", "printf '%s\\n' ", 1);
+        let output = Command::new("sh").arg("-c").arg(&line).output().unwrap();
+        let words: Vec<_> = String::from_utf8(output.stdout).unwrap().lines().map(str::to_string).collect();
+        assert_eq!(words, vec![
+            "/opt/omni graph/omnigraph".to_string(),
+            "check".to_string(),
+            "--path".to_string(),
+            path.display().to_string(),
+            "--staged".to_string(),
+        ]);
+    }
+}
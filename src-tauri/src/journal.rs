@@ -0,0 +1,146 @@
+use crate::triage::TriageEntry;
+use og_graph::overrides::Overrides;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Operations kept for undo before the oldest are forgotten
+pub const MAX_JOURNAL_ENTRIES: usize = 100;
+
+/// A change made through a command, with what it replaced so it can be reverted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum Operation {
+    /// A finding's triage entry changed; None is an open finding
+    FindingState {
+        base_path: PathBuf,
+        fingerprint: String,
+        before: Option<TriageEntry>,
+        after: Option<TriageEntry>,
+    },
+    /// The manual graph overrides of a codebase were replaced
+    Overrides {
+        base_path: PathBuf,
+        before: Overrides,
+        after: Overrides,
+    },
+}
+
+impl Operation {
+    /// The operation that reverts this one
+    pub fn inverse(&self) -> Self {
+        match self {
+            Operation::FindingState {
+                base_path,
+                fingerprint,
+                before,
+                after,
+            } => Operation::FindingState {
+                base_path: base_path.clone(),
+                fingerprint: fingerprint.clone(),
+                before: after.clone(),
+                after: before.clone(),
+            },
+            Operation::Overrides { base_path, before, after } => Operation::Overrides {
+                base_path: base_path.clone(),
+                before: after.clone(),
+                after: before.clone(),
+            },
+        }
+    }
+
+    /// Short description for undo and redo menus
+    pub fn label(&self) -> String {
+        match self {
+            Operation::FindingState { after, .. } => match after {
+                Some(entry) => format!("Set finding to {:?}", entry.state),
+                None => "Reopen finding".to_string(),
+            },
+            Operation::Overrides { .. } => "Edit graph overrides".to_string(),
+        }
+    }
+}
+
+/// A recorded operation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalEntry {
+    pub id: u64,
+    pub label: String,
+    /// Unix timestamp in milliseconds
+    pub recorded_at: u64,
+    pub operation: Operation,
+}
+
+/// What undo and redo would revert or replay, most recent first
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalSummary {
+    pub undo: Vec<String>,
+    pub redo: Vec<String>,
+}
+
+/// Undo and redo stacks of recorded operations
+///
+/// Recording a new operation discards the redo stack, as editors do.
+#[derive(Debug, Default)]
+pub struct Journal {
+    undo: Vec<JournalEntry>,
+    redo: Vec<JournalEntry>,
+    next_id: u64,
+}
+
+impl Journal {
+    pub fn record(&mut self, operation: Operation) {
+        self.next_id += 1;
+        self.undo.push(JournalEntry {
+            id: self.next_id,
+            label: operation.label(),
+            recorded_at: SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_millis() as u64),
+            operation,
+        });
+        let excess = self.undo.len().saturating_sub(MAX_JOURNAL_ENTRIES);
+        self.undo.drain(..excess);
+        self.redo.clear();
+    }
+
+    /// Take the latest operation to undo; pass it to `undone` once reverted
+    /// or back to `restore_undo` if reverting failed
+    pub fn pop_undo(&mut self) -> Option<JournalEntry> {
+        self.undo.pop()
+    }
+
+    pub fn undone(&mut self, entry: JournalEntry) {
+        self.redo.push(entry);
+    }
+
+    pub fn restore_undo(&mut self, entry: JournalEntry) {
+        self.undo.push(entry);
+    }
+
+    /// Take the latest undone operation; pass it to `redone` once replayed
+    /// or back to `restore_redo` if replaying failed
+    pub fn pop_redo(&mut self) -> Option<JournalEntry> {
+        self.redo.pop()
+    }
+
+    pub fn redone(&mut self, entry: JournalEntry) {
+        self.undo.push(entry);
+    }
+
+    pub fn restore_redo(&mut self, entry: JournalEntry) {
+        self.redo.push(entry);
+    }
+
+    pub fn summary(&self) -> JournalSummary {
+        JournalSummary {
+            undo: self.undo.iter().rev().map(|entry| entry.label.clone()).collect(),
+            redo: self.redo.iter().rev().map(|entry| entry.label.clone()).collect(),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
@@ -1,17 +1,86 @@
+mod cli;
+mod editor;
 mod engine_v2;
 mod lod;
 mod graph_store;
+mod hooks;
+mod journal;
+mod history;
+mod limits;
+mod logging;
+mod performance;
+mod project_config;
+mod revision;
+mod run_info;
+mod scheduler;
+mod timeline;
+mod triage;
 
-use engine_v2::{Engine, AnalyzedGraph, AnalysisSummary};
+pub use cli::run_cli;
+use editor::EditorSettings;
+use engine_v2::{Engine, AnalyzedGraph, AnalysisTimedOut};
 use lod::{LodLevel, GraphPayload, GraphDelta};
 use graph_store::GraphStore;
-use og_graph::graph::GraphData;
-use og_utils::ProgressReporter;
+use hooks::GitHook;
+use history::{MetricSnapshot, MetricTrend, TrendTarget};
+use journal::{Journal, JournalEntry, JournalSummary, Operation};
+use limits::{AnalysisLimits, LimitExceeded};
+use project_config::{ConfigValidation, ProjectConfig};
+use revision::RevisionAnalysis;
+use run_info::RunInfo;
+use triage::{FindingState, TriageStore, TriagedFinding};
+use scheduler::{ScheduleSettings, ScheduleStatus, Scheduler, ANALYSIS_ALERT_EVENT};
+use timeline::TimelineSettings;
+use og_analytics::analysis::evolution::EvolutionTimeline;
+use og_analytics::analysis::distribution::{metric_extractor, metric_names, metric_values, NodeMetricValues};
+use og_analytics::analysis::pr_summary::DEFAULT_PR_FILES;
+use og_analytics::analysis::export::{ArchitectureReport, ReportFormat, DEFAULT_TOP_N};
+use og_analytics::analysis::coloring::{self, ColorMap, ColorScale, Palette};
+use og_analytics::analysis::findings::{self, smell_counts, FindingFilter, FindingSort};
+use og_analytics::analysis::node_detail::NodeMetricsDetail;
+use og_analytics::analysis::node_table::{self, NodeFilter, NodePage, SortOrder};
+use og_analytics::analysis::documentation::DocumentationReport;
+use og_analytics::analysis::concurrency::ConcurrencyMap;
+use og_analytics::analysis::debt::DebtReport;
+use og_analytics::analysis::license::LicenseInventory;
+use og_analytics::analysis::query::{GraphQuery, QueryResult};
+use og_analytics::analysis::similarity::{self, SimilarNode, DEFAULT_SIMILAR_K};
+use og_analytics::analysis::affinity::{module_affinity, NodeAffinity, DEFAULT_AFFINITY_K};
+use og_analytics::analysis::sizing::{self, NodeSizes, SizeMetric, SizeScale};
+use og_analytics::analysis::structural_diff::StructuralDiff;
+use og_analytics::analysis::table::{MetricsTable, TableFormat};
+use og_analytics::analysis::treemap::{build_treemap, Treemap, TreemapMetric};
+use og_analytics::analysis::refactoring::{extract_module_candidates, RefactoringSuggestion, DEFAULT_MIN_MEMBERS};
+use og_db::store::GraphStore as PersistentStore;
+use og_db::{
+    CypherResult, MemoryStore, Namespace, NamespaceInfo, Neo4jClient, Neo4jStore, SqliteStore, StoreBackend,
+    SyncReport,
+};
+use og_types::error::{CommandError, ErrorCode};
+use og_types::{Message, DEBT_KINDS};
+use og_graph::attributes::LINE_ATTRIBUTE;
+use og_graph::file_tree::{build_file_tree, FileNode};
+use og_graph::graph::{CodeGraph, GraphData, GraphLink, GraphNode};
+use og_graph::incremental::IncrementalMetrics;
+use og_graph::bundling::EdgeMode;
+use og_graph::paging::GraphPage;
+use og_graph::plantuml::DEFAULT_DIAGRAM_DEPTH;
+use og_graph::routes::RouteEndpoint;
+use og_graph::env::EnvVarUsage;
+use og_graph::slice::SliceDirection;
+use og_graph::overrides::Overrides;
+use og_graph::external::ExternalDependencies;
+use og_graph::components::{ComponentReport, DEFAULT_ORPHAN_MAX_SIZE};
+use og_graph::condensation::CondensedGraph;
+use og_graph::stats::StructuralStats;
+use og_graph::edge_stats::{EdgeStats, DEFAULT_HEAVY_EDGES};
+use og_graph::viewport::{ViewportGraph, ViewportRequest};
+use og_utils::{LogBuffer, LogEntry, ProgressReporter};
 use serde::{Deserialize, Serialize};
 use std::path::{PathBuf, Path};
 use std::sync::{Arc, Mutex};
-use std::collections::{HashMap, HashSet};
-use tauri::Emitter;
+use std::collections::HashMap;
+use tauri::{Emitter, Manager};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ParseResult {
@@ -22,24 +91,15 @@ pub struct ParseResult {
     files: Vec<FileNode>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct FileNode {
-    name: String,
-    path: String,
-    #[serde(rename = "type")]
-    node_type: String,
-    children: Option<Vec<FileNode>>,
-    size: Option<usize>,
-    extension: Option<String>,
-    line_count: Option<usize>,
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProgressUpdate {
     current: usize,
     total: usize,
     percentage: f32,
+    /// English text of the step, for frontends without a translation
     message: String,
+    /// The step as a code and params the frontend can translate
+    localized: Message,
 }
 
 // Progress reporter implementation for Tauri
@@ -50,8 +110,12 @@ struct TauriProgressReporter {
 
 impl ProgressReporter for TauriProgressReporter {
     fn report(&self, message: &str, percentage: f32) {
+        self.report_message(&Message::from(message), percentage);
+    }
+
+    fn report_message(&self, message: &Message, percentage: f32) {
         // Add logging to track progress reports
-        println!("[PROGRESS] {}% - {}", percentage, message);
+        tracing::debug!("[PROGRESS] {}% - {}", percentage, message);
         
         // Wrap in panic catcher to prevent crashes in progress reporting
         let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
@@ -61,11 +125,12 @@ impl ProgressReporter for TauriProgressReporter {
                 total: self.total,
                 percentage,
                 message: message.to_string(),
+                localized: message.clone(),
             }).ok();
         }));
         
         if let Err(e) = result {
-            println!("[PROGRESS] ERROR: Failed to emit progress: {:?}", e);
+            tracing::error!("[PROGRESS] ERROR: Failed to emit progress: {:?}", e);
         }
     }
     
@@ -83,261 +148,37 @@ impl ProgressReporter for TauriProgressReporter {
     }
 }
 
-// Helper function to build file tree from graph data
-fn build_file_tree(graph_data: &GraphData) -> Vec<FileNode> {
-    println!("Building file tree from {} nodes", graph_data.nodes.len());
-    
-    // Collect all unique file paths
-    let mut file_paths: HashSet<String> = HashSet::new();
-    for node in &graph_data.nodes {
-        if node.node_type == "file" {
-            if let Some(file_path) = &node.file_path {
-                file_paths.insert(file_path.clone());
-            }
-        }
-    }
-    
-    if file_paths.is_empty() {
-        println!("No file nodes found in graph data");
-        return Vec::new();
-    }
-    
-    println!("Found {} unique file paths", file_paths.len());
-    
-    // Find the common base path
-    let base_path = find_common_base_path(&file_paths);
-    println!("Base path: {:?}", base_path);
-    
-    // Build the tree structure
-    let mut tree_map: HashMap<String, FileNode> = HashMap::new();
-    let mut children_map: HashMap<String, Vec<String>> = HashMap::new();
-    
-    // Process each file
-    for file_path in &file_paths {
-        let full_path = Path::new(file_path);
-        let relative_path = if let Some(base) = &base_path {
-            full_path.strip_prefix(base).unwrap_or(full_path)
-        } else {
-            full_path
-        };
-        
-        // Add the file node
-        let file_name = full_path.file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("unknown")
-            .to_string();
-        
-        let extension = full_path.extension()
-            .and_then(|e| e.to_str())
-            .map(|s| s.to_string());
-        
-        let relative_path_str = relative_path.to_str().unwrap_or("").to_string();
-        
-        tree_map.insert(relative_path_str.clone(), FileNode {
-            name: file_name,
-            path: file_path.clone(), // Keep full path for opening files
-            node_type: "file".to_string(),
-            children: None,
-            size: None,
-            extension,
-            line_count: None,
-        });
-        
-        // Create parent directories
-        let mut current = relative_path;
-        while let Some(parent) = current.parent() {
-            if parent.as_os_str().is_empty() {
-                // Reached root
-                children_map.entry(String::new()).or_insert_with(Vec::new).push(relative_path_str.clone());
-                break;
-            }
-            
-            let parent_str = parent.to_str().unwrap_or("").to_string();
-            let current_str = current.to_str().unwrap_or("").to_string();
-            
-            // Add to children map
-            children_map.entry(parent_str.clone()).or_insert_with(Vec::new).push(current_str.clone());
-            
-            // Create directory node if it doesn't exist
-            if !tree_map.contains_key(&parent_str) {
-                let dir_name = parent.file_name()
-                    .and_then(|n| n.to_str())
-                    .unwrap_or(parent.to_str().unwrap_or(""))
-                    .to_string();
-                
-                let full_dir_path = if let Some(base) = &base_path {
-                    base.join(parent).to_str().unwrap_or("").to_string()
-                } else {
-                    parent.to_str().unwrap_or("").to_string()
-                };
-                
-                tree_map.insert(parent_str.clone(), FileNode {
-                    name: dir_name,
-                    path: full_dir_path,
-                    node_type: "folder".to_string(),
-                    children: Some(Vec::new()),
-                    size: None,
-                    extension: None,
-                    line_count: None,
-                });
-            }
-            
-            current = parent;
-        }
-    }
-    
-    println!("Created {} nodes in tree", tree_map.len());
-    println!("Children map has {} entries", children_map.len());
-    
-    // Build parent-child relationships
-    for (parent_path, child_paths) in &children_map {
-        // Remove duplicates from child_paths
-        let unique_children: HashSet<&String> = child_paths.iter().collect();
-        
-        if parent_path.is_empty() {
-            // These are root items, we'll collect them later
-            continue;
-        }
-        
-        // Collect child nodes first to avoid borrow issues
-        let child_nodes: Vec<FileNode> = unique_children
-            .iter()
-            .filter_map(|path| tree_map.get(*path).cloned())
-            .collect();
-        
-        if let Some(parent_node) = tree_map.get_mut(parent_path) {
-            if parent_node.children.is_none() {
-                parent_node.children = Some(Vec::new());
-            }
-            
-            if let Some(ref mut children) = parent_node.children {
-                for child_node in child_nodes {
-                    // Avoid duplicates
-                    if !children.iter().any(|c| c.name == child_node.name) {
-                        children.push(child_node);
-                    }
-                }
-                // Sort children by name
-                children.sort_by(|a, b| {
-                    // Folders first, then files
-                    match (a.node_type.as_str(), b.node_type.as_str()) {
-                        ("folder", "file") => std::cmp::Ordering::Less,
-                        ("file", "folder") => std::cmp::Ordering::Greater,
-                        _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
-                    }
-                });
-            }
-        }
-    }
-    
-    // Find root nodes
-    let mut root_nodes = Vec::new();
-    
-    // Get root items from children_map (items with empty parent)
-    if let Some(root_children) = children_map.get("") {
-        for child_path in root_children {
-            if let Some(node) = tree_map.get(child_path) {
-                root_nodes.push(node.clone());
-            }
-        }
-    }
-    
-    // If no root nodes found, find the top-level directories
-    if root_nodes.is_empty() {
-        println!("No root nodes found in children_map, finding top-level items");
-        
-        // Find all nodes that are not children of any other node
-        let all_children: HashSet<&String> = children_map.values()
-            .flat_map(|v| v.iter())
-            .collect();
-        
-        for (path, node) in &tree_map {
-            if !all_children.contains(path) && !path.is_empty() {
-                root_nodes.push(node.clone());
-            }
-        }
-    }
-    
-    // If still no root nodes, just show the project name with all files
-    if root_nodes.is_empty() && !tree_map.is_empty() {
-        println!("Creating synthetic root node");
-        
-        // Get project name from base path
-        let project_name = if let Some(base) = &base_path {
-            base.file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("Project")
-                .to_string()
-        } else {
-            "Project".to_string()
-        };
-        
-        let mut all_items: Vec<FileNode> = tree_map.values().cloned().collect();
-        all_items.sort_by(|a, b| {
-            // Folders first, then files
-            match (a.node_type.as_str(), b.node_type.as_str()) {
-                ("folder", "file") => std::cmp::Ordering::Less,
-                ("file", "folder") => std::cmp::Ordering::Greater,
-                _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
-            }
-        });
-        
-        root_nodes.push(FileNode {
-            name: project_name,
-            path: base_path.as_ref().map(|p| p.to_str().unwrap_or("").to_string()).unwrap_or_default(),
-            node_type: "folder".to_string(),
-            children: Some(all_items),
-            size: None,
-            extension: None,
-            line_count: None,
-        });
-    } else {
-        // Sort root nodes
-        root_nodes.sort_by(|a, b| {
-            // Folders first, then files
-            match (a.node_type.as_str(), b.node_type.as_str()) {
-                ("folder", "file") => std::cmp::Ordering::Less,
-                ("file", "folder") => std::cmp::Ordering::Greater,
-                _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
-            }
-        });
-    }
-    
-    println!("Returning {} root nodes", root_nodes.len());
-    for (i, node) in root_nodes.iter().take(3).enumerate() {
-        println!("  Root {}: {} ({})", i, node.name, node.node_type);
-    }
-    
-    root_nodes
-}
-
-// Helper function to find common base path
-fn find_common_base_path(paths: &HashSet<String>) -> Option<PathBuf> {
-    if paths.is_empty() {
-        return None;
-    }
-    
-    let mut path_iter = paths.iter();
-    let first_path = Path::new(path_iter.next().unwrap());
-    
-    let mut common = first_path.parent()?.to_path_buf();
-    
-    for path_str in path_iter {
-        let path = Path::new(path_str);
-        while !path.starts_with(&common) {
-            common = common.parent()?.to_path_buf();
-        }
-    }
-    
-    Some(common)
-}
-
 // Global engine instance
 struct AppState {
     engine: Option<Engine>,
     current_graph: Option<GraphData>,
     analyzed_graph: Option<AnalyzedGraph>,
+    run_info: Option<RunInfo>,
+    limits: AnalysisLimits,
+    /// Extra C include directories for new analyses
+    include_dirs: Vec<PathBuf>,
+    /// How new analyses show third-party imports, unless a project config says otherwise
+    external_dependencies: ExternalDependencies,
+    /// Editor that nodes are opened in
+    editor: EditorSettings,
+    /// PageRank of the current graph, computed on first viewport request
+    viewport_ranks: Option<Arc<HashMap<String, f64>>>,
+    /// Degree, PageRank and communities of the current graph, kept up to date
+    /// across partial re-parses
+    live_metrics: Option<IncrementalMetrics>,
     lod_store: Arc<GraphStore>,
+    /// Background re-analysis, if scheduled
+    scheduler: Option<Scheduler>,
+    /// Neo4j database that Cypher queries run against, once connected
+    neo4j: Option<Arc<Neo4jClient>>,
+    /// Where `save_graph` persists graphs
+    persistent_store: Arc<dyn PersistentStore>,
+    /// Graph last written to `persistent_store`, which syncs are diffed against
+    synced_graph: Option<GraphData>,
+    /// Most recent syncs, oldest first
+    sync_log: Vec<SyncReport>,
+    /// Changes that undo and redo revert and replay
+    journal: Journal,
 }
 
 impl Default for AppState {
@@ -346,26 +187,102 @@ impl Default for AppState {
             engine: None,
             current_graph: None,
             analyzed_graph: None,
+            run_info: None,
+            limits: AnalysisLimits::default(),
+            include_dirs: Vec::new(),
+            external_dependencies: ExternalDependencies::default(),
+            editor: EditorSettings::default(),
+            viewport_ranks: None,
+            live_metrics: None,
             lod_store: Arc::new(GraphStore::new()),
+            scheduler: None,
+            neo4j: None,
+            persistent_store: Arc::new(MemoryStore::default()),
+            synced_graph: None,
+            sync_log: Vec::new(),
+            journal: Journal::default(),
         }
     }
 }
 
-// Parse codebase command
+// Syncs kept in the sync log
+const SYNC_LOG_LIMIT: usize = 100;
+
+// Error returned when a command needs a graph but none is loaded
+fn no_graph_error() -> CommandError {
+    CommandError::from_message(ErrorCode::InvalidState, Message::NoGraph)
+}
+
+// Map an engine failure to a command error, keeping the cause as details
+fn engine_failure(context: &str, error: anyhow::Error) -> CommandError {
+    if let Some(exceeded) = error.downcast_ref::<LimitExceeded>() {
+        return CommandError::invalid_input(format!("{}: {}", context, exceeded))
+            .with_details(exceeded.suggestions.join("\n"));
+    }
+
+    let code = if error.downcast_ref::<std::io::Error>().is_some()
+        || error.downcast_ref::<ignore::Error>().is_some()
+    {
+        ErrorCode::Io
+    } else if error.is::<AnalysisTimedOut>() {
+        ErrorCode::AnalysisTimeout
+    } else {
+        ErrorCode::Internal
+    };
+
+    CommandError::new(code, context).with_details(format!("{:#}", error))
+}
+
+// Parse codebase command, optionally re-parsing only a subdirectory of the last analysis
 #[tauri::command]
 async fn parse_codebase(
     path: String,
+    subpath: Option<String>,
     state: tauri::State<'_, Mutex<AppState>>,
     window: tauri::Window,
-) -> Result<ParseResult, String> {
-    println!("Parsing codebase at: {}", path);
+) -> Result<ParseResult, CommandError> {
+    tracing::info!("Parsing codebase at: {}", path);
     
-    let path_buf = PathBuf::from(path);
+    let path_buf = PathBuf::from(&path);
+
+    // A partial parse reuses the previous graph of the same root for everything outside the subpath
+    let partial = match subpath {
+        Some(subpath) => {
+            let subpath = PathBuf::from(subpath);
+            if subpath.is_absolute() || subpath.components().any(|c| c == std::path::Component::ParentDir) {
+                return Err(CommandError::invalid_input(format!(
+                    "Subpath must be inside the codebase: {}",
+                    subpath.display()
+                )));
+            }
+            let state_guard = state.lock().unwrap();
+            match (&state_guard.current_graph, &state_guard.run_info) {
+                (Some(graph_data), Some(run_info)) if Path::new(&run_info.analyzed_path) == path_buf => {
+                    Some((subpath, graph_data.clone(), run_info.clone(), state_guard.live_metrics.clone()))
+                }
+                _ => {
+                    return Err(CommandError::invalid_state(format!(
+                        "Analyze {} in full before re-parsing part of it",
+                        path
+                    )))
+                }
+            }
+        }
+        None => None,
+    };
     
     // Create or update engine with the base path
     {
         let mut state_guard = state.lock().unwrap();
-        state_guard.engine = Some(Engine::new(path_buf.clone()));
+        let mut engine = Engine::new(path_buf.clone())
+            .with_limits(state_guard.limits.clone())
+            .with_include_dirs(&state_guard.include_dirs)
+            .with_external_dependencies(state_guard.external_dependencies);
+        // A partial re-parse edits the codebase the last engine analyzed
+        if let (Some(_), Some(previous)) = (&partial, &state_guard.engine) {
+            engine = engine.with_parse_cache(previous.parse_cache());
+        }
+        state_guard.engine = Some(engine);
     }
     
     // Create progress reporter
@@ -378,28 +295,76 @@ async fn parse_codebase(
     let engine_clone = {
         let state_guard = state.lock().unwrap();
         state_guard.engine.as_ref()
-            .ok_or_else(|| "Engine not initialized".to_string())?
+            .ok_or_else(|| CommandError::from_message(ErrorCode::InvalidState, Message::EngineNotInitialized))?
             .clone()
     };
     
     // Analyze codebase (outside of mutex lock)
-    let graph_data = engine_clone.analyze_codebase(Some(progress.clone()))
-        .await
-        .map_err(|e| format!("Failed to analyze codebase: {}", e))?;
+    let (graph_data, run_info, live_metrics) = match partial {
+        Some((subpath, cached, cached_info, cached_metrics)) => {
+            let (graph_data, mut run_info) = engine_clone
+                .analyze_subtree(&subpath, &cached, Some(progress.clone()))
+                .await
+                .map_err(|e| engine_failure("Failed to analyze codebase", e))?;
+            let live_metrics = engine_v2::update_live_metrics(cached_metrics, &cached, &graph_data);
+            // Nodes outside the subpath were not re-parsed, keep what was measured of them
+            for (file_path, stats) in cached_info.file_stats {
+                run_info.file_stats.entry(file_path).or_insert(stats);
+            }
+            for (id, shape) in cached_info.function_metrics {
+                run_info.function_metrics.entry(id).or_insert(shape);
+            }
+            for (file_path, density) in cached_info.comments {
+                run_info.comments.entry(file_path).or_insert(density);
+            }
+            for (id, documented) in cached_info.documented {
+                run_info.documented.entry(id).or_insert(documented);
+            }
+            for (id, profile) in cached_info.concurrency {
+                run_info.concurrency.entry(id).or_insert(profile);
+            }
+            if let Some(dir) = &run_info.partial_path {
+                let outside = cached_info
+                    .debt_markers
+                    .into_iter()
+                    .filter(|marker| !Path::new(&marker.file_path).starts_with(dir));
+                run_info.debt_markers.extend(outside);
+                for (file_path, header) in cached_info.license_headers {
+                    if !Path::new(&file_path).starts_with(dir) {
+                        run_info.license_headers.entry(file_path).or_insert(header);
+                    }
+                }
+                for license_file in cached_info.license_files {
+                    if !run_info.license_files.iter().any(|found| found.path == license_file.path) {
+                        run_info.license_files.push(license_file);
+                    }
+                }
+            }
+            (graph_data, run_info, live_metrics)
+        }
+        None => {
+            let (graph_data, run_info) = engine_clone
+                .analyze_codebase(Some(progress.clone()))
+                .await
+                .map_err(|e| engine_failure("Failed to analyze codebase", e))?;
+            let live_metrics = engine_v2::seed_live_metrics(&graph_data);
+            (graph_data, run_info, live_metrics)
+        }
+    };
     
     // Build file tree from graph nodes
-    println!("Graph data stats: files={}, nodes={}, edges={}", 
+    tracing::debug!("Graph data stats: files={}, nodes={}, edges={}", 
         graph_data.stats.file_count, 
         graph_data.stats.node_count, 
         graph_data.stats.link_count
     );
     
-    let files = build_file_tree(&graph_data);
-    println!("Built file tree with {} root items", files.len());
+    let files = file_tree(&graph_data, &run_info);
+    tracing::debug!("Built file tree with {} root items", files.len());
     
     // Debug: print first few file names
     for (i, file) in files.iter().take(3).enumerate() {
-        println!("  Root item {}: {} (type: {})", i, file.name, file.node_type);
+        tracing::debug!("  Root item {}: {} (type: {})", i, file.name, file.node_type);
     }
     
     let result = ParseResult {
@@ -414,24 +379,111 @@ async fn parse_codebase(
     {
         let mut state_guard = state.lock().unwrap();
         state_guard.current_graph = Some(graph_data);
+        state_guard.analyzed_graph = None;
+        state_guard.run_info = Some(run_info);
+        // The viewport ranks by the live PageRank, which a partial re-parse updates
+        state_guard.viewport_ranks = Some(Arc::new(live_metrics.pagerank.clone()));
+        state_guard.live_metrics = Some(live_metrics);
     }
     
     Ok(result)
 }
 
+/// File explorer tree of a graph, with each file's stats and code smells
+fn file_tree(graph_data: &GraphData, run_info: &RunInfo) -> Vec<FileNode> {
+    let smells = smell_counts(
+        &CodeGraph::from_graph_data(graph_data),
+        &run_info.suppressions,
+        &run_info.function_metrics,
+    );
+    let mut stats = run_info.file_stats.clone();
+    for (file_path, file) in stats.iter_mut() {
+        file.smells = Some(smells.get(file_path).copied().unwrap_or(0));
+    }
+    build_file_tree(graph_data, &stats)
+}
+
+// Get the file explorer tree of the current graph, with totals per folder
+#[tauri::command]
+async fn get_file_tree(
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<Vec<FileNode>, CommandError> {
+    let state_guard = state.lock().unwrap();
+    let Some(ref graph_data) = state_guard.current_graph else {
+        return Err(no_graph_error());
+    };
+    Ok(file_tree(graph_data, &state_guard.run_info.clone().unwrap_or_default()))
+}
+
 // Generate graph from parsed data
 #[tauri::command]
 async fn generate_graph(
     state: tauri::State<'_, Mutex<AppState>>,
-) -> Result<GraphData, String> {
-    println!("Generating graph...");
+) -> Result<GraphData, CommandError> {
+    tracing::debug!("Generating graph...");
     
     let state_guard = state.lock().unwrap();
     
     if let Some(ref graph_data) = state_guard.current_graph {
         Ok(graph_data.clone())
     } else {
-        Err("No parsed data available. Please parse a codebase first.".to_string())
+        Err(no_graph_error())
+    }
+}
+
+// Get one page of the current graph for progressive loading
+#[tauri::command]
+async fn get_graph_page(
+    offset: usize,
+    limit: usize,
+    edges: Option<EdgeMode>,
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<GraphPage, CommandError> {
+    if limit == 0 {
+        return Err(CommandError::from_message(ErrorCode::InvalidInput, Message::PageLimitZero));
+    }
+
+    let state_guard = state.lock().unwrap();
+
+    match state_guard.current_graph {
+        Some(ref graph_data) => {
+            // Bundles only count this page's links, the frontend sums them across pages
+            let mut page = graph_data.page(offset, limit);
+            let links = std::mem::take(&mut page.links);
+            (page.links, page.bundles) = graph_data.bundle_links(links, edges.unwrap_or_default());
+            Ok(page)
+        }
+        None => Err(no_graph_error()),
+    }
+}
+
+// Get a range of nodes from the current graph
+#[tauri::command]
+async fn get_node_range(
+    start: usize,
+    end: usize,
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<Vec<GraphNode>, CommandError> {
+    let state_guard = state.lock().unwrap();
+
+    match state_guard.current_graph {
+        Some(ref graph_data) => Ok(graph_data.node_range(start, end).to_vec()),
+        None => Err(no_graph_error()),
+    }
+}
+
+// Get a range of edges from the current graph
+#[tauri::command]
+async fn get_edge_range(
+    start: usize,
+    end: usize,
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<Vec<GraphLink>, CommandError> {
+    let state_guard = state.lock().unwrap();
+
+    match state_guard.current_graph {
+        Some(ref graph_data) => Ok(graph_data.link_range(start, end).to_vec()),
+        None => Err(no_graph_error()),
     }
 }
 
@@ -441,8 +493,7 @@ async fn analyze_with_metrics(
     path: String,
     state: tauri::State<'_, Mutex<AppState>>,
     window: tauri::Window,
-) -> Result<AnalyzedGraph, String> {
-    println!("[ANALYZE] Starting analyze_with_metrics at: {}", path);
+) -> Result<AnalyzedGraph, CommandError> {
     tracing::info!("[ANALYZE] Starting analyze_with_metrics at: {}", path);
     
     let path_buf = PathBuf::from(path);
@@ -450,7 +501,12 @@ async fn analyze_with_metrics(
     // Create or update engine with the base path
     {
         let mut state_guard = state.lock().unwrap();
-        state_guard.engine = Some(Engine::new(path_buf.clone()));
+        state_guard.engine = Some(
+            Engine::new(path_buf.clone())
+                .with_limits(state_guard.limits.clone())
+                .with_include_dirs(&state_guard.include_dirs)
+                .with_external_dependencies(state_guard.external_dependencies),
+        );
     }
     
     // Create progress reporter
@@ -463,80 +519,263 @@ async fn analyze_with_metrics(
     let engine_clone = {
         let state_guard = state.lock().unwrap();
         state_guard.engine.as_ref()
-            .ok_or_else(|| "Engine not initialized".to_string())?
+            .ok_or_else(|| CommandError::from_message(ErrorCode::InvalidState, Message::EngineNotInitialized))?
             .clone()
     };
     
     // Try to analyze with metrics, but fall back to basic analysis if it fails
-    println!("[ANALYZE] Calling engine.analyze_with_metrics...");
     tracing::info!("[ANALYZE] Calling engine.analyze_with_metrics...");
     
     // Try to run the analysis without blocking
     let analyzed_graph = match engine_clone.analyze_with_metrics(Some(progress.clone())).await {
         Ok(graph) => {
-            println!("[ANALYZE] Success! Got analyzed graph");
             tracing::info!("[ANALYZE] Success! Got analyzed graph");
             graph
         },
+        // Retrying cannot help when the codebase is over the limits
+        Err(e) if e.is::<LimitExceeded>() => {
+            return Err(engine_failure("Codebase too large to analyze", e));
+        },
         Err(e) => {
-            println!("[ANALYZE] ERROR: Metrics analysis failed: {}", e);
             tracing::error!("[ANALYZE] ERROR: Metrics analysis failed: {}", e);
-            println!("[ANALYZE] Attempting fallback to basic analysis...");
+            tracing::info!("[ANALYZE] Attempting fallback to basic analysis...");
             
             // Try basic analysis without metrics
-            let graph_data = engine_clone.analyze_codebase(Some(progress))
+            let (graph_data, run_info) = engine_clone.analyze_codebase(Some(progress))
                 .await
-                .map_err(|e| format!("Failed to analyze codebase: {}", e))?;
+                .map_err(|e| engine_failure("Failed to analyze codebase", e))?;
             
             // Return graph without metrics
-            AnalyzedGraph {
-                graph_data,
-                metrics: Vec::new(),
-                summary: AnalysisSummary {
-                    total_nodes: 0,
-                    total_edges: 0,
-                    num_communities: 0,
-                    modularity: 0.0,
-                    avg_complexity: 0.0,
-                    high_risk_count: 0,
-                    circular_dependencies: 0,
-                },
-            }
+            let mut fallback = AnalyzedGraph::without_metrics(graph_data, 0, 0, run_info);
+            fallback.performance.record_stages(&fallback.run_info);
+            fallback
         }
     };
     
+    // Keep the metrics for trend tracking across runs
+    if let Some(snapshot) = MetricSnapshot::from_analysis(&analyzed_graph) {
+        if let Err(e) = snapshot.save(&path_buf) {
+            tracing::warn!("[ANALYZE] Failed to save metric snapshot: {}", e);
+        }
+    }
+
     // Store the results
+    let live_metrics = engine_v2::seed_live_metrics(&analyzed_graph.graph_data);
     {
         let mut state_guard = state.lock().unwrap();
         state_guard.current_graph = Some(analyzed_graph.graph_data.clone());
         state_guard.analyzed_graph = Some(analyzed_graph.clone());
+        state_guard.run_info = Some(analyzed_graph.run_info.clone());
+        state_guard.viewport_ranks = Some(Arc::new(live_metrics.pagerank.clone()));
+        state_guard.live_metrics = Some(live_metrics);
+    }
+
+    for alert in &analyzed_graph.summary.alerts {
+        tracing::warn!("[ANALYZE] Alert: {}", alert.message);
+        window.emit(ANALYSIS_ALERT_EVENT, alert).ok();
     }
     
     Ok(analyzed_graph)
 }
 
-// Connect to Neo4j (placeholder)
+// Connect to Neo4j and keep the connection for Cypher queries
 #[tauri::command]
-async fn connect_neo4j(uri: String, _username: String, _password: String) -> Result<bool, String> {
-    println!("Connecting to Neo4j at: {}", uri);
-    
-    // TODO: Implement actual connection using og-db crate
+async fn connect_neo4j(
+    uri: String,
+    username: String,
+    password: String,
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<bool, CommandError> {
+    tracing::info!("Connecting to Neo4j at: {}", uri);
+    let client = Neo4jClient::connect(&uri, &username, &password).await?;
+    state.lock().unwrap().neo4j = Some(Arc::new(client));
     Ok(true)
 }
 
+// Run a Cypher query against the connected database, shaping nodes and
+// relationships in the rows into graph data. Queries are read-only unless
+// `read_only` is false.
+#[tauri::command]
+async fn run_cypher(
+    query: String,
+    params: Option<serde_json::Map<String, serde_json::Value>>,
+    read_only: Option<bool>,
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<CypherResult, CommandError> {
+    let client = state
+        .lock()
+        .unwrap()
+        .neo4j
+        .clone()
+        .ok_or_else(|| CommandError::from_message(ErrorCode::InvalidState, Message::NotConnectedToNeo4j))?;
+    let result = client
+        .run_cypher(&query, &params.unwrap_or_default(), read_only.unwrap_or(true))
+        .await?;
+    tracing::info!(
+        "Cypher query returned {} rows, {} nodes and {} links",
+        result.rows.len(),
+        result.graph.nodes.len(),
+        result.graph.links.len()
+    );
+    Ok(result)
+}
+
+// Choose where graphs are persisted. SQLite needs a database file path and
+// Neo4j (or Memgraph) an earlier `connect_neo4j`; graphs there are kept in a
+// project and snapshot namespace so several repositories can share it.
+#[tauri::command]
+async fn set_graph_store(
+    backend: StoreBackend,
+    path: Option<String>,
+    namespace: Option<Namespace>,
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<(), CommandError> {
+    let mut state_guard = state.lock().unwrap();
+    let store: Arc<dyn PersistentStore> = match backend {
+        StoreBackend::Memory => Arc::new(MemoryStore::default()),
+        StoreBackend::Sqlite => {
+            let path = path.ok_or_else(|| CommandError::invalid_input("A SQLite store needs a database path"))?;
+            Arc::new(SqliteStore::open(Path::new(&path))?)
+        }
+        StoreBackend::Neo4j => {
+            let client = state_guard
+                .neo4j
+                .clone()
+                .ok_or_else(|| CommandError::from_message(ErrorCode::InvalidState, Message::NotConnectedToNeo4j))?;
+            let namespace = match namespace {
+                Some(namespace) => Namespace::new(&namespace.project, &namespace.snapshot)?,
+                None => Namespace::default(),
+            };
+            Arc::new(Neo4jStore::new(client, namespace))
+        }
+    };
+    tracing::info!("Persisting graphs to {:?}", backend);
+    state_guard.persistent_store = store;
+    state_guard.synced_graph = None;
+    Ok(())
+}
+
+// Persist the current graph to the selected store
+#[tauri::command]
+async fn save_graph(
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<(), CommandError> {
+    let (store, graph) = {
+        let state_guard = state.lock().unwrap();
+        let graph = state_guard.current_graph.clone().ok_or_else(no_graph_error)?;
+        (state_guard.persistent_store.clone(), graph)
+    };
+    store.save(&graph).await?;
+    tracing::info!("Saved {} nodes to the {:?} store", graph.nodes.len(), store.backend());
+    state.lock().unwrap().synced_graph = Some(graph);
+    Ok(())
+}
+
+// Bring the selected store up to date with the current graph, writing only
+// the nodes and links that changed since the last save or sync
+#[tauri::command]
+async fn sync_graph(
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<SyncReport, CommandError> {
+    let (store, previous, graph) = {
+        let state_guard = state.lock().unwrap();
+        let graph = state_guard.current_graph.clone().ok_or_else(no_graph_error)?;
+        (state_guard.persistent_store.clone(), state_guard.synced_graph.clone(), graph)
+    };
+    let report = og_db::sync(store.as_ref(), previous.as_ref(), &graph).await?;
+    tracing::info!(
+        "Synced {:?} store in {}ms: +{} ~{} -{} nodes, +{} ~{} -{} links",
+        report.backend,
+        report.duration_ms,
+        report.added_nodes,
+        report.updated_nodes,
+        report.removed_nodes,
+        report.added_links,
+        report.updated_links,
+        report.removed_links
+    );
+
+    let mut state_guard = state.lock().unwrap();
+    state_guard.synced_graph = Some(graph);
+    state_guard.sync_log.push(report.clone());
+    let excess = state_guard.sync_log.len().saturating_sub(SYNC_LOG_LIMIT);
+    state_guard.sync_log.drain(..excess);
+    Ok(report)
+}
+
+// Project and snapshot namespaces stored in the connected database
+#[tauri::command]
+async fn list_namespaces(
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<Vec<NamespaceInfo>, CommandError> {
+    let client = state
+        .lock()
+        .unwrap()
+        .neo4j
+        .clone()
+        .ok_or_else(|| CommandError::from_message(ErrorCode::InvalidState, Message::NotConnectedToNeo4j))?;
+    Ok(client.list_namespaces().await?)
+}
+
+// Delete a snapshot of a project from the connected database, or every
+// snapshot when none is given. Returns how many snapshots were dropped.
+#[tauri::command]
+async fn drop_namespace(
+    project: String,
+    snapshot: Option<String>,
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<usize, CommandError> {
+    let client = state
+        .lock()
+        .unwrap()
+        .neo4j
+        .clone()
+        .ok_or_else(|| CommandError::from_message(ErrorCode::InvalidState, Message::NotConnectedToNeo4j))?;
+    let dropped = client.drop_namespace(&project, snapshot.as_deref()).await?;
+    tracing::info!("Dropped {} snapshot(s) of project {}", dropped, project);
+    // The dropped namespace may be the one syncs diff against
+    state.lock().unwrap().synced_graph = None;
+    Ok(dropped)
+}
+
+// Recent syncs, oldest first
+#[tauri::command]
+async fn get_sync_log(
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<Vec<SyncReport>, CommandError> {
+    Ok(state.lock().unwrap().sync_log.clone())
+}
+
 // Get saved graph data
 #[tauri::command]
-async fn get_saved_graph() -> Result<Option<GraphData>, String> {
-    // TODO: Load from persistence
-    Ok(None)
+async fn get_saved_graph(
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<Option<GraphData>, CommandError> {
+    let store = state.lock().unwrap().persistent_store.clone();
+    Ok(store.load().await?)
+}
+
+// Dependency slice of a node in the saved graph, computed by the store
+#[tauri::command]
+async fn get_stored_slice(
+    node_id: String,
+    direction: SliceDirection,
+    max_depth: Option<usize>,
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<GraphData, CommandError> {
+    let store = state.lock().unwrap().persistent_store.clone();
+    store
+        .slice(&node_id, direction, max_depth)
+        .await?
+        .ok_or_else(|| CommandError::from_message(ErrorCode::InvalidInput, Message::UnknownNode { id: node_id.to_string() }))
 }
 
 // Reset app state
 #[tauri::command]
 async fn reset_app(
     state: tauri::State<'_, Mutex<AppState>>,
-) -> Result<(), String> {
-    println!("Resetting app state...");
+) -> Result<(), CommandError> {
+    tracing::info!("Resetting app state...");
     
     let mut state_guard = state.lock().unwrap();
     
@@ -544,18 +783,1206 @@ async fn reset_app(
     state_guard.engine = None;
     state_guard.current_graph = None;
     state_guard.analyzed_graph = None;
+    state_guard.run_info = None;
+    state_guard.viewport_ranks = None;
+    state_guard.live_metrics = None;
+    state_guard.journal.clear();
+    if let Some(scheduler) = state_guard.scheduler.take() {
+        scheduler.stop();
+    }
     
-    println!("App state reset successfully");
+    tracing::info!("App state reset successfully");
     Ok(())
 }
 
+// Render a Markdown PR comment comparing a baseline checkout with the current analysis
+#[tauri::command]
+async fn format_pr_summary(
+    baseline_path: String,
+    limit: Option<usize>,
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<String, CommandError> {
+    let (current, engine) = {
+        let state_guard = state.lock().unwrap();
+        let Some(ref current) = state_guard.analyzed_graph else {
+            return Err(no_graph_error());
+        };
+        let engine = Engine::new(PathBuf::from(&baseline_path))
+            .with_limits(state_guard.limits.clone())
+            .with_include_dirs(&state_guard.include_dirs)
+            .with_external_dependencies(state_guard.external_dependencies);
+        (current.clone(), engine)
+    };
+    if current.metrics.is_empty() {
+        return Err(CommandError::invalid_state("The current analysis has no metrics to compare"));
+    }
+
+    let baseline = engine
+        .analyze_with_metrics(None)
+        .await
+        .map_err(|e| engine_failure("Failed to analyze baseline", e))?;
+    Ok(cli::summarize(&baseline, &current, limit.unwrap_or(DEFAULT_PR_FILES)).to_markdown())
+}
+
+// Sample a codebase's git history and report when its modules appeared, grew, split or were deleted
+#[tauri::command]
+async fn get_evolution_timeline(
+    path: String,
+    settings: Option<TimelineSettings>,
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<EvolutionTimeline, CommandError> {
+    let settings = settings.unwrap_or_default();
+    settings.validate().map_err(CommandError::invalid_input)?;
+    let root = PathBuf::from(&path);
+    if !root.is_dir() {
+        return Err(CommandError::from_message(ErrorCode::InvalidInput, Message::NotADirectory { path: path.to_string() }));
+    }
+    let engine = {
+        let state_guard = state.lock().unwrap();
+        Engine::new(root.clone())
+            .with_limits(state_guard.limits.clone())
+            .with_include_dirs(&state_guard.include_dirs)
+            .with_external_dependencies(state_guard.external_dependencies)
+    };
+
+    tauri::async_runtime::spawn_blocking(move || timeline::build(&engine, &root, &settings))
+        .await
+        .map_err(|e| CommandError::new(ErrorCode::Internal, "Timeline task failed").with_details(e.to_string()))?
+        .map_err(|e| engine_failure("Failed to build evolution timeline", e))
+}
+
+// Analyze a commit or branch in a temporary worktree and store it as a snapshot
+#[tauri::command]
+async fn analyze_revision(
+    path: String,
+    rev: String,
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<RevisionAnalysis, CommandError> {
+    let root = PathBuf::from(&path);
+    if !root.is_dir() {
+        return Err(CommandError::from_message(ErrorCode::InvalidInput, Message::NotADirectory { path: path.to_string() }));
+    }
+    if rev.trim().is_empty() || rev.starts_with('-') {
+        return Err(CommandError::from_message(ErrorCode::InvalidInput, Message::InvalidRevision { revision: rev.to_string() }));
+    }
+    let (limits, include_dirs) = {
+        let state_guard = state.lock().unwrap();
+        (state_guard.limits.clone(), state_guard.include_dirs.clone())
+    };
+
+    revision::analyze_revision(&root, &rev, limits, &include_dirs)
+        .await
+        .map_err(|e| engine_failure(&format!("Failed to analyze {}", rev), e))
+}
+
+// Compare the structure and metrics of the codebase at two commits or branches
+#[tauri::command]
+async fn diff_revisions(
+    path: String,
+    rev_a: String,
+    rev_b: String,
+    limit: Option<usize>,
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<StructuralDiff, CommandError> {
+    let root = PathBuf::from(&path);
+    if !root.is_dir() {
+        return Err(CommandError::from_message(ErrorCode::InvalidInput, Message::NotADirectory { path: path.to_string() }));
+    }
+    for rev in [&rev_a, &rev_b] {
+        if rev.trim().is_empty() || rev.starts_with('-') {
+            return Err(CommandError::from_message(ErrorCode::InvalidInput, Message::InvalidRevision { revision: rev.to_string() }));
+        }
+    }
+    let (limits, include_dirs) = {
+        let state_guard = state.lock().unwrap();
+        (state_guard.limits.clone(), state_guard.include_dirs.clone())
+    };
+
+    revision::diff_revisions(&root, &rev_a, &rev_b, limits, &include_dirs, limit.unwrap_or(DEFAULT_PR_FILES))
+        .await
+        .map_err(|e| engine_failure(&format!("Failed to compare {} with {}", rev_a, rev_b), e))
+}
+
+// Install a git hook that blocks changes adding cycles or god objects
+#[tauri::command]
+async fn install_git_hook(
+    path: String,
+    hook: GitHook,
+    overwrite: Option<bool>,
+) -> Result<PathBuf, CommandError> {
+    hooks::install(Path::new(&path), hook, overwrite.unwrap_or(false))
+        .map_err(|e| CommandError::new(ErrorCode::Io, "Failed to install git hook").with_details(e))
+}
+
+// Re-analyze a codebase in the background on an interval or when its HEAD changes
+#[tauri::command]
+async fn start_scheduled_analysis(
+    path: String,
+    settings: ScheduleSettings,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<ScheduleStatus, CommandError> {
+    settings.validate().map_err(CommandError::invalid_input)?;
+    let path_buf = PathBuf::from(&path);
+    if !path_buf.is_dir() {
+        return Err(CommandError::from_message(ErrorCode::InvalidInput, Message::NotADirectory { path: path.to_string() }));
+    }
+
+    let scheduler = Scheduler::start(app, path_buf, settings);
+    let status = scheduler.status().clone();
+    let mut state_guard = state.lock().unwrap();
+    if let Some(previous) = state_guard.scheduler.replace(scheduler) {
+        previous.stop();
+    }
+    Ok(status)
+}
+
+// Stop background re-analysis, returning whether one was scheduled
+#[tauri::command]
+async fn stop_scheduled_analysis(
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<bool, CommandError> {
+    let mut state_guard = state.lock().unwrap();
+    let scheduler = state_guard.scheduler.take();
+    let running = scheduler.is_some();
+    if let Some(scheduler) = scheduler {
+        scheduler.stop();
+    }
+    Ok(running)
+}
+
+// Get the current background re-analysis schedule
+#[tauri::command]
+async fn get_scheduled_analysis(
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<Option<ScheduleStatus>, CommandError> {
+    let state_guard = state.lock().unwrap();
+    Ok(state_guard.scheduler.as_ref().map(|scheduler| scheduler.status().clone()))
+}
+
+// Get recent log entries at or above the given level
+#[tauri::command]
+async fn get_recent_logs(
+    level: Option<String>,
+    limit: Option<usize>,
+    buffer: tauri::State<'_, Arc<LogBuffer>>,
+) -> Result<Vec<LogEntry>, CommandError> {
+    let level = match level {
+        Some(level) => level.parse::<tracing::Level>()
+            .map_err(|_| CommandError::invalid_input(format!("Unknown log level: {}", level)))?,
+        None => tracing::Level::INFO,
+    };
+
+    Ok(buffer.recent(level, limit.unwrap_or(200)))
+}
+
+// Enable or disable the live diagnostics log stream
+#[tauri::command]
+async fn set_diagnostics_stream(
+    enabled: bool,
+    app: tauri::AppHandle,
+    buffer: tauri::State<'_, Arc<LogBuffer>>,
+) -> Result<(), CommandError> {
+    logging::set_diagnostics_stream(&app, &buffer, enabled);
+    Ok(())
+}
+
+// Get the size limits applied to new analyses
+#[tauri::command]
+async fn get_analysis_limits(
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<AnalysisLimits, CommandError> {
+    let state_guard = state.lock().unwrap();
+    Ok(state_guard.limits.clone())
+}
+
+// Set the size limits applied to new analyses
+#[tauri::command]
+async fn set_analysis_limits(
+    limits: AnalysisLimits,
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<(), CommandError> {
+    if limits.max_files == 0 || limits.max_nodes == 0 || limits.max_edges == 0 {
+        return Err(CommandError::invalid_input("Analysis limits must be greater than zero"));
+    }
+
+    let mut state_guard = state.lock().unwrap();
+    state_guard.limits = limits;
+    Ok(())
+}
+
+// Get the extra C include directories used by new analyses
+#[tauri::command]
+async fn get_include_dirs(
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<Vec<PathBuf>, CommandError> {
+    let state_guard = state.lock().unwrap();
+    Ok(state_guard.include_dirs.clone())
+}
+
+// Set the extra C include directories used by new analyses
+#[tauri::command]
+async fn set_include_dirs(
+    include_dirs: Vec<PathBuf>,
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<(), CommandError> {
+    let mut state_guard = state.lock().unwrap();
+    state_guard.include_dirs = include_dirs;
+    Ok(())
+}
+
+// Get how new analyses show third-party imports
+#[tauri::command]
+async fn get_external_dependencies(
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<ExternalDependencies, CommandError> {
+    let state_guard = state.lock().unwrap();
+    Ok(state_guard.external_dependencies)
+}
+
+// Set whether new analyses drop third-party imports or group them by package or import
+#[tauri::command]
+async fn set_external_dependencies(
+    external_dependencies: ExternalDependencies,
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<(), CommandError> {
+    let mut state_guard = state.lock().unwrap();
+    state_guard.external_dependencies = external_dependencies;
+    Ok(())
+}
+
+// Get the editor that nodes are opened in
+#[tauri::command]
+async fn get_editor_settings(
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<EditorSettings, CommandError> {
+    let state_guard = state.lock().unwrap();
+    Ok(state_guard.editor.clone())
+}
+
+// Set the editor that nodes are opened in
+#[tauri::command]
+async fn set_editor_settings(
+    settings: EditorSettings,
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<(), CommandError> {
+    settings.validate().map_err(CommandError::invalid_input)?;
+
+    let mut state_guard = state.lock().unwrap();
+    state_guard.editor = settings;
+    Ok(())
+}
+
+// Open a node's source file at its line in the configured editor
+#[tauri::command]
+async fn open_in_editor(
+    node_id: String,
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<(), CommandError> {
+    // Release the state before the editor starts
+    let (editor, file_path, line) = {
+        let state_guard = state.lock().unwrap();
+        let Some(ref graph_data) = state_guard.current_graph else {
+            return Err(no_graph_error());
+        };
+        let node = graph_data
+            .nodes
+            .iter()
+            .find(|node| node.id == node_id)
+            .ok_or_else(|| CommandError::from_message(ErrorCode::InvalidInput, Message::NodeNotFound { id: node_id.to_string() }))?;
+        let file_path = node
+            .file_path
+            .as_deref()
+            .map(PathBuf::from)
+            .ok_or_else(|| CommandError::invalid_input(format!("Node has no source file: {}", node_id)))?;
+        // Files and nodes from older caches have no line, so open them at the top
+        let line = node.number(LINE_ATTRIBUTE).map_or(1, |line| line as usize);
+        (state_guard.editor.clone(), file_path, line)
+    };
+
+    editor
+        .open(&file_path, line)
+        .map_err(|e| CommandError::new(ErrorCode::Io, e))
+}
+
+// Check a codebase's .omnigraph/config.toml before analyzing it
+#[tauri::command]
+async fn validate_config(path: String) -> Result<ConfigValidation, CommandError> {
+    Ok(ProjectConfig::validate(Path::new(&path)))
+}
+
+// Write the current analysis as a standalone HTML report, or Markdown for .md paths
+#[tauri::command]
+async fn export_report_html(
+    path: String,
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<(), CommandError> {
+    let report = {
+        let state_guard = state.lock().unwrap();
+        let Some(ref graph_data) = state_guard.current_graph else {
+            return Err(no_graph_error());
+        };
+        let metrics = state_guard
+            .analyzed_graph
+            .as_ref()
+            .map_or(&[][..], |analyzed| analyzed.metrics.as_slice());
+        let run_info = state_guard.run_info.clone().unwrap_or_default();
+        ArchitectureReport::build(
+            &format!("Architecture report: {}", run_info.analyzed_path),
+            &CodeGraph::from_graph_data(graph_data),
+            metrics,
+            &run_info.suppressions,
+            &run_info.function_metrics,
+            run_info
+                .config
+                .as_ref()
+                .map_or(&[][..], |config| config.community_edge_types.as_slice()),
+            DEFAULT_TOP_N,
+        )
+    };
+
+    let path = PathBuf::from(path);
+    std::fs::write(&path, report.render(ReportFormat::from_path(&path))).map_err(|e| {
+        CommandError::new(ErrorCode::Io, format!("Failed to write report to {}", path.display()))
+            .with_details(e.to_string())
+    })
+}
+
+// Write one row per node and one column per metric as CSV or Parquet
+#[tauri::command]
+async fn export_metrics_table(
+    path: String,
+    format: TableFormat,
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<(), CommandError> {
+    let table = {
+        let state_guard = state.lock().unwrap();
+        let metrics = state_guard
+            .analyzed_graph
+            .as_ref()
+            .map_or(&[][..], |analyzed| analyzed.metrics.as_slice());
+        if metrics.is_empty() {
+            return Err(CommandError::invalid_state(
+                "There are no metrics to export. Please analyze with metrics first.",
+            ));
+        }
+        MetricsTable::new(metrics)
+    };
+
+    let path = PathBuf::from(path);
+    table.write(&path, format).map_err(|e| {
+        CommandError::new(ErrorCode::Io, format!("Failed to write metrics to {}", path.display()))
+            .with_details(format!("{:#}", e))
+    })
+}
+
+// Answer a plain-language question such as "which files import the auth module?"
+#[tauri::command]
+async fn query_natural(
+    text: String,
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<QueryResult, CommandError> {
+    let query = GraphQuery::parse_natural(&text).ok_or_else(|| {
+        CommandError::invalid_input(format!("Could not understand {:?}", text)).with_details(
+            "Try questions like \"which files import the auth module?\", \"what does app.ts call?\" \
+             or \"top 10 most complex functions\"",
+        )
+    })?;
+
+    let state_guard = state.lock().unwrap();
+    let Some(ref graph_data) = state_guard.current_graph else {
+        return Err(no_graph_error());
+    };
+    let metrics = state_guard
+        .analyzed_graph
+        .as_ref()
+        .map_or(&[][..], |analyzed| analyzed.metrics.as_slice());
+    if query.sort_by.is_some_and(|metric| metric.needs_analysis()) && metrics.is_empty() {
+        return Err(CommandError::invalid_state(
+            "Ranking by this metric needs a full analysis. Please analyze with metrics first.",
+        ));
+    }
+
+    Ok(query.answer(&CodeGraph::from_graph_data(graph_data), metrics))
+}
+
+// Find the files or classes most structurally similar to a node
+#[tauri::command]
+async fn find_similar_nodes(
+    node_id: String,
+    k: Option<usize>,
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<Vec<SimilarNode>, CommandError> {
+    let state_guard = state.lock().unwrap();
+    let Some(ref graph_data) = state_guard.current_graph else {
+        return Err(no_graph_error());
+    };
+    let metrics = state_guard
+        .analyzed_graph
+        .as_ref()
+        .map_or(&[][..], |analyzed| analyzed.metrics.as_slice());
+
+    similarity::find_similar_nodes(
+        &CodeGraph::from_graph_data(graph_data),
+        metrics,
+        &node_id,
+        k.unwrap_or(DEFAULT_SIMILAR_K),
+    )
+    .ok_or_else(|| CommandError::from_message(ErrorCode::InvalidInput, Message::NodeNotFound { id: node_id.to_string() }))
+}
+
+// Rank nodes by how strongly a random walk from the seed nodes is pulled toward them
+#[tauri::command]
+async fn get_module_affinity(
+    seeds: Vec<String>,
+    k: Option<usize>,
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<Vec<NodeAffinity>, CommandError> {
+    let state_guard = state.lock().unwrap();
+    let Some(ref graph_data) = state_guard.current_graph else {
+        return Err(no_graph_error());
+    };
+    if seeds.is_empty() {
+        return Err(CommandError::from_message(ErrorCode::InvalidInput, Message::SeedsRequired));
+    }
+
+    let graph = CodeGraph::from_graph_data(graph_data);
+    module_affinity(&graph, &seeds, k.unwrap_or(DEFAULT_AFFINITY_K)).ok_or_else(|| {
+        let missing = seeds.iter().find(|id| !graph.node_map.contains_key(*id)).cloned().unwrap_or_default();
+        CommandError::from_message(ErrorCode::InvalidInput, Message::NodeNotFound { id: missing.to_string() })
+    })
+}
+
+// Get everything known about one node for the inspector panel in a single call
+#[tauri::command]
+async fn get_node_metrics(
+    node_id: String,
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<NodeMetricsDetail, CommandError> {
+    let state_guard = state.lock().unwrap();
+    let Some(ref graph_data) = state_guard.current_graph else {
+        return Err(no_graph_error());
+    };
+    let metrics = state_guard
+        .analyzed_graph
+        .as_ref()
+        .map_or(&[][..], |analyzed| analyzed.metrics.as_slice());
+    let run_info = state_guard.run_info.clone().unwrap_or_default();
+
+    NodeMetricsDetail::build(
+        &CodeGraph::from_graph_data(graph_data),
+        metrics,
+        &run_info.suppressions,
+        &run_info.function_metrics,
+        &node_id,
+    )
+        .ok_or_else(|| CommandError::from_message(ErrorCode::InvalidInput, Message::NodeNotFound { id: node_id.to_string() }))
+}
+
+// List smells, cycles, unused exports and high-risk nodes as one prioritized queue
+//
+// Findings the team acknowledged or snoozed are left out unless `include_triaged`.
+#[tauri::command]
+async fn list_findings(
+    sort: Option<FindingSort>,
+    filter: Option<FindingFilter>,
+    include_triaged: Option<bool>,
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<Vec<TriagedFinding>, CommandError> {
+    let state_guard = state.lock().unwrap();
+    let (Some(graph_data), Some(run_info)) = (&state_guard.current_graph, &state_guard.run_info) else {
+        return Err(no_graph_error());
+    };
+    let metrics = state_guard
+        .analyzed_graph
+        .as_ref()
+        .map_or(&[][..], |analyzed| analyzed.metrics.as_slice());
+
+    let found = findings::list_findings(
+        &CodeGraph::from_graph_data(graph_data),
+        metrics,
+        &run_info.suppressions,
+        &run_info.function_metrics,
+        sort.unwrap_or_default(),
+        &filter.unwrap_or_default(),
+    );
+    Ok(TriageStore::load(Path::new(&run_info.analyzed_path)).triage(
+        found,
+        &run_info.analyzed_path,
+        include_triaged.unwrap_or(false),
+    ))
+}
+
+// Acknowledge, snooze, resolve or reopen a finding by its fingerprint
+#[tauri::command]
+async fn set_finding_state(
+    fingerprint: String,
+    finding_state: FindingState,
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<(), CommandError> {
+    let base_path = {
+        let state_guard = state.lock().unwrap();
+        let Some(ref run_info) = state_guard.run_info else {
+            return Err(no_graph_error());
+        };
+        PathBuf::from(&run_info.analyzed_path)
+    };
+
+    let mut store = TriageStore::load(&base_path);
+    let before = store.entry(&fingerprint);
+    store.set(&fingerprint, finding_state);
+    store.save(&base_path).map_err(|e| {
+        CommandError::new(ErrorCode::Io, "Failed to save finding states").with_details(e.to_string())
+    })?;
+    state.lock().unwrap().journal.record(Operation::FindingState {
+        after: store.entry(&fingerprint),
+        base_path,
+        fingerprint,
+        before,
+    });
+    Ok(())
+}
+
+// Carry out a journaled operation, as recorded or inverted by undo
+fn apply_operation(operation: &Operation) -> Result<(), CommandError> {
+    match operation {
+        Operation::FindingState {
+            base_path,
+            fingerprint,
+            after,
+            ..
+        } => {
+            let mut store = TriageStore::load(base_path);
+            store.restore(fingerprint, after.clone());
+            store.save(base_path).map_err(|e| {
+                CommandError::new(ErrorCode::Io, "Failed to save finding states").with_details(e.to_string())
+            })
+        }
+        Operation::Overrides { base_path, after, .. } => save_overrides(base_path, after),
+    }
+}
+
+fn save_overrides(base_path: &Path, overrides: &Overrides) -> Result<(), CommandError> {
+    overrides.save(base_path).map_err(|e| {
+        CommandError::new(ErrorCode::Io, "Failed to save graph overrides").with_details(e.to_string())
+    })
+}
+
+// Get the manual nodes, added edges and suppressed edges of the analyzed codebase
+#[tauri::command]
+async fn get_overrides(
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<Overrides, CommandError> {
+    let state_guard = state.lock().unwrap();
+    let Some(ref run_info) = state_guard.run_info else {
+        return Err(no_graph_error());
+    };
+    Ok(Overrides::load(Path::new(&run_info.analyzed_path)))
+}
+
+// Replace the manual graph overrides of the analyzed codebase. They are
+// merged into the graph from the next analysis on.
+#[tauri::command]
+async fn set_overrides(
+    overrides: Overrides,
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<(), CommandError> {
+    let blank = overrides
+        .nodes
+        .iter()
+        .map(|node| node.id.as_str())
+        .chain(overrides.added_edges.iter().chain(&overrides.suppressed_edges).flat_map(|edge| {
+            [edge.source.as_str(), edge.target.as_str(), edge.edge_type.as_str()]
+        }))
+        .any(|value| value.trim().is_empty());
+    if blank {
+        return Err(CommandError::invalid_input("Override node ids and edge types must not be empty"));
+    }
+
+    let mut state_guard = state.lock().unwrap();
+    let Some(ref run_info) = state_guard.run_info else {
+        return Err(no_graph_error());
+    };
+    let base_path = PathBuf::from(&run_info.analyzed_path);
+    let before = Overrides::load(&base_path);
+    save_overrides(&base_path, &overrides)?;
+    state_guard.journal.record(Operation::Overrides {
+        base_path,
+        before,
+        after: overrides,
+    });
+    Ok(())
+}
+
+// Revert the latest journaled change, returning it, or None if there is nothing to undo
+#[tauri::command]
+async fn undo(
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<Option<JournalEntry>, CommandError> {
+    let mut state_guard = state.lock().unwrap();
+    let Some(entry) = state_guard.journal.pop_undo() else {
+        return Ok(None);
+    };
+    if let Err(e) = apply_operation(&entry.operation.inverse()) {
+        state_guard.journal.restore_undo(entry);
+        return Err(e);
+    }
+    tracing::info!("Undid: {}", entry.label);
+    state_guard.journal.undone(entry.clone());
+    Ok(Some(entry))
+}
+
+// Replay the latest undone change, returning it, or None if there is nothing to redo
+#[tauri::command]
+async fn redo(
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<Option<JournalEntry>, CommandError> {
+    let mut state_guard = state.lock().unwrap();
+    let Some(entry) = state_guard.journal.pop_redo() else {
+        return Ok(None);
+    };
+    if let Err(e) = apply_operation(&entry.operation) {
+        state_guard.journal.restore_redo(entry);
+        return Err(e);
+    }
+    tracing::info!("Redid: {}", entry.label);
+    state_guard.journal.redone(entry.clone());
+    Ok(Some(entry))
+}
+
+// Labels of the changes undo and redo would revert or replay, most recent first
+#[tauri::command]
+async fn get_journal(
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<JournalSummary, CommandError> {
+    Ok(state.lock().unwrap().journal.summary())
+}
+
+// Get selected metrics for many nodes at once, such as the rows of a table view
+#[tauri::command]
+async fn get_metrics_batch(
+    node_ids: Vec<String>,
+    fields: Vec<String>,
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<Vec<NodeMetricValues>, CommandError> {
+    if let Some(unknown) = fields.iter().find(|field| metric_extractor(field).is_none()) {
+        return Err(CommandError::invalid_input(format!(
+            "Unknown metric: {} (expected one of {})",
+            unknown,
+            metric_names().collect::<Vec<_>>().join(", ")
+        )));
+    }
+
+    let state_guard = state.lock().unwrap();
+    let Some(ref graph_data) = state_guard.current_graph else {
+        return Err(no_graph_error());
+    };
+    let metrics = state_guard
+        .analyzed_graph
+        .as_ref()
+        .map_or(&[][..], |analyzed| analyzed.metrics.as_slice());
+
+    Ok(metric_values(&CodeGraph::from_graph_data(graph_data), metrics, &node_ids, &fields))
+}
+
+// Get one page of a node table, filtered and sorted by name, type, path or any
+// metric, with the chosen metric columns (every metric when none are given)
+#[tauri::command]
+async fn list_nodes(
+    filter: Option<NodeFilter>,
+    sort_by: Option<String>,
+    order: Option<SortOrder>,
+    offset: usize,
+    limit: usize,
+    columns: Option<Vec<String>>,
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<NodePage, CommandError> {
+    if limit == 0 {
+        return Err(CommandError::from_message(ErrorCode::InvalidInput, Message::PageLimitZero));
+    }
+
+    let state_guard = state.lock().unwrap();
+    let Some(ref graph_data) = state_guard.current_graph else {
+        return Err(no_graph_error());
+    };
+    let metrics = state_guard
+        .analyzed_graph
+        .as_ref()
+        .map_or(&[][..], |analyzed| analyzed.metrics.as_slice());
+
+    node_table::list_nodes(
+        graph_data,
+        metrics,
+        &filter.unwrap_or_default(),
+        sort_by.as_deref().unwrap_or("name"),
+        order.unwrap_or_default(),
+        &columns.unwrap_or_default(),
+        offset..offset.saturating_add(limit),
+    )
+    .map_err(|e| CommandError::invalid_input(e.to_string()))
+}
+
+// Color nodes by a metric: a heatmap by default, categories for "community"
+// and a diverging scale for "risk_delta", the change since the previous snapshot
+#[tauri::command]
+async fn recolor_graph(
+    metric: String,
+    palette: Option<Palette>,
+    scale: Option<ColorScale>,
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<ColorMap, CommandError> {
+    let state_guard = state.lock().unwrap();
+    let (Some(graph_data), Some(run_info)) = (&state_guard.current_graph, &state_guard.run_info) else {
+        return Err(no_graph_error());
+    };
+    let metrics = state_guard
+        .analyzed_graph
+        .as_ref()
+        .map_or(&[][..], |analyzed| analyzed.metrics.as_slice());
+    if metrics.is_empty() {
+        return Err(CommandError::invalid_state(
+            "Coloring by a metric needs a full analysis. Please analyze with metrics first.",
+        ));
+    }
+    let graph = CodeGraph::from_graph_data(graph_data);
+
+    let (values, default_palette) = match metric.as_str() {
+        "community" => (
+            coloring::node_values(&graph, metrics, |m| Some(m.community as f64)),
+            Palette::Categorical,
+        ),
+        "risk_delta" => {
+            let previous = history::load_snapshots(Path::new(&run_info.analyzed_path))
+                .into_iter()
+                .rev()
+                .find(|snapshot| snapshot.taken_at < run_info.started_at)
+                .ok_or_else(|| {
+                    CommandError::invalid_state("There is no earlier snapshot to compare risk with.")
+                })?;
+            let before: HashMap<(&str, &str, &str), f32> = previous
+                .metrics
+                .iter()
+                .map(|m| ((m.path.as_str(), m.name.as_str(), m.node_type.as_str()), m.risk))
+                .collect();
+            let values = coloring::node_values(&graph, metrics, |m| {
+                let previous = before.get(&(m.path.as_str(), m.name.as_str(), m.node_type.as_str()))?;
+                Some((m.risk - previous) as f64)
+            });
+            (values, Palette::Diverging)
+        }
+        name => {
+            let extract = metric_extractor(name).ok_or_else(|| {
+                CommandError::invalid_input(format!(
+                    "Unknown metric: {} (expected community, risk_delta or one of {})",
+                    name,
+                    metric_names().collect::<Vec<_>>().join(", ")
+                ))
+            })?;
+            (coloring::node_values(&graph, metrics, extract), Palette::Heatmap)
+        }
+    };
+
+    Ok(coloring::recolor(
+        &graph,
+        &metric,
+        &values,
+        palette.unwrap_or(default_palette),
+        scale.unwrap_or_default(),
+    ))
+}
+
+// Size nodes by lines of code, PageRank or fan-in, on a log scale by default
+#[tauri::command]
+async fn resize_graph(
+    metric: SizeMetric,
+    scale: Option<SizeScale>,
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<NodeSizes, CommandError> {
+    let state_guard = state.lock().unwrap();
+    let Some(ref graph_data) = state_guard.current_graph else {
+        return Err(no_graph_error());
+    };
+    let metrics = state_guard
+        .analyzed_graph
+        .as_ref()
+        .map_or(&[][..], |analyzed| analyzed.metrics.as_slice());
+    if metric == SizeMetric::Pagerank && metrics.is_empty() {
+        return Err(CommandError::invalid_state(
+            "Sizing by PageRank needs a full analysis. Please analyze with metrics first.",
+        ));
+    }
+    let graph = CodeGraph::from_graph_data(graph_data);
+
+    let values = sizing::size_values(&graph, metrics, metric);
+    Ok(sizing::resize(&graph, &values, metric, scale.unwrap_or_default()))
+}
+
+// Get the directory hierarchy with LOC, complexity, risk or churn rolled up per folder
+#[tauri::command]
+async fn get_treemap(
+    metric: TreemapMetric,
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<Treemap, CommandError> {
+    let state_guard = state.lock().unwrap();
+    let (Some(graph_data), Some(run_info)) = (&state_guard.current_graph, &state_guard.run_info) else {
+        return Err(no_graph_error());
+    };
+    let metrics = state_guard
+        .analyzed_graph
+        .as_ref()
+        .map_or(&[][..], |analyzed| analyzed.metrics.as_slice());
+    if metric.needs_metrics() && metrics.is_empty() {
+        return Err(CommandError::invalid_state(
+            "This treemap needs a full analysis. Please analyze with metrics first.",
+        ));
+    }
+
+    Ok(build_treemap(
+        &CodeGraph::from_graph_data(graph_data),
+        metrics,
+        &run_info.analyzed_path,
+        metric,
+    ))
+}
+
+// Get comment density per file and the public symbols lacking docs
+#[tauri::command]
+async fn get_documentation(
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<DocumentationReport, CommandError> {
+    let state_guard = state.lock().unwrap();
+    let (Some(graph_data), Some(run_info)) = (&state_guard.current_graph, &state_guard.run_info) else {
+        return Err(no_graph_error());
+    };
+
+    Ok(DocumentationReport::build(
+        &CodeGraph::from_graph_data(graph_data),
+        &run_info.comments,
+        &run_info.documented,
+        &run_info.suppressions,
+    ))
+}
+
+// Get the modules that start threads or tasks or take locks, and the functions that do
+#[tauri::command]
+async fn get_concurrency_map(
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<ConcurrencyMap, CommandError> {
+    let state_guard = state.lock().unwrap();
+    let (Some(graph_data), Some(run_info)) = (&state_guard.current_graph, &state_guard.run_info) else {
+        return Err(no_graph_error());
+    };
+
+    Ok(ConcurrencyMap::build(
+        &CodeGraph::from_graph_data(graph_data),
+        &run_info.concurrency,
+        &run_info.analyzed_path,
+    ))
+}
+
+// List TODO, FIXME, HACK and XXX comments with counts per kind and file
+#[tauri::command]
+async fn list_debt_markers(
+    kind: Option<String>,
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<DebtReport, CommandError> {
+    let state_guard = state.lock().unwrap();
+    let Some(run_info) = &state_guard.run_info else {
+        return Err(no_graph_error());
+    };
+    if let Some(kind) = kind.as_deref().filter(|kind| !DEBT_KINDS.iter().any(|k| k.eq_ignore_ascii_case(kind))) {
+        return Err(CommandError::invalid_input(format!(
+            "Unknown marker '{}'. Expected one of: {}",
+            kind,
+            DEBT_KINDS.join(", ")
+        )));
+    }
+
+    Ok(DebtReport::build(&run_info.debt_markers, kind.as_deref()))
+}
+
+// License inventory of the loaded codebase, if its config scans licenses
+fn license_inventory(state: &AppState) -> Result<LicenseInventory, CommandError> {
+    let Some(run_info) = &state.run_info else {
+        return Err(no_graph_error());
+    };
+    if run_info.license_headers.is_empty() && run_info.license_files.is_empty() {
+        return Err(CommandError::invalid_state(format!(
+            "Licenses were not scanned. Set scan_licenses = true in {} and analyze again.",
+            project_config::CONFIG_PATH
+        )));
+    }
+    Ok(LicenseInventory::build(&run_info.license_headers, &run_info.license_files))
+}
+
+// Get the licenses of each package and file, and files with missing or conflicting ones
+#[tauri::command]
+async fn get_license_inventory(
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<LicenseInventory, CommandError> {
+    license_inventory(&state.lock().unwrap())
+}
+
+// Write the license inventory as CSV for `.csv` paths and JSON otherwise
+#[tauri::command]
+async fn export_license_inventory(
+    path: String,
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<(), CommandError> {
+    let inventory = license_inventory(&state.lock().unwrap())?;
+
+    let path = PathBuf::from(path);
+    let contents = match path.extension().and_then(|ext| ext.to_str()) {
+        Some(ext) if ext.eq_ignore_ascii_case("csv") => inventory.to_csv(),
+        _ => serde_json::to_string_pretty(&inventory)
+            .map_err(|e| CommandError::new(ErrorCode::Internal, "Failed to serialize the license inventory").with_details(e.to_string()))?,
+    };
+    std::fs::write(&path, contents).map_err(|e| {
+        CommandError::new(ErrorCode::Io, format!("Failed to write license inventory to {}", path.display()))
+            .with_details(e.to_string())
+    })
+}
+
+// Get a node's or package's metric across stored snapshots of the codebase
+#[tauri::command]
+async fn get_metric_trend(
+    node_id: Option<String>,
+    package: Option<String>,
+    metric: String,
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<MetricTrend, CommandError> {
+    let Some(extract) = metric_extractor(&metric) else {
+        return Err(CommandError::invalid_input(format!(
+            "Unknown metric: {} (expected one of {})",
+            metric,
+            metric_names().collect::<Vec<_>>().join(", ")
+        )));
+    };
+
+    let state_guard = state.lock().unwrap();
+    let Some(ref run_info) = state_guard.run_info else {
+        return Err(no_graph_error());
+    };
+    let base_path = PathBuf::from(&run_info.analyzed_path);
+
+    let target = match (node_id, package) {
+        (Some(node_id), None) => {
+            let Some(ref graph_data) = state_guard.current_graph else {
+                return Err(no_graph_error());
+            };
+            let node = graph_data
+                .nodes
+                .iter()
+                .find(|node| node.id == node_id)
+                .ok_or_else(|| CommandError::from_message(ErrorCode::InvalidInput, Message::NodeNotFound { id: node_id.to_string() }))?;
+            TrendTarget::Node {
+                path: node.file_path.clone().unwrap_or_else(|| node.id.clone()),
+                name: node.name.clone(),
+                node_type: node.node_type.clone(),
+            }
+        }
+        // Package directories may be given relative to the codebase root
+        (None, Some(package)) => TrendTarget::Package {
+            path: base_path.join(package).to_string_lossy().into_owned(),
+        },
+        _ => return Err(CommandError::invalid_input("Specify exactly one of a node id or a package")),
+    };
+    drop(state_guard);
+
+    let snapshots = history::load_snapshots(&base_path);
+    Ok(history::metric_trend(&snapshots, &metric, extract, target))
+}
+
+// Get metadata for the most recent analysis run
+#[tauri::command]
+async fn get_run_info(
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<RunInfo, CommandError> {
+    let state_guard = state.lock().unwrap();
+
+    state_guard.run_info.clone()
+        .ok_or_else(|| CommandError::from_message(ErrorCode::InvalidState, Message::NoAnalysis))
+}
+
+// Get global structural statistics for the current graph
+#[tauri::command]
+async fn get_graph_stats(
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<StructuralStats, CommandError> {
+    let state_guard = state.lock().unwrap();
+
+    match state_guard.current_graph {
+        Some(ref graph_data) => Ok(CodeGraph::from_graph_data(graph_data).structural_stats()),
+        None => Err(no_graph_error()),
+    }
+}
+
+// Get edge counts and weight distributions per edge type, with the heaviest edges
+#[tauri::command]
+async fn get_edge_stats(
+    top: Option<usize>,
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<EdgeStats, CommandError> {
+    let state_guard = state.lock().unwrap();
+
+    match state_guard.current_graph {
+        Some(ref graph_data) => Ok(CodeGraph::from_graph_data(graph_data)
+            .edge_stats(top.unwrap_or(DEFAULT_HEAVY_EDGES))),
+        None => Err(no_graph_error()),
+    }
+}
+
+// Get size-ranked import components and orphaned files
+#[tauri::command]
+async fn get_components(
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<ComponentReport, CommandError> {
+    let state_guard = state.lock().unwrap();
+
+    match state_guard.current_graph {
+        Some(ref graph_data) => Ok(CodeGraph::from_graph_data(graph_data)
+            .import_components(DEFAULT_ORPHAN_MAX_SIZE)),
+        None => Err(no_graph_error()),
+    }
+}
+
+// Get the acyclic view with each dependency cycle collapsed into one node
+#[tauri::command]
+async fn get_condensed_graph(
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<CondensedGraph, CommandError> {
+    let state_guard = state.lock().unwrap();
+
+    match state_guard.current_graph {
+        Some(ref graph_data) => Ok(CodeGraph::from_graph_data(graph_data).condensed()),
+        None => Err(no_graph_error()),
+    }
+}
+
+// Get the current graph without nodes below a degree or PageRank, keeping
+// nodes of `keep_types` and linking kept nodes across the ones dropped, for
+// exports and layouts that would otherwise be a hairball
+#[tauri::command]
+async fn get_pruned_graph(
+    min_degree: usize,
+    min_pagerank: f64,
+    keep_types: Vec<String>,
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<GraphData, CommandError> {
+    let state_guard = state.lock().unwrap();
+
+    match state_guard.current_graph {
+        Some(ref graph_data) => Ok(CodeGraph::from_graph_data(graph_data).prune_graph(min_degree, min_pagerank, &keep_types)),
+        None => Err(no_graph_error()),
+    }
+}
+
+// Get the HTTP endpoint inventory with each route's handler
+#[tauri::command]
+async fn get_route_map(
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<Vec<RouteEndpoint>, CommandError> {
+    let state_guard = state.lock().unwrap();
+
+    match state_guard.current_graph {
+        Some(ref graph_data) => Ok(CodeGraph::from_graph_data(graph_data).route_map()),
+        None => Err(no_graph_error()),
+    }
+}
+
+// Get the environment variables the codebase reads and where each is read
+#[tauri::command]
+async fn get_env_usage(
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<Vec<EnvVarUsage>, CommandError> {
+    let state_guard = state.lock().unwrap();
+
+    match state_guard.current_graph {
+        Some(ref graph_data) => Ok(CodeGraph::from_graph_data(graph_data).env_usage()),
+        None => Err(no_graph_error()),
+    }
+}
+
+// Suggest clusters of functions that could be extracted from large files and classes
+#[tauri::command]
+async fn get_refactoring_suggestions(
+    min_members: Option<usize>,
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<Vec<RefactoringSuggestion>, CommandError> {
+    let state_guard = state.lock().unwrap();
+
+    match state_guard.current_graph {
+        Some(ref graph_data) => Ok(extract_module_candidates(
+            &CodeGraph::from_graph_data(graph_data),
+            min_members.unwrap_or(DEFAULT_MIN_MEMBERS),
+        )),
+        None => Err(no_graph_error()),
+    }
+}
+
+// Get the transitive dependency slice of a node
+#[tauri::command]
+async fn slice_graph(
+    node_id: String,
+    direction: SliceDirection,
+    max_depth: Option<usize>,
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<GraphData, CommandError> {
+    let state_guard = state.lock().unwrap();
+
+    let graph = match state_guard.current_graph {
+        Some(ref graph_data) => CodeGraph::from_graph_data(graph_data),
+        None => return Err(no_graph_error()),
+    };
+
+    graph.slice(&node_id, direction, max_depth)
+        .ok_or_else(|| CommandError::from_message(ErrorCode::InvalidInput, Message::UnknownNode { id: node_id.to_string() }))
+}
+
+// Generate a PlantUML class diagram around a class, file or method
+#[tauri::command]
+async fn export_plantuml(
+    node_id: String,
+    depth: Option<usize>,
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<String, CommandError> {
+    let state_guard = state.lock().unwrap();
+
+    let graph = match state_guard.current_graph {
+        Some(ref graph_data) => CodeGraph::from_graph_data(graph_data),
+        None => return Err(no_graph_error()),
+    };
+
+    graph.plantuml_class_diagram(&node_id, depth.unwrap_or(DEFAULT_DIAGRAM_DEPTH))
+        .ok_or_else(|| CommandError::invalid_input(format!("No class found for node: {}", node_id)))
+}
+
+// Get the nodes and edges worth drawing for the current viewport
+#[tauri::command]
+async fn get_viewport_graph(
+    request: ViewportRequest,
+    state: tauri::State<'_, Mutex<AppState>>,
+) -> Result<ViewportGraph, CommandError> {
+    let mut state_guard = state.lock().unwrap();
+
+    let graph = match state_guard.current_graph {
+        Some(ref graph_data) => CodeGraph::from_graph_data(graph_data),
+        None => return Err(no_graph_error()),
+    };
+    let ranks = state_guard.viewport_ranks
+        .get_or_insert_with(|| Arc::new(graph.calculate_pagerank(20, 0.85)))
+        .clone();
+
+    Ok(graph.viewport(&request, &ranks))
+}
+
 // LOD Commands
 
 #[tauri::command]
 async fn get_graph_at_lod(
     lod: LodLevel,
     state: tauri::State<'_, Mutex<AppState>>,
-) -> Result<GraphPayload, String> {
+) -> Result<GraphPayload, CommandError> {
     
     let state_guard = state.lock().unwrap();
     
@@ -563,7 +1990,7 @@ async fn get_graph_at_lod(
     if let Some(graph_data) = &state_guard.current_graph {
         let snapshot = lod::GraphSnapshot::from_parsed_graph(graph_data);
         state_guard.lod_store.load_snapshot(snapshot)
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| engine_failure("Failed to load graph snapshot", e))?;
     }
     
     Ok(state_guard.lod_store.get_graph_at_lod(lod))
@@ -574,7 +2001,7 @@ async fn expand_node(
     node_id: String,
     target_lod: LodLevel,
     state: tauri::State<'_, Mutex<AppState>>,
-) -> Result<GraphDelta, String> {
+) -> Result<GraphDelta, CommandError> {
     
     let state_guard = state.lock().unwrap();
     Ok(state_guard.lod_store.expand_node(node_id, target_lod))
@@ -584,8 +2011,8 @@ async fn expand_node(
 async fn collapse_node(
     node_id: String,
     state: tauri::State<'_, Mutex<AppState>>,
-) -> Result<GraphDelta, String> {
-    println!("Collapsing node: {}", node_id);
+) -> Result<GraphDelta, CommandError> {
+    tracing::debug!("Collapsing node: {}", node_id);
     
     let state_guard = state.lock().unwrap();
     Ok(state_guard.lod_store.collapse_node(node_id))
@@ -597,13 +2024,86 @@ pub fn run() {
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .manage(Mutex::new(AppState::default()))
+        .setup(|app| {
+            let buffer = logging::init(app.handle());
+            app.manage(buffer);
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             parse_codebase,
             connect_neo4j,
+            run_cypher,
             generate_graph,
+            get_file_tree,
+            get_graph_page,
+            get_node_range,
+            get_edge_range,
             analyze_with_metrics,
+            set_graph_store,
+            save_graph,
+            sync_graph,
+            get_sync_log,
+            list_namespaces,
+            drop_namespace,
             get_saved_graph,
+            get_stored_slice,
             reset_app,
+            get_recent_logs,
+            set_diagnostics_stream,
+            get_analysis_limits,
+            set_analysis_limits,
+            get_include_dirs,
+            get_external_dependencies,
+            validate_config,
+            export_report_html,
+            export_metrics_table,
+            query_natural,
+            find_similar_nodes,
+            get_module_affinity,
+            get_node_metrics,
+            list_findings,
+            set_finding_state,
+            undo,
+            redo,
+            get_journal,
+            get_overrides,
+            set_overrides,
+            get_metrics_batch,
+            list_nodes,
+            recolor_graph,
+            resize_graph,
+            get_treemap,
+            get_documentation,
+            list_debt_markers,
+            get_concurrency_map,
+            get_license_inventory,
+            export_license_inventory,
+            get_metric_trend,
+            format_pr_summary,
+            get_evolution_timeline,
+            analyze_revision,
+            diff_revisions,
+            install_git_hook,
+            start_scheduled_analysis,
+            stop_scheduled_analysis,
+            get_scheduled_analysis,
+            set_include_dirs,
+            set_external_dependencies,
+            get_editor_settings,
+            set_editor_settings,
+            open_in_editor,
+            get_run_info,
+            get_graph_stats,
+            get_edge_stats,
+            get_components,
+            get_condensed_graph,
+            get_pruned_graph,
+            get_route_map,
+            get_env_usage,
+            get_refactoring_suggestions,
+            slice_graph,
+            export_plantuml,
+            get_viewport_graph,
             get_graph_at_lod,
             expand_node,
             collapse_node
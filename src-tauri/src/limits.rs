@@ -0,0 +1,486 @@
+use og_types::{AstNode, NodeType, ParsedFile};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+use std::path::Path;
+
+/// Size limits applied before the graph is built
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AnalysisLimits {
+    pub max_files: usize,
+    pub max_nodes: usize,
+    pub max_edges: usize,
+    /// What to do when the node or edge limit is exceeded
+    pub on_exceed: DegradationStrategy,
+    /// Largest file parsed in full, in bytes
+    pub max_file_bytes: u64,
+    /// What to do with files over `max_file_bytes` or that look minified
+    pub on_large_file: LargeFileStrategy,
+}
+
+impl Default for AnalysisLimits {
+    fn default() -> Self {
+        Self {
+            max_files: 20_000,
+            max_nodes: 200_000,
+            max_edges: 500_000,
+            on_exceed: DegradationStrategy::FileLevel,
+            max_file_bytes: 2 * 1024 * 1024,
+            on_large_file: LargeFileStrategy::FileNodeOnly,
+        }
+    }
+}
+
+/// How to handle a file too large to parse in full
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LargeFileStrategy {
+    /// Leave the file out of the graph
+    Skip,
+    /// Keep a file node without parsing its contents
+    #[default]
+    FileNodeOnly,
+    /// Parse the lines within the size cap; minified files keep only a file node
+    Truncate,
+}
+
+/// A file that was not parsed in full
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LargeFile {
+    pub path: String,
+    pub bytes: u64,
+    pub minified: bool,
+    pub handling: LargeFileStrategy,
+}
+
+/// Contents of a file to parse, cut down when it is too large or minified
+pub struct SourceText {
+    pub content: String,
+    /// Set when the file was not read in full
+    pub large: Option<LargeFile>,
+}
+
+/// How to shrink a graph that exceeds the configured limits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DegradationStrategy {
+    /// Keep only file and module nodes
+    FileLevel,
+    /// Keep structural nodes and the best-connected functions
+    SampleFunctions,
+    /// Fail the analysis
+    Reject,
+}
+
+/// Record of how a graph was reduced to fit the limits
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Degradation {
+    pub strategy: DegradationStrategy,
+    pub reason: String,
+    pub original_nodes: usize,
+    pub original_edges: usize,
+    pub kept_nodes: usize,
+    pub kept_edges: usize,
+}
+
+/// A limit was exceeded and could not be degraded around
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("{what} count {count} exceeds the limit of {limit}")]
+pub struct LimitExceeded {
+    pub what: &'static str,
+    pub count: usize,
+    pub limit: usize,
+    pub suggestions: Vec<String>,
+}
+
+impl LimitExceeded {
+    fn new(what: &'static str, count: usize, limit: usize) -> Self {
+        let mut suggestions = vec![
+            "Analyze a subdirectory instead of the repository root".to_string(),
+            "Exclude generated or vendored code with .omnigraphignore or .gitignore".to_string(),
+        ];
+        suggestions.push(match what {
+            "file" => "Raise maxFiles in the analysis limits".to_string(),
+            _ => "Switch to file-level analysis or raise the node and edge limits".to_string(),
+        });
+
+        Self {
+            what,
+            count,
+            limit,
+            suggestions,
+        }
+    }
+}
+
+impl AnalysisLimits {
+    /// Read a file for parsing, never more than `max_file_bytes` of it
+    ///
+    /// Files over the cap or that look minified are handled by
+    /// `on_large_file`; a file kept as a file node has empty content.
+    pub fn read_source(&self, path: &Path) -> std::io::Result<SourceText> {
+        let bytes = std::fs::metadata(path)?.len();
+        let oversized = bytes > self.max_file_bytes;
+        let content = if oversized {
+            let mut prefix = Vec::new();
+            std::fs::File::open(path)?
+                .take(self.max_file_bytes)
+                .read_to_end(&mut prefix)?;
+            // Cut at the last full line so a multi-byte character is never split
+            prefix.truncate(prefix.iter().rposition(|&byte| byte == b'\n').map_or(0, |end| end + 1));
+            String::from_utf8_lossy(&prefix).into_owned()
+        } else {
+            std::fs::read_to_string(path)?
+        };
+
+        let minified = og_parser::minified::is_minified(path, &content);
+        if !oversized && !minified {
+            return Ok(SourceText { content, large: None });
+        }
+
+        let handling = match self.on_large_file {
+            LargeFileStrategy::Truncate if minified => LargeFileStrategy::FileNodeOnly,
+            handling => handling,
+        };
+        tracing::warn!(path = %path.display(), bytes, minified, ?handling, "Not parsing file in full");
+        Ok(SourceText {
+            content: if handling == LargeFileStrategy::Truncate { content } else { String::new() },
+            large: Some(LargeFile {
+                path: path.display().to_string(),
+                bytes,
+                minified,
+                handling,
+            }),
+        })
+    }
+
+    /// Reject discovery results that are too large to parse
+    pub fn check_files(&self, count: usize) -> Result<(), LimitExceeded> {
+        if count > self.max_files {
+            return Err(LimitExceeded::new("file", count, self.max_files));
+        }
+        Ok(())
+    }
+
+    /// Running totals for a parse that shrinks files as soon as they exceed the limits
+    pub fn budget(&self) -> ParseBudget<'_> {
+        ParseBudget {
+            limits: self,
+            original_nodes: 0,
+            original_edges: 0,
+            kept_nodes: 0,
+            kept_edges: 0,
+            reason: None,
+        }
+    }
+
+    /// Shrink parsed files in place so the resulting graph fits the limits
+    pub fn enforce(&self, files: &mut [ParsedFile]) -> Result<Option<Degradation>, LimitExceeded> {
+        let mut budget = self.budget();
+        budget.admit(files, files.len())?;
+        budget.finish(files)
+    }
+
+    /// Why totals of `nodes` and `edges` are over the limits, None if they fit
+    fn over(&self, nodes: usize, edges: usize) -> Option<String> {
+        if nodes > self.max_nodes {
+            Some(format!("{} nodes exceed the limit of {}", nodes, self.max_nodes))
+        } else if edges > self.max_edges {
+            Some(format!("{} edges exceed the limit of {}", edges, self.max_edges))
+        } else {
+            None
+        }
+    }
+
+    /// Shrink files by `on_exceed`, failing when it rejects oversized graphs
+    fn degrade(&self, files: &mut [ParsedFile], nodes: usize, edges: usize) -> Result<(), LimitExceeded> {
+        match self.on_exceed {
+            DegradationStrategy::Reject => return Err(self.exceeded(nodes, edges)),
+            DegradationStrategy::FileLevel => keep_nodes(files, |node| is_structural_file(node.node_type)),
+            DegradationStrategy::SampleFunctions => self.sample_functions(files),
+        }
+        Ok(())
+    }
+
+    /// Keep every non-callable node plus the callables with the most relationships
+    fn sample_functions(&self, files: &mut [ParsedFile]) {
+        let fixed = files
+            .iter()
+            .flat_map(|file| &file.nodes)
+            .filter(|node| !is_callable(node.node_type))
+            .count();
+        let budget = self.max_nodes.saturating_sub(fixed);
+
+        let mut degree: HashMap<&str, usize> = HashMap::new();
+        for rel in files.iter().flat_map(|file| &file.relationships) {
+            *degree.entry(rel.source.as_str()).or_insert(0) += 1;
+            *degree.entry(rel.target.as_str()).or_insert(0) += 1;
+        }
+
+        let mut callables: Vec<(&str, usize)> = files
+            .iter()
+            .flat_map(|file| &file.nodes)
+            .filter(|node| is_callable(node.node_type))
+            .map(|node| (node.id.as_str(), degree.get(node.id.as_str()).copied().unwrap_or(0)))
+            .collect();
+        // Ties broken by id so repeated runs keep the same sample
+        callables.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+
+        let sampled: HashSet<String> = callables
+            .into_iter()
+            .take(budget)
+            .map(|(id, _)| id.to_string())
+            .collect();
+
+        keep_nodes(files, |node| !is_callable(node.node_type) || sampled.contains(&node.id));
+    }
+
+    fn exceeded(&self, nodes: usize, edges: usize) -> LimitExceeded {
+        if nodes > self.max_nodes {
+            LimitExceeded::new("node", nodes, self.max_nodes)
+        } else {
+            LimitExceeded::new("edge", edges, self.max_edges)
+        }
+    }
+}
+
+/// Node and edge totals of a parse in progress
+///
+/// Files are admitted in batches as they are parsed. Once the files parsed so
+/// far exceed the node or edge limit, they are shrunk by `on_exceed` and so is
+/// every later batch, so a codebase far over the limits is never held in
+/// memory in full. `Reject` stops the parse at the first batch over them.
+pub struct ParseBudget<'a> {
+    limits: &'a AnalysisLimits,
+    /// Totals of everything parsed, before any of it was shrunk
+    original_nodes: usize,
+    original_edges: usize,
+    /// Totals after the last batch was admitted
+    kept_nodes: usize,
+    kept_edges: usize,
+    /// Why the files are being shrunk, once they are
+    reason: Option<String>,
+}
+
+impl ParseBudget<'_> {
+    /// Count the last `batch` of `files`, which were just parsed, and shrink
+    /// `files` when the totals are over the limits
+    pub fn admit(&mut self, files: &mut [ParsedFile], batch: usize) -> Result<(), LimitExceeded> {
+        let (nodes, edges) = totals(&files[files.len() - batch..]);
+        self.original_nodes += nodes;
+        self.original_edges += edges;
+        if self.reason.is_none() {
+            self.reason = self.limits.over(self.original_nodes, self.original_edges);
+        }
+        if self.reason.is_some() {
+            self.limits.degrade(files, self.original_nodes, self.original_edges)?;
+        }
+        (self.kept_nodes, self.kept_edges) = totals(files);
+        Ok(())
+    }
+
+    /// Shrink `files` once more, for nodes and edges added since the last
+    /// batch, and report how the graph was reduced to fit the limits
+    pub fn finish(mut self, files: &mut [ParsedFile]) -> Result<Option<Degradation>, LimitExceeded> {
+        let (nodes, edges) = totals(files);
+        self.original_nodes += nodes.saturating_sub(self.kept_nodes);
+        self.original_edges += edges.saturating_sub(self.kept_edges);
+        let limits = self.limits;
+        let Some(reason) = self.reason.or_else(|| limits.over(self.original_nodes, self.original_edges)) else {
+            return Ok(None);
+        };
+
+        limits.degrade(files, self.original_nodes, self.original_edges)?;
+        let (kept_nodes, kept_edges) = totals(files);
+        if kept_nodes > limits.max_nodes || kept_edges > limits.max_edges {
+            return Err(limits.exceeded(kept_nodes, kept_edges));
+        }
+
+        tracing::warn!(
+            strategy = ?limits.on_exceed,
+            original_nodes = self.original_nodes,
+            kept_nodes,
+            "Graph exceeds analysis limits, degrading: {}",
+            reason
+        );
+
+        Ok(Some(Degradation {
+            strategy: limits.on_exceed,
+            reason,
+            original_nodes: self.original_nodes,
+            original_edges: self.original_edges,
+            kept_nodes,
+            kept_edges,
+        }))
+    }
+}
+
+/// Drop nodes failing `keep` along with relationships that no longer resolve
+fn keep_nodes(files: &mut [ParsedFile], keep: impl Fn(&AstNode) -> bool) {
+    let mut removed: HashSet<String> = HashSet::new();
+    for file in files.iter_mut() {
+        file.nodes.retain(|node| {
+            let kept = keep(node);
+            if !kept {
+                removed.insert(node.id.clone());
+            }
+            kept
+        });
+    }
+
+    for file in files.iter_mut() {
+        file.relationships
+            .retain(|rel| !removed.contains(&rel.source) && !removed.contains(&rel.target));
+        for node in &mut file.nodes {
+            node.children.retain(|child| !removed.contains(child));
+        }
+    }
+}
+
+fn totals(files: &[ParsedFile]) -> (usize, usize) {
+    files.iter().fold((0, 0), |(nodes, edges), file| {
+        (nodes + file.nodes.len(), edges + file.relationships.len())
+    })
+}
+
+fn is_structural_file(node_type: NodeType) -> bool {
+    matches!(node_type, NodeType::File | NodeType::Module)
+}
+
+fn is_callable(node_type: NodeType) -> bool {
+    matches!(node_type, NodeType::Function | NodeType::Method)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use og_types::{Language, Relationship, RelationshipType};
+
+    fn node(id: &str, node_type: NodeType) -> AstNode {
+        AstNode {
+            id: id.to_string(),
+            node_type,
+            name: id.to_string(),
+            start_line: 1,
+            end_line: 1,
+            children: vec![],
+        }
+    }
+
+    fn calls(source: &str, target: &str) -> Relationship {
+        Relationship {
+            source: source.to_string(),
+            target: target.to_string(),
+            relationship_type: RelationshipType::Calls,
+        }
+    }
+
+    // A file node with `functions` functions, each calling the one before it
+    fn file(name: &str, functions: usize) -> ParsedFile {
+        let file_id = format!("file:{}", name);
+        let mut nodes = vec![node(&file_id, NodeType::File)];
+        let mut relationships = Vec::new();
+        for i in 0..functions {
+            let id = format!("function:{}:f{}", name, i);
+            nodes.push(node(&id, NodeType::Function));
+            if i > 0 {
+                relationships.push(calls(&id, &format!("function:{}:f{}", name, i - 1)));
+            }
+        }
+        ParsedFile {
+            path: name.into(),
+            language: Language::Python,
+            nodes,
+            relationships,
+            metrics: Default::default(),
+        }
+    }
+
+    fn limits(max_nodes: usize, on_exceed: DegradationStrategy) -> AnalysisLimits {
+        AnalysisLimits {
+            max_nodes,
+            on_exceed,
+            ..AnalysisLimits::default()
+        }
+    }
+
+    #[test]
+    fn test_enforce_within_limits_keeps_everything() {
+        let mut files = vec![file("a.py", 3)];
+        assert!(limits(10, DegradationStrategy::Reject).enforce(&mut files).unwrap().is_none());
+        assert_eq!(files[0].nodes.len(), 4);
+    }
+
+    #[test]
+    fn test_enforce_file_level_keeps_file_nodes() {
+        let mut files = vec![file("a.py", 3), file("b.py", 3)];
+        let degradation = limits(5, DegradationStrategy::FileLevel).enforce(&mut files).unwrap().unwrap();
+
+        assert_eq!(degradation.strategy, DegradationStrategy::FileLevel);
+        assert_eq!((degradation.original_nodes, degradation.original_edges), (8, 4));
+        assert_eq!((degradation.kept_nodes, degradation.kept_edges), (2, 0));
+        assert!(files.iter().all(|file| file.nodes.len() == 1 && file.relationships.is_empty()));
+    }
+
+    #[test]
+    fn test_enforce_reject_fails() {
+        let mut files = vec![file("a.py", 6)];
+        let error = limits(5, DegradationStrategy::Reject).enforce(&mut files).unwrap_err();
+        assert_eq!((error.what, error.count, error.limit), ("node", 7, 5));
+        assert!(!error.suggestions.is_empty());
+    }
+
+    #[test]
+    fn test_sample_functions_keeps_best_connected() {
+        // f1 and f2 each have two calls; f0 and f3 have one
+        let mut files = vec![file("a.py", 4)];
+        let limits = limits(3, DegradationStrategy::SampleFunctions);
+        limits.sample_functions(&mut files);
+
+        let ids: Vec<&str> = files[0].nodes.iter().map(|node| node.id.as_str()).collect();
+        assert_eq!(ids, vec!["file:a.py", "function:a.py:f1", "function:a.py:f2"]);
+        assert_eq!(files[0].relationships.len(), 1);
+    }
+
+    #[test]
+    fn test_sample_functions_breaks_ties_by_id() {
+        let mut files = vec![file("b.py", 1), file("a.py", 1)];
+        limits(3, DegradationStrategy::SampleFunctions).sample_functions(&mut files);
+
+        let kept: Vec<&str> = files.iter().flat_map(|file| &file.nodes).map(|node| node.id.as_str()).collect();
+        assert_eq!(kept, vec!["file:b.py", "file:a.py", "function:a.py:f0"]);
+    }
+
+    #[test]
+    fn test_budget_degrades_while_parsing() {
+        let limits = limits(5, DegradationStrategy::FileLevel);
+        let mut budget = limits.budget();
+        let mut files = vec![file("a.py", 3)];
+        budget.admit(&mut files, 1).unwrap();
+        assert_eq!(files[0].nodes.len(), 4, "the first batch fits");
+
+        files.push(file("b.py", 3));
+        budget.admit(&mut files, 1).unwrap();
+        assert!(files.iter().all(|file| file.nodes.len() == 1), "both batches shrink once over");
+
+        files.push(file("c.py", 3));
+        budget.admit(&mut files, 1).unwrap();
+        assert_eq!(files[2].nodes.len(), 1, "later batches shrink as they arrive");
+
+        let degradation = budget.finish(&mut files).unwrap().unwrap();
+        assert_eq!((degradation.original_nodes, degradation.kept_nodes), (12, 3));
+    }
+
+    #[test]
+    fn test_budget_reject_stops_at_first_batch_over() {
+        let limits = limits(5, DegradationStrategy::Reject);
+        let mut budget = limits.budget();
+        let mut files = vec![file("a.py", 3)];
+        budget.admit(&mut files, 1).unwrap();
+        files.push(file("b.py", 3));
+        assert_eq!(budget.admit(&mut files, 1).unwrap_err().count, 8);
+    }
+}
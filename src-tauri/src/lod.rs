@@ -152,20 +152,20 @@ impl GraphSnapshot {
         }
         
         // Process edges to extract imports and calls
-        println!("[LOD] Processing {} links", graph_data.links.len());
+        tracing::debug!("[LOD] Processing {} links", graph_data.links.len());
         
         // Create a mapping from file nodes to their IDs for import resolution
         let file_id_map: HashMap<String, String> = files.iter()
             .map(|(id, file)| (file.label.clone(), id.clone()))
             .collect();
-        println!("[LOD] File ID map: {:?}", file_id_map);
+        tracing::debug!("[LOD] File ID map: {:?}", file_id_map);
         
         // Collect import relationships to add later
         let mut file_import_relationships: Vec<(String, String)> = Vec::new();
         let mut func_call_relationships: Vec<(String, String)> = Vec::new();
         
         for link in &graph_data.links {
-            println!("[LOD] Link: {} -> {} (type: {})", link.source, link.target, link.link_type);
+            tracing::debug!("[LOD] Link: {} -> {} (type: {})", link.source, link.target, link.link_type);
             match link.link_type.as_str() {
                 "imports" | "includes" => {
                     if files.contains_key(&link.source) {
@@ -174,7 +174,7 @@ impl GraphSnapshot {
                         // Check if target exists in our files
                         if files.contains_key(&link.target) {
                             file_import_relationships.push((link.source.clone(), link.target.clone()));
-                            println!("[LOD] Added import: {} imports {}", link.source, link.target);
+                            tracing::debug!("[LOD] Added import: {} imports {}", link.source, link.target);
                         } else {
                             // Try to find the file by name
                             // Extract filename from include path if it looks like a path
@@ -188,9 +188,9 @@ impl GraphSnapshot {
                             
                             if let Some(target_id) = file_id_map.get(&target_name) {
                                 file_import_relationships.push((link.source.clone(), target_id.clone()));
-                                println!("[LOD] Resolved import: {} imports {} (via {})", link.source, target_id, target_name);
+                                tracing::debug!("[LOD] Resolved import: {} imports {} (via {})", link.source, target_id, target_name);
                             } else {
-                                println!("[LOD] Could not resolve import target: {}", link.target);
+                                tracing::debug!("[LOD] Could not resolve import target: {}", link.target);
                             }
                         }
                     }
@@ -198,7 +198,7 @@ impl GraphSnapshot {
                 "calls" => {
                     if functions.contains_key(&link.source) {
                         func_call_relationships.push((link.source.clone(), link.target.clone()));
-                        println!("[LOD] Added call: {} calls {}", link.source, link.target);
+                        tracing::debug!("[LOD] Added call: {} calls {}", link.source, link.target);
                     }
                 }
                 "contains" => {
@@ -206,7 +206,7 @@ impl GraphSnapshot {
                     // But we can use them to verify our hierarchy is correct
                 }
                 _ => {
-                    println!("[LOD] Unknown link type: {}", link.link_type);
+                    tracing::warn!("[LOD] Unknown link type: {}", link.link_type);
                 }
             }
         }
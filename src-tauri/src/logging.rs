@@ -0,0 +1,55 @@
+use og_utils::{init_logging_with_sinks, open_session_log, LogBuffer};
+use std::sync::Arc;
+use tauri::{Emitter, Manager};
+
+/// Default tracing filter for the desktop app
+const DEFAULT_FILTER: &str = "info,og_parser=info,og_analytics=info,omnigraph_tauri_lib=debug";
+
+/// Number of recent entries kept in memory for `get_recent_logs`
+const LOG_BUFFER_CAPACITY: usize = 2000;
+
+/// Number of per-session log files kept on disk
+const SESSION_LOGS_TO_KEEP: usize = 10;
+
+/// Event name used for the live diagnostics stream
+pub const DIAGNOSTICS_EVENT: &str = "diagnostics-log";
+
+/// Install the tracing subscriber with stdout, session file and in-memory sinks
+pub fn init(app: &tauri::AppHandle) -> Arc<LogBuffer> {
+    let buffer = Arc::new(LogBuffer::new(LOG_BUFFER_CAPACITY));
+
+    let session_log = app
+        .path()
+        .app_log_dir()
+        .map_err(|e| e.to_string())
+        .and_then(|dir| open_session_log(&dir, SESSION_LOGS_TO_KEEP).map_err(|e| e.to_string()));
+
+    let (log_file, session_log) = match session_log {
+        Ok((path, file)) => (Some(file), Ok(path)),
+        Err(e) => (None, Err(e)),
+    };
+
+    if !init_logging_with_sinks(DEFAULT_FILTER, log_file, buffer.clone()) {
+        tracing::warn!("Tracing subscriber already installed, in-app logs disabled");
+    }
+
+    // Reported after init so the outcome lands in the logs themselves
+    match session_log {
+        Ok(path) => tracing::info!(path = %path.display(), "Writing session log"),
+        Err(e) => tracing::warn!("Failed to open session log: {}", e),
+    }
+
+    buffer
+}
+
+/// Start or stop forwarding log entries to the frontend
+pub fn set_diagnostics_stream(app: &tauri::AppHandle, buffer: &LogBuffer, enabled: bool) {
+    if enabled {
+        let app = app.clone();
+        buffer.set_listener(Some(Box::new(move |entry| {
+            app.emit(DIAGNOSTICS_EVENT, entry).ok();
+        })));
+    } else {
+        buffer.set_listener(None);
+    }
+}
@@ -2,5 +2,9 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(code) = omnigraph_tauri_lib::run_cli(&args) {
+        std::process::exit(code);
+    }
     omnigraph_tauri_lib::run()
 }
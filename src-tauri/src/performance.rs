@@ -0,0 +1,122 @@
+use crate::run_info::RunInfo;
+use og_analytics::MetricTiming;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Where analysis time went, per pipeline stage and per metric
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PerformanceReport {
+    pub total_ms: f64,
+    /// Pipeline stages with their share of total time
+    pub stages: Vec<TimingEntry>,
+    /// Metrics with their share of analytics time
+    pub metrics: Vec<TimingEntry>,
+    /// Human-readable notes on the biggest costs
+    pub highlights: Vec<String>,
+}
+
+/// Duration of one stage, metric, or metric step
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimingEntry {
+    pub name: String,
+    pub duration_ms: f64,
+    /// Fraction of the parent total, between 0 and 1
+    pub share: f64,
+    pub steps: Vec<TimingEntry>,
+}
+
+impl PerformanceReport {
+    /// Build the metric section from analytics timings
+    pub fn from_metric_timings(timings: &[MetricTiming]) -> Self {
+        let analytics_ms: f64 = timings.iter().map(|t| millis(t.duration)).sum();
+
+        let metrics = timings
+            .iter()
+            .map(|timing| TimingEntry {
+                name: timing.name.clone(),
+                duration_ms: millis(timing.duration),
+                share: share(millis(timing.duration), analytics_ms),
+                steps: timing
+                    .steps
+                    .iter()
+                    .map(|(step, duration)| TimingEntry {
+                        name: step.clone(),
+                        duration_ms: millis(*duration),
+                        share: share(millis(*duration), analytics_ms),
+                        steps: Vec::new(),
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        Self {
+            metrics,
+            ..Default::default()
+        }
+    }
+
+    /// Fill in pipeline stages from run metadata and summarize the hotspots
+    pub fn record_stages(&mut self, run_info: &RunInfo) {
+        self.total_ms = run_info.total_duration_ms as f64;
+        self.stages = run_info
+            .stage_durations_ms
+            .iter()
+            .map(|stage| TimingEntry {
+                name: stage.stage.clone(),
+                duration_ms: stage.duration_ms as f64,
+                share: share(stage.duration_ms as f64, self.total_ms),
+                steps: Vec::new(),
+            })
+            .collect();
+
+        self.highlights.clear();
+        if let Some(stage) = slowest(&self.stages) {
+            self.highlights.push(format!(
+                "{} took {:.0}% of total time",
+                stage.name,
+                stage.share * 100.0
+            ));
+        }
+
+        // Prefer the individual step when a metric reports them
+        let steps: Vec<TimingEntry> = self
+            .metrics
+            .iter()
+            .flat_map(|metric| {
+                if metric.steps.is_empty() {
+                    vec![metric.clone()]
+                } else {
+                    metric.steps.clone()
+                }
+            })
+            .collect();
+        if let Some(step) = slowest(&steps) {
+            self.highlights.push(format!(
+                "{} took {:.0}% of analysis time",
+                step.name,
+                step.share * 100.0
+            ));
+        }
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+fn share(part: f64, total: f64) -> f64 {
+    if total > 0.0 {
+        (part / total).clamp(0.0, 1.0)
+    } else {
+        0.0
+    }
+}
+
+fn slowest(entries: &[TimingEntry]) -> Option<&TimingEntry> {
+    entries
+        .iter()
+        .filter(|entry| entry.duration_ms > 0.0)
+        .max_by(|a, b| a.duration_ms.total_cmp(&b.duration_ms))
+}
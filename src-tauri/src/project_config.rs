@@ -0,0 +1,400 @@
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use og_analytics::{AlertRules, MetricWeights, PathWeighting, DEFAULT_METRICS};
+use og_graph::check::CheckRules;
+use og_graph::direction::AnalysisMode;
+use og_graph::external::ExternalDependencies;
+use og_graph::graph::ParallelEdgePolicy;
+use og_parser::lsp::LspSettings;
+use og_types::{Language, NormalizationMode};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::limits::{AnalysisLimits, LargeFileStrategy};
+
+/// Location of the project config file, relative to the codebase root
+pub const CONFIG_PATH: &str = ".omnigraph/config.toml";
+
+/// Analysis settings read from `.omnigraph/config.toml`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProjectConfig {
+    /// Globs a file must match to be analyzed, all files when empty
+    pub include: Vec<String>,
+    /// Globs of files to skip, applied after `include`
+    pub exclude: Vec<String>,
+    /// Walk into symlinked files and directories, which are skipped by default
+    pub follow_symlinks: bool,
+    /// Languages switched on or off by name, others stay on
+    pub languages: BTreeMap<String, bool>,
+    /// Metrics to compute, all default metrics when unset
+    pub metrics: Option<Vec<String>>,
+    pub thresholds: Thresholds,
+    pub weights: MetricWeights,
+    /// How node metrics are scaled for display: min_max, percentile or z_score
+    pub normalization: NormalizationMode,
+    /// Seed for sampled betweenness on large graphs, a fixed default when unset
+    pub betweenness_seed: Option<u64>,
+    /// Edge lengths for closeness and betweenness, hop counts when unset
+    pub path_weighting: PathWeighting,
+    /// Direction each metric reads edges in: directed, undirected or reverse
+    pub modes: BTreeMap<String, AnalysisMode>,
+    /// How third-party imports appear: drop, package or import; the app's setting when unset
+    pub external_dependencies: Option<ExternalDependencies>,
+    /// How the weights of repeated relationships of one type between two nodes
+    /// are folded into one edge: aggregate-sum, aggregate-max or keep-first
+    pub parallel_edges: ParallelEdgePolicy,
+    /// Edge types community detection clusters on, such as ["IMPORTS"]; all when empty
+    pub community_edge_types: Vec<String>,
+    /// Leave edges from a node to itself out of every metric
+    pub drop_self_loops: bool,
+    /// Edge types each metric ignores, such as { centrality = ["CONTAINS"] }
+    pub excluded_edge_types: BTreeMap<String, Vec<String>>,
+    /// Report smells and risk in generated files like in hand-written code
+    pub analyze_generated: bool,
+    /// Read license headers and license files for the license inventory
+    pub scan_licenses: bool,
+    /// Globs of files that are entered from outside the codebase; files no
+    /// dependency path from them reaches are reported as unreachable
+    pub entry_points: Vec<String>,
+    /// Architectural layers; dependencies on layers a layer may not use are reported
+    pub layers: Vec<LayerRule>,
+    /// Language servers that refine call and reference edges
+    pub lsp: LspSettings,
+    /// Rules the commit and push hooks enforce on changed files
+    pub check: CheckRules,
+    /// Thresholds that raise an alert after each analysis
+    pub alerts: AlertRules,
+}
+
+/// Size limits that override the application defaults
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Thresholds {
+    pub max_files: Option<usize>,
+    pub max_nodes: Option<usize>,
+    pub max_edges: Option<usize>,
+    /// Largest file parsed in full, in bytes
+    pub max_file_bytes: Option<u64>,
+    /// What to do with larger or minified files: skip, file-node-only or truncate
+    pub on_large_file: Option<LargeFileStrategy>,
+}
+
+/// An architectural layer and the layers it may depend on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LayerRule {
+    pub name: String,
+    /// Globs of the files in this layer
+    pub paths: Vec<String>,
+    #[serde(default)]
+    pub may_depend_on: Vec<String>,
+}
+
+/// A problem found while validating a project config
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigProblem {
+    /// Config key the problem is in, empty for the file as a whole
+    pub field: String,
+    pub message: String,
+}
+
+impl ConfigProblem {
+    fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Result of validating the config file of a codebase
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigValidation {
+    pub path: PathBuf,
+    /// False when the codebase has no config file and defaults apply
+    pub found: bool,
+    pub problems: Vec<ConfigProblem>,
+}
+
+impl ProjectConfig {
+    /// Parse a config file's contents
+    pub fn parse(contents: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(contents)
+    }
+
+    /// Load the config of a codebase, falling back to defaults if it is missing or unparsable
+    pub fn load(base_path: &Path) -> Self {
+        let path = base_path.join(CONFIG_PATH);
+        let Ok(contents) = std::fs::read_to_string(&path) else {
+            return Self::default();
+        };
+
+        match Self::parse(&contents) {
+            Ok(config) => {
+                tracing::info!("Loaded project config from {}", path.display());
+                for problem in config.problems() {
+                    tracing::warn!("{}: {}: {}", path.display(), problem.field, problem.message);
+                }
+                config
+            }
+            Err(e) => {
+                tracing::warn!("Ignoring invalid {}: {}", path.display(), e);
+                Self::default()
+            }
+        }
+    }
+
+    /// Check the config file of a codebase without loading it into an engine
+    pub fn validate(base_path: &Path) -> ConfigValidation {
+        let path = base_path.join(CONFIG_PATH);
+        let (found, problems) = match std::fs::read_to_string(&path) {
+            Ok(contents) => match Self::parse(&contents) {
+                Ok(config) => (true, config.problems()),
+                Err(e) => (true, vec![ConfigProblem::new("", e.message())]),
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => (false, Vec::new()),
+            Err(e) => (true, vec![ConfigProblem::new("", e.to_string())]),
+        };
+
+        ConfigValidation { path, found, problems }
+    }
+
+    /// Problems that parsing alone does not catch
+    pub fn problems(&self) -> Vec<ConfigProblem> {
+        let mut problems = Vec::new();
+
+        for (field, globs) in [
+            ("include", &self.include),
+            ("exclude", &self.exclude),
+            ("entry_points", &self.entry_points),
+        ] {
+            check_globs(field, globs, &mut problems);
+        }
+
+        for name in self.languages.keys() {
+            if !Language::ALL.iter().any(|language| language.as_str() == name) {
+                problems.push(ConfigProblem::new(
+                    format!("languages.{}", name),
+                    format!("Unknown language, expected one of: {}", language_names().join(", ")),
+                ));
+            }
+        }
+
+        if let Some(metrics) = &self.metrics {
+            for metric in metrics {
+                if !DEFAULT_METRICS.contains(&metric.as_str()) {
+                    problems.push(ConfigProblem::new(
+                        "metrics",
+                        format!("Unknown metric {:?}, expected one of: {}", metric, DEFAULT_METRICS.join(", ")),
+                    ));
+                }
+            }
+        }
+
+        for metric in self.modes.keys() {
+            if !DEFAULT_METRICS.contains(&metric.as_str()) {
+                problems.push(ConfigProblem::new(
+                    format!("modes.{}", metric),
+                    format!("Unknown metric, expected one of: {}", DEFAULT_METRICS.join(", ")),
+                ));
+            }
+        }
+
+        for (field, limit) in [
+            ("thresholds.max_files", self.thresholds.max_files),
+            ("thresholds.max_nodes", self.thresholds.max_nodes),
+            ("thresholds.max_edges", self.thresholds.max_edges),
+        ] {
+            if limit == Some(0) {
+                problems.push(ConfigProblem::new(field, "Must be greater than zero"));
+            }
+        }
+        if self.thresholds.max_file_bytes == Some(0) {
+            problems.push(ConfigProblem::new("thresholds.max_file_bytes", "Must be greater than zero"));
+        }
+
+        for (edge_type, cost) in &self.path_weighting.type_costs {
+            if !cost.is_finite() || *cost <= 0.0 {
+                problems.push(ConfigProblem::new(
+                    format!("path_weighting.type_costs.{}", edge_type),
+                    "Costs must be positive numbers",
+                ));
+            }
+        }
+
+        let weights = &self.weights;
+        for (group, values) in [
+            ("importance", [weights.importance_pagerank, weights.importance_degree, weights.importance_betweenness]),
+            ("risk", [weights.risk_complexity, weights.risk_coupling, weights.risk_churn]),
+            ("chokepoint", [weights.chokepoint_betweenness, weights.chokepoint_clustering, weights.chokepoint_degree]),
+            ("payoff", [weights.payoff_risk, weights.payoff_importance, weights.payoff_coverage]),
+        ] {
+            if values.iter().any(|value| !value.is_finite() || *value < 0.0) {
+                problems.push(ConfigProblem::new(
+                    format!("weights.{}_*", group),
+                    "Weights must be non-negative numbers",
+                ));
+                continue;
+            }
+            let sum: f64 = values.iter().sum();
+            if (sum - 1.0).abs() > 1e-6 {
+                problems.push(ConfigProblem::new(
+                    format!("weights.{}_*", group),
+                    format!("Weights sum to {:.3}, expected 1.0", sum),
+                ));
+            }
+        }
+
+        let mut layer_names = HashSet::new();
+        for (i, layer) in self.layers.iter().enumerate() {
+            let field = format!("layers[{}]", i);
+            if layer.name.is_empty() {
+                problems.push(ConfigProblem::new(&field, "Layer has no name"));
+            } else if !layer_names.insert(layer.name.as_str()) {
+                problems.push(ConfigProblem::new(&field, format!("Duplicate layer {:?}", layer.name)));
+            }
+            if layer.paths.is_empty() {
+                problems.push(ConfigProblem::new(format!("{}.paths", field), "Layer matches no paths"));
+            }
+            check_globs(&format!("{}.paths", field), &layer.paths, &mut problems);
+        }
+        for (i, layer) in self.layers.iter().enumerate() {
+            for dependency in &layer.may_depend_on {
+                if !layer_names.contains(dependency.as_str()) {
+                    problems.push(ConfigProblem::new(
+                        format!("layers[{}].may_depend_on", i),
+                        format!("Unknown layer {:?}", dependency),
+                    ));
+                }
+            }
+        }
+
+        for (name, command) in &self.lsp.servers {
+            let field = format!("lsp.servers.{}", name);
+            if !Language::ALL.iter().any(|language| language.as_str() == name) {
+                problems.push(ConfigProblem::new(
+                    field,
+                    format!("Unknown language, expected one of: {}", language_names().join(", ")),
+                ));
+            } else if command.first().is_none_or(|program| program.trim().is_empty()) {
+                problems.push(ConfigProblem::new(field, "Server command is empty"));
+            }
+        }
+        if self.lsp.timeout_ms == 0 {
+            problems.push(ConfigProblem::new("lsp.timeout_ms", "Must be greater than zero"));
+        }
+
+        if self.alerts.max_risk.is_some_and(|risk| !(0.0..=1.0).contains(&risk)) {
+            problems.push(ConfigProblem::new("alerts.max_risk", "Must be between 0 and 1"));
+        }
+
+        problems
+    }
+
+    /// Override the limits this config sets thresholds for
+    pub fn apply_thresholds(&self, mut limits: AnalysisLimits) -> AnalysisLimits {
+        if let Some(max_files) = self.thresholds.max_files {
+            limits.max_files = max_files;
+        }
+        if let Some(max_nodes) = self.thresholds.max_nodes {
+            limits.max_nodes = max_nodes;
+        }
+        if let Some(max_edges) = self.thresholds.max_edges {
+            limits.max_edges = max_edges;
+        }
+        if let Some(max_file_bytes) = self.thresholds.max_file_bytes {
+            limits.max_file_bytes = max_file_bytes;
+        }
+        if let Some(on_large_file) = self.thresholds.on_large_file {
+            limits.on_large_file = on_large_file;
+        }
+        limits
+    }
+
+    /// Whether files in the given language should be analyzed
+    pub fn language_enabled(&self, language: Language) -> bool {
+        self.languages.get(language.as_str()).copied().unwrap_or(true)
+    }
+
+    /// Matcher for the include and exclude globs
+    pub fn file_filter(&self) -> FileFilter {
+        FileFilter {
+            include: (!self.include.is_empty()).then(|| build_globs(&self.include)),
+            exclude: build_globs(&self.exclude),
+        }
+    }
+
+    /// Layer of each of `files` the layer globs match, by the first matching layer
+    ///
+    /// Globs are matched against paths relative to `base_path`.
+    pub fn layers_of<'a>(&self, base_path: &Path, files: impl IntoIterator<Item = &'a str>) -> HashMap<String, String> {
+        let layers: Vec<(&str, GlobSet)> = self
+            .layers
+            .iter()
+            .map(|layer| (layer.name.as_str(), build_globs(&layer.paths)))
+            .collect();
+        files
+            .into_iter()
+            .filter_map(|file| {
+                let relative = Path::new(file).strip_prefix(base_path).unwrap_or(Path::new(file));
+                let (name, _) = layers.iter().find(|(_, globs)| globs.is_match(relative))?;
+                Some((file.to_string(), name.to_string()))
+            })
+            .collect()
+    }
+
+    /// Layers each layer may depend on besides itself
+    pub fn allowed_layers(&self) -> HashMap<String, HashSet<String>> {
+        self.layers
+            .iter()
+            .map(|layer| (layer.name.clone(), layer.may_depend_on.iter().cloned().collect()))
+            .collect()
+    }
+
+    /// Those of `files` the entry point globs match, relative to `base_path`
+    pub fn entry_files<'a>(&self, base_path: &Path, files: impl IntoIterator<Item = &'a str>) -> HashSet<String> {
+        let globs = build_globs(&self.entry_points);
+        files
+            .into_iter()
+            .filter(|file| globs.is_match(Path::new(file).strip_prefix(base_path).unwrap_or(Path::new(file))))
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+/// Include and exclude globs matched against paths relative to the codebase root
+pub struct FileFilter {
+    include: Option<GlobSet>,
+    exclude: GlobSet,
+}
+
+impl FileFilter {
+    pub fn matches(&self, relative_path: &Path) -> bool {
+        self.include.as_ref().is_none_or(|include| include.is_match(relative_path))
+            && !self.exclude.is_match(relative_path)
+    }
+}
+
+fn language_names() -> Vec<&'static str> {
+    Language::ALL.iter().map(Language::as_str).collect()
+}
+
+fn check_globs(field: &str, globs: &[String], problems: &mut Vec<ConfigProblem>) {
+    for glob in globs {
+        if let Err(e) = Glob::new(glob) {
+            problems.push(ConfigProblem::new(field, e.to_string()));
+        }
+    }
+}
+
+/// Compile globs, skipping invalid ones since `problems` reports them
+fn build_globs(globs: &[String]) -> GlobSet {
+    let mut builder = GlobSetBuilder::new();
+    for glob in globs.iter().filter_map(|glob| Glob::new(glob).ok()) {
+        builder.add(glob);
+    }
+    builder.build().unwrap_or_else(|_| GlobSet::empty())
+}
@@ -0,0 +1,250 @@
+use crate::engine_v2::{AnalysisSummary, AnalyzedGraph, Engine};
+use crate::history::MetricSnapshot;
+use crate::limits::AnalysisLimits;
+use anyhow::{bail, Context, Result};
+use og_analytics::analysis::pr_summary::AnalysisSide;
+use og_analytics::StructuralDiff;
+use og_graph::graph::CodeGraph;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Analysis of a commit or branch other than the checked-out one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RevisionAnalysis {
+    /// Revision as requested, such as a branch name
+    pub rev: String,
+    /// Commit the revision resolved to
+    pub commit: String,
+    /// Snapshot file the metrics were stored in, None if there were no metrics
+    pub snapshot: Option<PathBuf>,
+    pub summary: AnalysisSummary,
+}
+
+fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    run_git(Command::new("git"), dir, args)
+}
+
+/// Run git on a linked worktree, which keeps its own index
+///
+/// Git hooks may run with GIT_INDEX_FILE pointing at the index being
+/// committed, which must not be used or overwritten by the worktree.
+fn worktree_git(dir: &Path, args: &[&str]) -> Result<String> {
+    let mut command = Command::new("git");
+    command.env_remove("GIT_INDEX_FILE");
+    run_git(command, dir, args)
+}
+
+fn run_git(mut command: Command, dir: &Path, args: &[&str]) -> Result<String> {
+    let output = command
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .context("failed to run git")?;
+    if !output.status.success() {
+        bail!("git {} failed: {}", args[0], String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// A temporary checkout of one commit or of the staged files, removed when dropped
+struct Worktree {
+    repo: PathBuf,
+    dir: PathBuf,
+    /// Whether git tracks the checkout as a linked worktree
+    linked: bool,
+}
+
+fn temp_checkout_dir(label: &str) -> PathBuf {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_nanos());
+    std::env::temp_dir().join(format!("omnigraph-{}-{}", label, nanos))
+}
+
+impl Worktree {
+    fn add(repo: &Path, commit: &str) -> Result<Self> {
+        let dir = temp_checkout_dir(&commit[..commit.len().min(12)]);
+        worktree_git(repo, &["worktree", "add", "--detach", &dir.to_string_lossy(), commit])?;
+        Ok(Self {
+            repo: repo.to_path_buf(),
+            dir,
+            linked: true,
+        })
+    }
+
+    /// A plain copy of the files staged in the index of `repo`
+    fn staged(repo: &Path) -> Result<Self> {
+        let dir = temp_checkout_dir("index");
+        let checkout = Self {
+            repo: repo.to_path_buf(),
+            dir,
+            linked: false,
+        };
+        let prefix = format!("--prefix={}/", checkout.dir.to_string_lossy());
+        git(repo, &["checkout-index", "--all", &prefix])?;
+        Ok(checkout)
+    }
+}
+
+impl Drop for Worktree {
+    fn drop(&mut self) {
+        if !self.linked {
+            let _ = std::fs::remove_dir_all(&self.dir);
+            return;
+        }
+        if let Err(e) = worktree_git(&self.repo, &["worktree", "remove", "--force", &self.dir.to_string_lossy()]) {
+            tracing::warn!("Failed to remove worktree {}: {}", self.dir.display(), e);
+            let _ = std::fs::remove_dir_all(&self.dir);
+            let _ = worktree_git(&self.repo, &["worktree", "prune"]);
+        }
+    }
+}
+
+/// Point every path under the worktree at the same path under the codebase
+fn rebase_paths(analyzed: &mut AnalyzedGraph, worktree: &Path, root: &Path) {
+    let from = worktree.display().to_string();
+    let to = root.display().to_string().trim_end_matches('/').to_string();
+    let rebase = |path: &mut String| {
+        if path.contains(&from) {
+            *path = path.replace(&from, &to);
+        }
+    };
+    for metrics in &mut analyzed.metrics {
+        rebase(&mut metrics.path);
+    }
+    for member in analyzed.cycles.iter_mut().flatten() {
+        rebase(member);
+    }
+    for node in &mut analyzed.graph_data.nodes {
+        rebase(&mut node.id);
+        if let Some(file_path) = &mut node.file_path {
+            rebase(file_path);
+        }
+    }
+    for link in &mut analyzed.graph_data.links {
+        rebase(&mut link.source);
+        rebase(&mut link.target);
+    }
+    analyzed.run_info.analyzed_path = to;
+}
+
+/// Analyze the codebase at `root` as of `rev`, returning the commit it resolved to
+///
+/// The revision is checked out into a temporary git worktree, so the working
+/// copy at `root` is left alone. Paths in the analysis are those the files
+/// would have under `root`, so it can be compared with other analyses of it.
+pub(crate) async fn analyze_at(
+    root: &Path,
+    rev: &str,
+    limits: AnalysisLimits,
+    include_dirs: &[PathBuf],
+) -> Result<(String, AnalyzedGraph)> {
+    let commit = git(root, &["rev-parse", "--verify", &format!("{}^{{commit}}", rev)])
+        .with_context(|| format!("Unknown revision: {}", rev))?;
+    let toplevel = PathBuf::from(git(root, &["rev-parse", "--show-toplevel"])?);
+
+    let worktree = Worktree::add(&toplevel, &commit)?;
+    tracing::info!("Analyzing {} at {} ({})", root.display(), rev, commit);
+    let mut analyzed = analyze_checkout(root, &worktree, rev, limits, include_dirs).await?;
+    analyzed.run_info.git_commit = Some(commit.clone());
+    Ok((commit, analyzed))
+}
+
+/// Analyze the files staged in the index of the repository containing `root`
+///
+/// Unstaged edits in the working copy are left out, so the analysis is of
+/// what a commit made now would contain. Paths are those under `root`.
+pub(crate) async fn analyze_staged(
+    root: &Path,
+    limits: AnalysisLimits,
+    include_dirs: &[PathBuf],
+) -> Result<AnalyzedGraph> {
+    let toplevel = PathBuf::from(git(root, &["rev-parse", "--show-toplevel"])?);
+    let staged = Worktree::staged(&toplevel)?;
+    tracing::info!("Analyzing staged files of {}", root.display());
+    analyze_checkout(root, &staged, "the index", limits, include_dirs).await
+}
+
+/// Analyze the part of `worktree` that `root` is within its repository
+async fn analyze_checkout(
+    root: &Path,
+    worktree: &Worktree,
+    label: &str,
+    limits: AnalysisLimits,
+    include_dirs: &[PathBuf],
+) -> Result<AnalyzedGraph> {
+    let prefix = git(root, &["rev-parse", "--show-prefix"])?;
+    let prefix = prefix.trim_end_matches('/');
+    let checkout = if prefix.is_empty() { worktree.dir.clone() } else { worktree.dir.join(prefix) };
+    if !checkout.is_dir() {
+        bail!("{} does not exist in {}", prefix, label);
+    }
+
+    let engine = Engine::new(checkout.clone())
+        .with_limits(limits)
+        .with_include_dirs(include_dirs);
+    let mut analyzed = engine.analyze_with_metrics(None).await?;
+    rebase_paths(&mut analyzed, &checkout, root);
+    Ok(analyzed)
+}
+
+/// Best common ancestor of two revisions, where their histories diverged
+pub(crate) fn merge_base(root: &Path, rev_a: &str, rev_b: &str) -> Result<String> {
+    git(root, &["merge-base", rev_a, rev_b])
+}
+
+/// Whether `rev` names a commit in the repository containing `root`
+pub(crate) fn resolves(root: &Path, rev: &str) -> bool {
+    git(root, &["rev-parse", "--verify", "--quiet", &format!("{}^{{commit}}", rev)]).is_ok()
+}
+
+/// Analyze the codebase at `root` as of `rev` and store it as a snapshot
+pub async fn analyze_revision(
+    root: &Path,
+    rev: &str,
+    limits: AnalysisLimits,
+    include_dirs: &[PathBuf],
+) -> Result<RevisionAnalysis> {
+    let (commit, analyzed) = analyze_at(root, rev, limits, include_dirs).await?;
+    let snapshot = MetricSnapshot::from_analysis(&analyzed)
+        .map(|snapshot| snapshot.save(root))
+        .transpose()?;
+
+    Ok(RevisionAnalysis {
+        rev: rev.to_string(),
+        commit,
+        snapshot,
+        summary: analyzed.summary,
+    })
+}
+
+/// Structural differences of the codebase at `root` from `rev_a` to `rev_b`
+///
+/// At most `limit` files with changed metrics are listed.
+pub async fn diff_revisions(
+    root: &Path,
+    rev_a: &str,
+    rev_b: &str,
+    limits: AnalysisLimits,
+    include_dirs: &[PathBuf],
+    limit: usize,
+) -> Result<StructuralDiff> {
+    let (_, before) = analyze_at(root, rev_a, limits.clone(), include_dirs).await?;
+    let (_, after) = analyze_at(root, rev_b, limits, include_dirs).await?;
+    let before_graph = CodeGraph::from_graph_data(&before.graph_data);
+    let after_graph = CodeGraph::from_graph_data(&after.graph_data);
+    Ok(StructuralDiff::build(
+        &AnalysisSide {
+            root: &before.run_info.analyzed_path,
+            graph: &before_graph,
+            metrics: &before.metrics,
+        },
+        &AnalysisSide {
+            root: &after.run_info.analyzed_path,
+            graph: &after_graph,
+            metrics: &after.metrics,
+        },
+        limit,
+    ))
+}
@@ -0,0 +1,191 @@
+use crate::limits::{Degradation, LargeFile};
+use og_analytics::{AnalyticsConfig, PathWeighting};
+use og_graph::direction::AnalysisMode;
+use og_graph::external::ExternalFanOut;
+use og_graph::file_tree::FileStats;
+use og_graph::layers::LayerViolation;
+use og_graph::overrides::OverrideReport;
+use og_parser::LanguageThroughput;
+use og_types::metrics::NormalizationMode;
+use og_types::{CommentDensity, ConcurrencyProfile, DebtMarker, FunctionMetrics, LicenseFile, LicenseHeader, Suppressions};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::process::Command;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Metadata describing how an analysis run was produced
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunInfo {
+    pub omnigraph_version: String,
+    /// Unix timestamp in milliseconds
+    pub started_at: u64,
+    pub analyzed_path: String,
+    pub git_commit: Option<String>,
+    pub files_by_language: BTreeMap<String, usize>,
+    /// Wall-clock time per stage in milliseconds, in execution order
+    pub stage_durations_ms: Vec<StageDuration>,
+    /// Files, bytes and parse time of each language
+    #[serde(default)]
+    pub parse_throughput: Vec<LanguageThroughput>,
+    pub total_duration_ms: u64,
+    pub config: Option<RunConfig>,
+    /// Set when the graph was reduced to fit the analysis limits
+    pub degradation: Option<Degradation>,
+    /// Files with dynamic imports the static graph could not follow
+    #[serde(default)]
+    pub dynamic_import_files: Vec<String>,
+    /// Directory that was re-parsed when only part of the codebase was analyzed
+    #[serde(default)]
+    pub partial_path: Option<String>,
+    /// Files left out by an `omnigraph-ignore-file` comment
+    #[serde(default)]
+    pub ignored_files: Vec<String>,
+    /// Files written by a code generator
+    #[serde(default)]
+    pub generated_files: Vec<String>,
+    /// Files over the size cap or minified, and how each was handled
+    #[serde(default)]
+    pub large_files: Vec<LargeFile>,
+    /// Findings silenced by inline comments
+    #[serde(default)]
+    pub suppressions: Suppressions,
+    /// Manual nodes and edges merged into the graph, and overrides that matched nothing
+    #[serde(default)]
+    pub overrides: OverrideReport,
+    /// Third-party packages and imports of each file, most packages first
+    #[serde(default)]
+    pub external_fan_out: Vec<ExternalFanOut>,
+    /// Dependencies that break the layer rules of the project config
+    #[serde(default)]
+    pub layer_violations: Vec<LayerViolation>,
+    /// Files the configured entry points do not reach, empty without entry points
+    #[serde(default)]
+    pub unreachable_files: Vec<String>,
+    /// TODO, FIXME, HACK and XXX comments, by file then line
+    #[serde(default)]
+    pub debt_markers: Vec<DebtMarker>,
+    /// License header of each source file, by path, when licenses are scanned
+    #[serde(default)]
+    pub license_headers: BTreeMap<String, LicenseHeader>,
+    /// LICENSE and COPYING files above the source files, when licenses are scanned
+    #[serde(default)]
+    pub license_files: Vec<LicenseFile>,
+    /// Size, lines and complexity of each file, by file path, for the file explorer
+    #[serde(skip)]
+    pub file_stats: HashMap<String, FileStats>,
+    /// Shape of each function and method body, by node id, for shape smells
+    #[serde(skip)]
+    pub function_metrics: HashMap<String, FunctionMetrics>,
+    /// Comment and code lines of each file, by file path, for documentation coverage
+    #[serde(skip)]
+    pub comments: HashMap<String, CommentDensity>,
+    /// Whether each public function, method or type has docs, by node id
+    #[serde(skip)]
+    pub documented: HashMap<String, bool>,
+    /// Threads, tasks and locks each function uses, by node id, for the concurrency map
+    #[serde(skip)]
+    pub concurrency: HashMap<String, ConcurrencyProfile>,
+}
+
+/// Duration of a single pipeline stage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StageDuration {
+    pub stage: String,
+    pub duration_ms: u64,
+}
+
+/// Analytics settings used for a run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunConfig {
+    pub parallel: bool,
+    pub use_cache: bool,
+    pub pagerank_iterations: usize,
+    pub pagerank_damping: f64,
+    pub louvain_resolution: f64,
+    pub normalization: NormalizationMode,
+    pub timeout_secs: u64,
+    /// Seed of sampled betweenness, so approximate results can be reproduced
+    #[serde(default)]
+    pub betweenness_seed: u64,
+    /// Edge lengths closeness and betweenness were measured with
+    #[serde(default)]
+    pub path_weighting: PathWeighting,
+    /// Metrics that read edges other than as directed
+    #[serde(default)]
+    pub modes: BTreeMap<String, AnalysisMode>,
+    /// Edge types communities were found on, all of them when empty
+    #[serde(default)]
+    pub community_edge_types: Vec<String>,
+    /// Whether self-loops were left out of the metrics
+    #[serde(default)]
+    pub drop_self_loops: bool,
+    /// Edge types each metric ignored, by metric name
+    #[serde(default)]
+    pub excluded_edge_types: BTreeMap<String, Vec<String>>,
+}
+
+impl RunConfig {
+    pub fn new(config: &AnalyticsConfig, timeout: Duration) -> Self {
+        Self {
+            parallel: config.parallel,
+            use_cache: config.use_cache,
+            pagerank_iterations: config.pagerank_iterations,
+            pagerank_damping: config.pagerank_damping,
+            louvain_resolution: config.louvain_resolution,
+            normalization: config.normalization,
+            timeout_secs: timeout.as_secs(),
+            betweenness_seed: config.betweenness_seed,
+            path_weighting: config.path_weighting.clone(),
+            modes: config.modes.clone(),
+            community_edge_types: config.community_edge_types.clone(),
+            drop_self_loops: config.drop_self_loops,
+            excluded_edge_types: config.excluded_edge_types.clone(),
+        }
+    }
+}
+
+impl RunInfo {
+    /// Start a new run for the given path
+    pub fn start(path: &Path) -> Self {
+        let started_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+
+        Self {
+            omnigraph_version: env!("CARGO_PKG_VERSION").to_string(),
+            started_at,
+            analyzed_path: path.display().to_string(),
+            git_commit: git_commit(path),
+            ..Default::default()
+        }
+    }
+
+    /// Record how long a stage took
+    pub fn record_stage(&mut self, stage: &str, duration: Duration) {
+        self.stage_durations_ms.push(StageDuration {
+            stage: stage.to_string(),
+            duration_ms: duration.as_millis() as u64,
+        });
+    }
+}
+
+/// Resolve the HEAD commit of the repository containing `path`, if any
+pub(crate) fn git_commit(path: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .current_dir(path)
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let commit = String::from_utf8(output.stdout).ok()?.trim().to_string();
+    (!commit.is_empty()).then_some(commit)
+}
@@ -0,0 +1,244 @@
+use crate::engine_v2::Engine;
+use crate::history::{self, MetricSnapshot};
+use crate::run_info::git_commit;
+use crate::AppState;
+use og_types::metrics::UINodeMetricsV1;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Event emitted after every scheduled analysis
+pub const ANALYSIS_REFRESHED_EVENT: &str = "analysis-refreshed";
+
+/// Event emitted when node risk rose past the threshold since the last snapshot
+pub const RISK_ALERT_EVENT: &str = "risk-alert";
+
+/// Event emitted for each configured alert threshold an analysis crossed
+pub const ANALYSIS_ALERT_EVENT: &str = "analysis-alert";
+
+/// Shortest allowed interval between scheduled analyses
+pub const MIN_INTERVAL_SECS: u64 = 60;
+
+/// How often the repository HEAD is checked for changes
+const HEAD_POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+/// When background re-analysis runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ScheduleSettings {
+    /// Re-analyze after this many seconds, if set
+    pub interval_secs: Option<u64>,
+    /// Re-analyze when the git HEAD commit changes
+    pub on_head_change: bool,
+    /// Risk increase of a node, from 0 to 1, that triggers an alert
+    pub risk_delta_threshold: f32,
+}
+
+impl Default for ScheduleSettings {
+    fn default() -> Self {
+        Self {
+            interval_secs: None,
+            on_head_change: true,
+            risk_delta_threshold: 0.2,
+        }
+    }
+}
+
+impl ScheduleSettings {
+    /// Check that the settings describe a usable schedule
+    pub fn validate(&self) -> Result<(), String> {
+        if self.interval_secs.is_none() && !self.on_head_change {
+            return Err("Schedule needs an interval or HEAD change trigger".to_string());
+        }
+        if self.interval_secs.is_some_and(|secs| secs < MIN_INTERVAL_SECS) {
+            return Err(format!("Interval must be at least {} seconds", MIN_INTERVAL_SECS));
+        }
+        if !(self.risk_delta_threshold > 0.0 && self.risk_delta_threshold <= 1.0) {
+            return Err("Risk delta threshold must be in (0, 1]".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// What started a scheduled analysis
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Trigger {
+    Interval,
+    HeadChange,
+}
+
+/// A node whose risk rose since the previous snapshot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RiskDelta {
+    pub path: String,
+    pub name: String,
+    pub node_type: String,
+    pub previous: f32,
+    pub current: f32,
+    pub delta: f32,
+}
+
+/// Outcome of one scheduled analysis
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledRun {
+    pub path: String,
+    pub trigger: Trigger,
+    pub git_commit: Option<String>,
+    pub total_nodes: usize,
+    pub high_risk_count: usize,
+    /// Nodes whose risk rose by at least the threshold, largest rise first
+    pub risk_alerts: Vec<RiskDelta>,
+}
+
+/// Current schedule, as reported to the frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleStatus {
+    pub path: String,
+    pub settings: ScheduleSettings,
+}
+
+/// A running background re-analysis loop
+pub struct Scheduler {
+    status: ScheduleStatus,
+    task: JoinHandle<()>,
+}
+
+impl Scheduler {
+    /// Start re-analyzing a codebase in the background
+    pub fn start(app: AppHandle, path: PathBuf, settings: ScheduleSettings) -> Self {
+        let status = ScheduleStatus {
+            path: path.display().to_string(),
+            settings: settings.clone(),
+        };
+        let task = tauri::async_runtime::spawn(run_loop(app, path, settings));
+        Self { status, task }
+    }
+
+    pub fn status(&self) -> &ScheduleStatus {
+        &self.status
+    }
+
+    /// Stop the loop, abandoning any analysis in progress
+    pub fn stop(self) {
+        self.task.abort();
+    }
+}
+
+async fn run_loop(app: AppHandle, path: PathBuf, settings: ScheduleSettings) {
+    let interval = settings.interval_secs.map(Duration::from_secs);
+    let poll = if settings.on_head_change {
+        interval.map_or(HEAD_POLL_INTERVAL, |i| i.min(HEAD_POLL_INTERVAL))
+    } else {
+        interval.unwrap_or(HEAD_POLL_INTERVAL)
+    };
+    let mut last_run = Instant::now();
+    let mut head = git_commit(&path);
+
+    loop {
+        tokio::time::sleep(poll).await;
+
+        let trigger = if interval.is_some_and(|i| last_run.elapsed() >= i) {
+            Trigger::Interval
+        } else if settings.on_head_change && git_commit(&path) != head {
+            Trigger::HeadChange
+        } else {
+            continue;
+        };
+
+        tracing::info!("[SCHEDULER] Re-analyzing {} ({:?})", path.display(), trigger);
+        if let Err(e) = analyze(&app, &path, &settings, trigger).await {
+            tracing::warn!("[SCHEDULER] Scheduled analysis failed: {}", e);
+        }
+        last_run = Instant::now();
+        head = git_commit(&path);
+    }
+}
+
+async fn analyze(app: &AppHandle, path: &Path, settings: &ScheduleSettings, trigger: Trigger) -> anyhow::Result<()> {
+    let engine = {
+        let state = app.state::<Mutex<AppState>>();
+        let state_guard = state.lock().unwrap();
+        Engine::new(path.to_path_buf())
+            .with_limits(state_guard.limits.clone())
+            .with_include_dirs(&state_guard.include_dirs)
+    };
+    let analyzed = engine.analyze_with_metrics(None).await?;
+
+    let previous = history::load_snapshots(path).pop();
+    if let Some(snapshot) = MetricSnapshot::from_analysis(&analyzed) {
+        snapshot.save(path)?;
+    }
+    let risk_alerts = previous.map_or_else(Vec::new, |previous| {
+        risk_deltas(&previous.metrics, &analyzed.metrics, settings.risk_delta_threshold)
+    });
+
+    let run = ScheduledRun {
+        path: path.display().to_string(),
+        trigger,
+        git_commit: analyzed.run_info.git_commit.clone(),
+        total_nodes: analyzed.summary.total_nodes,
+        high_risk_count: analyzed.summary.high_risk_count,
+        risk_alerts,
+    };
+
+    let alerts = analyzed.summary.alerts.clone();
+
+    // Refresh the dashboard only while it still shows this codebase
+    {
+        let state = app.state::<Mutex<AppState>>();
+        let mut state_guard = state.lock().unwrap();
+        let showing = state_guard
+            .run_info
+            .as_ref()
+            .is_some_and(|run_info| Path::new(&run_info.analyzed_path) == path);
+        if showing {
+            state_guard.current_graph = Some(analyzed.graph_data.clone());
+            state_guard.run_info = Some(analyzed.run_info.clone());
+            state_guard.analyzed_graph = Some(analyzed);
+            state_guard.viewport_ranks = None;
+        }
+    }
+
+    for alert in &alerts {
+        app.emit(ANALYSIS_ALERT_EVENT, alert)?;
+    }
+    if !run.risk_alerts.is_empty() {
+        app.emit(RISK_ALERT_EVENT, &run)?;
+    }
+    app.emit(ANALYSIS_REFRESHED_EVENT, &run)?;
+    Ok(())
+}
+
+/// Nodes present in both runs whose risk rose by at least the threshold
+pub fn risk_deltas(previous: &[UINodeMetricsV1], current: &[UINodeMetricsV1], threshold: f32) -> Vec<RiskDelta> {
+    let before: HashMap<(&str, &str, &str), f32> = previous
+        .iter()
+        .map(|m| ((m.path.as_str(), m.name.as_str(), m.node_type.as_str()), m.risk))
+        .collect();
+
+    let mut deltas: Vec<RiskDelta> = current
+        .iter()
+        .filter_map(|m| {
+            let previous = *before.get(&(m.path.as_str(), m.name.as_str(), m.node_type.as_str()))?;
+            let delta = m.risk - previous;
+            (delta >= threshold).then(|| RiskDelta {
+                path: m.path.clone(),
+                name: m.name.clone(),
+                node_type: m.node_type.clone(),
+                previous,
+                current: m.risk,
+                delta,
+            })
+        })
+        .collect();
+    deltas.sort_by(|a, b| b.delta.total_cmp(&a.delta));
+    deltas
+}
@@ -0,0 +1,177 @@
+use crate::engine_v2::Engine;
+use anyhow::{bail, Context, Result};
+use og_analytics::analysis::evolution::{EvolutionTimeline, HistorySnapshot, DEFAULT_MIN_GROWTH};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// How git history is sampled for the evolution timeline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TimelineSettings {
+    /// Days between sampled commits, 0 for every commit
+    pub interval_days: u64,
+    /// Most recent snapshots kept, HEAD always among them
+    pub max_snapshots: usize,
+    /// Smallest relative increase in files reported as a module growing
+    pub min_growth: f64,
+}
+
+impl Default for TimelineSettings {
+    fn default() -> Self {
+        Self {
+            interval_days: 30,
+            max_snapshots: 24,
+            min_growth: DEFAULT_MIN_GROWTH,
+        }
+    }
+}
+
+impl TimelineSettings {
+    /// Check that the settings describe a usable sample
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_snapshots == 0 {
+            return Err("At least one snapshot is needed".to_string());
+        }
+        if !(self.min_growth.is_finite() && self.min_growth > 0.0) {
+            return Err("Minimum growth must be a positive number".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// A commit on the first-parent history of HEAD
+#[derive(Debug, Clone)]
+struct Commit {
+    id: String,
+    /// Unix timestamp in seconds
+    committed_at: u64,
+}
+
+fn git(root: &Path, args: &[&str]) -> Result<Vec<u8>> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(root)
+        .output()
+        .context("failed to run git")?;
+    if !output.status.success() {
+        bail!("git {} failed: {}", args[0], String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(output.stdout)
+}
+
+/// Commits on the first-parent history of HEAD, oldest first
+fn history(root: &Path) -> Result<Vec<Commit>> {
+    let log = git(root, &["log", "--first-parent", "--reverse", "--format=%H %ct", "HEAD"])?;
+    Ok(String::from_utf8_lossy(&log)
+        .lines()
+        .filter_map(|line| {
+            let (id, time) = line.split_once(' ')?;
+            Some(Commit {
+                id: id.to_string(),
+                committed_at: time.parse().ok()?,
+            })
+        })
+        .collect())
+}
+
+/// Commits at least `interval_secs` apart, plus the last one
+fn sample(commits: Vec<Commit>, interval_secs: u64, max: usize) -> Vec<Commit> {
+    let last = commits.len().saturating_sub(1);
+    let mut sampled: Vec<Commit> = Vec::new();
+    for (i, commit) in commits.into_iter().enumerate() {
+        let due = sampled
+            .last()
+            .is_none_or(|previous| commit.committed_at >= previous.committed_at + interval_secs);
+        if due || i == last {
+            sampled.push(commit);
+        }
+    }
+    let skip = sampled.len().saturating_sub(max);
+    sampled.split_off(skip)
+}
+
+/// Blob id of every file in a commit, by path relative to `root`
+fn tree(root: &Path, commit: &str) -> Result<HashMap<String, String>> {
+    let listing = git(root, &["ls-tree", "-r", "-z", commit])?;
+    Ok(listing
+        .split(|&byte| byte == 0)
+        .filter_map(|entry| {
+            let entry = std::str::from_utf8(entry).ok()?;
+            let (meta, path) = entry.split_once('\t')?;
+            let mut meta = meta.split(' ');
+            let (_, kind, id) = (meta.next()?, meta.next()?, meta.next()?);
+            (kind == "blob").then(|| (path.to_string(), id.to_string()))
+        })
+        .collect())
+}
+
+/// Contents of the given blobs in one `git cat-file` call, None for binary or missing ones
+fn read_blobs(root: &Path, ids: &[&str]) -> Result<Vec<Option<String>>> {
+    let mut child = Command::new("git")
+        .args(["cat-file", "--batch"])
+        .current_dir(root)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .context("failed to run git")?;
+
+    // Write from another thread so a full stdout pipe cannot block the request
+    let mut stdin = child.stdin.take().context("git stdin")?;
+    let request: String = ids.iter().map(|id| format!("{}\n", id)).collect();
+    let writer = std::thread::spawn(move || stdin.write_all(request.as_bytes()));
+
+    let mut stdout = BufReader::new(child.stdout.take().context("git stdout")?);
+    let mut blobs = Vec::with_capacity(ids.len());
+    let mut header = String::new();
+    for _ in ids {
+        header.clear();
+        stdout.read_line(&mut header)?;
+        let Some(size) = header.split(' ').nth(2).and_then(|size| size.trim().parse::<usize>().ok()) else {
+            blobs.push(None);
+            continue;
+        };
+        let mut content = vec![0; size + 1];
+        stdout.read_exact(&mut content)?;
+        content.pop();
+        blobs.push(String::from_utf8(content).ok());
+    }
+
+    writer.join().map_err(|_| anyhow::anyhow!("git writer panicked"))??;
+    child.wait()?;
+    Ok(blobs)
+}
+
+/// File graph of the codebase at one commit
+fn snapshot(engine: &Engine, root: &Path, commit: &Commit) -> Result<HistorySnapshot> {
+    let tree = tree(root, &commit.id)?;
+    let paths = engine.select_files(tree.keys().cloned().collect());
+    let ids: Vec<&str> = paths.iter().map(|path| tree[path].as_str()).collect();
+    let contents = read_blobs(root, &ids)?;
+    let files = paths
+        .iter()
+        .zip(contents)
+        .filter_map(|(path, content)| Some((path.clone(), content?)))
+        .collect();
+
+    Ok(HistorySnapshot {
+        commit: commit.id.clone(),
+        committed_at: commit.committed_at,
+        graph: engine.file_graph_of(files)?,
+    })
+}
+
+/// Sample the history of the repository at `root` and compare its modules over time
+pub fn build(engine: &Engine, root: &Path, settings: &TimelineSettings) -> Result<EvolutionTimeline> {
+    let commits = sample(history(root)?, settings.interval_days * SECS_PER_DAY, settings.max_snapshots);
+    let mut snapshots = Vec::with_capacity(commits.len());
+    for commit in &commits {
+        tracing::info!("Building history snapshot at {}", commit.id);
+        snapshots.push(snapshot(engine, root, commit)?);
+    }
+    Ok(EvolutionTimeline::build(&snapshots, settings.min_growth))
+}
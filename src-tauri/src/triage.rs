@@ -0,0 +1,139 @@
+use og_analytics::Finding;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// File under a codebase root that holds triaged finding states
+pub const TRIAGE_PATH: &str = ".omnigraph/findings.json";
+
+/// Where a finding stands with the team
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "state")]
+pub enum FindingState {
+    #[default]
+    Open,
+    /// Known and accepted, not listed again
+    Acknowledged,
+    /// Hidden until the Unix timestamp in milliseconds
+    Snoozed { until: u64 },
+    /// Fixed; reopened if the finding is detected again
+    Resolved,
+}
+
+/// State of a finding and when it was last changed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TriageEntry {
+    #[serde(flatten)]
+    pub state: FindingState,
+    /// Unix timestamp in milliseconds
+    pub updated_at: u64,
+}
+
+/// A finding with its fingerprint and current state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TriagedFinding {
+    #[serde(flatten)]
+    pub finding: Finding,
+    pub fingerprint: String,
+    #[serde(flatten)]
+    pub state: FindingState,
+}
+
+/// Finding states of one codebase, by fingerprint
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TriageStore {
+    entries: BTreeMap<String, TriageEntry>,
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+impl TriageStore {
+    fn path(base_path: &Path) -> PathBuf {
+        base_path.join(TRIAGE_PATH)
+    }
+
+    /// Stored states of a codebase, empty if none were saved or the file is unreadable
+    pub fn load(base_path: &Path) -> Self {
+        let path = Self::path(base_path);
+        let Ok(bytes) = std::fs::read(&path) else {
+            return Self::default();
+        };
+        serde_json::from_slice(&bytes)
+            .inspect_err(|e| tracing::warn!("Ignoring finding states in {}: {}", path.display(), e))
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, base_path: &Path) -> std::io::Result<()> {
+        let path = Self::path(base_path);
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let json = serde_json::to_vec_pretty(self).map_err(std::io::Error::other)?;
+        std::fs::write(path, json)
+    }
+
+    /// Record a new state for a finding, forgetting it when set back to open
+    pub fn set(&mut self, fingerprint: &str, state: FindingState) {
+        if state == FindingState::Open {
+            self.entries.remove(fingerprint);
+        } else {
+            self.entries.insert(
+                fingerprint.to_string(),
+                TriageEntry {
+                    state,
+                    updated_at: now_millis(),
+                },
+            );
+        }
+    }
+
+    /// Stored entry of a finding, None while it is open
+    pub fn entry(&self, fingerprint: &str) -> Option<TriageEntry> {
+        self.entries.get(fingerprint).cloned()
+    }
+
+    /// Put back an entry taken with `entry`, keeping its timestamp
+    pub fn restore(&mut self, fingerprint: &str, entry: Option<TriageEntry>) {
+        match entry {
+            Some(entry) => self.entries.insert(fingerprint.to_string(), entry),
+            None => self.entries.remove(fingerprint),
+        };
+    }
+
+    /// State of a finding that is detected at `now`
+    ///
+    /// Expired snoozes and resolved findings that came back count as open.
+    pub fn state(&self, fingerprint: &str, now: u64) -> FindingState {
+        match self.entries.get(fingerprint).map(|entry| entry.state) {
+            Some(FindingState::Snoozed { until }) if until > now => FindingState::Snoozed { until },
+            Some(FindingState::Acknowledged) => FindingState::Acknowledged,
+            _ => FindingState::Open,
+        }
+    }
+
+    /// Attach fingerprints and states, keeping only open findings unless `include_triaged`
+    pub fn triage(&self, findings: Vec<Finding>, root: &str, include_triaged: bool) -> Vec<TriagedFinding> {
+        let now = now_millis();
+        findings
+            .into_iter()
+            .map(|finding| {
+                let fingerprint = finding.fingerprint(root);
+                let state = self.state(&fingerprint, now);
+                TriagedFinding {
+                    finding,
+                    fingerprint,
+                    state,
+                }
+            })
+            .filter(|triaged| include_triaged || triaged.state == FindingState::Open)
+            .collect()
+    }
+}
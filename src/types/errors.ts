@@ -0,0 +1,34 @@
+/**
+ * TypeScript interfaces for errors returned by Tauri commands
+ * Matches CommandError in the og-types crate
+ */
+
+import type { LocalizedMessage } from './messages';
+
+export type ErrorCode =
+  | 'io'
+  | 'parse'
+  | 'analysis-timeout'
+  | 'invalid-state'
+  | 'invalid-input'
+  | 'cancelled'
+  | 'internal';
+
+export interface CommandError {
+  code: ErrorCode;
+  message: string;
+  details: string | null;
+  retryable: boolean;
+  /** The message as a code the frontend can translate, when it has one */
+  localized?: LocalizedMessage;
+}
+
+export function isCommandError(value: unknown): value is CommandError {
+  return (
+    typeof value === 'object' &&
+    value !== null &&
+    'code' in value &&
+    'message' in value &&
+    'retryable' in value
+  );
+}
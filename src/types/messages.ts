@@ -0,0 +1,20 @@
+/**
+ * TypeScript interfaces for user-facing messages sent by the backend
+ * Matches Message in the og-types crate
+ */
+
+/** A message code in kebab-case, such as 'graph-built' or 'node-not-found' */
+export interface LocalizedMessage {
+  code: string;
+  params?: Record<string, string | number>;
+}
+
+/** Payload of the parse-progress event */
+export interface ProgressUpdate {
+  current: number;
+  total: number;
+  percentage: number;
+  /** English text of the step */
+  message: string;
+  localized: LocalizedMessage;
+}
@@ -9,6 +9,8 @@ export interface RawMetrics {
   indegree: number;
   outdegree: number;
   kCore: number;
+  kTruss?: number;
+  articulationPoint?: boolean;
   clustering: number;
   betweenness: number;
   churn: number;
@@ -41,6 +43,8 @@ export interface UINodeMetricsV1 {
   payoff: number;
   raw: RawMetrics;
   normalized: NormalizedMetrics;
+  /** Declared in a file written by a code generator */
+  generated?: boolean;
   version?: number;
 }
 
@@ -58,6 +62,17 @@ export interface AnalyzedGraph {
   graphData: GraphData;
   metrics: UINodeMetricsV1[];
   summary: AnalysisSummary;
+  // Typed results of each metric family, when analytics ran
+  results?: ModularAnalysisReport;
+}
+
+// Each family carries the schema version it was written with
+export interface ModularAnalysisReport {
+  centrality: { schema: number; pagerank: Record<string, number>; betweenness: Record<string, number>; closeness: Record<string, number> };
+  community: { schema: number; communities: Record<string, number>; numCommunities: number; modularity: number };
+  risk: { schema: number; riskScores: Record<string, { overall: number }>; circularDependencies: string[][]; highRiskCount: number };
+  quality: { schema: number; maintainability: Record<string, number>; avgComplexity: number; totalCodeSmells: number };
+  errors: string[];
 }
 
 export interface GraphNode {
@@ -69,6 +84,8 @@ export interface GraphNode {
   fileType?: string;
   // Add metrics reference
   metrics?: UINodeMetricsV1;
+  // Measurements and enrichments by name, such as loc, complexity or tags
+  attributes?: Record<string, boolean | number | string | string[]>;
 }
 
 export interface GraphLink {
@@ -78,6 +95,8 @@ export interface GraphLink {
   linkType?: string;
   value?: number;
   strength?: number;
+  /** Relationships folded into this link, absent when there is only one */
+  count?: number;
 }
 
 export interface GraphData {
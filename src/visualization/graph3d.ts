@@ -161,9 +161,9 @@ export class Graph3DVisualization {
         // The graph library adds these properties after data is loaded
         let weight = 1;
         
-        // If node has explicit size property, use it as a multiplier
-        if (node.size && node.size > 0) {
-            weight = Math.sqrt(node.size / 10); // Square root for better visual scaling
+        // If node has an explicit visual size, use it as a multiplier; size is lines of code
+        if (node.visualSize && node.visualSize > 0) {
+            weight = Math.sqrt(node.visualSize / 10); // Square root for better visual scaling
         }
         
         // If node has connection data (added by force-graph), use it